@@ -0,0 +1,8 @@
+-- Places that have been imported from OpenStreetMap
+CREATE TABLE place_osm_node (
+    place_rowid INTEGER PRIMARY KEY NOT NULL,
+    osm_node_id INTEGER NOT NULL,
+    --
+    UNIQUE (osm_node_id),
+    FOREIGN KEY (place_rowid) REFERENCES place(rowid)
+);
@@ -0,0 +1,10 @@
+-- Policies of organizations for outside edits of their moderated tags
+CREATE TABLE org_tag_policy (
+    org_rowid INTEGER NOT NULL,
+    tag_label TEXT NOT NULL,
+    --
+    policy    TINYINT NOT NULL,
+    --
+    PRIMARY KEY (org_rowid, tag_label),
+    FOREIGN KEY (org_rowid) REFERENCES organization(rowid)
+);
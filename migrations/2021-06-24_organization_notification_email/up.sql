@@ -0,0 +1,2 @@
+-- Organizations are notified about outside edits of their moderated tags
+ALTER TABLE organization ADD COLUMN notification_email TEXT;
@@ -0,0 +1,13 @@
+-- Users that are notified about all places and events
+-- that are tagged with the moderated tags of an organization
+CREATE TABLE organization_subscriptions (
+    id        INTEGER PRIMARY KEY NOT NULL,
+    uid       TEXT NOT NULL,
+    user_id   INTEGER NOT NULL,
+    org_rowid INTEGER NOT NULL,
+    --
+    UNIQUE (uid),
+    UNIQUE (user_id, org_rowid),
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    FOREIGN KEY (org_rowid) REFERENCES organization(rowid)
+);
@@ -0,0 +1,14 @@
+-- Aggregated number of views per day without any
+-- information about the visitors
+CREATE TABLE view_counter (
+    entity_kind TINYINT NOT NULL,
+    entity_id   TEXT NOT NULL,
+    day         INTEGER NOT NULL,
+    --
+    count       INTEGER NOT NULL,
+    --
+    PRIMARY KEY (entity_kind, entity_id, day)
+    -- no FK for entity_id (either a place or an event)
+);
+
+CREATE INDEX view_counter_idx_day ON view_counter(day);
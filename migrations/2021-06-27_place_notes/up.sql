@@ -0,0 +1,19 @@
+-- Internal notes of scouts and admins about places
+-- that are never published
+CREATE TABLE place_note (
+    rowid       INTEGER PRIMARY KEY NOT NULL,
+    uid         TEXT NOT NULL,
+    place_rowid INTEGER NOT NULL,
+    -- Replies refer to the note they are answering
+    parent_uid  TEXT,
+    created_at  INTEGER NOT NULL,
+    created_by  INTEGER,
+    text        TEXT NOT NULL,
+    --
+    UNIQUE (uid),
+    FOREIGN KEY (place_rowid) REFERENCES place(rowid),
+    FOREIGN KEY (parent_uid) REFERENCES place_note(uid),
+    FOREIGN KEY (created_by) REFERENCES users(id)
+);
+
+CREATE INDEX place_note_idx_place_rowid ON place_note(place_rowid);
@@ -0,0 +1,14 @@
+-- Events are not revisioned. Their creation, updates and
+-- deletion are recorded separately for replicating them.
+CREATE TABLE event_changes (
+    rowid      INTEGER PRIMARY KEY NOT NULL,
+    event_uid  TEXT NOT NULL,
+    changed_at INTEGER NOT NULL,
+    changed_by INTEGER,
+    kind       TINYINT NOT NULL,
+    --
+    -- no FK for event_uid (deleted events)
+    FOREIGN KEY (changed_by) REFERENCES users(id)
+);
+
+CREATE INDEX event_changes_idx_changed_at ON event_changes(changed_at);
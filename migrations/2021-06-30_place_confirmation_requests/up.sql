@@ -0,0 +1,16 @@
+-- Requests to the creator or owner of a place
+-- to confirm that the place is still valid
+CREATE TABLE place_confirmation_request (
+    rowid       INTEGER PRIMARY KEY NOT NULL,
+    place_rowid INTEGER NOT NULL,
+    nonce       TEXT NOT NULL,
+    sent_to     TEXT NOT NULL,
+    sent_at     INTEGER NOT NULL,
+    answered_at INTEGER,
+    flagged_at  INTEGER,
+    --
+    UNIQUE (nonce),
+    FOREIGN KEY (place_rowid) REFERENCES place(rowid)
+);
+
+CREATE INDEX place_confirmation_request_idx_place_rowid ON place_confirmation_request(place_rowid);
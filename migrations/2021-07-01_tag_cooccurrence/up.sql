@@ -0,0 +1,12 @@
+-- How often a word in the title or description of a
+-- confirmed place occurs together with a tag. The whole
+-- table is replaced when the statistics are recomputed.
+CREATE TABLE tag_cooccurrence (
+    word       TEXT NOT NULL,
+    tag        TEXT NOT NULL,
+    count      INTEGER NOT NULL,
+    -- Number of places that contain the word
+    word_count INTEGER NOT NULL,
+    --
+    PRIMARY KEY (word, tag)
+);
@@ -0,0 +1,9 @@
+-- Results of checking the homepage and image links
+-- of places and events periodically
+CREATE TABLE link_check (
+    url        TEXT PRIMARY KEY NOT NULL,
+    -- Number of consecutive failed checks
+    failures   INTEGER NOT NULL,
+    checked_at INTEGER NOT NULL,
+    last_error TEXT
+);
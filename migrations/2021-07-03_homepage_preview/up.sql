@@ -0,0 +1,8 @@
+-- Previews of place homepages that are fetched periodically.
+-- They are kept apart from the data entered by users.
+CREATE TABLE homepage_preview (
+    url        TEXT PRIMARY KEY NOT NULL,
+    title      TEXT,
+    image_url  TEXT,
+    fetched_at INTEGER NOT NULL
+);
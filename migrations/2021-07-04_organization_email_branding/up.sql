@@ -0,0 +1,4 @@
+-- E-mails about moderated tags are sent on behalf of the organization
+ALTER TABLE organization ADD COLUMN email_sender_name TEXT;
+ALTER TABLE organization ADD COLUMN email_reply_to TEXT;
+ALTER TABLE organization ADD COLUMN email_footer TEXT;
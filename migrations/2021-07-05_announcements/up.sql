@@ -0,0 +1,14 @@
+-- Users have to opt into receiving announcements
+ALTER TABLE users ADD COLUMN announcements BOOLEAN NOT NULL DEFAULT 0;
+
+-- E-mails that are sent in throttled batches
+CREATE TABLE email_outbox (
+    id         TEXT PRIMARY KEY NOT NULL,
+    recipient  TEXT NOT NULL,
+    subject    TEXT NOT NULL,
+    body       TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    sent_at    INTEGER
+);
+
+CREATE INDEX email_outbox_idx_sent_at ON email_outbox(sent_at);
@@ -0,0 +1,17 @@
+-- Subscribers have to confirm that they want to receive notifications
+CREATE TABLE notification_consent (
+    email        TEXT PRIMARY KEY NOT NULL,
+    nonce        TEXT NOT NULL,
+    requested_at INTEGER NOT NULL,
+    confirmed_at INTEGER
+);
+
+-- Existing subscribers keep receiving notifications
+INSERT INTO notification_consent (email, nonce, requested_at, confirmed_at)
+SELECT email, lower(hex(randomblob(16))), strftime('%s', 'now'), strftime('%s', 'now')
+FROM users
+WHERE id IN (
+    SELECT user_id FROM bbox_subscriptions
+    UNION
+    SELECT user_id FROM organization_subscriptions
+);
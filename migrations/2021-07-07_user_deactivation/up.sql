@@ -0,0 +1,2 @@
+-- Deleted users are anonymized after a grace period
+ALTER TABLE users ADD COLUMN deactivated_at INTEGER;
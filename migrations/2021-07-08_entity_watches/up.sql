@@ -0,0 +1,11 @@
+-- Users that are notified about all changes and
+-- new ratings of individual places or events
+CREATE TABLE entity_watches (
+    id         INTEGER PRIMARY KEY NOT NULL,
+    user_id    INTEGER NOT NULL,
+    entity_id  TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    --
+    UNIQUE (user_id, entity_id),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+);
@@ -0,0 +1,18 @@
+-- Subscribers may receive a daily or weekly summary
+-- instead of an e-mail per change (NULL)
+ALTER TABLE bbox_subscriptions ADD COLUMN digest SMALLINT;
+
+-- Changes that are collected for the next digest
+CREATE TABLE pending_notifications (
+    id         INTEGER PRIMARY KEY NOT NULL,
+    uid        TEXT NOT NULL,
+    user_id    INTEGER NOT NULL,
+    digest     SMALLINT NOT NULL,
+    kind       SMALLINT NOT NULL,
+    entity_id  TEXT NOT NULL,
+    title      TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    --
+    UNIQUE (uid),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+);
@@ -0,0 +1,9 @@
+ALTER TABLE events ADD COLUMN created_at INTEGER;
+ALTER TABLE events ADD COLUMN updated_at INTEGER;
+
+-- Restore the time stamps of existing events from their recorded changes
+UPDATE events SET
+    created_at = (SELECT MIN(changed_at) FROM event_changes WHERE event_uid = events.uid),
+    updated_at = (SELECT MAX(changed_at) FROM event_changes WHERE event_uid = events.uid);
+
+CREATE INDEX events_idx_updated_at ON events(updated_at);
@@ -0,0 +1,14 @@
+-- Revocable tokens for authenticating users in scripts.
+-- Only the hash of the secret is stored.
+CREATE TABLE personal_api_tokens (
+    rowid       INTEGER PRIMARY KEY NOT NULL,
+    id          TEXT NOT NULL,
+    user_id     INTEGER NOT NULL,
+    scope       TINYINT NOT NULL,
+    label       TEXT,
+    secret_hash TEXT NOT NULL,
+    created_at  INTEGER NOT NULL,
+    --
+    UNIQUE (id),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+);
@@ -0,0 +1,16 @@
+-- Outside edits of moderated tags that are collected
+-- for the next summary e-mail to the organization
+CREATE TABLE pending_moderated_tags_notifications (
+    rowid        INTEGER PRIMARY KEY NOT NULL,
+    id           TEXT NOT NULL,
+    org_rowid    INTEGER NOT NULL,
+    place_id     TEXT NOT NULL,
+    place_title  TEXT NOT NULL,
+    -- Comma-separated lists of tags
+    added_tags   TEXT NOT NULL,
+    removed_tags TEXT NOT NULL,
+    created_at   INTEGER NOT NULL,
+    --
+    UNIQUE (id),
+    FOREIGN KEY (org_rowid) REFERENCES organization(rowid)
+);
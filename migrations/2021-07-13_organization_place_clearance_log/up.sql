@@ -0,0 +1,15 @@
+-- Decisions of organizations about pending clearances
+CREATE TABLE organization_place_clearance_log (
+    rowid        INTEGER PRIMARY KEY NOT NULL,
+    --
+    org_rowid    INTEGER NOT NULL,
+    place_id     TEXT NOT NULL,
+    place_rev    INTEGER NOT NULL, -- current revision when the decision was made
+    cleared_rev  INTEGER NOT NULL, -- last cleared revision afterwards
+    decision     TINYINT NOT NULL, -- 1 = approved, 2 = rejected
+    decided_at   INTEGER NOT NULL,
+    --
+    FOREIGN KEY (org_rowid) REFERENCES organization(rowid)
+);
+
+CREATE INDEX organization_place_clearance_log_idx_place_id ON organization_place_clearance_log(place_id);
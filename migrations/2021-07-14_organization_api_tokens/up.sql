@@ -0,0 +1,15 @@
+-- Revocable tokens of organizations in addition to the
+-- initial api_token. Only the hash of the secret is stored.
+CREATE TABLE organization_api_tokens (
+    rowid        INTEGER PRIMARY KEY NOT NULL,
+    id           TEXT NOT NULL,
+    org_rowid    INTEGER NOT NULL,
+    label        TEXT,
+    secret_hash  TEXT NOT NULL,
+    created_at   INTEGER NOT NULL,
+    expires_at   INTEGER,
+    last_used_at INTEGER,
+    --
+    UNIQUE (id),
+    FOREIGN KEY (org_rowid) REFERENCES organization(rowid)
+);
@@ -0,0 +1,37 @@
+-- Sums and counts of the unarchived ratings per place and context
+CREATE TABLE place_rating_aggregates (
+    place_rowid         INTEGER PRIMARY KEY NOT NULL,
+    --
+    diversity_sum       INTEGER NOT NULL,
+    diversity_count     INTEGER NOT NULL,
+    fairness_sum        INTEGER NOT NULL,
+    fairness_count      INTEGER NOT NULL,
+    humanity_sum        INTEGER NOT NULL,
+    humanity_count      INTEGER NOT NULL,
+    renewable_sum       INTEGER NOT NULL,
+    renewable_count     INTEGER NOT NULL,
+    solidarity_sum      INTEGER NOT NULL,
+    solidarity_count    INTEGER NOT NULL,
+    transparency_sum    INTEGER NOT NULL,
+    transparency_count  INTEGER NOT NULL,
+    --
+    FOREIGN KEY (place_rowid) REFERENCES place(rowid)
+);
+
+INSERT INTO place_rating_aggregates
+SELECT p.rowid,
+    coalesce(sum(CASE r.context WHEN 'diversity' THEN r.value END), 0),
+    count(CASE r.context WHEN 'diversity' THEN 1 END),
+    coalesce(sum(CASE r.context WHEN 'fairness' THEN r.value END), 0),
+    count(CASE r.context WHEN 'fairness' THEN 1 END),
+    coalesce(sum(CASE r.context WHEN 'humanity' THEN r.value END), 0),
+    count(CASE r.context WHEN 'humanity' THEN 1 END),
+    coalesce(sum(CASE r.context WHEN 'renewable' THEN r.value END), 0),
+    count(CASE r.context WHEN 'renewable' THEN 1 END),
+    coalesce(sum(CASE r.context WHEN 'solidarity' THEN r.value END), 0),
+    count(CASE r.context WHEN 'solidarity' THEN 1 END),
+    coalesce(sum(CASE r.context WHEN 'transparency' THEN r.value END), 0),
+    count(CASE r.context WHEN 'transparency' THEN 1 END)
+FROM place p
+LEFT JOIN place_rating r ON r.parent_rowid = p.rowid AND r.archived_at IS NULL
+GROUP BY p.rowid;
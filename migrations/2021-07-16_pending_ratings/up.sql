@@ -0,0 +1,22 @@
+-- New ratings whose comments violate the content rules
+-- and that are held back until a scout decides about them
+CREATE TABLE pending_ratings (
+    rowid        INTEGER PRIMARY KEY NOT NULL,
+    id           TEXT NOT NULL,
+    place_id     TEXT NOT NULL,
+    created_at   INTEGER NOT NULL,
+    created_by   TEXT,
+    title        TEXT NOT NULL,
+    value        INTEGER NOT NULL,
+    context      TEXT NOT NULL,
+    source       TEXT,
+    comment_id   TEXT NOT NULL,
+    comment_text TEXT NOT NULL,
+    -- Comma-separated list of violated rules
+    violations   TEXT NOT NULL,
+    --
+    UNIQUE (id)
+);
+
+-- Unknown for users who registered before
+ALTER TABLE users ADD COLUMN created_at INTEGER;
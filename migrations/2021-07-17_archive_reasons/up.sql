@@ -0,0 +1,4 @@
+-- Unknown for ratings and comments that have been archived before
+-- or that have been archived together with their place
+ALTER TABLE place_rating ADD COLUMN archived_reason SMALLINT;
+ALTER TABLE place_rating_comment ADD COLUMN archived_reason SMALLINT;
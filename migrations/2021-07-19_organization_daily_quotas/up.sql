@@ -0,0 +1,10 @@
+-- Individual upload quotas of organizations that
+-- replace the configured defaults
+CREATE TABLE org_daily_quotas (
+    org_rowid INTEGER PRIMARY KEY NOT NULL,
+    --
+    events    INTEGER,
+    places    INTEGER,
+    --
+    FOREIGN KEY (org_rowid) REFERENCES organization(rowid)
+);
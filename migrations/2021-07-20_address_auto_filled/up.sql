@@ -0,0 +1,3 @@
+-- Addresses that have been resolved from the coordinates
+ALTER TABLE place_revision ADD COLUMN address_auto_filled BOOLEAN NOT NULL DEFAULT 0;
+ALTER TABLE events ADD COLUMN address_auto_filled BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+-- The organization API token that has been used for a decision
+ALTER TABLE organization_place_clearance_log ADD COLUMN api_token_id TEXT;
+ALTER TABLE organization_place_clearance_log ADD COLUMN api_token_label TEXT;
@@ -0,0 +1,29 @@
+-- Changes of imported places in OpenStreetMap that
+-- are not applied until a scout accepts them
+CREATE TABLE osm_node_proposal (
+    rowid       INTEGER PRIMARY KEY NOT NULL,
+    id          TEXT NOT NULL,
+    place_rowid INTEGER NOT NULL,
+    osm_node_id INTEGER NOT NULL,
+    place_rev   INTEGER NOT NULL, -- current revision when the changes were detected
+    created_at  INTEGER NOT NULL,
+    -- Only the upstream values that differ from the place
+    title       TEXT,
+    lat         DOUBLE,
+    lon         DOUBLE,
+    street      TEXT,
+    zip         TEXT,
+    city        TEXT,
+    country     TEXT,
+    state       TEXT,
+    --
+    status      TINYINT NOT NULL, -- 0 = pending, 1 = accepted, 2 = rejected
+    reviewed_at INTEGER,
+    reviewed_by INTEGER,
+    --
+    UNIQUE (id),
+    FOREIGN KEY (place_rowid) REFERENCES place(rowid),
+    FOREIGN KEY (reviewed_by) REFERENCES users(id)
+);
+
+CREATE INDEX osm_node_proposal_idx_place_rowid ON osm_node_proposal(place_rowid);
@@ -1,4 +1,5 @@
 use super::*;
+use chrono::NaiveDateTime;
 use ofdb_entities as e;
 use std::convert::{TryFrom, TryInto};
 
@@ -50,6 +51,7 @@ impl From<e::review::ReviewStatus> for ReviewStatus {
             Archived => ReviewStatus::Archived,
             Confirmed => ReviewStatus::Confirmed,
             Created => ReviewStatus::Created,
+            Draft => ReviewStatus::Draft,
             Rejected => ReviewStatus::Rejected,
         }
     }
@@ -62,11 +64,82 @@ impl From<ReviewStatus> for e::review::ReviewStatus {
             ReviewStatus::Archived => Archived,
             ReviewStatus::Confirmed => Confirmed,
             ReviewStatus::Created => Created,
+            ReviewStatus::Draft => Draft,
             ReviewStatus::Rejected => Rejected,
         }
     }
 }
 
+impl From<e::subscription::NotificationDigest> for NotificationDigest {
+    fn from(from: e::subscription::NotificationDigest) -> Self {
+        use e::subscription::NotificationDigest::*;
+        match from {
+            Daily => NotificationDigest::Daily,
+            Weekly => NotificationDigest::Weekly,
+        }
+    }
+}
+
+impl From<e::user::ApiTokenScope> for ApiTokenScope {
+    fn from(from: e::user::ApiTokenScope) -> Self {
+        use e::user::ApiTokenScope::*;
+        match from {
+            Read => ApiTokenScope::Read,
+            Write => ApiTokenScope::Write,
+        }
+    }
+}
+
+impl From<ApiTokenScope> for e::user::ApiTokenScope {
+    fn from(from: ApiTokenScope) -> Self {
+        use ApiTokenScope::*;
+        match from {
+            Read => e::user::ApiTokenScope::Read,
+            Write => e::user::ApiTokenScope::Write,
+        }
+    }
+}
+
+impl From<e::organization::OrganizationApiToken> for OrganizationApiToken {
+    fn from(from: e::organization::OrganizationApiToken) -> Self {
+        let e::organization::OrganizationApiToken {
+            id,
+            label,
+            created_at,
+            expires_at,
+            last_used_at,
+            ..
+        } = from;
+        Self {
+            id: id.into(),
+            label,
+            created_at: created_at.into_seconds(),
+            expires_at: expires_at.map(|t| t.into_seconds()),
+            last_used_at: last_used_at.map(|t| t.into_seconds()),
+            token: None,
+        }
+    }
+}
+
+impl From<e::user::PersonalApiToken> for PersonalApiToken {
+    fn from(from: e::user::PersonalApiToken) -> Self {
+        let e::user::PersonalApiToken {
+            id,
+            scope,
+            label,
+            created_at,
+            ..
+        } = from;
+        Self {
+            id: id.into(),
+            scope: scope.into(),
+            label,
+            created_at: created_at.into_seconds(),
+            token: None,
+        }
+    }
+}
+
 impl From<e::user::User> for User {
     fn from(from: e::user::User) -> Self {
         let e::user::User {
@@ -169,6 +242,100 @@ impl From<RatingValue> for e::rating::RatingValue {
     }
 }
 
+impl From<e::comment::Comment> for Comment {
+    fn from(from: e::comment::Comment) -> Self {
+        let e::comment::Comment {
+            id,
+            created_at,
+            text,
+            ..
+        } = from;
+        Self {
+            id: id.into(),
+            created: created_at.into_seconds(),
+            text,
+        }
+    }
+}
+
+impl From<(e::rating::Rating, Vec<e::comment::Comment>)> for Rating {
+    fn from(from: (e::rating::Rating, Vec<e::comment::Comment>)) -> Self {
+        let (rating, comments) = from;
+        let e::rating::Rating {
+            id,
+            created_at,
+            title,
+            value,
+            context,
+            source,
+            ..
+        } = rating;
+        Self {
+            id: id.into(),
+            created: created_at.into_seconds(),
+            title,
+            value: value.into(),
+            context: context.into(),
+            source: source.unwrap_or_default(),
+            comments: comments.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<e::rating::CommentRuleViolation> for CommentRuleViolation {
+    fn from(from: e::rating::CommentRuleViolation) -> Self {
+        use e::rating::CommentRuleViolation as V;
+        match from {
+            V::TooManyLinks => Self::TooManyLinks,
+            V::BannedPhrase => Self::BannedPhrase,
+            V::AccountTooNew => Self::AccountTooNew,
+        }
+    }
+}
+
+impl From<e::rating::PendingRating> for PendingRating {
+    fn from(from: e::rating::PendingRating) -> Self {
+        let e::rating::PendingRating {
+            rating,
+            comment,
+            created_by,
+            violations,
+        } = from;
+        Self {
+            place_id: rating.place_id.to_string(),
+            rating: (rating, vec![comment]).into(),
+            created_by,
+            violations: violations.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<e::place::OsmNodeProposal> for OsmNodeProposal {
+    fn from(from: e::place::OsmNodeProposal) -> Self {
+        let e::place::OsmNodeProposal {
+            id,
+            place_id,
+            osm_node_id,
+            place_revision,
+            created_at,
+            title,
+            pos,
+            address,
+            ..
+        } = from;
+        Self {
+            id: id.into(),
+            place_id: place_id.into(),
+            osm_node_id,
+            place_revision: place_revision.into(),
+            created_at: created_at.into_inner(),
+            title,
+            pos: pos.map(Into::into),
+            address: address.map(Into::into),
+        }
+    }
+}
+
 impl From<e::event::Event> for Event {
     fn from(e: e::event::Event) -> Self {
         let e::event::Event {
@@ -184,6 +351,12 @@ impl From<e::event::Event> for Event {
             registration,
             image_url,
             image_link_url,
+            time_zone,
+            recurrence,
+            address_auto_filled,
+            publish_at,
+            created_at,
+            updated_at,
             ..
         } = e;
 
@@ -246,6 +419,99 @@ impl From<e::event::Event> for Event {
             organizer,
             image_url: image_url.map(Into::into),
             image_link_url: image_link_url.map(Into::into),
+            time_zone,
+            recurrence: recurrence.as_ref().map(ToString::to_string),
+            address_auto_filled,
+            publish_at: publish_at.map(e::time::Timestamp::into_seconds),
+            created_at: created_at.map(e::time::Timestamp::into_seconds),
+            updated_at: updated_at.map(e::time::Timestamp::into_seconds),
+        }
+    }
+}
+
+// Invalid URLs and unknown registration types are dropped
+impl From<Event> for e::event::Event {
+    fn from(from: Event) -> Self {
+        let Event {
+            id,
+            title,
+            description,
+            start,
+            end,
+            lat,
+            lng,
+            street,
+            zip,
+            city,
+            country,
+            state,
+            email,
+            telephone,
+            homepage,
+            tags,
+            registration,
+            organizer,
+            image_url,
+            image_link_url,
+            time_zone,
+            recurrence,
+            address_auto_filled,
+            publish_at,
+            created_at,
+            updated_at,
+        } = from;
+        let address = e::address::Address {
+            street,
+            zip,
+            city,
+            country,
+            state,
+        };
+        let address = if address.is_empty() {
+            None
+        } else {
+            Some(address)
+        };
+        let location = match (lat, lng) {
+            (Some(lat), Some(lng)) => Some(e::location::Location {
+                pos: e::geo::MapPoint::from_lat_lng_deg(lat, lng),
+                address,
+            }),
+            _ => address.map(|address| e::location::Location {
+                pos: Default::default(),
+                address: Some(address),
+            }),
+        };
+        let contact = e::contact::Contact {
+            name: organizer,
+            email: email.map(Into::into),
+            phone: telephone,
+        };
+        Self {
+            id: id.into(),
+            title,
+            description,
+            start: NaiveDateTime::from_timestamp(start, 0),
+            end: end.map(|end| NaiveDateTime::from_timestamp(end, 0)),
+            location,
+            contact: if contact.is_empty() {
+                None
+            } else {
+                Some(contact)
+            },
+            tags,
+            homepage: homepage.and_then(|url| url.parse().ok()),
+            created_by: None,
+            registration: registration.and_then(|r| r.parse().ok()),
+            archived: None,
+            image_url: image_url.and_then(|url| url.parse().ok()),
+            image_link_url: image_link_url.and_then(|url| url.parse().ok()),
+            time_zone,
+            recurrence: recurrence.and_then(|r| r.parse().ok()),
+            address_auto_filled,
+            publish_at: publish_at.map(e::time::Timestamp::from_seconds),
+            created_at: created_at.map(e::time::Timestamp::from_seconds),
+            updated_at: updated_at.map(e::time::Timestamp::from_seconds),
         }
     }
 }
@@ -278,6 +544,91 @@ impl From<ClearanceForPlace> for e::clearance::ClearanceForPlace {
     }
 }
 
+impl From<e::clearance::ClearanceDecision> for ClearanceDecision {
+    fn from(from: e::clearance::ClearanceDecision) -> Self {
+        use e::clearance::ClearanceDecision::*;
+        match from {
+            Approved => ClearanceDecision::Approved,
+            Rejected => ClearanceDecision::Rejected,
+        }
+    }
+}
+
+impl From<e::clearance::ClearanceLogEntry> for ClearanceLogEntry {
+    fn from(from: e::clearance::ClearanceLogEntry) -> Self {
+        let e::clearance::ClearanceLogEntry {
+            org_id,
+            place_id,
+            place_revision,
+            cleared_revision,
+            decision,
+            decided_at,
+            api_token_id,
+            api_token_label,
+        } = from;
+        Self {
+            org_id: org_id.into(),
+            place_id: place_id.into(),
+            place_revision: place_revision.into(),
+            cleared_revision: cleared_revision.into(),
+            decision: decision.into(),
+            decided_at: decided_at.into_inner(),
+            api_token_id: api_token_id.map(Into::into),
+            api_token_label,
+        }
+    }
+}
+
+impl From<e::subscription::OrganizationSubscription> for OrganizationSubscription {
+    fn from(from: e::subscription::OrganizationSubscription) -> Self {
+        let e::subscription::OrganizationSubscription {
+            id,
+            user_email: _,
+            org_id,
+        } = from;
+        Self {
+            id: id.into(),
+            org_id: org_id.into(),
+        }
+    }
+}
+
+impl From<e::organization::TagModerationPolicy> for TagModerationPolicy {
+    fn from(from: e::organization::TagModerationPolicy) -> Self {
+        use e::organization::TagModerationPolicy::*;
+        match from {
+            Forbidden => TagModerationPolicy::Forbidden,
+            AllowFreely => TagModerationPolicy::AllowFreely,
+            RequireClearance => TagModerationPolicy::RequireClearance,
+        }
+    }
+}
+
+impl From<TagModerationPolicy> for e::organization::TagModerationPolicy {
+    fn from(from: TagModerationPolicy) -> Self {
+        use e::organization::TagModerationPolicy::*;
+        match from {
+            TagModerationPolicy::Forbidden => Forbidden,
+            TagModerationPolicy::AllowFreely => AllowFreely,
+            TagModerationPolicy::RequireClearance => RequireClearance,
+        }
+    }
+}
+
+impl From<e::organization::DailyQuotas> for DailyQuotas {
+    fn from(from: e::organization::DailyQuotas) -> Self {
+        let e::organization::DailyQuotas { events, places } = from;
+        Self { events, places }
+    }
+}
+
+impl From<DailyQuotas> for e::organization::DailyQuotas {
+    fn from(from: DailyQuotas) -> Self {
+        let DailyQuotas { events, places } = from;
+        Self { events, places }
+    }
+}
+
 impl From<e::geo::MapPoint> for LatLonDegrees {
     fn from(from: e::geo::MapPoint) -> Self {
         Self(from.lat().to_deg(), from.lng().to_deg())
@@ -473,6 +824,7 @@ impl From<e::place::PlaceRevision> for PlaceRevision {
             title,
             description,
             location,
+            address_auto_filled: _,
             contact,
             opening_hours,
             founded_on,
@@ -514,6 +866,7 @@ impl From<PlaceRevision> for e::place::PlaceRevision {
             title,
             description,
             location: location.into(),
+            address_auto_filled: false,
             contact: Some(contact.into()),
             opening_hours: opening_hours.map(Into::into),
             founded_on: founded_on.map(Into::into),
@@ -537,13 +890,16 @@ impl From<e::place::PlaceHistory> for PlaceHistory {
                     )
                 })
                 .collect(),
+            notes: vec![],
         }
     }
 }
 
 impl From<PlaceHistory> for e::place::PlaceHistory {
     fn from(from: PlaceHistory) -> Self {
-        let PlaceHistory { place, revisions } = from;
+        let PlaceHistory {
+            place, revisions, ..
+        } = from;
         Self {
             place: place.into(),
             revisions: revisions
@@ -559,6 +915,45 @@ impl From<PlaceHistory> for e::place::PlaceHistory {
     }
 }
 
+impl From<e::place::PlaceNote> for PlaceNote {
+    fn from(from: e::place::PlaceNote) -> Self {
+        let e::place::PlaceNote {
+            id,
+            parent_id,
+            created,
+            text,
+            ..
+        } = from;
+        Self {
+            id: id.into(),
+            parent: parent_id.map(Into::into),
+            created: created.into(),
+            text,
+        }
+    }
+}
+
+impl From<e::place::PlaceConfirmationRequest> for UnansweredConfirmationRequest {
+    fn from(from: e::place::PlaceConfirmationRequest) -> Self {
+        let e::place::PlaceConfirmationRequest {
+            place_id,
+            sent_to,
+            sent_at,
+            flagged_at,
+            ..
+        } = from;
+        debug_assert!(flagged_at.is_some());
+        Self {
+            place_id: place_id.into(),
+            sent_to: sent_to.into(),
+            sent_at: sent_at.into_inner(),
+            flagged_at: flagged_at
+                .map(e::time::TimestampMs::into_inner)
+                .unwrap_or_default(),
+        }
+    }
+}
+
 impl From<e::activity::ActivityLog> for ActivityLog {
     fn from(from: e::activity::ActivityLog) -> Self {
         let e::activity::ActivityLog {
@@ -621,3 +1016,85 @@ impl From<ReviewStatusLog> for e::review::ReviewStatusLog {
         }
     }
 }
+
+/// The review status is not revealed by default
+pub fn entry_from_place_with_ratings(
+    place: e::place::Place,
+    ratings: Vec<e::rating::Rating>,
+) -> Entry {
+    let e::place::Place {
+        id,
+        license,
+        revision,
+        created,
+        title,
+        description,
+        location,
+        address_auto_filled,
+        contact,
+        opening_hours,
+        founded_on,
+        links,
+        tags,
+    } = place;
+
+    let e::location::Location { pos, address } = location;
+    let lat = pos.lat().to_deg();
+    let lng = pos.lng().to_deg();
+    let e::address::Address {
+        street,
+        zip,
+        city,
+        country,
+        state,
+    } = address.unwrap_or_default();
+
+    let e::contact::Contact {
+        name: contact_name,
+        email,
+        phone: telephone,
+    } = contact.unwrap_or_default();
+
+    let (homepage_url, image_url, image_link_url, custom_links) = links
+        .map(
+            |e::links::Links {
+                 homepage,
+                 image,
+                 image_href,
+                 custom,
+             }| (homepage, image, image_href, custom),
+        )
+        .unwrap_or_default();
+
+    let (tags, categories) = e::category::Category::split_from_tags(tags);
+
+    Entry {
+        id: id.into(),
+        created: created.at.into_seconds(),
+        version: revision.into(),
+        title,
+        description,
+        lat,
+        lng,
+        street,
+        zip,
+        city,
+        country,
+        state,
+        contact_name,
+        email: email.map(Into::into),
+        telephone,
+        homepage: homepage_url.map(Into::into),
+        opening_hours: opening_hours.map(Into::into),
+        founded_on: founded_on.map(Into::into),
+        categories: categories.into_iter().map(|c| c.id.to_string()).collect(),
+        tags,
+        ratings: ratings.into_iter().map(|r| r.id.to_string()).collect(),
+        license: Some(license),
+        image_url: image_url.map(Into::into),
+        image_link_url: image_link_url.map(Into::into),
+        custom_links: custom_links.into_iter().map(Into::into).collect(),
+        address_auto_filled,
+        status: None,
+    }
+}
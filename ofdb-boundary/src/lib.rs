@@ -15,6 +15,9 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "entity-conversions")]
 mod conv;
 
+#[cfg(feature = "entity-conversions")]
+pub use conv::entry_from_place_with_ratings;
+
 type RevisionValue = u64;
 type Url = String;
 
@@ -49,6 +52,14 @@ pub struct Entry {
 
     #[serde(rename = "custom", skip_serializing_if = "Vec::is_empty", default = "Default::default")]
     pub custom_links   : Vec<CustomLink>,
+
+    // The address has been resolved from the coordinates
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub address_auto_filled: bool,
+
+    // Only revealed to scouts and admins
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub status         : Option<ReviewStatus>,
 }
 
 #[rustfmt::skip]
@@ -157,6 +168,49 @@ pub struct Event {
     pub image_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub image_link_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
+    /// Recurrence rule (RRULE), e.g. "FREQ=WEEKLY;COUNT=4"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<String>,
+    /// The address has been resolved from the coordinates
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub address_auto_filled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publish_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+}
+
+#[rustfmt::skip]
+#[derive(Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq))]
+pub struct NewEvent {
+    pub title        : String,
+    pub description  : Option<String>,
+    pub start        : i64,
+    pub end          : Option<i64>,
+    pub lat          : Option<f64>,
+    pub lng          : Option<f64>,
+    pub street       : Option<String>,
+    pub zip          : Option<String>,
+    pub city         : Option<String>,
+    pub country      : Option<String>,
+    pub state        : Option<String>,
+    pub email        : Option<String>,
+    pub telephone    : Option<String>,
+    pub homepage     : Option<String>,
+    pub tags         : Option<Vec<String>>,
+    pub created_by   : Option<String>,
+    pub registration : Option<String>,
+    pub organizer    : Option<String>,
+    pub image_url     : Option<String>,
+    pub image_link_url: Option<String>,
+    pub time_zone     : Option<String>,
+    pub recurrence    : Option<String>,
+    pub publish_at    : Option<i64>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -254,6 +308,59 @@ pub struct PlaceSearchResult {
     pub categories: Vec<String>,
     pub tags: Vec<String>,
     pub ratings: EntrySearchRatings,
+    /// Completeness of the place (0 - 100)
+    #[serde(default)]
+    pub quality: u8,
+    /// Distance in meters from the requested origin
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance: Option<f64>,
+    /// Only included on request
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub preview: Option<HomepagePreview>,
+}
+
+/// Metadata that has been fetched from the homepage of a place
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq))]
+pub struct HomepagePreview {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_url: Option<String>,
+}
+
+/// Compact search result of nearby places
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct NearbyPlace {
+    pub id: String,
+    pub title: String,
+    pub lat: f64,
+    pub lng: f64,
+    /// Distance in meters from the requested position
+    pub distance: f64,
+    pub categories: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, Copy, PartialEq, Eq))]
+#[serde(rename_all = "lowercase")]
+pub enum SearchSuggestionKind {
+    Place,
+    Tag,
+}
+
+/// Completion of a search text
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct SearchSuggestion {
+    pub kind: SearchSuggestionKind,
+    /// The title of the place or the tag
+    pub text: String,
+    /// The id of the place
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -266,6 +373,7 @@ pub enum ReviewStatus {
     Archived,
     Confirmed,
     Created,
+    Draft,
     Rejected,
 }
 
@@ -281,6 +389,24 @@ pub struct Review {
 pub struct SearchResponse {
     pub visible: Vec<PlaceSearchResult>,
     pub invisible: Vec<PlaceSearchResult>,
+    /// Only included on request
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub facets: Option<SearchFacets>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct FacetCount {
+    pub value: String,
+    pub count: u64,
+}
+
+/// The number of visible results per category and tag
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct SearchFacets {
+    pub categories: Vec<FacetCount>,
+    pub tags: Vec<FacetCount>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -296,6 +422,45 @@ pub enum UserRole {
     Admin,
 }
 
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "extra-derive",
+    derive(Debug, Clone, Copy, PartialEq, Eq, Hash)
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangedEntity {
+    Place,
+    Event,
+    Rating,
+    Comment,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "extra-derive",
+    derive(Debug, Clone, Copy, PartialEq, Eq, Hash)
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    Reviewed,
+    Archived,
+    Deleted,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct ChangeRecord {
+    pub entity: ChangedEntity,
+    pub id: String,
+    pub kind: ChangeKind,
+    /// Unix time in seconds
+    pub at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor_role: Option<UserRole>,
+}
+
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
 pub struct BboxSubscription {
@@ -304,6 +469,26 @@ pub struct BboxSubscription {
     pub south_west_lng: f64,
     pub north_east_lat: f64,
     pub north_east_lng: f64,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub digest: Option<NotificationDigest>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "extra-derive",
+    derive(Debug, Clone, Copy, PartialEq, Eq, Hash)
+)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationDigest {
+    Daily,
+    Weekly,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct OrganizationSubscription {
+    pub id: String,
+    pub org_id: String,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -356,6 +541,26 @@ pub struct Rating {
     pub source: String,
 }
 
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, Copy, PartialEq, Eq))]
+#[serde(rename_all = "snake_case")]
+pub enum CommentRuleViolation {
+    TooManyLinks,
+    BannedPhrase,
+    AccountTooNew,
+}
+
+/// A new rating that is held back for moderation
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct PendingRating {
+    pub place_id: String,
+    pub rating: Rating,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub created_by: Option<String>,
+    pub violations: Vec<CommentRuleViolation>,
+}
+
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
 pub struct PendingClearanceForPlace {
@@ -364,6 +569,24 @@ pub struct PendingClearanceForPlace {
     pub last_cleared_revision: Option<RevisionValue>,
 }
 
+/// A place with an owned tag of an organization
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct OrgPlaceSearchResult {
+    #[serde(flatten)]
+    pub place: PlaceSearchResult,
+    /// Only present if the latest changes have not been cleared yet
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub pending_clearance: Option<PendingClearanceForPlace>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct OrgSearchResponse {
+    pub places: Vec<OrgPlaceSearchResult>,
+    pub events: Vec<Event>,
+}
+
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
 pub struct ClearanceForPlace {
@@ -371,6 +594,458 @@ pub struct ClearanceForPlace {
     pub cleared_revision: Option<RevisionValue>,
 }
 
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "extra-derive",
+    derive(Debug, Clone, Copy, PartialEq, Eq, Hash)
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ClearanceDecision {
+    Approved,
+    Rejected,
+}
+
+/// A decision of an organization about a pending clearance
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct ClearanceLogEntry {
+    pub org_id: String,
+    pub place_id: String,
+    /// The current revision when the decision was made
+    pub place_revision: RevisionValue,
+    /// The last cleared revision afterwards
+    pub cleared_revision: RevisionValue,
+    pub decision: ClearanceDecision,
+    pub decided_at: i64,
+    /// The API token of the organization that has been used
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub api_token_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub api_token_label: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "extra-derive",
+    derive(Debug, Clone, Copy, PartialEq, Eq, Hash)
+)]
+#[serde(rename_all = "snake_case")]
+pub enum TagModerationPolicy {
+    Forbidden,
+    AllowFreely,
+    RequireClearance,
+}
+
+/// Another organization that receives an owned tag
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct TagOwner {
+    pub org_id: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct NewOrganizationApiToken {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub expires_at: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct OrganizationApiToken {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub label: Option<String>,
+    pub created_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub expires_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub last_used_at: Option<i64>,
+    /// The bearer token is only revealed once when
+    /// creating the token
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct OsmTagMappingRule {
+    pub key: String,
+    /// Matches any value of the key if missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct OsmTagMapping {
+    pub rules: Vec<OsmTagMappingRule>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct OsmImport {
+    /// Overpass QL
+    pub query: String,
+    pub mapping: OsmTagMapping,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct TagSuggestionRequest {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq))]
+pub struct TagSuggestion {
+    pub tag: String,
+    /// Between 0.0 (unlikely) and 1.0 (certain)
+    pub score: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct TagUsage {
+    pub tag: String,
+    pub places: u64,
+    pub places_by_status: Vec<ReviewStatusCount>,
+    pub events: u64,
+    pub regions: Vec<RegionTagUsage>,
+    pub recent_places: Vec<RecentTagUsage>,
+    pub recent_events: Vec<RecentTagUsage>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct ReviewStatusCount {
+    pub status: ReviewStatus,
+    pub count: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct RegionTagUsage {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub country: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub state: Option<String>,
+    pub places: u64,
+    pub events: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct RecentTagUsage {
+    pub id: String,
+    pub title: String,
+    /// The last change of places or the start of events
+    pub at: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct TagRenaming {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct UserMerge {
+    /// The e-mail address of the user that is merged and anonymized
+    pub from: String,
+    /// The e-mail address of the surviving user
+    pub into: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct MergedUser {
+    pub place_revisions: u64,
+    pub place_reviews: u64,
+    pub ratings: u64,
+    pub comments: u64,
+    pub events: u64,
+    pub subscriptions: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct Announcement {
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct QueuedAnnouncement {
+    /// The number of recipients
+    pub recipients: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct ServerMetrics {
+    pub search_index: SearchIndexMetrics,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct SearchIndexMetrics {
+    pub commits: u64,
+    pub last_commit_duration_ms: u64,
+    pub max_commit_duration_ms: u64,
+    pub segments: usize,
+    pub pending_docs: usize,
+    /// Too many segments, merging can't keep up with the commits
+    pub merges_behind: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, Copy, PartialEq, Eq))]
+#[serde(rename_all = "snake_case")]
+pub enum PlaceQualityIssue {
+    MissingAddress,
+    InvalidUrl,
+    ZeroCoordinates,
+    NoTags,
+    Unreviewed,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct PlaceQualityIssues {
+    pub id: String,
+    pub title: String,
+    pub issues: Vec<PlaceQualityIssue>,
+}
+
+/// Places with quality issues within a region
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct RegionQualityIssues {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub country: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub state: Option<String>,
+    pub places: Vec<PlaceQualityIssues>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct UserPreferences {
+    /// Receive announcements of the operators by e-mail
+    pub announcements: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "extra-derive",
+    derive(Debug, Clone, Copy, PartialEq, Eq, Hash)
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiTokenScope {
+    Read,
+    Write,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct NewPersonalApiToken {
+    pub scope: ApiTokenScope,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub label: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct PersonalApiToken {
+    pub id: String,
+    pub scope: ApiTokenScope,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub label: Option<String>,
+    pub created_at: i64,
+    /// The bearer token is only revealed once when
+    /// creating the token
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct RenamedTag {
+    /// Number of places with a new revision
+    pub places: u64,
+    pub events: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq))]
+pub struct ExternalPlace {
+    pub title: String,
+    pub lat: f64,
+    pub lng: f64,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub street: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub zip: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub city: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq))]
+pub struct PlaceMatch {
+    pub id: String,
+    pub title: String,
+    /// Between 0.0 (unlikely) and 1.0 (certain)
+    pub confidence: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, PartialEq))]
+pub struct EnrichedPlace {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub address: Option<Address>,
+    /// The address has been completed by reverse geocoding
+    pub address_resolved: bool,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub country: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub state: Option<String>,
+    pub suggested_tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct OsmImportReport {
+    pub imported: u64,
+    pub already_imported: u64,
+    pub duplicates: u64,
+    pub unmapped: u64,
+    pub failed: u64,
+    #[serde(default)]
+    pub rows: Vec<OsmImportRow>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, Copy, PartialEq, Eq))]
+#[serde(rename_all = "snake_case")]
+pub enum OsmImportOutcome {
+    Imported,
+    AlreadyImported,
+    Duplicate,
+    Unmapped,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct OsmImportRow {
+    pub osm_node_id: u64,
+    pub outcome: OsmImportOutcome,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub categories: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub place_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub error: Option<String>,
+}
+
+/// Changes of an imported place in OpenStreetMap
+/// that are waiting for a scout
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct OsmNodeProposal {
+    pub id: String,
+    pub place_id: String,
+    pub osm_node_id: u64,
+    pub place_revision: RevisionValue,
+    pub created_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub pos: Option<MapPoint>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub address: Option<Address>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct QuotaUsage {
+    pub used: u32,
+    /// Unlimited if missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quota: Option<u32>,
+}
+
+/// Individual upload quotas of an organization per day.
+/// Missing quotas fall back to the defaults of the instance.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct DailyQuotas {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub events: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub places: Option<u32>,
+}
+
+/// Uploads of an organization on the current day (UTC)
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct OrganizationUsage {
+    pub events: QuotaUsage,
+    pub places: QuotaUsage,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
+pub struct RatingCounts {
+    pub total: u64,
+    pub diversity: u64,
+    pub fairness: u64,
+    pub humanity: u64,
+    pub renewable: u64,
+    pub solidarity: u64,
+    pub transparency: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct MonthlyAvgRating {
+    /// Formatted as "YYYY-MM"
+    pub month: String,
+    pub count: u64,
+    pub avg: AvgRatingValue,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct PlaceStats {
+    pub ratings: RatingCounts,
+    pub monthly_avg_ratings: Vec<MonthlyAvgRating>,
+    pub revisions: u64,
+    /// Only available if views are tracked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub views: Option<u64>,
+}
+
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(feature = "extra-derive", derive(Debug, Clone, PartialEq, Eq))]
 pub struct ResultCount {
@@ -410,6 +1085,15 @@ impl Address {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, PartialEq))]
+pub struct AddressSuggestion {
+    pub label: String,
+    pub lat: f64,
+    pub lng: f64,
+    pub address: Address,
+}
+
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(feature = "extra-derive", derive(Debug, PartialEq))]
 pub struct Location {
@@ -543,6 +1227,51 @@ pub struct PlaceRevision {
 pub struct PlaceHistory {
     pub place: PlaceRoot,
     pub revisions: Vec<(PlaceRevision, Vec<ReviewStatusLog>)>,
+
+    /// Internal notes that are only revealed to scouts and admins
+    #[serde(skip_serializing_if = "Vec::is_empty", default = "Default::default")]
+    pub notes: Vec<PlaceNote>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug))]
+pub struct PlaceNote {
+    pub id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+
+    pub created: Activity,
+
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct ConfirmPlaceStillValid {
+    pub token: String,
+}
+
+/// A request for confirming that a place is still
+/// valid that has not been answered in time.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug))]
+pub struct UnansweredConfirmationRequest {
+    pub place_id: String,
+    pub sent_to: String,
+    /// Unix time in milliseconds
+    pub sent_at: i64,
+    /// Unix time in milliseconds
+    pub flagged_at: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct NewPlaceNote {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+
+    pub text: String,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -626,3 +1355,51 @@ impl From<Entry> for UpdatePlace {
 pub struct JwtToken {
     pub token: String,
 }
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct Error {
+    /// HTTP status code
+    pub http_status: u16,
+    pub message: String,
+}
+
+/// Rejected update of a place that has been
+/// modified in the meantime (HTTP 409).
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug))]
+pub struct RevisionConflict {
+    /// HTTP status code
+    pub http_status: u16,
+    pub message: String,
+    /// The revision that has been modified, i.e. the
+    /// next update needs to refer to `current_version + 1`
+    pub current_version: u64,
+    /// The editor is only disclosed to scouts,
+    /// admins and organizations
+    pub last_change: Activity,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct CsrfToken {
+    pub token: String,
+}
+
+/// The outcome of validating a new place or event
+/// without storing it.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "extra-derive", derive(Debug, Clone))]
+pub struct Validation {
+    /// Normalized tags, even if the validation failed
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub homepage: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub image_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub image_link_url: Option<String>,
+    /// The errors that would have rejected the request
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub errors: Vec<Error>,
+}
@@ -0,0 +1,270 @@
+//! A blocking client for the OpenFairDB API that reuses the
+//! data structures of [`ofdb_boundary`].
+//!
+//! ```no_run
+//! use ofdb_client::{Client, SearchQuery};
+//!
+//! let client = Client::new("https://api.ofdb.io/v0");
+//! let query = SearchQuery {
+//!     bbox: "47.0,5.0,55.0,15.0".into(),
+//!     text: Some("bioladen".into()),
+//!     ..Default::default()
+//! };
+//! let response = client.search(&query).unwrap();
+//! println!("Found {} places", response.visible.len());
+//! ```
+
+use ofdb_boundary as json;
+use reqwest::blocking::{RequestBuilder, Response};
+use serde::{de::DeserializeOwned, Serialize};
+use thiserror::Error;
+
+pub use ofdb_boundary;
+
+const USER_AGENT: &str = concat!("ofdb-client/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Request has been rejected with status {0}")]
+    Status(u16),
+    #[error("No token has been issued")]
+    MissingToken,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Parameters of `GET /search`. Lists are comma-separated.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct SearchQuery {
+    /// `lat,lng` of the south-west corner followed by
+    /// `lat,lng` of the north-east corner
+    pub bbox: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub categories: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_avg_rating: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_rating_count: Option<u32>,
+    /// Only places that have been confirmed within the given number of days
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub boost_freshness: Option<bool>,
+    /// Include the previews of the homepages
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub with_previews: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// Parameters of `GET /events`
+#[derive(Debug, Default, Clone)]
+pub struct EventQuery {
+    pub bbox: Option<String>,
+    pub tags: Vec<String>,
+    pub text: Option<String>,
+    /// Unix time in seconds
+    pub start_min: Option<i64>,
+    /// Unix time in seconds
+    pub start_max: Option<i64>,
+    pub created_by: Option<String>,
+    /// Unix time in seconds of the last synchronization
+    pub since: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+impl EventQuery {
+    // Tags are passed as repeated parameters
+    fn to_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![];
+        if let Some(ref bbox) = self.bbox {
+            params.push(("bbox", bbox.clone()));
+        }
+        for tag in &self.tags {
+            params.push(("tag", tag.clone()));
+        }
+        if let Some(ref text) = self.text {
+            params.push(("text", text.clone()));
+        }
+        if let Some(start_min) = self.start_min {
+            params.push(("start_min", start_min.to_string()));
+        }
+        if let Some(start_max) = self.start_max {
+            params.push(("start_max", start_max.to_string()));
+        }
+        if let Some(ref created_by) = self.created_by {
+            params.push(("created_by", created_by.clone()));
+        }
+        if let Some(since) = self.since {
+            params.push(("since", since.to_string()));
+        }
+        if let Some(limit) = self.limit {
+            params.push(("limit", limit.to_string()));
+        }
+        params
+    }
+}
+
+/// Sends requests to a single OpenFairDB instance.
+///
+/// Requests are authorized with a bearer token, i.e. either the
+/// API token of an organization or a JWT that has been returned
+/// by [`Client::login`].
+pub struct Client {
+    api_url: String,
+    token: Option<String>,
+    http: reqwest::blocking::Client,
+}
+
+impl Client {
+    pub fn new(api_url: impl Into<String>) -> Self {
+        Self {
+            api_url: api_url.into().trim_end_matches('/').to_string(),
+            token: None,
+            http: reqwest::blocking::Client::new(),
+        }
+    }
+
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> RequestBuilder {
+        let req = self
+            .http
+            .request(method, &format!("{}{}", self.api_url, path))
+            .header(reqwest::header::USER_AGENT, USER_AGENT);
+        match self.token {
+            Some(ref token) => req.bearer_auth(token),
+            None => req,
+        }
+    }
+
+    fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        Ok(send(self.request(reqwest::Method::GET, path))?.json()?)
+    }
+
+    fn send_json<B: Serialize, T: DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: &B,
+    ) -> Result<T> {
+        Ok(send(self.request(method, path).json(body))?.json()?)
+    }
+
+    /// Authorizes all subsequent requests of this client.
+    ///
+    /// Fails if the server doesn't issue JWTs.
+    pub fn login(&mut self, credentials: &json::Credentials) -> Result<()> {
+        let token: Option<json::JwtToken> =
+            self.send_json(reqwest::Method::POST, "/login", credentials)?;
+        let token = token.ok_or(Error::MissingToken)?;
+        self.token = Some(token.token);
+        Ok(())
+    }
+
+    pub fn logout(&mut self) -> Result<()> {
+        send(self.request(reqwest::Method::POST, "/logout").json(&()))?;
+        self.token = None;
+        Ok(())
+    }
+
+    pub fn search(&self, query: &SearchQuery) -> Result<json::SearchResponse> {
+        let req = self.request(reqwest::Method::GET, "/search").query(query);
+        Ok(send(req)?.json()?)
+    }
+
+    pub fn entries(&self, ids: &[&str]) -> Result<Vec<json::Entry>> {
+        self.get(&format!("/entries/{}", ids.join(",")))
+    }
+
+    /// Returns the id of the new place
+    pub fn create_place(&self, place: &json::NewPlace) -> Result<String> {
+        self.send_json(reqwest::Method::POST, "/entries", place)
+    }
+
+    pub fn update_place(&self, id: &str, place: &json::UpdatePlace) -> Result<()> {
+        let _: String = self.send_json(reqwest::Method::PUT, &format!("/entries/{}", id), place)?;
+        Ok(())
+    }
+
+    pub fn event(&self, id: &str) -> Result<json::Event> {
+        self.get(&format!("/events/{}", id))
+    }
+
+    pub fn events(&self, query: &EventQuery) -> Result<Vec<json::Event>> {
+        let req = self
+            .request(reqwest::Method::GET, "/events")
+            .query(&query.to_params());
+        Ok(send(req)?.json()?)
+    }
+
+    /// Requires the API token of an organization.
+    /// Returns the id of the new event.
+    pub fn create_event(&self, event: &json::NewEvent) -> Result<String> {
+        self.send_json(reqwest::Method::POST, "/events", event)
+    }
+
+    /// Requires the API token of an organization
+    pub fn update_event(&self, id: &str, event: &json::NewEvent) -> Result<()> {
+        self.send_json(reqwest::Method::PUT, &format!("/events/{}", id), event)
+    }
+
+    /// Requires the API token of an organization
+    pub fn delete_event(&self, id: &str) -> Result<()> {
+        send(self.request(reqwest::Method::DELETE, &format!("/events/{}", id)))?;
+        Ok(())
+    }
+}
+
+fn send(req: RequestBuilder) -> Result<Response> {
+    let res = req.send()?;
+    let status = res.status();
+    if !status.is_success() {
+        return Err(Error::Status(status.as_u16()));
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_query_params() {
+        let query = EventQuery {
+            tags: vec!["bio".into(), "fair".into()],
+            start_min: Some(100),
+            limit: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![
+                ("tag", "bio".to_string()),
+                ("tag", "fair".to_string()),
+                ("start_min", "100".to_string()),
+                ("limit", "10".to_string()),
+            ],
+            query.to_params()
+        );
+        assert!(EventQuery::default().to_params().is_empty());
+    }
+
+    #[test]
+    fn trim_trailing_slash_of_api_url() {
+        assert_eq!(
+            "https://api.ofdb.io/v0",
+            Client::new("https://api.ofdb.io/v0/").api_url
+        );
+    }
+}
@@ -1,5 +1,20 @@
-use ofdb_entities::email::Email;
+use ofdb_entities::{email::Email, organization::EmailBranding};
 
 pub trait EmailGateway {
     fn compose_and_send(&self, recipients: &[Email], subject: &str, body: &str);
+
+    /// Sends an e-mail on behalf of an organization, i.e. with
+    /// the configured sender name and reply-to address.
+    ///
+    /// The branding is ignored by gateways that don't support it.
+    fn compose_and_send_branded(
+        &self,
+        recipients: &[Email],
+        branding: &EmailBranding,
+        subject: &str,
+        body: &str,
+    ) {
+        let _ = branding;
+        self.compose_and_send(recipients, subject, body);
+    }
 }
@@ -1,5 +1,170 @@
 use ofdb_entities::address::Address;
+use std::{
+    sync::{Mutex, PoisonError},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// A complete address that matches an incomplete query
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddressSuggestion {
+    pub label: String,
+    pub lat: f64,
+    pub lng: f64,
+    pub address: Address,
+}
 
 pub trait GeoCodingGateway {
     fn resolve_address_lat_lng(&self, addr: &Address) -> Option<(f64, f64)>;
+
+    /// Reverse geocoding is optional and not supported by all providers.
+    fn resolve_lat_lng_address(&self, _lat: f64, _lng: f64) -> Option<Address> {
+        None
+    }
+
+    /// Autocompletion is optional and not supported by all providers.
+    fn complete_address(&self, _query: &str, _limit: usize) -> Vec<AddressSuggestion> {
+        vec![]
+    }
+}
+
+/// Delays requests to the wrapped gateway to respect
+/// the usage policy of the provider.
+pub struct RateLimitedGeoCodingGateway<G> {
+    gateway: G,
+    min_request_interval: Duration,
+    last_request: Mutex<Option<Instant>>,
+}
+
+impl<G> RateLimitedGeoCodingGateway<G> {
+    pub fn new(gateway: G, min_request_interval: Duration) -> Self {
+        Self {
+            gateway,
+            min_request_interval,
+            last_request: Mutex::new(None),
+        }
+    }
+}
+
+impl<G: GeoCodingGateway> RateLimitedGeoCodingGateway<G> {
+    fn throttled<T>(&self, request: impl FnOnce(&G) -> T) -> T {
+        // The lock is held during the request to serialize concurrent requests
+        let mut last_request = self
+            .last_request
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(elapsed) = last_request.map(|instant| instant.elapsed()) {
+            if elapsed < self.min_request_interval {
+                thread::sleep(self.min_request_interval - elapsed);
+            }
+        }
+        let res = request(&self.gateway);
+        *last_request = Some(Instant::now());
+        res
+    }
+}
+
+impl<G: GeoCodingGateway> GeoCodingGateway for RateLimitedGeoCodingGateway<G> {
+    fn resolve_address_lat_lng(&self, addr: &Address) -> Option<(f64, f64)> {
+        self.throttled(|gw| gw.resolve_address_lat_lng(addr))
+    }
+
+    fn resolve_lat_lng_address(&self, lat: f64, lng: f64) -> Option<Address> {
+        self.throttled(|gw| gw.resolve_lat_lng_address(lat, lng))
+    }
+
+    fn complete_address(&self, query: &str, limit: usize) -> Vec<AddressSuggestion> {
+        self.throttled(|gw| gw.complete_address(query, limit))
+    }
+}
+
+/// Asks all gateways in order until the address could be resolved.
+#[derive(Default)]
+pub struct GeoCodingGatewayChain {
+    gateways: Vec<Box<dyn GeoCodingGateway + Send + Sync>>,
+}
+
+impl GeoCodingGatewayChain {
+    pub fn new(gateways: Vec<Box<dyn GeoCodingGateway + Send + Sync>>) -> Self {
+        Self { gateways }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.gateways.is_empty()
+    }
+}
+
+impl GeoCodingGateway for GeoCodingGatewayChain {
+    fn resolve_address_lat_lng(&self, addr: &Address) -> Option<(f64, f64)> {
+        if addr.is_empty() {
+            return None;
+        }
+        self.gateways
+            .iter()
+            .find_map(|gw| gw.resolve_address_lat_lng(addr))
+    }
+
+    fn resolve_lat_lng_address(&self, lat: f64, lng: f64) -> Option<Address> {
+        self.gateways
+            .iter()
+            .filter_map(|gw| gw.resolve_lat_lng_address(lat, lng))
+            .find(|addr| !addr.is_empty())
+    }
+
+    fn complete_address(&self, query: &str, limit: usize) -> Vec<AddressSuggestion> {
+        if query.trim().is_empty() {
+            return vec![];
+        }
+        self.gateways
+            .iter()
+            .map(|gw| gw.complete_address(query, limit))
+            .find(|suggestions| !suggestions.is_empty())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedGateway(Option<(f64, f64)>);
+
+    impl GeoCodingGateway for FixedGateway {
+        fn resolve_address_lat_lng(&self, _: &Address) -> Option<(f64, f64)> {
+            self.0
+        }
+    }
+
+    fn address() -> Address {
+        Address {
+            city: Some("A city".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn chain_falls_back_to_next_gateway() {
+        let chain = GeoCodingGatewayChain::new(vec![
+            Box::new(FixedGateway(None)),
+            Box::new(FixedGateway(Some((1.0, 2.0)))),
+            Box::new(FixedGateway(Some((3.0, 4.0)))),
+        ]);
+        assert_eq!(Some((1.0, 2.0)), chain.resolve_address_lat_lng(&address()));
+    }
+
+    #[test]
+    fn chain_ignores_empty_addresses() {
+        let chain = GeoCodingGatewayChain::new(vec![Box::new(FixedGateway(Some((1.0, 2.0))))]);
+        assert_eq!(None, chain.resolve_address_lat_lng(&Address::default()));
+    }
+
+    #[test]
+    fn rate_limited_gateway_waits_between_requests() {
+        let interval = Duration::from_millis(50);
+        let gw = RateLimitedGeoCodingGateway::new(FixedGateway(Some((1.0, 2.0))), interval);
+        let start = Instant::now();
+        assert!(gw.resolve_address_lat_lng(&address()).is_some());
+        assert!(gw.resolve_address_lat_lng(&address()).is_some());
+        assert!(start.elapsed() >= interval);
+    }
 }
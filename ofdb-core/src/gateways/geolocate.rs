@@ -0,0 +1,65 @@
+use ofdb_entities::geo::{Distance, MapBbox, MapPoint};
+use std::net::IpAddr;
+
+// Regions are never smaller than a city, even if the
+// reported accuracy is much higher
+const MIN_REGION_RADIUS: Distance = Distance::from_meters(25_000.0);
+const MAX_REGION_RADIUS: Distance = Distance::from_meters(250_000.0);
+
+/// The approximate location of an IP address
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IpLocation {
+    pub pos: MapPoint,
+    /// Radius around the position that contains the
+    /// actual location with a high probability
+    pub accuracy: Option<Distance>,
+}
+
+impl IpLocation {
+    /// The region around the location, e.g. as the
+    /// default bounding box of searches.
+    pub fn region(&self) -> MapBbox {
+        let radius = self
+            .accuracy
+            .unwrap_or(MIN_REGION_RADIUS)
+            .to_meters()
+            .max(MIN_REGION_RADIUS.to_meters())
+            .min(MAX_REGION_RADIUS.to_meters());
+        let diameter = Distance::from_meters(2.0 * radius);
+        MapBbox::centered_around(self.pos, diameter, diameter)
+    }
+}
+
+pub trait IpGeoLocationGateway {
+    fn locate_ip(&self, ip: IpAddr) -> Option<IpLocation>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn region_around_ip_location() {
+        let pos = MapPoint::from_lat_lng_deg(48.0, 9.0);
+        let precise = IpLocation {
+            pos,
+            accuracy: Some(Distance::from_meters(1_000.0)),
+        };
+        let unknown = IpLocation {
+            pos,
+            accuracy: None,
+        };
+        assert_eq!(precise.region(), unknown.region());
+        assert!(precise.region().contains_point(pos));
+        let coarse = IpLocation {
+            pos,
+            accuracy: Some(Distance::from_meters(100_000.0)),
+        };
+        assert!(coarse
+            .region()
+            .contains_point(MapPoint::from_lat_lng_deg(48.5, 9.0)));
+        assert!(!precise
+            .region()
+            .contains_point(MapPoint::from_lat_lng_deg(48.5, 9.0)));
+    }
+}
@@ -0,0 +1,10 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+#[error("Link check failed: {0}")]
+pub struct LinkCheckError(pub String);
+
+pub trait LinkChecker {
+    /// Fails if the link is not reachable
+    fn check_link(&self, url: &str) -> Result<(), LinkCheckError>;
+}
@@ -0,0 +1,88 @@
+use ofdb_entities::geo::MapPoint;
+use std::f64::consts::PI;
+use thiserror::Error;
+
+/// A tile of a [slippy map](https://wiki.openstreetmap.org/wiki/Slippy_map_tilenames)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MapTile {
+    pub zoom: u8,
+    pub x: u32,
+    pub y: u32,
+}
+
+/// A tile together with the relative position of a point
+/// on that tile, i.e. a map snippet around that point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MapSnippet {
+    pub tile: MapTile,
+    /// Horizontal offset of the point from the left edge (0.0 - 1.0)
+    pub offset_x: f64,
+    /// Vertical offset of the point from the top edge (0.0 - 1.0)
+    pub offset_y: f64,
+}
+
+impl MapSnippet {
+    pub fn around(pos: MapPoint, zoom: u8) -> Self {
+        let n = f64::from(1u32 << zoom);
+        let lat = pos.lat().to_rad();
+        let x = (pos.lng().to_deg() + 180.0) / 360.0 * n;
+        let y = (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / PI) / 2.0 * n;
+        // Both coordinates are clamped to the valid range
+        let max = n - 1.0;
+        let tile_x = x.floor().max(0.0).min(max);
+        let tile_y = y.floor().max(0.0).min(max);
+        Self {
+            tile: MapTile {
+                zoom,
+                x: tile_x as u32,
+                y: tile_y as u32,
+            },
+            offset_x: (x - tile_x).max(0.0).min(1.0),
+            offset_y: (y - tile_y).max(0.0).min(1.0),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Fetching the map tile failed: {0}")]
+pub struct MapTileError(pub String);
+
+pub trait MapTileGateway {
+    /// The public URL of the tile image
+    fn tile_url(&self, tile: &MapTile) -> String;
+    /// Fetches the tile as a PNG image
+    fn fetch_tile(&self, tile: &MapTile) -> Result<Vec<u8>, MapTileError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snippet_around_point() {
+        let snippet = MapSnippet::around(MapPoint::from_lat_lng_deg(0.0, 0.0), 1);
+        assert_eq!(
+            MapTile {
+                zoom: 1,
+                x: 1,
+                y: 1
+            },
+            snippet.tile
+        );
+        assert!(snippet.offset_x < 0.001);
+        assert!(snippet.offset_y < 0.001);
+
+        // Berlin
+        let snippet = MapSnippet::around(MapPoint::from_lat_lng_deg(52.5200, 13.4050), 15);
+        assert_eq!(
+            MapTile {
+                zoom: 15,
+                x: 17604,
+                y: 10746
+            },
+            snippet.tile
+        );
+        assert!(snippet.offset_x > 0.0 && snippet.offset_x < 1.0);
+        assert!(snippet.offset_y > 0.0 && snippet.offset_y < 1.0);
+    }
+}
@@ -1,3 +1,9 @@
 pub mod email;
 pub mod geocode;
+pub mod geolocate;
+pub mod link_check;
+pub mod map_tile;
 pub mod notify;
+pub mod osm;
+pub mod preview;
+pub mod upstream;
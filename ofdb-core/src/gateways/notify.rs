@@ -1,5 +1,11 @@
 use ofdb_entities::{
-    category::Category, event::Event, nonce::EmailNonce, place::Place, user::User,
+    category::Category,
+    event::Event,
+    nonce::EmailNonce,
+    organization::{EmailBranding, PendingModeratedTagsNotification},
+    place::{Place, PlaceConfirmationRequest},
+    rating::Rating,
+    user::User,
 };
 
 pub trait NotificationGateway {
@@ -10,10 +16,27 @@ pub trait NotificationGateway {
         place: &Place,
         all_categories: Vec<Category>,
     );
+    fn place_moderated_tags_changed(
+        &self,
+        email_addresses: &[String],
+        place: &Place,
+        added_tags: &[String],
+        removed_tags: &[String],
+        branding: &EmailBranding,
+    );
+    fn moderated_tags_summary(
+        &self,
+        email_addresses: &[String],
+        notifications: &[PendingModeratedTagsNotification],
+        branding: &EmailBranding,
+    );
+    fn place_rated(&self, email_addresses: &[String], place: &Place, rating: &Rating);
     fn event_created(&self, email_addresses: &[String], event: &Event);
     fn event_updated(&self, email_addresses: &[String], event: &Event);
     fn user_registered_kvm(&self, user: &User);
     fn user_registered_ofdb(&self, user: &User);
     fn user_registered(&self, user: &User, url: &str);
     fn user_reset_password_requested(&self, email_nonce: &EmailNonce);
+    fn subscription_consent_requested(&self, email_nonce: &EmailNonce);
+    fn place_confirmation_requested(&self, place: &Place, request: &PlaceConfirmationRequest);
 }
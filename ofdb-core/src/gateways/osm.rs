@@ -0,0 +1,19 @@
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// A tagged node of OpenStreetMap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OsmNode {
+    pub id: u64,
+    pub lat: f64,
+    pub lon: f64,
+    pub tags: HashMap<String, String>,
+}
+
+#[derive(Debug, Error)]
+#[error("OSM query failed: {0}")]
+pub struct OsmQueryError(pub String);
+
+pub trait OsmGateway {
+    fn query_nodes(&self, query: &str) -> Result<Vec<OsmNode>, OsmQueryError>;
+}
@@ -0,0 +1,18 @@
+use thiserror::Error;
+
+/// Basic metadata of a web page, e.g. from its OpenGraph tags.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PagePreview {
+    pub title: Option<String>,
+    pub image_url: Option<String>,
+}
+
+#[derive(Debug, Error)]
+#[error("Fetching the page preview failed: {0}")]
+pub struct PreviewError(pub String);
+
+pub trait PreviewGateway {
+    /// Returns `None` if the page must not be fetched
+    /// according to the `robots.txt` of the site.
+    fn fetch_preview(&self, url: &str) -> Result<Option<PagePreview>, PreviewError>;
+}
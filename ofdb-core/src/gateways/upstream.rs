@@ -0,0 +1,35 @@
+use ofdb_entities::{event::Event, id::Id, place::Place, review::ReviewStatus, time::Timestamp};
+use thiserror::Error;
+
+/// A place or event that has been changed on an upstream instance.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UpstreamChange {
+    Place(Id),
+    Event(Id),
+}
+
+/// A batch of changes that has been fetched from upstream.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UpstreamChanges {
+    /// Changes of places and events in chronological order
+    pub changes: Vec<(Timestamp, UpstreamChange)>,
+    /// The time of the most recent change in the batch, including
+    /// changes of entities that are not mirrored
+    pub last_change_at: Option<Timestamp>,
+}
+
+#[derive(Debug, Error)]
+#[error("Upstream request failed: {0}")]
+pub struct UpstreamError(pub String);
+
+/// Another OpenFairDB instance that is mirrored.
+pub trait UpstreamGateway {
+    /// Returns the changes of places and events in chronological order,
+    /// starting at the given time. The number of changes might be limited
+    /// and the changes are only delivered with second precision.
+    fn changes_since(&self, since: Timestamp) -> Result<UpstreamChanges, UpstreamError>;
+    /// Returns `None` if the place doesn't exist (anymore)
+    fn place(&self, id: &Id) -> Result<Option<(Place, ReviewStatus)>, UpstreamError>;
+    /// Returns `None` if the event doesn't exist (anymore)
+    fn event(&self, id: &Id) -> Result<Option<Event>, UpstreamError>;
+}
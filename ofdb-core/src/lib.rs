@@ -1,5 +1,6 @@
 pub mod bbox;
 pub mod gateways;
+pub mod quality;
 pub mod rating;
 pub mod tag;
 pub mod text;
@@ -0,0 +1,121 @@
+use ofdb_entities::{category::*, place::*, time::*};
+
+/// The best possible quality score
+pub const MAX_QUALITY_SCORE: u8 = 100;
+
+// Descriptions shorter than this are considered incomplete
+const MIN_DESCRIPTION_LEN: usize = 50;
+
+// Confirmations expire after about a year
+const CONFIRMATION_VALIDITY_MILLIS: i64 = 365 * 24 * 60 * 60 * 1000;
+
+// Each rating counts up to this number of ratings
+const MAX_SCORED_RATINGS: u32 = 5;
+
+/// Estimates how complete and well-maintained a place
+/// is on a scale from 0 to [MAX_QUALITY_SCORE].
+///
+/// | Criterion                                  | Points |
+/// |--------------------------------------------|--------|
+/// | Description with at least 50 characters    | 15     |
+/// | Street, zip code and city                  | 15     |
+/// | E-mail address or phone number             | 15     |
+/// | Image                                      | 15     |
+/// | Homepage                                   | 10     |
+/// | Confirmed within the last year             | 10     |
+/// | Ratings (2 points each, at most 5 ratings) | 10     |
+/// | Opening hours                              | 5      |
+/// | Tags                                       | 5      |
+pub fn place_quality_score(
+    place: &Place,
+    last_confirmed_at: Option<TimestampMs>,
+    rating_count: u32,
+    now: TimestampMs,
+) -> u8 {
+    let mut score = 0;
+    if place.description.trim().chars().count() >= MIN_DESCRIPTION_LEN {
+        score += 15;
+    }
+    if let Some(address) = &place.location.address {
+        if address.street.is_some() && address.zip.is_some() && address.city.is_some() {
+            score += 15;
+        }
+    }
+    if let Some(contact) = &place.contact {
+        if contact.email.is_some() || contact.phone.is_some() {
+            score += 15;
+        }
+    }
+    if let Some(links) = &place.links {
+        if links.image.is_some() {
+            score += 15;
+        }
+        if links.homepage.is_some() {
+            score += 10;
+        }
+    }
+    if let Some(last_confirmed_at) = last_confirmed_at {
+        if now.into_inner() - last_confirmed_at.into_inner() <= CONFIRMATION_VALIDITY_MILLIS {
+            score += 10;
+        }
+    }
+    score += 2 * rating_count.min(MAX_SCORED_RATINGS) as u8;
+    if place.opening_hours.is_some() {
+        score += 5;
+    }
+    // Categories are not counted as tags
+    let (tags, _) = Category::split_from_tags(place.tags.clone());
+    if !tags.is_empty() {
+        score += 5;
+    }
+    debug_assert!(score <= MAX_QUALITY_SCORE);
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ofdb_entities::{address::*, builders::*, contact::*};
+
+    #[test]
+    fn score_of_empty_and_complete_places() {
+        let now = TimestampMs::now();
+        let place = Place::build()
+            .id("a")
+            .tags(vec![Category::TAG_NON_PROFIT])
+            .finish();
+        assert_eq!(0, place_quality_score(&place, None, 0, now));
+
+        let mut place = Place::build()
+            .id("b")
+            .description(&"x".repeat(MIN_DESCRIPTION_LEN))
+            .tags(vec!["bio", Category::TAG_NON_PROFIT])
+            .image_url(Some("https://example.org/image.png"))
+            .finish();
+        place.location.address = Some(
+            Address::build()
+                .street("Hauptstraße 1")
+                .zip("12345")
+                .city("Musterstadt")
+                .finish(),
+        );
+        place.contact = Some(Contact {
+            name: None,
+            email: None,
+            phone: Some("0123".into()),
+        });
+        place.opening_hours = Some("Mo-Fr 08:00-18:00".parse().unwrap());
+        place.links.as_mut().unwrap().homepage = Some("https://example.org".parse().unwrap());
+        assert_eq!(
+            MAX_QUALITY_SCORE,
+            place_quality_score(&place, Some(now), 7, now)
+        );
+        assert_eq!(94, place_quality_score(&place, Some(now), 2, now));
+        let two_years_ago =
+            TimestampMs::from_inner(now.into_inner() - 2 * CONFIRMATION_VALIDITY_MILLIS);
+        assert_eq!(
+            MAX_QUALITY_SCORE - 10,
+            place_quality_score(&place, Some(two_years_ago), 5, now)
+        );
+    }
+}
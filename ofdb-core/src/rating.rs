@@ -85,5 +85,7 @@ pub mod tests {
 
         assert_eq!(entry1.avg_ratings(&ratings1).total(), 0.5.into());
         assert_eq!(entry2.avg_ratings(&ratings2).total(), 0.0.into());
+        assert_eq!(6, entry1.avg_ratings(&ratings1).count);
+        assert_eq!(0, new_place("c").avg_ratings(&[]).count);
     }
 }
@@ -2,9 +2,48 @@ use crate::text;
 
 pub mod moderated;
 
+/// Normalizes user input into a sorted list of unique,
+/// lowercase tags without the reserved `#` character.
+pub fn prepare_tag_list<'a>(tags: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut tags: Vec<_> = tags
+        .into_iter()
+        // Split by whitespace
+        .flat_map(|t| t.split_whitespace())
+        // Remove reserved character
+        .map(|t| t.replace("#", ""))
+        // Filter empty tags (2nd pass) and conversion to lowercase
+        .filter_map(|t| match t.trim() {
+            t if t.is_empty() => None,
+            t => Some(t.to_lowercase()),
+        })
+        .collect();
+    tags.sort_unstable();
+    tags.dedup();
+    tags
+}
+
 pub fn split_text_into_tags(text: &str) -> Vec<String> {
     text::split_text_into_words(text)
         .into_iter()
         .map(str::to_lowercase)
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tag_lists() {
+        assert_eq!(
+            vec![
+                "a".to_string(),
+                "b".to_string(),
+                "c".to_string(),
+                "d".to_string(),
+                "e-f".to_string()
+            ],
+            prepare_tag_list(vec!["  A\n#d\tc #B ", "#", "#e-f"].into_iter())
+        );
+    }
+}
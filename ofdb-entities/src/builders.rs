@@ -3,7 +3,7 @@ pub trait Builder {
     fn build() -> Self::Build;
 }
 
-pub use self::{address_builder::*, place_builder::*};
+pub use self::{address_builder::*, event_builder::*, place_builder::*, rating_builder::*};
 
 pub mod place_builder {
 
@@ -105,6 +105,7 @@ pub mod place_builder {
                         pos: MapPoint::from_lat_lng_deg(0.0, 0.0),
                         address: None,
                     },
+                    address_auto_filled: false,
                     contact: None,
                     opening_hours: None,
                     founded_on: None,
@@ -116,6 +117,140 @@ pub mod place_builder {
     }
 }
 
+pub mod event_builder {
+
+    use super::*;
+    use crate::{event::*, geo::*, id::*, location::*};
+    use chrono::NaiveDateTime;
+
+    #[derive(Debug)]
+    pub struct EventBuild {
+        event: Event,
+    }
+
+    impl EventBuild {
+        pub fn id(mut self, id: &str) -> Self {
+            self.event.id = id.into();
+            self
+        }
+        pub fn title(mut self, title: &str) -> Self {
+            self.event.title = title.into();
+            self
+        }
+        pub fn start(mut self, start: NaiveDateTime) -> Self {
+            self.event.start = start;
+            self
+        }
+        pub fn end(mut self, end: NaiveDateTime) -> Self {
+            self.event.end = Some(end);
+            self
+        }
+        pub fn recurrence(mut self, recurrence: &str) -> Self {
+            self.event.recurrence = Some(recurrence.parse().unwrap());
+            self
+        }
+        pub fn pos(mut self, pos: MapPoint) -> Self {
+            self.event.location = Some(Location {
+                pos,
+                address: self.event.location.and_then(|l| l.address),
+            });
+            self
+        }
+        pub fn tags(mut self, tags: Vec<impl Into<String>>) -> Self {
+            self.event.tags = tags.into_iter().map(|x| x.into()).collect();
+            self
+        }
+        pub fn finish(self) -> Event {
+            self.event
+        }
+    }
+
+    impl Builder for Event {
+        type Build = EventBuild;
+        fn build() -> EventBuild {
+            EventBuild {
+                event: Event {
+                    id: Id::new(),
+                    title: "".into(),
+                    description: None,
+                    start: NaiveDateTime::from_timestamp(0, 0),
+                    end: None,
+                    location: None,
+                    contact: None,
+                    tags: vec![],
+                    homepage: None,
+                    created_by: None,
+                    registration: None,
+                    archived: None,
+                    image_url: None,
+                    image_link_url: None,
+                    time_zone: None,
+                    recurrence: None,
+                    address_auto_filled: false,
+                    publish_at: None,
+                    created_at: None,
+                    updated_at: None,
+                },
+            }
+        }
+    }
+}
+
+pub mod rating_builder {
+
+    use super::*;
+    use crate::{id::*, rating::*, time::*};
+
+    #[derive(Debug)]
+    pub struct RatingBuild {
+        rating: Rating,
+    }
+
+    impl RatingBuild {
+        pub fn id(mut self, id: &str) -> Self {
+            self.rating.id = id.into();
+            self
+        }
+        pub fn place_id(mut self, place_id: &str) -> Self {
+            self.rating.place_id = place_id.into();
+            self
+        }
+        pub fn title(mut self, title: &str) -> Self {
+            self.rating.title = title.into();
+            self
+        }
+        pub fn value(mut self, value: i8) -> Self {
+            self.rating.value = value.into();
+            self
+        }
+        pub fn context(mut self, context: RatingContext) -> Self {
+            self.rating.context = context;
+            self
+        }
+        pub fn finish(self) -> Rating {
+            self.rating
+        }
+    }
+
+    impl Builder for Rating {
+        type Build = RatingBuild;
+        fn build() -> RatingBuild {
+            RatingBuild {
+                rating: Rating {
+                    id: Id::new(),
+                    place_id: Id::new(),
+                    created_at: Timestamp::now(),
+                    archived_at: None,
+                    title: "".into(),
+                    value: RatingValue::default(),
+                    context: RatingContext::Diversity,
+                    source: None,
+                },
+            }
+        }
+    }
+}
+
 pub mod address_builder {
 
     use super::*;
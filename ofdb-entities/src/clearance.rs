@@ -12,3 +12,31 @@ pub struct ClearanceForPlace {
     pub place_id: Id,
     pub cleared_revision: Option<Revision>,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClearanceDecision {
+    /// The current revision has been cleared
+    Approved,
+    /// A previous revision remains visible
+    Rejected,
+}
+
+/// A decision of an organization about the pending
+/// clearance of a place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClearanceLogEntry {
+    pub org_id: Id,
+    pub place_id: Id,
+    /// The current revision of the place when the
+    /// decision was made
+    pub place_revision: Revision,
+    /// The revision that is visible for the organization afterwards
+    pub cleared_revision: Revision,
+    pub decision: ClearanceDecision,
+    pub decided_at: TimestampMs,
+    /// The API token of the organization that has been used for
+    /// the decision. `None` for decisions recorded before.
+    pub api_token_id: Option<Id>,
+    /// The label of the API token at the time of the decision
+    pub api_token_label: Option<String>,
+}
@@ -1,4 +1,4 @@
-use crate::{contact::*, id::*, location::*, time::*, url::*};
+use crate::{contact::*, id::*, location::*, recurrence::*, time::*, url::*};
 use chrono::prelude::*;
 use std::str::FromStr;
 
@@ -43,6 +43,17 @@ pub struct Event {
     pub archived     : Option<Timestamp>,
     pub image_url     : Option<Url>,
     pub image_link_url: Option<Url>,
+    // IANA name, e.g. "Europe/Berlin"
+    pub time_zone     : Option<String>,
+    // The start and end refer to the first occurrence
+    pub recurrence    : Option<Recurrence>,
+    // The address has been resolved from the position
+    pub address_auto_filled: bool,
+    // Hidden from the public until this time
+    pub publish_at    : Option<Timestamp>,
+    // Maintained by the repository
+    pub created_at    : Option<Timestamp>,
+    pub updated_at    : Option<Timestamp>,
 }
 
 impl Event {
@@ -67,6 +78,24 @@ impl Event {
         }
     }
 
+    /// The start and end of all occurrences. Events
+    /// without a recurrence rule occur only once.
+    ///
+    /// The recurrence rule is expanded in UTC regardless
+    /// of the time zone of the event.
+    pub fn occurrence_times(&self) -> impl Iterator<Item = (NaiveDateTime, Option<NaiveDateTime>)> {
+        let starts: Box<dyn Iterator<Item = NaiveDateTime>> = match &self.recurrence {
+            Some(recurrence) => Box::new(recurrence.starts(self.start)),
+            None => Box::new(std::iter::once(self.start)),
+        };
+        let duration = self.end.map(|end| end - self.start);
+        starts.map(move |start| (start, duration.map(|duration| start + duration)))
+    }
+
+    pub fn is_scheduled(&self, now: Timestamp) -> bool {
+        self.publish_at.map(|at| at > now).unwrap_or(false)
+    }
+
     pub fn is_owned<'a>(&self, moderated_tags: impl IntoIterator<Item = &'a str>) -> bool {
         // Exclusive ownership of events is determined by the associated tags
         moderated_tags
@@ -78,6 +107,7 @@ impl Event {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::builders::*;
     #[test]
     fn registration_type_from_str() {
         assert_eq!(
@@ -107,4 +137,31 @@ mod tests {
         assert!(RegistrationType::from_str("foo").is_err());
         assert!(RegistrationType::from_str("").is_err());
     }
+
+    #[test]
+    fn shift_end_of_occurrences() {
+        let start = NaiveDate::from_ymd(2021, 7, 5).and_hms(18, 0, 0);
+        let end = NaiveDate::from_ymd(2021, 7, 5).and_hms(20, 0, 0);
+        let event = Event::build()
+            .start(start)
+            .end(end)
+            .recurrence("FREQ=WEEKLY;COUNT=2")
+            .finish();
+        let occurrences: Vec<_> = event.occurrence_times().collect();
+        assert_eq!(
+            vec![
+                (start, Some(end)),
+                (
+                    NaiveDate::from_ymd(2021, 7, 12).and_hms(18, 0, 0),
+                    Some(NaiveDate::from_ymd(2021, 7, 12).and_hms(20, 0, 0))
+                ),
+            ],
+            occurrences
+        );
+        let once = Event {
+            recurrence: None,
+            ..event
+        };
+        assert_eq!(1, once.occurrence_times().count());
+    }
 }
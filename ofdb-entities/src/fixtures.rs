@@ -0,0 +1,216 @@
+//! Pseudo-random but reproducible entities for tests.
+//!
+//! The same seed always yields the same sequence of entities,
+//! i.e. a failing test can be replayed without storing its input.
+//! All optional sections of the generated entities are populated.
+
+use crate::{
+    activity::*, address::*, contact::*, event::*, geo::*, id::*, links::*, location::*, place::*,
+    rating::*, revision::*, time::*,
+};
+use chrono::{NaiveDate, NaiveDateTime};
+
+const TAGS: &[&str] = &[
+    "bio",
+    "fair",
+    "regional",
+    "vegan",
+    "repair",
+    "unverpackt",
+    "coop",
+];
+
+const CITIES: &[&str] = &["Stuttgart", "Berlin", "Leipzig", "Wien", "Zürich"];
+
+const RATING_CONTEXTS: &[RatingContext] = &[
+    RatingContext::Diversity,
+    RatingContext::Renewable,
+    RatingContext::Fairness,
+    RatingContext::Humanity,
+    RatingContext::Transparency,
+    RatingContext::Solidarity,
+];
+
+#[derive(Debug, Clone)]
+pub struct Fixtures {
+    state: u64,
+}
+
+impl Fixtures {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    // SplitMix64
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len() as u64) as usize]
+    }
+
+    pub fn id(&mut self) -> Id {
+        format!("{:016x}{:016x}", self.next_u64(), self.next_u64()).into()
+    }
+
+    /// Sorted and without duplicates
+    pub fn tags(&mut self) -> Vec<String> {
+        let count = 1 + self.below(3);
+        let mut tags: Vec<_> = (0..count).map(|_| self.pick(TAGS).to_string()).collect();
+        tags.sort_unstable();
+        tags.dedup();
+        tags
+    }
+
+    pub fn pos(&mut self) -> MapPoint {
+        let lat = self.below(170_000_000) as f64 / 1_000_000.0 - 85.0;
+        let lng = self.below(350_000_000) as f64 / 1_000_000.0 - 175.0;
+        MapPoint::from_lat_lng_deg(lat, lng)
+    }
+
+    pub fn address(&mut self) -> Address {
+        Address {
+            street: Some(format!("Hauptstraße {}", 1 + self.below(200))),
+            zip: Some(format!("{:05}", self.below(100_000))),
+            city: Some(self.pick(CITIES).to_string()),
+            country: Some("Germany".into()),
+            state: Some("BW".into()),
+        }
+    }
+
+    pub fn contact(&mut self) -> Contact {
+        let n = self.below(10_000);
+        Contact {
+            name: Some(format!("Contact {}", n)),
+            email: Some(format!("contact{}@example.com", n).into()),
+            phone: Some(format!("+49 711 {}", n)),
+        }
+    }
+
+    /// Whole seconds between 2020 and 2030
+    pub fn date_time(&mut self) -> NaiveDateTime {
+        NaiveDateTime::from_timestamp(1_577_836_800 + self.below(315_360_000) as i64, 0)
+    }
+
+    pub fn place(&mut self) -> Place {
+        let n = self.below(10_000);
+        Place {
+            id: self.id(),
+            license: "CC0-1.0".into(),
+            revision: Revision::initial(),
+            created: Activity {
+                at: TimestampMs::from_seconds(self.date_time().timestamp()),
+                by: None,
+            },
+            title: format!("Place {}", n),
+            description: format!("Description of place {}", n),
+            location: Location {
+                pos: self.pos(),
+                address: Some(self.address()),
+            },
+            address_auto_filled: false,
+            contact: Some(self.contact()),
+            opening_hours: Some("Mo-Fr 09:00-18:00".to_string().into()),
+            founded_on: Some(NaiveDate::from_ymd(
+                1950 + self.below(70) as i32,
+                1 + self.below(12) as u32,
+                1 + self.below(28) as u32,
+            )),
+            links: Some(Links {
+                homepage: Some(format!("https://place{}.example.com/", n).parse().unwrap()),
+                image: None,
+                image_href: None,
+                custom: vec![CustomLink {
+                    url: format!("https://example.com/places/{}", n).parse().unwrap(),
+                    title: Some("Details".into()),
+                    description: None,
+                }],
+            }),
+            tags: self.tags(),
+        }
+    }
+
+    pub fn event(&mut self) -> Event {
+        let n = self.below(10_000);
+        let start = self.date_time();
+        Event {
+            id: self.id(),
+            title: format!("Event {}", n),
+            description: Some(format!("Description of event {}", n)),
+            start,
+            end: Some(start + chrono::Duration::hours(1 + self.below(8) as i64)),
+            location: Some(Location {
+                pos: self.pos(),
+                address: Some(self.address()),
+            }),
+            contact: Some(self.contact()),
+            tags: self.tags(),
+            homepage: Some(format!("https://event{}.example.com/", n).parse().unwrap()),
+            created_by: None,
+            registration: None,
+            archived: None,
+            image_url: None,
+            image_link_url: None,
+            time_zone: Some("Europe/Berlin".into()),
+            recurrence: None,
+            address_auto_filled: false,
+            publish_at: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    pub fn rating(&mut self, place_id: &Id) -> Rating {
+        let value = self.below(4) as i8 - 1;
+        Rating {
+            id: self.id(),
+            place_id: place_id.clone(),
+            created_at: Timestamp::from_seconds(self.date_time().timestamp()),
+            archived_at: None,
+            title: format!("Rating {}", self.below(10_000)),
+            value: value.into(),
+            context: self.pick(RATING_CONTEXTS),
+            source: Some("fixture".into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_yields_same_entities() {
+        let mut a = Fixtures::new(1);
+        let mut b = Fixtures::new(1);
+        assert_eq!(a.place(), b.place());
+        assert_eq!(a.event(), b.event());
+        let place_id = a.id();
+        assert_eq!(place_id, b.id());
+        assert_eq!(a.rating(&place_id), b.rating(&place_id));
+        assert_ne!(a.place(), Fixtures::new(2).place());
+    }
+
+    #[test]
+    fn generated_entities_are_valid() {
+        let mut fixtures = Fixtures::new(0);
+        for _ in 0..100 {
+            assert!(fixtures.pos().is_valid());
+            let place = fixtures.place();
+            assert!(!place.tags.is_empty());
+            assert!(place.tags.windows(2).all(|w| w[0] < w[1]));
+            assert!(fixtures.rating(&place.id).value.is_valid());
+            let event = fixtures.event();
+            assert!(event.end.unwrap() > event.start);
+        }
+    }
+}
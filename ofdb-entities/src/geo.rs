@@ -354,6 +354,12 @@ impl MapPoint {
         self.lat.is_valid() && self.lng.is_valid()
     }
 
+    /// Exactly 0/0 is a valid position, but in practice it almost
+    /// always results from missing or unparsed coordinates.
+    pub fn is_null_island(self) -> bool {
+        self.lat.to_raw() == 0 && self.lng.to_raw() == 0
+    }
+
     pub fn to_lat_lng_rad(self) -> (f64, f64) {
         (self.lat.to_rad(), self.lng.to_rad())
     }
@@ -593,6 +599,131 @@ pub enum MapBboxInputError {
     Format(String),
 }
 
+/// A simple polygon that is given by the vertices of its
+/// outer ring. The ring is closed implicitly.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MapPolygon {
+    vertices: Vec<MapPoint>,
+}
+
+impl MapPolygon {
+    pub fn new(vertices: Vec<MapPoint>) -> Self {
+        Self { vertices }
+    }
+
+    pub fn vertices(&self) -> &[MapPoint] {
+        &self.vertices
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.vertices.len() >= 3 && self.vertices.iter().all(|v| v.is_valid())
+    }
+
+    /// The smallest bounding box that contains all vertices.
+    ///
+    /// Polygons that cross the antimeridian are not supported.
+    pub fn bbox(&self) -> MapBbox {
+        debug_assert!(self.is_valid());
+        let (mut min_lat, mut min_lng) = (LatCoord::max(), LngCoord::max());
+        let (mut max_lat, mut max_lng) = (LatCoord::min(), LngCoord::min());
+        for v in &self.vertices {
+            if v.lat() < min_lat {
+                min_lat = v.lat();
+            }
+            if v.lat() > max_lat {
+                max_lat = v.lat();
+            }
+            if v.lng() < min_lng {
+                min_lng = v.lng();
+            }
+            if v.lng() > max_lng {
+                max_lng = v.lng();
+            }
+        }
+        MapBbox::new(
+            MapPoint::new(min_lat, min_lng),
+            MapPoint::new(max_lat, max_lng),
+        )
+    }
+
+    /// Point-in-polygon test (even-odd rule)
+    pub fn contains_point(&self, pt: MapPoint) -> bool {
+        debug_assert!(self.is_valid());
+        debug_assert!(pt.is_valid());
+        let (lat, lng) = pt.to_lat_lng_deg();
+        let mut inside = false;
+        let mut prev = self.vertices[self.vertices.len() - 1].to_lat_lng_deg();
+        for v in &self.vertices {
+            let next = v.to_lat_lng_deg();
+            if (next.0 > lat) != (prev.0 > lat)
+                && lng < (prev.1 - next.1) * (lat - next.0) / (prev.0 - next.0) + next.1
+            {
+                inside = !inside;
+            }
+            prev = next;
+        }
+        inside
+    }
+
+    /// Decodes the vertices from an encoded polyline with
+    /// a precision of 5 decimal places.
+    ///
+    /// See also: <https://developers.google.com/maps/documentation/utilities/polylinealgorithm>
+    pub fn decode_polyline(s: &str) -> Result<Self, MapPolygonInputError> {
+        let bytes = s.trim().as_bytes();
+        let invalid = || MapPolygonInputError::Polyline(s.to_string());
+        let mut pos = 0;
+        let mut vertices = vec![];
+        let (mut lat, mut lng) = (0, 0);
+        while pos < bytes.len() {
+            lat += decode_polyline_value(bytes, &mut pos).ok_or_else(invalid)?;
+            lng += decode_polyline_value(bytes, &mut pos).ok_or_else(invalid)?;
+            let vertex = MapPoint::try_from_lat_lng_deg(lat as f64 / 1e5, lng as f64 / 1e5)
+                .map_err(|_| invalid())?;
+            vertices.push(vertex);
+        }
+        let polygon = Self::new(vertices);
+        if !polygon.is_valid() {
+            return Err(MapPolygonInputError::TooFewVertices);
+        }
+        Ok(polygon)
+    }
+}
+
+fn decode_polyline_value(bytes: &[u8], pos: &mut usize) -> Option<i64> {
+    let mut result = 0i64;
+    let mut shift = 0;
+    loop {
+        let chunk = match bytes.get(*pos).copied() {
+            Some(b @ 63..=126) => i64::from(b - 63),
+            _ => return None,
+        };
+        *pos += 1;
+        result |= (chunk & 0x1f) << shift;
+        if chunk < 0x20 {
+            break;
+        }
+        shift += 5;
+        if shift > 30 {
+            return None;
+        }
+    }
+    if result & 1 == 0 {
+        Some(result >> 1)
+    } else {
+        Some(!(result >> 1))
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum MapPolygonInputError {
+    #[error("invalid polyline: '{0}'")]
+    Polyline(String),
+
+    #[error("too few vertices")]
+    TooFewVertices,
+}
+
 #[cfg(test)]
 #[allow(clippy::unreadable_literal, clippy::float_cmp)]
 mod tests {
@@ -658,6 +789,14 @@ mod tests {
         assert!(LngCoord::try_from_deg(180.000001).is_err());
     }
 
+    #[test]
+    fn null_island() {
+        assert!(MapPoint::from_lat_lng_deg(0.0, 0.0).is_null_island());
+        assert!(!MapPoint::from_lat_lng_deg(0.0, 0.0001).is_null_island());
+        assert!(!MapPoint::from_lat_lng_deg(-0.0001, 0.0).is_null_island());
+        assert!(!MapPoint::default().is_null_island());
+    }
+
     #[test]
     fn no_distance() {
         let p1 = MapPoint::from_lat_lng_deg(0.0, 0.0);
@@ -803,6 +942,47 @@ mod tests {
         assert!(bbox4.contains_point(MapPoint::from_lat_lng_deg(lat4, lng4)));
     }
 
+    #[test]
+    fn decode_polygon_from_polyline() {
+        let polygon = MapPolygon::decode_polyline("_p~iF~ps|U_ulLnnqC_mqNvxq`@").unwrap();
+        assert_eq!(
+            vec![
+                MapPoint::from_lat_lng_deg(38.5, -120.2),
+                MapPoint::from_lat_lng_deg(40.7, -120.95),
+                MapPoint::from_lat_lng_deg(43.252, -126.453),
+            ],
+            polygon.vertices()
+        );
+        assert!(MapPolygon::decode_polyline("_p~iF~ps|U").is_err());
+        assert!(MapPolygon::decode_polyline("_p~iF~ps|U_ulLnnqC_mqN").is_err());
+        assert!(MapPolygon::decode_polyline("not a polyline!").is_err());
+    }
+
+    #[test]
+    fn point_in_polygon() {
+        // L-shaped
+        let polygon = MapPolygon::new(vec![
+            MapPoint::from_lat_lng_deg(0.0, 0.0),
+            MapPoint::from_lat_lng_deg(0.0, 2.0),
+            MapPoint::from_lat_lng_deg(1.0, 2.0),
+            MapPoint::from_lat_lng_deg(1.0, 1.0),
+            MapPoint::from_lat_lng_deg(2.0, 1.0),
+            MapPoint::from_lat_lng_deg(2.0, 0.0),
+        ]);
+        assert_eq!(
+            MapBbox::new(
+                MapPoint::from_lat_lng_deg(0.0, 0.0),
+                MapPoint::from_lat_lng_deg(2.0, 2.0)
+            ),
+            polygon.bbox()
+        );
+        assert!(polygon.contains_point(MapPoint::from_lat_lng_deg(0.5, 0.5)));
+        assert!(polygon.contains_point(MapPoint::from_lat_lng_deg(0.5, 1.5)));
+        assert!(polygon.contains_point(MapPoint::from_lat_lng_deg(1.5, 0.5)));
+        assert!(!polygon.contains_point(MapPoint::from_lat_lng_deg(1.5, 1.5)));
+        assert!(!polygon.contains_point(MapPoint::from_lat_lng_deg(2.5, 0.5)));
+    }
+
     // ---- BENCHMARKS ---- //
     //
     // To run the benchmarks you need Rust nightly.
@@ -26,6 +26,7 @@ pub mod organization;
 pub mod password;
 pub mod place;
 pub mod rating;
+pub mod recurrence;
 pub mod review;
 pub mod revision;
 pub mod subscription;
@@ -41,3 +42,6 @@ pub mod url;
 
 #[cfg(any(test, feature = "builders"))]
 pub mod builders;
+
+#[cfg(any(test, feature = "builders"))]
+pub mod fixtures;
@@ -1,4 +1,5 @@
 use crate::time::*;
+use sha2::{Digest, Sha256};
 use std::{fmt, ops::Deref, str::FromStr};
 use uuid::Uuid;
 
@@ -111,6 +112,17 @@ impl EmailNonce {
     }
 }
 
+/// Hashes the secret of an API token.
+///
+/// Unlike passwords the secret is a random nonce with enough
+/// entropy, i.e. a plain and fast hash is sufficient.
+pub fn hash_secret(secret: &str) -> String {
+    Sha256::digest(secret.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct UserToken {
     pub email_nonce: EmailNonce,
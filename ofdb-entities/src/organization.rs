@@ -1,4 +1,12 @@
-use crate::id::Id;
+use crate::{
+    email::Email,
+    id::Id,
+    nonce::{hash_secret, Nonce},
+    time::Timestamp,
+    user::PersonalApiToken,
+};
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::*;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ModeratedTag {
@@ -8,6 +16,48 @@ pub struct ModeratedTag {
     pub require_clearance: bool,
 }
 
+impl ModeratedTag {
+    /// Replaces the individual permissions according to the policy
+    pub fn with_policy(self, policy: TagModerationPolicy) -> Self {
+        let (allow_add, allow_remove, require_clearance) = match policy {
+            TagModerationPolicy::Forbidden => (false, false, false),
+            TagModerationPolicy::AllowFreely => (true, true, false),
+            TagModerationPolicy::RequireClearance => (true, true, true),
+        };
+        Self {
+            label: self.label,
+            allow_add,
+            allow_remove,
+            require_clearance,
+        }
+    }
+}
+
+pub type TagModerationPolicyPrimitive = i16;
+
+/// How an organization treats outside edits of an owned tag,
+/// i.e. adding or removing the tag without the organization's
+/// API token.
+#[rustfmt::skip]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum TagModerationPolicy {
+    Forbidden        = 0,
+    AllowFreely      = 1,
+    RequireClearance = 2,
+}
+
+impl TagModerationPolicy {
+    pub fn try_from(from: TagModerationPolicyPrimitive) -> Option<Self> {
+        Self::from_i16(from)
+    }
+}
+
+impl From<TagModerationPolicy> for TagModerationPolicyPrimitive {
+    fn from(from: TagModerationPolicy) -> Self {
+        from.to_i16().unwrap()
+    }
+}
+
 // Workaround for backwards compatbility
 // TODO: Remove after updating tests
 impl From<&str> for ModeratedTag {
@@ -21,10 +71,146 @@ impl From<&str> for ModeratedTag {
     }
 }
 
+/// Customizes the e-mails that are sent in the context
+/// of the moderated tags of an organization.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EmailBranding {
+    /// Display name of the sender
+    pub sender_name: Option<String>,
+    pub reply_to: Option<Email>,
+    /// Replaces the default greeting at the end
+    pub footer: Option<String>,
+}
+
+/// Max. number of uploads of an organization per day
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DailyQuotas {
+    /// Created or updated events
+    pub events: Option<u32>,
+    /// Created, updated or imported places
+    pub places: Option<u32>,
+}
+
+impl DailyQuotas {
+    /// Missing quotas are replaced by the defaults
+    pub fn or(self, defaults: DailyQuotas) -> Self {
+        Self {
+            events: self.events.or(defaults.events),
+            places: self.places.or(defaults.places),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Organization {
     pub id: Id,
     pub name: String,
     pub api_token: String,
     pub moderated_tags: Vec<ModeratedTag>,
+    /// Receives notifications about outside edits of moderated tags
+    pub notification_email: Option<String>,
+    pub email_branding: EmailBranding,
+}
+
+/// An outside edit of the moderated tags of a place that
+/// is reported to an organization as part of a summary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingModeratedTagsNotification {
+    pub id: Id,
+    pub org_id: Id,
+    pub place_id: Id,
+    pub place_title: String,
+    pub added_tags: Vec<String>,
+    pub removed_tags: Vec<String>,
+    pub created_at: Timestamp,
+}
+
+/// One of multiple revocable API tokens of an organization.
+///
+/// Organizations may use several tokens at the same time for
+/// replacing a token without interrupting their clients.
+#[rustfmt::skip]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrganizationApiToken {
+    pub id           : Id,
+    pub org_id       : Id,
+    pub label        : Option<String>,
+    // Only the hash of the secret is stored
+    pub secret_hash  : String,
+    pub created_at   : Timestamp,
+    pub expires_at   : Option<Timestamp>,
+    pub last_used_at : Option<Timestamp>,
+}
+
+impl OrganizationApiToken {
+    /// Generates a new token.
+    ///
+    /// The returned bearer token contains the secret and
+    /// can't be recovered later.
+    pub fn generate(
+        org_id: Id,
+        label: Option<String>,
+        expires_at: Option<Timestamp>,
+        created_at: Timestamp,
+    ) -> (Self, String) {
+        let id = Id::new();
+        let secret = Nonce::new().to_string();
+        let secret_hash = hash_secret(&secret);
+        // Same format as the bearer tokens of personal API tokens
+        let bearer_token = format!("{}.{}", id, secret);
+        let token = Self {
+            id,
+            org_id,
+            label,
+            secret_hash,
+            created_at,
+            expires_at,
+            last_used_at: None,
+        };
+        (token, bearer_token)
+    }
+
+    /// Splits a bearer token into the id and the secret
+    pub fn split_bearer_token(bearer_token: &str) -> Option<(&str, &str)> {
+        PersonalApiToken::split_bearer_token(bearer_token)
+    }
+
+    pub fn verify_secret(&self, secret: &str) -> bool {
+        hash_secret(secret) == self.secret_hash
+    }
+
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        self.expires_at
+            .map(|expires_at| expires_at <= now)
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_generated_organization_api_token() {
+        let (token, bearer_token) =
+            OrganizationApiToken::generate(Id::new(), None, None, Timestamp::now());
+        let (id, secret) = OrganizationApiToken::split_bearer_token(&bearer_token).unwrap();
+        assert_eq!(token.id.as_str(), id);
+        assert_eq!(64, token.secret_hash.len());
+        assert!(token.verify_secret(secret));
+        assert!(!token.verify_secret(&Nonce::new().to_string()));
+    }
+
+    #[test]
+    fn expiry_of_organization_api_token() {
+        let now = Timestamp::from_inner(1000);
+        let (token, _) = OrganizationApiToken::generate(Id::new(), None, None, now);
+        assert!(!token.is_expired(now));
+        let token = OrganizationApiToken {
+            expires_at: Some(Timestamp::from_inner(1001)),
+            ..token
+        };
+        assert!(!token.is_expired(now));
+        assert!(token.is_expired(Timestamp::from_inner(1001)));
+    }
 }
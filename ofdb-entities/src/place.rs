@@ -1,4 +1,7 @@
-use crate::{activity::*, contact::*, id::*, links::*, location::*, review::*, revision::*};
+use crate::{
+    activity::*, address::Address, contact::*, email::Email, geo::MapPoint, id::*, links::*,
+    location::*, nonce::Nonce, review::*, revision::*, time::TimestampMs,
+};
 
 use chrono::NaiveDate;
 use std::str::FromStr;
@@ -56,6 +59,8 @@ pub struct PlaceRevision {
     pub title: String,
     pub description: String,
     pub location: Location,
+    // The address has been resolved from the position
+    pub address_auto_filled: bool,
     pub contact: Option<Contact>,
     pub opening_hours: Option<OpeningHours>,
     pub founded_on: Option<NaiveDate>,
@@ -74,6 +79,8 @@ pub struct Place {
     pub title: String,
     pub description: String,
     pub location: Location,
+    // The address has been resolved from the position
+    pub address_auto_filled: bool,
     pub contact: Option<Contact>,
     pub opening_hours: Option<OpeningHours>,
     pub founded_on: Option<NaiveDate>,
@@ -114,6 +121,7 @@ impl From<(PlaceRoot, PlaceRevision)> for Place {
                 title,
                 description,
                 location,
+                address_auto_filled,
                 contact,
                 opening_hours,
                 founded_on,
@@ -129,6 +137,7 @@ impl From<(PlaceRoot, PlaceRevision)> for Place {
             title,
             description,
             location,
+            address_auto_filled,
             contact,
             opening_hours,
             founded_on,
@@ -148,6 +157,7 @@ impl From<Place> for (PlaceRoot, PlaceRevision) {
             title,
             description,
             location,
+            address_auto_filled,
             contact,
             opening_hours,
             founded_on,
@@ -162,6 +172,7 @@ impl From<Place> for (PlaceRoot, PlaceRevision) {
                 title,
                 description,
                 location,
+                address_auto_filled,
                 contact,
                 opening_hours,
                 founded_on,
@@ -177,3 +188,55 @@ pub struct PlaceHistory {
     pub place: PlaceRoot,
     pub revisions: Vec<(PlaceRevision, Vec<ReviewStatusLog>)>,
 }
+
+/// An internal note of a scout or admin about a place
+/// that is never published.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaceNote {
+    pub id: Id,
+    pub place_id: Id,
+    /// The note that is answered by this note
+    pub parent_id: Option<Id>,
+    pub created: Activity,
+    pub text: String,
+}
+
+/// Asks the creator or the owner of a place to
+/// confirm that the place is still valid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaceConfirmationRequest {
+    pub place_id: Id,
+    /// The secret part of the confirmation link
+    pub nonce: Nonce,
+    pub sent_to: Email,
+    pub sent_at: TimestampMs,
+    pub answered_at: Option<TimestampMs>,
+    /// Unanswered requests are flagged for scouts
+    pub flagged_at: Option<TimestampMs>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsmNodeProposalStatus {
+    Pending,
+    Accepted,
+    Rejected,
+}
+
+/// Changes of an imported place in OpenStreetMap. The place
+/// is not modified until a scout accepts the proposal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OsmNodeProposal {
+    pub id: Id,
+    pub place_id: Id,
+    pub osm_node_id: u64,
+    /// The current revision of the place when the changes
+    /// have been detected
+    pub place_revision: Revision,
+    pub created_at: TimestampMs,
+    // Only the upstream values that differ from the place
+    pub title: Option<String>,
+    pub pos: Option<MapPoint>,
+    pub address: Option<Address>,
+    pub status: OsmNodeProposalStatus,
+    pub reviewed: Option<Activity>,
+}
@@ -1,4 +1,5 @@
-use crate::{id::*, time::*};
+use crate::{comment::*, id::*, time::*};
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum RatingContext {
@@ -159,6 +160,8 @@ impl std::ops::AddAssign<RatingValue> for AvgRatingValueBuilder {
 
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct AvgRatings {
+    /// Number of ratings in all contexts
+    pub count: u32,
     pub diversity: AvgRatingValue,
     pub fairness: AvgRatingValue,
     pub humanity: AvgRatingValue,
@@ -204,7 +207,19 @@ impl AvgRatingsBuilder {
     }
 
     pub fn build(self) -> AvgRatings {
+        let count = [
+            &self.diversity,
+            &self.fairness,
+            &self.humanity,
+            &self.renewable,
+            &self.solidarity,
+            &self.transparency,
+        ]
+        .iter()
+        .map(|builder| builder.cnt as u32)
+        .sum();
         AvgRatings {
+            count,
             diversity: self.diversity.build(),
             fairness: self.fairness.build(),
             humanity: self.humanity.build(),
@@ -221,6 +236,43 @@ impl std::ops::AddAssign<(RatingContext, RatingValue)> for AvgRatingsBuilder {
     }
 }
 
+/// Number of ratings per context and of the comments
+/// on all those ratings
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RatingCounts {
+    pub diversity: u32,
+    pub fairness: u32,
+    pub humanity: u32,
+    pub renewable: u32,
+    pub solidarity: u32,
+    pub transparency: u32,
+    pub comments: u32,
+}
+
+impl RatingCounts {
+    pub fn add_ratings(&mut self, ctx: RatingContext, count: u32) {
+        use RatingContext::*;
+        match ctx {
+            Diversity => self.diversity += count,
+            Fairness => self.fairness += count,
+            Humanity => self.humanity += count,
+            Renewable => self.renewable += count,
+            Solidarity => self.solidarity += count,
+            Transparency => self.transparency += count,
+        }
+    }
+
+    /// Number of ratings in all contexts
+    pub fn total_ratings(&self) -> u32 {
+        self.diversity
+            + self.fairness
+            + self.humanity
+            + self.renewable
+            + self.solidarity
+            + self.transparency
+    }
+}
+
 #[rustfmt::skip]
 #[derive(Debug, Clone, PartialEq)]
 pub struct Rating {
@@ -234,3 +286,70 @@ pub struct Rating {
     pub context     : RatingContext,
     pub source      : Option<String>,
 }
+
+/// Why a rating or comment has been archived by a scout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ArchiveReason {
+    Spam,
+    OffTopic,
+    Abusive,
+    AuthorRequest,
+}
+
+impl ArchiveReason {
+    pub const fn all() -> [Self; 4] {
+        [
+            Self::Spam,
+            Self::OffTopic,
+            Self::Abusive,
+            Self::AuthorRequest,
+        ]
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Spam => "spam",
+            Self::OffTopic => "off-topic",
+            Self::Abusive => "abusive",
+            Self::AuthorRequest => "author-request",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ArchiveReasonParseError;
+
+impl FromStr for ArchiveReason {
+    type Err = ArchiveReasonParseError;
+    fn from_str(s: &str) -> Result<ArchiveReason, Self::Err> {
+        // Both "off-topic" and "off_topic" are accepted
+        match &*s.trim().to_lowercase().replace('_', "-") {
+            "spam" => Ok(ArchiveReason::Spam),
+            "off-topic" => Ok(ArchiveReason::OffTopic),
+            "abusive" => Ok(ArchiveReason::Abusive),
+            "author-request" => Ok(ArchiveReason::AuthorRequest),
+            _ => Err(ArchiveReasonParseError),
+        }
+    }
+}
+
+/// Content rules that are violated by the comment of a new rating
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CommentRuleViolation {
+    TooManyLinks,
+    BannedPhrase,
+    AccountTooNew,
+}
+
+/// A new rating whose comment violates the content rules.
+///
+/// The rating is held back until a scout either publishes
+/// or rejects it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingRating {
+    pub rating: Rating,
+    pub comment: Comment,
+    /// E-mail address of the author if logged in
+    pub created_by: Option<String>,
+    pub violations: Vec<CommentRuleViolation>,
+}
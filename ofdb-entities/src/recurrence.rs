@@ -0,0 +1,245 @@
+//! Recurrence rules of events, i.e. a subset of the RRULE
+//! property of iCalendar (RFC 5545).
+
+use chrono::{prelude::*, Duration};
+use std::{fmt, str::FromStr};
+
+/// The maximum number of occurrences of a recurring event.
+///
+/// Rules without an end are expanded up to this number.
+pub const MAX_OCCURRENCES: usize = 1000;
+
+const UNTIL_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Weekly,
+    Monthly,
+}
+
+impl Frequency {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Weekly => "WEEKLY",
+            Self::Monthly => "MONTHLY",
+        }
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    // Number of weeks or months between two occurrences
+    pub interval : u32,
+    // The last occurrence starts at or before this time
+    pub until    : Option<NaiveDateTime>,
+    // Total number of occurrences including the first one
+    pub count    : Option<u32>,
+}
+
+impl Recurrence {
+    /// The start times of all occurrences, beginning with `first`.
+    ///
+    /// Monthly occurrences are skipped if the day does not exist
+    /// in a month, e.g. the 31st in April.
+    pub fn starts(&self, first: NaiveDateTime) -> impl Iterator<Item = NaiveDateTime> {
+        let Self {
+            frequency,
+            interval,
+            until,
+            count,
+        } = self.clone();
+        let count = count.map_or(MAX_OCCURRENCES, |count| {
+            (count as usize).min(MAX_OCCURRENCES)
+        });
+        (0..)
+            .map(move |period| nth_start(first, frequency, i64::from(interval) * period))
+            // Stop when the date is out of range
+            .take_while(Option::is_some)
+            .filter_map(Option::flatten)
+            .take_while(move |start| until.map_or(true, |until| *start <= until))
+            .take(count)
+    }
+}
+
+// The outer option is `None` if the date is out of range,
+// the inner option if the day does not exist in that month
+fn nth_start(first: NaiveDateTime, frequency: Frequency, n: i64) -> Option<Option<NaiveDateTime>> {
+    match frequency {
+        Frequency::Weekly => first.checked_add_signed(Duration::weeks(n)).map(Some),
+        Frequency::Monthly => {
+            let month0 = i64::from(first.month0()) + n;
+            let year = i64::from(first.year()) + month0.div_euclid(12);
+            if year > i64::from(i32::MAX) {
+                return None;
+            }
+            let date = NaiveDate::from_ymd_opt(year as i32, month0.rem_euclid(12) as u32 + 1, 1)?;
+            Some(
+                date.with_day(first.day())
+                    .map(|date| date.and_time(first.time())),
+            )
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RecurrenceParseError;
+
+impl FromStr for Recurrence {
+    type Err = RecurrenceParseError;
+    fn from_str(s: &str) -> Result<Recurrence, Self::Err> {
+        let s = s.trim();
+        let s = s
+            .get(..6)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("RRULE:"))
+            .map_or(s, |_| &s[6..]);
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut until = None;
+        let mut count = None;
+        for part in s.split(';').filter(|part| !part.trim().is_empty()) {
+            let mut key_value = part.splitn(2, '=');
+            let key = key_value.next().unwrap_or_default().trim().to_uppercase();
+            let value = key_value.next().ok_or(RecurrenceParseError)?.trim();
+            match &*key {
+                "FREQ" => {
+                    frequency = Some(match &*value.to_uppercase() {
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        _ => return Err(RecurrenceParseError),
+                    });
+                }
+                "INTERVAL" => {
+                    interval = value.parse().map_err(|_| RecurrenceParseError)?;
+                }
+                "UNTIL" => {
+                    until = Some(parse_until(value)?);
+                }
+                "COUNT" => {
+                    count = Some(value.parse().map_err(|_| RecurrenceParseError)?);
+                }
+                // Other parts like BYDAY are not supported
+                _ => return Err(RecurrenceParseError),
+            }
+        }
+        if interval == 0 || count == Some(0) || (until.is_some() && count.is_some()) {
+            return Err(RecurrenceParseError);
+        }
+        Ok(Recurrence {
+            frequency: frequency.ok_or(RecurrenceParseError)?,
+            interval,
+            until,
+            count,
+        })
+    }
+}
+
+// Both dates and date-times (UTC) are accepted. A date includes
+// all occurrences on that day.
+fn parse_until(value: &str) -> Result<NaiveDateTime, RecurrenceParseError> {
+    let value = value.to_uppercase();
+    NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), "%Y%m%dT%H%M%S")
+        .or_else(|_| {
+            NaiveDate::parse_from_str(&value, "%Y%m%d").map(|date| date.and_hms(23, 59, 59))
+        })
+        .map_err(|_| RecurrenceParseError)
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "FREQ={}", self.frequency.as_str())?;
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format(UNTIL_FORMAT))?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date_time(y: i32, m: u32, d: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd(y, m, d).and_hms(18, 30, 0)
+    }
+
+    #[test]
+    fn parse_and_format_rules() {
+        let rule: Recurrence = "RRULE:FREQ=weekly;INTERVAL=2;UNTIL=20211231"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            Recurrence {
+                frequency: Frequency::Weekly,
+                interval: 2,
+                until: Some(NaiveDate::from_ymd(2021, 12, 31).and_hms(23, 59, 59)),
+                count: None,
+            },
+            rule
+        );
+        assert_eq!(
+            "FREQ=WEEKLY;INTERVAL=2;UNTIL=20211231T235959Z",
+            rule.to_string()
+        );
+        assert_eq!(rule, rule.to_string().parse().unwrap());
+        let rule: Recurrence = "FREQ=MONTHLY;COUNT=3".parse().unwrap();
+        assert_eq!("FREQ=MONTHLY;COUNT=3", rule.to_string());
+    }
+
+    #[test]
+    fn reject_invalid_rules() {
+        assert!("".parse::<Recurrence>().is_err());
+        assert!("COUNT=3".parse::<Recurrence>().is_err());
+        assert!("FREQ=DAILY".parse::<Recurrence>().is_err());
+        assert!("FREQ=WEEKLY;BYDAY=MO".parse::<Recurrence>().is_err());
+        assert!("FREQ=WEEKLY;INTERVAL=0".parse::<Recurrence>().is_err());
+        assert!("FREQ=WEEKLY;COUNT=0".parse::<Recurrence>().is_err());
+        assert!("FREQ=WEEKLY;COUNT=2;UNTIL=20211231"
+            .parse::<Recurrence>()
+            .is_err());
+        assert!("FREQ=WEEKLY;UNTIL=tomorrow".parse::<Recurrence>().is_err());
+    }
+
+    #[test]
+    fn weekly_occurrences() {
+        let rule: Recurrence = "FREQ=WEEKLY;INTERVAL=2;UNTIL=20210816".parse().unwrap();
+        let starts: Vec<_> = rule.starts(date_time(2021, 7, 5)).collect();
+        assert_eq!(
+            vec![
+                date_time(2021, 7, 5),
+                date_time(2021, 7, 19),
+                date_time(2021, 8, 2),
+                date_time(2021, 8, 16),
+            ],
+            starts
+        );
+    }
+
+    #[test]
+    fn monthly_occurrences_skip_missing_days() {
+        let rule: Recurrence = "FREQ=MONTHLY;COUNT=4".parse().unwrap();
+        let starts: Vec<_> = rule.starts(date_time(2021, 12, 31)).collect();
+        assert_eq!(
+            vec![
+                date_time(2021, 12, 31),
+                date_time(2022, 1, 31),
+                date_time(2022, 3, 31),
+                date_time(2022, 5, 31),
+            ],
+            starts
+        );
+    }
+
+    #[test]
+    fn limit_occurrences_without_end() {
+        let rule: Recurrence = "FREQ=MONTHLY".parse().unwrap();
+        assert_eq!(MAX_OCCURRENCES, rule.starts(date_time(2021, 7, 1)).count());
+    }
+}
@@ -8,6 +8,8 @@ pub type ReviewStatusPrimitive = i16;
 #[rustfmt::skip]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, FromPrimitive, ToPrimitive, EnumIter, EnumCount)]
 pub enum ReviewStatus {
+    /// Only visible to the user who created it
+    Draft     = -2,
     Rejected  = -1,
     Archived  =  0,
     Created   =  1,
@@ -1,8 +1,69 @@
-use crate::{geo::*, id::*};
+use crate::{geo::*, id::*, time::*};
+use std::str::FromStr;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct BboxSubscription {
     pub id: Id,
     pub user_email: String,
     pub bbox: MapBbox,
+    /// Notify about every single change if not set
+    pub digest: Option<NotificationDigest>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrganizationSubscription {
+    pub id: Id,
+    pub user_email: String,
+    pub org_id: Id,
+}
+
+/// Collect changes and send them as one summary
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum NotificationDigest {
+    Daily,
+    Weekly,
+}
+
+#[derive(Debug)]
+pub struct NotificationDigestParseError;
+
+impl FromStr for NotificationDigest {
+    type Err = NotificationDigestParseError;
+    fn from_str(s: &str) -> Result<NotificationDigest, Self::Err> {
+        match &*s.to_lowercase() {
+            "daily" => Ok(NotificationDigest::Daily),
+            "weekly" => Ok(NotificationDigest::Weekly),
+            _ => Err(NotificationDigestParseError),
+        }
+    }
+}
+
+impl NotificationDigest {
+    pub fn interval_seconds(self) -> i64 {
+        match self {
+            NotificationDigest::Daily => 24 * 60 * 60,
+            NotificationDigest::Weekly => 7 * 24 * 60 * 60,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum NotificationChange {
+    PlaceAdded,
+    PlaceUpdated,
+    EventCreated,
+    EventUpdated,
+}
+
+/// A change that has not been reported to a
+/// subscriber of a digest yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingNotification {
+    pub id: Id,
+    pub user_email: String,
+    pub digest: NotificationDigest,
+    pub change: NotificationChange,
+    pub entity_id: Id,
+    pub title: String,
+    pub created_at: Timestamp,
 }
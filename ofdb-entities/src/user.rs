@@ -1,4 +1,9 @@
-use crate::password::Password;
+use crate::{
+    id::Id,
+    nonce::{hash_secret, Nonce},
+    password::Password,
+    time::Timestamp,
+};
 use num_derive::{FromPrimitive, ToPrimitive};
 
 #[rustfmt::skip]
@@ -24,3 +29,93 @@ impl Default for Role {
         Role::Guest
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, FromPrimitive, ToPrimitive)]
+pub enum ApiTokenScope {
+    Read = 0,
+    Write = 1,
+}
+
+/// A revocable token that authenticates a user without
+/// a password, e.g. in scripts.
+#[rustfmt::skip]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersonalApiToken {
+    pub id          : Id,
+    pub user_email  : String,
+    pub scope       : ApiTokenScope,
+    pub label       : Option<String>,
+    // Only the hash of the secret is stored
+    pub secret_hash : String,
+    pub created_at  : Timestamp,
+}
+
+impl PersonalApiToken {
+    const SEPARATOR: char = '.';
+
+    /// Generates a new token.
+    ///
+    /// The returned bearer token contains the secret and
+    /// can't be recovered later.
+    pub fn generate(
+        user_email: String,
+        scope: ApiTokenScope,
+        label: Option<String>,
+        created_at: Timestamp,
+    ) -> (Self, String) {
+        let id = Id::new();
+        let secret = Nonce::new().to_string();
+        let secret_hash = hash_secret(&secret);
+        let bearer_token = format!("{}{}{}", id, Self::SEPARATOR, secret);
+        let token = Self {
+            id,
+            user_email,
+            scope,
+            label,
+            secret_hash,
+            created_at,
+        };
+        (token, bearer_token)
+    }
+
+    /// Splits a bearer token into the id and the secret
+    pub fn split_bearer_token(bearer_token: &str) -> Option<(&str, &str)> {
+        let mut parts = bearer_token.splitn(2, Self::SEPARATOR);
+        match (parts.next(), parts.next()) {
+            (Some(id), Some(secret)) if !id.is_empty() && !secret.is_empty() => Some((id, secret)),
+            _ => None,
+        }
+    }
+
+    pub fn verify_secret(&self, secret: &str) -> bool {
+        hash_secret(secret) == self.secret_hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_generated_personal_api_token() {
+        let (token, bearer_token) = PersonalApiToken::generate(
+            "user@example.com".into(),
+            ApiTokenScope::Read,
+            None,
+            Timestamp::now(),
+        );
+        let (id, secret) = PersonalApiToken::split_bearer_token(&bearer_token).unwrap();
+        assert_eq!(token.id.as_str(), id);
+        assert!(token.verify_secret(secret));
+        assert!(!token.verify_secret(&Nonce::new().to_string()));
+        assert_ne!(secret, token.secret_hash);
+    }
+
+    #[test]
+    fn split_invalid_bearer_tokens() {
+        assert!(PersonalApiToken::split_bearer_token("").is_none());
+        assert!(PersonalApiToken::split_bearer_token("abc").is_none());
+        assert!(PersonalApiToken::split_bearer_token(".abc").is_none());
+        assert!(PersonalApiToken::split_bearer_token("abc.").is_none());
+    }
+}
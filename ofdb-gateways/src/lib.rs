@@ -1,8 +1,19 @@
 #[macro_use]
 extern crate log;
 
+pub mod link_checker;
+mod mailbox;
 pub mod mailgun;
+pub mod maxmind;
+pub mod nominatim;
 pub mod notify;
 pub mod opencage;
+pub mod openfairdb;
+pub mod opengraph;
+pub mod overpass;
+pub mod photon;
+mod public_url;
 pub mod sendmail;
+pub mod smtp;
+pub mod tile_server;
 pub mod user_communication;
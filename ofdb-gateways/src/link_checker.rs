@@ -0,0 +1,48 @@
+use crate::public_url;
+use ofdb_core::gateways::link_check::{LinkCheckError, LinkChecker};
+use reqwest::{blocking::Client, StatusCode, Url};
+use std::time::Duration;
+
+const USER_AGENT: &str = concat!("openFairDB/", env!("CARGO_PKG_VERSION"));
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Checks links with `HEAD` requests. Redirects are followed.
+///
+/// Links to hosts that are not public are considered as broken.
+pub struct HttpLinkChecker {
+    client: Client,
+}
+
+impl Default for HttpLinkChecker {
+    fn default() -> Self {
+        let client = Client::builder()
+            .user_agent(USER_AGENT)
+            .timeout(TIMEOUT)
+            .redirect(public_url::redirect_policy())
+            .build()
+            .expect("HTTP client");
+        Self { client }
+    }
+}
+
+impl LinkChecker for HttpLinkChecker {
+    fn check_link(&self, url: &str) -> Result<(), LinkCheckError> {
+        let url = Url::parse(url).map_err(|err| LinkCheckError(err.to_string()))?;
+        public_url::check_public_url(&url).map_err(LinkCheckError)?;
+        let send = |req: reqwest::blocking::RequestBuilder| {
+            req.send()
+                .map(|res| res.status())
+                .map_err(|err| LinkCheckError(err.to_string()))
+        };
+        let mut status = send(self.client.head(url.clone()))?;
+        // Some servers don't support HEAD requests
+        if status == StatusCode::METHOD_NOT_ALLOWED {
+            status = send(self.client.get(url))?;
+        }
+        if !status.is_success() {
+            return Err(LinkCheckError(status.to_string()));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+//! Formatting of e-mail addresses in header fields.
+
+/// Strips the display name, e.g. `"Name" <mail@example.com>`.
+pub fn bare_address(address: &str) -> &str {
+    match (address.rfind('<'), address.rfind('>')) {
+        (Some(start), Some(end)) if start < end => &address[start + 1..end],
+        _ => address.trim(),
+    }
+}
+
+/// Replaces the display name of the given address.
+pub fn with_display_name(address: &str, name: &str) -> String {
+    let name = name.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{}\" <{}>", name, bare_address(address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replace_display_name() {
+        assert_eq!(
+            "\"Org \\\"A\\\"\" <from@ofdb.io>",
+            with_display_name("\"OFDB\" <from@ofdb.io>", "Org \"A\"")
+        );
+        assert_eq!(
+            "\"Org\" <from@ofdb.io>",
+            with_display_name(" from@ofdb.io ", "Org")
+        );
+    }
+}
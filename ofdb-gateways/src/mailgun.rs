@@ -1,5 +1,6 @@
+use crate::mailbox;
 use ofdb_core::gateways::email::EmailGateway;
-use ofdb_entities::email::*;
+use ofdb_entities::{email::*, organization::EmailBranding};
 #[cfg(not(test))]
 use std::io::{Error, ErrorKind};
 use std::{io::Result, thread};
@@ -59,6 +60,16 @@ fn send_raw(_: &str, _: &str, params: Vec<(&'static str, String)>) -> Result<()>
 
 impl EmailGateway for Mailgun {
     fn compose_and_send(&self, recipients: &[Email], subject: &str, body: &str) {
+        self.compose_and_send_branded(recipients, &Default::default(), subject, body);
+    }
+
+    fn compose_and_send_branded(
+        &self,
+        recipients: &[Email],
+        branding: &EmailBranding,
+        subject: &str,
+        body: &str,
+    ) {
         if recipients.is_empty() {
             warn!("No valid email adresses specified");
             return;
@@ -71,12 +82,19 @@ impl EmailGateway for Mailgun {
             .collect::<Vec<_>>()
             .join(",");
 
-        let params = vec![
-            ("from", (*self.from_email).clone()),
+        let from = match branding.sender_name {
+            Some(ref name) => mailbox::with_display_name(&self.from_email, name),
+            None => (*self.from_email).clone(),
+        };
+        let mut params = vec![
+            ("from", from),
             ("bcc", recipients),
             ("subject", subject.to_owned()),
             ("text", body.to_owned()),
         ];
+        if let Some(ref reply_to) = branding.reply_to {
+            params.push(("h:Reply-To", reply_to.to_string()));
+        }
         self.send(params);
     }
 }
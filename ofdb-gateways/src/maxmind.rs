@@ -0,0 +1,38 @@
+use maxminddb::{geoip2, MaxMindDBError, Reader};
+use ofdb_core::gateways::geolocate::{IpGeoLocationGateway, IpLocation};
+use ofdb_entities::geo::{Distance, MapPoint};
+use std::{net::IpAddr, path::Path};
+
+/// Locates IP addresses with a local database in the
+/// [MaxMind DB](https://maxmind.github.io/MaxMind-DB/) format,
+/// e.g. GeoLite2 City or DB-IP City Lite.
+pub struct MaxMindDb {
+    reader: Reader<Vec<u8>>,
+}
+
+impl MaxMindDb {
+    pub fn open(path: &Path) -> Result<Self, MaxMindDBError> {
+        let reader = Reader::open_readfile(path)?;
+        Ok(Self { reader })
+    }
+}
+
+impl IpGeoLocationGateway for MaxMindDb {
+    fn locate_ip(&self, ip: IpAddr) -> Option<IpLocation> {
+        let city: geoip2::City = match self.reader.lookup(ip) {
+            Ok(city) => city,
+            Err(MaxMindDBError::AddressNotFoundError(_)) => return None,
+            Err(err) => {
+                warn!("Failed to locate IP address {}: {}", ip, err);
+                return None;
+            }
+        };
+        let location = city.location?;
+        let pos = MapPoint::try_from_lat_lng_deg(location.latitude?, location.longitude?).ok()?;
+        // The accuracy radius is given in kilometers
+        let accuracy = location
+            .accuracy_radius
+            .map(|km| Distance::from_meters(f64::from(km) * 1_000.0));
+        Some(IpLocation { pos, accuracy })
+    }
+}
@@ -0,0 +1,199 @@
+use crate::opencage::address_to_forward_query_string;
+use ofdb_core::gateways::geocode::{AddressSuggestion, GeoCodingGateway};
+use ofdb_entities::address::Address;
+use serde_json::Value;
+
+const DEFAULT_API_URL: &str = "https://nominatim.openstreetmap.org";
+
+// Nominatim requires an identifying user agent
+const USER_AGENT: &str = concat!("openFairDB/", env!("CARGO_PKG_VERSION"));
+
+/// A geocoding gateway based on [Nominatim](https://nominatim.org/).
+pub struct Nominatim {
+    api_url: String,
+    client: reqwest::blocking::Client,
+}
+
+impl Nominatim {
+    pub fn new(api_url: Option<String>) -> Self {
+        Self {
+            api_url: api_url.unwrap_or_else(|| DEFAULT_API_URL.to_string()),
+            client: reqwest::blocking::Client::new(),
+        }
+    }
+
+    fn get(&self, path: &str, query: &[(&str, &str)]) -> reqwest::Result<Value> {
+        self.client
+            .get(format!("{}/{}", self.api_url.trim_end_matches('/'), path))
+            .header(reqwest::header::USER_AGENT, USER_AGENT)
+            .query(&[("format", "json")])
+            .query(query)
+            .send()?
+            .error_for_status()?
+            .json()
+    }
+}
+
+// The response is a list of places with coordinates encoded as strings
+fn lat_lng_from_response(res: &Value) -> Option<(f64, f64)> {
+    let place = res.as_array()?.first()?;
+    let lat = place.get("lat")?.as_str()?.parse().ok()?;
+    let lng = place.get("lon")?.as_str()?.parse().ok()?;
+    Some((lat, lng))
+}
+
+fn address_from_response(res: &Value) -> Option<Address> {
+    let addr = res.get("address")?;
+    let field = |key: &str| {
+        addr.get(key)
+            .and_then(Value::as_str)
+            .map(ToString::to_string)
+    };
+    let street = field("road").map(|road| match field("house_number") {
+        Some(nr) => format!("{} {}", road, nr),
+        None => road,
+    });
+    let city = field("city")
+        .or_else(|| field("town"))
+        .or_else(|| field("village"));
+    Some(Address {
+        street,
+        zip: field("postcode"),
+        city,
+        country: field("country"),
+        state: field("state"),
+    })
+}
+
+fn suggestions_from_response(res: &Value) -> Vec<AddressSuggestion> {
+    res.as_array()
+        .map(|places| {
+            places
+                .iter()
+                .filter_map(|place| {
+                    let label = place.get("display_name")?.as_str()?.to_string();
+                    let lat = place.get("lat")?.as_str()?.parse().ok()?;
+                    let lng = place.get("lon")?.as_str()?.parse().ok()?;
+                    let address = address_from_response(place).unwrap_or_default();
+                    Some(AddressSuggestion {
+                        label,
+                        lat,
+                        lng,
+                        address,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+impl GeoCodingGateway for Nominatim {
+    fn resolve_address_lat_lng(&self, addr: &Address) -> Option<(f64, f64)> {
+        if addr.is_empty() {
+            return None;
+        }
+        let addr_str = address_to_forward_query_string(addr);
+        match self.get("search", &[("q", addr_str.as_str()), ("limit", "1")]) {
+            Ok(res) => {
+                let lat_lng = lat_lng_from_response(&res);
+                debug!("Resolved address location '{}': {:?}", addr_str, lat_lng);
+                lat_lng
+            }
+            Err(err) => {
+                warn!("Failed to resolve address location '{}': {}", addr_str, err);
+                None
+            }
+        }
+    }
+
+    fn resolve_lat_lng_address(&self, lat: f64, lng: f64) -> Option<Address> {
+        let (lat, lon) = (lat.to_string(), lng.to_string());
+        match self.get("reverse", &[("lat", lat.as_str()), ("lon", lon.as_str())]) {
+            Ok(res) => {
+                let addr = address_from_response(&res);
+                debug!("Resolved location address ({}, {}): {:?}", lat, lon, addr);
+                addr
+            }
+            Err(err) => {
+                warn!(
+                    "Failed to resolve location address ({}, {}): {}",
+                    lat, lon, err
+                );
+                None
+            }
+        }
+    }
+
+    fn complete_address(&self, query: &str, limit: usize) -> Vec<AddressSuggestion> {
+        let limit = limit.to_string();
+        match self.get(
+            "search",
+            &[
+                ("q", query),
+                ("limit", limit.as_str()),
+                ("addressdetails", "1"),
+            ],
+        ) {
+            Ok(res) => suggestions_from_response(&res),
+            Err(err) => {
+                warn!("Failed to complete address '{}': {}", query, err);
+                vec![]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_search_response() {
+        let res = serde_json::json!([
+            { "lat": "48.7758459", "lon": "9.1829321", "display_name": "Stuttgart" },
+            { "lat": "0.0", "lon": "0.0" }
+        ]);
+        assert_eq!(Some((48.7758459, 9.1829321)), lat_lng_from_response(&res));
+        assert_eq!(None, lat_lng_from_response(&serde_json::json!([])));
+    }
+
+    #[test]
+    fn parse_reverse_response() {
+        let res = serde_json::json!({
+            "address": {
+                "house_number": "1",
+                "road": "Schlossplatz",
+                "town": "Stuttgart",
+                "state": "Baden-Württemberg",
+                "postcode": "70173",
+                "country": "Deutschland"
+            }
+        });
+        let addr = address_from_response(&res).unwrap();
+        assert_eq!(Some("Schlossplatz 1"), addr.street.as_deref());
+        assert_eq!(Some("70173"), addr.zip.as_deref());
+        assert_eq!(Some("Stuttgart"), addr.city.as_deref());
+        assert_eq!(Some("Deutschland"), addr.country.as_deref());
+        assert_eq!(Some("Baden-Württemberg"), addr.state.as_deref());
+        assert!(
+            address_from_response(&serde_json::json!({ "error": "Unable to geocode" })).is_none()
+        );
+    }
+
+    #[test]
+    fn parse_suggestions() {
+        let res = serde_json::json!([
+            {
+                "lat": "48.7785",
+                "lon": "9.1800",
+                "display_name": "Schlossplatz, Stuttgart, Deutschland",
+                "address": { "road": "Schlossplatz", "city": "Stuttgart" }
+            },
+            { "lat": "48.0", "lon": "9.0" }
+        ]);
+        let suggestions = suggestions_from_response(&res);
+        assert_eq!(1, suggestions.len());
+        assert_eq!("Schlossplatz, Stuttgart, Deutschland", suggestions[0].label);
+        assert_eq!(Some("Stuttgart"), suggestions[0].address.city.as_deref());
+    }
+}
@@ -1,6 +1,15 @@
 use crate::user_communication;
 use ofdb_core::gateways::{email::EmailGateway, notify::NotificationGateway};
-use ofdb_entities::{category::*, email::*, event::*, nonce::*, place::*, user::*};
+use ofdb_entities::{
+    category::*,
+    email::*,
+    event::*,
+    nonce::*,
+    organization::{EmailBranding, PendingModeratedTagsNotification},
+    place::*,
+    rating::*,
+    user::*,
+};
 
 pub struct Notify {
     email_gw: Box<dyn EmailGateway + Send + Sync + 'static>,
@@ -78,6 +87,80 @@ impl NotificationGateway for Notify {
             );
         }
     }
+    fn place_moderated_tags_changed(
+        &self,
+        email_addresses: &[String],
+        place: &Place,
+        added_tags: &[String],
+        removed_tags: &[String],
+        branding: &EmailBranding,
+    ) {
+        let content = user_communication::place_moderated_tags_changed_email(
+            place,
+            added_tags,
+            removed_tags,
+            branding.footer.as_deref(),
+        );
+
+        {
+            info!(
+                "Sending e-mails to {} recipients after moderated tags of place {} changed",
+                email_addresses.len(),
+                place.id
+            );
+            let recipients: Vec<_> = email_addresses.iter().cloned().map(Email::from).collect();
+            self.email_gw.compose_and_send_branded(
+                &recipients,
+                branding,
+                &content.subject,
+                &content.body,
+            );
+        }
+    }
+    fn moderated_tags_summary(
+        &self,
+        email_addresses: &[String],
+        notifications: &[PendingModeratedTagsNotification],
+        branding: &EmailBranding,
+    ) {
+        let content = user_communication::moderated_tags_summary_email(
+            notifications,
+            branding.footer.as_deref(),
+        );
+
+        {
+            info!(
+                "Sending e-mails to {} recipients about {} changes of moderated tags",
+                email_addresses.len(),
+                notifications.len()
+            );
+            let recipients: Vec<_> = email_addresses.iter().cloned().map(Email::from).collect();
+            self.email_gw.compose_and_send_branded(
+                &recipients,
+                branding,
+                &content.subject,
+                &content.body,
+            );
+        }
+    }
+    fn place_rated(&self, email_addresses: &[String], place: &Place, rating: &Rating) {
+        let content = user_communication::place_rated_email(place, rating);
+
+        {
+            info!(
+                "Sending e-mails to {} recipients after place {} was rated",
+                email_addresses.len(),
+                place.id
+            );
+            compose_and_send_emails(
+                &*self.email_gw,
+                email_addresses,
+                &content.subject,
+                &content.body,
+            );
+        }
+    }
+
     fn event_created(&self, email_addresses: &[String], event: &Event) {
         let content = user_communication::event_created_email(&event);
 
@@ -163,6 +246,46 @@ impl NotificationGateway for Notify {
             );
         }
     }
+    fn subscription_consent_requested(&self, email_nonce: &EmailNonce) {
+        let url = format!(
+            "https://kartevonmorgen.org/#/?confirm_subscriptions={}",
+            email_nonce.encode_to_string()
+        );
+        let content = user_communication::subscription_consent_email(&url);
+
+        {
+            info!(
+                "Sending e-mail to {} for confirming the consent to notifications",
+                email_nonce.email
+            );
+            compose_and_send_emails(
+                &*self.email_gw,
+                &[email_nonce.email.to_owned()],
+                &content.subject,
+                &content.body,
+            );
+        }
+    }
+    fn place_confirmation_requested(&self, place: &Place, request: &PlaceConfirmationRequest) {
+        let url = format!(
+            "https://kartevonmorgen.org/#/?confirm_still_valid={}&token={}",
+            place.id, request.nonce
+        );
+        let content = user_communication::place_confirmation_request_email(place, &url);
+
+        {
+            info!(
+                "Sending e-mail to {} for confirming that place {} is still valid",
+                request.sent_to, place.id
+            );
+            compose_and_send_emails(
+                &*self.email_gw,
+                &[request.sent_to.to_string()],
+                &content.subject,
+                &content.body,
+            );
+        }
+    }
 }
 
 fn compose_and_send_emails(
@@ -13,7 +13,7 @@ impl OpenCage {
     }
 }
 
-fn address_to_forward_query_string(addr: &Address) -> String {
+pub(crate) fn address_to_forward_query_string(addr: &Address) -> String {
     let addr_parts = [&addr.street, &addr.zip, &addr.city, &addr.country];
     addr_parts.iter().filter_map(|x| x.as_ref()).join(",")
 }
@@ -0,0 +1,139 @@
+use ofdb_boundary as json;
+use ofdb_core::gateways::upstream::{
+    UpstreamChange, UpstreamChanges, UpstreamError, UpstreamGateway,
+};
+use ofdb_entities::{event::Event, id::Id, place::Place, review::ReviewStatus, time::Timestamp};
+use reqwest::{blocking::Response, StatusCode};
+use serde_json::Value;
+
+const USER_AGENT: &str = concat!("openFairDB/", env!("CARGO_PKG_VERSION"));
+
+// The maximum number of changes that is returned by the API
+const MAX_CHANGES_PER_REQUEST: u64 = 1000;
+
+/// Fetches places and events from the API of another OpenFairDB instance.
+pub struct OpenFairDb {
+    api_url: String,
+    client: reqwest::blocking::Client,
+}
+
+impl OpenFairDb {
+    pub fn new(api_url: String) -> Self {
+        Self {
+            api_url: api_url.trim_end_matches('/').to_string(),
+            client: reqwest::blocking::Client::new(),
+        }
+    }
+
+    fn get(&self, path: &str) -> Result<Option<Response>, UpstreamError> {
+        let res = self
+            .client
+            .get(&format!("{}{}", self.api_url, path))
+            .header(reqwest::header::USER_AGENT, USER_AGENT)
+            .send()
+            .map_err(|err| UpstreamError(err.to_string()))?;
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        res.error_for_status()
+            .map(Some)
+            .map_err(|err| UpstreamError(err.to_string()))
+    }
+}
+
+// Ratings and comments are not mirrored, but they still
+// count for continuing with the next batch of changes
+fn changes_from_response(res: Value) -> Result<UpstreamChanges, UpstreamError> {
+    let changes: Vec<json::ChangeRecord> =
+        serde_json::from_value(res).map_err(|err| UpstreamError(err.to_string()))?;
+    let last_change_at = changes
+        .iter()
+        .map(|change| change.at)
+        .max()
+        .map(Timestamp::from_seconds);
+    let changes = changes
+        .into_iter()
+        .filter_map(|change| {
+            let json::ChangeRecord { entity, id, at, .. } = change;
+            let change = match entity {
+                json::ChangedEntity::Place => UpstreamChange::Place(id.into()),
+                json::ChangedEntity::Event => UpstreamChange::Event(id.into()),
+                json::ChangedEntity::Rating | json::ChangedEntity::Comment => return None,
+            };
+            Some((Timestamp::from_seconds(at), change))
+        })
+        .collect();
+    Ok(UpstreamChanges {
+        changes,
+        last_change_at,
+    })
+}
+
+impl UpstreamGateway for OpenFairDb {
+    fn changes_since(&self, since: Timestamp) -> Result<UpstreamChanges, UpstreamError> {
+        let path = format!(
+            "/changes?since={}&limit={}",
+            since.into_seconds(),
+            MAX_CHANGES_PER_REQUEST
+        );
+        let res: Value = match self.get(&path)? {
+            Some(res) => res.json().map_err(|err| UpstreamError(err.to_string()))?,
+            None => return Err(UpstreamError("Change log not available".to_string())),
+        };
+        let changes = changes_from_response(res)?;
+        debug!("Fetched {} upstream changes", changes.changes.len());
+        Ok(changes)
+    }
+
+    fn place(&self, id: &Id) -> Result<Option<(Place, ReviewStatus)>, UpstreamError> {
+        let res = match self.get(&format!("/places/{}", id))? {
+            Some(res) => res,
+            None => return Ok(None),
+        };
+        let (root, revision, status): (json::PlaceRoot, json::PlaceRevision, json::ReviewStatus) =
+            res.json().map_err(|err| UpstreamError(err.to_string()))?;
+        let place = Place::from((root.into(), revision.into()));
+        Ok(Some((place, status.into())))
+    }
+
+    fn event(&self, id: &Id) -> Result<Option<Event>, UpstreamError> {
+        let res = match self.get(&format!("/events/{}", id))? {
+            Some(res) => res,
+            None => return Ok(None),
+        };
+        let event: json::Event = res.json().map_err(|err| UpstreamError(err.to_string()))?;
+        Ok(Some(event.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_changes_of_places_and_events() {
+        let res = serde_json::json!([
+            { "entity": "place", "id": "a", "kind": "created", "at": 10 },
+            { "entity": "rating", "id": "b", "kind": "created", "at": 11 },
+            { "entity": "event", "id": "c", "kind": "deleted", "at": 12, "actor_role": "scout" },
+            { "entity": "comment", "id": "d", "kind": "archived", "at": 13 }
+        ]);
+        let changes = changes_from_response(res).unwrap();
+        assert_eq!(
+            vec![
+                (
+                    Timestamp::from_seconds(10),
+                    UpstreamChange::Place("a".into())
+                ),
+                (
+                    Timestamp::from_seconds(12),
+                    UpstreamChange::Event("c".into())
+                ),
+            ],
+            changes.changes
+        );
+        // The comment is not mirrored but the most recent change
+        assert_eq!(Some(Timestamp::from_seconds(13)), changes.last_change_at);
+        assert!(changes_from_response(serde_json::json!({})).is_err());
+    }
+}
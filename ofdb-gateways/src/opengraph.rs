@@ -0,0 +1,345 @@
+use crate::public_url;
+use ofdb_core::gateways::preview::{PagePreview, PreviewError, PreviewGateway};
+use reqwest::{blocking::Client, Url};
+use std::{
+    collections::HashMap,
+    io::Read,
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
+
+const ROBOT_NAME: &str = "openfairdb";
+
+const USER_AGENT: &str = concat!("openFairDB/", env!("CARGO_PKG_VERSION"));
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+// The metadata is expected in the head of the page
+const MAX_PAGE_SIZE: u64 = 256 * 1024;
+
+/// Fetches the [OpenGraph](https://ogp.me/) metadata of web pages.
+///
+/// The `robots.txt` of each site is only fetched once during
+/// the lifetime of this gateway.
+///
+/// Only pages on public hosts are fetched, including all redirects.
+pub struct OpenGraph {
+    client: Client,
+    // Disallowed path prefixes per origin
+    robots: Mutex<HashMap<String, Vec<String>>>,
+}
+
+impl Default for OpenGraph {
+    fn default() -> Self {
+        let client = Client::builder()
+            .user_agent(USER_AGENT)
+            .timeout(TIMEOUT)
+            .redirect(public_url::redirect_policy())
+            .build()
+            .expect("HTTP client");
+        Self {
+            client,
+            robots: Default::default(),
+        }
+    }
+}
+
+impl OpenGraph {
+    fn fetch_disallowed_paths(&self, origin: &str) -> Vec<String> {
+        match self.client.get(&format!("{}/robots.txt", origin)).send() {
+            Ok(res) if res.status().is_success() => res
+                .text()
+                .map(|robots_txt| disallowed_paths(&robots_txt))
+                .unwrap_or_default(),
+            // A missing robots.txt allows everything
+            Ok(_) => vec![],
+            Err(err) => {
+                warn!("Failed to fetch robots.txt of {}: {}", origin, err);
+                vec!["/".into()]
+            }
+        }
+    }
+
+    fn is_allowed_by_robots(&self, url: &Url) -> bool {
+        let origin = url.origin().ascii_serialization();
+        let cached = self
+            .robots
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&origin)
+            .cloned();
+        let disallowed = match cached {
+            Some(disallowed) => disallowed,
+            None => {
+                // The lock is released while fetching, i.e. the robots.txt
+                // might be fetched twice by concurrent requests
+                let disallowed = self.fetch_disallowed_paths(&origin);
+                self.robots
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .entry(origin)
+                    .or_insert(disallowed)
+                    .clone()
+            }
+        };
+        is_allowed(&disallowed, url.path())
+    }
+}
+
+impl PreviewGateway for OpenGraph {
+    fn fetch_preview(&self, url: &str) -> Result<Option<PagePreview>, PreviewError> {
+        let url = Url::parse(url).map_err(|err| PreviewError(err.to_string()))?;
+        public_url::check_public_url(&url).map_err(PreviewError)?;
+        if !self.is_allowed_by_robots(&url) {
+            return Ok(None);
+        }
+        let res = self
+            .client
+            .get(url)
+            .send()
+            .map_err(|err| PreviewError(err.to_string()))?;
+        if !res.status().is_success() {
+            return Err(PreviewError(res.status().to_string()));
+        }
+        // Relative URLs are resolved against the URL after redirects
+        let base_url = res.url().clone();
+        let mut page = vec![];
+        res.take(MAX_PAGE_SIZE)
+            .read_to_end(&mut page)
+            .map_err(|err| PreviewError(err.to_string()))?;
+        Ok(Some(parse_preview(
+            &String::from_utf8_lossy(&page),
+            &base_url,
+        )))
+    }
+}
+
+// Only `Disallow` rules without wildcards are supported. The rules
+// for all robots apply unless there is a group for this robot.
+fn disallowed_paths(robots_txt: &str) -> Vec<String> {
+    let mut groups: Vec<(Vec<String>, Vec<String>)> = vec![];
+    let mut reading_agents = false;
+    for line in robots_txt.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        let (key, value) = match line.find(':') {
+            Some(i) => (line[..i].trim().to_lowercase(), line[i + 1..].trim()),
+            None => continue,
+        };
+        match key.as_str() {
+            "user-agent" => {
+                if !reading_agents {
+                    groups.push((vec![], vec![]));
+                }
+                reading_agents = true;
+                if let Some((agents, _)) = groups.last_mut() {
+                    agents.push(value.to_lowercase());
+                }
+            }
+            "disallow" => {
+                reading_agents = false;
+                if let Some((_, disallowed)) = groups.last_mut() {
+                    if !value.is_empty() {
+                        disallowed.push(value.to_string());
+                    }
+                }
+            }
+            _ => {
+                reading_agents = false;
+            }
+        }
+    }
+    let rules_for = |is_agent: &dyn Fn(&str) -> bool| -> Option<Vec<String>> {
+        let matching: Vec<_> = groups
+            .iter()
+            .filter(|(agents, _)| agents.iter().any(|a| is_agent(a)))
+            .collect();
+        if matching.is_empty() {
+            return None;
+        }
+        Some(
+            matching
+                .into_iter()
+                .flat_map(|(_, disallowed)| disallowed.clone())
+                .collect(),
+        )
+    };
+    rules_for(&|agent| agent != "*" && agent.contains(ROBOT_NAME))
+        .or_else(|| rules_for(&|agent| agent == "*"))
+        .unwrap_or_default()
+}
+
+fn is_allowed(disallowed: &[String], path: &str) -> bool {
+    !disallowed
+        .iter()
+        .any(|prefix| path.starts_with(prefix.as_str()))
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+fn normalize_text(text: &str) -> Option<String> {
+    let text = decode_entities(text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+// The value of an attribute within the contents of a tag, e.g.
+// `property="og:title" content="Title"`. Attribute names are
+// expected in lowercase.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    // ASCII lowercase preserves the byte offsets
+    let lower = tag.to_ascii_lowercase();
+    let mut start = 0;
+    while let Some(i) = lower[start..].find(name) {
+        let i = start + i;
+        start = i + name.len();
+        if !lower[..i].ends_with(char::is_whitespace) {
+            continue;
+        }
+        let rest = lower[start..].trim_start();
+        if !rest.starts_with('=') {
+            continue;
+        }
+        let value = tag[tag.len() - rest.len() + 1..].trim_start();
+        return match value.chars().next() {
+            Some(quote) if quote == '"' || quote == '\'' => {
+                let value = &value[1..];
+                value.find(quote).map(|end| &value[..end])
+            }
+            _ => value
+                .split_whitespace()
+                .next()
+                .map(|value| value.trim_end_matches('/')),
+        };
+    }
+    None
+}
+
+fn parse_preview(html: &str, base_url: &Url) -> PagePreview {
+    let mut preview = PagePreview::default();
+    let lower = html.to_ascii_lowercase();
+    for (i, _) in lower.match_indices("<meta") {
+        let end = match lower[i..].find('>') {
+            Some(end) => i + end,
+            None => break,
+        };
+        let tag = &html[i + "<meta".len()..end];
+        let property = attribute(tag, "property")
+            .or_else(|| attribute(tag, "name"))
+            .map(str::to_ascii_lowercase);
+        let content = match attribute(tag, "content") {
+            Some(content) => content,
+            None => continue,
+        };
+        match property.as_deref() {
+            Some("og:title") if preview.title.is_none() => {
+                preview.title = normalize_text(content);
+            }
+            Some("og:image") if preview.image_url.is_none() => {
+                preview.image_url = base_url
+                    .join(decode_entities(content).trim())
+                    .ok()
+                    .filter(|url| url.scheme() == "http" || url.scheme() == "https")
+                    .map(String::from);
+            }
+            _ => {}
+        }
+    }
+    if preview.title.is_none() {
+        preview.title = lower.find("<title").and_then(|i| {
+            let start = i + lower[i..].find('>')? + 1;
+            let end = start + lower[start..].find("</title")?;
+            normalize_text(&html[start..end])
+        });
+    }
+    preview
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn respect_rules_for_all_robots() {
+        let robots_txt = "
+            User-agent: *
+            Disallow: /private/ # Comment
+            Disallow:
+
+            User-agent: Googlebot
+            Disallow: /
+        ";
+        let disallowed = disallowed_paths(robots_txt);
+        assert_eq!(vec!["/private/"], disallowed);
+        assert!(is_allowed(&disallowed, "/"));
+        assert!(!is_allowed(&disallowed, "/private/index.html"));
+    }
+
+    #[test]
+    fn prefer_rules_for_this_robot() {
+        let robots_txt = "
+            User-agent: *
+            Disallow: /private/
+
+            User-agent: Bingbot
+            User-agent: openFairDB
+            Disallow: /
+        ";
+        assert_eq!(vec!["/"], disallowed_paths(robots_txt));
+        assert!(disallowed_paths("").is_empty());
+    }
+
+    #[test]
+    fn parse_opengraph_metadata() {
+        let html = r#"<!DOCTYPE html>
+            <html><head>
+            <title>Fallback</title>
+            <meta name="description" content="Ignored">
+            <META property='og:title' content="Bioladen &amp; Café
+                am Markt" />
+            <meta content="/images/shop.jpg" property="og:image">
+            </head></html>"#;
+        let base_url = Url::parse("https://example.com/shop/").unwrap();
+        assert_eq!(
+            PagePreview {
+                title: Some("Bioladen & Café am Markt".into()),
+                image_url: Some("https://example.com/images/shop.jpg".into()),
+            },
+            parse_preview(html, &base_url)
+        );
+    }
+
+    #[test]
+    fn fall_back_to_the_title_of_the_page() {
+        let html = "<html><head><title lang=\"de\"> Bioladen </title></head></html>";
+        let base_url = Url::parse("https://example.com/").unwrap();
+        assert_eq!(
+            PagePreview {
+                title: Some("Bioladen".into()),
+                image_url: None,
+            },
+            parse_preview(html, &base_url)
+        );
+        assert_eq!(PagePreview::default(), parse_preview("", &base_url));
+    }
+
+    #[test]
+    fn find_attribute_values() {
+        let tag = r#" data-content="x" content=unquoted name='og:title'"#;
+        assert_eq!(Some("unquoted"), attribute(tag, "content"));
+        assert_eq!(Some("og:title"), attribute(tag, "name"));
+        assert_eq!(None, attribute(tag, "property"));
+    }
+}
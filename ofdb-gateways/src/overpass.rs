@@ -0,0 +1,119 @@
+use ofdb_core::gateways::osm::{OsmGateway, OsmNode, OsmQueryError};
+use serde_json::Value;
+
+const DEFAULT_API_URL: &str = "https://overpass-api.de/api/interpreter";
+
+const USER_AGENT: &str = concat!("openFairDB/", env!("CARGO_PKG_VERSION"));
+
+/// Fetches OSM nodes from an [Overpass API](https://wiki.openstreetmap.org/wiki/Overpass_API).
+pub struct Overpass {
+    api_url: String,
+    client: reqwest::blocking::Client,
+}
+
+impl Overpass {
+    pub fn new(api_url: Option<String>) -> Self {
+        Self {
+            api_url: api_url.unwrap_or_else(|| DEFAULT_API_URL.to_string()),
+            client: reqwest::blocking::Client::new(),
+        }
+    }
+}
+
+// The results are only parsed as JSON
+fn json_output_query(query: &str) -> String {
+    let query = query.trim();
+    if query.starts_with("[out:json]") {
+        query.to_string()
+    } else {
+        format!("[out:json];{}", query)
+    }
+}
+
+// Only nodes are considered, ways and relations are ignored
+fn nodes_from_response(res: &Value) -> Vec<OsmNode> {
+    res.get("elements")
+        .and_then(Value::as_array)
+        .map(|elements| {
+            elements
+                .iter()
+                .filter(|e| e.get("type").and_then(Value::as_str) == Some("node"))
+                .filter_map(|e| {
+                    let id = e.get("id")?.as_u64()?;
+                    let lat = e.get("lat")?.as_f64()?;
+                    let lon = e.get("lon")?.as_f64()?;
+                    let tags = e
+                        .get("tags")
+                        .and_then(Value::as_object)
+                        .map(|tags| {
+                            tags.iter()
+                                .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    Some(OsmNode { id, lat, lon, tags })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+impl OsmGateway for Overpass {
+    fn query_nodes(&self, query: &str) -> Result<Vec<OsmNode>, OsmQueryError> {
+        let res: Value = self
+            .client
+            .post(&self.api_url)
+            .header(reqwest::header::USER_AGENT, USER_AGENT)
+            .form(&[("data", json_output_query(query))])
+            .send()
+            .and_then(|res| res.error_for_status())
+            .and_then(|res| res.json())
+            .map_err(|err| OsmQueryError(err.to_string()))?;
+        let nodes = nodes_from_response(&res);
+        debug!("Fetched {} OSM nodes", nodes.len());
+        Ok(nodes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_json_output() {
+        assert_eq!(
+            "[out:json];node[shop=organic](48.7,9.1,48.8,9.2);out;",
+            json_output_query("node[shop=organic](48.7,9.1,48.8,9.2);out;")
+        );
+        assert_eq!(
+            "[out:json][timeout:25];node;out;",
+            json_output_query(" [out:json][timeout:25];node;out;")
+        );
+    }
+
+    #[test]
+    fn parse_nodes() {
+        let res = serde_json::json!({
+            "elements": [
+                {
+                    "type": "node",
+                    "id": 42,
+                    "lat": 48.7758,
+                    "lon": 9.1829,
+                    "tags": { "name": "Bioladen", "shop": "organic" }
+                },
+                { "type": "node", "id": 43, "lat": 48.7, "lon": 9.1 },
+                { "type": "way", "id": 44, "nodes": [42, 43] }
+            ]
+        });
+        let nodes = nodes_from_response(&res);
+        assert_eq!(2, nodes.len());
+        assert_eq!(42, nodes[0].id);
+        assert_eq!(
+            Some("Bioladen"),
+            nodes[0].tags.get("name").map(String::as_str)
+        );
+        assert!(nodes[1].tags.is_empty());
+        assert!(nodes_from_response(&serde_json::json!({})).is_empty());
+    }
+}
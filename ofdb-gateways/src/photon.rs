@@ -0,0 +1,231 @@
+use crate::opencage::address_to_forward_query_string;
+use ofdb_core::gateways::geocode::{AddressSuggestion, GeoCodingGateway};
+use ofdb_entities::address::Address;
+use serde_json::Value;
+
+const DEFAULT_API_URL: &str = "https://photon.komoot.io";
+
+/// A geocoding gateway based on [Photon](https://photon.komoot.io/).
+pub struct Photon {
+    api_url: String,
+    client: reqwest::blocking::Client,
+}
+
+impl Photon {
+    pub fn new(api_url: Option<String>) -> Self {
+        Self {
+            api_url: api_url.unwrap_or_else(|| DEFAULT_API_URL.to_string()),
+            client: reqwest::blocking::Client::new(),
+        }
+    }
+
+    fn get(&self, path: &str, query: &[(&str, &str)]) -> reqwest::Result<Value> {
+        self.client
+            .get(format!("{}/{}", self.api_url.trim_end_matches('/'), path))
+            .query(query)
+            .send()?
+            .error_for_status()?
+            .json()
+    }
+}
+
+fn first_feature(res: &Value) -> Option<&Value> {
+    res.get("features")?.as_array()?.first()
+}
+
+// The response is a GeoJSON feature collection with
+// coordinates in (lng, lat) order
+fn lat_lng_from_feature(feature: &Value) -> Option<(f64, f64)> {
+    let coordinates = feature.get("geometry")?.get("coordinates")?.as_array()?;
+    let lng = coordinates.get(0)?.as_f64()?;
+    let lat = coordinates.get(1)?.as_f64()?;
+    Some((lat, lng))
+}
+
+fn lat_lng_from_response(res: &Value) -> Option<(f64, f64)> {
+    lat_lng_from_feature(first_feature(res)?)
+}
+
+fn address_from_feature(feature: &Value) -> Option<Address> {
+    let props = feature.get("properties")?;
+    let field = |key: &str| {
+        props
+            .get(key)
+            .and_then(Value::as_str)
+            .map(ToString::to_string)
+    };
+    let street = field("street").map(|street| match field("housenumber") {
+        Some(nr) => format!("{} {}", street, nr),
+        None => street,
+    });
+    Some(Address {
+        street,
+        zip: field("postcode"),
+        city: field("city"),
+        country: field("country"),
+        state: field("state"),
+    })
+}
+
+fn address_from_response(res: &Value) -> Option<Address> {
+    address_from_feature(first_feature(res)?)
+}
+
+// Photon doesn't return a display name
+fn suggestion_label(feature: &Value, address: &Address) -> String {
+    let name = feature
+        .get("properties")
+        .and_then(|props| props.get("name"))
+        .and_then(Value::as_str);
+    let city = match (&address.zip, &address.city) {
+        (Some(zip), Some(city)) => Some(format!("{} {}", zip, city)),
+        (None, Some(city)) => Some(city.clone()),
+        (Some(zip), None) => Some(zip.clone()),
+        (None, None) => None,
+    };
+    name.map(ToString::to_string)
+        .into_iter()
+        .chain(address.street.clone())
+        .chain(city)
+        .chain(address.country.clone())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn suggestions_from_response(res: &Value) -> Vec<AddressSuggestion> {
+    res.get("features")
+        .and_then(Value::as_array)
+        .map(|features| {
+            features
+                .iter()
+                .filter_map(|feature| {
+                    let (lat, lng) = lat_lng_from_feature(feature)?;
+                    let address = address_from_feature(feature).unwrap_or_default();
+                    let label = suggestion_label(feature, &address);
+                    Some(AddressSuggestion {
+                        label,
+                        lat,
+                        lng,
+                        address,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+impl GeoCodingGateway for Photon {
+    fn resolve_address_lat_lng(&self, addr: &Address) -> Option<(f64, f64)> {
+        if addr.is_empty() {
+            return None;
+        }
+        let addr_str = address_to_forward_query_string(addr);
+        match self.get("api/", &[("q", addr_str.as_str()), ("limit", "1")]) {
+            Ok(res) => {
+                let lat_lng = lat_lng_from_response(&res);
+                debug!("Resolved address location '{}': {:?}", addr_str, lat_lng);
+                lat_lng
+            }
+            Err(err) => {
+                warn!("Failed to resolve address location '{}': {}", addr_str, err);
+                None
+            }
+        }
+    }
+
+    fn resolve_lat_lng_address(&self, lat: f64, lng: f64) -> Option<Address> {
+        let (lat, lon) = (lat.to_string(), lng.to_string());
+        match self.get(
+            "reverse",
+            &[("lat", lat.as_str()), ("lon", lon.as_str()), ("limit", "1")],
+        ) {
+            Ok(res) => {
+                let addr = address_from_response(&res);
+                debug!("Resolved location address ({}, {}): {:?}", lat, lon, addr);
+                addr
+            }
+            Err(err) => {
+                warn!(
+                    "Failed to resolve location address ({}, {}): {}",
+                    lat, lon, err
+                );
+                None
+            }
+        }
+    }
+
+    fn complete_address(&self, query: &str, limit: usize) -> Vec<AddressSuggestion> {
+        let limit = limit.to_string();
+        match self.get("api/", &[("q", query), ("limit", limit.as_str())]) {
+            Ok(res) => suggestions_from_response(&res),
+            Err(err) => {
+                warn!("Failed to complete address '{}': {}", query, err);
+                vec![]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_search_response() {
+        let res = serde_json::json!({
+            "type": "FeatureCollection",
+            "features": [{
+                "type": "Feature",
+                "geometry": { "type": "Point", "coordinates": [9.1829321, 48.7758459] },
+                "properties": { "name": "Stuttgart" }
+            }]
+        });
+        assert_eq!(Some((48.7758459, 9.1829321)), lat_lng_from_response(&res));
+        let empty = serde_json::json!({ "type": "FeatureCollection", "features": [] });
+        assert_eq!(None, lat_lng_from_response(&empty));
+    }
+
+    #[test]
+    fn parse_reverse_response() {
+        let res = serde_json::json!({
+            "features": [{
+                "geometry": { "type": "Point", "coordinates": [9.1829321, 48.7758459] },
+                "properties": {
+                    "street": "Schlossplatz",
+                    "housenumber": "1",
+                    "postcode": "70173",
+                    "city": "Stuttgart",
+                    "country": "Deutschland"
+                }
+            }]
+        });
+        let addr = address_from_response(&res).unwrap();
+        assert_eq!(Some("Schlossplatz 1"), addr.street.as_deref());
+        assert_eq!(Some("70173"), addr.zip.as_deref());
+        assert_eq!(Some("Stuttgart"), addr.city.as_deref());
+        assert_eq!(Some("Deutschland"), addr.country.as_deref());
+        assert_eq!(None, addr.state);
+    }
+
+    #[test]
+    fn parse_suggestions() {
+        let res = serde_json::json!({
+            "features": [{
+                "geometry": { "type": "Point", "coordinates": [9.18, 48.7785] },
+                "properties": {
+                    "name": "Schlossplatz",
+                    "postcode": "70173",
+                    "city": "Stuttgart",
+                    "country": "Deutschland"
+                }
+            }]
+        });
+        let suggestions = suggestions_from_response(&res);
+        assert_eq!(1, suggestions.len());
+        assert_eq!(
+            "Schlossplatz, 70173 Stuttgart, Deutschland",
+            suggestions[0].label
+        );
+        assert_eq!((48.7785, 9.18), (suggestions[0].lat, suggestions[0].lng));
+    }
+}
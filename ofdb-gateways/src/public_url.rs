@@ -0,0 +1,129 @@
+//! Restrict outgoing requests for user-provided URLs to
+//! public hosts, e.g. to prevent requests to services
+//! in the internal network or to cloud metadata endpoints.
+
+use reqwest::{
+    redirect::{Attempt, Policy},
+    Url,
+};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, ToSocketAddrs};
+
+const MAX_REDIRECTS: usize = 10;
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let octets = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        // Includes the metadata endpoint 169.254.169.254 of cloud providers
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        // "This network" 0.0.0.0/8
+        || octets[0] == 0
+        // Shared address space 100.64.0.0/10
+        || (octets[0] == 100 && (octets[1] & 0xc0) == 64))
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    if ip.is_loopback() || ip.is_unspecified() || ip.is_multicast() {
+        return false;
+    }
+    // IPv4-mapped and IPv4-compatible addresses
+    if let Some(ipv4) = ip.to_ipv4() {
+        return is_public_ipv4(ipv4);
+    }
+    let first_segment = ip.segments()[0];
+    // Unique local fc00::/7 and link-local fe80::/10 addresses
+    (first_segment & 0xfe00) != 0xfc00 && (first_segment & 0xffc0) != 0xfe80
+}
+
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => is_public_ipv6(ip),
+    }
+}
+
+/// Checks the scheme and that all addresses of the host are public.
+///
+/// The host is resolved again when connecting, i.e. this
+/// doesn't protect against DNS rebinding.
+pub fn check_public_url(url: &Url) -> Result<(), String> {
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(format!("Unsupported scheme {}", url.scheme()));
+    }
+    let host = url
+        .host_str()
+        .ok_or_else(|| format!("Missing host in {}", url))?;
+    // IPv6 addresses are enclosed in brackets
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let port = url.port_or_known_default().unwrap_or(80);
+    let addrs: Vec<_> = (host, port)
+        .to_socket_addrs()
+        .map_err(|err| format!("Failed to resolve {}: {}", host, err))?
+        .collect();
+    if addrs.is_empty() {
+        return Err(format!("Failed to resolve {}", host));
+    }
+    if let Some(addr) = addrs.into_iter().find(|addr| !is_public_ip(addr.ip())) {
+        return Err(format!("Host {} is not public ({})", host, addr.ip()));
+    }
+    Ok(())
+}
+
+/// Follows only redirects to public hosts.
+pub fn redirect_policy() -> Policy {
+    Policy::custom(|attempt: Attempt| {
+        if attempt.previous().len() >= MAX_REDIRECTS {
+            return attempt.error("too many redirects");
+        }
+        match check_public_url(attempt.url()) {
+            Ok(()) => attempt.follow(),
+            Err(err) => attempt.error(err),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(url: &str) -> Result<(), String> {
+        check_public_url(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn reject_non_public_ip_addresses() {
+        for url in &[
+            "http://127.0.0.1/",
+            "http://10.1.2.3/",
+            "http://172.16.0.1/",
+            "http://192.168.178.1:8080/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://0.0.0.0/",
+            "http://100.64.0.1/",
+            "http://[::1]/",
+            "http://[::]/",
+            "http://[fd00::1]/",
+            "http://[fe80::1]/",
+            "http://[::ffff:127.0.0.1]/",
+            "http://[::ffff:169.254.169.254]/",
+        ] {
+            assert!(check(url).is_err(), "{}", url);
+        }
+    }
+
+    #[test]
+    fn accept_public_ip_addresses() {
+        assert!(check("http://93.184.216.34/").is_ok());
+        assert!(check("https://[2606:2800:220:1:248:1893:25c8:1946]/").is_ok());
+    }
+
+    #[test]
+    fn reject_unsupported_schemes() {
+        assert!(check("ftp://93.184.216.34/").is_err());
+        assert!(check("file:///etc/passwd").is_err());
+    }
+}
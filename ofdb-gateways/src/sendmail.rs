@@ -1,7 +1,8 @@
+use crate::mailbox;
 use chrono::*;
 use fast_chemail::is_valid_email;
 use ofdb_core::gateways::email::EmailGateway;
-use ofdb_entities::email::*;
+use ofdb_entities::{email::*, organization::EmailBranding};
 #[cfg(not(test))]
 use std::{
     io::prelude::*,
@@ -55,9 +56,24 @@ fn send_raw(email: &str) -> Result<()> {
 
 impl EmailGateway for Sendmail {
     fn compose_and_send(&self, recipients: &[Email], subject: &str, body: &str) {
+        self.compose_and_send_branded(recipients, &Default::default(), subject, body);
+    }
+
+    fn compose_and_send_branded(
+        &self,
+        recipients: &[Email],
+        branding: &EmailBranding,
+        subject: &str,
+        body: &str,
+    ) {
         debug!("Sending e-mails to: {:?}", recipients);
+        let from_header = match branding.sender_name {
+            Some(ref name) => from_header_with_name(&self.from, name),
+            None => format!("From:{}", self.from),
+        };
+        let reply_to = branding.reply_to.as_ref().map(|reply_to| reply_to.as_str());
         for to in recipients {
-            match compose(&self.from, &[to], subject, body) {
+            match compose_message(&from_header, reply_to, &[to], subject, body) {
                 Ok(email) => {
                     self.send(email);
                 }
@@ -135,7 +151,29 @@ fn encode_header_field(name: &str, input: &str) -> String {
     encoded_output
 }
 
+pub(crate) fn from_header_with_name(from: &str, name: &str) -> String {
+    if name.is_ascii() {
+        format!("From:{}", mailbox::with_display_name(from, name))
+    } else {
+        format!(
+            "{} <{}>",
+            encode_header_field("From", name),
+            mailbox::bare_address(from)
+        )
+    }
+}
+
 pub fn compose(from: &str, to: &[&str], subject: &str, body: &str) -> Result<String> {
+    compose_message(&format!("From:{}", from), None, to, subject, body)
+}
+
+pub(crate) fn compose_message(
+    from_header: &str,
+    reply_to: Option<&str>,
+    to: &[&str],
+    subject: &str,
+    body: &str,
+) -> Result<String> {
     let to: Vec<_> = to.iter().filter(|m| is_valid_email(m)).cloned().collect();
 
     if to.is_empty() {
@@ -147,16 +185,22 @@ pub fn compose(from: &str, to: &[&str], subject: &str, body: &str) -> Result<Str
 
     let now = Local::now();
 
+    let reply_to_header = reply_to
+        .map(|reply_to| format!("Reply-To:{}{}", reply_to, LINE_BREAK))
+        .unwrap_or_default();
+
     let email = format!(
         "Date:{date}\r\n\
-         From:{from}\r\n\
+         {from_header}\r\n\
+         {reply_to_header}\
          To:{to}\r\n\
          {subject_header}\r\n\
          MIME-Version:1.0\r\n\
          Content-Type:text/plain;charset=utf-8\r\n\r\n\
          {body}",
         date = now.to_rfc2822(),
-        from = from,
+        from_header = from_header,
+        reply_to_header = reply_to_header,
         to = to.join(","),
         subject_header = encode_header_field("Subject", &subject),
         body = body
@@ -190,6 +234,26 @@ mod tests {
         assert!(mail.contains(expected));
     }
 
+    #[test]
+    fn create_branded_mail() {
+        let mail = compose_message(
+            &from_header_with_name("\"OFDB\" <from@ofdb.io>", "Bioladen \"Süd\""),
+            Some("info@bioladen.org"),
+            &["mail@test.org"],
+            "Subject",
+            "Hello Mail",
+        )
+        .unwrap();
+        let expected = "From:=?UTF-8?Q?Bioladen \"S=C3=BCd\"?= <from@ofdb.io>\r\n\
+             Reply-To:info@bioladen.org\r\n\
+             To:mail@test.org\r\n";
+        assert!(mail.contains(expected));
+        assert_eq!(
+            "From:\"Bioladen\" <from@ofdb.io>",
+            from_header_with_name("from@ofdb.io", "Bioladen")
+        );
+    }
+
     #[test]
     fn check_addresses() {
         assert!(compose("from@mail.org", &[], "foo", "bar").is_err());
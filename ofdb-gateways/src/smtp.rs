@@ -0,0 +1,284 @@
+use crate::{mailbox, sendmail};
+use lettre::{address::Envelope, transport::smtp::Error as SendError, Address};
+use ofdb_core::gateways::email::EmailGateway;
+use ofdb_entities::{email::*, organization::EmailBranding};
+use std::{fmt, str::FromStr, thread, time::Duration};
+
+/// How the connection to the SMTP server is secured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    /// Upgrade a plain connection with `STARTTLS` (port 587)
+    StartTls,
+    /// Connect with TLS from the start (port 465)
+    Tls,
+}
+
+impl SmtpSecurity {
+    pub fn default_port(self) -> u16 {
+        match self {
+            Self::StartTls => 587,
+            Self::Tls => 465,
+        }
+    }
+}
+
+impl FromStr for SmtpSecurity {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "starttls" => Ok(Self::StartTls),
+            "tls" | "ssl" => Ok(Self::Tls),
+            _ => Err(format!("Invalid SMTP security '{}'", s)),
+        }
+    }
+}
+
+/// Transient failures, e.g. a greylisting server or a lost
+/// connection, are retried with an exponential backoff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    /// Pause before the first retry that is doubled for every further retry
+    pub initial_backoff: Duration,
+}
+
+impl RetryPolicy {
+    fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff * 2u32.saturating_pow(retry)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            initial_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+/// An e-mail gateway that delivers the e-mails to an SMTP server.
+#[derive(Clone)]
+pub struct Smtp {
+    pub from: Email,
+    pub host: String,
+    pub port: Option<u16>,
+    pub security: SmtpSecurity,
+    /// User name and password
+    pub credentials: Option<(String, String)>,
+    pub timeout: Duration,
+    pub retry: RetryPolicy,
+}
+
+impl fmt::Debug for Smtp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Never log the password
+        f.debug_struct("Smtp")
+            .field("from", &self.from)
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("security", &self.security)
+            .field(
+                "user",
+                &self.credentials.as_ref().map(|(user, _)| user.as_str()),
+            )
+            .field("timeout", &self.timeout)
+            .field("retry", &self.retry)
+            .finish()
+    }
+}
+
+impl Smtp {
+    fn send(&self, envelope: Envelope, mail: String) {
+        let smtp = self.clone();
+        thread::spawn(move || {
+            let result = send_with_retries(&smtp.retry, is_transient, || {
+                send_raw(&smtp, &envelope, &mail)
+            });
+            if let Err(err) = result {
+                warn!("Could not send e-mail to {:?}: {}", envelope.to(), err);
+            }
+        });
+    }
+}
+
+fn send_with_retries<E, F>(
+    retry: &RetryPolicy,
+    is_transient: impl Fn(&E) -> bool,
+    mut send: F,
+) -> Result<(), E>
+where
+    E: fmt::Display,
+    F: FnMut() -> Result<(), E>,
+{
+    let mut retries = 0;
+    loop {
+        match send() {
+            Ok(()) => return Ok(()),
+            Err(err) if retries < retry.max_retries && is_transient(&err) => {
+                let backoff = retry.backoff(retries);
+                info!(
+                    "Failed to send e-mail ({}), retrying in {} s",
+                    err,
+                    backoff.as_secs()
+                );
+                thread::sleep(backoff);
+                retries += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Rejected messages, e.g. due to an unknown recipient,
+/// and invalid TLS certificates are not retried.
+fn is_transient(err: &SendError) -> bool {
+    !(err.is_permanent() || err.is_client() || err.is_tls())
+}
+
+#[cfg(not(test))]
+fn send_raw(smtp: &Smtp, envelope: &Envelope, mail: &str) -> Result<(), SendError> {
+    use lettre::{
+        transport::smtp::{
+            authentication::Credentials,
+            client::{Tls, TlsParameters},
+            SmtpTransport,
+        },
+        Transport,
+    };
+
+    let tls_parameters = TlsParameters::new(smtp.host.clone())?;
+    let tls = match smtp.security {
+        SmtpSecurity::StartTls => Tls::Required(tls_parameters),
+        SmtpSecurity::Tls => Tls::Wrapper(tls_parameters),
+    };
+    let mut builder = SmtpTransport::builder_dangerous(&smtp.host)
+        .port(smtp.port.unwrap_or_else(|| smtp.security.default_port()))
+        .tls(tls)
+        .timeout(Some(smtp.timeout));
+    if let Some((user, password)) = &smtp.credentials {
+        builder = builder.credentials(Credentials::new(user.clone(), password.clone()));
+    }
+    builder.build().send_raw(envelope, mail.as_bytes())?;
+    Ok(())
+}
+
+/// Don't actually send emails while running the tests or
+/// if the `email` feature is disabled.
+#[cfg(test)]
+fn send_raw(_: &Smtp, envelope: &Envelope, mail: &str) -> Result<(), SendError> {
+    debug!("Would send e-mail to {:?}: {}", envelope.to(), mail);
+    Ok(())
+}
+
+impl EmailGateway for Smtp {
+    fn compose_and_send(&self, recipients: &[Email], subject: &str, body: &str) {
+        self.compose_and_send_branded(recipients, &Default::default(), subject, body);
+    }
+
+    fn compose_and_send_branded(
+        &self,
+        recipients: &[Email],
+        branding: &EmailBranding,
+        subject: &str,
+        body: &str,
+    ) {
+        debug!("Sending e-mails to: {:?}", recipients);
+        let from = match mailbox::bare_address(&self.from).parse::<Address>() {
+            Ok(from) => from,
+            Err(err) => {
+                warn!("Invalid sender address {}: {}", self.from, err);
+                return;
+            }
+        };
+        let from_header = match branding.sender_name {
+            Some(ref name) => sendmail::from_header_with_name(&self.from, name),
+            None => format!("From:{}", self.from),
+        };
+        let reply_to = branding.reply_to.as_ref().map(|reply_to| reply_to.as_str());
+        for to in recipients {
+            let envelope = to
+                .parse::<Address>()
+                .map_err(|err| err.to_string())
+                .and_then(|to| {
+                    Envelope::new(Some(from.clone()), vec![to]).map_err(|err| err.to_string())
+                });
+            let envelope = match envelope {
+                Ok(envelope) => envelope,
+                Err(err) => {
+                    warn!("Invalid recipient address {}: {}", to.as_str(), err);
+                    continue;
+                }
+            };
+            match sendmail::compose_message(&from_header, reply_to, &[to], subject, body) {
+                Ok(email) => {
+                    self.send(envelope, email);
+                }
+                Err(err) => {
+                    warn!("Failed to compose e-mail: {}", err);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Error, ErrorKind};
+
+    #[test]
+    fn parse_security() {
+        assert_eq!(Ok(SmtpSecurity::StartTls), "STARTTLS".parse());
+        assert_eq!(Ok(SmtpSecurity::Tls), " tls ".parse());
+        assert!("plain".parse::<SmtpSecurity>().is_err());
+    }
+
+    #[test]
+    fn double_backoff_for_every_retry() {
+        let retry = RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_secs(10),
+        };
+        assert_eq!(Duration::from_secs(10), retry.backoff(0));
+        assert_eq!(Duration::from_secs(20), retry.backoff(1));
+        assert_eq!(Duration::from_secs(40), retry.backoff(2));
+    }
+
+    #[test]
+    fn retry_transient_failures_only() {
+        let retry = RetryPolicy {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(0),
+        };
+        let is_transient = |err: &Error| err.kind() == ErrorKind::TimedOut;
+
+        let mut attempts = 0;
+        let result = send_with_retries(&retry, is_transient, || {
+            attempts += 1;
+            if attempts < 3 {
+                Err(Error::new(ErrorKind::TimedOut, "timeout"))
+            } else {
+                Ok(())
+            }
+        });
+        assert!(result.is_ok());
+        assert_eq!(3, attempts);
+
+        let mut attempts = 0;
+        let result = send_with_retries(&retry, is_transient, || {
+            attempts += 1;
+            Err(Error::new(ErrorKind::TimedOut, "timeout"))
+        });
+        assert!(result.is_err());
+        assert_eq!(3, attempts);
+
+        let mut attempts = 0;
+        let result = send_with_retries(&retry, is_transient, || {
+            attempts += 1;
+            Err(Error::new(ErrorKind::InvalidData, "550 unknown recipient"))
+        });
+        assert!(result.is_err());
+        assert_eq!(1, attempts);
+    }
+}
@@ -0,0 +1,74 @@
+use ofdb_core::gateways::map_tile::{MapTile, MapTileError, MapTileGateway};
+use reqwest::blocking::Client;
+use std::{io::Read, time::Duration};
+
+const USER_AGENT: &str = concat!("openFairDB/", env!("CARGO_PKG_VERSION"));
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+const MAX_TILE_SIZE: u64 = 1024 * 1024;
+
+/// A server for raster map tiles with an URL template
+/// like `https://tile.example.org/{z}/{x}/{y}.png`.
+pub struct TileServer {
+    client: Client,
+    url_template: String,
+}
+
+impl TileServer {
+    pub fn new(url_template: String) -> Self {
+        let client = Client::builder()
+            .user_agent(USER_AGENT)
+            .timeout(TIMEOUT)
+            .build()
+            .expect("HTTP client");
+        Self {
+            client,
+            url_template,
+        }
+    }
+}
+
+impl MapTileGateway for TileServer {
+    fn tile_url(&self, tile: &MapTile) -> String {
+        self.url_template
+            .replace("{z}", &tile.zoom.to_string())
+            .replace("{x}", &tile.x.to_string())
+            .replace("{y}", &tile.y.to_string())
+    }
+
+    fn fetch_tile(&self, tile: &MapTile) -> Result<Vec<u8>, MapTileError> {
+        let res = self
+            .client
+            .get(&self.tile_url(tile))
+            .send()
+            .map_err(|err| MapTileError(err.to_string()))?;
+        if !res.status().is_success() {
+            return Err(MapTileError(res.status().to_string()));
+        }
+        let mut image = vec![];
+        res.take(MAX_TILE_SIZE)
+            .read_to_end(&mut image)
+            .map_err(|err| MapTileError(err.to_string()))?;
+        Ok(image)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tile_url_from_template() {
+        let server = TileServer::new("https://tile.example.org/{z}/{x}/{y}.png".into());
+        let tile = MapTile {
+            zoom: 15,
+            x: 17604,
+            y: 10746,
+        };
+        assert_eq!(
+            "https://tile.example.org/15/17604/10746.png",
+            server.tile_url(&tile)
+        );
+    }
+}
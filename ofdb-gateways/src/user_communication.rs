@@ -1,4 +1,7 @@
-use ofdb_entities::{address::*, contact::*, event::*, place::*, url::*};
+use ofdb_entities::{
+    address::*, contact::*, event::*, id::*, organization::PendingModeratedTagsNotification,
+    place::*, rating::*, subscription::*, url::*,
+};
 
 pub struct EmailContent {
     pub subject: String,
@@ -74,6 +77,21 @@ das Karte von morgen-Team",
     EmailContent { subject, body }
 }
 
+pub fn subscription_consent_email(url: &str) -> EmailContent {
+    let subject = "Karte von morgen: Bitte bestätige dein Abonnement".into();
+    let body = format!(
+        "Na du Weltverbesserer*,\n
+für deine Email-Adresse wurde ein Abonnement von Änderungen auf der Karte von morgen angelegt.\n\n
+Bitte bestätige hier, dass du darüber benachrichtigt werden möchtest:\n
+{url}\n\n
+Ohne deine Bestätigung erhältst du keine Benachrichtigungen.\n
+euphorische Grüße,\n
+das Karte von morgen-Team",
+        url = url,
+    );
+    EmailContent { subject, body }
+}
+
 pub fn place_created_email(place: &Place, category_names: &[String]) -> EmailContent {
     let subject = subject_entry_created(&place.title);
     let body = place_email(place, category_names, INTRO_ENTRY_CREATED);
@@ -87,6 +105,266 @@ pub fn place_updated_email(place: &Place, category_names: &[String]) -> EmailCon
     EmailContent { subject, body }
 }
 
+/// The footer replaces the default greeting if provided.
+pub fn place_moderated_tags_changed_email(
+    place: &Place,
+    added_tags: &[String],
+    removed_tags: &[String],
+    footer: Option<&str>,
+) -> EmailContent {
+    let subject = format!("Kvm - Tags verändert: {}", place.title);
+    let body = format!(
+        "Hallo,\n
+bei folgendem Eintrag auf der Karte von morgen wurden von euch moderierte Tags verändert:\n
+{title}\n
+    Hinzugefügt: {added_tags}
+    Entfernt: {removed_tags}\n
+Eintrag anschauen:
+https://kartevonmorgen.org/#/?entry={id}\n
+{footer}",
+        title = &place.title,
+        added_tags = added_tags.join(", "),
+        removed_tags = removed_tags.join(", "),
+        id = &place.id,
+        footer = footer.unwrap_or("euphorische Grüße,\n\ndas Karte von morgen-Team"),
+    );
+    EmailContent { subject, body }
+}
+
+/// Lists each changed place once with all changes of its
+/// moderated tags. The footer replaces the default greeting
+/// if provided.
+pub fn moderated_tags_summary_email(
+    notifications: &[PendingModeratedTagsNotification],
+    footer: Option<&str>,
+) -> EmailContent {
+    let mut changes: Vec<(&Id, &str, Vec<&str>, Vec<&str>)> = vec![];
+    for n in notifications {
+        let idx = match changes.iter().position(|(id, _, _, _)| **id == n.place_id) {
+            Some(idx) => {
+                // Show the current title
+                changes[idx].1 = &n.place_title;
+                idx
+            }
+            None => {
+                changes.push((&n.place_id, &n.place_title, vec![], vec![]));
+                changes.len() - 1
+            }
+        };
+        let (_, _, added_tags, removed_tags) = &mut changes[idx];
+        // Subsequent edits that revert each other cancel out
+        for t in &n.added_tags {
+            if let Some(pos) = removed_tags.iter().position(|r| *r == t.as_str()) {
+                removed_tags.remove(pos);
+            } else if !added_tags.contains(&t.as_str()) {
+                added_tags.push(t.as_str());
+            }
+        }
+        for t in &n.removed_tags {
+            if let Some(pos) = added_tags.iter().position(|a| *a == t.as_str()) {
+                added_tags.remove(pos);
+            } else if !removed_tags.contains(&t.as_str()) {
+                removed_tags.push(t.as_str());
+            }
+        }
+    }
+    let subject = format!("Kvm - Tags verändert: {} Einträge", changes.len());
+    let list: Vec<_> = changes
+        .iter()
+        .map(|(id, title, added_tags, removed_tags)| {
+            format!(
+                "{title}
+    Hinzugefügt: {added_tags}
+    Entfernt: {removed_tags}
+https://kartevonmorgen.org/#/?entry={id}",
+                title = title,
+                added_tags = added_tags.join(", "),
+                removed_tags = removed_tags.join(", "),
+                id = id
+            )
+        })
+        .collect();
+    let body = format!(
+        "Hallo,\n
+bei folgenden Einträgen auf der Karte von morgen wurden von euch moderierte Tags verändert:\n
+{list}\n
+{footer}",
+        list = list.join("\n\n"),
+        footer = footer.unwrap_or("euphorische Grüße,\n\ndas Karte von morgen-Team"),
+    );
+    EmailContent { subject, body }
+}
+
+fn rating_context_name(context: RatingContext) -> &'static str {
+    match context {
+        RatingContext::Diversity => "Vielfalt",
+        RatingContext::Renewable => "Erneuerbarkeit",
+        RatingContext::Fairness => "Fairness",
+        RatingContext::Humanity => "Menschlichkeit",
+        RatingContext::Transparency => "Transparenz",
+        RatingContext::Solidarity => "Solidarität",
+    }
+}
+
+pub fn place_rated_email(place: &Place, rating: &Rating) -> EmailContent {
+    let subject = format!("Kvm - neue Bewertung: {}", place.title);
+    let body = format!(
+        "Hallo,\n
+folgender Eintrag auf der Karte von morgen, den du beobachtest, wurde bewertet:\n
+{title}
+https://kartevonmorgen.org/#/?entry={id}\n
+    {rating_title}
+    {context}: {value}\n
+euphorische Grüße,\n
+das Karte von morgen-Team",
+        title = &place.title,
+        id = &place.id,
+        rating_title = &rating.title,
+        context = rating_context_name(rating.context),
+        value = i8::from(rating.value),
+    );
+    EmailContent { subject, body }
+}
+
+/// Lists at most `max_places` of the pending places.
+pub fn review_digest_email(places: &[Place], max_places: usize) -> EmailContent {
+    let subject = format!(
+        "Kvm - {} Einträge warten auf deine Überprüfung",
+        places.len()
+    );
+    let mut list: Vec<_> = places
+        .iter()
+        .take(max_places)
+        .map(|place| {
+            format!(
+                "{title}\nhttps://kartevonmorgen.org/#/?entry={id}",
+                title = place.title,
+                id = place.id
+            )
+        })
+        .collect();
+    if places.len() > max_places {
+        list.push(format!("... und {} weitere", places.len() - max_places));
+    }
+    let body = format!(
+        "Hallo,\n
+in deinen abonnierten Gebieten auf der Karte von morgen wurden folgende Einträge noch nicht überprüft:\n
+{list}\n
+Bitte bestätige oder korrigiere sie, damit neue Einträge nicht unbeachtet bleiben.\n
+euphorische Grüße,\n
+das Karte von morgen-Team",
+        list = list.join("\n\n"),
+    );
+    EmailContent { subject, body }
+}
+
+/// Lists at most `max_places` of the places with
+/// proposed changes from OpenStreetMap.
+pub fn osm_node_proposals_email(places: &[Place], max_places: usize) -> EmailContent {
+    let subject = format!(
+        "Kvm - {} Einträge wurden in OpenStreetMap verändert",
+        places.len()
+    );
+    let mut list: Vec<_> = places
+        .iter()
+        .take(max_places)
+        .map(|place| {
+            format!(
+                "{title}\nhttps://kartevonmorgen.org/#/?entry={id}",
+                title = place.title,
+                id = place.id
+            )
+        })
+        .collect();
+    if places.len() > max_places {
+        list.push(format!("... und {} weitere", places.len() - max_places));
+    }
+    let body = format!(
+        "Hallo,\n
+folgende aus OpenStreetMap importierte Einträge wurden dort verschoben, umbenannt oder haben eine neue Adresse bekommen:\n
+{list}\n
+Die Änderungen werden erst übernommen, wenn du sie bestätigst. Abgelehnte Änderungen werden nicht erneut vorgeschlagen.\n
+euphorische Grüße,\n
+das Karte von morgen-Team",
+        list = list.join("\n\n"),
+    );
+    EmailContent { subject, body }
+}
+
+/// Summarizes the collected changes, each changed
+/// entry is listed only once.
+pub fn notification_digest_email(
+    digest: NotificationDigest,
+    notifications: &[PendingNotification],
+) -> EmailContent {
+    let mut changes: Vec<(NotificationChange, &Id, &str)> = vec![];
+    for n in notifications {
+        if let Some(c) = changes.iter_mut().find(|(_, id, _)| **id == n.entity_id) {
+            // Keep the initial kind of change, but show the current title
+            c.2 = &n.title;
+        } else {
+            changes.push((n.change, &n.entity_id, &n.title));
+        }
+    }
+    let (subject_prefix, period) = match digest {
+        NotificationDigest::Daily => ("Tägliche", "am letzten Tag"),
+        NotificationDigest::Weekly => ("Wöchentliche", "in der letzten Woche"),
+    };
+    let subject = format!(
+        "Kvm - {} Zusammenfassung: {} Änderungen",
+        subject_prefix,
+        changes.len()
+    );
+    let list: Vec<_> = changes
+        .iter()
+        .map(|(change, id, title)| {
+            let label = match change {
+                NotificationChange::PlaceAdded => "Neuer Eintrag",
+                NotificationChange::PlaceUpdated => "Eintrag verändert",
+                NotificationChange::EventCreated => "Neue Veranstaltung",
+                NotificationChange::EventUpdated => "Veranstaltung verändert",
+            };
+            format!(
+                "{label}: {title}\nhttps://kartevonmorgen.org/#/?entry={id}",
+                label = label,
+                title = title,
+                id = id
+            )
+        })
+        .collect();
+    let body = format!(
+        "Hallo,\n
+in deinen abonnierten Gebieten auf der Karte von morgen hat sich {period} folgendes geändert:\n
+{list}\n
+euphorische Grüße,\n
+das Karte von morgen-Team\n
+{outro_text}",
+        period = period,
+        list = list.join("\n\n"),
+        outro_text = OUTRO_HINT,
+    );
+    EmailContent { subject, body }
+}
+
+pub fn place_confirmation_request_email(place: &Place, url: &str) -> EmailContent {
+    let subject = format!("Kvm - Ist dein Eintrag noch aktuell? {}", place.title);
+    let body = format!(
+        "Hallo,\n
+folgender Eintrag auf der Karte von morgen wurde schon länger weder aktualisiert noch bestätigt:\n
+{title}
+https://kartevonmorgen.org/#/?entry={id}\n
+Bitte bestätige uns über diesen Link, dass der Eintrag noch aktuell ist:\n
+{url}\n
+Falls sich etwas geändert hat, kannst du den Eintrag auch direkt bearbeiten.\n
+euphorische Grüße,\n
+das Karte von morgen-Team",
+        title = &place.title,
+        id = &place.id,
+        url = url,
+    );
+    EmailContent { subject, body }
+}
+
 fn place_email(place: &Place, category_names: &[String], intro_sentence: &str) -> String {
     let category = if !category_names.is_empty() {
         category_names[0].clone()
@@ -251,6 +529,7 @@ mod tests {
                     state: Some("<state>".into()),
                 }),
             },
+            address_auto_filled: false,
             contact: Some(Contact {
                 name: Some("<name>".into()),
                 email: Some("<email>".into()),
@@ -294,6 +573,12 @@ mod tests {
             homepage: Some("https://kartevonmorgen.org".parse().unwrap()),
             image_url: None,
             image_link_url: None,
+            time_zone: None,
+            recurrence: None,
+            address_auto_filled: false,
+            publish_at: None,
+            created_at: None,
+            updated_at: None,
             tags: vec!["<tag1>".into(), "<tag2>".into()],
         }
     }
@@ -337,6 +622,148 @@ mod tests {
         print_email(&email);
     }
 
+    #[test]
+    fn print_place_moderated_tags_changed_email() {
+        let place = new_place();
+        let email = place_moderated_tags_changed_email(
+            &place,
+            &["<tag1>".into()],
+            &["<tag3>".into()],
+            None,
+        );
+        assert!(email.body.contains(&format!("/#/?entry={}", place.id)));
+        assert!(email.body.contains(&place.title));
+        assert!(email.body.contains("<tag1>"));
+        assert!(email.body.contains("<tag3>"));
+        assert!(email.body.contains("das Karte von morgen-Team"));
+        print_email(&email);
+
+        let email = place_moderated_tags_changed_email(
+            &place,
+            &["<tag1>".into()],
+            &[],
+            Some("Viele Grüße\nvom Bioladen"),
+        );
+        assert!(email.body.ends_with("Viele Grüße\nvom Bioladen"));
+        assert!(!email.body.contains("Karte von morgen-Team"));
+    }
+
+    #[test]
+    fn print_moderated_tags_summary_email() {
+        let notification = |id: &str, title: &str, added_tags: &[&str], removed_tags: &[&str]| {
+            PendingModeratedTagsNotification {
+                id: Id::new(),
+                org_id: "<org>".into(),
+                place_id: id.into(),
+                place_title: title.into(),
+                added_tags: added_tags.iter().map(|t| t.to_string()).collect(),
+                removed_tags: removed_tags.iter().map(|t| t.to_string()).collect(),
+                created_at: Timestamp::now(),
+            }
+        };
+        let email = moderated_tags_summary_email(
+            &[
+                notification("<id1>", "<title1>", &["<tag1>"], &[]),
+                notification("<id2>", "<title2>", &[], &["<tag2>"]),
+                notification("<id1>", "<new title1>", &["<tag3>"], &["<tag1>"]),
+            ],
+            None,
+        );
+        assert!(email.subject.contains("2 Einträge"));
+        assert!(email.body.contains("/#/?entry=<id1>"));
+        assert!(email.body.contains("/#/?entry=<id2>"));
+        assert!(email.body.contains("<new title1>"));
+        assert!(!email.body.contains("<tag1>"));
+        assert!(email.body.contains("<tag2>"));
+        assert!(email.body.contains("<tag3>"));
+        assert!(email.body.contains("das Karte von morgen-Team"));
+        print_email(&email);
+    }
+
+    #[test]
+    fn print_subscription_consent_email() {
+        let url = "https://kartevonmorgen.org/#/?confirm_subscriptions=<token>";
+        let email = subscription_consent_email(url);
+        assert!(email.body.contains(url));
+        print_email(&email);
+    }
+
+    #[test]
+    fn print_place_confirmation_request_email() {
+        let place = new_place();
+        let url = "https://kartevonmorgen.org/#/?confirm_still_valid=<id>&token=<token>";
+        let email = place_confirmation_request_email(&place, url);
+        assert!(email.subject.contains(&place.title));
+        assert!(email.body.contains(place.id.as_str()));
+        assert!(email.body.contains(url));
+        print_email(&email);
+    }
+
+    #[test]
+    fn print_place_rated_email() {
+        let place = new_place();
+        let rating = Rating {
+            id: "<rating>".into(),
+            place_id: place.id.clone(),
+            created_at: Timestamp::now(),
+            archived_at: None,
+            title: "<rating title>".into(),
+            value: RatingValue::new(2),
+            context: RatingContext::Fairness,
+            source: None,
+        };
+        let email = place_rated_email(&place, &rating);
+        assert!(email.subject.contains(&place.title));
+        assert!(email.body.contains(place.id.as_str()));
+        assert!(email.body.contains(&rating.title));
+        assert!(email.body.contains("Fairness: 2"));
+        print_email(&email);
+    }
+
+    #[test]
+    fn print_review_digest_email() {
+        let places = vec![new_place(), new_place(), new_place()];
+        let email = review_digest_email(&places, 2);
+        assert!(email.subject.contains("3 Einträge"));
+        assert!(email.body.contains(places[0].id.as_str()));
+        assert!(email.body.contains("und 1 weitere"));
+        print_email(&email);
+    }
+
+    #[test]
+    fn print_osm_node_proposals_email() {
+        let places = vec![new_place(), new_place(), new_place()];
+        let email = osm_node_proposals_email(&places, 2);
+        assert!(email.subject.contains("3 Einträge"));
+        assert!(email.body.contains(places[0].id.as_str()));
+        assert!(email.body.contains("und 1 weitere"));
+        print_email(&email);
+    }
+
+    #[test]
+    fn print_notification_digest_email() {
+        let notification = |change, entity_id: &str, title: &str| PendingNotification {
+            id: Default::default(),
+            user_email: "subscriber@example.com".into(),
+            digest: NotificationDigest::Weekly,
+            change,
+            entity_id: entity_id.into(),
+            title: title.into(),
+            created_at: Timestamp::now(),
+        };
+        let notifications = vec![
+            notification(NotificationChange::PlaceAdded, "<place-id>", "Old title"),
+            notification(NotificationChange::EventUpdated, "<event-id>", "Event"),
+            notification(NotificationChange::PlaceUpdated, "<place-id>", "New title"),
+        ];
+        let email = notification_digest_email(NotificationDigest::Weekly, &notifications);
+        assert!(email.subject.contains("2 Änderungen"));
+        assert!(email.body.contains("Neuer Eintrag: New title"));
+        assert!(!email.body.contains("Old title"));
+        assert!(email.body.contains("<event-id>"));
+        print_email(&email);
+    }
+
     #[test]
     fn print_event_created_email() {
         let event = new_event();
@@ -1,4 +1,4 @@
-use crate::core::{entities::*, util::time::Timestamp};
+use crate::core::{db::IndexedPlace, entities::*, util::time::Timestamp};
 
 #[derive(Debug, Serialize)]
 pub struct CsvRecord {
@@ -27,11 +27,18 @@ pub struct CsvRecord {
     pub image_url: Option<String>,
     pub image_link_url: Option<String>,
     pub avg_rating: f64,
+    pub ratings_diversity: u32,
+    pub ratings_fairness: u32,
+    pub ratings_humanity: u32,
+    pub ratings_renewable: u32,
+    pub ratings_solidarity: u32,
+    pub ratings_transparency: u32,
+    pub comments: u32,
 }
 
-impl From<(Place, Vec<Category>, AvgRatingValue)> for CsvRecord {
-    fn from(from: (Place, Vec<Category>, AvgRatingValue)) -> Self {
-        let (place, categories, avg_rating) = from;
+impl From<(Place, Vec<Category>, AvgRatingValue, RatingCounts)> for CsvRecord {
+    fn from(from: (Place, Vec<Category>, AvgRatingValue, RatingCounts)) -> Self {
+        let (place, categories, avg_rating, rating_counts) = from;
 
         let Place {
             id,
@@ -84,6 +91,16 @@ impl From<(Place, Vec<Category>, AvgRatingValue)> for CsvRecord {
             (None, None, None)
         };
 
+        let RatingCounts {
+            diversity: ratings_diversity,
+            fairness: ratings_fairness,
+            humanity: ratings_humanity,
+            renewable: ratings_renewable,
+            solidarity: ratings_solidarity,
+            transparency: ratings_transparency,
+            comments,
+        } = rating_counts;
+
         CsvRecord {
             id: id.into(),
             created_at: created_at.into_seconds(),
@@ -110,6 +127,13 @@ impl From<(Place, Vec<Category>, AvgRatingValue)> for CsvRecord {
             categories,
             tags: tags.join(","),
             avg_rating: avg_rating.into(),
+            ratings_diversity,
+            ratings_fairness,
+            ratings_humanity,
+            ratings_renewable,
+            ratings_solidarity,
+            ratings_transparency,
+            comments,
         }
     }
 }
@@ -208,3 +232,49 @@ impl From<Event> for EventRecord {
         }
     }
 }
+
+#[derive(Debug, Serialize)]
+pub struct RatingRecord {
+    pub id: String,
+    pub place_id: String,
+    pub place_title: String,
+    pub created_at: i64,
+    pub title: String,
+    pub context: ofdb_boundary::RatingContext,
+    pub value: i8,
+    pub source: Option<String>,
+    /// The texts of all comments in chronological order
+    pub comments: String,
+}
+
+impl From<(&IndexedPlace, Rating, Vec<Comment>)> for RatingRecord {
+    fn from(from: (&IndexedPlace, Rating, Vec<Comment>)) -> Self {
+        let (place, rating, mut comments) = from;
+        let Rating {
+            id,
+            created_at,
+            title,
+            value,
+            context,
+            source,
+            ..
+        } = rating;
+        comments.sort_by_key(|c| c.created_at);
+        let comments = comments
+            .into_iter()
+            .map(|c| c.text)
+            .collect::<Vec<_>>()
+            .join("\n");
+        Self {
+            id: id.into(),
+            place_id: place.id.clone(),
+            place_title: place.title.clone(),
+            created_at: created_at.into_seconds(),
+            title,
+            context: context.into(),
+            value: value.into(),
+            source,
+            comments,
+        }
+    }
+}
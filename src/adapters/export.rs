@@ -0,0 +1,149 @@
+use super::{csv::CsvRecord, geojson, json};
+use crate::core::entities::*;
+use ofdb_core::rating::Rated;
+use rocket::{
+    http::{ContentType, MediaType, Status},
+    request::Request,
+    response::{self, content::Content, Responder},
+};
+use rocket_contrib::json::Json;
+
+pub struct ExportedPlace {
+    pub place: Place,
+    pub ratings: Vec<Rating>,
+    pub rating_counts: RatingCounts,
+    /// Only revealed to scouts and admins
+    pub status: Option<ReviewStatus>,
+}
+
+impl From<ExportedPlace> for json::Entry {
+    fn from(from: ExportedPlace) -> Self {
+        let ExportedPlace {
+            place,
+            ratings,
+            status,
+            ..
+        } = from;
+        let mut entry = json::entry_from_place_with_ratings(place, ratings);
+        entry.status = status.map(Into::into);
+        entry
+    }
+}
+
+impl From<ExportedPlace> for CsvRecord {
+    fn from(from: ExportedPlace) -> Self {
+        let ExportedPlace {
+            mut place,
+            ratings,
+            rating_counts,
+            ..
+        } = from;
+        let avg_rating = place.avg_ratings(&ratings).total();
+        let (tags, categories) = Category::split_from_tags(place.tags);
+        place.tags = tags;
+        (place, categories, avg_rating, rating_counts).into()
+    }
+}
+
+/// The properties of an exported GeoJSON feature, i.e. the
+/// JSON entry extended by the same rating and comment counts
+/// as exported into CSV.
+#[derive(Debug, Serialize)]
+pub struct PlaceProperties {
+    #[serde(flatten)]
+    pub entry: json::Entry,
+    pub ratings_diversity: u32,
+    pub ratings_fairness: u32,
+    pub ratings_humanity: u32,
+    pub ratings_renewable: u32,
+    pub ratings_solidarity: u32,
+    pub ratings_transparency: u32,
+    pub comments: u32,
+}
+
+impl From<ExportedPlace> for geojson::Feature<PlaceProperties> {
+    fn from(from: ExportedPlace) -> Self {
+        let RatingCounts {
+            diversity,
+            fairness,
+            humanity,
+            renewable,
+            solidarity,
+            transparency,
+            comments,
+        } = from.rating_counts.clone();
+        let entry = json::Entry::from(from);
+        geojson::Feature {
+            id: entry.id.clone(),
+            geometry: geojson::Point::from_lat_lng(entry.lat, entry.lng),
+            properties: PlaceProperties {
+                entry,
+                ratings_diversity: diversity,
+                ratings_fairness: fairness,
+                ratings_humanity: humanity,
+                ratings_renewable: renewable,
+                ratings_solidarity: solidarity,
+                ratings_transparency: transparency,
+                comments,
+            },
+        }
+    }
+}
+
+/// Responds with the format that is preferred by the client,
+/// i.e. CSV, GeoJSON or JSON as fallback.
+pub struct PlacesExport(pub Vec<ExportedPlace>);
+
+fn geo_json() -> MediaType {
+    MediaType::new("application", "geo+json")
+}
+
+fn csv_string(places: Vec<ExportedPlace>) -> Result<String, Box<dyn std::error::Error>> {
+    let mut wtr = csv::Writer::from_writer(vec![]);
+    for place in places {
+        wtr.serialize(CsvRecord::from(place))?;
+    }
+    wtr.flush()?;
+    Ok(String::from_utf8(wtr.into_inner()?)?)
+}
+
+impl<'r> Responder<'r> for PlacesExport {
+    fn respond_to(self, req: &Request) -> response::Result<'r> {
+        let PlacesExport(places) = self;
+        let preferred = req.accept().map(|accept| accept.preferred().media_type());
+        let mut res = match preferred {
+            Some(media_type) if media_type.is_csv() => {
+                let data = csv_string(places).map_err(|err| {
+                    error!("Failed to export places as CSV: {}", err);
+                    Status::InternalServerError
+                })?;
+                Content(ContentType::CSV, data).respond_to(req)?
+            }
+            Some(media_type)
+                if media_type.top() == geo_json().top() && media_type.sub() == geo_json().sub() =>
+            {
+                let collection = geojson::FeatureCollection {
+                    features: places
+                        .into_iter()
+                        .map(geojson::Feature::<PlaceProperties>::from)
+                        .collect(),
+                };
+                let data = serde_json::to_string(&collection).map_err(|err| {
+                    error!("Failed to export places as GeoJSON: {}", err);
+                    Status::InternalServerError
+                })?;
+                Content(ContentType(geo_json()), data).respond_to(req)?
+            }
+            _ => Json(
+                places
+                    .into_iter()
+                    .map(json::Entry::from)
+                    .collect::<Vec<_>>(),
+            )
+            .respond_to(req)?,
+        };
+        // Caches must not mix up the formats
+        res.adjoin_raw_header("Vary", "Accept");
+        Ok(res)
+    }
+}
@@ -0,0 +1,51 @@
+//! A minimal subset of [GeoJSON](https://tools.ietf.org/html/rfc7946)
+//! to export points.
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type")]
+pub struct FeatureCollection<P> {
+    pub features: Vec<Feature<P>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type")]
+pub struct Feature<P> {
+    pub id: String,
+    pub geometry: Point,
+    pub properties: P,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type")]
+pub struct Point {
+    /// Longitude and latitude in this order
+    pub coordinates: [f64; 2],
+}
+
+impl Point {
+    pub fn from_lat_lng(lat: f64, lng: f64) -> Self {
+        Self {
+            coordinates: [lng, lat],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_feature_collection() {
+        let collection = FeatureCollection {
+            features: vec![Feature {
+                id: "foo".into(),
+                geometry: Point::from_lat_lng(48.7, 9.1),
+                properties: (),
+            }],
+        };
+        assert_eq!(
+            r#"{"type":"FeatureCollection","features":[{"type":"Feature","id":"foo","geometry":{"type":"Point","coordinates":[9.1,48.7]},"properties":null}]}"#,
+            serde_json::to_string(&collection).unwrap()
+        );
+    }
+}
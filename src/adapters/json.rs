@@ -1,4 +1,9 @@
-use crate::core::{db::IndexedPlace, entities as e, usecases};
+use crate::core::{
+    db::{self, IndexedPlace},
+    entities as e, usecases,
+    util::geo::Distance,
+};
+use ofdb_core::gateways::geocode;
 
 pub use ofdb_boundary::*;
 
@@ -9,6 +14,65 @@ impl From<Credentials> for usecases::Login {
     }
 }
 
+impl From<(IndexedPlace, Distance)> for NearbyPlace {
+    fn from((place, distance): (IndexedPlace, Distance)) -> Self {
+        let IndexedPlace {
+            id,
+            title,
+            tags,
+            pos,
+            ..
+        } = place;
+        let (tags, categories) = e::Category::split_from_tags(tags);
+        let categories = categories.into_iter().map(|c| c.id.to_string()).collect();
+        Self {
+            id,
+            title,
+            lat: pos.lat().to_deg(),
+            lng: pos.lng().to_deg(),
+            distance: distance.to_meters(),
+            categories,
+            tags,
+        }
+    }
+}
+
+impl From<usecases::SearchSuggestion> for SearchSuggestion {
+    fn from(from: usecases::SearchSuggestion) -> Self {
+        match from {
+            usecases::SearchSuggestion::Place { id, title } => Self {
+                kind: SearchSuggestionKind::Place,
+                text: title,
+                id: Some(id),
+            },
+            usecases::SearchSuggestion::Tag(tag) => Self {
+                kind: SearchSuggestionKind::Tag,
+                text: tag,
+                id: None,
+            },
+        }
+    }
+}
+
+impl From<usecases::SearchFacets> for SearchFacets {
+    fn from(from: usecases::SearchFacets) -> Self {
+        let usecases::SearchFacets { categories, tags } = from;
+        Self {
+            categories: categories
+                .into_iter()
+                .map(|(id, count)| FacetCount {
+                    value: id.into(),
+                    count,
+                })
+                .collect(),
+            tags: tags
+                .into_iter()
+                .map(|e::TagFrequency(tag, count)| FacetCount { value: tag, count })
+                .collect(),
+        }
+    }
+}
+
 impl From<IndexedPlace> for PlaceSearchResult {
     fn from(from: IndexedPlace) -> Self {
         let IndexedPlace {
@@ -19,7 +83,8 @@ impl From<IndexedPlace> for PlaceSearchResult {
             tags,
             pos,
             ratings,
-            ..
+            quality,
+            address: _,
         } = from;
         // The status should never be undefined! It is optional only
         // for technical reasons.
@@ -36,6 +101,7 @@ impl From<IndexedPlace> for PlaceSearchResult {
             renewable,
             solidarity,
             transparency,
+            ..
         } = ratings;
         let total = ratings.total().into();
         let ratings = EntrySearchRatings {
@@ -57,6 +123,35 @@ impl From<IndexedPlace> for PlaceSearchResult {
             categories,
             tags,
             ratings,
+            quality,
+            distance: None,
+            preview: None,
+        }
+    }
+}
+
+impl From<db::HomepagePreview> for HomepagePreview {
+    fn from(from: db::HomepagePreview) -> Self {
+        let db::HomepagePreview {
+            title, image_url, ..
+        } = from;
+        Self { title, image_url }
+    }
+}
+
+impl From<geocode::AddressSuggestion> for AddressSuggestion {
+    fn from(from: geocode::AddressSuggestion) -> Self {
+        let geocode::AddressSuggestion {
+            label,
+            lat,
+            lng,
+            address,
+        } = from;
+        Self {
+            label,
+            lat,
+            lng,
+            address: address.into(),
         }
     }
 }
@@ -127,6 +222,415 @@ impl From<NewPlace> for usecases::NewPlace {
     }
 }
 
+impl From<NewEvent> for usecases::NewEvent {
+    fn from(e: NewEvent) -> Self {
+        let NewEvent {
+            title,
+            description,
+            start,
+            end,
+            lat,
+            lng,
+            street,
+            zip,
+            city,
+            country,
+            state,
+            email,
+            telephone,
+            homepage,
+            tags,
+            created_by,
+            registration,
+            organizer,
+            image_url,
+            image_link_url,
+            time_zone,
+            recurrence,
+            publish_at,
+        } = e;
+        usecases::NewEvent {
+            title,
+            description,
+            start,
+            end,
+            lat,
+            lng,
+            street,
+            zip,
+            city,
+            country,
+            state,
+            email,
+            telephone,
+            homepage,
+            tags,
+            created_by,
+            registration,
+            organizer,
+            image_url,
+            image_link_url,
+            time_zone,
+            recurrence,
+            publish_at,
+        }
+    }
+}
+
+impl From<OsmTagMapping> for usecases::OsmTagMapping {
+    fn from(from: OsmTagMapping) -> Self {
+        let OsmTagMapping { rules } = from;
+        Self {
+            rules: rules
+                .into_iter()
+                .map(|rule| {
+                    let OsmTagMappingRule {
+                        key,
+                        value,
+                        categories,
+                        tags,
+                    } = rule;
+                    usecases::OsmTagMappingRule {
+                        key,
+                        value,
+                        categories,
+                        tags,
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<usecases::TagUsage> for TagUsage {
+    fn from(from: usecases::TagUsage) -> Self {
+        let places = from.places() as u64;
+        let usecases::TagUsage {
+            tag,
+            places_by_status,
+            events,
+            regions,
+            recent_places,
+            recent_events,
+        } = from;
+        Self {
+            tag,
+            places,
+            places_by_status: places_by_status
+                .into_iter()
+                .map(|(status, count)| ReviewStatusCount {
+                    status: status.into(),
+                    count: count as u64,
+                })
+                .collect(),
+            events: events as u64,
+            regions: regions.into_iter().map(Into::into).collect(),
+            recent_places: recent_places.into_iter().map(Into::into).collect(),
+            recent_events: recent_events.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<usecases::TagSuggestion> for TagSuggestion {
+    fn from(from: usecases::TagSuggestion) -> Self {
+        let usecases::TagSuggestion { tag, score } = from;
+        Self { tag, score }
+    }
+}
+
+impl From<usecases::RegionTagUsage> for RegionTagUsage {
+    fn from(from: usecases::RegionTagUsage) -> Self {
+        let usecases::RegionTagUsage {
+            region: usecases::Region { country, state },
+            places,
+            events,
+        } = from;
+        Self {
+            country,
+            state,
+            places: places as u64,
+            events: events as u64,
+        }
+    }
+}
+
+impl From<db::PlaceQualityIssue> for PlaceQualityIssue {
+    fn from(from: db::PlaceQualityIssue) -> Self {
+        use db::PlaceQualityIssue as I;
+        match from {
+            I::MissingAddress => Self::MissingAddress,
+            I::InvalidUrl => Self::InvalidUrl,
+            I::ZeroCoordinates => Self::ZeroCoordinates,
+            I::NoTags => Self::NoTags,
+            I::Unreviewed => Self::Unreviewed,
+        }
+    }
+}
+
+impl From<usecases::RegionQualityIssues> for RegionQualityIssues {
+    fn from(from: usecases::RegionQualityIssues) -> Self {
+        let usecases::RegionQualityIssues {
+            region: usecases::Region { country, state },
+            places,
+        } = from;
+        let places = places
+            .into_iter()
+            .map(|place| {
+                let usecases::PlaceQualityIssues { id, title, issues } = place;
+                PlaceQualityIssues {
+                    id: id.into(),
+                    title,
+                    issues: issues.into_iter().map(Into::into).collect(),
+                }
+            })
+            .collect();
+        Self {
+            country,
+            state,
+            places,
+        }
+    }
+}
+
+impl From<usecases::RecentTagUsage> for RecentTagUsage {
+    fn from(from: usecases::RecentTagUsage) -> Self {
+        let usecases::RecentTagUsage { id, title, at } = from;
+        Self {
+            id: id.into(),
+            title,
+            at: at.into_inner(),
+        }
+    }
+}
+
+impl From<usecases::RenamedTag> for RenamedTag {
+    fn from(from: usecases::RenamedTag) -> Self {
+        let usecases::RenamedTag { places, events } = from;
+        Self {
+            places: places.len() as u64,
+            events: events.len() as u64,
+        }
+    }
+}
+
+impl From<db::MergedUserRecords> for MergedUser {
+    fn from(from: db::MergedUserRecords) -> Self {
+        let db::MergedUserRecords {
+            place_revisions,
+            place_reviews,
+            ratings,
+            comments,
+            events,
+            subscriptions,
+        } = from;
+        Self {
+            place_revisions: place_revisions as u64,
+            place_reviews: place_reviews as u64,
+            ratings: ratings as u64,
+            comments: comments as u64,
+            events: events as u64,
+            subscriptions: subscriptions as u64,
+        }
+    }
+}
+
+impl From<db::ChangedEntity> for ChangedEntity {
+    fn from(from: db::ChangedEntity) -> Self {
+        use db::ChangedEntity as E;
+        match from {
+            E::Place => Self::Place,
+            E::Event => Self::Event,
+            E::Rating => Self::Rating,
+            E::Comment => Self::Comment,
+        }
+    }
+}
+
+impl From<db::ChangeKind> for ChangeKind {
+    fn from(from: db::ChangeKind) -> Self {
+        use db::ChangeKind as K;
+        match from {
+            K::Created => Self::Created,
+            K::Updated => Self::Updated,
+            K::Reviewed => Self::Reviewed,
+            K::Archived => Self::Archived,
+            K::Deleted => Self::Deleted,
+        }
+    }
+}
+
+impl From<db::ChangeRecord> for ChangeRecord {
+    fn from(from: db::ChangeRecord) -> Self {
+        let db::ChangeRecord {
+            entity,
+            id,
+            kind,
+            at,
+            actor_role,
+        } = from;
+        Self {
+            entity: entity.into(),
+            id: id.into(),
+            kind: kind.into(),
+            at: at.into_seconds(),
+            actor_role: actor_role.map(Into::into),
+        }
+    }
+}
+
+impl From<ExternalPlace> for usecases::ExternalPlace {
+    fn from(from: ExternalPlace) -> Self {
+        let ExternalPlace {
+            title,
+            lat,
+            lng,
+            street,
+            zip,
+            city,
+        } = from;
+        Self {
+            title,
+            lat,
+            lng,
+            street,
+            zip,
+            city,
+        }
+    }
+}
+
+impl From<usecases::EnrichedPlace> for EnrichedPlace {
+    fn from(from: usecases::EnrichedPlace) -> Self {
+        let usecases::EnrichedPlace {
+            place_id,
+            address,
+            address_resolved,
+            region: usecases::Region { country, state },
+            suggested_tags,
+        } = from;
+        Self {
+            id: place_id.into(),
+            address: address.map(Into::into),
+            address_resolved,
+            country,
+            state,
+            suggested_tags,
+        }
+    }
+}
+
+impl From<usecases::PlaceMatch> for PlaceMatch {
+    fn from(from: usecases::PlaceMatch) -> Self {
+        let usecases::PlaceMatch {
+            place_id,
+            title,
+            confidence,
+        } = from;
+        Self {
+            id: place_id.into(),
+            title,
+            confidence,
+        }
+    }
+}
+
+impl From<usecases::OsmImportReport> for OsmImportReport {
+    fn from(from: usecases::OsmImportReport) -> Self {
+        let usecases::OsmImportReport {
+            imported,
+            already_imported,
+            duplicates,
+            unmapped,
+            failed,
+            rows,
+        } = from;
+        Self {
+            imported: imported as u64,
+            already_imported: already_imported as u64,
+            duplicates: duplicates as u64,
+            unmapped: unmapped as u64,
+            failed: failed as u64,
+            rows: rows.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<usecases::OsmImportOutcome> for OsmImportOutcome {
+    fn from(from: usecases::OsmImportOutcome) -> Self {
+        use usecases::OsmImportOutcome as O;
+        match from {
+            O::Imported => Self::Imported,
+            O::AlreadyImported => Self::AlreadyImported,
+            O::Duplicate => Self::Duplicate,
+            O::Unmapped => Self::Unmapped,
+            O::Failed => Self::Failed,
+        }
+    }
+}
+
+impl From<usecases::OsmImportRow> for OsmImportRow {
+    fn from(from: usecases::OsmImportRow) -> Self {
+        let usecases::OsmImportRow {
+            osm_node_id,
+            outcome,
+            title,
+            categories,
+            tags,
+            place_ids,
+            error,
+        } = from;
+        Self {
+            osm_node_id,
+            outcome: outcome.into(),
+            title,
+            categories,
+            tags,
+            place_ids: place_ids.into_iter().map(Into::into).collect(),
+            error,
+        }
+    }
+}
+
+impl From<usecases::PlaceStats> for PlaceStats {
+    fn from(from: usecases::PlaceStats) -> Self {
+        let usecases::PlaceStats {
+            rating_counts,
+            monthly_avg_ratings,
+            revision_count,
+            view_count,
+        } = from;
+        let usecases::RatingCounts {
+            diversity,
+            fairness,
+            humanity,
+            renewable,
+            solidarity,
+            transparency,
+        } = rating_counts;
+        Self {
+            ratings: RatingCounts {
+                total: rating_counts.total() as u64,
+                diversity: diversity as u64,
+                fairness: fairness as u64,
+                humanity: humanity as u64,
+                renewable: renewable as u64,
+                solidarity: solidarity as u64,
+                transparency: transparency as u64,
+            },
+            monthly_avg_ratings: monthly_avg_ratings
+                .into_iter()
+                .map(|r| MonthlyAvgRating {
+                    month: format!("{:04}-{:02}", r.year, r.month),
+                    count: r.count as u64,
+                    avg: f64::from(r.avg).into(),
+                })
+                .collect(),
+            revisions: revision_count,
+            views: view_count,
+        }
+    }
+}
+
 impl From<UpdatePlace> for usecases::UpdatePlace {
     fn from(p: UpdatePlace) -> Self {
         let UpdatePlace {
@@ -177,80 +681,3 @@ impl From<UpdatePlace> for usecases::UpdatePlace {
         }
     }
 }
-
-// Entity -> JSON
-
-pub fn entry_from_place_with_ratings(place: e::Place, ratings: Vec<e::Rating>) -> Entry {
-    let e::Place {
-        id,
-        license,
-        revision,
-        created,
-        title,
-        description,
-        location,
-        contact,
-        opening_hours,
-        founded_on,
-        links,
-        tags,
-    } = place;
-
-    let e::Location { pos, address } = location;
-    let lat = pos.lat().to_deg();
-    let lng = pos.lng().to_deg();
-    let e::Address {
-        street,
-        zip,
-        city,
-        country,
-        state,
-    } = address.unwrap_or_default();
-
-    let e::Contact {
-        name: contact_name,
-        email,
-        phone: telephone,
-    } = contact.unwrap_or_default();
-
-    let (homepage_url, image_url, image_link_url, custom_links) = links
-        .map(
-            |e::Links {
-                 homepage,
-                 image,
-                 image_href,
-                 custom,
-             }| (homepage, image, image_href, custom),
-        )
-        .unwrap_or_default();
-
-    let (tags, categories) = e::Category::split_from_tags(tags);
-
-    Entry {
-        id: id.into(),
-        created: created.at.into_seconds(),
-        version: revision.into(),
-        title,
-        description,
-        lat,
-        lng,
-        street,
-        zip,
-        city,
-        country,
-        state,
-        contact_name,
-        email: email.map(Into::into),
-        telephone,
-        homepage: homepage_url.map(Into::into),
-        opening_hours: opening_hours.map(Into::into),
-        founded_on: founded_on.map(Into::into),
-        categories: categories.into_iter().map(|c| c.id.to_string()).collect(),
-        tags,
-        ratings: ratings.into_iter().map(|r| r.id.to_string()).collect(),
-        license: Some(license),
-        image_url: image_url.map(Into::into),
-        image_link_url: image_link_url.map(Into::into),
-        custom_links: custom_links.into_iter().map(Into::into).collect(),
-    }
-}
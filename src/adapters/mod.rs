@@ -1,2 +1,4 @@
 pub mod csv;
+pub mod export;
+pub mod geojson;
 pub mod json;
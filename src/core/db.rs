@@ -18,6 +18,14 @@ pub struct MostPopularTagsParams {
     pub max_count: Option<u64>,
 }
 
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TagFilter {
+    /// Only tags that start with this prefix
+    pub prefix: Option<String>,
+    /// Only tags of at least this many visible places
+    pub min_count: Option<u64>,
+}
+
 #[derive(Clone, Debug)]
 pub struct RecentlyChangedEntriesParams {
     pub since: Option<TimestampMs>,
@@ -25,10 +33,18 @@ pub struct RecentlyChangedEntriesParams {
 }
 
 pub trait PlaceRepo {
-    fn get_place(&self, id: &str) -> Result<(Place, ReviewStatus)>;
-    fn get_places(&self, ids: &[&str]) -> Result<Vec<(Place, ReviewStatus)>>;
+    fn get_place_by_id(&self, id: &str) -> Result<(Place, ReviewStatus)>;
+    // Never loads all places if no ids are given
+    fn get_places_by_ids(&self, ids: &[&str]) -> Result<Vec<(Place, ReviewStatus)>>;
 
     fn all_places(&self) -> Result<Vec<(Place, ReviewStatus)>>;
+    // Ordered by creation, drafts are excluded
+    fn list_places(&self, pagination: &Pagination) -> Result<Vec<(Place, ReviewStatus)>>;
+    // Drafts are only loaded individually or by the user who created them
+    fn drafts_created_by(&self, email: &str) -> Result<Vec<Place>>;
+    // The revisions and review status of all places that
+    // have been current at the given time
+    fn all_places_as_of(&self, as_of: TimestampMs) -> Result<Vec<(Place, ReviewStatus)>>;
     fn count_places(&self) -> Result<usize>;
 
     fn recently_changed_places(
@@ -51,10 +67,19 @@ pub trait PlaceRepo {
     ) -> Result<usize>;
 
     fn create_or_update_place(&self, place: Place) -> Result<()>;
+    // Records the comment for the review of the new revision
+    fn create_or_update_place_with_comment(&self, place: Place, comment: &str) -> Result<()>;
 
     fn get_place_history(&self, id: &str, revision: Option<Revision>) -> Result<PlaceHistory>;
+    // The most recent review that confirmed any revision of the place
+    fn last_confirmed_at(&self, id: &str) -> Result<Option<TimestampMs>>;
 
     fn load_place_revision(&self, id: &str, rev: Revision) -> Result<(Place, ReviewStatus)>;
+
+    // Places that have been imported from OpenStreetMap
+    fn link_place_to_osm_node(&self, place_id: &str, osm_node_id: u64) -> Result<()>;
+    fn find_place_id_by_osm_node(&self, osm_node_id: u64) -> Result<Option<Id>>;
+    fn all_osm_node_links(&self) -> Result<Vec<(Id, u64)>>;
 }
 
 pub trait EventGateway {
@@ -66,6 +91,17 @@ pub trait EventGateway {
     fn get_events_chronologically(&self, ids: &[&str]) -> Result<Vec<Event>>;
 
     fn all_events_chronologically(&self) -> Result<Vec<Event>>;
+    // Events that are published until `published_until` (inclusive) and
+    // optionally have been created or updated since `modified_since` (inclusive)
+    fn list_events_chronologically(
+        &self,
+        published_until: Timestamp,
+        modified_since: Option<Timestamp>,
+        pagination: &Pagination,
+    ) -> Result<Vec<Event>>;
+    // Events that are scheduled to be published after `since`
+    // and until `until` (inclusive)
+    fn events_published_between(&self, since: Timestamp, until: Timestamp) -> Result<Vec<Event>>;
 
     fn count_events(&self) -> Result<usize>;
 
@@ -79,26 +115,96 @@ pub trait EventGateway {
     fn is_event_owned_by_any_organization(&self, id: &str) -> Result<bool>;
 }
 
+// Number of records that referenced an anonymized user
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AnonymizedUserRecords {
+    pub place_revisions: usize,
+    pub place_reviews: usize,
+    pub ratings: usize,
+    pub comments: usize,
+    pub events: usize,
+}
+
+// Number of records that have been reassigned to the surviving user
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MergedUserRecords {
+    pub place_revisions: usize,
+    pub place_reviews: usize,
+    pub ratings: usize,
+    pub comments: usize,
+    pub events: usize,
+    pub subscriptions: usize,
+}
+
 pub trait UserGateway {
     fn create_user(&self, user: &User) -> Result<()>;
     fn update_user(&self, user: &User) -> Result<()>;
-    fn delete_user_by_email(&self, email: &str) -> Result<()>;
+
+    // Detach all authored records from the user before
+    // deleting the user together with all personal data.
+    fn anonymize_user_by_email(&self, email: &str) -> Result<AnonymizedUserRecords>;
+
+    // Reassign all authored records, subscriptions and tokens of
+    // the merged user to the surviving user. The merged user itself
+    // is not deleted.
+    fn merge_users_by_email(
+        &self,
+        merged_email: &str,
+        surviving_email: &str,
+    ) -> Result<MergedUserRecords>;
 
     fn all_users(&self) -> Result<Vec<User>>;
     fn count_users(&self) -> Result<usize>;
 
     fn get_user_by_email(&self, email: &str) -> Result<User>;
     fn try_get_user_by_email(&self, email: &str) -> Result<Option<User>>;
+
+    fn wants_announcements(&self, email: &str) -> Result<bool>;
+    fn set_wants_announcements(&self, email: &str, wants_announcements: bool) -> Result<()>;
+    // All users with a confirmed e-mail address who want announcements
+    fn announcement_recipients(&self) -> Result<Vec<Email>>;
+
+    // Unknown for users who registered before it has been recorded
+    fn user_created_at(&self, email: &str) -> Result<Option<Timestamp>>;
+
+    fn user_deactivated_at(&self, email: &str) -> Result<Option<Timestamp>>;
+    // Deactivated users are reactivated by `None`
+    fn set_user_deactivated_at(&self, email: &str, deactivated_at: Option<Timestamp>)
+        -> Result<()>;
+    fn users_deactivated_before(&self, deactivated_before: Timestamp) -> Result<Vec<String>>;
 }
 
 pub trait OrganizationRepo {
     fn create_org(&mut self, _: Organization) -> Result<()>;
+    fn get_org_by_id(&self, id: &Id) -> Result<Organization>;
     fn get_org_by_api_token(&self, token: &str) -> Result<Organization>;
+    // Invalidates the previous legacy API token
+    fn replace_org_api_token(&mut self, org_id: &Id, api_token: &str) -> Result<()>;
     fn map_tag_to_clearance_org_id(&self, tag: &str) -> Result<Option<Id>>;
     fn get_moderated_tags_by_org(
         &self,
         excluded_org_id: Option<&Id>,
     ) -> Result<Vec<(Id, ModeratedTag)>>;
+    // Overrides the permissions of a moderated tag
+    fn set_tag_moderation_policy(
+        &self,
+        org_id: &Id,
+        tag: &str,
+        policy: TagModerationPolicy,
+    ) -> Result<()>;
+    // `None` if the individual permissions of the tag apply
+    fn get_tag_moderation_policy(
+        &self,
+        org_id: &Id,
+        tag: &str,
+    ) -> Result<Option<TagModerationPolicy>>;
+    // A tag may be owned by multiple organizations
+    fn add_moderated_tag(&self, org_id: &Id, tag: &ModeratedTag) -> Result<()>;
+    // Also removes the moderation policy of the tag
+    fn remove_moderated_tag(&self, org_id: &Id, tag: &str) -> Result<()>;
+    // The individual quotas of the organization if any
+    fn get_org_daily_quotas(&self, org_id: &Id) -> Result<DailyQuotas>;
+    fn set_org_daily_quotas(&self, org_id: &Id, quotas: &DailyQuotas) -> Result<()>;
 }
 
 pub trait PlaceClearanceRepo {
@@ -124,6 +230,271 @@ pub trait PlaceClearanceRepo {
         clearances: &[ClearanceForPlace],
     ) -> Result<usize>;
     fn cleanup_pending_clearances_for_places(&self, org_id: &Id) -> Result<u64>;
+    // Regardless of the last cleared revision
+    fn remove_pending_clearances_for_places(
+        &self,
+        org_id: &Id,
+        place_ids: &[&str],
+    ) -> Result<usize>;
+}
+
+// Decisions about pending clearances are kept forever
+pub trait ClearanceLogRepo {
+    fn add_clearance_log_entries(&self, entries: &[ClearanceLogEntry]) -> Result<()>;
+    // Ordered by decision time, newest first
+    fn list_clearance_log_entries(
+        &self,
+        org_id: Option<&Id>,
+        place_id: Option<&Id>,
+        pagination: &Pagination,
+    ) -> Result<Vec<ClearanceLogEntry>>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewedEntity {
+    Place,
+    Event,
+}
+
+// Only the total number of views per day is stored
+pub trait ViewCounterRepo {
+    fn increment_view_count(&self, entity: ViewedEntity, id: &str, day: Timestamp) -> Result<()>;
+    fn count_views(&self, entity: ViewedEntity, id: &str) -> Result<u64>;
+    fn count_all_views_since(&self, since: Timestamp) -> Result<u64>;
+}
+
+// Notes are only visible for scouts and admins
+pub trait PlaceNoteRepo {
+    fn create_place_note(&self, note: &PlaceNote) -> Result<()>;
+    // Ordered by creation time
+    fn load_place_notes(&self, place_id: &str) -> Result<Vec<PlaceNote>>;
+}
+
+pub trait PlaceConfirmationRepo {
+    fn create_place_confirmation_request(&self, request: &PlaceConfirmationRequest) -> Result<()>;
+    // Ordered by the time they have been sent
+    fn load_place_confirmation_requests(
+        &self,
+        place_id: &str,
+    ) -> Result<Vec<PlaceConfirmationRequest>>;
+    // Fails if there is no unanswered request with this nonce for the place
+    fn answer_place_confirmation_request(
+        &self,
+        place_id: &str,
+        nonce: &Nonce,
+        answered_at: TimestampMs,
+    ) -> Result<()>;
+    fn flag_unanswered_place_confirmation_requests(
+        &self,
+        sent_before: TimestampMs,
+        flagged_at: TimestampMs,
+    ) -> Result<usize>;
+    // Flagged requests that are still unanswered
+    fn load_flagged_place_confirmation_requests(&self) -> Result<Vec<PlaceConfirmationRequest>>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagCooccurrence {
+    pub word: String,
+    pub tag: String,
+    // Number of places with both the word and the tag
+    pub count: u64,
+    // Number of places with the word
+    pub word_count: u64,
+}
+
+// The statistics are computed offline from confirmed places
+pub trait TagSuggestionRepo {
+    fn replace_tag_cooccurrences(&self, cooccurrences: &[TagCooccurrence]) -> Result<()>;
+    fn load_tag_cooccurrences(&self, words: &[&str]) -> Result<Vec<TagCooccurrence>>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkCheck {
+    pub url: String,
+    // Number of consecutive failed checks
+    pub failures: u32,
+    pub checked_at: Timestamp,
+    pub last_error: Option<String>,
+}
+
+pub trait LinkCheckRepo {
+    // A successful check resets the failures
+    fn record_link_check(
+        &self,
+        url: &str,
+        error: Option<&str>,
+        checked_at: Timestamp,
+    ) -> Result<()>;
+    // Ordered by descending number of failures
+    fn load_broken_links(&self, min_failures: u32) -> Result<Vec<LinkCheck>>;
+    // The subset of the given links that are broken
+    fn filter_broken_links(&self, urls: &[&str], min_failures: u32) -> Result<Vec<String>>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PlaceQualityIssue {
+    // Street or city are missing
+    MissingAddress,
+    // The homepage or image links are not HTTP(S) URLs
+    InvalidUrl,
+    ZeroCoordinates,
+    // Categories are not counted as tags
+    NoTags,
+    // Neither reviewed nor changed for a long time
+    Unreviewed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaceWithQualityIssue {
+    pub id: Id,
+    pub title: String,
+    pub country: Option<String>,
+    pub state: Option<String>,
+    pub issue: PlaceQualityIssue,
+}
+
+pub trait DataQualityRepo {
+    // Checks all visible places with one query per issue and
+    // returns at most `limit_per_issue` places for each issue
+    // ordered by title. Places without any review since
+    // `unreviewed_since` are considered as unreviewed.
+    fn find_places_with_quality_issues(
+        &self,
+        unreviewed_since: TimestampMs,
+        limit_per_issue: u64,
+    ) -> Result<Vec<PlaceWithQualityIssue>>;
+}
+
+// The average ratings of places are aggregated from all
+// unarchived ratings whenever ratings are added or archived.
+pub trait RatingAggregateRepo {
+    // Places without any aggregated ratings are omitted
+    fn load_avg_ratings_of_all_places(&self) -> Result<Vec<(Id, AvgRatings)>>;
+
+    // Recomputes the aggregates of all places from scratch and
+    // returns the number of places
+    fn recompute_rating_aggregates(&self) -> Result<usize>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedEmail {
+    pub id: Id,
+    pub recipient: Email,
+    pub subject: String,
+    pub body: String,
+    pub created_at: Timestamp,
+}
+
+pub trait EmailOutboxRepo {
+    fn enqueue_emails(&self, emails: &[QueuedEmail]) -> Result<()>;
+    // Ordered by creation, oldest first
+    fn load_unsent_emails(&self, limit: usize) -> Result<Vec<QueuedEmail>>;
+    fn mark_email_as_sent(&self, id: &Id, sent_at: Timestamp) -> Result<()>;
+}
+
+pub trait NotificationConsentRepo {
+    // Replaces a pending request for the same e-mail address
+    fn request_notification_consent(
+        &self,
+        email_nonce: &EmailNonce,
+        requested_at: Timestamp,
+    ) -> Result<()>;
+    // Fails if there is no request with this nonce
+    fn confirm_notification_consent(
+        &self,
+        email_nonce: &EmailNonce,
+        confirmed_at: Timestamp,
+    ) -> Result<()>;
+    fn has_notification_consent(&self, email: &str) -> Result<bool>;
+    // The subset of the e-mail addresses that confirmed their consent
+    fn emails_with_notification_consent(&self, emails: &[&str]) -> Result<Vec<String>>;
+}
+
+/// Places or events that are watched by individual users.
+pub trait EntityWatchRepo {
+    // Watching twice has no effect
+    fn watch_entity(&self, email: &str, entity_id: &str, created_at: Timestamp) -> Result<()>;
+    fn unwatch_entity(&self, email: &str, entity_id: &str) -> Result<()>;
+    fn emails_watching_entity(&self, entity_id: &str) -> Result<Vec<String>>;
+    fn entity_ids_watched_by_email(&self, email: &str) -> Result<Vec<String>>;
+}
+
+pub trait PersonalApiTokenRepo {
+    fn create_personal_api_token(&self, token: &PersonalApiToken) -> Result<()>;
+    fn load_personal_api_token(&self, id: &str) -> Result<PersonalApiToken>;
+    /// Ordered by creation time
+    fn personal_api_tokens_of_user(&self, email: &str) -> Result<Vec<PersonalApiToken>>;
+    // Returns NotFound if the user doesn't own a token with this id
+    fn delete_personal_api_token(&self, email: &str, id: &str) -> Result<()>;
+}
+
+pub trait OrganizationApiTokenRepo {
+    fn create_org_api_token(&self, token: &OrganizationApiToken) -> Result<()>;
+    fn load_org_api_token(&self, id: &str) -> Result<OrganizationApiToken>;
+    /// Ordered by creation time
+    fn org_api_tokens(&self, org_id: &Id) -> Result<Vec<OrganizationApiToken>>;
+    fn update_org_api_token_last_used(&self, id: &str, last_used_at: Timestamp) -> Result<()>;
+    // Returns NotFound if the organization doesn't own a token with this id
+    fn delete_org_api_token(&self, org_id: &Id, id: &str) -> Result<()>;
+}
+
+pub trait PendingNotificationRepo {
+    fn add_pending_notifications(&self, notifications: &[PendingNotification]) -> Result<()>;
+    /// Ordered by creation time
+    fn all_pending_notifications(&self) -> Result<Vec<PendingNotification>>;
+    fn delete_pending_notifications(&self, ids: &[&str]) -> Result<usize>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HomepagePreview {
+    pub url: String,
+    pub title: Option<String>,
+    pub image_url: Option<String>,
+    pub fetched_at: Timestamp,
+}
+
+pub trait HomepagePreviewRepo {
+    // Replaces a previous preview of the same URL
+    fn store_homepage_preview(&self, preview: &HomepagePreview) -> Result<()>;
+    fn load_homepage_previews(&self, urls: &[&str]) -> Result<Vec<HomepagePreview>>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangedEntity {
+    Place,
+    Event,
+    Rating,
+    Comment,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    Reviewed,
+    Archived,
+    Deleted,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeRecord {
+    pub entity: ChangedEntity,
+    pub id: Id,
+    pub kind: ChangeKind,
+    pub at: Timestamp,
+    // Unknown if the change has not been caused by a registered user
+    pub actor_role: Option<Role>,
+}
+
+pub trait ChangeLogRepo {
+    // Changes within [since, until) in chronological order
+    fn load_changes(
+        &self,
+        since: Timestamp,
+        until: Timestamp,
+        limit: u64,
+    ) -> Result<Vec<ChangeRecord>>;
 }
 
 //TODO:
@@ -136,6 +507,46 @@ pub struct Pagination {
     pub limit: Option<u64>,
 }
 
+pub trait PendingModeratedTagsNotificationRepo {
+    fn add_pending_moderated_tags_notifications(
+        &self,
+        notifications: &[PendingModeratedTagsNotification],
+    ) -> Result<()>;
+    /// Ordered by creation time
+    fn all_pending_moderated_tags_notifications(
+        &self,
+    ) -> Result<Vec<PendingModeratedTagsNotification>>;
+    fn delete_pending_moderated_tags_notifications(&self, ids: &[&str]) -> Result<usize>;
+}
+
+pub trait PendingRatingRepo {
+    fn add_pending_rating(&self, pending_rating: &PendingRating) -> Result<()>;
+    /// Ordered by creation time
+    fn list_pending_ratings(&self, pagination: &Pagination) -> Result<Vec<PendingRating>>;
+    fn load_pending_ratings(&self, ids: &[&str]) -> Result<Vec<PendingRating>>;
+    fn delete_pending_ratings(&self, ids: &[&str]) -> Result<usize>;
+}
+
+pub trait OsmNodeProposalRepo {
+    fn add_osm_node_proposal(&self, proposal: &OsmNodeProposal) -> Result<()>;
+    fn get_osm_node_proposal(&self, id: &str) -> Result<OsmNodeProposal>;
+    // All proposals regardless of their status, oldest first
+    fn load_osm_node_proposals_of_place(&self, place_id: &str) -> Result<Vec<OsmNodeProposal>>;
+    /// Ordered by creation time
+    fn list_pending_osm_node_proposals(
+        &self,
+        pagination: &Pagination,
+    ) -> Result<Vec<OsmNodeProposal>>;
+    // Fails if the proposal is not pending anymore
+    fn review_osm_node_proposal(
+        &self,
+        id: &str,
+        status: OsmNodeProposalStatus,
+        reviewed: &Activity,
+    ) -> Result<()>;
+    fn delete_pending_osm_node_proposals_of_place(&self, place_id: &str) -> Result<usize>;
+}
+
 pub trait Db:
     PlaceRepo
     + UserGateway
@@ -145,6 +556,25 @@ pub trait Db:
     + RatingRepository
     + UserTokenRepo
     + PlaceClearanceRepo
+    + ClearanceLogRepo
+    + ViewCounterRepo
+    + PlaceNoteRepo
+    + PlaceConfirmationRepo
+    + TagSuggestionRepo
+    + LinkCheckRepo
+    + DataQualityRepo
+    + RatingAggregateRepo
+    + HomepagePreviewRepo
+    + EmailOutboxRepo
+    + NotificationConsentRepo
+    + ChangeLogRepo
+    + EntityWatchRepo
+    + PendingNotificationRepo
+    + PendingModeratedTagsNotificationRepo
+    + PendingRatingRepo
+    + OsmNodeProposalRepo
+    + PersonalApiTokenRepo
+    + OrganizationApiTokenRepo
 {
     fn create_tag_if_it_does_not_exist(&self, _: &Tag) -> Result<()>;
 
@@ -155,13 +585,40 @@ pub trait Db:
             Category::new_event(),
         ])
     }
+    fn get_category_by_id(&self, id: &str) -> Result<Category> {
+        self.all_categories()?
+            .into_iter()
+            .find(|c| c.id.as_str() == id)
+            .ok_or(RepoError::NotFound)
+    }
+    fn get_categories_by_ids(&self, ids: &[&str]) -> Result<Vec<Category>> {
+        Ok(self
+            .all_categories()?
+            .into_iter()
+            .filter(|c| ids.iter().any(|id| c.id.as_str() == *id))
+            .collect())
+    }
     fn all_tags(&self) -> Result<Vec<Tag>>;
+    /// Ordered by name
+    fn list_tags(&self, filter: &TagFilter, pagination: &Pagination) -> Result<Vec<Tag>>;
     fn count_tags(&self) -> Result<usize>;
+    fn count_filtered_tags(&self, filter: &TagFilter) -> Result<usize>;
 
     fn create_bbox_subscription(&self, _: &BboxSubscription) -> Result<()>;
     fn all_bbox_subscriptions(&self) -> Result<Vec<BboxSubscription>>;
     fn all_bbox_subscriptions_by_email(&self, user_email: &str) -> Result<Vec<BboxSubscription>>;
     fn delete_bbox_subscriptions_by_email(&self, user_email: &str) -> Result<()>;
+
+    fn create_org_subscription(&self, _: &OrganizationSubscription) -> Result<()>;
+    fn all_org_subscriptions_by_org_ids(
+        &self,
+        org_ids: &[&Id],
+    ) -> Result<Vec<OrganizationSubscription>>;
+    fn all_org_subscriptions_by_email(
+        &self,
+        user_email: &str,
+    ) -> Result<Vec<OrganizationSubscription>>;
+    fn delete_org_subscription(&self, user_email: &str, org_id: &Id) -> Result<()>;
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -184,12 +641,33 @@ pub struct IndexQuery<'a, 'b> {
     pub categories: Vec<&'a str>,
     pub ids: Vec<&'b str>,
     pub hash_tags: Vec<String>,
+    // Entries with any of these categories or tags are excluded
+    pub excluded_categories: Vec<&'a str>,
+    pub excluded_hash_tags: Vec<String>,
+    // Places with a lower total rating or fewer ratings are excluded
+    pub min_avg_rating: Option<AvgRatingValue>,
+    pub min_rating_count: Option<u32>,
+    // Places that have not been confirmed since then are excluded
+    pub confirmed_since: Option<TimestampMs>,
+    // Recently confirmed places are ranked higher
+    pub boost_freshness: bool,
+    // Places are ordered by their quality score instead of their relevance
+    pub order_by_quality: bool,
     pub text_tags: Vec<String>,
     pub text: Option<String>,
+    // Maximum number of typos per word of the text, i.e. the edit
+    // distance. Short words are always matched exactly.
+    pub fuzziness: Option<u8>,
+    // Exact (case-insensitive) match of the address fields
+    pub country: Option<String>,
+    pub region: Option<String>,
     pub ts_min_lb: Option<Timestamp>, // lower bound (inclusive)
     pub ts_min_ub: Option<Timestamp>, // upper bound (inclusive)
     pub ts_max_lb: Option<Timestamp>, // lower bound (inclusive)
     pub ts_max_ub: Option<Timestamp>, // upper bound (inclusive)
+    // Exact (case-insensitive) match of the organizer of events
+    pub organizer: Option<String>,
+    pub registration: Option<RegistrationType>,
 }
 
 pub trait Indexer {
@@ -218,10 +696,29 @@ pub struct IndexedPlace {
     pub description: String,
     pub tags: Vec<String>,
     pub ratings: AvgRatings,
+    /// Completeness of the place at index time (0 - 100)
+    pub quality: u8,
+    /// The country and state are not stored in the index
+    pub address: Option<Address>,
 }
 
 pub trait PlaceIndex {
     fn query_places(&self, query: &IndexQuery, limit: usize) -> Fallible<Vec<IndexedPlace>>;
+
+    /// The number of matching places per tag (including
+    /// categories) ordered by descending count.
+    fn count_place_tags(&self, query: &IndexQuery) -> Fallible<Vec<TagFrequency>>;
+
+    /// Places with a title that contains all words of the text
+    /// with the last word as a prefix or with a tag that starts
+    /// with the text. Small typos are tolerated. The results
+    /// are ordered by their rating.
+    fn query_place_suggestions(
+        &self,
+        query: &IndexQuery,
+        text: &str,
+        limit: usize,
+    ) -> Fallible<Vec<IndexedPlace>>;
 }
 
 pub trait PlaceIndexer: IdIndexer + PlaceIndex {
@@ -229,6 +726,7 @@ pub trait PlaceIndexer: IdIndexer + PlaceIndex {
         &self,
         place: &Place,
         status: ReviewStatus,
+        last_confirmed_at: Option<TimestampMs>,
         ratings: &AvgRatings,
     ) -> Fallible<()>;
 }
@@ -1,7 +1,8 @@
 pub use ofdb_entities::{
     activity::*, address::*, category::*, clearance::*, comment::*, contact::*, email::*, event::*,
     geo::*, id::*, links::*, location::*, nonce::*, organization::*, password::*, place::*,
-    rating::*, review::*, revision::*, subscription::*, tag::*, time::*, url::Url, user::*,
+    rating::*, recurrence::*, review::*, revision::*, subscription::*, tag::*, time::*, url::Url,
+    user::*,
 };
 
 #[cfg(test)]
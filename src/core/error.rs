@@ -5,6 +5,8 @@ use thiserror::Error;
 pub enum ParameterError {
     #[error("The title is invalid")]
     Title,
+    #[error("The tag is invalid")]
+    Tag,
     #[error("Bounding box is invalid")]
     Bbox,
     #[error("Unsupported license")]
@@ -21,6 +23,8 @@ pub enum ParameterError {
     RegistrationType,
     #[error("The user already exists")]
     UserExists,
+    #[error("Registration with this e-mail domain is not allowed")]
+    EmailDomainNotAllowed,
     #[error("The user does not exist")]
     UserDoesNotExist,
     #[error("Invalid password")]
@@ -35,6 +39,12 @@ pub enum ParameterError {
     Credentials,
     #[error("Email not confirmed")]
     EmailNotConfirmed,
+    #[error("The user has been deactivated")]
+    UserDeactivated,
+    #[error("The user has not been deactivated")]
+    UserNotDeactivated,
+    #[error("A user cannot be merged with itself")]
+    MergeSameUser,
     #[error("This is not allowed")]
     Forbidden,
     #[error("This is not allowed without auth")]
@@ -45,22 +55,52 @@ pub enum ParameterError {
     EndDateBeforeStart,
     #[error("The tag is owned by an organization")]
     ModeratedTag,
+    #[error("The tag is already owned by the organization")]
+    TagAlreadyOwned,
     #[error("Missing the email of the creator")]
     CreatorEmail,
     #[error("Invalid opening hours")]
     InvalidOpeningHours,
     #[error("Invalid position")]
     InvalidPosition,
+    #[error("The position 0/0 needs to be confirmed")]
+    UnconfirmedPosition,
     #[error("Invalid limit")]
     InvalidLimit,
+    #[error("Invalid offset")]
+    InvalidOffset,
+    #[error("Invalid radius")]
+    InvalidRadius,
+    #[error("Invalid search area")]
+    InvalidSearchArea,
+    #[error("Invalid sort order")]
+    InvalidSortOrder,
+    #[error("Invalid time zone")]
+    InvalidTimeZone,
+    #[error("Invalid recurrence rule")]
+    InvalidRecurrence,
+    #[error("Invalid notification digest")]
+    NotificationDigest,
+    #[error("Invalid archive reason")]
+    ArchiveReason,
+    #[error("Unknown categories: {}", .0.join(", "))]
+    Category(Vec<String>),
     #[error("Token invalid")]
     TokenInvalid,
     #[error("Token expired")]
     TokenExpired,
+    #[error("The label of the API token is too long")]
+    ApiTokenLabel,
+    #[error("The API token would already be expired")]
+    ApiTokenExpiry,
     #[error("Invalid nonce")]
     InvalidNonce,
     #[error("Missing id list")]
     EmptyIdList,
+    #[error("Empty subject or body of the announcement")]
+    EmptyAnnouncement,
+    #[error("The daily upload quota has been exceeded")]
+    UploadQuotaExceeded,
 }
 
 #[derive(Debug, Error)]
@@ -110,6 +150,24 @@ impl From<ofdb_entities::event::RegistrationTypeParseError> for Error {
     }
 }
 
+impl From<ofdb_entities::recurrence::RecurrenceParseError> for Error {
+    fn from(_: ofdb_entities::recurrence::RecurrenceParseError) -> Self {
+        Error::Parameter(ParameterError::InvalidRecurrence)
+    }
+}
+
+impl From<ofdb_entities::subscription::NotificationDigestParseError> for Error {
+    fn from(_: ofdb_entities::subscription::NotificationDigestParseError) -> Self {
+        Error::Parameter(ParameterError::NotificationDigest)
+    }
+}
+
+impl From<ofdb_entities::rating::ArchiveReasonParseError> for Error {
+    fn from(_: ofdb_entities::rating::ArchiveReasonParseError) -> Self {
+        Error::Parameter(ParameterError::ArchiveReason)
+    }
+}
+
 impl From<ofdb_entities::nonce::EmailNonceDecodingError> for Error {
     fn from(_: ofdb_entities::nonce::EmailNonceDecodingError) -> Self {
         Error::Parameter(ParameterError::InvalidNonce)
@@ -30,13 +30,28 @@ pub trait CommentRepository {
         Ok(results)
     }
 
-    fn archive_comments(&self, ids: &[&str], activity: &Activity) -> Result<usize>;
+    fn archive_comments(
+        &self,
+        ids: &[&str],
+        activity: &Activity,
+        reason: ArchiveReason,
+    ) -> Result<usize>;
     fn archive_comments_of_ratings(
         &self,
         rating_ids: &[&str],
         activity: &Activity,
+        reason: ArchiveReason,
     ) -> Result<usize>;
     fn archive_comments_of_places(&self, place_ids: &[&str], activity: &Activity) -> Result<usize>;
+
+    // Only archived comments of both archived and unarchived ratings.
+    // The reason is unknown if the comments have been archived
+    // together with their place.
+    fn load_archived_comments_of_place(
+        &self,
+        place_id: &str,
+    ) -> Result<Vec<(Comment, Option<ArchiveReason>)>>;
+    fn count_archived_comments_by_reason(&self) -> Result<Vec<(Option<ArchiveReason>, usize)>>;
 }
 
 pub trait RatingRepository {
@@ -47,10 +62,29 @@ pub trait RatingRepository {
     fn load_ratings(&self, ids: &[&str]) -> Result<Vec<Rating>>;
     fn load_ratings_of_place(&self, place_id: &str) -> Result<Vec<Rating>>;
 
-    fn archive_ratings(&self, ids: &[&str], activity: &Activity) -> Result<usize>;
+    fn archive_ratings(
+        &self,
+        ids: &[&str],
+        activity: &Activity,
+        reason: ArchiveReason,
+    ) -> Result<usize>;
     fn archive_ratings_of_places(&self, place_ids: &[&str], activity: &Activity) -> Result<usize>;
 
+    // Only archived ratings, see also load_archived_comments_of_place()
+    fn load_archived_ratings_of_place(
+        &self,
+        place_id: &str,
+    ) -> Result<Vec<(Rating, Option<ArchiveReason>)>>;
+    fn count_archived_ratings_by_reason(&self) -> Result<Vec<(Option<ArchiveReason>, usize)>>;
+
     fn load_place_ids_of_ratings(&self, ids: &[&str]) -> Result<Vec<String>>;
+
+    // Only unarchived ratings and comments, aggregated per place.
+    // Places without any ratings are omitted.
+    fn count_ratings_and_comments_of_places(
+        &self,
+        place_ids: &[&str],
+    ) -> Result<Vec<(String, RatingCounts)>>;
 }
 
 pub trait UserTokenRepo {
@@ -62,3 +96,6 @@ pub trait UserTokenRepo {
 
     fn get_user_token_by_email(&self, email: &str) -> Result<UserToken>;
 }
+
+#[cfg(test)]
+pub mod conformance;
@@ -0,0 +1,319 @@
+//! Behavior that every database backend must share.
+//!
+//! Each backend invokes these functions from its own tests. The
+//! entities are generated by [`Fixtures`] with all optional sections
+//! populated, because not every backend is able to distinguish a
+//! missing address, contact or links section from an empty one.
+
+use super::{CommentRepository, RatingRepository};
+use crate::core::{db::*, entities::*, error::RepoError};
+use ofdb_core::rating::Rated;
+use ofdb_entities::fixtures::Fixtures;
+use std::collections::BTreeMap;
+
+const SEED: u64 = 0x0fdb;
+
+const COUNT: usize = 10;
+
+// The order of tags is not specified
+fn sorted_tags(mut tags: Vec<String>) -> Vec<String> {
+    tags.sort_unstable();
+    tags
+}
+
+fn place_with_sorted_tags(place: Place) -> Place {
+    Place {
+        tags: sorted_tags(place.tags),
+        ..place
+    }
+}
+
+fn event_with_sorted_tags(event: Event) -> Event {
+    Event {
+        tags: sorted_tags(event.tags),
+        ..event
+    }
+}
+
+// The time stamps are maintained by the repository
+fn event_without_time_stamps(event: Event) -> Event {
+    assert!(event.created_at.is_some());
+    assert!(event.updated_at >= event.created_at);
+    Event {
+        created_at: None,
+        updated_at: None,
+        ..event
+    }
+}
+
+fn is_not_found<T>(res: Result<T, RepoError>) -> bool {
+    matches!(res, Err(RepoError::NotFound))
+}
+
+pub fn place_repo<R: PlaceRepo>(repo: &R) {
+    let mut fixtures = Fixtures::new(SEED);
+    let places: Vec<_> = (0..COUNT).map(|_| fixtures.place()).collect();
+    for place in &places {
+        repo.create_or_update_place(place.clone()).unwrap();
+    }
+    assert_eq!(COUNT, repo.count_places().unwrap());
+    for place in &places {
+        let (loaded, status) = repo.get_place_by_id(place.id.as_str()).unwrap();
+        assert_eq!(ReviewStatus::Created, status);
+        assert_eq!(*place, place_with_sorted_tags(loaded));
+    }
+    assert!(is_not_found(repo.get_place_by_id(fixtures.id().as_str())));
+
+    let mut ids: Vec<_> = places.iter().step_by(3).map(|p| p.id.as_str()).collect();
+    ids.sort_unstable();
+    let mut loaded: Vec<_> = repo
+        .get_places_by_ids(&ids)
+        .unwrap()
+        .into_iter()
+        .map(|(p, _)| p.id)
+        .collect();
+    loaded.sort_unstable();
+    assert_eq!(ids, loaded.iter().map(Id::as_str).collect::<Vec<_>>());
+
+    // A new revision replaces the current revision
+    let mut place = places[0].clone();
+    place.revision = place.revision.next();
+    place.title = "Updated".into();
+    place.tags = fixtures.tags();
+    place.address_auto_filled = true;
+    repo.create_or_update_place(place.clone()).unwrap();
+    let (loaded, _) = repo.get_place_by_id(place.id.as_str()).unwrap();
+    assert_eq!(place, place_with_sorted_tags(loaded));
+    assert_eq!(COUNT, repo.count_places().unwrap());
+}
+
+pub fn event_gateway<R: EventGateway>(repo: &R) {
+    let mut fixtures = Fixtures::new(SEED);
+    let events: Vec<_> = (0..COUNT).map(|_| fixtures.event()).collect();
+    for event in &events {
+        repo.create_event(event.clone()).unwrap();
+    }
+    assert_eq!(COUNT, repo.count_events().unwrap());
+    for event in &events {
+        let loaded = repo.get_event(event.id.as_str()).unwrap();
+        assert_eq!(
+            *event,
+            event_with_sorted_tags(event_without_time_stamps(loaded))
+        );
+    }
+    assert!(is_not_found(repo.get_event(fixtures.id().as_str())));
+
+    let chronologically = repo.all_events_chronologically().unwrap();
+    assert_eq!(COUNT, chronologically.len());
+    assert!(chronologically.windows(2).all(|w| w[0].start <= w[1].start));
+
+    let mut event = events[0].clone();
+    let created_at = repo.get_event(event.id.as_str()).unwrap().created_at;
+    event.title = "Updated".into();
+    event.tags = fixtures.tags();
+    event.address_auto_filled = true;
+    repo.update_event(&event).unwrap();
+    let loaded = repo.get_event(event.id.as_str()).unwrap();
+    assert_eq!(created_at, loaded.created_at);
+    assert_eq!(
+        event,
+        event_with_sorted_tags(event_without_time_stamps(loaded))
+    );
+    assert!(is_not_found(repo.update_event(&fixtures.event())));
+    assert_eq!(COUNT, repo.count_events().unwrap());
+}
+
+pub fn rating_repo<R: PlaceRepo + RatingRepository + CommentRepository>(repo: &R) {
+    let mut fixtures = Fixtures::new(SEED);
+    let places: Vec<_> = (0..2).map(|_| fixtures.place()).collect();
+    for place in &places {
+        repo.create_or_update_place(place.clone()).unwrap();
+    }
+    let ratings: Vec<_> = (0..COUNT)
+        .map(|i| fixtures.rating(&places[i % 2].id))
+        .collect();
+    for rating in &ratings {
+        repo.create_rating(rating.clone()).unwrap();
+    }
+    for rating in &ratings {
+        assert_eq!(*rating, repo.load_rating(rating.id.as_str()).unwrap());
+    }
+    assert!(is_not_found(repo.load_rating(fixtures.id().as_str())));
+
+    let mut expected: Vec<_> = ratings
+        .iter()
+        .filter(|r| r.place_id == places[0].id)
+        .map(|r| r.id.clone())
+        .collect();
+    expected.sort_unstable();
+    let mut loaded: Vec<_> = repo
+        .load_ratings_of_place(places[0].id.as_str())
+        .unwrap()
+        .into_iter()
+        .map(|r| r.id)
+        .collect();
+    loaded.sort_unstable();
+    assert_eq!(expected, loaded);
+
+    for (i, rating) in ratings.iter().take(3).enumerate() {
+        repo.create_comment(Comment {
+            id: fixtures.id(),
+            rating_id: rating.id.clone(),
+            created_at: rating.created_at,
+            archived_at: None,
+            text: format!("comment {}", i),
+        })
+        .unwrap();
+    }
+    let unknown_place_id = fixtures.id();
+    let place_ids = [
+        places[0].id.as_str(),
+        places[1].id.as_str(),
+        unknown_place_id.as_str(),
+    ];
+    let mut counts = repo
+        .count_ratings_and_comments_of_places(&place_ids)
+        .unwrap();
+    counts.sort_unstable_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+    let mut expected: Vec<_> = places
+        .iter()
+        .map(|place| {
+            let mut counts = RatingCounts::default();
+            for (i, rating) in ratings.iter().enumerate() {
+                if rating.place_id == place.id {
+                    counts.add_ratings(rating.context, 1);
+                    if i < 3 {
+                        counts.comments += 1;
+                    }
+                }
+            }
+            (place.id.to_string(), counts)
+        })
+        .collect();
+    expected.sort_unstable_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+    assert_eq!(expected, counts);
+}
+
+pub fn archived_ratings_and_comments<R: PlaceRepo + RatingRepository + CommentRepository>(
+    repo: &R,
+) {
+    let mut fixtures = Fixtures::new(SEED);
+    let places: Vec<_> = (0..2).map(|_| fixtures.place()).collect();
+    for place in &places {
+        repo.create_or_update_place(place.clone()).unwrap();
+    }
+    let ratings: Vec<_> = (0..3).map(|i| fixtures.rating(&places[i % 2].id)).collect();
+    let mut comments = vec![];
+    for rating in &ratings {
+        repo.create_rating(rating.clone()).unwrap();
+        let comment = Comment {
+            id: fixtures.id(),
+            rating_id: rating.id.clone(),
+            created_at: rating.created_at,
+            archived_at: None,
+            text: "comment".into(),
+        };
+        repo.create_comment(comment.clone()).unwrap();
+        comments.push(comment);
+    }
+
+    let activity = Activity::now(None);
+    repo.archive_comments(&[comments[0].id.as_str()], &activity, ArchiveReason::Spam)
+        .unwrap();
+    repo.archive_comments_of_ratings(&[ratings[2].id.as_str()], &activity, ArchiveReason::Abusive)
+        .unwrap();
+    repo.archive_ratings(&[ratings[2].id.as_str()], &activity, ArchiveReason::Abusive)
+        .unwrap();
+    // Without a reason
+    repo.archive_comments_of_places(&[places[1].id.as_str()], &activity)
+        .unwrap();
+    repo.archive_ratings_of_places(&[places[1].id.as_str()], &activity)
+        .unwrap();
+
+    let archived_ratings: Vec<_> = repo
+        .load_archived_ratings_of_place(places[0].id.as_str())
+        .unwrap()
+        .into_iter()
+        .map(|(r, reason)| (r.id, reason))
+        .collect();
+    assert_eq!(
+        vec![(ratings[2].id.clone(), Some(ArchiveReason::Abusive))],
+        archived_ratings
+    );
+    let mut archived_comments: Vec<_> = repo
+        .load_archived_comments_of_place(places[0].id.as_str())
+        .unwrap()
+        .into_iter()
+        .map(|(c, reason)| (c.id, reason))
+        .collect();
+    archived_comments.sort_unstable();
+    let mut expected = vec![
+        (comments[0].id.clone(), Some(ArchiveReason::Spam)),
+        (comments[2].id.clone(), Some(ArchiveReason::Abusive)),
+    ];
+    expected.sort_unstable();
+    assert_eq!(expected, archived_comments);
+
+    let mut rating_counts = repo.count_archived_ratings_by_reason().unwrap();
+    rating_counts.sort_unstable();
+    assert_eq!(
+        vec![(None, 1), (Some(ArchiveReason::Abusive), 1)],
+        rating_counts
+    );
+    let mut comment_counts = repo.count_archived_comments_by_reason().unwrap();
+    comment_counts.sort_unstable();
+    assert_eq!(
+        vec![
+            (None, 1),
+            (Some(ArchiveReason::Spam), 1),
+            (Some(ArchiveReason::Abusive), 1)
+        ],
+        comment_counts
+    );
+}
+
+pub fn rating_aggregate_repo<R: PlaceRepo + RatingRepository + RatingAggregateRepo>(repo: &R) {
+    let mut fixtures = Fixtures::new(SEED);
+    let places: Vec<_> = (0..3).map(|_| fixtures.place()).collect();
+    for place in &places {
+        repo.create_or_update_place(place.clone()).unwrap();
+    }
+    // The last place remains unrated
+    let ratings: Vec<_> = (0..COUNT)
+        .map(|i| fixtures.rating(&places[i % 2].id))
+        .collect();
+    for rating in &ratings {
+        repo.create_rating(rating.clone()).unwrap();
+    }
+    let assert_aggregates = |repo: &R| {
+        let mut aggregates: BTreeMap<_, _> = repo
+            .load_avg_ratings_of_all_places()
+            .unwrap()
+            .into_iter()
+            .collect();
+        for place in &places {
+            let ratings = repo.load_ratings_of_place(place.id.as_str()).unwrap();
+            assert_eq!(
+                place.avg_ratings(&ratings),
+                aggregates.remove(&place.id).unwrap_or_default()
+            );
+        }
+        assert!(aggregates.is_empty());
+    };
+    assert_aggregates(repo);
+
+    let activity = Activity::now(None);
+    repo.archive_ratings(
+        &[ratings[0].id.as_str()],
+        &activity,
+        ArchiveReason::OffTopic,
+    )
+    .unwrap();
+    assert_aggregates(repo);
+    repo.archive_ratings_of_places(&[places[1].id.as_str()], &activity)
+        .unwrap();
+    assert_aggregates(repo);
+
+    assert_eq!(places.len(), repo.recompute_rating_aggregates().unwrap());
+    assert_aggregates(repo);
+}
@@ -0,0 +1,87 @@
+use crate::core::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct NewPlaceNote {
+    pub place_id: String,
+    /// Reply to an existing note of the same place
+    pub parent_id: Option<String>,
+    pub text: String,
+}
+
+/// Attaches an internal note to a place.
+///
+/// The caller is responsible for checking that only
+/// scouts and admins are allowed to add notes.
+pub fn add_place_note<D: Db>(db: &D, note: NewPlaceNote, created_by: &Email) -> Result<PlaceNote> {
+    let NewPlaceNote {
+        place_id,
+        parent_id,
+        text,
+    } = note;
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(Error::Parameter(ParameterError::EmptyComment));
+    }
+    let (place, _) = db.get_place_by_id(&place_id)?;
+    if let Some(ref parent_id) = parent_id {
+        if !db
+            .load_place_notes(place.id.as_str())?
+            .iter()
+            .any(|n| n.id.as_str() == parent_id)
+        {
+            return Err(Error::Repo(RepoError::NotFound));
+        }
+    }
+    let note = PlaceNote {
+        id: Id::new(),
+        place_id: place.id,
+        parent_id: parent_id.map(Into::into),
+        created: Activity::now(Some(created_by.clone())),
+        text: text.to_string(),
+    };
+    db.create_place_note(&note)?;
+    Ok(note)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn new_note(place_id: &str, parent_id: Option<&str>, text: &str) -> NewPlaceNote {
+        NewPlaceNote {
+            place_id: place_id.into(),
+            parent_id: parent_id.map(Into::into),
+            text: text.into(),
+        }
+    }
+
+    #[test]
+    fn add_notes_and_replies() {
+        let db = MockDb::default();
+        for id in &["a", "b"] {
+            db.entries
+                .borrow_mut()
+                .push((Place::build().id(id).finish(), ReviewStatus::Created));
+        }
+        let scout = Email::from("scout@example.com");
+
+        let note = add_place_note(&db, new_note("a", None, " Owner contacted "), &scout).unwrap();
+        assert_eq!("Owner contacted", note.text);
+        assert_eq!(Some(scout.clone()), note.created.by);
+        let reply = add_place_note(
+            &db,
+            new_note("a", Some(note.id.as_str()), "No answer yet"),
+            &scout,
+        )
+        .unwrap();
+        assert_eq!(Some(note.id.clone()), reply.parent_id);
+        assert_eq!(2, db.load_place_notes("a").unwrap().len());
+
+        // Replies must refer to a note of the same place
+        assert!(add_place_note(&db, new_note("b", Some(note.id.as_str()), "?"), &scout).is_err());
+        assert!(add_place_note(&db, new_note("a", None, "  "), &scout).is_err());
+        assert!(add_place_note(&db, new_note("c", None, "Note"), &scout).is_err());
+        assert!(db.load_place_notes("b").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,66 @@
+use crate::core::prelude::*;
+
+/// Queues an announcement for all users with a confirmed
+/// e-mail address who opted into announcements.
+///
+/// Returns the number of recipients.
+pub fn queue_announcement<D: Db>(db: &D, subject: &str, body: &str) -> Result<usize> {
+    let subject = subject.trim();
+    let body = body.trim();
+    if subject.is_empty() || body.is_empty() {
+        return Err(Error::Parameter(ParameterError::EmptyAnnouncement));
+    }
+    let created_at = Timestamp::now();
+    let emails: Vec<_> = db
+        .announcement_recipients()?
+        .into_iter()
+        .map(|recipient| QueuedEmail {
+            id: Id::new(),
+            recipient,
+            subject: subject.into(),
+            body: body.into(),
+            created_at,
+        })
+        .collect();
+    db.enqueue_emails(&emails)?;
+    Ok(emails.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn user(email: &str, email_confirmed: bool) -> User {
+        User {
+            email: email.into(),
+            email_confirmed,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::User,
+        }
+    }
+
+    #[test]
+    fn queue_announcement_for_users_who_opted_in() {
+        let db = MockDb::default();
+        for (email, email_confirmed, wants_announcements) in vec![
+            ("a@example.com", true, true),
+            ("b@example.com", true, false),
+            ("c@example.com", false, true),
+        ] {
+            db.create_user(&user(email, email_confirmed)).unwrap();
+            db.set_wants_announcements(email, wants_announcements)
+                .unwrap();
+        }
+        assert!(db.set_wants_announcements("x@example.com", true).is_err());
+
+        assert!(queue_announcement(&db, " ", "body").is_err());
+        assert!(queue_announcement(&db, "subject", "").is_err());
+        assert_eq!(1, queue_announcement(&db, "News ", "Hello").unwrap());
+
+        let emails = db.load_unsent_emails(10).unwrap();
+        assert_eq!(1, emails.len());
+        assert_eq!("a@example.com", emails[0].recipient.as_str());
+        assert_eq!("News", emails[0].subject);
+    }
+}
@@ -0,0 +1,63 @@
+use crate::core::prelude::*;
+
+pub fn anonymize_user<D: Db>(db: &D, email: &str) -> Result<AnonymizedUserRecords> {
+    info!("Anonymizing user {}", email);
+    db.try_get_user_by_email(email)?
+        .ok_or(ParameterError::UserDoesNotExist)?;
+    Ok(db.anonymize_user_by_email(email)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    #[test]
+    fn anonymize_user_and_detach_events() {
+        let db = MockDb::default();
+        db.create_user(&User {
+            email: "user@example.com".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::User,
+        })
+        .unwrap();
+        db.create_event(Event {
+            id: "x".into(),
+            title: "t".into(),
+            description: None,
+            start: chrono::NaiveDateTime::from_timestamp(0, 0),
+            end: None,
+            contact: None,
+            location: None,
+            homepage: None,
+            tags: vec![],
+            created_by: Some("user@example.com".into()),
+            registration: None,
+            archived: None,
+            image_url: None,
+            image_link_url: None,
+            time_zone: None,
+            recurrence: None,
+            address_auto_filled: false,
+            publish_at: None,
+            created_at: None,
+            updated_at: None,
+        })
+        .unwrap();
+
+        let records = anonymize_user(&db, "user@example.com").unwrap();
+        assert_eq!(1, records.events);
+        assert_eq!(0, db.count_users().unwrap());
+        assert!(db.events.borrow()[0].created_by.is_none());
+    }
+
+    #[test]
+    fn anonymize_unknown_user() {
+        let db = MockDb::default();
+        assert!(matches!(
+            anonymize_user(&db, "user@example.com"),
+            Err(Error::Parameter(ParameterError::UserDoesNotExist))
+        ));
+    }
+}
@@ -1,6 +1,11 @@
 use crate::core::prelude::*;
 
-pub fn archive_comments<D: Db>(db: &D, user_email: &str, ids: &[&str]) -> Result<usize> {
+pub fn archive_comments<D: Db>(
+    db: &D,
+    user_email: &str,
+    ids: &[&str],
+    reason: ArchiveReason,
+) -> Result<usize> {
     info!("Archiving {} comments", ids.len());
     // TODO: Pass an authentication token with user id and role to
     // check if the user is authorized to perform this use case
@@ -8,7 +13,7 @@ pub fn archive_comments<D: Db>(db: &D, user_email: &str, ids: &[&str]) -> Result
     if let Some(user) = user {
         if user.role >= Role::Scout {
             let archived = Activity::now(Some(user_email.into()));
-            return Ok(db.archive_comments(ids, &archived)?);
+            return Ok(db.archive_comments(ids, &archived, reason)?);
         }
     }
     Err(ParameterError::Forbidden.into())
@@ -1,6 +1,11 @@
 use crate::core::prelude::*;
 
-pub fn archive_ratings<D: Db>(db: &D, user_email: &str, ids: &[&str]) -> Result<usize> {
+pub fn archive_ratings<D: Db>(
+    db: &D,
+    user_email: &str,
+    ids: &[&str],
+    reason: ArchiveReason,
+) -> Result<usize> {
     debug!("Archiving ratings {:?}", ids);
     // TODO: Pass an authentication token with user id and role to
     // check if the user is authorized to perform this use case
@@ -8,8 +13,8 @@ pub fn archive_ratings<D: Db>(db: &D, user_email: &str, ids: &[&str]) -> Result<
     if let Some(user) = user {
         if user.role >= Role::Scout {
             let archived = Activity::now(Some(user_email.into()));
-            db.archive_comments_of_ratings(ids, &archived)?;
-            return Ok(db.archive_ratings(ids, &archived)?);
+            db.archive_comments_of_ratings(ids, &archived, reason)?;
+            return Ok(db.archive_ratings(ids, &archived, reason)?);
         }
     }
     Err(ParameterError::Forbidden.into())
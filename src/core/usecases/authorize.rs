@@ -16,6 +16,10 @@ pub fn authorize_organization_by_possible_api_tokens<D: OrganizationRepo>(
 
 pub fn authorize_user_by_email(db: &dyn Db, email: &str, min_required_role: Role) -> Result<User> {
     if let Some(user) = db.try_get_user_by_email(email)? {
+        // Sessions of deleted accounts end immediately
+        if db.user_deactivated_at(&user.email)?.is_some() {
+            return Err(Error::Parameter(ParameterError::Unauthorized));
+        }
         return ofdb_core::user::authorize_role(&user, min_required_role)
             .map(|()| user)
             .map_err(|_| Error::Parameter(ParameterError::Unauthorized));
@@ -23,21 +27,37 @@ pub fn authorize_user_by_email(db: &dyn Db, email: &str, min_required_role: Role
     Err(Error::Parameter(ParameterError::Unauthorized))
 }
 
+// The moderated tags of all organizations except the given one.
+//
+// Tags that are co-owned by the given organization are excluded,
+// because each owner is permitted to moderate them on its own.
+pub(crate) fn moderated_tags_of_other_orgs<R: OrganizationRepo>(
+    repo: &R,
+    org: Option<&Organization>,
+) -> Result<Vec<(Id, ModeratedTag)>> {
+    let mut moderated_tags_by_org = repo.get_moderated_tags_by_org(org.map(|org| &org.id))?;
+    if let Some(org) = org {
+        moderated_tags_by_org
+            .retain(|(_, tag)| !org.moderated_tags.iter().any(|own| own.label == tag.label));
+    }
+    Ok(moderated_tags_by_org)
+}
+
 // Checks if the addition and removal of tags is permitted.
 //
 // Returns a list with the ids of other organizations that require
 // clearance of the pending changes.
 //
-// If an organization is provided than this organization is excluded
-// from both the checks and the pending clearance list.
+// If an organization is provided than this organization and the
+// co-owners of its tags are excluded from both the checks and the
+// pending clearance list.
 pub fn authorize_editing_of_tagged_entry<R: OrganizationRepo>(
     repo: &R,
     old_tags: &[String],
     new_tags: &[String],
     org: Option<&Organization>,
 ) -> Result<Vec<Id>> {
-    let org_id = org.map(|org| &org.id);
-    let moderated_tags_by_org = repo.get_moderated_tags_by_org(org_id)?;
+    let moderated_tags_by_org = moderated_tags_of_other_orgs(repo, org)?;
     ofdb_core::tag::moderated::authorize_editing_of_tagged_entry(
         moderated_tags_by_org,
         old_tags,
@@ -45,3 +65,40 @@ pub fn authorize_editing_of_tagged_entry<R: OrganizationRepo>(
     )
     .map_err(|_| ParameterError::ModeratedTag.into())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::usecases::tests::MockDb;
+
+    #[test]
+    fn co_owners_are_permitted_to_moderate_shared_tags() {
+        let mut db = MockDb::default();
+        let shared_tag = ModeratedTag {
+            label: "shared".into(),
+            allow_add: false,
+            allow_remove: false,
+            require_clearance: true,
+        };
+        for id in &["a", "b"] {
+            db.create_org(Organization {
+                id: (*id).into(),
+                name: (*id).into(),
+                api_token: (*id).into(),
+                moderated_tags: vec![shared_tag.clone()],
+                notification_email: None,
+                email_branding: Default::default(),
+            })
+            .unwrap();
+        }
+        let old_tags = vec![];
+        let new_tags = vec!["shared".to_string()];
+        assert!(authorize_editing_of_tagged_entry(&db, &old_tags, &new_tags, None).is_err());
+        let org = db.get_org_by_id(&"a".into()).unwrap();
+        assert!(
+            authorize_editing_of_tagged_entry(&db, &old_tags, &new_tags, Some(&org))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use super::{NewEvent, NewPlace};
+use crate::core::prelude::*;
+use ofdb_core::gateways::geocode::GeoCodingGateway;
+
+fn resolve_address(gw: &dyn GeoCodingGateway, lat: f64, lng: f64) -> Option<Address> {
+    let pos = MapPoint::try_from_lat_lng_deg(lat, lng).ok()?;
+    if !pos.is_valid() {
+        return None;
+    }
+    gw.resolve_lat_lng_address(lat, lng)
+}
+
+// Only missing addresses are resolved
+fn resolve_missing_address(
+    gw: &dyn GeoCodingGateway,
+    lat: f64,
+    lng: f64,
+    given: Address,
+) -> Option<Address> {
+    if !given.is_empty() {
+        return None;
+    }
+    resolve_address(gw, lat, lng)
+}
+
+/// Fill in missing address fields of a new place from its coordinates.
+///
+/// Returns `true` if the address has been auto-filled.
+pub fn auto_fill_new_place_address(gw: &dyn GeoCodingGateway, p: &mut NewPlace) -> bool {
+    let given = Address {
+        street: p.street.clone(),
+        zip: p.zip.clone(),
+        city: p.city.clone(),
+        country: p.country.clone(),
+        state: p.state.clone(),
+    };
+    if let Some(addr) = resolve_missing_address(gw, p.lat, p.lng, given) {
+        debug!(
+            "Auto-filled address of new place at ({}, {}): {:?}",
+            p.lat, p.lng, addr
+        );
+        let Address {
+            street,
+            zip,
+            city,
+            country,
+            state,
+        } = addr;
+        p.street = street;
+        p.zip = zip;
+        p.city = city;
+        p.country = country;
+        p.state = state;
+        true
+    } else {
+        false
+    }
+}
+
+/// Fill in missing address fields of a new or updated event
+/// from its coordinates.
+///
+/// Returns `true` if the address has been auto-filled.
+pub fn auto_fill_new_event_address(gw: &dyn GeoCodingGateway, e: &mut NewEvent) -> bool {
+    let (lat, lng) = match (e.lat, e.lng) {
+        (Some(lat), Some(lng)) => (lat, lng),
+        _ => return false,
+    };
+    let given = Address {
+        street: e.street.clone(),
+        zip: e.zip.clone(),
+        city: e.city.clone(),
+        country: e.country.clone(),
+        state: e.state.clone(),
+    };
+    if let Some(addr) = resolve_missing_address(gw, lat, lng, given) {
+        debug!(
+            "Auto-filled address of event at ({}, {}): {:?}",
+            lat, lng, addr
+        );
+        let Address {
+            street,
+            zip,
+            city,
+            country,
+            state,
+        } = addr;
+        e.street = street;
+        e.zip = zip;
+        e.city = city;
+        e.country = country;
+        e.state = state;
+        true
+    } else {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct DummyGeoCodingGw;
+
+    impl GeoCodingGateway for DummyGeoCodingGw {
+        fn resolve_address_lat_lng(&self, _: &Address) -> Option<(f64, f64)> {
+            None
+        }
+        fn resolve_lat_lng_address(&self, _: f64, _: f64) -> Option<Address> {
+            Some(Address {
+                street: Some("Schlossplatz 1".into()),
+                city: Some("Stuttgart".into()),
+                ..Default::default()
+            })
+        }
+    }
+
+    fn new_place() -> NewPlace {
+        NewPlace {
+            title: "foo".into(),
+            description: "bar".into(),
+            lat: 48.7,
+            lng: 9.1,
+            street: None,
+            zip: None,
+            city: None,
+            country: None,
+            state: None,
+            contact_name: None,
+            email: None,
+            telephone: None,
+            homepage: None,
+            opening_hours: None,
+            founded_on: None,
+            categories: vec![],
+            tags: vec![],
+            license: "CC0-1.0".into(),
+            image_url: None,
+            image_link_url: None,
+            custom_links: vec![],
+        }
+    }
+
+    #[test]
+    fn fill_missing_place_address() {
+        let mut p = new_place();
+        assert!(auto_fill_new_place_address(&DummyGeoCodingGw, &mut p));
+        assert_eq!(Some("Schlossplatz 1"), p.street.as_deref());
+        assert_eq!(Some("Stuttgart"), p.city.as_deref());
+    }
+
+    #[test]
+    fn keep_given_place_address() {
+        let mut p = new_place();
+        p.zip = Some("70173".into());
+        assert!(!auto_fill_new_place_address(&DummyGeoCodingGw, &mut p));
+        assert!(p.street.is_none());
+    }
+
+    #[test]
+    fn fill_missing_event_address() {
+        let mut e = NewEvent {
+            lat: Some(48.7),
+            lng: Some(9.1),
+            ..Default::default()
+        };
+        assert!(auto_fill_new_event_address(&DummyGeoCodingGw, &mut e));
+        assert_eq!(Some("Stuttgart"), e.city.as_deref());
+    }
+
+    #[test]
+    fn ignore_events_without_position() {
+        let mut e = NewEvent::default();
+        assert!(!auto_fill_new_event_address(&DummyGeoCodingGw, &mut e));
+        assert!(e.city.is_none());
+    }
+}
@@ -0,0 +1,155 @@
+use crate::core::prelude::*;
+use std::collections::BTreeSet;
+
+// A single failure might be caused by a temporary outage
+pub const MIN_FAILURES_OF_BROKEN_LINKS: u32 = 3;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LinkCheckReport {
+    pub checked: usize,
+    pub failed: usize,
+}
+
+/// Collects the distinct homepage and image links of
+/// all existing places and all events that are not archived.
+pub fn links_to_check<D: Db>(db: &D) -> Result<Vec<String>> {
+    let mut links = BTreeSet::new();
+    for (place, status) in db.all_places()? {
+        if !status.exists() {
+            continue;
+        }
+        if let Some(links_of_place) = place.links {
+            links.extend(links_of_place.homepage.map(Url::into_string));
+            links.extend(links_of_place.image.map(Url::into_string));
+        }
+    }
+    for event in db.all_events_chronologically()? {
+        if event.archived.is_some() {
+            continue;
+        }
+        links.extend(event.homepage.map(Url::into_string));
+        links.extend(event.image_url.map(Url::into_string));
+    }
+    Ok(links.into_iter().collect())
+}
+
+fn is_broken(broken: &[String], url: Option<&Url>) -> bool {
+    url.map(|url| broken.iter().any(|b| b == url.as_str()))
+        .unwrap_or(false)
+}
+
+/// Removes images that repeatedly failed to load.
+pub fn hide_broken_place_images<'a, R: LinkCheckRepo>(
+    repo: &R,
+    places: impl IntoIterator<Item = &'a mut Place>,
+) -> Result<()> {
+    let mut links: Vec<_> = places
+        .into_iter()
+        .filter_map(|p| p.links.as_mut())
+        .collect();
+    let images: Vec<_> = links
+        .iter()
+        .filter_map(|l| l.image.as_ref())
+        .map(Url::as_str)
+        .collect();
+    let broken = repo.filter_broken_links(&images, MIN_FAILURES_OF_BROKEN_LINKS)?;
+    for links in links.iter_mut() {
+        if is_broken(&broken, links.image.as_ref()) {
+            links.image = None;
+            links.image_href = None;
+        }
+    }
+    Ok(())
+}
+
+/// Removes images that repeatedly failed to load.
+pub fn hide_broken_event_images<'a, R: LinkCheckRepo>(
+    repo: &R,
+    events: impl IntoIterator<Item = &'a mut Event>,
+) -> Result<()> {
+    let mut events: Vec<_> = events.into_iter().collect();
+    let images: Vec<_> = events
+        .iter()
+        .filter_map(|e| e.image_url.as_ref())
+        .map(Url::as_str)
+        .collect();
+    let broken = repo.filter_broken_links(&images, MIN_FAILURES_OF_BROKEN_LINKS)?;
+    for event in events.iter_mut() {
+        if is_broken(&broken, event.image_url.as_ref()) {
+            event.image_url = None;
+            event.image_link_url = None;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn place_with_image(id: &str, image: &str) -> Place {
+        let mut place = Place::build().id(id).title(id).finish();
+        place.links = Some(Links {
+            homepage: Some(format!("https://{}.example.com/", id).parse().unwrap()),
+            image: Some(image.parse().unwrap()),
+            image_href: None,
+            custom: vec![],
+        });
+        place
+    }
+
+    #[test]
+    fn hide_images_after_repeated_failures() {
+        let db = MockDb::default();
+        db.entries.borrow_mut().extend(vec![
+            (
+                place_with_image("a", "https://img.example.com/broken.png"),
+                ReviewStatus::Created,
+            ),
+            (
+                place_with_image("b", "https://img.example.com/ok.png"),
+                ReviewStatus::Confirmed,
+            ),
+            (
+                place_with_image("c", "https://img.example.com/archived.png"),
+                ReviewStatus::Archived,
+            ),
+        ]);
+        assert_eq!(
+            vec![
+                "https://a.example.com/",
+                "https://b.example.com/",
+                "https://img.example.com/broken.png",
+                "https://img.example.com/ok.png",
+            ],
+            links_to_check(&db).unwrap()
+        );
+
+        let broken = "https://img.example.com/broken.png";
+        for i in 0..MIN_FAILURES_OF_BROKEN_LINKS {
+            let mut places: Vec<_> = db.entries.borrow().iter().map(|(p, _)| p.clone()).collect();
+            hide_broken_place_images(&db, &mut places).unwrap();
+            assert!(places[0].links.as_ref().unwrap().image.is_some());
+            db.record_link_check(
+                broken,
+                Some("404 Not Found"),
+                Timestamp::from_inner(i.into()),
+            )
+            .unwrap();
+        }
+        let mut places: Vec<_> = db.entries.borrow().iter().map(|(p, _)| p.clone()).collect();
+        hide_broken_place_images(&db, &mut places).unwrap();
+        assert!(places[0].links.as_ref().unwrap().image.is_none());
+        assert!(places[0].links.as_ref().unwrap().homepage.is_some());
+        assert!(places[1].links.as_ref().unwrap().image.is_some());
+
+        // A successful check restores the image
+        db.record_link_check(broken, None, Timestamp::from_inner(10))
+            .unwrap();
+        assert!(db
+            .load_broken_links(MIN_FAILURES_OF_BROKEN_LINKS)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,96 @@
+use crate::core::{error::ParameterError, prelude::*};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionIssue {
+    /// The stored coordinates are out of range or not a number
+    Invalid,
+    /// The position is exactly 0/0
+    NullIsland,
+}
+
+pub fn position_issue(pos: MapPoint) -> Option<PositionIssue> {
+    if !pos.is_valid() {
+        Some(PositionIssue::Invalid)
+    } else if pos.is_null_island() {
+        Some(PositionIssue::NullIsland)
+    } else {
+        None
+    }
+}
+
+/// Submitting a place at 0/0 requires an explicit confirmation.
+pub fn check_submitted_position(lat: f64, lng: f64, confirmed: bool) -> Result<()> {
+    if confirmed {
+        return Ok(());
+    }
+    match MapPoint::try_from_lat_lng_deg(lat, lng) {
+        Ok(pos) if pos.is_null_island() => {
+            Err(Error::Parameter(ParameterError::UnconfirmedPosition))
+        }
+        // Out of range coordinates are rejected later
+        _ => Ok(()),
+    }
+}
+
+pub fn find_places_with_position_issues<D: Db>(db: &D) -> Result<Vec<(Place, PositionIssue)>> {
+    Ok(db
+        .all_places()?
+        .into_iter()
+        .filter_map(|(place, _)| position_issue(place.location.pos).map(|issue| (place, issue)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::super::tests::MockDb;
+    use super::*;
+
+    #[test]
+    fn find_places_with_invalid_or_null_island_positions() {
+        let db = MockDb::default();
+        db.entries.borrow_mut().extend(vec![
+            (
+                Place::build()
+                    .id("valid")
+                    .pos(MapPoint::from_lat_lng_deg(48.7, 9.1))
+                    .finish(),
+                ReviewStatus::Created,
+            ),
+            (
+                Place::build()
+                    .id("invalid")
+                    .pos(MapPoint::default())
+                    .finish(),
+                ReviewStatus::Created,
+            ),
+            (
+                Place::build()
+                    .id("null-island")
+                    .pos(MapPoint::from_lat_lng_deg(0.0, 0.0))
+                    .finish(),
+                ReviewStatus::Created,
+            ),
+        ]);
+        let issues: Vec<_> = find_places_with_position_issues(&db)
+            .unwrap()
+            .into_iter()
+            .map(|(place, issue)| (place.id.to_string(), issue))
+            .collect();
+        assert_eq!(
+            vec![
+                ("invalid".to_string(), PositionIssue::Invalid),
+                ("null-island".to_string(), PositionIssue::NullIsland),
+            ],
+            issues
+        );
+    }
+
+    #[test]
+    fn reject_unconfirmed_null_island_submissions() {
+        assert!(check_submitted_position(0.0, 0.0, false).is_err());
+        assert!(check_submitted_position(0.0, 0.0, true).is_ok());
+        assert!(check_submitted_position(48.7, 9.1, false).is_ok());
+        assert!(check_submitted_position(0.0, 0.5, false).is_ok());
+    }
+}
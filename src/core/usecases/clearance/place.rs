@@ -1,4 +1,4 @@
-use crate::core::prelude::*;
+use crate::core::{prelude::*, usecases::LEGACY_ORGANIZATION_API_TOKEN_ID};
 
 use std::collections::HashMap;
 
@@ -25,11 +25,21 @@ pub fn list_pending_clearances<R: OrganizationRepo + PlaceClearanceRepo>(
     Ok(repo.list_pending_clearances_for_places(&org.id, pagination)?)
 }
 
-pub fn update_pending_clearances<R: OrganizationRepo + PlaceClearanceRepo>(
+/// Every decision about a pending clearance is recorded
+/// in the clearance log together with the API token that
+/// has been used. No token refers to the initial API token
+/// of the organization.
+pub fn update_pending_clearances<R>(
     repo: &R,
     org: &Organization,
+    api_token: Option<&OrganizationApiToken>,
     clearances: &[ClearanceForPlace],
-) -> Result<usize> {
+) -> Result<usize>
+where
+    R: OrganizationRepo + PlaceRepo + PlaceClearanceRepo + ClearanceLogRepo,
+{
+    let place_ids: Vec<_> = clearances.iter().map(|c| c.place_id.as_str()).collect();
+    let pending_clearances = repo.load_pending_clearances_for_places(&org.id, &place_ids)?;
     let count = repo.update_pending_clearances_for_places(&org.id, clearances)?;
     log::info!(
         "Updated {} of {} pending clearance(s) for places on behalf of organization '{}'",
@@ -37,10 +47,53 @@ pub fn update_pending_clearances<R: OrganizationRepo + PlaceClearanceRepo>(
         clearances.len(),
         org.name
     );
+    let decided_at = TimestampMs::now();
+    let (api_token_id, api_token_label) = match api_token {
+        Some(token) => (token.id.clone(), token.label.clone()),
+        None => (Id::from(LEGACY_ORGANIZATION_API_TOKEN_ID), None),
+    };
+    let mut log_entries = Vec::with_capacity(pending_clearances.len());
+    for clearance in clearances {
+        if !pending_clearances
+            .iter()
+            .any(|p| p.place_id == clearance.place_id)
+        {
+            continue;
+        }
+        let (place, _) = repo.get_place_by_id(clearance.place_id.as_str())?;
+        let cleared_revision = clearance.cleared_revision.unwrap_or(place.revision);
+        let decision = if cleared_revision == place.revision {
+            ClearanceDecision::Approved
+        } else {
+            ClearanceDecision::Rejected
+        };
+        log_entries.push(ClearanceLogEntry {
+            org_id: org.id.clone(),
+            place_id: place.id,
+            place_revision: place.revision,
+            cleared_revision,
+            decision,
+            decided_at,
+            api_token_id: Some(api_token_id.clone()),
+            api_token_label: api_token_label.clone(),
+        });
+    }
+    repo.add_clearance_log_entries(&log_entries)?;
     repo.cleanup_pending_clearances_for_places(&org.id)?;
     Ok(count)
 }
 
+/// Lists the recorded decisions of an organization, or of all
+/// organizations if none is given, newest first.
+pub fn list_clearance_log<R: ClearanceLogRepo>(
+    repo: &R,
+    org: Option<&Organization>,
+    place_id: Option<&Id>,
+    pagination: &Pagination,
+) -> Result<Vec<ClearanceLogEntry>> {
+    Ok(repo.list_clearance_log_entries(org.map(|org| &org.id), place_id, pagination)?)
+}
+
 pub fn clear_repo_results<R: PlaceRepo + PlaceClearanceRepo>(
     repo: &R,
     org_id: &Id,
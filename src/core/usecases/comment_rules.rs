@@ -0,0 +1,144 @@
+use crate::core::prelude::*;
+use std::time::Duration;
+
+/// Content rules for the comments of new ratings.
+///
+/// Ratings with comments that violate any of these rules
+/// are held back for moderation instead of being published.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommentRules {
+    /// Max. number of links within a comment
+    pub max_links: Option<usize>,
+    /// Phrases are matched case-insensitive
+    pub banned_phrases: Vec<String>,
+    /// Comments of anonymous authors are always
+    /// held back if a min. account age is required
+    pub min_account_age: Option<Duration>,
+}
+
+fn count_links(text: &str) -> usize {
+    text.split_whitespace()
+        .map(|word| word.trim_start_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|word| {
+            let word = word.to_lowercase();
+            word.contains("://") || word.starts_with("www.")
+        })
+        .count()
+}
+
+impl CommentRules {
+    /// Checks the text of a comment, but not the author
+    pub fn check_text(&self, text: &str) -> Vec<CommentRuleViolation> {
+        let mut violations = vec![];
+        if let Some(max_links) = self.max_links {
+            if count_links(text) > max_links {
+                violations.push(CommentRuleViolation::TooManyLinks);
+            }
+        }
+        let text = text.to_lowercase();
+        if self
+            .banned_phrases
+            .iter()
+            .map(|phrase| phrase.trim().to_lowercase())
+            .any(|phrase| !phrase.is_empty() && text.contains(&phrase))
+        {
+            violations.push(CommentRuleViolation::BannedPhrase);
+        }
+        violations
+    }
+}
+
+/// Collects all rules that are violated by a new comment.
+///
+/// The author is given by the e-mail address of the account
+/// if logged in. Accounts of users who registered before the
+/// registration time has been recorded are considered old enough.
+pub fn check_comment_rules<D: UserGateway>(
+    db: &D,
+    rules: &CommentRules,
+    text: &str,
+    author_email: Option<&str>,
+    now: Timestamp,
+) -> Result<Vec<CommentRuleViolation>> {
+    let mut violations = rules.check_text(text);
+    if let Some(min_account_age) = rules.min_account_age {
+        let too_new = match author_email {
+            Some(email) => db
+                .user_created_at(email)?
+                .map(|created_at| {
+                    now.into_inner() - created_at.into_inner() < min_account_age.as_secs() as i64
+                })
+                .unwrap_or(false),
+            None => true,
+        };
+        if too_new {
+            violations.push(CommentRuleViolation::AccountTooNew);
+        }
+    }
+    Ok(violations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    #[test]
+    fn limit_the_number_of_links() {
+        let rules = CommentRules {
+            max_links: Some(1),
+            ..Default::default()
+        };
+        assert!(rules
+            .check_text("See https://example.org for details")
+            .is_empty());
+        assert_eq!(
+            vec![CommentRuleViolation::TooManyLinks],
+            rules.check_text("Cheap (www.example.com) and http://example.org")
+        );
+    }
+
+    #[test]
+    fn reject_banned_phrases_ignoring_case() {
+        let rules = CommentRules {
+            banned_phrases: vec!["cheap pills".into(), " ".into()],
+            ..Default::default()
+        };
+        assert!(rules.check_text("Nice and friendly").is_empty());
+        assert_eq!(
+            vec![CommentRuleViolation::BannedPhrase],
+            rules.check_text("Buy CHEAP Pills now")
+        );
+    }
+
+    #[test]
+    fn hold_back_comments_of_anonymous_authors_if_a_min_account_age_is_required() {
+        let db = MockDb::default();
+        db.users.borrow_mut().push(User {
+            email: "foo@bar.org".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::User,
+        });
+        let now = Timestamp::now();
+        let rules = CommentRules {
+            min_account_age: Some(Duration::from_secs(24 * 60 * 60)),
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![CommentRuleViolation::AccountTooNew],
+            check_comment_rules(&db, &rules, "comment", None, now).unwrap()
+        );
+        // The registration time of this user is unknown
+        assert!(
+            check_comment_rules(&db, &rules, "comment", Some("foo@bar.org"), now)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            check_comment_rules(&db, &CommentRules::default(), "comment", None, now)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
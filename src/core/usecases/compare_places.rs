@@ -0,0 +1,210 @@
+use super::find_duplicates::{
+    is_in_close_proximity_pos, is_similar_text, levenshtein_distance, search_nearby_places,
+    MAX_NEARBY_RADIUS, MAX_TEXT_RELATIVE_EDIT_DISTANCE, MAX_WORDS_HAMMING_DISTANCE,
+};
+use crate::core::prelude::*;
+
+/// A place that is managed outside of OpenFairDB,
+/// e.g. in the database of an organization.
+#[derive(Debug, Clone)]
+pub struct ExternalPlace {
+    pub title: String,
+    pub lat: f64,
+    pub lng: f64,
+    pub street: Option<String>,
+    pub zip: Option<String>,
+    pub city: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaceMatch {
+    pub place_id: Id,
+    pub title: String,
+    /// Between 0.0 (unlikely) and 1.0 (certain)
+    pub confidence: f64,
+}
+
+// Keeps the number of index queries per request reasonably small
+pub const MAX_COMPARED_PLACES: usize = 100;
+
+const TITLE_WEIGHT: f64 = 0.5;
+const DISTANCE_WEIGHT: f64 = 0.3;
+const ADDRESS_WEIGHT: f64 = 0.2;
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn title_similarity(title1: &str, title2: &str) -> f64 {
+    let (title1, title2) = (normalize(title1), normalize(title2));
+    let max_len = title1.len().max(title2.len());
+    if max_len == 0 {
+        return 0.0;
+    }
+    let dist = levenshtein_distance(&title1, &title2).min(max_len);
+    1.0 - dist as f64 / max_len as f64
+}
+
+fn address_similarity(external: &ExternalPlace, address: Option<&Address>) -> Option<f64> {
+    let address = address?;
+    let fields = [
+        (&external.street, &address.street),
+        (&external.zip, &address.zip),
+        (&external.city, &address.city),
+    ];
+    let compared: Vec<_> = fields
+        .iter()
+        .filter_map(|(external, existing)| match (external, existing) {
+            (Some(external), Some(existing)) => Some(normalize(external) == normalize(existing)),
+            _ => None,
+        })
+        .collect();
+    if compared.is_empty() {
+        return None;
+    }
+    let equal = compared.iter().filter(|equal| **equal).count();
+    Some(equal as f64 / compared.len() as f64)
+}
+
+/// Estimates how likely the external place and the
+/// existing place are the same or returns `None`
+/// if they are obviously different.
+fn match_confidence(external: &ExternalPlace, place: &Place) -> Option<f64> {
+    let pos = MapPoint::from_lat_lng_deg(external.lat, external.lng);
+    if !is_in_close_proximity_pos(&pos, &place.location.pos, MAX_NEARBY_RADIUS) {
+        return None;
+    }
+    let address = address_similarity(external, place.location.address.as_ref());
+    let similar_title = is_similar_text(
+        &external.title,
+        &place.title,
+        MAX_TEXT_RELATIVE_EDIT_DISTANCE,
+        MAX_WORDS_HAMMING_DISTANCE,
+    );
+    if !similar_title && address != Some(1.0) {
+        return None;
+    }
+    let distance = MapPoint::distance(pos, place.location.pos)?;
+    let distance = 1.0 - (distance.to_meters() / MAX_NEARBY_RADIUS.to_meters()).min(1.0);
+    let title = title_similarity(&external.title, &place.title);
+    let confidence = match address {
+        Some(address) => {
+            TITLE_WEIGHT * title + DISTANCE_WEIGHT * distance + ADDRESS_WEIGHT * address
+        }
+        None => (TITLE_WEIGHT * title + DISTANCE_WEIGHT * distance) / (1.0 - ADDRESS_WEIGHT),
+    };
+    Some(confidence)
+}
+
+/// Finds existing places that match the external places.
+///
+/// The matches of each external place are ordered by
+/// descending confidence.
+pub fn compare_places<R: PlaceRepo>(
+    repo: &R,
+    index: &dyn PlaceIndex,
+    external_places: &[ExternalPlace],
+) -> Result<Vec<Vec<PlaceMatch>>> {
+    if external_places.len() > MAX_COMPARED_PLACES {
+        return Err(Error::Parameter(ParameterError::InvalidLimit));
+    }
+    let mut results = Vec::with_capacity(external_places.len());
+    for external in external_places {
+        let pos = MapPoint::from_lat_lng_deg(external.lat, external.lng);
+        if !pos.is_valid() {
+            return Err(Error::Parameter(ParameterError::InvalidPosition));
+        }
+        let nearby_places = search_nearby_places(index, pos)?;
+        let ids: Vec<_> = nearby_places.iter().map(|p| p.id.as_str()).collect();
+        let mut matches: Vec<_> = repo
+            .get_places_by_ids(&ids)?
+            .into_iter()
+            .filter_map(|(place, _)| {
+                match_confidence(external, &place).map(|confidence| PlaceMatch {
+                    place_id: place.id,
+                    title: place.title,
+                    confidence,
+                })
+            })
+            .collect();
+        matches.sort_by(|m1, m2| m2.confidence.partial_cmp(&m1.confidence).unwrap());
+        results.push(matches);
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn external_place(title: &str) -> ExternalPlace {
+        ExternalPlace {
+            title: title.into(),
+            lat: 48.7755,
+            lng: 9.1827,
+            street: Some("Königstraße 1".into()),
+            zip: Some("70173".into()),
+            city: None,
+        }
+    }
+
+    fn place(title: &str, lat: f64, street: Option<&str>) -> Place {
+        let mut place = Place::build()
+            .title(title)
+            .pos(MapPoint::from_lat_lng_deg(lat, 9.1827))
+            .finish();
+        place.location.address = street.map(|street| Address {
+            street: Some(street.into()),
+            zip: Some("70173".into()),
+            ..Default::default()
+        });
+        place
+    }
+
+    #[test]
+    fn rate_identical_places_with_full_confidence() {
+        let confidence = match_confidence(
+            &external_place("Bioladen"),
+            &place("Bioladen", 48.7755, Some("königstraße  1")),
+        )
+        .unwrap();
+        assert!((confidence - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn rate_nearby_places_with_similar_titles() {
+        let external = external_place("Bioladen am Markt");
+        let close = match_confidence(&external, &place("Bioladen Markt", 48.7755, None)).unwrap();
+        let distant = match_confidence(&external, &place("Bioladen Markt", 48.7762, None)).unwrap();
+        assert!(close > distant);
+        assert!(distant > 0.0);
+        let other_address = match_confidence(
+            &external,
+            &place("Bioladen Markt", 48.7755, Some("Marktplatz 3")),
+        )
+        .unwrap();
+        assert!(close > other_address);
+    }
+
+    #[test]
+    fn ignore_different_or_distant_places() {
+        let external = external_place("Bioladen");
+        assert_eq!(
+            None,
+            match_confidence(&external, &place("Fahrradwerkstatt", 48.7755, None))
+        );
+        assert_eq!(
+            None,
+            match_confidence(&external, &place("Bioladen", 48.79, None))
+        );
+        // The same address is sufficient
+        assert!(match_confidence(
+            &external,
+            &place("Fahrradwerkstatt", 48.7755, Some("Königstraße 1"))
+        )
+        .is_some());
+    }
+}
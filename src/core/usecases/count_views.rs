@@ -0,0 +1,48 @@
+use crate::core::prelude::*;
+use chrono::{Duration, Utc};
+
+// Views are aggregated per day (UTC)
+fn start_of_day(days_ago: i64) -> Timestamp {
+    (Utc::today() - Duration::days(days_ago))
+        .and_hms(0, 0, 0)
+        .into()
+}
+
+pub fn count_place_view<D: Db>(db: &D, place_id: &str) -> Result<()> {
+    // Only views of existing entities are counted
+    db.get_place_by_id(place_id)?;
+    db.increment_view_count(ViewedEntity::Place, place_id, start_of_day(0))?;
+    Ok(())
+}
+
+pub fn count_event_view<D: Db>(db: &D, event_id: &str) -> Result<()> {
+    db.get_event(event_id)?;
+    db.increment_view_count(ViewedEntity::Event, event_id, start_of_day(0))?;
+    Ok(())
+}
+
+/// Counts the views of all places and events during
+/// the given number of days including today.
+pub fn count_recent_views<D: Db>(db: &D, days: u32) -> Result<u64> {
+    let since = start_of_day(i64::from(days.max(1)) - 1);
+    Ok(db.count_all_views_since(since)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::usecases::tests::MockDb;
+
+    #[test]
+    fn count_views_of_existing_places() {
+        let db = MockDb::default();
+        db.create_or_update_place(Place::build().id("a").finish())
+            .unwrap();
+        count_place_view(&db, "a").unwrap();
+        count_place_view(&db, "a").unwrap();
+        assert!(count_place_view(&db, "b").is_err());
+        assert_eq!(2, db.count_views(ViewedEntity::Place, "a").unwrap());
+        assert_eq!(0, db.count_views(ViewedEntity::Event, "a").unwrap());
+        assert_eq!(2, count_recent_views(&db, 1).unwrap());
+    }
+}
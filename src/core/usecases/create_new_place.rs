@@ -1,11 +1,12 @@
-use super::{parse_custom_link_param, CustomLinkParam};
+use super::{check_category_ids, parse_custom_link_param, CustomLinkParam};
 
 use crate::core::{
     prelude::*,
-    util::{parse::parse_url_param, validate::Validate},
+    util::{address::normalize_address, parse::parse_url_param, validate::Validate},
 };
 
 use chrono::NaiveDate;
+use ofdb_core::gateways::geocode::GeoCodingGateway;
 use std::collections::HashSet;
 
 #[rustfmt::skip]
@@ -40,13 +41,19 @@ pub struct Storable {
     clearance_org_ids: Vec<Id>,
 }
 
+/// A missing address is resolved from the coordinates if a
+/// geocoding gateway is provided.
 pub fn prepare_new_place<D: Db>(
     db: &D,
-    e: NewPlace,
+    mut e: NewPlace,
     created_by_email: Option<&str>,
     created_by_org: Option<&Organization>,
     accepted_licenses: &HashSet<String>,
+    auto_fill_address: Option<&dyn GeoCodingGateway>,
 ) -> Result<Storable> {
+    let address_auto_filled = auto_fill_address
+        .map(|gw| super::auto_fill_new_place_address(gw, &mut e))
+        .unwrap_or(false);
     let NewPlace {
         title,
         description,
@@ -73,6 +80,7 @@ pub fn prepare_new_place<D: Db>(
     let pos =
         MapPoint::try_from_lat_lng_deg(lat, lng).map_err(|_| ParameterError::InvalidPosition)?;
 
+    check_category_ids(db, &categories)?;
     let categories: Vec<_> = categories.into_iter().map(Id::from).collect();
     let old_tags = vec![];
     let new_tags = super::prepare_tag_list(
@@ -83,13 +91,13 @@ pub fn prepare_new_place<D: Db>(
     let clearance_org_ids =
         super::authorize_editing_of_tagged_entry(db, &old_tags, &new_tags, created_by_org)?;
 
-    let address = Address {
+    let address = normalize_address(Address {
         street,
         zip,
         city,
         country,
         state,
-    };
+    });
     let address = if address.is_empty() {
         None
     } else {
@@ -142,6 +150,7 @@ pub fn prepare_new_place<D: Db>(
         title,
         description,
         location,
+        address_auto_filled,
         contact,
         opening_hours: opening_hours
             .map(|s| {
@@ -163,6 +172,35 @@ pub fn prepare_new_place<D: Db>(
     })
 }
 
+/// Runs all checks of [`prepare_new_place`] without storing anything.
+///
+/// The normalized tags are returned even if the validation fails.
+pub fn validate_new_place<D: Db>(
+    db: &D,
+    e: NewPlace,
+    created_by_email: Option<&str>,
+    created_by_org: Option<&Organization>,
+    accepted_licenses: &HashSet<String>,
+) -> (Vec<String>, Result<Place>) {
+    let categories: Vec<_> = e.categories.iter().cloned().map(Id::from).collect();
+    let tags = super::prepare_tag_list(
+        Category::merge_ids_into_tags(&categories, e.tags.clone())
+            .iter()
+            .map(String::as_str),
+    );
+    match prepare_new_place(
+        db,
+        e,
+        created_by_email,
+        created_by_org,
+        accepted_licenses,
+        None,
+    ) {
+        Ok(Storable { place, .. }) => (place.tags.clone(), Ok(place)),
+        Err(err) => (tags, Err(err)),
+    }
+}
+
 pub fn store_new_place<D: Db>(db: &D, s: Storable) -> Result<(Place, Vec<Rating>)> {
     let Storable {
         place,
@@ -186,6 +224,36 @@ pub fn store_new_place<D: Db>(db: &D, s: Storable) -> Result<(Place, Vec<Rating>
     Ok((place, ratings))
 }
 
+/// Stores a new place that is only visible to the user who
+/// created it until it is published.
+pub fn store_new_draft<D: Db>(db: &D, s: Storable) -> Result<Place> {
+    // Pending clearances are added when the draft gets published
+    let Storable {
+        place,
+        clearance_org_ids: _,
+    } = s;
+    let created_by = place
+        .created
+        .by
+        .clone()
+        .ok_or(Error::Parameter(ParameterError::Unauthorized))?;
+    debug!("Storing new draft: {:?}", place);
+    for t in &place.tags {
+        db.create_tag_if_it_does_not_exist(&Tag { id: t.clone() })?;
+    }
+    db.create_or_update_place_with_comment(place.clone(), "draft")?;
+    let activity_log = ActivityLog {
+        activity: Activity {
+            at: place.created.at,
+            by: Some(created_by),
+        },
+        context: None,
+        comment: None,
+    };
+    db.review_places(&[place.id.as_str()], ReviewStatus::Draft, &activity_log)?;
+    Ok(place)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -225,6 +293,7 @@ mod tests {
             Some("test@example.com"),
             None,
             &Cfg::default().accepted_licenses,
+            None,
         )
         .unwrap();
         let (_, initial_ratings) = store_new_place(&mock_db, storable).unwrap();
@@ -265,9 +334,15 @@ mod tests {
             custom_links: vec![],
         };
         let mock_db: MockDb = MockDb::default();
-        assert!(
-            prepare_new_place(&mock_db, x, None, None, &Cfg::default().accepted_licenses).is_err()
-        );
+        assert!(prepare_new_place(
+            &mock_db,
+            x,
+            None,
+            None,
+            &Cfg::default().accepted_licenses,
+            None
+        )
+        .is_err());
     }
 
     #[test]
@@ -297,10 +372,81 @@ mod tests {
             custom_links: vec![],
         };
         let mock_db = MockDb::default();
-        let e =
-            prepare_new_place(&mock_db, x, None, None, &Cfg::default().accepted_licenses).unwrap();
+        let e = prepare_new_place(
+            &mock_db,
+            x,
+            None,
+            None,
+            &Cfg::default().accepted_licenses,
+            None,
+        )
+        .unwrap();
         assert!(store_new_place(&mock_db, e).is_ok());
         assert_eq!(mock_db.tags.borrow().len(), 2);
         assert_eq!(mock_db.entries.borrow().len(), 1);
     }
+
+    struct DummyGeoCodingGw;
+
+    impl GeoCodingGateway for DummyGeoCodingGw {
+        fn resolve_address_lat_lng(&self, _: &Address) -> Option<(f64, f64)> {
+            None
+        }
+        fn resolve_lat_lng_address(&self, _: f64, _: f64) -> Option<Address> {
+            Some(Address {
+                city: Some("Stuttgart".into()),
+                ..Default::default()
+            })
+        }
+    }
+
+    #[test]
+    fn flag_auto_filled_address_of_new_place() {
+        #[rustfmt::skip]
+        let x = NewPlace {
+            title       : "foo".into(),
+            description : "bar".into(),
+            lat         : 48.7,
+            lng         : 9.1,
+            street      : None,
+            zip         : None,
+            city        : None,
+            country     : None,
+            state       : None,
+            contact_name: None,
+            email       : None,
+            telephone   : None,
+            homepage    : None,
+            opening_hours: None,
+            founded_on  : None,
+            categories  : vec![],
+            tags        : vec![],
+            license     : "ODbL-1.0".into(),
+            image_url     : None,
+            image_link_url: None,
+            custom_links: vec![],
+        };
+        let mock_db = MockDb::default();
+        let accepted_licenses = &Cfg::default().accepted_licenses;
+        let e =
+            prepare_new_place(&mock_db, x.clone(), None, None, accepted_licenses, None).unwrap();
+        let (place, _) = store_new_place(&mock_db, e).unwrap();
+        assert!(place.location.address.is_none());
+        assert!(!place.address_auto_filled);
+        let e = prepare_new_place(
+            &mock_db,
+            x,
+            None,
+            None,
+            accepted_licenses,
+            Some(&DummyGeoCodingGw),
+        )
+        .unwrap();
+        let (place, _) = store_new_place(&mock_db, e).unwrap();
+        assert_eq!(
+            Some("Stuttgart"),
+            place.location.address.unwrap().city.as_deref()
+        );
+        assert!(place.address_auto_filled);
+    }
 }
@@ -0,0 +1,110 @@
+use super::Region;
+use crate::core::prelude::*;
+use std::collections::BTreeMap;
+
+/// Places without any review or change for this
+/// many days are reported as unreviewed
+pub const UNREVIEWED_DAYS: i64 = 365;
+
+/// The number of reported places is limited for each issue
+pub const MAX_PLACES_PER_QUALITY_ISSUE: u64 = 500;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaceQualityIssues {
+    pub id: Id,
+    pub title: String,
+    pub issues: Vec<PlaceQualityIssue>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RegionQualityIssues {
+    pub region: Region,
+    pub places: Vec<PlaceQualityIssues>,
+}
+
+/// Collects all visible places with quality issues
+/// grouped by their region, i.e. country and state.
+pub fn data_quality_report<D: Db>(db: &D, now: TimestampMs) -> Result<Vec<RegionQualityIssues>> {
+    let unreviewed_since =
+        TimestampMs::from_inner(now.into_inner() - UNREVIEWED_DAYS * 24 * 60 * 60 * 1000);
+    let places_with_issues =
+        db.find_places_with_quality_issues(unreviewed_since, MAX_PLACES_PER_QUALITY_ISSUE)?;
+    Ok(group_by_region(places_with_issues))
+}
+
+fn group_by_region(places_with_issues: Vec<PlaceWithQualityIssue>) -> Vec<RegionQualityIssues> {
+    let mut regions: BTreeMap<Region, Vec<PlaceQualityIssues>> = BTreeMap::new();
+    for place in places_with_issues {
+        let PlaceWithQualityIssue {
+            id,
+            title,
+            country,
+            state,
+            issue,
+        } = place;
+        let places = regions.entry(Region { country, state }).or_default();
+        if let Some(place) = places.iter_mut().find(|p| p.id == id) {
+            place.issues.push(issue);
+        } else {
+            places.push(PlaceQualityIssues {
+                id,
+                title,
+                issues: vec![issue],
+            });
+        }
+    }
+    regions
+        .into_iter()
+        .map(|(region, mut places)| {
+            for place in &mut places {
+                place.issues.sort_unstable();
+            }
+            places.sort_by(|p1, p2| p1.title.cmp(&p2.title));
+            RegionQualityIssues { region, places }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn place_with_issue(id: &str, state: &str, issue: PlaceQualityIssue) -> PlaceWithQualityIssue {
+        PlaceWithQualityIssue {
+            id: id.into(),
+            title: id.to_uppercase(),
+            country: Some("DE".into()),
+            state: Some(state.into()),
+            issue,
+        }
+    }
+
+    #[test]
+    fn group_issues_by_region_and_place() {
+        let report = group_by_region(vec![
+            place_with_issue("b", "BW", PlaceQualityIssue::NoTags),
+            place_with_issue("a", "BY", PlaceQualityIssue::MissingAddress),
+            place_with_issue("c", "BW", PlaceQualityIssue::MissingAddress),
+            place_with_issue("b", "BW", PlaceQualityIssue::MissingAddress),
+        ]);
+        assert_eq!(2, report.len());
+        assert_eq!(Some("BW"), report[0].region.state.as_deref());
+        assert_eq!(
+            vec![
+                PlaceQualityIssues {
+                    id: "b".into(),
+                    title: "B".into(),
+                    issues: vec![PlaceQualityIssue::MissingAddress, PlaceQualityIssue::NoTags],
+                },
+                PlaceQualityIssues {
+                    id: "c".into(),
+                    title: "C".into(),
+                    issues: vec![PlaceQualityIssue::MissingAddress],
+                },
+            ],
+            report[0].places
+        );
+        assert_eq!(Some("BY"), report[1].region.state.as_deref());
+        assert_eq!(1, report[1].places.len());
+    }
+}
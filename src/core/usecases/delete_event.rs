@@ -15,6 +15,16 @@ pub fn delete_event<D: Db>(db: &mut D, token: &str, id: &str) -> Result<()> {
         // if the given organization does not own any tags.
         return Err(Error::Parameter(ParameterError::ModeratedTag));
     }
+    // Deleting an event implicitly removes all of its tags, including
+    // those that are moderated by other organizations
+    match db.get_event(id) {
+        Ok(event) => {
+            super::authorize_editing_of_tagged_entry(&*db, &event.tags, &[], Some(&org))?;
+        }
+        // Archived events are not loaded
+        Err(RepoError::NotFound) => (),
+        Err(err) => return Err(Error::Repo(err)),
+    }
     let deleted = db.delete_event_with_matching_tags(id, &moderated_tags)?;
     if !deleted {
         // No matching tags, i.e. event is not owned by the given organization
@@ -0,0 +1,205 @@
+use super::{find_duplicates::search_nearby_places, tag_usage::Region};
+use crate::core::prelude::*;
+use ofdb_core::gateways::geocode::GeoCodingGateway;
+use std::collections::HashMap;
+
+/// Additional information about an existing place
+/// that helps organizations to improve their own data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnrichedPlace {
+    pub place_id: Id,
+    pub address: Option<Address>,
+    /// The address has been completed by reverse geocoding
+    pub address_resolved: bool,
+    pub region: Region,
+    /// Tags of similar places nearby, most frequent first
+    pub suggested_tags: Vec<String>,
+}
+
+// Every place might require a request to the geocoding provider
+pub const MAX_ENRICHED_PLACES: usize = 100;
+
+const MAX_SUGGESTED_TAGS: usize = 5;
+
+fn is_complete(address: &Address) -> bool {
+    address.street.is_some()
+        && address.zip.is_some()
+        && address.city.is_some()
+        && address.country.is_some()
+}
+
+// Given fields are never replaced
+fn complete_address(given: Option<&Address>, resolved: Address) -> Address {
+    let given = given.cloned().unwrap_or_default();
+    Address {
+        street: given.street.or(resolved.street),
+        zip: given.zip.or(resolved.zip),
+        city: given.city.or(resolved.city),
+        country: given.country.or(resolved.country),
+        state: given.state.or(resolved.state),
+    }
+}
+
+fn resolve_address(gw: &dyn GeoCodingGateway, place: &Place) -> (Option<Address>, bool) {
+    let given = place.location.address.as_ref();
+    if given.map(is_complete).unwrap_or(false) {
+        return (given.cloned(), false);
+    }
+    let (lat, lng) = place.location.pos.to_lat_lng_deg();
+    match gw.resolve_lat_lng_address(lat, lng) {
+        Some(resolved) => {
+            let address = complete_address(given, resolved);
+            let address_resolved = given != Some(&address);
+            (Some(address), address_resolved)
+        }
+        None => (given.cloned(), false),
+    }
+}
+
+// Nearby places are similar if they share at least one tag
+// or, if the place has no tags yet, simply by being nearby.
+fn suggest_tags(place: &Place, nearby_places: &[IndexedPlace]) -> Vec<String> {
+    let mut counts = HashMap::<&str, usize>::new();
+    for nearby in nearby_places {
+        if nearby.id == place.id.as_str() {
+            continue;
+        }
+        let is_similar =
+            place.tags.is_empty() || nearby.tags.iter().any(|t| place.tags.contains(t));
+        if !is_similar {
+            continue;
+        }
+        for tag in nearby.tags.iter().filter(|t| !place.tags.contains(t)) {
+            *counts.entry(tag.as_str()).or_default() += 1;
+        }
+    }
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|(t1, c1), (t2, c2)| c2.cmp(c1).then_with(|| t1.cmp(t2)));
+    counts
+        .into_iter()
+        .take(MAX_SUGGESTED_TAGS)
+        .map(|(tag, _)| tag.to_owned())
+        .collect()
+}
+
+/// Loads the places that should be enriched.
+///
+/// The results are in the same order as the ids.
+pub fn load_places_to_enrich<R: PlaceRepo>(repo: &R, ids: &[&str]) -> Result<Vec<Place>> {
+    if ids.len() > MAX_ENRICHED_PLACES {
+        return Err(Error::Parameter(ParameterError::InvalidLimit));
+    }
+    ids.iter()
+        .map(|id| Ok(repo.get_place_by_id(id)?.0))
+        .collect()
+}
+
+/// Resolves missing address fields, assigns the region and
+/// suggests tags for each of the given places.
+///
+/// This requires a request to the geocoding provider for
+/// every incomplete address and should therefore not be
+/// done while holding a database connection.
+pub fn enrich_places(
+    index: &dyn PlaceIndex,
+    gw: &dyn GeoCodingGateway,
+    places: Vec<Place>,
+) -> Result<Vec<EnrichedPlace>> {
+    let mut results = Vec::with_capacity(places.len());
+    for place in places {
+        let (address, address_resolved) = resolve_address(gw, &place);
+        let region = Region::of_address(address.as_ref());
+        let nearby_places = search_nearby_places(index, place.location.pos)?;
+        let suggested_tags = suggest_tags(&place, &nearby_places);
+        results.push(EnrichedPlace {
+            place_id: place.id,
+            address,
+            address_resolved,
+            region,
+            suggested_tags,
+        });
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct DummyGeoCodingGw;
+
+    impl GeoCodingGateway for DummyGeoCodingGw {
+        fn resolve_address_lat_lng(&self, _: &Address) -> Option<(f64, f64)> {
+            None
+        }
+        fn resolve_lat_lng_address(&self, _: f64, _: f64) -> Option<Address> {
+            Some(Address {
+                street: Some("Schlossplatz 1".into()),
+                zip: Some("70173".into()),
+                city: Some("Stuttgart".into()),
+                country: Some("Germany".into()),
+                state: Some("BW".into()),
+            })
+        }
+    }
+
+    fn indexed_place(id: &str, tags: &[&str]) -> IndexedPlace {
+        IndexedPlace {
+            id: id.into(),
+            status: None,
+            pos: MapPoint::from_lat_lng_deg(48.7, 9.1),
+            title: id.into(),
+            description: String::new(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ratings: Default::default(),
+            quality: 0,
+            address: None,
+        }
+    }
+
+    #[test]
+    fn complete_missing_address_fields() {
+        let mut place = Place::build()
+            .pos(MapPoint::from_lat_lng_deg(48.7, 9.1))
+            .finish();
+        place.location.address = Some(Address {
+            street: Some("Königstraße 1".into()),
+            ..Default::default()
+        });
+        let (address, resolved) = resolve_address(&DummyGeoCodingGw, &place);
+        assert!(resolved);
+        let address = address.unwrap();
+        assert_eq!(Some("Königstraße 1"), address.street.as_deref());
+        assert_eq!(Some("Stuttgart"), address.city.as_deref());
+        assert_eq!(
+            Region {
+                country: Some("Germany".into()),
+                state: Some("BW".into()),
+            },
+            Region::of_address(Some(&address))
+        );
+
+        place.location.address = Some(address.clone());
+        assert_eq!(
+            (Some(address), false),
+            resolve_address(&DummyGeoCodingGw, &place)
+        );
+    }
+
+    #[test]
+    fn suggest_tags_of_similar_places() {
+        let place = Place::build().id("a").tags(vec!["bio"]).finish();
+        let nearby = vec![
+            indexed_place("a", &["bio", "self"]),
+            indexed_place("b", &["bio", "vegan", "fair"]),
+            indexed_place("c", &["bio", "vegan"]),
+            indexed_place("d", &["repair", "vegan"]),
+        ];
+        assert_eq!(vec!["vegan", "fair"], suggest_tags(&place, &nearby));
+        let untagged = Place::build().id("a").finish();
+        assert_eq!(
+            vec!["vegan", "bio", "fair", "repair"],
+            suggest_tags(&untagged, &nearby)
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use crate::core::prelude::*;
+use ofdb_entities::geo::MapBbox;
+
+/// All unarchived ratings of the visible places within the
+/// bounding box, each together with its unarchived comments.
+pub fn export_ratings<D: Db>(
+    db: &D,
+    index: &dyn PlaceIndex,
+    bbox: MapBbox,
+) -> Result<Vec<(IndexedPlace, Vec<(Rating, Vec<Comment>)>)>> {
+    let query = IndexQuery {
+        include_bbox: Some(bbox),
+        // Only visible places
+        status: Some(vec![]),
+        ..Default::default()
+    };
+    // Unlimited
+    let limit = db.count_places()? + 100;
+    let places = index
+        .query_places(&query, limit)
+        .map_err(RepoError::Other)?;
+    let mut results = Vec::with_capacity(places.len());
+    for place in places {
+        let ratings = db.load_ratings_of_place(&place.id)?;
+        if ratings.is_empty() {
+            continue;
+        }
+        let ratings = db.zip_ratings_with_comments(ratings)?;
+        results.push((place, ratings));
+    }
+    Ok(results)
+}
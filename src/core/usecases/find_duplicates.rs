@@ -1,24 +1,52 @@
-use crate::core::{prelude::*, usecases::NewPlace};
-use std::{cmp::min, collections::HashSet};
+use crate::core::{prelude::*, usecases::NewPlace, util::address::is_same_address};
+use std::{
+    cmp::min,
+    collections::{BTreeMap, HashMap, HashSet},
+};
 
 #[derive(Debug, PartialEq, Serialize)]
 pub enum DuplicateType {
     SimilarChars,
     SimilarWords,
+    SameAddress,
 }
 
 // Return vector of places like: (entry1ID, entry2ID, reason)
 // where entry1 and entry2 are similar places.
+//
+// The places are grouped by their geohash cell. Nearby places
+// are queried once per cell and only compared with places
+// in the neighboring cells.
 pub fn find_duplicates(
     place_index: &dyn PlaceIndex,
     places: &[(Place, ReviewStatus)],
 ) -> Result<Vec<(Id, Id, DuplicateType)>> {
+    let mut places_by_cell: BTreeMap<GeoCell, Vec<&Place>> = BTreeMap::new();
+    for (place, _) in places {
+        places_by_cell
+            .entry(GeoCell::of(place.location.pos))
+            .or_default()
+            .push(place);
+    }
     let mut duplicates = Vec::new();
-    for (p1, _) in places {
-        let nearby_places = search_nearby_places(place_index, p1.location.pos)?;
+    for cell_places in places_by_cell.values() {
+        let nearby_places = search_nearby_places_of_all(place_index, cell_places)?;
+        let mut nearby_places_by_cell: HashMap<GeoCell, Vec<IndexedPlace>> = HashMap::new();
         for p2 in nearby_places {
-            if let Some(t) = is_duplicate(p1, &p2) {
-                duplicates.push((p1.id.clone(), Id::from(p2.id), t));
+            nearby_places_by_cell
+                .entry(GeoCell::of(p2.pos))
+                .or_default()
+                .push(p2);
+        }
+        for p1 in cell_places {
+            let nearby_places = GeoCell::of(p1.location.pos)
+                .neighbors(MAX_NEARBY_RADIUS)
+                .filter_map(|cell| nearby_places_by_cell.get(&cell))
+                .flatten();
+            for p2 in nearby_places {
+                if let Some(t) = is_duplicate(p1, p2) {
+                    duplicates.push((p1.id.clone(), p2.id.as_str().into(), t));
+                }
             }
         }
     }
@@ -37,15 +65,23 @@ pub fn retain_duplicates_of(
 
 const MAX_NEARBY_RESULTS: usize = 1000;
 
-const MAX_NEARBY_RADIUS: Distance = Distance::from_meters(100.0);
+// The nearby places of all places within the same geohash cell
+const MAX_NEARBY_RESULTS_PER_CELL: usize = 10 * MAX_NEARBY_RESULTS;
+
+pub(super) const MAX_NEARBY_RADIUS: Distance = Distance::from_meters(100.0);
 
 const MAX_NEARBY_DIAMETER: Distance = Distance::from_meters(MAX_NEARBY_RADIUS.to_meters() * 2.0);
 
-const MAX_TEXT_RELATIVE_EDIT_DISTANCE: f64 = 0.3; // max. 30% text difference
+pub(super) const MAX_TEXT_RELATIVE_EDIT_DISTANCE: f64 = 0.3; // max. 30% text difference
+
+pub(super) const MAX_WORDS_HAMMING_DISTANCE: u32 = 2; // up to 2 words may differ
+
+// Titles of places at the same address may differ more
+const MAX_TEXT_RELATIVE_EDIT_DISTANCE_AT_SAME_ADDRESS: f64 = 0.5;
 
-const MAX_WORDS_HAMMING_DISTANCE: u32 = 2; // up to 2 words may differ
+const MAX_WORDS_HAMMING_DISTANCE_AT_SAME_ADDRESS: u32 = 3;
 
-fn search_nearby_places(
+pub(super) fn search_nearby_places(
     place_index: &dyn crate::core::db::PlaceIndex,
     center: MapPoint,
 ) -> Result<Vec<IndexedPlace>> {
@@ -59,6 +95,38 @@ fn search_nearby_places(
         .map_err(RepoError::Other)?)
 }
 
+// Queries the union of the nearby areas of all places at once
+fn search_nearby_places_of_all(
+    place_index: &dyn crate::core::db::PlaceIndex,
+    places: &[&Place],
+) -> Result<Vec<IndexedPlace>> {
+    if places.is_empty() {
+        return Ok(vec![]);
+    }
+    let (mut sw_lat, mut sw_lng) = (f64::MAX, f64::MAX);
+    let (mut ne_lat, mut ne_lng) = (f64::MIN, f64::MIN);
+    for place in places {
+        let bbox = nearby_bbox(place.location.pos);
+        let (lat, lng) = bbox.southwest().to_lat_lng_deg();
+        sw_lat = sw_lat.min(lat);
+        sw_lng = sw_lng.min(lng);
+        let (lat, lng) = bbox.northeast().to_lat_lng_deg();
+        ne_lat = ne_lat.max(lat);
+        ne_lng = ne_lng.max(lng);
+    }
+    let nearby_bbox = MapBbox::new(
+        MapPoint::from_lat_lng_deg(sw_lat, sw_lng),
+        MapPoint::from_lat_lng_deg(ne_lat, ne_lng),
+    );
+    let nearby_query = crate::core::db::IndexQuery {
+        include_bbox: Some(nearby_bbox),
+        ..Default::default()
+    };
+    Ok(place_index
+        .query_places(&nearby_query, MAX_NEARBY_RESULTS_PER_CELL)
+        .map_err(RepoError::Other)?)
+}
+
 pub fn search_duplicates(
     place_index: &dyn crate::core::db::PlaceIndex,
     new_place: &NewPlace,
@@ -75,6 +143,69 @@ pub fn nearby_bbox(center: MapPoint) -> MapBbox {
     MapBbox::centered_around(center, MAX_NEARBY_DIAMETER, MAX_NEARBY_DIAMETER)
 }
 
+// Number of bits of the latitude and longitude in a geohash with
+// 7 characters, i.e. cells of about 150 x 150 m at the equator
+const GEOHASH_LAT_BITS: u32 = 17;
+const GEOHASH_LNG_BITS: u32 = 18;
+
+// Lower bound of the length of one degree of latitude or
+// longitude (at the equator), i.e. of the size of the cells
+const MIN_METERS_PER_DEGREE: f64 = 110_574.0;
+
+/// A cell of the geohash grid, identified by the
+/// row (latitude) and column (longitude) in the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct GeoCell {
+    row: i64,
+    col: i64,
+}
+
+impl GeoCell {
+    const ROWS: i64 = 1 << GEOHASH_LAT_BITS;
+    const COLS: i64 = 1 << GEOHASH_LNG_BITS;
+    const HEIGHT_DEG: f64 = 180.0 / Self::ROWS as f64;
+    const WIDTH_DEG: f64 = 360.0 / Self::COLS as f64;
+
+    fn of(pos: MapPoint) -> Self {
+        let (lat, lng) = pos.to_lat_lng_deg();
+        let row = ((lat + 90.0) / Self::HEIGHT_DEG).floor() as i64;
+        let col = ((lng + 180.0) / Self::WIDTH_DEG).floor() as i64;
+        Self {
+            // The north pole and the antimeridian belong to the last cell
+            row: row.min(Self::ROWS - 1),
+            col: col.min(Self::COLS - 1),
+        }
+    }
+
+    /// All cells that might contain points within the
+    /// given distance of any point in this cell.
+    fn neighbors(self, distance: Distance) -> impl Iterator<Item = GeoCell> {
+        let distance_deg = distance.to_meters() / MIN_METERS_PER_DEGREE;
+        let row_steps = (distance_deg / Self::HEIGHT_DEG).ceil() as i64;
+        // The cells get narrower towards the poles
+        let max_abs_lat = ((self.row + 1) as f64 * Self::HEIGHT_DEG - 90.0)
+            .abs()
+            .max((self.row as f64 * Self::HEIGHT_DEG - 90.0).abs());
+        let col_steps = (distance_deg / (Self::WIDTH_DEG * max_abs_lat.to_radians().cos()))
+            .ceil()
+            .min(Self::COLS as f64) as i64;
+        let (first_col, col_count) = if 2 * col_steps + 1 < Self::COLS {
+            (self.col - col_steps, 2 * col_steps + 1)
+        } else {
+            // All cells of the rows close to the poles
+            (0, Self::COLS)
+        };
+        let rows = (self.row - row_steps).max(0)..=(self.row + row_steps).min(Self::ROWS - 1);
+        rows.flat_map(move |row| {
+            (first_col..first_col + col_count).map(move |col| GeoCell {
+                row,
+                // Wrap around at the antimeridian
+                col: col.rem_euclid(Self::COLS),
+            })
+        })
+    }
+}
+
 // returns a DuplicateType if the two places have a similar title and location, otherweise returns None.
 fn is_duplicate(e1: &Place, e2: &IndexedPlace) -> Option<DuplicateType> {
     if e1.id.as_str() == e2.id.as_str() {
@@ -90,10 +221,27 @@ fn is_duplicate(e1: &Place, e2: &IndexedPlace) -> Option<DuplicateType> {
     if is_similar_text(&e1.title, &e2.title, 0.0, MAX_WORDS_HAMMING_DISTANCE) {
         return Some(DuplicateType::SimilarWords);
     }
+    if is_at_same_address(e1.location.address.as_ref(), e2.address.as_ref())
+        && is_similar_text(
+            &e1.title,
+            &e2.title,
+            MAX_TEXT_RELATIVE_EDIT_DISTANCE_AT_SAME_ADDRESS,
+            MAX_WORDS_HAMMING_DISTANCE_AT_SAME_ADDRESS,
+        )
+    {
+        return Some(DuplicateType::SameAddress);
+    }
     None
 }
 
-fn is_similar_text(
+fn is_at_same_address(a1: Option<&Address>, a2: Option<&Address>) -> bool {
+    match (a1, a2) {
+        (Some(a1), Some(a2)) => is_same_address(a1, a2),
+        _ => false,
+    }
+}
+
+pub(super) fn is_similar_text(
     text1: &str,
     text2: &str,
     max_text_relative_edit_distance: f64,
@@ -110,19 +258,31 @@ fn is_duplicate_of(new_place: &NewPlace, indexed_place: &IndexedPlace) -> bool {
     if !is_in_close_proximity_pos(&pos, &indexed_place.pos, MAX_NEARBY_RADIUS) {
         return false;
     }
-    // TODO: Compare more (text) fields than just the title?
-    if !is_similar_text(
+    let address = Address {
+        street: new_place.street.clone(),
+        zip: new_place.zip.clone(),
+        city: new_place.city.clone(),
+        country: new_place.country.clone(),
+        state: new_place.state.clone(),
+    };
+    let (max_text_relative_edit_distance, max_words_hamming_distance) =
+        if is_at_same_address(Some(&address), indexed_place.address.as_ref()) {
+            (
+                MAX_TEXT_RELATIVE_EDIT_DISTANCE_AT_SAME_ADDRESS,
+                MAX_WORDS_HAMMING_DISTANCE_AT_SAME_ADDRESS,
+            )
+        } else {
+            (MAX_TEXT_RELATIVE_EDIT_DISTANCE, MAX_WORDS_HAMMING_DISTANCE)
+        };
+    is_similar_text(
         &new_place.title,
         &indexed_place.title,
-        MAX_TEXT_RELATIVE_EDIT_DISTANCE,
-        MAX_WORDS_HAMMING_DISTANCE,
-    ) {
-        return false;
-    }
-    true
+        max_text_relative_edit_distance,
+        max_words_hamming_distance,
+    )
 }
 
-fn is_in_close_proximity_pos(p1: &MapPoint, p2: &MapPoint, max_dist: Distance) -> bool {
+pub(super) fn is_in_close_proximity_pos(p1: &MapPoint, p2: &MapPoint, max_dist: Distance) -> bool {
     if let Some(dist) = MapPoint::distance(*p1, *p2) {
         return dist <= max_dist;
     }
@@ -166,53 +326,53 @@ fn words_equal_except_k_words(str1: &str, str2: &str, k: u32) -> bool {
 // Levenshtein Distance more realistically captures typos (all of the following
 // operations are counted as distance 1: add one character in between, delete
 // one character, change one character)
-// but it proved to be way too slow to be run on the whole dataset
 fn levenshtein_distance_small(s: &str, t: &str, max_dist: usize) -> bool {
-    levenshtein_distance(s, t) <= max_dist
+    let (s, t): (Vec<_>, Vec<_>) = (s.chars().collect(), t.chars().collect());
+    // The distance is at least the difference of the lengths
+    if s.len().max(t.len()) - s.len().min(t.len()) > max_dist {
+        return false;
+    }
+    levenshtein_distance_of_chars(&s, &t, Some(max_dist)) <= max_dist
+}
+
+pub(super) fn levenshtein_distance(s: &str, t: &str) -> usize {
+    let (s, t): (Vec<_>, Vec<_>) = (s.chars().collect(), t.chars().collect());
+    levenshtein_distance_of_chars(&s, &t, None)
 }
 
 // Algorithm from
-// https://en.wikipedia.org/wiki/Levenshtein_distance#Computing_Levenshtein_distance
-fn levenshtein_distance(s: &str, t: &str) -> usize {
-    let max_s: usize = s.len() + 1;
-    let max_t: usize = t.len() + 1;
-
-    // for all i and j, d[i,j] will hold the Levenshtein distance between
-    // the first i characters of s and the first j characters of t
-    // comment that d has (m+1)*(n+1) values
-    let mut d: Vec<Vec<usize>> = vec![];
-    for _ in 0..max_s {
-        d.push(vec![0; max_t]);
-    }
-
-    // source (s) prefixes can be transformed into empty string by
-    // dropping all characters
-    for (i, item) in d.iter_mut().enumerate().take(max_s).skip(1) {
-        item[0] = i;
-    }
-
-    // target (t) prefixes can be reached from empty source prefix
-    // by inserting every character
-    for j in 1..max_t {
-        d[0][j] = j;
-    }
-
-    for j in 1..max_t {
-        for i in 1..max_s {
-            let substitution_cost = if s.chars().nth(i) == t.chars().nth(j) {
-                0
-            } else {
-                1
-            };
-            d[i][j] = min3(
-                d[i - 1][j] + 1,                     // deletion
-                d[i][j - 1] + 1,                     // insertion
-                d[i - 1][j - 1] + substitution_cost, // substitution
-            )
+// https://en.wikipedia.org/wiki/Levenshtein_distance#Iterative_with_two_matrix_rows
+//
+// Only the previous row of the matrix is kept. The calculation stops
+// early if all distances in a row exceed the maximum distance.
+fn levenshtein_distance_of_chars(s: &[char], t: &[char], max_dist: Option<usize>) -> usize {
+    // the distances between the first i characters of s and
+    // the first j characters of t for the previous and current i
+    let mut prev_row: Vec<usize> = (0..=t.len()).collect();
+    let mut row = vec![0; t.len() + 1];
+
+    for (i, s_char) in s.iter().enumerate() {
+        // source (s) prefixes can be transformed into empty string by
+        // dropping all characters
+        row[0] = i + 1;
+        for (j, t_char) in t.iter().enumerate() {
+            let substitution_cost = if s_char == t_char { 0 } else { 1 };
+            row[j + 1] = min3(
+                prev_row[j + 1] + 1,             // deletion
+                row[j] + 1,                      // insertion
+                prev_row[j] + substitution_cost, // substitution
+            );
         }
+        if let Some(max_dist) = max_dist {
+            let min_dist = row.iter().copied().min().unwrap_or_default();
+            if min_dist > max_dist {
+                return min_dist;
+            }
+        }
+        std::mem::swap(&mut prev_row, &mut row);
     }
 
-    d[max_s - 1][max_t - 1]
+    prev_row[t.len()]
 }
 
 fn min3(s: usize, t: usize, u: usize) -> usize {
@@ -406,6 +566,61 @@ mod tests {
         assert_eq!(None, is_duplicate(&p4, &ip5));
     }
 
+    #[test]
+    fn test_is_duplicate_at_same_address() {
+        let pos = MapPoint::from_lat_lng_deg(48.7755, 9.1827);
+        let address = |street: &str| Address {
+            street: Some(street.into()),
+            zip: Some("70173".into()),
+            city: Some("Stuttgart".into()),
+            country: Some("DE".into()),
+            state: None,
+        };
+        let mut p1 = new_place("Weltladen".to_string(), "".to_string(), pos);
+        let mut ip2 = new_indexed_place("Weltladen an der Kirche".to_string(), "".to_string(), pos);
+        assert_eq!(None, is_duplicate(&p1, &ip2));
+
+        p1.location.address = Some(address("Königstraße 1"));
+        // Streets are not compared fuzzy
+        ip2.address = Some(Address {
+            zip: Some("701 73".into()),
+            ..address("KÖNIGSTRASSE 1")
+        });
+        assert_eq!(None, is_duplicate(&p1, &ip2));
+        ip2.address = Some(Address {
+            zip: Some("701 73".into()),
+            ..address("königstraße  1")
+        });
+        assert_eq!(Some(DuplicateType::SameAddress), is_duplicate(&p1, &ip2));
+
+        let new_place = NewPlace {
+            title: "Weltladen".into(),
+            description: "".into(),
+            lat: pos.lat().to_deg(),
+            lng: pos.lng().to_deg(),
+            street: Some("Königstraße 1".into()),
+            zip: None,
+            city: Some("stuttgart".into()),
+            country: None,
+            state: None,
+            contact_name: None,
+            email: None,
+            telephone: None,
+            homepage: None,
+            opening_hours: None,
+            founded_on: None,
+            categories: vec![],
+            tags: vec![],
+            license: "ODbL-1.0".into(),
+            image_url: None,
+            image_link_url: None,
+            custom_links: vec![],
+        };
+        assert!(is_duplicate_of(&new_place, &ip2));
+        ip2.address = None;
+        assert!(!is_duplicate_of(&new_place, &ip2));
+    }
+
     #[test]
     fn test_min() {
         assert_eq!(1, min3(1, 2, 3));
@@ -428,4 +643,47 @@ mod tests {
         assert_eq!(1, levenshtein_distance("12345", "a12345")); // insert a
         assert_eq!(1, levenshtein_distance("aabaa", "aacaa")); // replace b by c
     }
+
+    #[test]
+    fn test_levenshtein_distance_of_multibyte_chars() {
+        assert_eq!(1, levenshtein_distance("Bioläden", "Bioladen"));
+        assert_eq!(2, levenshtein_distance("", "äö"));
+        assert!(levenshtein_distance_small("Bioläden", "Bioladen", 1));
+        assert!(!levenshtein_distance_small("Weltladen", "Bioladen", 3));
+        assert!(!levenshtein_distance_small("Laden", "Bioladen", 2));
+    }
+
+    #[test]
+    fn test_neighboring_geo_cells() {
+        let radius = MAX_NEARBY_RADIUS;
+        let offset = 0.95 * radius.to_meters() / MIN_METERS_PER_DEGREE;
+        let positions = [
+            (0.0, 0.0),
+            (48.7755, 9.1827),
+            (69.6492, 18.9553),
+            (-33.9, 179.9999),
+        ];
+        for &(lat, lng) in &positions {
+            let p1 = MapPoint::from_lat_lng_deg(lat, lng);
+            let neighbors: HashSet<_> = GeoCell::of(p1).neighbors(radius).collect();
+            assert!(neighbors.contains(&GeoCell::of(p1)));
+            // All nearby points are located in a neighboring cell
+            let lng_offset = offset / f64::to_radians(lat).cos();
+            for &(north, east) in &[
+                (1.0, 0.0),
+                (0.0, 1.0),
+                (-1.0, 0.0),
+                (0.0, -1.0),
+                (0.7, -0.7),
+            ] {
+                let mut lng2 = lng + east * lng_offset;
+                if lng2 > 180.0 {
+                    lng2 -= 360.0;
+                }
+                let p2 = MapPoint::from_lat_lng_deg(lat + north * offset, lng2);
+                assert!(is_in_close_proximity_pos(&p1, &p2, radius));
+                assert!(neighbors.contains(&GeoCell::of(p2)));
+            }
+        }
+    }
 }
@@ -0,0 +1,152 @@
+use crate::core::{prelude::*, util::time_zone::time_zone_at};
+use ofdb_core::gateways::geocode::GeoCodingGateway;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeoCodingOutcome {
+    /// The location of the event has been completed
+    Updated,
+    /// Nothing to resolve
+    Unchanged,
+    /// The location could not be resolved (yet)
+    Unresolved,
+}
+
+/// Resolve the position of an event from its address or,
+/// if requested, the missing address from its position.
+pub fn geocode_event_location(
+    gw: &dyn GeoCodingGateway,
+    event: &mut Event,
+    auto_fill_address: bool,
+) -> GeoCodingOutcome {
+    let location = match event.location.as_mut() {
+        Some(location) => location,
+        None => return GeoCodingOutcome::Unchanged,
+    };
+    if !location.pos.is_valid() {
+        let addr = match location.address.as_ref().filter(|addr| !addr.is_empty()) {
+            Some(addr) => addr,
+            None => return GeoCodingOutcome::Unchanged,
+        };
+        let pos = gw
+            .resolve_address_lat_lng(addr)
+            .and_then(|(lat, lng)| MapPoint::try_from_lat_lng_deg(lat, lng).ok())
+            .filter(|pos| pos.is_valid());
+        return match pos {
+            Some(pos) => {
+                debug!("Resolved location of event {}: {:?}", event.id, pos);
+                location.pos = pos;
+                if event.time_zone.is_none() {
+                    event.time_zone = time_zone_at(pos);
+                }
+                GeoCodingOutcome::Updated
+            }
+            None => GeoCodingOutcome::Unresolved,
+        };
+    }
+    if auto_fill_address && location.address.as_ref().map_or(true, Address::is_empty) {
+        let (lat, lng) = location.pos.to_lat_lng_deg();
+        return match gw.resolve_lat_lng_address(lat, lng) {
+            Some(addr) => {
+                debug!("Auto-filled address of event {}: {:?}", event.id, addr);
+                location.address = Some(addr);
+                event.address_auto_filled = true;
+                GeoCodingOutcome::Updated
+            }
+            None => GeoCodingOutcome::Unresolved,
+        };
+    }
+    GeoCodingOutcome::Unchanged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    struct DummyGeoCodingGw;
+
+    impl GeoCodingGateway for DummyGeoCodingGw {
+        fn resolve_address_lat_lng(&self, _: &Address) -> Option<(f64, f64)> {
+            Some((48.7, 9.1))
+        }
+        fn resolve_lat_lng_address(&self, _: f64, _: f64) -> Option<Address> {
+            Some(Address {
+                city: Some("Stuttgart".into()),
+                ..Default::default()
+            })
+        }
+    }
+
+    fn new_event(location: Option<Location>) -> Event {
+        Event {
+            id: "x".into(),
+            title: "t".into(),
+            description: None,
+            start: NaiveDateTime::from_timestamp(0, 0),
+            end: None,
+            location,
+            contact: None,
+            tags: vec![],
+            homepage: None,
+            created_by: None,
+            registration: None,
+            archived: None,
+            image_url: None,
+            image_link_url: None,
+            time_zone: None,
+            recurrence: None,
+            address_auto_filled: false,
+            publish_at: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn resolve_position_from_address() {
+        let mut e = new_event(Some(Location {
+            pos: MapPoint::default(),
+            address: Some(Address {
+                city: Some("Stuttgart".into()),
+                ..Default::default()
+            }),
+        }));
+        assert_eq!(
+            GeoCodingOutcome::Updated,
+            geocode_event_location(&DummyGeoCodingGw, &mut e, false)
+        );
+        assert!(e.location.unwrap().pos.is_valid());
+    }
+
+    #[test]
+    fn auto_fill_address_only_if_requested() {
+        let location = Location {
+            pos: MapPoint::from_lat_lng_deg(48.7, 9.1),
+            address: None,
+        };
+        let mut e = new_event(Some(location.clone()));
+        assert_eq!(
+            GeoCodingOutcome::Unchanged,
+            geocode_event_location(&DummyGeoCodingGw, &mut e, false)
+        );
+        assert_eq!(Some(location), e.location);
+        assert_eq!(
+            GeoCodingOutcome::Updated,
+            geocode_event_location(&DummyGeoCodingGw, &mut e, true)
+        );
+        assert!(e.address_auto_filled);
+        assert_eq!(
+            Some("Stuttgart"),
+            e.location.unwrap().address.unwrap().city.as_deref()
+        );
+    }
+
+    #[test]
+    fn ignore_events_without_location() {
+        let mut e = new_event(None);
+        assert_eq!(
+            GeoCodingOutcome::Unchanged,
+            geocode_event_location(&DummyGeoCodingGw, &mut e, true)
+        );
+    }
+}
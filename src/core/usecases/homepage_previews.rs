@@ -0,0 +1,124 @@
+use crate::core::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HomepagePreviewReport {
+    pub fetched: usize,
+    /// Disallowed by the `robots.txt` of the site
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/// Collects the distinct homepages of all existing places
+/// that have not been previewed since `fetched_since`.
+pub fn homepages_to_preview<D: Db>(db: &D, fetched_since: Timestamp) -> Result<Vec<String>> {
+    let homepages: BTreeSet<_> = db
+        .all_places()?
+        .into_iter()
+        .filter(|(_, status)| status.exists())
+        .filter_map(|(place, _)| place.links.and_then(|l| l.homepage))
+        .map(Url::into_string)
+        .collect();
+    let urls: Vec<_> = homepages.iter().map(String::as_str).collect();
+    let recent: BTreeSet<_> = db
+        .load_homepage_previews(&urls)?
+        .into_iter()
+        .filter(|p| p.fetched_at >= fetched_since)
+        .map(|p| p.url)
+        .collect();
+    Ok(homepages.difference(&recent).cloned().collect())
+}
+
+/// Loads the previews of the homepages of the given places.
+///
+/// Places without a homepage or without a preview with
+/// at least a title or an image are omitted.
+pub fn load_homepage_previews_of_places<R: PlaceRepo + HomepagePreviewRepo>(
+    repo: &R,
+    ids: &[&str],
+) -> Result<BTreeMap<Id, HomepagePreview>> {
+    let homepages: Vec<_> = repo
+        .get_places_by_ids(ids)?
+        .into_iter()
+        .filter_map(|(place, _)| {
+            let homepage = place.links?.homepage?;
+            Some((place.id, homepage.into_string()))
+        })
+        .collect();
+    let urls: Vec<_> = homepages.iter().map(|(_, url)| url.as_str()).collect();
+    let mut previews: HashMap<_, _> = repo
+        .load_homepage_previews(&urls)?
+        .into_iter()
+        .filter(|p| p.title.is_some() || p.image_url.is_some())
+        .map(|p| (p.url.clone(), p))
+        .collect();
+    Ok(homepages
+        .into_iter()
+        .filter_map(|(id, url)| previews.remove(&url).map(|p| (id, p)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn place_with_homepage(id: &str, homepage: &str) -> Place {
+        let mut place = Place::build().id(id).title(id).finish();
+        place.links = Some(Links {
+            homepage: Some(homepage.parse().unwrap()),
+            image: None,
+            image_href: None,
+            custom: vec![],
+        });
+        place
+    }
+
+    fn preview(url: &str, title: Option<&str>, fetched_at: i64) -> HomepagePreview {
+        HomepagePreview {
+            url: url.into(),
+            title: title.map(Into::into),
+            image_url: None,
+            fetched_at: Timestamp::from_inner(fetched_at),
+        }
+    }
+
+    #[test]
+    fn preview_homepages_without_recent_preview() {
+        let db = MockDb::default();
+        db.entries.borrow_mut().extend(vec![
+            (
+                place_with_homepage("a", "https://a.example.com/"),
+                ReviewStatus::Created,
+            ),
+            (
+                place_with_homepage("b", "https://b.example.com/"),
+                ReviewStatus::Confirmed,
+            ),
+            (
+                place_with_homepage("c", "https://c.example.com/"),
+                ReviewStatus::Confirmed,
+            ),
+            (
+                place_with_homepage("d", "https://d.example.com/"),
+                ReviewStatus::Rejected,
+            ),
+        ]);
+        db.store_homepage_preview(&preview("https://a.example.com/", Some("A"), 100))
+            .unwrap();
+        db.store_homepage_preview(&preview("https://b.example.com/", Some("B"), 10))
+            .unwrap();
+        assert_eq!(
+            vec!["https://b.example.com/", "https://c.example.com/"],
+            homepages_to_preview(&db, Timestamp::from_inner(50)).unwrap()
+        );
+
+        // Empty previews of pages that must not be fetched are omitted
+        db.store_homepage_preview(&preview("https://c.example.com/", None, 100))
+            .unwrap();
+        let previews = load_homepage_previews_of_places(&db, &["a", "b", "c"]).unwrap();
+        assert_eq!(2, previews.len());
+        assert_eq!(Some("A"), previews[&Id::from("a")].title.as_deref());
+        assert_eq!(Some("B"), previews[&Id::from("b")].title.as_deref());
+    }
+}
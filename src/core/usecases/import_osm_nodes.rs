@@ -0,0 +1,219 @@
+use super::NewPlace;
+use crate::core::entities::Id;
+use ofdb_core::gateways::osm::OsmNode;
+
+/// OSM data is licensed under the Open Database License
+pub const OSM_LICENSE: &str = "ODbL-1.0";
+
+/// Maps OSM tags to categories and tags.
+#[derive(Debug, Clone, Default)]
+pub struct OsmTagMapping {
+    pub rules: Vec<OsmTagMappingRule>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OsmTagMappingRule {
+    pub key: String,
+    // None matches any value of the key
+    pub value: Option<String>,
+    pub categories: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+impl OsmTagMappingRule {
+    fn matches(&self, node: &OsmNode) -> bool {
+        match (node.tags.get(&self.key), &self.value) {
+            (Some(_), None) => true,
+            (Some(v1), Some(v2)) => v1 == v2,
+            (None, _) => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsmImportOutcome {
+    // Would have been imported in a dry run
+    Imported,
+    AlreadyImported,
+    Duplicate,
+    Unmapped,
+    Failed,
+}
+
+/// The result of importing a single OSM node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OsmImportRow {
+    pub osm_node_id: u64,
+    pub outcome: OsmImportOutcome,
+    // The mapped place, i.e. the changes
+    pub title: Option<String>,
+    pub categories: Vec<String>,
+    pub tags: Vec<String>,
+    // The (would-be) imported place, the already imported
+    // place or the places that are considered as duplicates
+    pub place_ids: Vec<Id>,
+    // The reason why the node could not be imported
+    pub error: Option<String>,
+}
+
+impl OsmImportRow {
+    pub fn new(osm_node_id: u64, outcome: OsmImportOutcome, new_place: Option<&NewPlace>) -> Self {
+        Self {
+            osm_node_id,
+            outcome,
+            title: new_place.map(|p| p.title.clone()),
+            categories: new_place.map(|p| p.categories.clone()).unwrap_or_default(),
+            tags: new_place.map(|p| p.tags.clone()).unwrap_or_default(),
+            place_ids: vec![],
+            error: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OsmImportReport {
+    pub imported: usize,
+    pub already_imported: usize,
+    pub duplicates: usize,
+    pub unmapped: usize,
+    pub failed: usize,
+    pub rows: Vec<OsmImportRow>,
+}
+
+impl OsmImportReport {
+    pub fn add_row(&mut self, row: OsmImportRow) {
+        match row.outcome {
+            OsmImportOutcome::Imported => self.imported += 1,
+            OsmImportOutcome::AlreadyImported => self.already_imported += 1,
+            OsmImportOutcome::Duplicate => self.duplicates += 1,
+            OsmImportOutcome::Unmapped => self.unmapped += 1,
+            OsmImportOutcome::Failed => self.failed += 1,
+        }
+        self.rows.push(row);
+    }
+}
+
+pub(super) fn osm_street(node: &OsmNode) -> Option<String> {
+    let street = node.tags.get("addr:street")?;
+    Some(match node.tags.get("addr:housenumber") {
+        Some(nr) => format!("{} {}", street, nr),
+        None => street.clone(),
+    })
+}
+
+/// Returns `None` if the node has no name or doesn't match any rule.
+pub fn new_place_from_osm_node(node: &OsmNode, mapping: &OsmTagMapping) -> Option<NewPlace> {
+    let title = node.tags.get("name")?.trim().to_string();
+    if title.is_empty() {
+        return None;
+    }
+    let mut categories = vec![];
+    let mut tags = vec![];
+    for rule in mapping.rules.iter().filter(|rule| rule.matches(node)) {
+        categories.extend(rule.categories.iter().cloned());
+        tags.extend(rule.tags.iter().cloned());
+    }
+    if categories.is_empty() && tags.is_empty() {
+        return None;
+    }
+    categories.sort_unstable();
+    categories.dedup();
+    tags.sort_unstable();
+    tags.dedup();
+    let tag = |key: &str| node.tags.get(key).cloned();
+    Some(NewPlace {
+        description: tag("description").unwrap_or_else(|| title.clone()),
+        title,
+        lat: node.lat,
+        lng: node.lon,
+        street: osm_street(node),
+        zip: tag("addr:postcode"),
+        city: tag("addr:city"),
+        country: tag("addr:country"),
+        state: tag("addr:state"),
+        contact_name: None,
+        email: tag("email").or_else(|| tag("contact:email")),
+        telephone: tag("phone").or_else(|| tag("contact:phone")),
+        homepage: tag("website").or_else(|| tag("contact:website")),
+        opening_hours: tag("opening_hours"),
+        founded_on: None,
+        categories,
+        tags,
+        license: OSM_LICENSE.to_string(),
+        image_url: None,
+        image_link_url: None,
+        custom_links: vec![],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn organic_shop_mapping() -> OsmTagMapping {
+        OsmTagMapping {
+            rules: vec![
+                OsmTagMappingRule {
+                    key: "shop".into(),
+                    value: Some("organic".into()),
+                    categories: vec!["2cd00bebec0c48ba9db761da48678134".into()],
+                    tags: vec!["bio".into()],
+                },
+                OsmTagMappingRule {
+                    key: "organic".into(),
+                    value: None,
+                    categories: vec![],
+                    tags: vec!["organic".into()],
+                },
+            ],
+        }
+    }
+
+    fn node(tags: &[(&str, &str)]) -> OsmNode {
+        OsmNode {
+            id: 1,
+            lat: 48.7,
+            lon: 9.1,
+            tags: tags
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn map_tags_and_address() {
+        let place = new_place_from_osm_node(
+            &node(&[
+                ("name", "Bioladen"),
+                ("shop", "organic"),
+                ("organic", "only"),
+                ("addr:street", "Hauptstraße"),
+                ("addr:housenumber", "1"),
+                ("addr:city", "Stuttgart"),
+                ("website", "https://example.com"),
+            ]),
+            &organic_shop_mapping(),
+        )
+        .unwrap();
+        assert_eq!("Bioladen", place.title);
+        assert_eq!("Bioladen", place.description);
+        assert_eq!(vec!["2cd00bebec0c48ba9db761da48678134"], place.categories);
+        assert_eq!(vec!["bio", "organic"], place.tags);
+        assert_eq!(Some("Hauptstraße 1"), place.street.as_deref());
+        assert_eq!(Some("Stuttgart"), place.city.as_deref());
+        assert_eq!(Some("https://example.com"), place.homepage.as_deref());
+        assert_eq!(OSM_LICENSE, place.license);
+    }
+
+    #[test]
+    fn skip_unnamed_or_unmapped_nodes() {
+        let mapping = organic_shop_mapping();
+        assert!(new_place_from_osm_node(&node(&[("shop", "organic")]), &mapping).is_none());
+        assert!(new_place_from_osm_node(
+            &node(&[("name", "Bäcker"), ("shop", "bakery")]),
+            &mapping
+        )
+        .is_none());
+    }
+}
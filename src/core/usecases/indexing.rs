@@ -7,14 +7,19 @@ pub fn reindex_place(
     indexer: &dyn PlaceIndexer,
     place: &Place,
     status: ReviewStatus,
+    last_confirmed_at: Option<TimestampMs>,
     ratings: &[Rating],
 ) -> Fallible<AvgRatings> {
     let avg_ratings = place.avg_ratings(ratings);
-    indexer.add_or_update_place(place, status, &avg_ratings)?;
+    indexer.add_or_update_place(place, status, last_confirmed_at, &avg_ratings)?;
     Ok(avg_ratings)
 }
 
 pub fn index_event(indexer: &dyn EventIndexer, event: &Event) -> Fallible<()> {
+    // Scheduled events are indexed when they get published
+    if event.is_scheduled(Timestamp::now()) {
+        return indexer.remove_by_id(&event.id);
+    }
     indexer.add_or_update_event(event)
 }
 
@@ -5,7 +5,7 @@ pub fn load_places<R: PlaceRepo + PlaceClearanceRepo + OrganizationRepo>(
     ids: &[&str],
     org_tag: Option<&str>,
 ) -> Result<Vec<(Place, ReviewStatus)>> {
-    let places = repo.get_places(&ids)?;
+    let places = repo.get_places_by_ids(&ids)?;
     if let Some(org_tag) = org_tag {
         if let Some(org_id) = repo.map_tag_to_clearance_org_id(org_tag)? {
             return super::clearance::place::clear_repo_results(repo, &org_id, org_tag, places);
@@ -18,7 +18,9 @@ pub fn login_with_email<D: Db>(db: &D, login: &Credentials) -> Result<Role> {
         .and_then(|user| {
             if let Some(u) = user {
                 if u.password.verify(&login.password) {
-                    if u.email_confirmed {
+                    if db.user_deactivated_at(&u.email)?.is_some() {
+                        Err(Error::Parameter(ParameterError::UserDeactivated))
+                    } else if u.email_confirmed {
                         Ok(u.role)
                     } else {
                         Err(Error::Parameter(ParameterError::EmailNotConfirmed))
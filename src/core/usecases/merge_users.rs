@@ -0,0 +1,88 @@
+use crate::core::prelude::*;
+
+/// Merges the account of a user into the surviving account,
+/// e.g. for duplicate registrations with different e-mail
+/// addresses. The merged user is anonymized afterwards.
+///
+/// The role of the surviving user is not changed.
+pub fn merge_users<D: Db>(
+    db: &D,
+    merged_email: &str,
+    surviving_email: &str,
+) -> Result<MergedUserRecords> {
+    if merged_email == surviving_email {
+        return Err(Error::Parameter(ParameterError::MergeSameUser));
+    }
+    for email in &[merged_email, surviving_email] {
+        db.try_get_user_by_email(email)?
+            .ok_or(ParameterError::UserDoesNotExist)?;
+    }
+    info!("Merging user {} into {}", merged_email, surviving_email);
+    let records = db.merge_users_by_email(merged_email, surviving_email)?;
+    db.anonymize_user_by_email(merged_email)?;
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn create_user(db: &MockDb, email: &str) {
+        db.create_user(&User {
+            email: email.into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::User,
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn merge_user_into_surviving_user() {
+        let db = MockDb::default();
+        create_user(&db, "old@example.com");
+        create_user(&db, "new@example.com");
+        for (id, user_email, org_id) in vec![
+            ("1", "old@example.com", "a"),
+            ("2", "old@example.com", "b"),
+            ("3", "new@example.com", "a"),
+        ] {
+            db.create_org_subscription(&OrganizationSubscription {
+                id: id.into(),
+                user_email: user_email.into(),
+                org_id: org_id.into(),
+            })
+            .unwrap();
+        }
+
+        let records = merge_users(&db, "old@example.com", "new@example.com").unwrap();
+        assert_eq!(1, records.subscriptions);
+        assert!(db
+            .try_get_user_by_email("old@example.com")
+            .unwrap()
+            .is_none());
+        let mut org_ids: Vec<_> = db
+            .all_org_subscriptions_by_email("new@example.com")
+            .unwrap()
+            .into_iter()
+            .map(|s| s.org_id)
+            .collect();
+        org_ids.sort_unstable();
+        assert_eq!(vec![Id::from("a"), Id::from("b")], org_ids);
+    }
+
+    #[test]
+    fn merge_unknown_or_same_user() {
+        let db = MockDb::default();
+        create_user(&db, "new@example.com");
+        assert!(matches!(
+            merge_users(&db, "old@example.com", "new@example.com"),
+            Err(Error::Parameter(ParameterError::UserDoesNotExist))
+        ));
+        assert!(matches!(
+            merge_users(&db, "new@example.com", "new@example.com"),
+            Err(Error::Parameter(ParameterError::MergeSameUser))
+        ));
+    }
+}
@@ -0,0 +1,191 @@
+use crate::core::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MirrorOutcome {
+    Created,
+    Updated,
+    Reviewed,
+    Deleted,
+    Unchanged,
+    /// The local copy has diverged from upstream and is kept as is
+    Conflict,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MirrorReport {
+    pub created: usize,
+    pub updated: usize,
+    pub reviewed: usize,
+    pub deleted: usize,
+    pub unchanged: usize,
+    pub conflicts: usize,
+    pub failed: usize,
+}
+
+impl MirrorReport {
+    pub fn count(&mut self, outcome: MirrorOutcome) {
+        let count = match outcome {
+            MirrorOutcome::Created => &mut self.created,
+            MirrorOutcome::Updated => &mut self.updated,
+            MirrorOutcome::Reviewed => &mut self.reviewed,
+            MirrorOutcome::Deleted => &mut self.deleted,
+            MirrorOutcome::Unchanged => &mut self.unchanged,
+            MirrorOutcome::Conflict => &mut self.conflicts,
+        };
+        *count += 1;
+    }
+}
+
+fn mirror_review_status<D: Db>(db: &D, id: &Id, status: ReviewStatus) -> Result<()> {
+    let activity_log = ActivityLog {
+        activity: Activity::now(None),
+        context: None,
+        comment: Some("Mirrored from upstream".into()),
+    };
+    db.review_places(&[id.as_str()], status, &activity_log)?;
+    Ok(())
+}
+
+/// Aligns the local copy of a place with its upstream state.
+///
+/// The revisions are numbered locally, because intermediate
+/// revisions of upstream are not mirrored. A local copy that
+/// is newer than upstream or that doesn't exist upstream is
+/// considered as a conflict and kept unmodified.
+/// Should be executed within a transaction.
+pub fn mirror_place<D: Db>(
+    db: &D,
+    id: &Id,
+    upstream: Option<(Place, ReviewStatus)>,
+) -> Result<MirrorOutcome> {
+    let local = match db.get_place_by_id(id.as_str()) {
+        Ok(local) => Some(local),
+        Err(RepoError::NotFound) => None,
+        Err(err) => return Err(err.into()),
+    };
+    let (mut place, status) = match upstream {
+        Some(upstream) => upstream,
+        None if local.is_some() => return Ok(MirrorOutcome::Conflict),
+        None => return Ok(MirrorOutcome::Unchanged),
+    };
+    // Users are not mirrored
+    place.created.by = None;
+    let outcome = match local {
+        None => {
+            place.revision = Revision::initial();
+            MirrorOutcome::Created
+        }
+        Some((local_place, local_status)) => {
+            if local_place.created.at > place.created.at {
+                return Ok(MirrorOutcome::Conflict);
+            }
+            if local_place.created.at == place.created.at {
+                if local_status == status {
+                    return Ok(MirrorOutcome::Unchanged);
+                }
+                mirror_review_status(db, id, status)?;
+                return Ok(MirrorOutcome::Reviewed);
+            }
+            place.revision = local_place.revision.next();
+            MirrorOutcome::Updated
+        }
+    };
+    db.create_or_update_place(place)?;
+    if status != ReviewStatus::Created {
+        mirror_review_status(db, id, status)?;
+    }
+    Ok(outcome)
+}
+
+/// Aligns the local copy of an event with its upstream state.
+///
+/// Events don't have revisions. Therefore upstream always wins.
+pub fn mirror_event<D: Db>(db: &D, id: &Id, upstream: Option<Event>) -> Result<MirrorOutcome> {
+    let local = match db.get_event(id.as_str()) {
+        Ok(local) => Some(local),
+        Err(RepoError::NotFound) => None,
+        Err(err) => return Err(err.into()),
+    };
+    match (local, upstream) {
+        (None, None) => Ok(MirrorOutcome::Unchanged),
+        (Some(_), None) => {
+            db.delete_event_with_matching_tags(id.as_str(), &[])?;
+            Ok(MirrorOutcome::Deleted)
+        }
+        (None, Some(event)) => {
+            db.create_event(event)?;
+            Ok(MirrorOutcome::Created)
+        }
+        (Some(local), Some(event)) => {
+            let local = Event {
+                created_by: None,
+                created_at: event.created_at,
+                updated_at: event.updated_at,
+                ..local
+            };
+            if local == event {
+                return Ok(MirrorOutcome::Unchanged);
+            }
+            db.update_event(&event)?;
+            Ok(MirrorOutcome::Updated)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn place(id: &str, title: &str, created_at: i64) -> Place {
+        let mut place = Place::build().id(id).title(title).finish();
+        place.created = Activity {
+            at: TimestampMs::from_inner(created_at),
+            by: Some("upstream@example.com".into()),
+        };
+        place
+    }
+
+    #[test]
+    fn mirror_places_and_detect_conflicts() {
+        let db = MockDb::default();
+        let id = Id::from("a");
+
+        let upstream = (place("a", "Bioladen", 1000), ReviewStatus::Created);
+        assert_eq!(
+            MirrorOutcome::Created,
+            mirror_place(&db, &id, Some(upstream.clone())).unwrap()
+        );
+        let (local, _) = db.get_place_by_id("a").unwrap();
+        assert_eq!(Revision::initial(), local.revision);
+        assert_eq!(None, local.created.by);
+        assert_eq!(
+            MirrorOutcome::Unchanged,
+            mirror_place(&db, &id, Some(upstream)).unwrap()
+        );
+
+        let upstream = (place("a", "Unverpackt", 2000), ReviewStatus::Created);
+        assert_eq!(
+            MirrorOutcome::Updated,
+            mirror_place(&db, &id, Some(upstream)).unwrap()
+        );
+        let (local, _) = db.get_place_by_id("a").unwrap();
+        assert_eq!("Unverpackt", local.title);
+        assert_eq!(Revision::from(1), local.revision);
+
+        let outdated = (place("a", "Bioladen", 1500), ReviewStatus::Created);
+        assert_eq!(
+            MirrorOutcome::Conflict,
+            mirror_place(&db, &id, Some(outdated)).unwrap()
+        );
+        assert_eq!(
+            MirrorOutcome::Conflict,
+            mirror_place(&db, &id, None).unwrap()
+        );
+        assert_eq!("Unverpackt", db.get_place_by_id("a").unwrap().0.title);
+        assert_eq!(
+            MirrorOutcome::Unchanged,
+            mirror_place(&db, &Id::from("b"), None).unwrap()
+        );
+    }
+}
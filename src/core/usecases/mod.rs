@@ -8,43 +8,87 @@ use crate::core::{
     },
 };
 
+mod add_place_note;
+mod announcements;
+mod anonymize_user;
 mod archive_comments;
 mod archive_events;
 mod archive_ratings;
 mod authorize;
+mod auto_fill_address;
 mod change_user_role;
+mod check_links;
+mod check_positions;
 pub mod clearance;
+mod comment_rules;
+mod compare_places;
 mod confirm_email;
 mod confirm_email_and_reset_password;
+mod count_views;
 mod create_new_place;
 mod create_new_user;
+mod data_quality_report;
 mod delete_event;
+mod enrich_places;
 mod export_event;
 mod export_place;
+mod export_ratings;
 mod filter_event;
 mod filter_place;
 mod find_duplicates;
+mod geocode_event;
+mod homepage_previews;
+mod import_osm_nodes;
 mod indexing;
 mod load_places;
 mod login;
+mod merge_users;
+mod mirror_upstream;
+mod notification_consent;
+mod notification_digests;
+mod notify_moderated_tags;
+mod organization_api_tokens;
+mod personal_api_tokens;
+mod place_stats;
+mod publish_draft;
 mod query_events;
 mod rate_place;
 mod register;
+mod rename_tag;
+mod request_place_confirmations;
+mod resync_osm_nodes;
+mod review_digest;
 mod review_places;
 mod search;
+mod set_tag_moderation_policy;
+mod snapshot_places;
 mod store_event;
+mod suggest_tags;
+mod tag_usage;
+mod transfer_moderated_tag;
 mod update_place;
 mod user_tokens;
+mod watch_entity;
 
 #[cfg(test)]
 pub mod tests;
 
+pub use ofdb_core::tag::prepare_tag_list;
+
 pub use self::{
-    archive_comments::*, archive_events::*, archive_ratings::*, authorize::*, change_user_role::*,
-    confirm_email::*, confirm_email_and_reset_password::*, create_new_place::*, create_new_user::*,
-    delete_event::*, export_event::*, export_place::*, filter_event::*, filter_place::*,
-    find_duplicates::*, indexing::*, load_places::*, login::*, query_events::*, rate_place::*,
-    register::*, review_places::*, search::*, store_event::*, update_place::*, user_tokens::*,
+    add_place_note::*, announcements::*, anonymize_user::*, archive_comments::*, archive_events::*,
+    archive_ratings::*, authorize::*, auto_fill_address::*, change_user_role::*, check_links::*,
+    check_positions::*, comment_rules::*, compare_places::*, confirm_email::*,
+    confirm_email_and_reset_password::*, count_views::*, create_new_place::*, create_new_user::*,
+    data_quality_report::*, delete_event::*, enrich_places::*, export_event::*, export_place::*,
+    export_ratings::*, filter_event::*, filter_place::*, find_duplicates::*, geocode_event::*,
+    homepage_previews::*, import_osm_nodes::*, indexing::*, load_places::*, login::*,
+    merge_users::*, mirror_upstream::*, notification_consent::*, notification_digests::*,
+    notify_moderated_tags::*, organization_api_tokens::*, personal_api_tokens::*, place_stats::*,
+    publish_draft::*, query_events::*, rate_place::*, register::*, rename_tag::*,
+    request_place_confirmations::*, resync_osm_nodes::*, review_digest::*, review_places::*,
+    search::*, set_tag_moderation_policy::*, snapshot_places::*, store_event::*, suggest_tags::*,
+    tag_usage::*, transfer_moderated_tag::*, update_place::*, user_tokens::*, watch_entity::*,
 };
 
 //TODO: move usecases into separate files
@@ -58,6 +102,12 @@ pub fn load_ratings_with_comments<D: Db>(
     Ok(results)
 }
 
+pub fn load_rating_with_comments<D: Db>(db: &D, rating_id: &str) -> Result<(Rating, Vec<Comment>)> {
+    let rating = db.load_rating(rating_id)?;
+    let comments = db.load_comments_of_rating(rating_id)?;
+    Ok((rating, comments))
+}
+
 pub fn get_user<D: Db>(db: &D, logged_in_email: &str, requested_email: &str) -> Result<User> {
     if logged_in_email != requested_email {
         return Err(Error::Parameter(ParameterError::Forbidden));
@@ -66,7 +116,12 @@ pub fn get_user<D: Db>(db: &D, logged_in_email: &str, requested_email: &str) ->
 }
 
 pub fn get_event<D: Db>(db: &D, id: &str) -> Result<Event> {
-    Ok(db.get_event(id)?)
+    let event = db.get_event(id)?;
+    // Never reveal scheduled events
+    if event.is_scheduled(Timestamp::now()) {
+        return Err(Error::Repo(RepoError::NotFound));
+    }
+    Ok(event)
 }
 
 #[derive(Clone, Debug, Default)]
@@ -77,11 +132,18 @@ pub struct EventQuery {
     pub start_max: Option<Timestamp>,
     pub tags: Option<Vec<String>>,
     pub text: Option<String>,
+    pub organizer: Option<String>,
+    pub registration: Option<RegistrationType>,
+    // Only events that have been created or updated since then (inclusive)
+    pub since: Option<Timestamp>,
 
+    pub offset: Option<usize>,
     pub limit: Option<usize>,
 }
 
 impl EventQuery {
+    /// No search criteria, only the pagination or the time
+    /// of the last modification might be set
     pub fn is_empty(&self) -> bool {
         let Self {
             ref bbox,
@@ -90,7 +152,11 @@ impl EventQuery {
             ref start_max,
             ref tags,
             ref text,
-            ref limit,
+            ref organizer,
+            ref registration,
+            since: _,
+            offset: _,
+            limit: _,
         } = self;
         bbox.is_none()
             && created_by.is_none()
@@ -98,18 +164,36 @@ impl EventQuery {
             && start_max.is_none()
             && tags.is_none()
             && text.is_none()
-            && limit.is_none()
+            && organizer.is_none()
+            && registration.is_none()
     }
 }
 
+/// Deactivates the account of the user.
+///
+/// The login is blocked immediately, but the user is only
+/// anonymized after a grace period.
 pub fn delete_user(db: &dyn Db, login_email: &str, email: &str) -> Result<()> {
     if login_email != email {
         return Err(Error::Parameter(ParameterError::Forbidden));
     }
-    Ok(db.delete_user_by_email(email)?)
+    Ok(db.set_user_deactivated_at(email, Some(Timestamp::now()))?)
 }
 
-pub fn subscribe_to_bbox(db: &dyn Db, user_email: String, bbox: MapBbox) -> Result<()> {
+/// Restores a deleted account during the grace period.
+pub fn reactivate_user(db: &dyn Db, email: &str) -> Result<()> {
+    if db.user_deactivated_at(email)?.is_none() {
+        return Err(Error::Parameter(ParameterError::UserNotDeactivated));
+    }
+    Ok(db.set_user_deactivated_at(email, None)?)
+}
+
+pub fn subscribe_to_bbox(
+    db: &dyn Db,
+    user_email: String,
+    bbox: MapBbox,
+    digest: Option<NotificationDigest>,
+) -> Result<()> {
     validate::bbox(&bbox)?;
 
     // TODO: support multiple subscriptions in KVM (frontend)
@@ -122,6 +206,7 @@ pub fn subscribe_to_bbox(db: &dyn Db, user_email: String, bbox: MapBbox) -> Resu
         id,
         user_email,
         bbox,
+        digest,
     })?;
     Ok(())
 }
@@ -149,29 +234,79 @@ pub fn bbox_subscriptions_by_coordinate(
         .collect())
 }
 
+/// Subscribers that receive digests are omitted.
 pub fn email_addresses_by_coordinate(db: &dyn Db, pos: MapPoint) -> Result<Vec<String>> {
     Ok(bbox_subscriptions_by_coordinate(db, pos)?
         .into_iter()
+        .filter(|s| s.digest.is_none())
         .map(|s| s.user_email)
         .collect())
 }
 
-pub fn prepare_tag_list<'a>(tags: impl IntoIterator<Item = &'a str>) -> Vec<String> {
-    let mut tags: Vec<_> = tags
-        .into_iter()
-        // Split by whitespace
-        .flat_map(|t| t.split_whitespace())
-        // Remove reserved character
-        .map(|t| t.replace("#", ""))
-        // Filter empty tags (2nd pass) and conversion to lowercase
-        .filter_map(|t| match t.trim() {
-            t if t.is_empty() => None,
-            t => Some(t.to_lowercase()),
-        })
+pub fn subscribe_to_org(db: &dyn Db, user_email: String, org_id: Id) -> Result<()> {
+    // Only existing organizations can be subscribed
+    db.get_org_by_id(&org_id)?;
+    let id = Id::new();
+    db.create_org_subscription(&OrganizationSubscription {
+        id,
+        user_email,
+        org_id,
+    })?;
+    Ok(())
+}
+
+pub fn unsubscribe_from_org(db: &dyn Db, user_email: &str, org_id: &Id) -> Result<()> {
+    Ok(db.delete_org_subscription(user_email, org_id)?)
+}
+
+pub fn get_org_subscriptions(
+    db: &dyn Db,
+    user_email: &str,
+) -> Result<Vec<OrganizationSubscription>> {
+    Ok(db.all_org_subscriptions_by_email(user_email)?)
+}
+
+pub fn org_subscriptions_by_tags(
+    db: &dyn Db,
+    tags: &[String],
+) -> Result<Vec<OrganizationSubscription>> {
+    let moderated_tags_by_org = db.get_moderated_tags_by_org(None)?;
+    let mut org_ids: Vec<_> = moderated_tags_by_org
+        .iter()
+        .filter(|(_, moderated_tag)| tags.contains(&moderated_tag.label))
+        .map(|(org_id, _)| org_id)
         .collect();
-    tags.sort_unstable();
-    tags.dedup();
-    tags
+    org_ids.sort_unstable();
+    org_ids.dedup();
+    Ok(db.all_org_subscriptions_by_org_ids(&org_ids)?)
+}
+
+/// Collects the e-mail addresses of all users that either
+/// subscribed to an area containing the position or to an
+/// organization that moderates one of the tags.
+///
+/// Subscriptions are inactive until the subscriber confirmed
+/// the consent to receive notifications.
+pub fn email_addresses_of_subscribers(
+    db: &dyn Db,
+    pos: Option<MapPoint>,
+    tags: &[String],
+) -> Result<Vec<String>> {
+    let mut email_addresses = match pos {
+        Some(pos) => email_addresses_by_coordinate(db, pos)?,
+        None => vec![],
+    };
+    for s in org_subscriptions_by_tags(db, tags)? {
+        if !email_addresses.contains(&s.user_email) {
+            email_addresses.push(s.user_email);
+        }
+    }
+    let consented = {
+        let emails: Vec<_> = email_addresses.iter().map(String::as_str).collect();
+        db.emails_with_notification_consent(&emails)?
+    };
+    email_addresses.retain(|email| consented.contains(email));
+    Ok(email_addresses)
 }
 
 #[derive(Debug, Clone)]
@@ -196,6 +331,22 @@ impl From<CustomLink> for CustomLinkParam {
     }
 }
 
+// All invalid ids are reported at once
+fn check_category_ids<D: Db>(db: &D, ids: &[String]) -> Result<()> {
+    let categories = db.all_categories()?;
+    let mut invalid_ids: Vec<_> = ids
+        .iter()
+        .filter(|id| !categories.iter().any(|c| c.id.as_str() == id.as_str()))
+        .cloned()
+        .collect();
+    if invalid_ids.is_empty() {
+        return Ok(());
+    }
+    invalid_ids.sort_unstable();
+    invalid_ids.dedup();
+    Err(ParameterError::Category(invalid_ids).into())
+}
+
 fn parse_custom_link_param(from: CustomLinkParam) -> Result<CustomLink> {
     let CustomLinkParam {
         url,
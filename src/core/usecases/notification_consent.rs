@@ -0,0 +1,56 @@
+use crate::core::prelude::*;
+
+/// Requests the consent of a subscriber to receive notifications.
+///
+/// Returns the e-mail nonce for the activation link or `None`
+/// if the consent has already been confirmed.
+pub fn request_notification_consent(db: &dyn Db, email: &str) -> Result<Option<EmailNonce>> {
+    if db.has_notification_consent(email)? {
+        return Ok(None);
+    }
+    let email_nonce = EmailNonce {
+        email: email.to_owned(),
+        nonce: Nonce::new(),
+    };
+    db.request_notification_consent(&email_nonce, Timestamp::now())?;
+    Ok(Some(email_nonce))
+}
+
+pub fn confirm_notification_consent(db: &dyn Db, token: &str) -> Result<()> {
+    let email_nonce =
+        EmailNonce::decode_from_str(token).map_err(|_| ParameterError::TokenInvalid)?;
+    db.confirm_notification_consent(&email_nonce, Timestamp::now())
+        .map_err(|err| match err {
+            RepoError::NotFound => Error::Parameter(ParameterError::TokenInvalid),
+            err => err.into(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    #[test]
+    fn confirm_requested_notification_consent() {
+        let db = MockDb::default();
+        let first = request_notification_consent(&db, "foo@bar")
+            .unwrap()
+            .unwrap();
+        let second = request_notification_consent(&db, "foo@bar")
+            .unwrap()
+            .unwrap();
+        assert!(!db.has_notification_consent("foo@bar").unwrap());
+
+        // The activation link of a previous request is no longer valid
+        assert!(confirm_notification_consent(&db, &first.encode_to_string()).is_err());
+        assert!(confirm_notification_consent(&db, "invalid").is_err());
+        confirm_notification_consent(&db, &second.encode_to_string()).unwrap();
+        assert!(db.has_notification_consent("foo@bar").unwrap());
+
+        // No further requests after the consent has been confirmed
+        assert!(request_notification_consent(&db, "foo@bar")
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,153 @@
+use super::bbox_subscriptions_by_coordinate;
+use crate::core::prelude::*;
+
+/// The collected changes of a subscriber that are
+/// due to be sent as one summary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DueDigest {
+    pub email: String,
+    pub digest: NotificationDigest,
+    /// Ordered by creation, oldest first
+    pub notifications: Vec<PendingNotification>,
+}
+
+/// Collects a change for all subscribers of areas containing
+/// the position that receive digests instead of an e-mail
+/// per change.
+///
+/// Subscribers in `notified` already received an e-mail about
+/// this change, e.g. as the subscriber of an organization.
+///
+/// Returns the number of collected notifications.
+pub fn queue_digest_notifications(
+    db: &dyn Db,
+    pos: MapPoint,
+    change: NotificationChange,
+    entity_id: &Id,
+    title: &str,
+    notified: &[String],
+) -> Result<usize> {
+    let created_at = Timestamp::now();
+    let mut notifications: Vec<PendingNotification> = vec![];
+    for s in bbox_subscriptions_by_coordinate(db, pos)? {
+        let digest = match s.digest {
+            Some(digest) => digest,
+            None => continue,
+        };
+        if notified.contains(&s.user_email)
+            || notifications.iter().any(|n| n.user_email == s.user_email)
+        {
+            continue;
+        }
+        notifications.push(PendingNotification {
+            id: Id::new(),
+            user_email: s.user_email,
+            digest,
+            change,
+            entity_id: entity_id.clone(),
+            title: title.to_owned(),
+            created_at,
+        });
+    }
+    let consented = {
+        let emails: Vec<_> = notifications
+            .iter()
+            .map(|n| n.user_email.as_str())
+            .collect();
+        db.emails_with_notification_consent(&emails)?
+    };
+    notifications.retain(|n| consented.contains(&n.user_email));
+    db.add_pending_notifications(&notifications)?;
+    Ok(notifications.len())
+}
+
+/// Groups the pending notifications by subscriber and digest.
+///
+/// A digest is due if its oldest change was collected at least
+/// one digest interval (a day or a week) before `now`.
+pub fn due_digests(db: &dyn Db, now: Timestamp) -> Result<Vec<DueDigest>> {
+    let mut digests: Vec<DueDigest> = vec![];
+    for n in db.all_pending_notifications()? {
+        if let Some(d) = digests
+            .iter_mut()
+            .find(|d| d.email == n.user_email && d.digest == n.digest)
+        {
+            d.notifications.push(n);
+        } else {
+            digests.push(DueDigest {
+                email: n.user_email.clone(),
+                digest: n.digest,
+                notifications: vec![n],
+            });
+        }
+    }
+    digests.retain(|d| {
+        d.notifications[0].created_at.into_inner() + d.digest.interval_seconds() <= now.into_inner()
+    });
+    Ok(digests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        confirm_notification_consent, email_addresses_of_subscribers, request_notification_consent,
+        subscribe_to_bbox, tests::MockDb,
+    };
+    use super::*;
+
+    #[test]
+    fn collect_changes_for_digest_subscribers_only() {
+        let db = MockDb::default();
+        for (email, digest) in &[
+            ("immediate@example.com", None),
+            ("weekly@example.com", Some(NotificationDigest::Weekly)),
+        ] {
+            db.create_user(&User {
+                email: (*email).into(),
+                email_confirmed: true,
+                password: "secret".parse::<Password>().unwrap(),
+                role: Role::User,
+            })
+            .unwrap();
+            subscribe_to_bbox(
+                &db,
+                (*email).into(),
+                MapBbox::new(
+                    MapPoint::from_lat_lng_deg(0.0, 0.0),
+                    MapPoint::from_lat_lng_deg(10.0, 10.0),
+                ),
+                *digest,
+            )
+            .unwrap();
+            let email_nonce = request_notification_consent(&db, email).unwrap().unwrap();
+            confirm_notification_consent(&db, &email_nonce.encode_to_string()).unwrap();
+        }
+        let pos = MapPoint::from_lat_lng_deg(5.0, 5.0);
+
+        let immediate = email_addresses_of_subscribers(&db, Some(pos), &[]).unwrap();
+        assert_eq!(vec!["immediate@example.com".to_string()], immediate);
+
+        let place_id = Id::new();
+        assert_eq!(
+            1,
+            queue_digest_notifications(
+                &db,
+                pos,
+                NotificationChange::PlaceAdded,
+                &place_id,
+                "place",
+                &immediate
+            )
+            .unwrap()
+        );
+
+        let now = Timestamp::now();
+        assert!(due_digests(&db, now).unwrap().is_empty());
+        let week_later =
+            Timestamp::from_inner(now.into_inner() + NotificationDigest::Weekly.interval_seconds());
+        let digests = due_digests(&db, week_later).unwrap();
+        assert_eq!(1, digests.len());
+        assert_eq!("weekly@example.com", digests[0].email);
+        assert_eq!(place_id, digests[0].notifications[0].entity_id);
+    }
+}
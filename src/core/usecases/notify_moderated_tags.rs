@@ -0,0 +1,262 @@
+use crate::core::prelude::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModeratedTagsNotification {
+    pub org_id: Id,
+    pub email: String,
+    pub added_tags: Vec<String>,
+    pub removed_tags: Vec<String>,
+    pub branding: EmailBranding,
+}
+
+// Collects the changes of moderated tags for each organization
+// that has configured a notification e-mail address.
+//
+// If an organization is provided then it is excluded, i.e. nobody
+// is notified about their own edits or the edits of co-owners.
+pub fn moderated_tags_notifications<R: OrganizationRepo>(
+    repo: &R,
+    old_tags: &[String],
+    new_tags: &[String],
+    org: Option<&Organization>,
+) -> Result<Vec<ModeratedTagsNotification>> {
+    let added_tags: Vec<_> = new_tags.iter().filter(|t| !old_tags.contains(t)).collect();
+    let removed_tags: Vec<_> = old_tags.iter().filter(|t| !new_tags.contains(t)).collect();
+    if added_tags.is_empty() && removed_tags.is_empty() {
+        return Ok(vec![]);
+    }
+    let mut changes_by_org: Vec<(Id, Vec<String>, Vec<String>)> = vec![];
+    for (org_id, moderated_tag) in super::moderated_tags_of_other_orgs(repo, org)? {
+        let added = added_tags.contains(&&moderated_tag.label);
+        let removed = removed_tags.contains(&&moderated_tag.label);
+        if !added && !removed {
+            continue;
+        }
+        let idx = match changes_by_org.iter().position(|(id, _, _)| *id == org_id) {
+            Some(idx) => idx,
+            None => {
+                changes_by_org.push((org_id, vec![], vec![]));
+                changes_by_org.len() - 1
+            }
+        };
+        let (_, org_added_tags, org_removed_tags) = &mut changes_by_org[idx];
+        if added {
+            org_added_tags.push(moderated_tag.label);
+        } else {
+            org_removed_tags.push(moderated_tag.label);
+        }
+    }
+    let mut notifications = Vec::with_capacity(changes_by_org.len());
+    for (org_id, added_tags, removed_tags) in changes_by_org {
+        let org = repo.get_org_by_id(&org_id)?;
+        if let Some(email) = org.notification_email {
+            notifications.push(ModeratedTagsNotification {
+                org_id,
+                email,
+                added_tags,
+                removed_tags,
+                branding: org.email_branding,
+            });
+        }
+    }
+    Ok(notifications)
+}
+
+/// The collected outside edits of the moderated tags of an
+/// organization that are due to be sent as one summary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DueModeratedTagsSummary {
+    pub email: String,
+    pub branding: EmailBranding,
+    /// Ordered by creation, oldest first
+    pub notifications: Vec<PendingModeratedTagsNotification>,
+}
+
+/// Collects the notifications about a place for a summary
+/// instead of sending them immediately.
+///
+/// Returns the number of collected notifications.
+pub fn queue_moderated_tags_notifications<R: PendingModeratedTagsNotificationRepo>(
+    repo: &R,
+    place: &Place,
+    notifications: Vec<ModeratedTagsNotification>,
+) -> Result<usize> {
+    let created_at = Timestamp::now();
+    let pending: Vec<_> = notifications
+        .into_iter()
+        .map(|n| PendingModeratedTagsNotification {
+            id: Id::new(),
+            org_id: n.org_id,
+            place_id: place.id.clone(),
+            place_title: place.title.clone(),
+            added_tags: n.added_tags,
+            removed_tags: n.removed_tags,
+            created_at,
+        })
+        .collect();
+    repo.add_pending_moderated_tags_notifications(&pending)?;
+    Ok(pending.len())
+}
+
+/// Groups the pending notifications by organization.
+///
+/// A summary is due if its oldest edit was collected at least
+/// `window_seconds` before `now`. Organizations that removed
+/// their notification e-mail address in the meantime don't
+/// receive a summary, their notifications are returned as
+/// obsolete.
+pub fn due_moderated_tags_summaries<R>(
+    repo: &R,
+    window_seconds: i64,
+    now: Timestamp,
+) -> Result<(
+    Vec<DueModeratedTagsSummary>,
+    Vec<PendingModeratedTagsNotification>,
+)>
+where
+    R: OrganizationRepo + PendingModeratedTagsNotificationRepo,
+{
+    let mut notifications_by_org: Vec<(Id, Vec<PendingModeratedTagsNotification>)> = vec![];
+    for n in repo.all_pending_moderated_tags_notifications()? {
+        if let Some((_, org_notifications)) = notifications_by_org
+            .iter_mut()
+            .find(|(org_id, _)| *org_id == n.org_id)
+        {
+            org_notifications.push(n);
+        } else {
+            notifications_by_org.push((n.org_id.clone(), vec![n]));
+        }
+    }
+    let mut summaries = vec![];
+    let mut obsolete = vec![];
+    for (org_id, notifications) in notifications_by_org {
+        if notifications[0].created_at.into_inner() + window_seconds > now.into_inner() {
+            continue;
+        }
+        let org = repo.get_org_by_id(&org_id)?;
+        match org.notification_email {
+            Some(email) => summaries.push(DueModeratedTagsSummary {
+                email,
+                branding: org.email_branding,
+                notifications,
+            }),
+            None => obsolete.extend(notifications),
+        }
+    }
+    Ok((summaries, obsolete))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::usecases::tests::MockDb;
+
+    fn tags(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn notify_organizations_about_changes_of_their_tags() {
+        let mut db = MockDb::default();
+        let branding = EmailBranding {
+            sender_name: Some("Org A".into()),
+            reply_to: Some("info@a.example.com".into()),
+            footer: None,
+        };
+        for (id, moderated_tags, notification_email, email_branding) in vec![
+            (
+                "a",
+                vec!["a1".into(), "a2".into()],
+                Some("a@example.com"),
+                branding.clone(),
+            ),
+            (
+                "b",
+                vec!["b".into()],
+                Some("b@example.com"),
+                Default::default(),
+            ),
+            ("c", vec!["c".into()], None, Default::default()),
+        ] {
+            db.create_org(Organization {
+                id: id.into(),
+                name: id.into(),
+                api_token: id.into(),
+                moderated_tags,
+                notification_email: notification_email.map(Into::into),
+                email_branding,
+            })
+            .unwrap();
+        }
+        let old_tags = tags(&["a1", "b", "foo"]);
+        let new_tags = tags(&["a2", "b", "c", "bar"]);
+        assert_eq!(
+            vec![ModeratedTagsNotification {
+                org_id: "a".into(),
+                email: "a@example.com".into(),
+                added_tags: tags(&["a2"]),
+                removed_tags: tags(&["a1"]),
+                branding,
+            }],
+            moderated_tags_notifications(&db, &old_tags, &new_tags, None).unwrap()
+        );
+        let org = db.get_org_by_id(&"a".into()).unwrap();
+        assert!(
+            moderated_tags_notifications(&db, &old_tags, &new_tags, Some(&org))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn summarize_moderated_tags_notifications_per_org_after_window() {
+        let mut db = MockDb::default();
+        for (id, notification_email) in &[("a", Some("a@example.com")), ("b", None)] {
+            db.create_org(Organization {
+                id: (*id).into(),
+                name: (*id).into(),
+                api_token: (*id).into(),
+                moderated_tags: vec![],
+                notification_email: notification_email.map(Into::into),
+                email_branding: Default::default(),
+            })
+            .unwrap();
+        }
+        let place = Place::build().id("p").title("Place").finish();
+        let notification = |org_id: &str| ModeratedTagsNotification {
+            org_id: org_id.into(),
+            email: format!("{}@example.com", org_id),
+            added_tags: tags(&[org_id]),
+            removed_tags: vec![],
+            branding: Default::default(),
+        };
+        assert_eq!(
+            2,
+            queue_moderated_tags_notifications(
+                &db,
+                &place,
+                vec![notification("a"), notification("b")]
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            1,
+            queue_moderated_tags_notifications(&db, &place, vec![notification("a")]).unwrap()
+        );
+
+        let window = 600;
+        let now = Timestamp::now();
+        let (summaries, obsolete) = due_moderated_tags_summaries(&db, window, now).unwrap();
+        assert!(summaries.is_empty());
+        assert!(obsolete.is_empty());
+
+        let later = Timestamp::from_inner(now.into_inner() + window);
+        let (summaries, obsolete) = due_moderated_tags_summaries(&db, window, later).unwrap();
+        assert_eq!(1, summaries.len());
+        assert_eq!("a@example.com", summaries[0].email);
+        assert_eq!(2, summaries[0].notifications.len());
+        assert_eq!("Place", summaries[0].notifications[0].place_title);
+        assert_eq!(1, obsolete.len());
+        assert_eq!(Id::from("b"), obsolete[0].org_id);
+    }
+}
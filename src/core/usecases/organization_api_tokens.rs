@@ -0,0 +1,190 @@
+use crate::core::prelude::*;
+
+const MAX_LABEL_LEN: usize = 100;
+
+/// The pseudo id for revoking the initial API token of an
+/// organization that is not stored as a separate token.
+pub const LEGACY_ORGANIZATION_API_TOKEN_ID: &str = "legacy";
+
+// The time of the last usage is only updated occasionally
+// to avoid a write access for each authorized request.
+const LAST_USED_RESOLUTION_SECONDS: i64 = 60;
+
+/// Creates an additional token for the organization and returns
+/// it together with the secret bearer token.
+pub fn create_organization_api_token<D: Db>(
+    db: &D,
+    org_id: &Id,
+    label: Option<String>,
+    expires_at: Option<Timestamp>,
+    now: Timestamp,
+) -> Result<(OrganizationApiToken, String)> {
+    let label = label.map(|l| l.trim().to_owned()).filter(|l| !l.is_empty());
+    if label.as_ref().map(String::len).unwrap_or(0) > MAX_LABEL_LEN {
+        return Err(Error::Parameter(ParameterError::ApiTokenLabel));
+    }
+    if expires_at
+        .map(|expires_at| expires_at <= now)
+        .unwrap_or(false)
+    {
+        return Err(Error::Parameter(ParameterError::ApiTokenExpiry));
+    }
+    let org = db.get_org_by_id(org_id)?;
+    let (token, bearer_token) = OrganizationApiToken::generate(org.id, label, expires_at, now);
+    db.create_org_api_token(&token)?;
+    Ok((token, bearer_token))
+}
+
+pub fn revoke_organization_api_token<D: Db>(db: &mut D, org_id: &Id, id: &str) -> Result<()> {
+    if id == LEGACY_ORGANIZATION_API_TOKEN_ID {
+        // The legacy token is still used internally for identifying
+        // the organization. A new random token that is never revealed
+        // makes it unusable for clients.
+        return Ok(db.replace_org_api_token(org_id, &Nonce::new().to_string())?);
+    }
+    Ok(db.delete_org_api_token(org_id, id)?)
+}
+
+/// Returns the token that matches a valid bearer token.
+///
+/// Expired tokens are rejected.
+pub fn authorize_organization_by_api_token(
+    db: &dyn Db,
+    bearer_token: &str,
+    now: Timestamp,
+) -> Result<OrganizationApiToken> {
+    let unauthorized = || Error::Parameter(ParameterError::Unauthorized);
+    let (id, secret) =
+        OrganizationApiToken::split_bearer_token(bearer_token).ok_or_else(unauthorized)?;
+    let token = match db.load_org_api_token(id) {
+        Ok(token) => token,
+        Err(RepoError::NotFound) => return Err(unauthorized()),
+        Err(err) => return Err(err.into()),
+    };
+    if token.is_expired(now) || !token.verify_secret(secret) {
+        return Err(unauthorized());
+    }
+    Ok(token)
+}
+
+/// Checks if the recorded time of the last usage is outdated
+/// without accessing the database.
+pub fn is_organization_api_token_usage_due(token: &OrganizationApiToken, now: Timestamp) -> bool {
+    token
+        .last_used_at
+        .map(|last_used_at| {
+            now.into_inner() - last_used_at.into_inner() >= LAST_USED_RESOLUTION_SECONDS
+        })
+        .unwrap_or(true)
+}
+
+/// Updates the time of the last usage of an authorized token.
+///
+/// Returns `true` if the time has been updated.
+pub fn record_organization_api_token_usage<D: Db>(
+    db: &D,
+    token: &OrganizationApiToken,
+    now: Timestamp,
+) -> Result<bool> {
+    if !is_organization_api_token_usage_due(token, now) {
+        return Ok(false);
+    }
+    db.update_org_api_token_last_used(token.id.as_str(), now)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn org() -> Organization {
+        Organization {
+            id: "org".into(),
+            name: "Organization".into(),
+            api_token: "legacy".into(),
+            moderated_tags: vec![],
+            notification_email: None,
+            email_branding: Default::default(),
+        }
+    }
+
+    #[test]
+    fn authorize_by_organization_api_token_until_expired_or_revoked() {
+        let mut db = MockDb::default();
+        db.create_org(org()).unwrap();
+        let now = Timestamp::from_inner(1_000_000);
+
+        assert!(matches!(
+            create_organization_api_token(&db, &"org".into(), None, Some(now), now),
+            Err(Error::Parameter(ParameterError::ApiTokenExpiry))
+        ));
+        let (token, bearer_token) =
+            create_organization_api_token(&db, &"org".into(), Some(" rotation ".into()), None, now)
+                .unwrap();
+        assert_eq!(Some("rotation"), token.label.as_deref());
+        let (expiring_token, expiring_bearer_token) = create_organization_api_token(
+            &db,
+            &"org".into(),
+            None,
+            Some(Timestamp::from_inner(now.into_inner() + 100)),
+            now,
+        )
+        .unwrap();
+
+        let authorized = authorize_organization_by_api_token(&db, &bearer_token, now).unwrap();
+        assert_eq!(token, authorized);
+        assert_eq!("org", authorized.org_id.as_str());
+        let (id, _) = OrganizationApiToken::split_bearer_token(&bearer_token).unwrap();
+        assert!(authorize_organization_by_api_token(&db, &format!("{}.invalid", id), now).is_err());
+        assert!(authorize_organization_by_api_token(&db, "legacy", now).is_err());
+
+        assert!(authorize_organization_by_api_token(&db, &expiring_bearer_token, now).is_ok());
+        assert!(matches!(
+            authorize_organization_by_api_token(
+                &db,
+                &expiring_bearer_token,
+                expiring_token.expires_at.unwrap()
+            ),
+            Err(Error::Parameter(ParameterError::Unauthorized))
+        ));
+
+        assert!(is_organization_api_token_usage_due(&authorized, now));
+        assert!(record_organization_api_token_usage(&db, &authorized, now).unwrap());
+        let authorized = authorize_organization_by_api_token(&db, &bearer_token, now).unwrap();
+        assert_eq!(Some(now), authorized.last_used_at);
+        let later = Timestamp::from_inner(now.into_inner() + 1);
+        assert!(!is_organization_api_token_usage_due(&authorized, later));
+        assert!(!record_organization_api_token_usage(&db, &authorized, later).unwrap());
+        let much_later = Timestamp::from_inner(now.into_inner() + LAST_USED_RESOLUTION_SECONDS);
+        assert!(is_organization_api_token_usage_due(&authorized, much_later));
+
+        assert!(matches!(
+            revoke_organization_api_token(&mut db, &"other".into(), token.id.as_str()),
+            Err(Error::Repo(RepoError::NotFound))
+        ));
+        revoke_organization_api_token(&mut db, &"org".into(), token.id.as_str()).unwrap();
+        assert!(matches!(
+            authorize_organization_by_api_token(&db, &bearer_token, now),
+            Err(Error::Parameter(ParameterError::Unauthorized))
+        ));
+    }
+
+    #[test]
+    fn revoke_legacy_organization_api_token() {
+        let mut db = MockDb::default();
+        db.create_org(org()).unwrap();
+        assert!(super::super::authorize_organization_by_possible_api_tokens(
+            &db,
+            &["legacy".into()]
+        )
+        .is_ok());
+        revoke_organization_api_token(&mut db, &"org".into(), LEGACY_ORGANIZATION_API_TOKEN_ID)
+            .unwrap();
+        assert!(matches!(
+            super::super::authorize_organization_by_possible_api_tokens(&db, &["legacy".into()]),
+            Err(Error::Parameter(ParameterError::Unauthorized))
+        ));
+        assert_ne!("legacy", db.get_org_by_id(&"org".into()).unwrap().api_token);
+    }
+}
@@ -0,0 +1,91 @@
+use crate::core::prelude::*;
+
+const MAX_LABEL_LEN: usize = 100;
+
+/// Creates a new token for the user and returns it
+/// together with the secret bearer token.
+pub fn create_personal_api_token<D: Db>(
+    db: &D,
+    email: &str,
+    scope: ApiTokenScope,
+    label: Option<String>,
+) -> Result<(PersonalApiToken, String)> {
+    let label = label.map(|l| l.trim().to_owned()).filter(|l| !l.is_empty());
+    if label.as_ref().map(String::len).unwrap_or(0) > MAX_LABEL_LEN {
+        return Err(Error::Parameter(ParameterError::ApiTokenLabel));
+    }
+    let user = db.get_user_by_email(email)?;
+    let (token, bearer_token) =
+        PersonalApiToken::generate(user.email, scope, label, Timestamp::now());
+    db.create_personal_api_token(&token)?;
+    Ok((token, bearer_token))
+}
+
+pub fn revoke_personal_api_token<D: Db>(db: &D, email: &str, id: &str) -> Result<()> {
+    Ok(db.delete_personal_api_token(email, id)?)
+}
+
+/// Returns the e-mail address of the owner and the scope
+/// of a valid bearer token.
+pub fn authorize_user_by_personal_api_token(
+    db: &dyn Db,
+    bearer_token: &str,
+) -> Result<(String, ApiTokenScope)> {
+    let unauthorized = || Error::Parameter(ParameterError::Unauthorized);
+    let (id, secret) =
+        PersonalApiToken::split_bearer_token(bearer_token).ok_or_else(unauthorized)?;
+    let token = match db.load_personal_api_token(id) {
+        Ok(token) => token,
+        Err(RepoError::NotFound) => return Err(unauthorized()),
+        Err(err) => return Err(err.into()),
+    };
+    if !token.verify_secret(secret) {
+        return Err(unauthorized());
+    }
+    if db.user_deactivated_at(&token.user_email)?.is_some() {
+        return Err(Error::Parameter(ParameterError::UserDeactivated));
+    }
+    Ok((token.user_email, token.scope))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    #[test]
+    fn authorize_by_personal_api_token_until_revoked() {
+        let db = MockDb::default();
+        db.create_user(&User {
+            email: "user@example.com".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::User,
+        })
+        .unwrap();
+        let (token, bearer_token) = create_personal_api_token(
+            &db,
+            "user@example.com",
+            ApiTokenScope::Write,
+            Some(" script ".into()),
+        )
+        .unwrap();
+        assert_eq!(Some("script"), token.label.as_deref());
+        assert_eq!(
+            ("user@example.com".to_string(), ApiTokenScope::Write),
+            authorize_user_by_personal_api_token(&db, &bearer_token).unwrap()
+        );
+        let (id, _) = PersonalApiToken::split_bearer_token(&bearer_token).unwrap();
+        assert!(authorize_user_by_personal_api_token(&db, &format!("{}.invalid", id)).is_err());
+
+        assert!(matches!(
+            revoke_personal_api_token(&db, "other@example.com", token.id.as_str()),
+            Err(Error::Repo(RepoError::NotFound))
+        ));
+        revoke_personal_api_token(&db, "user@example.com", token.id.as_str()).unwrap();
+        assert!(matches!(
+            authorize_user_by_personal_api_token(&db, &bearer_token),
+            Err(Error::Parameter(ParameterError::Unauthorized))
+        ));
+    }
+}
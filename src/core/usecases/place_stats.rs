@@ -0,0 +1,161 @@
+use crate::core::prelude::*;
+use chrono::{Datelike, NaiveDateTime};
+use ofdb_entities::rating::AvgRatingValueBuilder;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RatingCounts {
+    pub diversity: usize,
+    pub fairness: usize,
+    pub humanity: usize,
+    pub renewable: usize,
+    pub solidarity: usize,
+    pub transparency: usize,
+}
+
+impl RatingCounts {
+    fn add(&mut self, ctx: RatingContext) {
+        use RatingContext::*;
+        match ctx {
+            Diversity => self.diversity += 1,
+            Fairness => self.fairness += 1,
+            Humanity => self.humanity += 1,
+            Renewable => self.renewable += 1,
+            Solidarity => self.solidarity += 1,
+            Transparency => self.transparency += 1,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.diversity
+            + self.fairness
+            + self.humanity
+            + self.renewable
+            + self.solidarity
+            + self.transparency
+    }
+}
+
+/// The average of all ratings that have been created
+/// within a calendar month, regardless of their context.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthlyAvgRating {
+    pub year: i32,
+    pub month: u32,
+    pub count: usize,
+    pub avg: AvgRatingValue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaceStats {
+    pub rating_counts: RatingCounts,
+    // Ordered chronologically, months without ratings are omitted
+    pub monthly_avg_ratings: Vec<MonthlyAvgRating>,
+    pub revision_count: u64,
+    // Only available if views are counted
+    pub view_count: Option<u64>,
+}
+
+pub fn place_stats<D: Db>(db: &D, place_id: &str, count_views: bool) -> Result<PlaceStats> {
+    let (place, _) = db.get_place_by_id(place_id)?;
+    let mut ratings = db.load_ratings_of_place(place_id)?;
+    ratings.sort_by_key(|r| r.created_at);
+
+    let mut rating_counts = RatingCounts::default();
+    let mut monthly_ratings: Vec<((i32, u32), AvgRatingValueBuilder, usize)> = vec![];
+    for rating in ratings {
+        rating_counts.add(rating.context);
+        let created_at = NaiveDateTime::from(rating.created_at);
+        let year_month = (created_at.year(), created_at.month());
+        match monthly_ratings.last_mut() {
+            Some((last_year_month, avg, count)) if *last_year_month == year_month => {
+                *avg += rating.value;
+                *count += 1;
+            }
+            _ => {
+                let mut avg = AvgRatingValueBuilder::default();
+                avg += rating.value;
+                monthly_ratings.push((year_month, avg, 1));
+            }
+        }
+    }
+    let monthly_avg_ratings = monthly_ratings
+        .into_iter()
+        .map(|((year, month), avg, count)| MonthlyAvgRating {
+            year,
+            month,
+            count,
+            avg: avg.build(),
+        })
+        .collect();
+
+    Ok(PlaceStats {
+        rating_counts,
+        monthly_avg_ratings,
+        revision_count: u64::from(place.revision) + 1,
+        view_count: if count_views {
+            Some(db.count_views(ViewedEntity::Place, place_id)?)
+        } else {
+            None
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::usecases::tests::MockDb;
+    use chrono::NaiveDate;
+
+    fn rating(place_id: &str, ymd: (i32, u32, u32), context: RatingContext, value: i8) -> Rating {
+        Rating {
+            id: Id::new(),
+            place_id: place_id.into(),
+            created_at: NaiveDate::from_ymd(ymd.0, ymd.1, ymd.2)
+                .and_hms(12, 0, 0)
+                .into(),
+            archived_at: None,
+            title: "title".into(),
+            value: value.into(),
+            context,
+            source: None,
+        }
+    }
+
+    #[test]
+    fn count_and_average_ratings_per_month() {
+        let db = MockDb::default();
+        let place = Place::build().id("a").revision(2).finish();
+        db.create_or_update_place(place).unwrap();
+        for r in vec![
+            rating("a", (2021, 5, 30), RatingContext::Fairness, 1),
+            rating("a", (2021, 3, 1), RatingContext::Diversity, 2),
+            rating("a", (2021, 3, 31), RatingContext::Fairness, 0),
+            rating("b", (2021, 3, 1), RatingContext::Fairness, 0),
+        ] {
+            db.create_rating(r).unwrap();
+        }
+        let stats = place_stats(&db, "a", false).unwrap();
+        assert_eq!(3, stats.revision_count);
+        assert_eq!(3, stats.rating_counts.total());
+        assert_eq!(2, stats.rating_counts.fairness);
+        assert_eq!(1, stats.rating_counts.diversity);
+        assert_eq!(
+            vec![
+                MonthlyAvgRating {
+                    year: 2021,
+                    month: 3,
+                    count: 2,
+                    avg: 1.0.into(),
+                },
+                MonthlyAvgRating {
+                    year: 2021,
+                    month: 5,
+                    count: 1,
+                    avg: 1.0.into(),
+                },
+            ],
+            stats.monthly_avg_ratings
+        );
+        assert!(stats.view_count.is_none());
+    }
+}
@@ -0,0 +1,68 @@
+use crate::core::prelude::*;
+
+/// Turns a draft into a regular place that needs to be indexed.
+///
+/// Only the user who created the draft is allowed to publish it.
+/// Should be executed within a transaction.
+pub fn publish_draft<D: Db>(db: &D, id: &str, published_by_email: &str) -> Result<Place> {
+    let (place, status) = db.get_place_by_id(id)?;
+    // Drafts of other users don't exist
+    if status != ReviewStatus::Draft
+        || place.created.by.as_ref().map(Email::as_ref) != Some(published_by_email)
+    {
+        return Err(RepoError::NotFound.into());
+    }
+    let clearance_org_ids = super::authorize_editing_of_tagged_entry(db, &[], &place.tags, None)?;
+    let activity_log = ActivityLog {
+        activity: Activity::now(Some(published_by_email.into())),
+        context: None,
+        comment: Some("published".into()),
+    };
+    db.review_places(&[id], ReviewStatus::Created, &activity_log)?;
+    if !clearance_org_ids.is_empty() {
+        let pending_clearance = PendingClearanceForPlace {
+            place_id: place.id.clone(),
+            created_at: place.created.at,
+            last_cleared_revision: None,
+        };
+        super::clearance::place::add_pending_clearance(db, &clearance_org_ids, &pending_clearance)?;
+    }
+    Ok(place)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    #[test]
+    fn only_the_creator_may_publish_a_draft() {
+        let db = MockDb::default();
+        let mut place = Place::build().id("draft").finish();
+        place.created.by = Some("creator@example.com".into());
+        db.entries.borrow_mut().push((place, ReviewStatus::Draft));
+
+        assert!(db.get_places_by_ids(&["draft"]).unwrap().is_empty());
+        assert_eq!(
+            1,
+            db.drafts_created_by("creator@example.com").unwrap().len()
+        );
+        assert!(db
+            .drafts_created_by("other@example.com")
+            .unwrap()
+            .is_empty());
+
+        assert!(matches!(
+            publish_draft(&db, "draft", "other@example.com"),
+            Err(Error::Repo(RepoError::NotFound))
+        ));
+        let place = publish_draft(&db, "draft", "creator@example.com").unwrap();
+        assert_eq!("draft", place.id.as_str());
+        assert_eq!(
+            ReviewStatus::Created,
+            db.get_place_by_id("draft").unwrap().1
+        );
+        // Already published
+        assert!(publish_draft(&db, "draft", "creator@example.com").is_err());
+    }
+}
@@ -1,17 +1,27 @@
 use super::EventQuery;
 use crate::core::{
     prelude::*,
-    util::{extract_hash_tags, remove_hash_tags},
+    util::{extract_hash_tags, remove_hash_tags, time_zone},
 };
+use chrono::NaiveDateTime;
 use ofdb_core::{bbox, tag};
 
 const DEFAULT_RESULT_LIMIT: usize = 100;
 
+// Search results are paginated by requesting all preceding
+// results from the index
+pub const MAX_EVENT_RESULT_OFFSET: usize = 10_000;
+
 #[allow(clippy::absurd_extreme_comparisons)]
 pub fn query_events<D: Db>(db: &D, index: &dyn IdIndex, query: EventQuery) -> Result<Vec<Event>> {
+    let now = Timestamp::now();
     if query.is_empty() {
         // Special case for backwards compatibility
-        return Ok(db.all_events_chronologically()?);
+        let pagination = Pagination {
+            offset: query.offset.map(|offset| offset as u64),
+            limit: query.limit.map(|limit| limit as u64),
+        };
+        return Ok(db.list_events_chronologically(now, query.since, &pagination)?);
     }
     let EventQuery {
         bbox: visible_bbox,
@@ -20,9 +30,18 @@ pub fn query_events<D: Db>(db: &D, index: &dyn IdIndex, query: EventQuery) -> Re
         start_max,
         tags,
         text,
+        organizer,
+        registration,
+        since,
+        offset,
         limit,
     } = query;
 
+    let offset = offset.unwrap_or(0);
+    if offset > MAX_EVENT_RESULT_OFFSET {
+        return Err(Error::Parameter(ParameterError::InvalidOffset));
+    }
+
     let mut hash_tags = text.as_deref().map(extract_hash_tags).unwrap_or_default();
     if let Some(tags) = tags {
         hash_tags.reserve(hash_tags.len() + tags.len());
@@ -53,6 +72,8 @@ pub fn query_events<D: Db>(db: &D, index: &dyn IdIndex, query: EventQuery) -> Re
         text,
         ts_min_lb: start_min,
         ts_min_ub: start_max,
+        organizer,
+        registration,
         ..Default::default()
     };
 
@@ -63,17 +84,22 @@ pub fn query_events<D: Db>(db: &D, index: &dyn IdIndex, query: EventQuery) -> Re
         );
         DEFAULT_RESULT_LIMIT
     });
+    let index_limit = offset + limit;
 
     // 1st query: Search for visible results only
     // This is required to reliably retrieve all available results!
     // See also: https://github.com/slowtec/openfairdb/issues/183
     let visible_event_ids = index
-        .query_ids(IndexQueryMode::WithoutRating, &visible_events_query, limit)
+        .query_ids(
+            IndexQueryMode::WithoutRating,
+            &visible_events_query,
+            index_limit,
+        )
         .map_err(RepoError::Other)?;
 
     // 2nd query: Search for remaining invisible results
     let invisible_event_ids = if let Some(visible_bbox) = visible_bbox {
-        if visible_event_ids.len() < limit {
+        if visible_event_ids.len() < index_limit {
             let invisible_events_query = IndexQuery {
                 include_bbox: Some(bbox::extend_bbox(&visible_bbox)),
                 exclude_bbox: visible_events_query.include_bbox,
@@ -83,7 +109,7 @@ pub fn query_events<D: Db>(db: &D, index: &dyn IdIndex, query: EventQuery) -> Re
                 .query_ids(
                     IndexQueryMode::WithoutRating,
                     &invisible_events_query,
-                    limit - visible_event_ids.len(),
+                    index_limit - visible_event_ids.len(),
                 )
                 .map_err(RepoError::Other)?
         } else {
@@ -93,12 +119,20 @@ pub fn query_events<D: Db>(db: &D, index: &dyn IdIndex, query: EventQuery) -> Re
         vec![]
     };
 
+    // The preceding pages are skipped in the order of the index
     let event_ids: Vec<_> = visible_event_ids
         .iter()
         .chain(invisible_event_ids.iter())
+        .skip(offset)
         .map(Id::as_str)
         .collect();
     let mut events = db.get_events_chronologically(&event_ids)?;
+    // Never reveal scheduled events
+    events.retain(|e| !e.is_scheduled(now));
+
+    if let Some(since) = since {
+        events.retain(|e| is_modified_since(e, since));
+    }
 
     if let Some(ref email) = created_by {
         if let Some(user) = db.try_get_user_by_email(email)? {
@@ -111,5 +145,52 @@ pub fn query_events<D: Db>(db: &D, index: &dyn IdIndex, query: EventQuery) -> Re
         }
     }
 
+    if start_min.is_some() || start_max.is_some() {
+        events = expand_occurrences(events, start_min, start_max, limit);
+    }
+
     Ok(events)
 }
+
+// Recurring events are replaced by their occurrences
+// that start within the requested time window
+fn expand_occurrences(
+    events: Vec<Event>,
+    start_min: Option<Timestamp>,
+    start_max: Option<Timestamp>,
+    limit: usize,
+) -> Vec<Event> {
+    let start_min = start_min.map(NaiveDateTime::from);
+    let start_max = start_max.map(NaiveDateTime::from);
+    let mut occurrences: Vec<_> = events
+        .into_iter()
+        .flat_map(|event| {
+            if event.recurrence.is_none() {
+                return vec![event];
+            }
+            time_zone::occurrence_times(&event)
+                .skip_while(|(start, _)| start_min.map(|min| *start < min).unwrap_or(false))
+                .take_while(|(start, _)| start_max.map(|max| *start <= max).unwrap_or(true))
+                .take(limit)
+                .map(|(start, end)| Event {
+                    start,
+                    end,
+                    ..event.clone()
+                })
+                .collect()
+        })
+        .collect();
+    // The sort is stable, i.e. the order of events
+    // with the same start is preserved
+    occurrences.sort_by_key(|e| e.start);
+    occurrences.truncate(limit);
+    occurrences
+}
+
+fn is_modified_since(event: &Event, since: Timestamp) -> bool {
+    event
+        .updated_at
+        .or(event.created_at)
+        .map(|at| at >= since)
+        .unwrap_or(false)
+}
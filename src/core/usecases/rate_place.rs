@@ -23,6 +23,9 @@ impl Storable {
     pub fn comment_id(&self) -> &str {
         &self.3.id.as_ref()
     }
+    pub fn comment_text(&self) -> &str {
+        &self.3.text
+    }
 }
 
 pub fn prepare_new_rating<D: Db>(db: &D, r: NewPlaceRating) -> Result<Storable> {
@@ -36,7 +39,7 @@ pub fn prepare_new_rating<D: Db>(db: &D, r: NewPlaceRating) -> Result<Storable>
     let now = Timestamp::now();
     let rating_id = Id::new();
     let comment_id = Id::new();
-    let (place, status) = db.get_place(&r.entry)?;
+    let (place, status) = db.get_place_by_id(&r.entry)?;
     debug_assert_eq!(place.id, r.entry.as_str().into());
     let rating = Rating {
         id: rating_id.clone(),
@@ -68,6 +71,42 @@ pub fn store_new_rating<D: Db>(db: &D, s: Storable) -> Result<(Place, ReviewStat
     Ok((place, status, ratings))
 }
 
+/// Holds back a new rating for moderation instead of publishing it
+pub fn hold_new_rating<D: PendingRatingRepo>(
+    db: &D,
+    s: Storable,
+    created_by: Option<String>,
+    violations: Vec<CommentRuleViolation>,
+) -> Result<PendingRating> {
+    let Storable(place, _, rating, comment) = s;
+    debug_assert_eq!(place.id, rating.place_id);
+    let pending_rating = PendingRating {
+        rating,
+        comment,
+        created_by,
+        violations,
+    };
+    db.add_pending_rating(&pending_rating)?;
+    Ok(pending_rating)
+}
+
+/// Publishes a rating that has been held back for moderation
+pub fn publish_pending_rating<D: Db>(
+    db: &D,
+    id: &str,
+) -> Result<(Place, ReviewStatus, Vec<Rating>)> {
+    let PendingRating {
+        rating, comment, ..
+    } = db
+        .load_pending_ratings(&[id])?
+        .into_iter()
+        .next()
+        .ok_or(RepoError::NotFound)?;
+    let (place, status) = db.get_place_by_id(rating.place_id.as_str())?;
+    db.delete_pending_ratings(&[id])?;
+    store_new_rating(db, Storable(place, status, rating, comment))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -171,4 +210,37 @@ mod tests {
         assert_eq!(db.ratings.borrow()[0].place_id, "foo".into());
         assert_eq!(db.comments.borrow()[0].rating_id, db.ratings.borrow()[0].id);
     }
+
+    #[test]
+    fn hold_and_publish_rating() {
+        let mut db = MockDb::default();
+        let p = Place::build().id("foo").finish();
+        db.entries = vec![(p, ReviewStatus::Created)].into();
+        let s = prepare_new_rating(
+            &db,
+            NewPlaceRating {
+                entry: "foo".into(),
+                comment: "comment".into(),
+                title: "title".into(),
+                context: ofdb_boundary::RatingContext::Fairness,
+                user: None,
+                value: ofdb_boundary::RatingValue::from(2),
+                source: None,
+            },
+        )
+        .unwrap();
+        let rating_id = s.rating_id().to_owned();
+        let pending_rating =
+            hold_new_rating(&db, s, None, vec![CommentRuleViolation::AccountTooNew]).unwrap();
+        assert_eq!(rating_id, pending_rating.rating.id.as_str());
+        assert!(db.ratings.borrow().is_empty());
+        assert!(db.comments.borrow().is_empty());
+        assert_eq!(1, db.pending_ratings.borrow().len());
+
+        let (_, _, ratings) = publish_pending_rating(&db, &rating_id).unwrap();
+        assert_eq!(vec![pending_rating.rating], ratings);
+        assert_eq!(1, db.comments.borrow().len());
+        assert!(db.pending_ratings.borrow().is_empty());
+        assert!(publish_pending_rating(&db, &rating_id).is_err());
+    }
 }
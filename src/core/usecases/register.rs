@@ -1,9 +1,89 @@
 use super::Credentials;
 use crate::core::prelude::*;
 
-pub fn register_with_email<D: UserGateway>(db: &mut D, credentials: &Credentials) -> Result<()> {
+/// Restricts the e-mail domains of new users.
+///
+/// Domains also match their subdomains.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EmailDomainPolicy {
+    /// Only these domains are allowed unless empty
+    pub allowed: Vec<String>,
+    /// e.g. disposable e-mail providers
+    pub denied: Vec<String>,
+}
+
+fn matches_domain(email_domain: &str, domain: &str) -> bool {
+    let domain = domain.trim().trim_start_matches('@').to_lowercase();
+    email_domain == domain || email_domain.ends_with(&format!(".{}", domain))
+}
+
+impl EmailDomainPolicy {
+    pub fn check(&self, email: &str) -> Result<()> {
+        let email_domain = email.rsplit('@').next().unwrap_or_default().to_lowercase();
+        let allowed = self.allowed.is_empty()
+            || self
+                .allowed
+                .iter()
+                .any(|domain| matches_domain(&email_domain, domain));
+        let denied = self
+            .denied
+            .iter()
+            .any(|domain| matches_domain(&email_domain, domain));
+        if !allowed || denied {
+            return Err(Error::Parameter(ParameterError::EmailDomainNotAllowed));
+        }
+        Ok(())
+    }
+}
+
+pub fn register_with_email<D: UserGateway>(
+    db: &mut D,
+    credentials: &Credentials,
+    email_domains: &EmailDomainPolicy,
+) -> Result<()> {
+    email_domains.check(credentials.email)?;
     let password = credentials.password.to_string();
     let email = credentials.email.to_string();
     let new_user = super::NewUser { email, password };
     super::create_new_user(db, new_user)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn register(db: &mut MockDb, email: &str, email_domains: &EmailDomainPolicy) -> Result<()> {
+        let credentials = Credentials {
+            email,
+            password: "secret",
+        };
+        register_with_email(db, &credentials, email_domains)
+    }
+
+    #[test]
+    fn register_with_allowed_email_domains_only() {
+        let mut db = MockDb::default();
+        let email_domains = EmailDomainPolicy {
+            allowed: vec!["example.org".into()],
+            denied: vec!["spam.example.org".into(), "@trashmail.com".into()],
+        };
+        assert!(register(&mut db, "a@example.org", &email_domains).is_ok());
+        assert!(register(&mut db, "b@Mail.Example.org", &email_domains).is_ok());
+        for email in &["c@spam.example.org", "d@example.com", "e@badexample.org"] {
+            assert!(matches!(
+                register(&mut db, email, &email_domains),
+                Err(Error::Parameter(ParameterError::EmailDomainNotAllowed))
+            ));
+        }
+
+        // Without an allow-list all but the denied domains are allowed
+        let email_domains = EmailDomainPolicy {
+            allowed: vec![],
+            ..email_domains
+        };
+        assert!(register(&mut db, "f@example.com", &email_domains).is_ok());
+        assert!(register(&mut db, "g@trashmail.com", &email_domains).is_err());
+        assert_eq!(3, db.count_users().unwrap());
+    }
+}
@@ -0,0 +1,83 @@
+use super::prepare_tag_list;
+use crate::core::prelude::*;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RenamedTag {
+    pub places: Vec<Id>,
+    pub events: Vec<Id>,
+}
+
+pub(super) fn parse_single_tag(tag: &str) -> Result<String> {
+    let mut tags = prepare_tag_list(Some(tag));
+    match tags.len() {
+        1 => Ok(tags.remove(0)),
+        _ => Err(Error::Parameter(ParameterError::Tag)),
+    }
+}
+
+fn replace_tag(tags: &[String], old_tag: &str, new_tag: &str) -> Option<Vec<String>> {
+    if !tags.iter().any(|t| t == old_tag) {
+        return None;
+    }
+    Some(prepare_tag_list(tags.iter().map(|t| {
+        if t == old_tag {
+            new_tag
+        } else {
+            t.as_str()
+        }
+    })))
+}
+
+/// Replaces a tag of all current places and events.
+///
+/// Each affected place gets a new revision that keeps the
+/// review status. Should be executed within a transaction.
+pub fn rename_tag<D: Db>(
+    db: &D,
+    old_tag: &str,
+    new_tag: &str,
+    renamed_by: &Email,
+) -> Result<RenamedTag> {
+    let old_tag = parse_single_tag(old_tag)?;
+    let new_tag = parse_single_tag(new_tag)?;
+    let mut renamed = RenamedTag::default();
+    if old_tag == new_tag {
+        return Ok(renamed);
+    }
+    db.create_tag_if_it_does_not_exist(&Tag {
+        id: new_tag.clone(),
+    })?;
+    let comment = format!("Renamed tag '{}' to '{}'", old_tag, new_tag);
+    for (mut place, status) in db.all_places()? {
+        let tags = match replace_tag(&place.tags, &old_tag, &new_tag) {
+            Some(tags) => tags,
+            None => continue,
+        };
+        place.tags = tags;
+        place.revision = place.revision.next();
+        place.created = Activity::now(Some(renamed_by.clone()));
+        let id = place.id.clone();
+        db.create_or_update_place_with_comment(place, &comment)?;
+        // A new revision needs to be reviewed again,
+        // but renaming a tag doesn't change the place
+        if status != ReviewStatus::Created {
+            let activity_log = ActivityLog {
+                activity: Activity::now(Some(renamed_by.clone())),
+                context: None,
+                comment: Some(comment.clone()),
+            };
+            db.review_places(&[id.as_str()], status, &activity_log)?;
+        }
+        renamed.places.push(id);
+    }
+    for mut event in db.all_events_chronologically()? {
+        let tags = match replace_tag(&event.tags, &old_tag, &new_tag) {
+            Some(tags) => tags,
+            None => continue,
+        };
+        event.tags = tags;
+        db.update_event(&event)?;
+        renamed.events.push(event.id);
+    }
+    Ok(renamed)
+}
@@ -0,0 +1,170 @@
+use crate::core::prelude::*;
+
+// The contact of the place is preferred over its creator
+fn confirmation_recipient(place: &Place) -> Option<Email> {
+    place
+        .contact
+        .as_ref()
+        .and_then(|contact| contact.email.clone())
+        .or_else(|| place.created.by.clone())
+}
+
+/// Asks for the confirmation of all places that have neither
+/// been updated nor confirmed since `stale_before`.
+///
+/// No new request is created as long as a previous request
+/// is still unanswered. The caller is responsible for sending
+/// the returned requests.
+pub fn request_place_confirmations<D: Db>(
+    db: &D,
+    stale_before: TimestampMs,
+) -> Result<Vec<(Place, PlaceConfirmationRequest)>> {
+    let mut new_requests = vec![];
+    for (place, status) in db.all_places()? {
+        if !status.exists() {
+            continue;
+        }
+        let requests = db.load_place_confirmation_requests(place.id.as_str())?;
+        if requests.iter().any(|r| r.answered_at.is_none()) {
+            continue;
+        }
+        let last_confirmed_at = requests
+            .iter()
+            .filter_map(|r| r.answered_at)
+            .chain(db.last_confirmed_at(place.id.as_str())?)
+            .fold(place.created.at, Ord::max);
+        if last_confirmed_at >= stale_before {
+            continue;
+        }
+        let sent_to = match confirmation_recipient(&place) {
+            Some(sent_to) => sent_to,
+            None => {
+                debug!("Nobody to ask for confirming stale place {}", place.id);
+                continue;
+            }
+        };
+        let request = PlaceConfirmationRequest {
+            place_id: place.id.clone(),
+            nonce: Nonce::new(),
+            sent_to,
+            sent_at: TimestampMs::now(),
+            answered_at: None,
+            flagged_at: None,
+        };
+        db.create_place_confirmation_request(&request)?;
+        new_requests.push((place, request));
+    }
+    Ok(new_requests)
+}
+
+/// Flags all requests that have been sent before `sent_before`
+/// and are still unanswered for scouts.
+pub fn flag_unanswered_place_confirmations<D: Db>(
+    db: &D,
+    sent_before: TimestampMs,
+) -> Result<usize> {
+    Ok(db.flag_unanswered_place_confirmation_requests(sent_before, TimestampMs::now())?)
+}
+
+/// Answers a request with the token from the confirmation link.
+pub fn confirm_place_still_valid<D: Db>(db: &D, place_id: &str, token: &str) -> Result<()> {
+    let nonce = token
+        .parse::<Nonce>()
+        .map_err(|_| Error::Parameter(ParameterError::TokenInvalid))?;
+    db.answer_place_confirmation_request(place_id, &nonce, TimestampMs::now())
+        .map_err(|err| match err {
+            RepoError::NotFound => Error::Parameter(ParameterError::TokenInvalid),
+            err => err.into(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn place(id: &str, created_at: TimestampMs, email: Option<&str>) -> Place {
+        let mut place = Place::build().id(id).title(id).finish();
+        place.created = Activity {
+            at: created_at,
+            by: Some("creator@example.com".into()),
+        };
+        place.contact = email.map(|email| Contact {
+            name: None,
+            email: Some(email.into()),
+            phone: None,
+        });
+        place
+    }
+
+    #[test]
+    fn request_confirmation_of_stale_places_once() {
+        let db = MockDb::default();
+        let stale_before = TimestampMs::from_inner(2000);
+        db.entries.borrow_mut().extend(vec![
+            (
+                place(
+                    "stale",
+                    TimestampMs::from_inner(1000),
+                    Some("owner@example.com"),
+                ),
+                ReviewStatus::Created,
+            ),
+            (
+                place("no_contact", TimestampMs::from_inner(1000), None),
+                ReviewStatus::Confirmed,
+            ),
+            (
+                place("archived", TimestampMs::from_inner(1000), None),
+                ReviewStatus::Archived,
+            ),
+            (
+                place("recent", TimestampMs::from_inner(3000), None),
+                ReviewStatus::Created,
+            ),
+        ]);
+
+        let requests = request_place_confirmations(&db, stale_before).unwrap();
+        let recipients: Vec<_> = requests
+            .iter()
+            .map(|(p, r)| (p.id.as_str(), r.sent_to.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("stale", "owner@example.com"),
+                ("no_contact", "creator@example.com")
+            ],
+            recipients
+        );
+        // Pending requests are not repeated
+        assert!(request_place_confirmations(&db, stale_before)
+            .unwrap()
+            .is_empty());
+
+        let nonce = requests[0].1.nonce.to_string();
+        assert!(confirm_place_still_valid(&db, "no_contact", &nonce).is_err());
+        assert!(confirm_place_still_valid(&db, "stale", "invalid").is_err());
+        confirm_place_still_valid(&db, "stale", &nonce).unwrap();
+        assert!(confirm_place_still_valid(&db, "stale", &nonce).is_err());
+
+        let flag_before = TimestampMs::from_inner(TimestampMs::now().into_inner() + 1);
+        assert_eq!(
+            1,
+            flag_unanswered_place_confirmations(&db, flag_before).unwrap()
+        );
+        let flagged = db.load_flagged_place_confirmation_requests().unwrap();
+        assert_eq!(1, flagged.len());
+        assert_eq!("no_contact", flagged[0].place_id.as_str());
+
+        // The answer counts as a confirmation
+        assert!(request_place_confirmations(&db, stale_before)
+            .unwrap()
+            .is_empty());
+        let ids: Vec<_> = request_place_confirmations(&db, flag_before)
+            .unwrap()
+            .into_iter()
+            .map(|(p, _)| p.id)
+            .collect();
+        assert_eq!(vec![Id::from("stale"), Id::from("recent")], ids);
+    }
+}
@@ -0,0 +1,336 @@
+use super::{import_osm_nodes::osm_street, UpdatePlace};
+use crate::core::prelude::*;
+use ofdb_core::gateways::osm::OsmNode;
+
+/// Smaller movements are considered as corrections of the imprecise position
+const MAX_UNCHANGED_DISTANCE: Distance = Distance::from_meters(10.0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsmNodeChange {
+    Moved,
+    Renamed,
+    AddressChanged,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OsmNodeProposalOutcome {
+    Unchanged,
+    /// The same changes are still pending
+    AlreadyProposed,
+    /// All changes have already been rejected by a scout
+    Rejected,
+    Proposed(OsmNodeProposal),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OsmResyncReport {
+    pub proposed: usize,
+    pub unchanged: usize,
+    /// Only changes that have been rejected before
+    pub rejected: usize,
+    /// The node doesn't exist anymore upstream
+    pub deleted: usize,
+    pub failed: usize,
+}
+
+pub fn osm_nodes_by_id_query(ids: &[u64]) -> String {
+    let ids: Vec<_> = ids.iter().map(ToString::to_string).collect();
+    format!("node(id:{});out;", ids.join(","))
+}
+
+fn osm_pos(node: &OsmNode) -> Option<MapPoint> {
+    MapPoint::try_from_lat_lng_deg(node.lat, node.lon)
+        .ok()
+        .filter(|pos| pos.is_valid())
+}
+
+fn osm_title(node: &OsmNode) -> Option<&str> {
+    node.tags
+        .get("name")
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+}
+
+// Only the address fields that are tagged in OSM are considered
+fn osm_address(node: &OsmNode) -> Address {
+    let tag = |key: &str| node.tags.get(key).cloned();
+    Address {
+        street: osm_street(node),
+        zip: tag("addr:postcode"),
+        city: tag("addr:city"),
+        country: tag("addr:country"),
+        state: tag("addr:state"),
+    }
+}
+
+fn merge_address(old: &mut Address, new: Address) {
+    let Address {
+        street,
+        zip,
+        city,
+        country,
+        state,
+    } = new;
+    old.street = street.or_else(|| old.street.take());
+    old.zip = zip.or_else(|| old.zip.take());
+    old.city = city.or_else(|| old.city.take());
+    old.country = country.or_else(|| old.country.take());
+    old.state = state.or_else(|| old.state.take());
+}
+
+pub fn osm_node_changes(place: &Place, node: &OsmNode) -> Vec<OsmNodeChange> {
+    let mut changes = vec![];
+    if let Some(pos) = osm_pos(node) {
+        let moved = MapPoint::distance(place.location.pos, pos)
+            .map_or(true, |distance| distance > MAX_UNCHANGED_DISTANCE);
+        if moved {
+            changes.push(OsmNodeChange::Moved);
+        }
+    }
+    if let Some(title) = osm_title(node) {
+        if title != place.title {
+            changes.push(OsmNodeChange::Renamed);
+        }
+    }
+    let old_address = place.location.address.clone().unwrap_or_default();
+    let mut new_address = old_address.clone();
+    merge_address(&mut new_address, osm_address(node));
+    if new_address != old_address {
+        changes.push(OsmNodeChange::AddressChanged);
+    }
+    changes
+}
+
+fn is_same_pos(a: Option<MapPoint>, b: Option<MapPoint>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            MapPoint::distance(a, b).map_or(false, |distance| distance <= MAX_UNCHANGED_DISTANCE)
+        }
+        (a, b) => a.is_none() && b.is_none(),
+    }
+}
+
+fn has_same_changes(a: &OsmNodeProposal, b: &OsmNodeProposal) -> bool {
+    a.title == b.title && is_same_pos(a.pos, b.pos) && a.address == b.address
+}
+
+/// Collects the upstream changes of an imported place
+/// without modifying it.
+pub fn osm_node_proposal(place: &Place, node: &OsmNode) -> Option<OsmNodeProposal> {
+    let changes = osm_node_changes(place, node);
+    if changes.is_empty() {
+        return None;
+    }
+    Some(OsmNodeProposal {
+        id: Id::new(),
+        place_id: place.id.clone(),
+        osm_node_id: node.id,
+        place_revision: place.revision,
+        created_at: TimestampMs::now(),
+        title: osm_title(node)
+            .filter(|_| changes.contains(&OsmNodeChange::Renamed))
+            .map(ToString::to_string),
+        pos: osm_pos(node).filter(|_| changes.contains(&OsmNodeChange::Moved)),
+        address: Some(osm_address(node))
+            .filter(|_| changes.contains(&OsmNodeChange::AddressChanged)),
+        status: OsmNodeProposalStatus::Pending,
+        reviewed: None,
+    })
+}
+
+/// Stores the upstream changes of an imported place as a
+/// proposal that replaces any pending proposal of the place.
+/// Changes that have already been rejected are not proposed again.
+pub fn propose_osm_node_changes<R: OsmNodeProposalRepo>(
+    repo: &R,
+    place: &Place,
+    node: &OsmNode,
+) -> Result<OsmNodeProposalOutcome> {
+    let mut proposal = match osm_node_proposal(place, node) {
+        Some(proposal) => proposal,
+        None => return Ok(OsmNodeProposalOutcome::Unchanged),
+    };
+    let proposals = repo.load_osm_node_proposals_of_place(place.id.as_str())?;
+    for rejected in proposals
+        .iter()
+        .filter(|p| p.status == OsmNodeProposalStatus::Rejected)
+    {
+        if rejected.title.is_some() && proposal.title == rejected.title {
+            proposal.title = None;
+        }
+        if rejected.pos.is_some() && is_same_pos(proposal.pos, rejected.pos) {
+            proposal.pos = None;
+        }
+        if rejected.address.is_some() && proposal.address == rejected.address {
+            proposal.address = None;
+        }
+    }
+    if proposal.title.is_none() && proposal.pos.is_none() && proposal.address.is_none() {
+        return Ok(OsmNodeProposalOutcome::Rejected);
+    }
+    if proposals
+        .iter()
+        .filter(|p| p.status == OsmNodeProposalStatus::Pending)
+        .any(|p| has_same_changes(p, &proposal))
+    {
+        return Ok(OsmNodeProposalOutcome::AlreadyProposed);
+    }
+    repo.delete_pending_osm_node_proposals_of_place(place.id.as_str())?;
+    repo.add_osm_node_proposal(&proposal)?;
+    Ok(OsmNodeProposalOutcome::Proposed(proposal))
+}
+
+/// Applies the accepted changes to the current revision
+/// of an imported place.
+pub fn update_place_from_osm_proposal(place: Place, proposal: &OsmNodeProposal) -> UpdatePlace {
+    let mut address = place.location.address.clone().unwrap_or_default();
+    if let Some(new_address) = proposal.address.clone() {
+        merge_address(&mut address, new_address);
+    }
+    let mut update = UpdatePlace::from(place);
+    update.version += 1;
+    if let Some(pos) = proposal.pos {
+        update.lat = pos.lat().to_deg();
+        update.lng = pos.lng().to_deg();
+    }
+    if let Some(ref title) = proposal.title {
+        update.title = title.clone();
+    }
+    let Address {
+        street,
+        zip,
+        city,
+        country,
+        state,
+    } = address;
+    update.street = street;
+    update.zip = zip;
+    update.city = city;
+    update.country = country;
+    update.state = state;
+    update
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn imported_place() -> Place {
+        let mut place = Place::build()
+            .id("imported")
+            .title("Bioladen")
+            .pos(MapPoint::from_lat_lng_deg(48.7, 9.1))
+            .finish();
+        place.location.address = Some(Address {
+            street: Some("Hauptstraße 1".into()),
+            city: Some("Stuttgart".into()),
+            ..Default::default()
+        });
+        place
+    }
+
+    fn node(lat: f64, tags: &[(&str, &str)]) -> OsmNode {
+        OsmNode {
+            id: 1,
+            lat,
+            lon: 9.1,
+            tags: tags
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn detect_upstream_changes() {
+        let place = imported_place();
+        assert!(osm_node_changes(&place, &node(48.7, &[("name", "Bioladen")])).is_empty());
+        // Only tagged address fields are compared
+        assert!(osm_node_changes(&place, &node(48.7, &[("addr:city", "Stuttgart")])).is_empty());
+        assert_eq!(
+            vec![OsmNodeChange::Moved, OsmNodeChange::Renamed],
+            osm_node_changes(&place, &node(48.8, &[("name", "Unverpackt")]))
+        );
+        assert_eq!(
+            vec![OsmNodeChange::AddressChanged],
+            osm_node_changes(
+                &place,
+                &node(
+                    48.70001,
+                    &[("addr:street", "Hauptstraße"), ("addr:housenumber", "2")]
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn propose_only_changed_fields() {
+        let place = imported_place();
+        assert!(osm_node_proposal(&place, &node(48.7, &[("name", "Bioladen")])).is_none());
+        let proposal = osm_node_proposal(&place, &node(48.7, &[("name", "Unverpackt")])).unwrap();
+        assert_eq!(place.id, proposal.place_id);
+        assert_eq!(place.revision, proposal.place_revision);
+        assert_eq!(Some("Unverpackt"), proposal.title.as_deref());
+        assert!(proposal.pos.is_none());
+        assert!(proposal.address.is_none());
+        assert_eq!(OsmNodeProposalStatus::Pending, proposal.status);
+    }
+
+    #[test]
+    fn do_not_propose_rejected_changes_again() {
+        let db = MockDb::default();
+        let place = imported_place();
+        let renamed = node(48.7, &[("name", "Unverpackt")]);
+        let proposal = match propose_osm_node_changes(&db, &place, &renamed).unwrap() {
+            OsmNodeProposalOutcome::Proposed(proposal) => proposal,
+            outcome => panic!("Unexpected outcome: {:?}", outcome),
+        };
+        assert_eq!(
+            OsmNodeProposalOutcome::AlreadyProposed,
+            propose_osm_node_changes(&db, &place, &renamed).unwrap()
+        );
+        db.review_osm_node_proposal(
+            proposal.id.as_str(),
+            OsmNodeProposalStatus::Rejected,
+            &Activity::now(Some("scout@example.com".into())),
+        )
+        .unwrap();
+        assert_eq!(
+            OsmNodeProposalOutcome::Rejected,
+            propose_osm_node_changes(&db, &place, &renamed).unwrap()
+        );
+        // Only the new changes are proposed
+        let moved = node(48.8, &[("name", "Unverpackt")]);
+        match propose_osm_node_changes(&db, &place, &moved).unwrap() {
+            OsmNodeProposalOutcome::Proposed(proposal) => {
+                assert!(proposal.title.is_none());
+                assert!(proposal.pos.is_some());
+            }
+            outcome => panic!("Unexpected outcome: {:?}", outcome),
+        }
+        assert_eq!(2, db.osm_node_proposals.borrow().len());
+    }
+
+    #[test]
+    fn apply_accepted_proposal() {
+        let place = imported_place();
+        let proposal = osm_node_proposal(
+            &place,
+            &node(48.8, &[("name", "Unverpackt"), ("addr:postcode", "70173")]),
+        )
+        .unwrap();
+        let update = update_place_from_osm_proposal(place.clone(), &proposal);
+        assert_eq!(u64::from(place.revision) + 1, update.version);
+        assert_eq!("Unverpackt", update.title);
+        assert!((update.lat - 48.8).abs() < 1e-6);
+        assert_eq!(Some("Hauptstraße 1"), update.street.as_deref());
+        assert_eq!(Some("70173"), update.zip.as_deref());
+        assert_eq!(Some("Stuttgart"), update.city.as_deref());
+    }
+
+    #[test]
+    fn query_nodes_by_id() {
+        assert_eq!("node(id:1,42);out;", osm_nodes_by_id_query(&[1, 42]));
+    }
+}
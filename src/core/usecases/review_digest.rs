@@ -0,0 +1,105 @@
+use crate::core::prelude::*;
+
+/// Pending places within the subscribed areas of a scout.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewDigest {
+    pub email: String,
+    /// Ordered by creation, oldest first
+    pub places: Vec<Place>,
+}
+
+/// Collects the places that have not been reviewed yet
+/// for all scouts with at least one subscribed area.
+///
+/// Scouts without pending places in their areas are omitted.
+pub fn review_digests<D: Db>(db: &D) -> Result<Vec<ReviewDigest>> {
+    let mut pending: Vec<_> = db
+        .all_places()?
+        .into_iter()
+        .filter(|(_, status)| *status == ReviewStatus::Created)
+        .map(|(place, _)| place)
+        .collect();
+    pending.sort_by_key(|place| place.created.at);
+    let mut digests = vec![];
+    for scout in db
+        .all_users()?
+        .into_iter()
+        .filter(|user| user.role == Role::Scout)
+    {
+        let areas: Vec<_> = db
+            .all_bbox_subscriptions_by_email(&scout.email)?
+            .into_iter()
+            .map(|s| s.bbox)
+            .collect();
+        let places: Vec<_> = pending
+            .iter()
+            .filter(|place| {
+                areas
+                    .iter()
+                    .any(|bbox| bbox.contains_point(place.location.pos))
+            })
+            .cloned()
+            .collect();
+        if !places.is_empty() {
+            digests.push(ReviewDigest {
+                email: scout.email,
+                places,
+            });
+        }
+    }
+    Ok(digests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+    use crate::core::util::geo::MapBbox;
+
+    fn create_user(db: &MockDb, email: &str, role: Role) {
+        db.create_user(&User {
+            email: email.into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role,
+        })
+        .unwrap();
+        db.create_bbox_subscription(&BboxSubscription {
+            id: Id::new(),
+            user_email: email.into(),
+            bbox: MapBbox::new(
+                MapPoint::from_lat_lng_deg(0.0, 0.0),
+                MapPoint::from_lat_lng_deg(10.0, 10.0),
+            ),
+            digest: None,
+        })
+        .unwrap();
+    }
+
+    fn place(id: &str, lat: f64, status: ReviewStatus) -> (Place, ReviewStatus) {
+        let place = Place::build()
+            .id(id)
+            .title(id)
+            .pos(MapPoint::from_lat_lng_deg(lat, 5.0))
+            .finish();
+        (place, status)
+    }
+
+    #[test]
+    fn collect_pending_places_within_the_areas_of_scouts() {
+        let db = MockDb::default();
+        create_user(&db, "scout@example.com", Role::Scout);
+        create_user(&db, "user@example.com", Role::User);
+        db.entries.borrow_mut().extend(vec![
+            place("a", 5.0, ReviewStatus::Created),
+            place("b", 5.0, ReviewStatus::Confirmed),
+            place("c", 20.0, ReviewStatus::Created),
+        ]);
+
+        let digests = review_digests(&db).unwrap();
+        assert_eq!(1, digests.len());
+        assert_eq!("scout@example.com", digests[0].email);
+        assert_eq!(1, digests[0].places.len());
+        assert_eq!("a", digests[0].places[0].id.as_str());
+    }
+}
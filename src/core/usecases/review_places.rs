@@ -15,6 +15,10 @@ pub fn review_places<D: Db>(db: &D, ids: &[&str], review: Review) -> Result<usiz
         status,
         comment,
     } = review;
+    // Only the creator of a draft is allowed to publish it
+    if status == ReviewStatus::Draft {
+        return Err(Error::Parameter(ParameterError::Forbidden));
+    }
     let activity = Activity::now(Some(reviewer_email));
     //  TODO: Verify user role here instead of in web api
     info!(
@@ -1,6 +1,6 @@
 use crate::core::{prelude::*, util};
 use ofdb_core::{bbox, tag};
-use ofdb_entities::geo::MapBbox;
+use ofdb_entities::geo::{MapBbox, MapPolygon};
 
 use std::collections::HashMap;
 
@@ -14,6 +14,35 @@ pub struct SearchRequest<'a> {
     pub hash_tags  : Vec<&'a str>,
     pub text       : Option<&'a str>,
     pub status     : Vec<ReviewStatus>,
+    pub country    : Option<&'a str>,
+    pub region     : Option<&'a str>,
+    pub excluded_categories : Vec<&'a str>,
+    pub excluded_hash_tags  : Vec<&'a str>,
+    pub min_avg_rating      : Option<AvgRatingValue>,
+    pub min_rating_count    : Option<u32>,
+    pub confirmed_since     : Option<TimestampMs>,
+    pub boost_freshness     : bool,
+    pub order_by_quality    : bool,
+    pub fuzziness           : Option<u8>,
+    // Replaces the bounding box
+    pub area                : Option<MapPolygon>,
+}
+
+impl<'a> SearchRequest<'a> {
+    /// The bounding box of the area if available.
+    fn visible_bbox(&self) -> MapBbox {
+        self.area
+            .as_ref()
+            .map(MapPolygon::bbox)
+            .unwrap_or(self.bbox)
+    }
+
+    fn is_visible(&self, pos: MapPoint) -> bool {
+        match &self.area {
+            Some(area) => area.contains_point(pos),
+            None => self.bbox.contains_point(pos),
+        }
+    }
 }
 
 pub fn clear_search_results<D: Db>(
@@ -48,7 +77,7 @@ pub fn clear_search_results<D: Db>(
                 let Place {
                     description,
                     id,
-                    location: Location { pos, .. },
+                    location: Location { pos, address },
                     tags,
                     title,
                     ..
@@ -59,15 +88,19 @@ pub fn clear_search_results<D: Db>(
                 }
                 // Ratings are independent of the revision
                 let ratings = place.ratings;
+                // The quality score of the current revision is kept
+                let quality = place.quality;
                 // Replace the actual/current search result item with the last cleared revision
                 place = IndexedPlace {
                     id: id.into(),
                     description,
                     pos,
                     ratings,
+                    quality,
                     status: Some(current_status),
                     tags,
                     title,
+                    address,
                 };
             } else {
                 // Skip newly created but not yet cleared entry
@@ -79,20 +112,27 @@ pub fn clear_search_results<D: Db>(
     Ok(cleared_results)
 }
 
-pub fn search<D: Db>(
-    db: &D,
-    index: &dyn PlaceIndex,
-    req: SearchRequest,
-    limit: usize,
-) -> Result<(Vec<IndexedPlace>, Vec<IndexedPlace>)> {
+fn visible_places_query(req: SearchRequest<'_>) -> IndexQuery<'_, '_> {
+    let visible_bbox = req.visible_bbox();
     let SearchRequest {
-        bbox: visible_bbox,
+        bbox: _,
         ids,
         categories,
         org_tag,
         hash_tags: req_hash_tags,
         text,
         status,
+        country,
+        region,
+        excluded_categories,
+        excluded_hash_tags,
+        min_avg_rating,
+        min_rating_count,
+        confirmed_since,
+        boost_freshness,
+        order_by_quality,
+        fuzziness,
+        area: _,
     } = req;
 
     let mut hash_tags = text.map(util::extract_hash_tags).unwrap_or_default();
@@ -117,7 +157,7 @@ pub fn search<D: Db>(
         .map(tag::split_text_into_tags)
         .unwrap_or_default();
 
-    let visible_places_query = IndexQuery {
+    IndexQuery {
         include_bbox: Some(visible_bbox),
         exclude_bbox: None,
         categories,
@@ -126,18 +166,84 @@ pub fn search<D: Db>(
         text_tags,
         text,
         status: Some(status),
+        country: country.map(ToOwned::to_owned),
+        region: region.map(ToOwned::to_owned),
+        excluded_categories,
+        excluded_hash_tags: excluded_hash_tags
+            .into_iter()
+            .map(ToOwned::to_owned)
+            .collect(),
+        min_avg_rating,
+        min_rating_count,
+        confirmed_since,
+        boost_freshness,
+        order_by_quality,
+        fuzziness,
         ..Default::default()
-    };
+    }
+}
+
+// The bounding box of an area might contain many more places
+// than the area itself. The limit for prefiltering the places
+// by the bounding box is increased at most up to this factor.
+const MAX_AREA_PREFILTER_LIMIT_FACTOR: usize = 8;
+
+// Returns the places within the area and the
+// remaining places within its bounding box.
+fn query_places_within_area(
+    index: &dyn PlaceIndex,
+    query: &IndexQuery,
+    area: &MapPolygon,
+    limit: usize,
+) -> Result<(Vec<IndexedPlace>, Vec<IndexedPlace>)> {
+    let mut prefilter_limit = limit;
+    loop {
+        let places = index
+            .query_places(query, prefilter_limit)
+            .map_err(RepoError::Other)?;
+        let exhausted = places.len() < prefilter_limit;
+        let (mut within, outside): (Vec<_>, Vec<_>) =
+            places.into_iter().partition(|p| area.contains_point(p.pos));
+        if within.len() >= limit
+            || exhausted
+            || prefilter_limit >= limit * MAX_AREA_PREFILTER_LIMIT_FACTOR
+        {
+            within.truncate(limit);
+            return Ok((within, outside));
+        }
+        prefilter_limit *= 2;
+    }
+}
+
+/// Searches for places within the bounding box or the area
+/// of the request. Places within the area are filtered after
+/// querying its bounding box.
+///
+/// Nearby places that are not visible are returned separately.
+pub fn search<D: Db>(
+    db: &D,
+    index: &dyn PlaceIndex,
+    req: SearchRequest,
+    limit: usize,
+) -> Result<(Vec<IndexedPlace>, Vec<IndexedPlace>)> {
+    let visible_bbox = req.visible_bbox();
+    let visible_req = req.clone();
+    let org_tag = req.org_tag;
+    let visible_places_query = visible_places_query(req);
 
     // 1st query: Search for visible results only
     // This is required to reliably retrieve all available results!
     // See also: https://github.com/slowtec/openfairdb/issues/183
-    let mut visible_places = index
-        .query_places(&visible_places_query, limit)
-        .map_err(RepoError::Other)?;
-    debug_assert!(visible_places
-        .iter()
-        .all(|e| visible_bbox.contains_point(e.pos)));
+    let (mut visible_places, mut outside_area_places) = match &visible_req.area {
+        Some(area) => query_places_within_area(index, &visible_places_query, area, limit)?,
+        None => (
+            index
+                .query_places(&visible_places_query, limit)
+                .map_err(RepoError::Other)?,
+            vec![],
+        ),
+    };
+    debug_assert!(visible_places.iter().all(|e| visible_req.is_visible(e.pos)));
     if let Some(org_tag) = org_tag {
         if let Some(org_id) = db.map_tag_to_clearance_org_id(org_tag)? {
             visible_places = clear_search_results(db, &org_id, org_tag, visible_places)?;
@@ -145,21 +251,29 @@ pub fn search<D: Db>(
     }
 
     // 2nd query: Search for remaining invisible results
+    // that are not within the bounding box
     let mut invisible_places = if visible_places.len() < limit {
-        let invisible_places_query = IndexQuery {
-            include_bbox: Some(bbox::extend_bbox(&visible_bbox)),
-            exclude_bbox: visible_places_query.include_bbox,
-            ..visible_places_query
-        };
-        index
-            .query_places(&invisible_places_query, limit - visible_places.len())
-            .map_err(RepoError::Other)?
+        outside_area_places.truncate(limit - visible_places.len());
+        let remaining_limit = limit - visible_places.len() - outside_area_places.len();
+        if remaining_limit > 0 {
+            let invisible_places_query = IndexQuery {
+                include_bbox: Some(bbox::extend_bbox(&visible_bbox)),
+                exclude_bbox: visible_places_query.include_bbox,
+                ..visible_places_query
+            };
+            outside_area_places.extend(
+                index
+                    .query_places(&invisible_places_query, remaining_limit)
+                    .map_err(RepoError::Other)?,
+            );
+        }
+        outside_area_places
     } else {
         vec![]
     };
     debug_assert!(!invisible_places
         .iter()
-        .any(|e| visible_bbox.contains_point(e.pos)));
+        .any(|e| visible_req.is_visible(e.pos)));
     if let Some(org_tag) = org_tag {
         if let Some(org_id) = db.map_tag_to_clearance_org_id(org_tag)? {
             invisible_places = clear_search_results(db, &org_id, &org_tag, invisible_places)?;
@@ -169,13 +283,294 @@ pub fn search<D: Db>(
     Ok((visible_places, invisible_places))
 }
 
+/// The number of visible places per category and tag.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchFacets {
+    // Ordered by descending count
+    pub categories: Vec<(Id, TagCount)>,
+    // Ordered by descending count
+    pub tags: Vec<TagFrequency>,
+}
+
+fn split_tag_counts(tag_counts: Vec<TagFrequency>, max_tags: usize) -> SearchFacets {
+    let place_categories = [Category::new_non_profit(), Category::new_commercial()];
+    let mut facets = SearchFacets::default();
+    for TagFrequency(tag, count) in tag_counts {
+        if let Some(category) = place_categories.iter().find(|c| c.tag == tag) {
+            facets.categories.push((category.id.clone(), count));
+        } else if facets.tags.len() < max_tags {
+            facets.tags.push(TagFrequency(tag, count));
+        }
+    }
+    facets
+}
+
+/// Counts the places within the bounding box that match the
+/// search request per category and tag. Only the most frequent
+/// tags are returned.
+///
+/// The results of organizations are not replaced by their last
+/// cleared revisions, i.e. the counts might differ slightly.
+/// Places are only counted within the bounding box of an area.
+pub fn search_facets(
+    index: &dyn PlaceIndex,
+    req: SearchRequest,
+    max_tags: usize,
+) -> Result<SearchFacets> {
+    let query = visible_places_query(req);
+    let tag_counts = index.count_place_tags(&query).map_err(RepoError::Other)?;
+    Ok(split_tag_counts(tag_counts, max_tags))
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OrgSearchRequest<'a> {
+    pub bbox: Option<MapBbox>,
+    pub text: Option<&'a str>,
+}
+
+#[derive(Debug)]
+pub struct OrgSearchResults {
+    pub places: Vec<(IndexedPlace, Option<PendingClearanceForPlace>)>,
+    pub events: Vec<Event>,
+}
+
+/// Search for places and events that carry at least one of the
+/// tags owned by an organization.
+///
+/// Unlike the public search the results are neither restricted to
+/// visible entries nor replaced by their last cleared revision. Instead
+/// the pending clearance of each place is returned.
+pub fn search_org<D: Db>(
+    db: &D,
+    place_index: &dyn PlaceIndex,
+    event_index: &dyn IdIndex,
+    org: &Organization,
+    req: OrgSearchRequest,
+    limit: usize,
+) -> Result<OrgSearchResults> {
+    let OrgSearchRequest { bbox, text } = req;
+
+    let text_hash_tags = text.map(util::extract_hash_tags).unwrap_or_default();
+
+    let text = text.map(util::remove_hash_tags).and_then(|text| {
+        if text.trim().is_empty() {
+            None
+        } else {
+            Some(text)
+        }
+    });
+
+    let text_tags = text
+        .as_deref()
+        .map(tag::split_text_into_tags)
+        .unwrap_or_default();
+
+    let mut places: Vec<IndexedPlace> = vec![];
+    let mut event_ids: Vec<Id> = vec![];
+    // Each query matches all of its hash tags, i.e. the
+    // owned tags need to be queried one after another
+    for org_tag in org.moderated_tags.iter().map(|t| &t.label) {
+        let mut hash_tags = text_hash_tags.clone();
+        hash_tags.push(org_tag.to_owned());
+        let places_query = IndexQuery {
+            include_bbox: bbox,
+            excluded_categories: vec![Category::ID_EVENT],
+            hash_tags,
+            text_tags: text_tags.clone(),
+            text: text.clone(),
+            // Independent of the review status
+            status: None,
+            ..Default::default()
+        };
+        if places.len() < limit {
+            for place in place_index
+                .query_places(&places_query, limit)
+                .map_err(RepoError::Other)?
+            {
+                if !places.iter().any(|p| p.id == place.id) {
+                    places.push(place);
+                }
+            }
+        }
+        if event_ids.len() < limit {
+            let events_query = IndexQuery {
+                categories: vec![Category::ID_EVENT],
+                excluded_categories: vec![],
+                ..places_query
+            };
+            for id in event_index
+                .query_ids(IndexQueryMode::WithoutRating, &events_query, limit)
+                .map_err(RepoError::Other)?
+            {
+                if !event_ids.contains(&id) {
+                    event_ids.push(id);
+                }
+            }
+        }
+    }
+    places.truncate(limit);
+    event_ids.truncate(limit);
+
+    let place_ids: Vec<_> = places.iter().map(|p| p.id.as_str()).collect();
+    let mut pending_clearances: HashMap<_, _> = db
+        .load_pending_clearances_for_places(&org.id, &place_ids)?
+        .into_iter()
+        .map(|p| (p.place_id.to_string(), p))
+        .collect();
+    let places = places
+        .into_iter()
+        .map(|place| {
+            let pending_clearance = pending_clearances.remove(&place.id);
+            (place, pending_clearance)
+        })
+        .collect();
+
+    let event_ids: Vec<_> = event_ids.iter().map(Id::as_str).collect();
+    // Scheduled events are included
+    let events = db
+        .get_events_chronologically(&event_ids)?
+        .into_iter()
+        .map(|event| {
+            super::filter_event(event, org.moderated_tags.iter().map(|t| t.label.as_str()))
+        })
+        .collect();
+
+    Ok(OrgSearchResults { places, events })
+}
+
+// The index does not order the candidates by their distance
+const MAX_NEARBY_CANDIDATES: usize = 1000;
+
+// Bounds the narrowing of the search radius
+const MAX_NEARBY_QUERIES: usize = 8;
+
+/// Search for places within the given radius around a center
+/// point. The results are sorted by their distance.
+///
+/// If there are too many candidates within the radius the
+/// search radius is narrowed until all candidates can be
+/// compared by their distance. Otherwise the results would
+/// be an arbitrary selection of nearby places.
+pub fn search_nearby(
+    index: &dyn PlaceIndex,
+    center: MapPoint,
+    radius: Distance,
+    hash_tags: Vec<String>,
+    limit: usize,
+) -> Result<Vec<(IndexedPlace, Distance)>> {
+    search_nearby_candidates(
+        index,
+        center,
+        radius,
+        &hash_tags,
+        limit,
+        MAX_NEARBY_CANDIDATES,
+    )
+}
+
+fn search_nearby_candidates(
+    index: &dyn PlaceIndex,
+    center: MapPoint,
+    radius: Distance,
+    hash_tags: &[String],
+    limit: usize,
+    max_candidates: usize,
+) -> Result<Vec<(IndexedPlace, Distance)>> {
+    // Bisection of the search radius: All candidates within
+    // the lower bound are known, the upper bound has too many
+    let mut lower_meters = 0.0;
+    let mut upper_meters = radius.to_meters();
+    let mut search_radius = radius;
+    // The closest places of the widest complete search are
+    // an exact prefix of the results, even if incomplete
+    let mut closest_places = None;
+    let mut truncated_places = vec![];
+    for _ in 0..MAX_NEARBY_QUERIES {
+        let (places, complete) =
+            query_nearby_places(index, center, search_radius, hash_tags, max_candidates)?;
+        if complete {
+            if places.len() >= limit || search_radius >= radius {
+                return Ok(sorted_by_distance(places, limit));
+            }
+            lower_meters = search_radius.to_meters();
+            closest_places = Some(places);
+        } else {
+            upper_meters = search_radius.to_meters();
+            truncated_places = places;
+        }
+        search_radius = Distance::from_meters((lower_meters + upper_meters) / 2.0);
+    }
+    warn!(
+        "Too many places within {} m around {:?} for a nearby search",
+        upper_meters, center
+    );
+    Ok(sorted_by_distance(
+        closest_places.unwrap_or(truncated_places),
+        limit,
+    ))
+}
+
+// Returns the places within the radius and whether
+// the index found less than the maximum number
+fn query_nearby_places(
+    index: &dyn PlaceIndex,
+    center: MapPoint,
+    radius: Distance,
+    hash_tags: &[String],
+    max_candidates: usize,
+) -> Result<(Vec<(IndexedPlace, Distance)>, bool)> {
+    let diameter = Distance::from_meters(2.0 * radius.to_meters());
+    let bbox = MapBbox::centered_around(center, diameter, diameter);
+    let index_query = IndexQuery {
+        include_bbox: Some(bbox),
+        hash_tags: hash_tags.to_vec(),
+        status: Some(vec![]),
+        ..Default::default()
+    };
+    let candidates = index
+        .query_places(&index_query, max_candidates)
+        .map_err(RepoError::Other)?;
+    let complete = candidates.len() < max_candidates;
+    let places = candidates
+        .into_iter()
+        .filter_map(|place| {
+            // The corners of the bounding box are outside of the radius
+            let distance = MapPoint::distance(center, place.pos)?;
+            if distance <= radius {
+                Some((place, distance))
+            } else {
+                None
+            }
+        })
+        .collect();
+    Ok((places, complete))
+}
+
+fn sorted_by_distance(
+    mut places: Vec<(IndexedPlace, Distance)>,
+    limit: usize,
+) -> Vec<(IndexedPlace, Distance)> {
+    places.sort_by(|(_, d1), (_, d2)| d1.partial_cmp(d2).unwrap_or(std::cmp::Ordering::Equal));
+    places.truncate(limit);
+    places
+}
+
 /// The global search usecase is like the one
 /// of usual internet search engines that exists
 /// of only one single search input.
 /// So here we don't care about tags, categories etc.
 /// We also ignore the rating of an entry for now.
-pub fn global_search(index: &dyn PlaceIndex, txt: &str, limit: usize) -> Result<Vec<IndexedPlace>> {
+///
+/// The results are optionally restricted to a region,
+/// e.g. the approximate location of the user.
+pub fn global_search(
+    index: &dyn PlaceIndex,
+    txt: &str,
+    bbox: Option<MapBbox>,
+    limit: usize,
+) -> Result<Vec<IndexedPlace>> {
     let index_query = IndexQuery {
+        include_bbox: bbox,
         text: Some(txt.into()),
         ..Default::default()
     };
@@ -186,3 +581,293 @@ pub fn global_search(index: &dyn PlaceIndex, txt: &str, limit: usize) -> Result<
 
     Ok(entries)
 }
+
+// Only the best rated candidates are considered
+const MAX_SUGGESTION_CANDIDATES: usize = 100;
+
+/// A completion of a search text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchSuggestion {
+    Place { id: String, title: String },
+    Tag(String),
+}
+
+impl SearchSuggestion {
+    pub fn text(&self) -> &str {
+        match self {
+            Self::Place { title, .. } => title,
+            Self::Tag(tag) => tag,
+        }
+    }
+}
+
+/// Suggests titles of visible places and tags that complete
+/// the text while the user is typing, e.g. for a type-ahead
+/// search box. Exact matches are ranked higher than matches
+/// with typos and tags are ranked by their frequency.
+pub fn suggest(
+    index: &dyn PlaceIndex,
+    text: &str,
+    bbox: Option<MapBbox>,
+    limit: usize,
+) -> Result<Vec<SearchSuggestion>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(vec![]);
+    }
+    let index_query = IndexQuery {
+        include_bbox: bbox,
+        status: Some(vec![]),
+        ..Default::default()
+    };
+    let candidates = index
+        .query_place_suggestions(&index_query, text, MAX_SUGGESTION_CANDIDATES)
+        .map_err(RepoError::Other)?;
+    Ok(rank_suggestions(text, candidates, limit))
+}
+
+// Number of edits that turn `a` into `b`
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + if ca == cb { 0 } else { 1 };
+            cur[j + 1] = substitution.min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+// The higher the better, `None` if the tag doesn't match at all
+fn tag_suggestion_rank(prefix: &str, tag: &str) -> Option<u8> {
+    if tag.starts_with(prefix) {
+        return Some(2);
+    }
+    let prefix: Vec<_> = prefix.chars().collect();
+    let tag: Vec<_> = tag.chars().take(prefix.len()).collect();
+    if edit_distance(&prefix, &tag) <= 1 {
+        Some(0)
+    } else {
+        None
+    }
+}
+
+// The higher the better
+fn title_suggestion_rank(text: &str, title: &str) -> u8 {
+    let title = title.to_lowercase();
+    let last_word = text.split_whitespace().last().unwrap_or_default();
+    if title.starts_with(text) {
+        2
+    } else if title
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| word.starts_with(last_word))
+    {
+        1
+    } else {
+        // Typos
+        0
+    }
+}
+
+fn rank_suggestions(
+    text: &str,
+    candidates: Vec<IndexedPlace>,
+    limit: usize,
+) -> Vec<SearchSuggestion> {
+    let text = text.to_lowercase();
+    let tag_prefix = text.split_whitespace().collect::<Vec<_>>().join("-");
+    let mut tags: HashMap<String, (u8, usize)> = HashMap::new();
+    let mut titles: Vec<(u8, SearchSuggestion)> = vec![];
+    for place in candidates {
+        let mut matches_tag = false;
+        for tag in place.tags {
+            if let Some(rank) = tag_suggestion_rank(&tag_prefix, &tag) {
+                matches_tag = true;
+                tags.entry(tag).or_insert((rank, 0)).1 += 1;
+            }
+        }
+        let rank = title_suggestion_rank(&text, &place.title);
+        // Places that only match by tag are not suggested
+        if rank == 0 && matches_tag {
+            continue;
+        }
+        if titles.iter().any(|(_, s)| s.text() == place.title) {
+            continue;
+        }
+        titles.push((
+            rank,
+            SearchSuggestion::Place {
+                id: place.id,
+                title: place.title,
+            },
+        ));
+    }
+    let mut tags: Vec<_> = tags.into_iter().collect();
+    tags.sort_by(|(t1, (r1, n1)), (t2, (r2, n2))| {
+        r2.cmp(r1).then_with(|| n2.cmp(n1)).then_with(|| t1.cmp(t2))
+    });
+    let mut suggestions: Vec<_> = tags
+        .into_iter()
+        .map(|(tag, (rank, _))| (rank, SearchSuggestion::Tag(tag)))
+        .collect();
+    suggestions.extend(titles);
+    // Tags come first among equally ranked suggestions and
+    // places remain in the order of their rating
+    suggestions.sort_by(|(r1, _), (r2, _)| r2.cmp(r1));
+    suggestions.truncate(limit);
+    suggestions.into_iter().map(|(_, s)| s).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result as Fallible;
+
+    fn place(id: &str, title: &str, tags: &[&str]) -> IndexedPlace {
+        IndexedPlace {
+            id: id.into(),
+            title: title.into(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    // Returns the farthest places first like an index
+    // that is unaware of the distance
+    struct FarthestFirstIndex(Vec<IndexedPlace>);
+
+    impl PlaceIndex for FarthestFirstIndex {
+        fn query_places(&self, query: &IndexQuery, limit: usize) -> Fallible<Vec<IndexedPlace>> {
+            let bbox = query.include_bbox.unwrap();
+            Ok(self
+                .0
+                .iter()
+                .rev()
+                .filter(|p| bbox.contains_point(p.pos))
+                .take(limit)
+                .cloned()
+                .collect())
+        }
+
+        fn count_place_tags(&self, _query: &IndexQuery) -> Fallible<Vec<TagFrequency>> {
+            unimplemented!();
+        }
+
+        fn query_place_suggestions(
+            &self,
+            _query: &IndexQuery,
+            _text: &str,
+            _limit: usize,
+        ) -> Fallible<Vec<IndexedPlace>> {
+            unimplemented!();
+        }
+    }
+
+    #[test]
+    fn search_nearby_places_closest_first_despite_too_many_candidates() {
+        // 0.001° latitude are about 111 m
+        let index = FarthestFirstIndex(
+            (1..=20)
+                .map(|i| IndexedPlace {
+                    id: i.to_string(),
+                    pos: MapPoint::from_lat_lng_deg(48.0 + f64::from(i) * 0.001, 9.0),
+                    ..Default::default()
+                })
+                .collect(),
+        );
+        let center = MapPoint::from_lat_lng_deg(48.0, 9.0);
+        let ids = |places: Vec<(IndexedPlace, Distance)>| -> Vec<String> {
+            places.into_iter().map(|(p, _)| p.id).collect()
+        };
+        let places =
+            search_nearby_candidates(&index, center, Distance::from_meters(5_000.0), &[], 3, 5)
+                .unwrap();
+        assert_eq!(vec!["1", "2", "3"], ids(places));
+
+        // Not enough places within any complete search radius
+        let places =
+            search_nearby_candidates(&index, center, Distance::from_meters(5_000.0), &[], 10, 5)
+                .unwrap();
+        let ids = ids(places);
+        assert!(!ids.is_empty());
+        assert_eq!(
+            (1..=ids.len()).map(|i| i.to_string()).collect::<Vec<_>>(),
+            ids
+        );
+    }
+
+    #[test]
+    fn split_categories_from_tag_counts() {
+        let facets = split_tag_counts(
+            vec![
+                TagFrequency("bio".into(), 5),
+                TagFrequency(Category::TAG_NON_PROFIT.into(), 4),
+                TagFrequency("fair".into(), 3),
+                TagFrequency("regional".into(), 2),
+                TagFrequency(Category::TAG_COMMERCIAL.into(), 1),
+            ],
+            2,
+        );
+        assert_eq!(
+            vec![
+                (Id::from(Category::ID_NON_PROFIT), 4),
+                (Id::from(Category::ID_COMMERCIAL), 1),
+            ],
+            facets.categories
+        );
+        assert_eq!(
+            vec![
+                TagFrequency("bio".into(), 5),
+                TagFrequency("fair".into(), 3)
+            ],
+            facets.tags
+        );
+    }
+
+    #[test]
+    fn rank_exact_matches_before_typos() {
+        let candidates = vec![
+            place("b", "Biohof Sonnenschein", &["bio", "landwirtschaft"]),
+            place("c", "Der Bioladen", &["bio", "biolebensmittel"]),
+            place("d", "Boiladen am Markt", &["lebensmittel"]),
+            place("e", "Bioladen", &["biolebensmittel"]),
+        ];
+        let suggestions = rank_suggestions("Bio", candidates, 10);
+        assert_eq!(
+            vec![
+                SearchSuggestion::Tag("bio".into()),
+                SearchSuggestion::Tag("biolebensmittel".into()),
+                SearchSuggestion::Place {
+                    id: "b".into(),
+                    title: "Biohof Sonnenschein".into()
+                },
+                SearchSuggestion::Place {
+                    id: "e".into(),
+                    title: "Bioladen".into()
+                },
+                SearchSuggestion::Place {
+                    id: "c".into(),
+                    title: "Der Bioladen".into()
+                },
+                SearchSuggestion::Place {
+                    id: "d".into(),
+                    title: "Boiladen am Markt".into()
+                },
+            ],
+            suggestions
+        );
+        assert_eq!(
+            2,
+            rank_suggestions("bio", vec![place("b", "Biohof", &["bio", "bioland"])], 2).len()
+        );
+    }
+
+    #[test]
+    fn tolerate_typos_in_tags() {
+        assert_eq!(Some(2), tag_suggestion_rank("orga", "organic"));
+        assert_eq!(Some(0), tag_suggestion_rank("orgn", "organic"));
+        assert_eq!(None, tag_suggestion_rank("ogrn", "organic"));
+    }
+}
@@ -0,0 +1,19 @@
+use crate::core::prelude::*;
+
+pub fn set_tag_moderation_policy<R: OrganizationRepo>(
+    repo: &R,
+    org: &Organization,
+    tag: &str,
+    policy: TagModerationPolicy,
+) -> Result<()> {
+    // Organizations are only permitted to configure their own tags
+    if !org.moderated_tags.iter().any(|t| t.label == tag) {
+        return Err(ParameterError::Forbidden.into());
+    }
+    info!(
+        "Setting moderation policy of tag '{}' owned by organization {} to {:?}",
+        tag, org.id, policy
+    );
+    repo.set_tag_moderation_policy(&org.id, tag, policy)?;
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use super::SearchRequest;
+use crate::core::prelude::*;
+
+fn matches_request(place: &Place, status: ReviewStatus, req: &SearchRequest) -> bool {
+    let SearchRequest {
+        bbox,
+        categories,
+        hash_tags,
+        status: requested_status,
+        area,
+        ..
+    } = req;
+    if requested_status.is_empty() {
+        if !status.exists() {
+            return false;
+        }
+    } else if !requested_status.contains(&status) {
+        return false;
+    }
+    if !bbox.contains_point(place.location.pos) {
+        return false;
+    }
+    if let Some(area) = area {
+        if !area.contains_point(place.location.pos) {
+            return false;
+        }
+    }
+    let category_tags = Category::merge_ids_into_tags(
+        &categories
+            .iter()
+            .map(|id| Id::from(*id))
+            .collect::<Vec<_>>(),
+        vec![],
+    );
+    if !category_tags.is_empty() && !category_tags.iter().any(|t| place.tags.contains(t)) {
+        return false;
+    }
+    hash_tags
+        .iter()
+        .all(|t| place.tags.iter().any(|tag| tag == t))
+}
+
+/// Reconstructs the places as they have existed at the given time.
+///
+/// The search index only reflects the current state. Therefore only
+/// the bounding box, the area, the categories, the tags and the review
+/// status of the request are considered.
+pub fn snapshot_places<R: PlaceRepo>(
+    repo: &R,
+    as_of: TimestampMs,
+    req: &SearchRequest,
+    limit: usize,
+) -> Result<Vec<(Place, ReviewStatus)>> {
+    Ok(repo
+        .all_places_as_of(as_of)?
+        .into_iter()
+        .filter(|(place, status)| matches_request(place, *status, req))
+        .take(limit)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+    use crate::core::util::geo::MapBbox;
+
+    fn place(id: &str, tags: Vec<&str>, created_at: i64) -> Place {
+        let mut place = Place::build()
+            .id(id)
+            .tags(tags)
+            .pos(MapPoint::from_lat_lng_deg(1.0, 1.0))
+            .finish();
+        place.created.at = TimestampMs::from_inner(created_at);
+        place
+    }
+
+    fn request(hash_tags: Vec<&str>) -> SearchRequest {
+        SearchRequest {
+            bbox: MapBbox::new(
+                MapPoint::from_lat_lng_deg(0.0, 0.0),
+                MapPoint::from_lat_lng_deg(2.0, 2.0),
+            ),
+            ids: vec![],
+            categories: vec![],
+            org_tag: None,
+            hash_tags,
+            text: None,
+            status: vec![],
+            country: None,
+            region: None,
+            excluded_categories: vec![],
+            excluded_hash_tags: vec![],
+            min_avg_rating: None,
+            min_rating_count: None,
+            confirmed_since: None,
+            boost_freshness: false,
+            order_by_quality: false,
+            fuzziness: None,
+            area: None,
+        }
+    }
+
+    #[test]
+    fn snapshot_places_that_existed_at_the_given_time() {
+        let db = MockDb::default();
+        db.entries.borrow_mut().extend(vec![
+            (place("old", vec!["bio"], 1000), ReviewStatus::Confirmed),
+            (place("rejected", vec!["bio"], 1000), ReviewStatus::Rejected),
+            (place("new", vec!["bio"], 3000), ReviewStatus::Created),
+            (place("other", vec!["fair"], 1000), ReviewStatus::Created),
+        ]);
+        let as_of = TimestampMs::from_inner(2000);
+        let ids = |places: Vec<(Place, ReviewStatus)>| {
+            places
+                .into_iter()
+                .map(|(p, _)| p.id.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec!["old", "other"],
+            ids(snapshot_places(&db, as_of, &request(vec![]), 100).unwrap())
+        );
+        assert_eq!(
+            vec!["old"],
+            ids(snapshot_places(&db, as_of, &request(vec!["bio"]), 100).unwrap())
+        );
+        assert_eq!(
+            1,
+            snapshot_places(&db, as_of, &request(vec![]), 1)
+                .unwrap()
+                .len()
+        );
+    }
+}
@@ -2,11 +2,14 @@ use crate::core::{
     prelude::*,
     usecases::create_user_from_email,
     util::{
+        address::normalize_address,
         parse::parse_url_param,
+        time_zone::{is_valid_time_zone, time_zone_at},
         validate::{AutoCorrect, Validate},
     },
 };
 use chrono::prelude::*;
+use ofdb_core::gateways::geocode::GeoCodingGateway;
 use std::str::FromStr;
 
 #[rustfmt::skip]
@@ -32,6 +35,9 @@ pub struct NewEvent {
     pub organizer    : Option<String>,
     pub image_url     : Option<String>,
     pub image_link_url: Option<String>,
+    pub time_zone     : Option<String>,
+    pub recurrence    : Option<String>,
+    pub publish_at    : Option<i64>,
 }
 
 pub enum NewEventMode<'a> {
@@ -42,12 +48,18 @@ pub enum NewEventMode<'a> {
 #[derive(Debug, Clone)]
 pub struct Storable(Event);
 
+/// A missing address is resolved from the coordinates if a
+/// geocoding gateway is provided.
 pub fn import_new_event<D: Db>(
     db: &D,
     token: Option<&str>,
-    e: NewEvent,
+    mut e: NewEvent,
     mode: NewEventMode,
+    auto_fill_address: Option<&dyn GeoCodingGateway>,
 ) -> Result<Storable> {
+    let auto_filled = auto_fill_address
+        .map(|gw| super::auto_fill_new_event_address(gw, &mut e))
+        .unwrap_or(false);
     let NewEvent {
         title,
         description,
@@ -69,7 +81,9 @@ pub fn import_new_event<D: Db>(
         homepage,
         image_url,
         image_link_url,
-        ..
+        time_zone,
+        recurrence,
+        publish_at,
     } = e;
     let org = token
         .map(|t| {
@@ -152,29 +166,38 @@ pub fn import_new_event<D: Db>(
             }
         }
     } else {
-        super::authorize_editing_of_tagged_entry(db, &[], &new_tags, None)?
+        // The removal of moderated tags must be permitted, too
+        let old_tags = match mode {
+            NewEventMode::Create => vec![],
+            NewEventMode::Update(id) => db.get_event(id)?.tags,
+        };
+        super::authorize_editing_of_tagged_entry(db, &old_tags, &new_tags, None)?
     };
     // TODO: Record pending clearance for events
     debug_assert!(_clearance_org_ids.is_empty());
     new_tags.sort_unstable();
     new_tags.dedup();
 
-    //TODO: use address.is_empty()
-    let address = if street.is_some()
-        || zip.is_some()
-        || city.is_some()
-        || country.is_some()
-        || state.is_some()
-    {
-        Some(Address {
-            street,
-            zip,
-            city,
-            country,
-            state,
-        })
-    } else {
+    let address = normalize_address(Address {
+        street,
+        zip,
+        city,
+        country,
+        state,
+    });
+    let address = if address.is_empty() {
         None
+    } else {
+        Some(address)
+    };
+    let address_auto_filled = if auto_filled {
+        true
+    } else if let NewEventMode::Update(id) = mode {
+        // An auto-filled address remains flagged until it is edited
+        let old_event = db.get_event(id)?;
+        old_event.address_auto_filled && old_event.location.and_then(|l| l.address) == address
+    } else {
+        false
     };
 
     let pos = if let (Some(lat), Some(lng)) = (lat, lng) {
@@ -185,6 +208,21 @@ pub fn import_new_event<D: Db>(
     } else {
         None
     };
+
+    let time_zone = match time_zone
+        .map(|tz| tz.trim().to_owned())
+        .filter(|tz| !tz.is_empty())
+    {
+        Some(tz) => {
+            if !is_valid_time_zone(&tz) {
+                return Err(ParameterError::InvalidTimeZone.into());
+            }
+            Some(tz)
+        }
+        // Events without a position get their time zone after geocoding
+        None => pos.and_then(time_zone_at),
+    };
+
     //TODO: use location.is_empty()
     let location = if pos.is_some() || address.is_some() {
         Some(Location {
@@ -260,6 +298,12 @@ pub fn import_new_event<D: Db>(
         None => None,
     };
 
+    let recurrence = recurrence
+        .map(|r| r.trim().to_owned())
+        .filter(|r| !r.is_empty())
+        .map(|r| Recurrence::from_str(&r))
+        .transpose()?;
+
     let start = NaiveDateTime::from_timestamp(start, 0);
     let end = end.map(|e| NaiveDateTime::from_timestamp(e, 0));
 
@@ -288,12 +332,36 @@ pub fn import_new_event<D: Db>(
         archived: None,
         image_url,
         image_link_url,
+        time_zone,
+        recurrence,
+        address_auto_filled,
+        publish_at: publish_at.map(Timestamp::from_seconds),
+        created_at: None,
+        updated_at: None,
     };
     let event = event.auto_correct();
     event.validate()?;
     Ok(Storable(event))
 }
 
+/// Runs all checks of [`import_new_event`] without storing the event.
+///
+/// The normalized tags are returned even if the validation fails.
+/// The creator of the event might be registered as a new user. The
+/// caller should therefore roll back all changes afterwards.
+pub fn validate_new_event<D: Db>(
+    db: &D,
+    token: Option<&str>,
+    e: NewEvent,
+    mode: NewEventMode,
+) -> (Vec<String>, Result<Event>) {
+    let tags = super::prepare_tag_list(e.tags.iter().flatten().map(String::as_str));
+    match import_new_event(db, token, e, mode, None) {
+        Ok(Storable(event)) => (event.tags.clone(), Ok(event)),
+        Err(err) => (tags, Err(err)),
+    }
+}
+
 pub fn store_created_event<D: Db>(db: &D, storable: Storable) -> Result<Event> {
     let Storable(event) = storable;
     debug!("Storing newly created event: {:?}", event);
@@ -321,7 +389,7 @@ mod tests {
     use super::*;
 
     fn create_new_event<D: Db>(db: &D, token: Option<&str>, e: NewEvent) -> Result<Event> {
-        let s = import_new_event(db, token, e, NewEventMode::Create)?;
+        let s = import_new_event(db, token, e, NewEventMode::Create, None)?;
         store_created_event(db, s)
     }
 
@@ -350,6 +418,9 @@ mod tests {
             organizer    : None,
             image_url     : Some("http://somewhere.com/image_url.jpg".to_string()),
             image_link_url: Some("my.url/test.ext".to_string()),
+            time_zone    : None,
+            recurrence   : None,
+            publish_at   : None,
         };
         let mock_db = MockDb::default();
         let id = create_new_event(&mock_db, None, x).unwrap().id;
@@ -397,6 +468,9 @@ mod tests {
             organizer    : None,
             image_url     : None,
             image_link_url: None,
+            time_zone    : None,
+            recurrence   : None,
+            publish_at   : None,
         };
         let mock_db: MockDb = MockDb::default();
         assert!(create_new_event(&mock_db, None, x).is_err());
@@ -426,6 +500,9 @@ mod tests {
             organizer    : None,
             image_url     : None,
             image_link_url: None,
+            time_zone    : None,
+            recurrence   : None,
+            publish_at   : None,
         };
         let mock_db: MockDb = MockDb::default();
         assert!(create_new_event(&mock_db, None, x).is_ok());
@@ -469,9 +546,53 @@ mod tests {
             organizer    : None,
             image_url     : None,
             image_link_url: None,
+            time_zone    : None,
+            recurrence   : None,
+            publish_at   : None,
         };
         assert!(create_new_event(&mock_db, None, x).is_ok());
         let users = mock_db.all_users().unwrap();
         assert_eq!(users.len(), 1);
     }
+
+    struct DummyGeoCodingGw;
+
+    impl GeoCodingGateway for DummyGeoCodingGw {
+        fn resolve_address_lat_lng(&self, _: &Address) -> Option<(f64, f64)> {
+            None
+        }
+        fn resolve_lat_lng_address(&self, _: f64, _: f64) -> Option<Address> {
+            Some(Address {
+                city: Some("Stuttgart".into()),
+                ..Default::default()
+            })
+        }
+    }
+
+    #[test]
+    fn flag_auto_filled_address_of_new_event() {
+        let x = NewEvent {
+            title: "foo".into(),
+            start: Utc::now().naive_utc().timestamp(),
+            lat: Some(48.7),
+            lng: Some(9.1),
+            created_by: Some("foo@bar.com".into()),
+            ..Default::default()
+        };
+        let mock_db = MockDb::default();
+        let s = import_new_event(
+            &mock_db,
+            None,
+            x,
+            NewEventMode::Create,
+            Some(&DummyGeoCodingGw),
+        )
+        .unwrap();
+        let event = store_created_event(&mock_db, s).unwrap();
+        assert_eq!(
+            Some("Stuttgart"),
+            event.location.unwrap().address.unwrap().city.as_deref()
+        );
+        assert!(event.address_auto_filled);
+    }
 }
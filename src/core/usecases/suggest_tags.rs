@@ -0,0 +1,167 @@
+use crate::core::prelude::*;
+use std::collections::{BTreeSet, HashMap};
+
+// Shorter words are mostly articles and prepositions
+const MIN_WORD_LEN: usize = 3;
+
+// Rare combinations are more likely to be coincidental
+const MIN_COOCCURRENCE_COUNT: u64 = 2;
+
+pub const DEFAULT_TAG_SUGGESTION_LIMIT: usize = 10;
+
+pub const MAX_TAG_SUGGESTION_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagSuggestion {
+    pub tag: String,
+    /// Between 0.0 (unlikely) and 1.0 (certain)
+    pub score: f64,
+}
+
+fn words(text: &str) -> BTreeSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= MIN_WORD_LEN)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Counts how often the words in the title and description
+/// of confirmed places occur together with their tags and
+/// replaces the previous statistics.
+///
+/// Returns the number of stored word/tag combinations.
+pub fn compute_tag_cooccurrences<D: Db>(db: &D) -> Result<usize> {
+    let mut word_counts = HashMap::<String, u64>::new();
+    let mut counts = HashMap::<(String, String), u64>::new();
+    for (place, status) in db.all_places()? {
+        if status != ReviewStatus::Confirmed {
+            continue;
+        }
+        let mut text = place.title;
+        text.push(' ');
+        text.push_str(&place.description);
+        for word in words(&text) {
+            for tag in &place.tags {
+                *counts.entry((word.clone(), tag.clone())).or_default() += 1;
+            }
+            *word_counts.entry(word).or_default() += 1;
+        }
+    }
+    let cooccurrences: Vec<_> = counts
+        .into_iter()
+        .filter(|(_, count)| *count >= MIN_COOCCURRENCE_COUNT)
+        .map(|((word, tag), count)| TagCooccurrence {
+            word_count: word_counts[&word],
+            word,
+            tag,
+            count,
+        })
+        .collect();
+    db.replace_tag_cooccurrences(&cooccurrences)?;
+    Ok(cooccurrences.len())
+}
+
+/// Suggests tags for a new place with the given title and description.
+///
+/// The score of a tag is the probability that a confirmed place
+/// with one of the words is tagged with it, averaged over all words.
+/// Suggestions are ordered by descending score.
+pub fn suggest_tags<R: TagSuggestionRepo>(
+    repo: &R,
+    title: &str,
+    description: &str,
+    limit: Option<usize>,
+) -> Result<Vec<TagSuggestion>> {
+    let limit = limit.unwrap_or(DEFAULT_TAG_SUGGESTION_LIMIT);
+    if limit > MAX_TAG_SUGGESTION_LIMIT {
+        return Err(Error::Parameter(ParameterError::InvalidLimit));
+    }
+    let words = words(&format!("{} {}", title, description));
+    if words.is_empty() {
+        return Ok(vec![]);
+    }
+    let words_ref: Vec<_> = words.iter().map(String::as_str).collect();
+    let mut scores = HashMap::<String, f64>::new();
+    for c in repo.load_tag_cooccurrences(&words_ref)? {
+        *scores.entry(c.tag).or_default() += c.count as f64 / c.word_count as f64;
+    }
+    let mut suggestions: Vec<_> = scores
+        .into_iter()
+        .map(|(tag, score)| TagSuggestion {
+            tag,
+            score: score / words.len() as f64,
+        })
+        .collect();
+    suggestions.sort_by(|s1, s2| {
+        s2.score
+            .partial_cmp(&s1.score)
+            .unwrap()
+            .then_with(|| s1.tag.cmp(&s2.tag))
+    });
+    suggestions.truncate(limit);
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn place(id: &str, title: &str, tags: Vec<&str>) -> Place {
+        Place::build().id(id).title(title).tags(tags).finish()
+    }
+
+    #[test]
+    fn split_text_into_distinct_words() {
+        let words: Vec<_> = words("Der Bioladen, der Bio-Laden & ein Café")
+            .into_iter()
+            .collect();
+        assert_eq!(
+            vec!["bio", "bioladen", "café", "der", "ein", "laden"],
+            words
+        );
+    }
+
+    #[test]
+    fn suggest_tags_of_confirmed_places_with_the_same_words() {
+        let db = MockDb::default();
+        db.entries.borrow_mut().extend(vec![
+            (
+                place("a", "Bioladen am Markt", vec!["bio", "shop"]),
+                ReviewStatus::Confirmed,
+            ),
+            (
+                place("b", "Bioladen Süd", vec!["bio"]),
+                ReviewStatus::Confirmed,
+            ),
+            (
+                place("c", "Bioladen Nord", vec!["bio", "shop"]),
+                ReviewStatus::Confirmed,
+            ),
+            (
+                place("d", "Bioladen West", vec!["vegan"]),
+                ReviewStatus::Created,
+            ),
+        ]);
+        // Only "bioladen" occurs together with "bio" and "shop" at least twice
+        assert_eq!(2, compute_tag_cooccurrences(&db).unwrap());
+
+        let suggestions = suggest_tags(&db, "Neuer Bioladen", "", None).unwrap();
+        assert_eq!(
+            vec!["bio", "shop"],
+            suggestions
+                .iter()
+                .map(|s| s.tag.as_str())
+                .collect::<Vec<_>>()
+        );
+        // "neuer" is unknown
+        assert!((suggestions[0].score - 0.5).abs() < 0.001);
+        assert!((suggestions[1].score - 1.0 / 3.0).abs() < 0.001);
+
+        assert_eq!(1, suggest_tags(&db, "Bioladen", "", Some(1)).unwrap().len());
+        assert!(suggest_tags(&db, "Fahrradwerkstatt", "", None)
+            .unwrap()
+            .is_empty());
+        assert!(suggest_tags(&db, "Bioladen", "", Some(MAX_TAG_SUGGESTION_LIMIT + 1)).is_err());
+    }
+}
@@ -0,0 +1,202 @@
+use super::rename_tag::parse_single_tag;
+use crate::core::prelude::*;
+use std::collections::BTreeMap;
+
+// Only a glimpse at the most recent usages
+const MAX_RECENT_USAGES: usize = 10;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Region {
+    pub country: Option<String>,
+    pub state: Option<String>,
+}
+
+impl Region {
+    fn of(location: Option<&Location>) -> Self {
+        Self::of_address(location.and_then(|l| l.address.as_ref()))
+    }
+
+    pub(super) fn of_address(address: Option<&Address>) -> Self {
+        Self {
+            country: address.and_then(|a| a.country.clone()),
+            state: address.and_then(|a| a.state.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RegionTagUsage {
+    pub region: Region,
+    pub places: usize,
+    pub events: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecentTagUsage {
+    pub id: Id,
+    pub title: String,
+    /// The last change of places or the start of events
+    pub at: Timestamp,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TagUsage {
+    pub tag: String,
+    pub places_by_status: Vec<(ReviewStatus, usize)>,
+    pub events: usize,
+    pub regions: Vec<RegionTagUsage>,
+    pub recent_places: Vec<RecentTagUsage>,
+    pub recent_events: Vec<RecentTagUsage>,
+}
+
+impl TagUsage {
+    pub fn places(&self) -> usize {
+        self.places_by_status.iter().map(|(_, count)| count).sum()
+    }
+}
+
+fn most_recent(mut usages: Vec<RecentTagUsage>) -> Vec<RecentTagUsage> {
+    usages.sort_by(|u1, u2| u2.at.cmp(&u1.at));
+    usages.truncate(MAX_RECENT_USAGES);
+    usages
+}
+
+/// Reports how often a tag is used by places and events,
+/// e.g. before renaming it.
+pub fn tag_usage<D: Db>(db: &D, tag: &str) -> Result<TagUsage> {
+    let tag = parse_single_tag(tag)?;
+    let mut places_by_status = BTreeMap::new();
+    let mut regions = BTreeMap::<_, RegionTagUsage>::new();
+    let mut recent_places = vec![];
+    for (place, status) in db.all_places()? {
+        if !place.tags.contains(&tag) {
+            continue;
+        }
+        *places_by_status.entry(status).or_insert(0) += 1;
+        regions
+            .entry(Region::of(Some(&place.location)))
+            .or_default()
+            .places += 1;
+        recent_places.push(RecentTagUsage {
+            id: place.id,
+            title: place.title,
+            at: Timestamp::from_seconds(place.created.at.into_seconds()),
+        });
+    }
+    let mut events = 0;
+    let mut recent_events = vec![];
+    for event in db.all_events_chronologically()? {
+        if !event.tags.contains(&tag) {
+            continue;
+        }
+        events += 1;
+        regions
+            .entry(Region::of(event.location.as_ref()))
+            .or_default()
+            .events += 1;
+        recent_events.push(RecentTagUsage {
+            id: event.id,
+            title: event.title,
+            at: Timestamp::from(event.start),
+        });
+    }
+    Ok(TagUsage {
+        tag,
+        places_by_status: places_by_status.into_iter().collect(),
+        events,
+        regions: regions
+            .into_iter()
+            .map(|(region, usage)| RegionTagUsage { region, ..usage })
+            .collect(),
+        recent_places: most_recent(recent_places),
+        recent_events: most_recent(recent_events),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    fn place(id: &str, tags: Vec<&str>, country: Option<&str>) -> Place {
+        let mut place = Place::build().id(id).title(id).tags(tags).finish();
+        place.location.address = Some(Address {
+            country: country.map(Into::into),
+            ..Default::default()
+        });
+        place
+    }
+
+    #[test]
+    fn count_places_and_events_by_status_and_region() {
+        let db = MockDb::default();
+        db.entries.borrow_mut().extend(vec![
+            (
+                place("a", vec!["bio"], Some("Germany")),
+                ReviewStatus::Created,
+            ),
+            (
+                place("b", vec!["bio", "fair"], Some("Germany")),
+                ReviewStatus::Confirmed,
+            ),
+            (place("c", vec!["bio"], None), ReviewStatus::Confirmed),
+            (
+                place("d", vec!["fair"], Some("Germany")),
+                ReviewStatus::Created,
+            ),
+        ]);
+        db.events.borrow_mut().push(Event {
+            id: "e".into(),
+            title: "event".into(),
+            description: None,
+            start: chrono::NaiveDateTime::from_timestamp(0, 0),
+            end: None,
+            location: None,
+            contact: None,
+            tags: vec!["bio".into()],
+            homepage: None,
+            created_by: None,
+            registration: None,
+            archived: None,
+            image_url: None,
+            image_link_url: None,
+            time_zone: None,
+            recurrence: None,
+            address_auto_filled: false,
+            publish_at: None,
+            created_at: None,
+            updated_at: None,
+        });
+
+        let usage = tag_usage(&db, "#Bio").unwrap();
+        assert_eq!("bio", usage.tag);
+        assert_eq!(3, usage.places());
+        assert_eq!(1, usage.events);
+        assert_eq!(
+            vec![(ReviewStatus::Created, 1), (ReviewStatus::Confirmed, 2)],
+            usage.places_by_status
+        );
+        assert_eq!(
+            vec![
+                RegionTagUsage {
+                    region: Region::default(),
+                    places: 1,
+                    events: 1,
+                },
+                RegionTagUsage {
+                    region: Region {
+                        country: Some("Germany".into()),
+                        state: None,
+                    },
+                    places: 2,
+                    events: 0,
+                },
+            ],
+            usage.regions
+        );
+        assert_eq!(3, usage.recent_places.len());
+        assert_eq!("e", usage.recent_events[0].id.as_str());
+
+        assert!(tag_usage(&db, "").is_err());
+    }
+}
@@ -50,6 +50,18 @@ impl Key for Comment {
     }
 }
 
+impl Key for OrganizationApiToken {
+    fn key(&self) -> &str {
+        self.id.as_ref()
+    }
+}
+
+impl Key for PersonalApiToken {
+    fn key(&self) -> &str {
+        self.id.as_ref()
+    }
+}
+
 impl Key for Rating {
     fn key(&self) -> &str {
         &self.id.as_ref()
@@ -62,6 +74,18 @@ impl Key for BboxSubscription {
     }
 }
 
+impl Key for OrganizationSubscription {
+    fn key(&self) -> &str {
+        self.id.as_ref()
+    }
+}
+
+impl Key for PlaceNote {
+    fn key(&self) -> &str {
+        self.id.as_ref()
+    }
+}
+
 impl Key for Organization {
     fn key(&self) -> &str {
         self.id.as_ref()
@@ -77,8 +101,58 @@ pub struct MockDb {
     pub ratings: RefCell<Vec<Rating>>,
     pub comments: RefCell<Vec<Comment>>,
     pub bbox_subscriptions: RefCell<Vec<BboxSubscription>>,
+    pub org_subscriptions: RefCell<Vec<OrganizationSubscription>>,
     pub orgs: Vec<Organization>,
     pub token: RefCell<Vec<UserToken>>,
+    pub osm_nodes: RefCell<Vec<(Id, u64)>>,
+    pub views: RefCell<Vec<(ViewedEntity, String, Timestamp)>>,
+    pub place_notes: RefCell<Vec<PlaceNote>>,
+    pub place_confirmations: RefCell<Vec<PlaceConfirmationRequest>>,
+    pub tag_cooccurrences: RefCell<Vec<TagCooccurrence>>,
+    pub link_checks: RefCell<Vec<LinkCheck>>,
+    pub homepage_previews: RefCell<Vec<HomepagePreview>>,
+    pub announcement_opt_ins: RefCell<Vec<String>>,
+    pub email_outbox: RefCell<Vec<(QueuedEmail, Option<Timestamp>)>>,
+    pub notification_consents: RefCell<Vec<(EmailNonce, Option<Timestamp>)>>,
+    pub user_deactivations: RefCell<Vec<(String, Timestamp)>>,
+    pub entity_watches: RefCell<Vec<(String, String)>>,
+    pub pending_notifications: RefCell<Vec<PendingNotification>>,
+    pub clearance_log: RefCell<Vec<ClearanceLogEntry>>,
+    pub pending_moderated_tags_notifications: RefCell<Vec<PendingModeratedTagsNotification>>,
+    pub pending_ratings: RefCell<Vec<PendingRating>>,
+    pub personal_api_tokens: RefCell<Vec<PersonalApiToken>>,
+    pub org_api_tokens: RefCell<Vec<OrganizationApiToken>>,
+    pub osm_node_proposals: RefCell<Vec<OsmNodeProposal>>,
+}
+
+impl MockDb {
+    fn filtered_tags(&self, filter: &TagFilter) -> Vec<Tag> {
+        let entries = self.entries.borrow();
+        self.tags
+            .borrow()
+            .iter()
+            .filter(|t| {
+                filter
+                    .prefix
+                    .as_ref()
+                    .map(|prefix| t.id.starts_with(prefix.as_str()))
+                    .unwrap_or(true)
+            })
+            .filter(|t| {
+                filter
+                    .min_count
+                    .map(|min_count| {
+                        let count = entries
+                            .iter()
+                            .filter(|(p, s)| s.exists() && p.tags.contains(&t.id))
+                            .count();
+                        count as u64 >= min_count
+                    })
+                    .unwrap_or(true)
+            })
+            .cloned()
+            .collect()
+    }
 }
 
 impl UserTokenRepo for MockDb {
@@ -152,6 +226,19 @@ impl PlaceIndex for DummySearchEngine {
     fn query_places(&self, _query: &IndexQuery, _limit: usize) -> Fallible<Vec<IndexedPlace>> {
         unimplemented!();
     }
+
+    fn count_place_tags(&self, _query: &IndexQuery) -> Fallible<Vec<TagFrequency>> {
+        unimplemented!();
+    }
+
+    fn query_place_suggestions(
+        &self,
+        _query: &IndexQuery,
+        _text: &str,
+        _limit: usize,
+    ) -> Fallible<Vec<IndexedPlace>> {
+        unimplemented!();
+    }
 }
 
 impl PlaceIndexer for DummySearchEngine {
@@ -159,6 +246,7 @@ impl PlaceIndexer for DummySearchEngine {
         &self,
         _place: &Place,
         _status: ReviewStatus,
+        _last_confirmed_at: Option<TimestampMs>,
         _ratings: &AvgRatings,
     ) -> Fallible<()> {
         Ok(())
@@ -216,7 +304,10 @@ impl PlaceRepo for MockDb {
             (place, ReviewStatus::Created),
         )
     }
-    fn get_place(&self, id: &str) -> RepoResult<(Place, ReviewStatus)> {
+    fn create_or_update_place_with_comment(&self, place: Place, _: &str) -> RepoResult<()> {
+        self.create_or_update_place(place)
+    }
+    fn get_place_by_id(&self, id: &str) -> RepoResult<(Place, ReviewStatus)> {
         get(&self.entries.borrow(), id).and_then(|(p, s)| {
             if s != ReviewStatus::Archived {
                 Ok((p, s))
@@ -225,13 +316,15 @@ impl PlaceRepo for MockDb {
             }
         })
     }
-    fn get_places(&self, ids: &[&str]) -> RepoResult<Vec<(Place, ReviewStatus)>> {
+    fn get_places_by_ids(&self, ids: &[&str]) -> RepoResult<Vec<(Place, ReviewStatus)>> {
         Ok(self
             .entries
             .borrow()
             .iter()
             .filter(|(p, s)| {
-                *s != ReviewStatus::Archived && ids.iter().any(|id| p.id.as_str() == *id)
+                *s != ReviewStatus::Archived
+                    && *s != ReviewStatus::Draft
+                    && ids.iter().any(|id| p.id.as_str() == *id)
             })
             .cloned()
             .collect())
@@ -241,7 +334,38 @@ impl PlaceRepo for MockDb {
             .entries
             .borrow()
             .iter()
-            .filter(|(_, s)| *s != ReviewStatus::Archived)
+            .filter(|(_, s)| *s != ReviewStatus::Archived && *s != ReviewStatus::Draft)
+            .cloned()
+            .collect())
+    }
+    fn list_places(&self, pagination: &Pagination) -> RepoResult<Vec<(Place, ReviewStatus)>> {
+        let offset = pagination.offset.unwrap_or(0) as usize;
+        let limit = pagination.limit.map(|l| l as usize).unwrap_or(usize::MAX);
+        Ok(self
+            .all_places()?
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .collect())
+    }
+    fn drafts_created_by(&self, email: &str) -> RepoResult<Vec<Place>> {
+        Ok(self
+            .entries
+            .borrow()
+            .iter()
+            .filter(|(p, s)| {
+                *s == ReviewStatus::Draft && p.created.by.as_ref().map(Email::as_ref) == Some(email)
+            })
+            .map(|(p, _)| p.clone())
+            .collect())
+    }
+    fn all_places_as_of(&self, as_of: TimestampMs) -> RepoResult<Vec<(Place, ReviewStatus)>> {
+        // Only the current revisions are available
+        Ok(self
+            .entries
+            .borrow()
+            .iter()
+            .filter(|(p, _)| p.created.at <= as_of)
             .cloned()
             .collect())
     }
@@ -265,11 +389,18 @@ impl PlaceRepo for MockDb {
 
     fn review_places(
         &self,
-        _ids: &[&str],
-        _status: ReviewStatus,
+        ids: &[&str],
+        status: ReviewStatus,
         _activity: &ActivityLog,
     ) -> RepoResult<usize> {
-        unimplemented!();
+        let mut count = 0;
+        for (p, s) in self.entries.borrow_mut().iter_mut() {
+            if *s != status && ids.iter().any(|id| p.id.as_str() == *id) {
+                *s = status;
+                count += 1;
+            }
+        }
+        Ok(count)
     }
 
     fn get_place_history(
@@ -280,13 +411,51 @@ impl PlaceRepo for MockDb {
         unimplemented!();
     }
 
-    fn load_place_revision(&self, _id: &str, _rev: Revision) -> RepoResult<(Place, ReviewStatus)> {
-        unimplemented!();
+    // Reviews are not recorded
+    fn last_confirmed_at(&self, _id: &str) -> RepoResult<Option<TimestampMs>> {
+        Ok(None)
+    }
+
+    // Only the current revision is available
+    fn load_place_revision(&self, id: &str, rev: Revision) -> RepoResult<(Place, ReviewStatus)> {
+        get(&self.entries.borrow(), id).and_then(|(p, s)| {
+            if p.revision == rev {
+                Ok((p, s))
+            } else {
+                Err(RepoError::NotFound)
+            }
+        })
+    }
+
+    fn link_place_to_osm_node(&self, place_id: &str, osm_node_id: u64) -> RepoResult<()> {
+        self.osm_nodes
+            .borrow_mut()
+            .push((place_id.into(), osm_node_id));
+        Ok(())
+    }
+
+    fn find_place_id_by_osm_node(&self, osm_node_id: u64) -> RepoResult<Option<Id>> {
+        Ok(self
+            .osm_nodes
+            .borrow()
+            .iter()
+            .find(|(_, node_id)| *node_id == osm_node_id)
+            .map(|(place_id, _)| place_id.clone()))
+    }
+
+    fn all_osm_node_links(&self) -> RepoResult<Vec<(Id, u64)>> {
+        Ok(self.osm_nodes.borrow().clone())
     }
 }
 
 impl EventGateway for MockDb {
     fn create_event(&self, e: Event) -> RepoResult<()> {
+        let now = Timestamp::now();
+        let e = Event {
+            created_at: Some(now),
+            updated_at: Some(now),
+            ..e
+        };
         create(&mut self.events.borrow_mut(), e)
     }
 
@@ -312,6 +481,30 @@ impl EventGateway for MockDb {
         Ok(events)
     }
 
+    fn list_events_chronologically(
+        &self,
+        published_until: Timestamp,
+        modified_since: Option<Timestamp>,
+        pagination: &Pagination,
+    ) -> RepoResult<Vec<Event>> {
+        let offset = pagination.offset.unwrap_or(0) as usize;
+        let limit = pagination.limit.map(|l| l as usize).unwrap_or(usize::MAX);
+        Ok(self
+            .all_events_chronologically()?
+            .into_iter()
+            .filter(|e| !e.is_scheduled(published_until))
+            .filter(|e| {
+                modified_since.map_or(true, |since| {
+                    e.updated_at
+                        .or(e.created_at)
+                        .map_or(false, |at| at >= since)
+                })
+            })
+            .skip(offset)
+            .take(limit)
+            .collect())
+    }
+
     fn get_events_chronologically(&self, ids: &[&str]) -> RepoResult<Vec<Event>> {
         let mut events: Vec<_> = self
             .events
@@ -325,12 +518,35 @@ impl EventGateway for MockDb {
         Ok(events)
     }
 
+    fn events_published_between(
+        &self,
+        since: Timestamp,
+        until: Timestamp,
+    ) -> RepoResult<Vec<Event>> {
+        Ok(self
+            .all_events_chronologically()?
+            .into_iter()
+            .filter(|e| {
+                e.publish_at
+                    .map(|at| at > since && at <= until)
+                    .unwrap_or(false)
+            })
+            .collect())
+    }
+
     fn count_events(&self) -> RepoResult<usize> {
         self.all_events_chronologically().map(|v| v.len())
     }
 
     fn update_event(&self, e: &Event) -> RepoResult<()> {
-        update(&mut self.events.borrow_mut(), e)
+        let mut events = self.events.borrow_mut();
+        let created_at = get(&events, e.id.as_str())?.created_at;
+        let e = Event {
+            created_at,
+            updated_at: Some(Timestamp::now()),
+            ..e.clone()
+        };
+        update(&mut events, &e)
     }
 
     fn archive_events(&self, _ids: &[&str], _archived: Timestamp) -> RepoResult<usize> {
@@ -373,14 +589,181 @@ impl UserGateway for MockDb {
         self.all_users().map(|v| v.len())
     }
 
-    fn delete_user_by_email(&self, email: &str) -> RepoResult<()> {
+    fn anonymize_user_by_email(&self, email: &str) -> RepoResult<AnonymizedUserRecords> {
+        self.get_user_by_email(email)?;
+        let mut events = 0;
+        for e in self.events.borrow_mut().iter_mut() {
+            if e.created_by.as_deref() == Some(email) {
+                e.created_by = None;
+                events += 1;
+            }
+        }
+        self.bbox_subscriptions
+            .borrow_mut()
+            .retain(|s| s.user_email != email);
+        self.org_subscriptions
+            .borrow_mut()
+            .retain(|s| s.user_email != email);
+        self.entity_watches
+            .borrow_mut()
+            .retain(|(watcher, _)| watcher != email);
+        self.pending_notifications
+            .borrow_mut()
+            .retain(|n| n.user_email != email);
+        self.personal_api_tokens
+            .borrow_mut()
+            .retain(|t| t.user_email != email);
+        self.email_outbox
+            .borrow_mut()
+            .retain(|(e, _)| e.recipient.as_str() != email);
+        for p in self.pending_ratings.borrow_mut().iter_mut() {
+            if p.created_by.as_deref() == Some(email) {
+                p.created_by = None;
+            }
+        }
         self.users.borrow_mut().retain(|u| u.email != email);
-        Ok(())
+        Ok(AnonymizedUserRecords {
+            events,
+            ..Default::default()
+        })
+    }
+
+    fn merge_users_by_email(
+        &self,
+        merged_email: &str,
+        surviving_email: &str,
+    ) -> RepoResult<MergedUserRecords> {
+        self.get_user_by_email(merged_email)?;
+        self.get_user_by_email(surviving_email)?;
+        let mut events = 0;
+        for e in self.events.borrow_mut().iter_mut() {
+            if e.created_by.as_deref() == Some(merged_email) {
+                e.created_by = Some(surviving_email.into());
+                events += 1;
+            }
+        }
+        let mut subscriptions = 0;
+        for s in self.bbox_subscriptions.borrow_mut().iter_mut() {
+            if s.user_email == merged_email {
+                s.user_email = surviving_email.into();
+                subscriptions += 1;
+            }
+        }
+        let mut org_subscriptions = self.org_subscriptions.borrow_mut();
+        let subscribed_org_ids: Vec<_> = org_subscriptions
+            .iter()
+            .filter(|s| s.user_email == surviving_email)
+            .map(|s| s.org_id.clone())
+            .collect();
+        org_subscriptions
+            .retain(|s| s.user_email != merged_email || !subscribed_org_ids.contains(&s.org_id));
+        for s in org_subscriptions.iter_mut() {
+            if s.user_email == merged_email {
+                s.user_email = surviving_email.into();
+                subscriptions += 1;
+            }
+        }
+        let merged_watches = self.entity_ids_watched_by_email(merged_email)?;
+        self.entity_watches
+            .borrow_mut()
+            .retain(|(watcher, _)| watcher != merged_email);
+        for entity_id in merged_watches {
+            self.watch_entity(surviving_email, &entity_id, Timestamp::now())?;
+        }
+        for n in self.pending_notifications.borrow_mut().iter_mut() {
+            if n.user_email == merged_email {
+                n.user_email = surviving_email.into();
+            }
+        }
+        for t in self.personal_api_tokens.borrow_mut().iter_mut() {
+            if t.user_email == merged_email {
+                t.user_email = surviving_email.into();
+            }
+        }
+        for p in self.pending_ratings.borrow_mut().iter_mut() {
+            if p.created_by.as_deref() == Some(merged_email) {
+                p.created_by = Some(surviving_email.into());
+            }
+        }
+        Ok(MergedUserRecords {
+            events,
+            subscriptions,
+            ..Default::default()
+        })
     }
 
     fn update_user(&self, u: &User) -> RepoResult<()> {
         update(&mut self.users.borrow_mut(), u)
     }
+
+    fn wants_announcements(&self, email: &str) -> RepoResult<bool> {
+        self.get_user_by_email(email)?;
+        Ok(self
+            .announcement_opt_ins
+            .borrow()
+            .iter()
+            .any(|e| e == email))
+    }
+
+    fn set_wants_announcements(&self, email: &str, wants_announcements: bool) -> RepoResult<()> {
+        self.get_user_by_email(email)?;
+        let mut opt_ins = self.announcement_opt_ins.borrow_mut();
+        opt_ins.retain(|e| e != email);
+        if wants_announcements {
+            opt_ins.push(email.into());
+        }
+        Ok(())
+    }
+
+    fn announcement_recipients(&self) -> RepoResult<Vec<Email>> {
+        let opt_ins = self.announcement_opt_ins.borrow();
+        Ok(self
+            .users
+            .borrow()
+            .iter()
+            .filter(|u| u.email_confirmed && opt_ins.contains(&u.email))
+            .map(|u| Email::from(u.email.clone()))
+            .collect())
+    }
+
+    fn user_created_at(&self, email: &str) -> RepoResult<Option<Timestamp>> {
+        self.get_user_by_email(email)?;
+        Ok(None)
+    }
+
+    fn user_deactivated_at(&self, email: &str) -> RepoResult<Option<Timestamp>> {
+        self.get_user_by_email(email)?;
+        Ok(self
+            .user_deactivations
+            .borrow()
+            .iter()
+            .find(|(e, _)| e == email)
+            .map(|(_, deactivated_at)| *deactivated_at))
+    }
+
+    fn set_user_deactivated_at(
+        &self,
+        email: &str,
+        deactivated_at: Option<Timestamp>,
+    ) -> RepoResult<()> {
+        self.get_user_by_email(email)?;
+        let mut deactivations = self.user_deactivations.borrow_mut();
+        deactivations.retain(|(e, _)| e != email);
+        if let Some(deactivated_at) = deactivated_at {
+            deactivations.push((email.into(), deactivated_at));
+        }
+        Ok(())
+    }
+
+    fn users_deactivated_before(&self, deactivated_before: Timestamp) -> RepoResult<Vec<String>> {
+        Ok(self
+            .user_deactivations
+            .borrow()
+            .iter()
+            .filter(|(_, deactivated_at)| *deactivated_at < deactivated_before)
+            .map(|(email, _)| email.clone())
+            .collect())
+    }
 }
 
 impl CommentRepository for MockDb {
@@ -418,13 +801,19 @@ impl CommentRepository for MockDb {
             .collect())
     }
 
-    fn archive_comments(&self, _ids: &[&str], _activity: &Activity) -> RepoResult<usize> {
+    fn archive_comments(
+        &self,
+        _ids: &[&str],
+        _activity: &Activity,
+        _reason: ArchiveReason,
+    ) -> RepoResult<usize> {
         unimplemented!();
     }
     fn archive_comments_of_ratings(
         &self,
         _rating_ids: &[&str],
         _activity: &Activity,
+        _reason: ArchiveReason,
     ) -> RepoResult<usize> {
         unimplemented!();
     }
@@ -435,12 +824,24 @@ impl CommentRepository for MockDb {
     ) -> RepoResult<usize> {
         unimplemented!();
     }
+    fn load_archived_comments_of_place(
+        &self,
+        _place_id: &str,
+    ) -> RepoResult<Vec<(Comment, Option<ArchiveReason>)>> {
+        unimplemented!();
+    }
+    fn count_archived_comments_by_reason(&self) -> RepoResult<Vec<(Option<ArchiveReason>, usize)>> {
+        unimplemented!();
+    }
 }
 
 impl OrganizationRepo for MockDb {
     fn create_org(&mut self, o: Organization) -> RepoResult<()> {
         create(&mut self.orgs, o)
     }
+    fn get_org_by_id(&self, id: &Id) -> RepoResult<Organization> {
+        get(&self.orgs, id.as_ref())
+    }
     fn get_org_by_api_token(&self, token: &str) -> RepoResult<Organization> {
         let o = self
             .orgs
@@ -449,6 +850,15 @@ impl OrganizationRepo for MockDb {
             .ok_or(RepoError::NotFound)?;
         Ok(o.clone())
     }
+    fn replace_org_api_token(&mut self, org_id: &Id, api_token: &str) -> RepoResult<()> {
+        let o = self
+            .orgs
+            .iter_mut()
+            .find(|o| &o.id == org_id)
+            .ok_or(RepoError::NotFound)?;
+        o.api_token = api_token.to_owned();
+        Ok(())
+    }
     fn map_tag_to_clearance_org_id(&self, tag: &str) -> RepoResult<Option<Id>> {
         Ok(self
             .orgs
@@ -476,6 +886,33 @@ impl OrganizationRepo for MockDb {
             })
             .collect())
     }
+    fn set_tag_moderation_policy(
+        &self,
+        _org_id: &Id,
+        _tag: &str,
+        _policy: TagModerationPolicy,
+    ) -> RepoResult<()> {
+        unimplemented!();
+    }
+    fn get_tag_moderation_policy(
+        &self,
+        _org_id: &Id,
+        _tag: &str,
+    ) -> RepoResult<Option<TagModerationPolicy>> {
+        unimplemented!();
+    }
+    fn add_moderated_tag(&self, _org_id: &Id, _tag: &ModeratedTag) -> RepoResult<()> {
+        unimplemented!();
+    }
+    fn remove_moderated_tag(&self, _org_id: &Id, _tag: &str) -> RepoResult<()> {
+        unimplemented!();
+    }
+    fn get_org_daily_quotas(&self, _org_id: &Id) -> RepoResult<DailyQuotas> {
+        unimplemented!();
+    }
+    fn set_org_daily_quotas(&self, _org_id: &Id, _quotas: &DailyQuotas) -> RepoResult<()> {
+        unimplemented!();
+    }
 }
 
 impl RatingRepository for MockDb {
@@ -516,7 +953,41 @@ impl RatingRepository for MockDb {
     fn load_place_ids_of_ratings(&self, _ids: &[&str]) -> RepoResult<Vec<String>> {
         unimplemented!();
     }
-    fn archive_ratings(&self, _ids: &[&str], _activity: &Activity) -> RepoResult<usize> {
+    fn count_ratings_and_comments_of_places(
+        &self,
+        place_ids: &[&str],
+    ) -> RepoResult<Vec<(String, RatingCounts)>> {
+        let comments = self.comments.borrow();
+        let mut results: Vec<(String, RatingCounts)> = vec![];
+        for r in self.ratings.borrow().iter() {
+            if r.archived_at.is_some() || !place_ids.iter().any(|id| r.place_id.as_str() == *id) {
+                continue;
+            }
+            let index = match results
+                .iter()
+                .position(|(id, _)| *id == r.place_id.as_str())
+            {
+                Some(index) => index,
+                None => {
+                    results.push((r.place_id.to_string(), Default::default()));
+                    results.len() - 1
+                }
+            };
+            let counts = &mut results[index].1;
+            counts.add_ratings(r.context, 1);
+            counts.comments += comments
+                .iter()
+                .filter(|c| c.rating_id == r.id && c.archived_at.is_none())
+                .count() as u32;
+        }
+        Ok(results)
+    }
+    fn archive_ratings(
+        &self,
+        _ids: &[&str],
+        _activity: &Activity,
+        _reason: ArchiveReason,
+    ) -> RepoResult<usize> {
         unimplemented!();
     }
     fn archive_ratings_of_places(
@@ -526,6 +997,15 @@ impl RatingRepository for MockDb {
     ) -> RepoResult<usize> {
         unimplemented!();
     }
+    fn load_archived_ratings_of_place(
+        &self,
+        _place_id: &str,
+    ) -> RepoResult<Vec<(Rating, Option<ArchiveReason>)>> {
+        unimplemented!();
+    }
+    fn count_archived_ratings_by_reason(&self) -> RepoResult<Vec<(Option<ArchiveReason>, usize)>> {
+        unimplemented!();
+    }
 }
 
 impl PlaceClearanceRepo for MockDb {
@@ -568,45 +1048,675 @@ impl PlaceClearanceRepo for MockDb {
     fn cleanup_pending_clearances_for_places(&self, _org_id: &Id) -> RepoResult<u64> {
         Ok(0)
     }
+
+    fn remove_pending_clearances_for_places(
+        &self,
+        _org_id: &Id,
+        _place_ids: &[&str],
+    ) -> RepoResult<usize> {
+        Ok(0)
+    }
 }
 
-impl Db for MockDb {
-    fn create_tag_if_it_does_not_exist(&self, e: &Tag) -> RepoResult<()> {
-        if let Err(err) = create(&mut self.tags.borrow_mut(), e.clone()) {
-            match err {
-                RepoError::AlreadyExists => {
-                    // that's ok
-                }
-                _ => return Err(err),
-            }
-        }
+impl ClearanceLogRepo for MockDb {
+    fn add_clearance_log_entries(&self, entries: &[ClearanceLogEntry]) -> RepoResult<()> {
+        self.clearance_log
+            .borrow_mut()
+            .extend(entries.iter().cloned());
         Ok(())
     }
 
-    fn create_bbox_subscription(&self, s: &BboxSubscription) -> RepoResult<()> {
-        create(&mut self.bbox_subscriptions.borrow_mut(), s.clone())
+    fn list_clearance_log_entries(
+        &self,
+        org_id: Option<&Id>,
+        place_id: Option<&Id>,
+        pagination: &Pagination,
+    ) -> RepoResult<Vec<ClearanceLogEntry>> {
+        let mut entries: Vec<_> = self
+            .clearance_log
+            .borrow()
+            .iter()
+            .filter(|e| org_id.map(|id| *id == e.org_id).unwrap_or(true))
+            .filter(|e| place_id.map(|id| *id == e.place_id).unwrap_or(true))
+            .cloned()
+            .collect();
+        entries.sort_by(|a, b| b.decided_at.cmp(&a.decided_at));
+        let offset = pagination.offset.unwrap_or(0) as usize;
+        let limit = pagination.limit.map(|l| l as usize).unwrap_or(usize::MAX);
+        Ok(entries.into_iter().skip(offset).take(limit).collect())
     }
+}
 
-    fn all_tags(&self) -> RepoResult<Vec<Tag>> {
-        Ok(self.tags.borrow().clone())
+impl ViewCounterRepo for MockDb {
+    fn increment_view_count(
+        &self,
+        entity: ViewedEntity,
+        id: &str,
+        day: Timestamp,
+    ) -> RepoResult<()> {
+        self.views.borrow_mut().push((entity, id.to_string(), day));
+        Ok(())
     }
-    fn count_tags(&self) -> RepoResult<usize> {
-        self.all_tags().map(|v| v.len())
+
+    fn count_views(&self, entity: ViewedEntity, id: &str) -> RepoResult<u64> {
+        Ok(self
+            .views
+            .borrow()
+            .iter()
+            .filter(|(e, i, _)| *e == entity && i == id)
+            .count() as u64)
     }
 
-    fn all_bbox_subscriptions(&self) -> RepoResult<Vec<BboxSubscription>> {
-        Ok(self.bbox_subscriptions.borrow().clone())
+    fn count_all_views_since(&self, since: Timestamp) -> RepoResult<u64> {
+        Ok(self
+            .views
+            .borrow()
+            .iter()
+            .filter(|(_, _, day)| *day >= since)
+            .count() as u64)
+    }
+}
+
+impl PlaceNoteRepo for MockDb {
+    fn create_place_note(&self, note: &PlaceNote) -> RepoResult<()> {
+        create(&mut self.place_notes.borrow_mut(), note.clone())
     }
 
-    fn all_bbox_subscriptions_by_email(
-        &self,
-        user_email: &str,
-    ) -> RepoResult<Vec<BboxSubscription>> {
+    fn load_place_notes(&self, place_id: &str) -> RepoResult<Vec<PlaceNote>> {
         Ok(self
-            .bbox_subscriptions
+            .place_notes
             .borrow()
             .iter()
-            .filter(|s| s.user_email == user_email)
+            .filter(|n| n.place_id.as_str() == place_id)
+            .cloned()
+            .collect())
+    }
+}
+
+impl TagSuggestionRepo for MockDb {
+    fn replace_tag_cooccurrences(&self, cooccurrences: &[TagCooccurrence]) -> RepoResult<()> {
+        *self.tag_cooccurrences.borrow_mut() = cooccurrences.to_vec();
+        Ok(())
+    }
+
+    fn load_tag_cooccurrences(&self, words: &[&str]) -> RepoResult<Vec<TagCooccurrence>> {
+        Ok(self
+            .tag_cooccurrences
+            .borrow()
+            .iter()
+            .filter(|c| words.contains(&c.word.as_str()))
+            .cloned()
+            .collect())
+    }
+}
+
+impl LinkCheckRepo for MockDb {
+    fn record_link_check(
+        &self,
+        url: &str,
+        error: Option<&str>,
+        checked_at: Timestamp,
+    ) -> RepoResult<()> {
+        let mut checks = self.link_checks.borrow_mut();
+        let index = match checks.iter().position(|c| c.url == url) {
+            Some(index) => index,
+            None => {
+                checks.push(LinkCheck {
+                    url: url.into(),
+                    failures: 0,
+                    checked_at,
+                    last_error: None,
+                });
+                checks.len() - 1
+            }
+        };
+        let check = &mut checks[index];
+        check.checked_at = checked_at;
+        check.last_error = error.map(Into::into);
+        check.failures = if error.is_some() {
+            check.failures + 1
+        } else {
+            0
+        };
+        Ok(())
+    }
+
+    fn load_broken_links(&self, min_failures: u32) -> RepoResult<Vec<LinkCheck>> {
+        let mut broken: Vec<_> = self
+            .link_checks
+            .borrow()
+            .iter()
+            .filter(|c| c.failures >= min_failures)
+            .cloned()
+            .collect();
+        broken.sort_by(|c1, c2| {
+            c2.failures
+                .cmp(&c1.failures)
+                .then_with(|| c1.url.cmp(&c2.url))
+        });
+        Ok(broken)
+    }
+
+    fn filter_broken_links(&self, urls: &[&str], min_failures: u32) -> RepoResult<Vec<String>> {
+        Ok(self
+            .load_broken_links(min_failures)?
+            .into_iter()
+            .map(|c| c.url)
+            .filter(|url| urls.contains(&url.as_str()))
+            .collect())
+    }
+}
+
+impl EmailOutboxRepo for MockDb {
+    fn enqueue_emails(&self, emails: &[QueuedEmail]) -> RepoResult<()> {
+        self.email_outbox
+            .borrow_mut()
+            .extend(emails.iter().cloned().map(|email| (email, None)));
+        Ok(())
+    }
+
+    fn load_unsent_emails(&self, limit: usize) -> RepoResult<Vec<QueuedEmail>> {
+        let mut emails: Vec<_> = self
+            .email_outbox
+            .borrow()
+            .iter()
+            .filter(|(_, sent_at)| sent_at.is_none())
+            .map(|(email, _)| email.clone())
+            .collect();
+        emails.sort_by_key(|email| email.created_at);
+        emails.truncate(limit);
+        Ok(emails)
+    }
+
+    fn mark_email_as_sent(&self, id: &Id, sent_at: Timestamp) -> RepoResult<()> {
+        let mut outbox = self.email_outbox.borrow_mut();
+        let (_, email_sent_at) = outbox
+            .iter_mut()
+            .find(|(email, _)| email.id == *id)
+            .ok_or(RepoError::NotFound)?;
+        *email_sent_at = Some(sent_at);
+        Ok(())
+    }
+}
+
+impl NotificationConsentRepo for MockDb {
+    fn request_notification_consent(
+        &self,
+        email_nonce: &EmailNonce,
+        _requested_at: Timestamp,
+    ) -> RepoResult<()> {
+        let mut consents = self.notification_consents.borrow_mut();
+        consents.retain(|(n, _)| n.email != email_nonce.email);
+        consents.push((email_nonce.clone(), None));
+        Ok(())
+    }
+
+    fn confirm_notification_consent(
+        &self,
+        email_nonce: &EmailNonce,
+        confirmed_at: Timestamp,
+    ) -> RepoResult<()> {
+        let mut consents = self.notification_consents.borrow_mut();
+        let (_, consent_confirmed_at) = consents
+            .iter_mut()
+            .find(|(n, _)| n == email_nonce)
+            .ok_or(RepoError::NotFound)?;
+        *consent_confirmed_at = Some(confirmed_at);
+        Ok(())
+    }
+
+    fn has_notification_consent(&self, email: &str) -> RepoResult<bool> {
+        Ok(!self.emails_with_notification_consent(&[email])?.is_empty())
+    }
+
+    fn emails_with_notification_consent(&self, emails: &[&str]) -> RepoResult<Vec<String>> {
+        Ok(self
+            .notification_consents
+            .borrow()
+            .iter()
+            .filter(|(n, confirmed_at)| {
+                confirmed_at.is_some() && emails.contains(&n.email.as_str())
+            })
+            .map(|(n, _)| n.email.clone())
+            .collect())
+    }
+}
+
+impl EntityWatchRepo for MockDb {
+    fn watch_entity(&self, email: &str, entity_id: &str, _created_at: Timestamp) -> RepoResult<()> {
+        self.get_user_by_email(email)?;
+        let mut watches = self.entity_watches.borrow_mut();
+        let watch = (email.to_string(), entity_id.to_string());
+        if !watches.contains(&watch) {
+            watches.push(watch);
+        }
+        Ok(())
+    }
+
+    fn unwatch_entity(&self, email: &str, entity_id: &str) -> RepoResult<()> {
+        self.entity_watches
+            .borrow_mut()
+            .retain(|(watcher, id)| watcher != email || id != entity_id);
+        Ok(())
+    }
+
+    fn emails_watching_entity(&self, entity_id: &str) -> RepoResult<Vec<String>> {
+        Ok(self
+            .entity_watches
+            .borrow()
+            .iter()
+            .filter(|(_, id)| id == entity_id)
+            .map(|(watcher, _)| watcher.clone())
+            .collect())
+    }
+
+    fn entity_ids_watched_by_email(&self, email: &str) -> RepoResult<Vec<String>> {
+        Ok(self
+            .entity_watches
+            .borrow()
+            .iter()
+            .filter(|(watcher, _)| watcher == email)
+            .map(|(_, id)| id.clone())
+            .collect())
+    }
+}
+
+impl PendingNotificationRepo for MockDb {
+    fn add_pending_notifications(&self, notifications: &[PendingNotification]) -> RepoResult<()> {
+        for n in notifications {
+            self.get_user_by_email(&n.user_email)?;
+        }
+        self.pending_notifications
+            .borrow_mut()
+            .extend(notifications.iter().cloned());
+        Ok(())
+    }
+
+    fn all_pending_notifications(&self) -> RepoResult<Vec<PendingNotification>> {
+        let mut notifications = self.pending_notifications.borrow().clone();
+        notifications.sort_by_key(|n| n.created_at.into_inner());
+        Ok(notifications)
+    }
+
+    fn delete_pending_notifications(&self, ids: &[&str]) -> RepoResult<usize> {
+        let mut notifications = self.pending_notifications.borrow_mut();
+        let count = notifications.len();
+        notifications.retain(|n| !ids.contains(&n.id.as_str()));
+        Ok(count - notifications.len())
+    }
+}
+
+impl PendingModeratedTagsNotificationRepo for MockDb {
+    fn add_pending_moderated_tags_notifications(
+        &self,
+        notifications: &[PendingModeratedTagsNotification],
+    ) -> RepoResult<()> {
+        for n in notifications {
+            self.get_org_by_id(&n.org_id)?;
+        }
+        self.pending_moderated_tags_notifications
+            .borrow_mut()
+            .extend(notifications.iter().cloned());
+        Ok(())
+    }
+
+    fn all_pending_moderated_tags_notifications(
+        &self,
+    ) -> RepoResult<Vec<PendingModeratedTagsNotification>> {
+        let mut notifications = self.pending_moderated_tags_notifications.borrow().clone();
+        notifications.sort_by_key(|n| n.created_at.into_inner());
+        Ok(notifications)
+    }
+
+    fn delete_pending_moderated_tags_notifications(&self, ids: &[&str]) -> RepoResult<usize> {
+        let mut notifications = self.pending_moderated_tags_notifications.borrow_mut();
+        let count = notifications.len();
+        notifications.retain(|n| !ids.contains(&n.id.as_str()));
+        Ok(count - notifications.len())
+    }
+}
+
+impl PendingRatingRepo for MockDb {
+    fn add_pending_rating(&self, pending_rating: &PendingRating) -> RepoResult<()> {
+        self.pending_ratings
+            .borrow_mut()
+            .push(pending_rating.clone());
+        Ok(())
+    }
+
+    fn list_pending_ratings(&self, pagination: &Pagination) -> RepoResult<Vec<PendingRating>> {
+        let mut pending_ratings = self.pending_ratings.borrow().clone();
+        pending_ratings.sort_by_key(|p| p.rating.created_at.into_inner());
+        Ok(pending_ratings
+            .into_iter()
+            .skip(pagination.offset.unwrap_or(0) as usize)
+            .take(pagination.limit.unwrap_or(u64::MAX) as usize)
+            .collect())
+    }
+
+    fn load_pending_ratings(&self, ids: &[&str]) -> RepoResult<Vec<PendingRating>> {
+        Ok(self
+            .pending_ratings
+            .borrow()
+            .iter()
+            .filter(|p| ids.contains(&p.rating.id.as_str()))
+            .cloned()
+            .collect())
+    }
+
+    fn delete_pending_ratings(&self, ids: &[&str]) -> RepoResult<usize> {
+        let mut pending_ratings = self.pending_ratings.borrow_mut();
+        let count = pending_ratings.len();
+        pending_ratings.retain(|p| !ids.contains(&p.rating.id.as_str()));
+        Ok(count - pending_ratings.len())
+    }
+}
+
+impl PersonalApiTokenRepo for MockDb {
+    fn create_personal_api_token(&self, token: &PersonalApiToken) -> RepoResult<()> {
+        self.get_user_by_email(&token.user_email)?;
+        create(&mut self.personal_api_tokens.borrow_mut(), token.clone())
+    }
+
+    fn load_personal_api_token(&self, id: &str) -> RepoResult<PersonalApiToken> {
+        get(&self.personal_api_tokens.borrow(), id)
+    }
+
+    fn personal_api_tokens_of_user(&self, email: &str) -> RepoResult<Vec<PersonalApiToken>> {
+        let mut tokens: Vec<_> = self
+            .personal_api_tokens
+            .borrow()
+            .iter()
+            .filter(|t| t.user_email == email)
+            .cloned()
+            .collect();
+        tokens.sort_by_key(|t| t.created_at);
+        Ok(tokens)
+    }
+
+    fn delete_personal_api_token(&self, email: &str, id: &str) -> RepoResult<()> {
+        let mut tokens = self.personal_api_tokens.borrow_mut();
+        let count = tokens.len();
+        tokens.retain(|t| t.user_email != email || t.id.as_str() != id);
+        if tokens.len() == count {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+}
+
+impl OrganizationApiTokenRepo for MockDb {
+    fn create_org_api_token(&self, token: &OrganizationApiToken) -> RepoResult<()> {
+        self.get_org_by_id(&token.org_id)?;
+        create(&mut self.org_api_tokens.borrow_mut(), token.clone())
+    }
+
+    fn load_org_api_token(&self, id: &str) -> RepoResult<OrganizationApiToken> {
+        get(&self.org_api_tokens.borrow(), id)
+    }
+
+    fn org_api_tokens(&self, org_id: &Id) -> RepoResult<Vec<OrganizationApiToken>> {
+        let mut tokens: Vec<_> = self
+            .org_api_tokens
+            .borrow()
+            .iter()
+            .filter(|t| &t.org_id == org_id)
+            .cloned()
+            .collect();
+        tokens.sort_by_key(|t| t.created_at);
+        Ok(tokens)
+    }
+
+    fn update_org_api_token_last_used(&self, id: &str, last_used_at: Timestamp) -> RepoResult<()> {
+        let mut tokens = self.org_api_tokens.borrow_mut();
+        let token = tokens
+            .iter_mut()
+            .find(|t| t.id.as_str() == id)
+            .ok_or(RepoError::NotFound)?;
+        token.last_used_at = Some(last_used_at);
+        Ok(())
+    }
+
+    fn delete_org_api_token(&self, org_id: &Id, id: &str) -> RepoResult<()> {
+        let mut tokens = self.org_api_tokens.borrow_mut();
+        let count = tokens.len();
+        tokens.retain(|t| &t.org_id != org_id || t.id.as_str() != id);
+        if tokens.len() == count {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+}
+
+impl HomepagePreviewRepo for MockDb {
+    fn store_homepage_preview(&self, preview: &HomepagePreview) -> RepoResult<()> {
+        let mut previews = self.homepage_previews.borrow_mut();
+        previews.retain(|p| p.url != preview.url);
+        previews.push(preview.clone());
+        Ok(())
+    }
+
+    fn load_homepage_previews(&self, urls: &[&str]) -> RepoResult<Vec<HomepagePreview>> {
+        Ok(self
+            .homepage_previews
+            .borrow()
+            .iter()
+            .filter(|p| urls.contains(&p.url.as_str()))
+            .cloned()
+            .collect())
+    }
+}
+
+impl PlaceConfirmationRepo for MockDb {
+    fn create_place_confirmation_request(
+        &self,
+        request: &PlaceConfirmationRequest,
+    ) -> RepoResult<()> {
+        self.place_confirmations.borrow_mut().push(request.clone());
+        Ok(())
+    }
+
+    fn load_place_confirmation_requests(
+        &self,
+        place_id: &str,
+    ) -> RepoResult<Vec<PlaceConfirmationRequest>> {
+        Ok(self
+            .place_confirmations
+            .borrow()
+            .iter()
+            .filter(|r| r.place_id.as_str() == place_id)
+            .cloned()
+            .collect())
+    }
+
+    fn answer_place_confirmation_request(
+        &self,
+        place_id: &str,
+        nonce: &Nonce,
+        answered_at: TimestampMs,
+    ) -> RepoResult<()> {
+        let mut requests = self.place_confirmations.borrow_mut();
+        let request = requests
+            .iter_mut()
+            .find(|r| {
+                r.place_id.as_str() == place_id && r.nonce == *nonce && r.answered_at.is_none()
+            })
+            .ok_or(RepoError::NotFound)?;
+        request.answered_at = Some(answered_at);
+        Ok(())
+    }
+
+    fn flag_unanswered_place_confirmation_requests(
+        &self,
+        sent_before: TimestampMs,
+        flagged_at: TimestampMs,
+    ) -> RepoResult<usize> {
+        let mut count = 0;
+        for r in self.place_confirmations.borrow_mut().iter_mut() {
+            if r.sent_at < sent_before && r.answered_at.is_none() && r.flagged_at.is_none() {
+                r.flagged_at = Some(flagged_at);
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    fn load_flagged_place_confirmation_requests(
+        &self,
+    ) -> RepoResult<Vec<PlaceConfirmationRequest>> {
+        Ok(self
+            .place_confirmations
+            .borrow()
+            .iter()
+            .filter(|r| r.answered_at.is_none() && r.flagged_at.is_some())
+            .cloned()
+            .collect())
+    }
+}
+
+impl OsmNodeProposalRepo for MockDb {
+    fn add_osm_node_proposal(&self, proposal: &OsmNodeProposal) -> RepoResult<()> {
+        self.osm_node_proposals.borrow_mut().push(proposal.clone());
+        Ok(())
+    }
+
+    fn get_osm_node_proposal(&self, id: &str) -> RepoResult<OsmNodeProposal> {
+        self.osm_node_proposals
+            .borrow()
+            .iter()
+            .find(|p| p.id.as_str() == id)
+            .cloned()
+            .ok_or(RepoError::NotFound)
+    }
+
+    fn load_osm_node_proposals_of_place(&self, place_id: &str) -> RepoResult<Vec<OsmNodeProposal>> {
+        Ok(self
+            .osm_node_proposals
+            .borrow()
+            .iter()
+            .filter(|p| p.place_id.as_str() == place_id)
+            .cloned()
+            .collect())
+    }
+
+    fn list_pending_osm_node_proposals(
+        &self,
+        pagination: &Pagination,
+    ) -> RepoResult<Vec<OsmNodeProposal>> {
+        Ok(self
+            .osm_node_proposals
+            .borrow()
+            .iter()
+            .filter(|p| p.status == OsmNodeProposalStatus::Pending)
+            .skip(pagination.offset.unwrap_or(0) as usize)
+            .take(pagination.limit.unwrap_or(u64::MAX) as usize)
+            .cloned()
+            .collect())
+    }
+
+    fn review_osm_node_proposal(
+        &self,
+        id: &str,
+        status: OsmNodeProposalStatus,
+        reviewed: &Activity,
+    ) -> RepoResult<()> {
+        let mut proposals = self.osm_node_proposals.borrow_mut();
+        let proposal = proposals
+            .iter_mut()
+            .find(|p| p.id.as_str() == id && p.status == OsmNodeProposalStatus::Pending)
+            .ok_or(RepoError::NotFound)?;
+        proposal.status = status;
+        proposal.reviewed = Some(reviewed.clone());
+        Ok(())
+    }
+
+    fn delete_pending_osm_node_proposals_of_place(&self, place_id: &str) -> RepoResult<usize> {
+        let mut proposals = self.osm_node_proposals.borrow_mut();
+        let count = proposals.len();
+        proposals.retain(|p| {
+            p.place_id.as_str() != place_id || p.status != OsmNodeProposalStatus::Pending
+        });
+        Ok(count - proposals.len())
+    }
+}
+
+impl ChangeLogRepo for MockDb {
+    fn load_changes(
+        &self,
+        _since: Timestamp,
+        _until: Timestamp,
+        _limit: u64,
+    ) -> RepoResult<Vec<ChangeRecord>> {
+        unimplemented!();
+    }
+}
+
+impl DataQualityRepo for MockDb {
+    fn find_places_with_quality_issues(
+        &self,
+        _unreviewed_since: TimestampMs,
+        _limit_per_issue: u64,
+    ) -> RepoResult<Vec<PlaceWithQualityIssue>> {
+        unimplemented!();
+    }
+}
+
+impl RatingAggregateRepo for MockDb {
+    fn load_avg_ratings_of_all_places(&self) -> RepoResult<Vec<(Id, AvgRatings)>> {
+        unimplemented!();
+    }
+    fn recompute_rating_aggregates(&self) -> RepoResult<usize> {
+        unimplemented!();
+    }
+}
+
+impl Db for MockDb {
+    fn create_tag_if_it_does_not_exist(&self, e: &Tag) -> RepoResult<()> {
+        if let Err(err) = create(&mut self.tags.borrow_mut(), e.clone()) {
+            match err {
+                RepoError::AlreadyExists => {
+                    // that's ok
+                }
+                _ => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
+    fn create_bbox_subscription(&self, s: &BboxSubscription) -> RepoResult<()> {
+        create(&mut self.bbox_subscriptions.borrow_mut(), s.clone())
+    }
+
+    fn all_tags(&self) -> RepoResult<Vec<Tag>> {
+        Ok(self.tags.borrow().clone())
+    }
+    fn list_tags(&self, filter: &TagFilter, pagination: &Pagination) -> RepoResult<Vec<Tag>> {
+        let mut tags = self.filtered_tags(filter);
+        tags.sort_by(|a, b| a.id.cmp(&b.id));
+        let offset = pagination.offset.unwrap_or(0) as usize;
+        let limit = pagination.limit.map(|l| l as usize).unwrap_or(usize::MAX);
+        Ok(tags.into_iter().skip(offset).take(limit).collect())
+    }
+    fn count_tags(&self) -> RepoResult<usize> {
+        self.all_tags().map(|v| v.len())
+    }
+    fn count_filtered_tags(&self, filter: &TagFilter) -> RepoResult<usize> {
+        Ok(self.filtered_tags(filter).len())
+    }
+
+    fn all_bbox_subscriptions(&self) -> RepoResult<Vec<BboxSubscription>> {
+        Ok(self.bbox_subscriptions.borrow().clone())
+    }
+
+    fn all_bbox_subscriptions_by_email(
+        &self,
+        user_email: &str,
+    ) -> RepoResult<Vec<BboxSubscription>> {
+        Ok(self
+            .bbox_subscriptions
+            .borrow()
+            .iter()
+            .filter(|s| s.user_email == user_email)
             .cloned()
             .collect())
     }
@@ -617,6 +1727,51 @@ impl Db for MockDb {
             .retain(|s| s.user_email != user_email);
         Ok(())
     }
+
+    fn create_org_subscription(&self, s: &OrganizationSubscription) -> RepoResult<()> {
+        if self
+            .org_subscriptions
+            .borrow()
+            .iter()
+            .any(|x| x.user_email == s.user_email && x.org_id == s.org_id)
+        {
+            return Err(RepoError::AlreadyExists);
+        }
+        create(&mut self.org_subscriptions.borrow_mut(), s.clone())
+    }
+
+    fn all_org_subscriptions_by_org_ids(
+        &self,
+        org_ids: &[&Id],
+    ) -> RepoResult<Vec<OrganizationSubscription>> {
+        Ok(self
+            .org_subscriptions
+            .borrow()
+            .iter()
+            .filter(|s| org_ids.contains(&&s.org_id))
+            .cloned()
+            .collect())
+    }
+
+    fn all_org_subscriptions_by_email(
+        &self,
+        user_email: &str,
+    ) -> RepoResult<Vec<OrganizationSubscription>> {
+        Ok(self
+            .org_subscriptions
+            .borrow()
+            .iter()
+            .filter(|s| s.user_email == user_email)
+            .cloned()
+            .collect())
+    }
+
+    fn delete_org_subscription(&self, user_email: &str, org_id: &Id) -> RepoResult<()> {
+        self.org_subscriptions
+            .borrow_mut()
+            .retain(|s| s.user_email != user_email || s.org_id != *org_id);
+        Ok(())
+    }
 }
 
 #[test]
@@ -654,7 +1809,7 @@ fn create_bbox_subscription() {
             role: Role::Guest,
         })
         .is_ok());
-    assert!(usecases::subscribe_to_bbox(&db, "abc@abc.de".into(), bbox_new).is_ok());
+    assert!(usecases::subscribe_to_bbox(&db, "abc@abc.de".into(), bbox_new, None).is_ok());
 
     let bbox_subscription = db.all_bbox_subscriptions().unwrap()[0].clone();
     assert_eq!(
@@ -690,10 +1845,11 @@ fn modify_bbox_subscription() {
         id: "123".into(),
         user_email: "abc@abc.de".into(),
         bbox: bbox_old,
+        digest: None,
     };
     db.create_bbox_subscription(&bbox_subscription).unwrap();
 
-    usecases::subscribe_to_bbox(&db, "abc@abc.de".into(), bbox_new).unwrap();
+    usecases::subscribe_to_bbox(&db, "abc@abc.de".into(), bbox_new, None).unwrap();
 
     let bbox_subscriptions: Vec<_> = db
         .all_bbox_subscriptions()
@@ -735,6 +1891,7 @@ fn get_bbox_subscriptions() {
         id: "1".into(),
         user_email: "a@abc.de".into(),
         bbox: bbox1,
+        digest: None,
     };
     assert!(db.create_bbox_subscription(&bbox_subscription).is_ok());
 
@@ -750,6 +1907,7 @@ fn get_bbox_subscriptions() {
         id: "2".into(),
         user_email: "b@abc.de".into(),
         bbox: bbox2,
+        digest: None,
     };
     assert!(db.create_bbox_subscription(&bbox_subscription2).is_ok());
     let bbox_subscriptions = usecases::get_bbox_subscriptions(&db, "b@abc.de");
@@ -773,7 +1931,7 @@ fn email_addresses_by_coordinate() {
     })
     .unwrap();
 
-    usecases::subscribe_to_bbox(&db, "abc@abc.de".into(), bbox_new).unwrap();
+    usecases::subscribe_to_bbox(&db, "abc@abc.de".into(), bbox_new, None).unwrap();
 
     let email_addresses =
         usecases::email_addresses_by_coordinate(&db, MapPoint::from_lat_lng_deg(5.0, 5.0)).unwrap();
@@ -808,7 +1966,25 @@ fn delete_user() {
     assert_eq!(db.count_users().unwrap(), 2);
 
     assert!(usecases::delete_user(&db, "abc@abc.de", "abc@abc.de").is_ok());
-    assert_eq!(db.count_users().unwrap(), 1);
+    // The user is only deactivated during the grace period
+    assert_eq!(db.count_users().unwrap(), 2);
+    let credentials = usecases::Credentials {
+        email: "abc@abc.de",
+        password: "secret",
+    };
+    assert!(matches!(
+        usecases::login_with_email(&db, &credentials),
+        Err(Error::Parameter(ParameterError::UserDeactivated))
+    ));
+    assert_eq!(
+        vec!["abc@abc.de".to_string()],
+        db.users_deactivated_before(Timestamp::from_inner(Timestamp::now().into_inner() + 1))
+            .unwrap()
+    );
+
+    assert!(usecases::reactivate_user(&db, "abc@abc.de").is_ok());
+    assert!(usecases::login_with_email(&db, &credentials).is_ok());
+    assert!(usecases::reactivate_user(&db, "abc@abc.de").is_err());
 }
 
 #[test]
@@ -836,6 +2012,12 @@ fn receive_event_with_creators_email() {
         archived: None,
         image_url: None,
         image_link_url: None,
+        time_zone: None,
+        recurrence: None,
+        address_auto_filled: false,
+        publish_at: None,
+        created_at: None,
+        updated_at: None,
     })
     .unwrap();
     let e = usecases::get_event(&db, "x").unwrap();
@@ -843,15 +2025,42 @@ fn receive_event_with_creators_email() {
 }
 
 #[test]
-fn tag_lists() {
-    assert_eq!(
-        vec![
-            "a".to_string(),
-            "b".to_string(),
-            "c".to_string(),
-            "d".to_string(),
-            "e-f".to_string()
-        ],
-        prepare_tag_list(vec!["  A\n#d\tc #B ", "#", "#e-f"].into_iter())
-    );
+fn hide_scheduled_event() {
+    let db = MockDb::default();
+    let now = Timestamp::now().into_seconds();
+    db.create_event(Event {
+        id: "x".into(),
+        title: "t".into(),
+        description: None,
+        start: NaiveDateTime::from_timestamp(now, 0),
+        end: None,
+        contact: None,
+        location: None,
+        homepage: None,
+        tags: vec![],
+        created_by: None,
+        registration: None,
+        archived: None,
+        image_url: None,
+        image_link_url: None,
+        time_zone: None,
+        recurrence: None,
+        address_auto_filled: false,
+        publish_at: Some(Timestamp::from_seconds(now + 3600)),
+        created_at: None,
+        updated_at: None,
+    })
+    .unwrap();
+    assert!(matches!(
+        usecases::get_event(&db, "x"),
+        Err(Error::Repo(RepoError::NotFound))
+    ));
+}
+
+#[test]
+fn mock_db_conforms_to_repositories() {
+    use crate::core::repositories::conformance;
+    conformance::place_repo(&MockDb::default());
+    conformance::event_gateway(&MockDb::default());
+    conformance::rating_repo(&MockDb::default());
 }
@@ -0,0 +1,121 @@
+use crate::core::prelude::*;
+
+fn owned_tag<'a>(org: &'a Organization, tag: &str) -> Result<&'a ModeratedTag> {
+    // Organizations are only permitted to pass on their own tags
+    org.moderated_tags
+        .iter()
+        .find(|t| t.label == tag)
+        .ok_or_else(|| ParameterError::Forbidden.into())
+}
+
+fn load_new_owner<R: OrganizationRepo>(repo: &R, tag: &str, org_id: &Id) -> Result<Organization> {
+    let new_owner = repo.get_org_by_id(org_id)?;
+    if new_owner.moderated_tags.iter().any(|t| t.label == tag) {
+        return Err(ParameterError::TagAlreadyOwned.into());
+    }
+    Ok(new_owner)
+}
+
+// The moderation policy is carried over with the permissions
+fn add_moderated_tag<R: OrganizationRepo>(
+    repo: &R,
+    org: &Organization,
+    owner_id: &Id,
+    moderated_tag: &ModeratedTag,
+) -> Result<()> {
+    repo.add_moderated_tag(owner_id, moderated_tag)?;
+    if let Some(policy) = repo.get_tag_moderation_policy(&org.id, &moderated_tag.label)? {
+        repo.set_tag_moderation_policy(owner_id, &moderated_tag.label, policy)?;
+    }
+    Ok(())
+}
+
+fn transfer_pending_clearances<R: PlaceRepo + PlaceClearanceRepo>(
+    repo: &R,
+    org: &Organization,
+    new_owner_id: &Id,
+    tag: &str,
+) -> Result<()> {
+    let pending_clearances =
+        repo.list_pending_clearances_for_places(&org.id, &Pagination::default())?;
+    let place_ids: Vec<_> = pending_clearances
+        .iter()
+        .map(|pending| pending.place_id.as_str())
+        .collect();
+    let places = repo.get_places_by_ids(&place_ids)?;
+    let mut transferred_place_ids = vec![];
+    for pending in &pending_clearances {
+        let place_tags = match places.iter().find(|(p, _)| p.id == pending.place_id) {
+            Some((place, _)) => &place.tags,
+            None => continue,
+        };
+        if !place_tags.iter().any(|t| t == tag) {
+            continue;
+        }
+        super::clearance::place::add_pending_clearance(repo, &[new_owner_id.clone()], pending)?;
+        // The previous owner still needs to clear places that are
+        // tagged with any other of its tags that require clearance
+        let still_required = org.moderated_tags.iter().any(|t| {
+            t.label != tag && t.require_clearance && place_tags.iter().any(|l| *l == t.label)
+        });
+        if !still_required {
+            transferred_place_ids.push(pending.place_id.as_str());
+        }
+    }
+    if !transferred_place_ids.is_empty() {
+        debug!(
+            "Transferring {} pending clearance(s) from organization {} to {}",
+            transferred_place_ids.len(),
+            org.id,
+            new_owner_id
+        );
+        repo.remove_pending_clearances_for_places(&org.id, &transferred_place_ids)?;
+    }
+    Ok(())
+}
+
+/// Hands over a moderated tag with its current permissions
+/// to another organization. Pending clearances of places with
+/// this tag are handed over, too.
+///
+/// All changes should be applied within a single transaction.
+pub fn transfer_moderated_tag<R: OrganizationRepo + PlaceRepo + PlaceClearanceRepo>(
+    repo: &R,
+    org: &Organization,
+    tag: &str,
+    new_owner_id: &Id,
+) -> Result<()> {
+    let moderated_tag = owned_tag(org, tag)?;
+    let new_owner = load_new_owner(repo, tag, new_owner_id)?;
+    info!(
+        "Transferring tag '{}' from organization {} to {}",
+        tag, org.id, new_owner.id
+    );
+    add_moderated_tag(repo, org, &new_owner.id, moderated_tag)?;
+    repo.remove_moderated_tag(&org.id, tag)?;
+    if moderated_tag.require_clearance {
+        transfer_pending_clearances(repo, org, &new_owner.id, tag)?;
+    }
+    Ok(())
+}
+
+/// Shares a moderated tag with its current permissions with
+/// another organization. Both organizations are permitted to
+/// moderate the tag afterwards.
+///
+/// All changes should be applied within a single transaction.
+pub fn share_moderated_tag<R: OrganizationRepo>(
+    repo: &R,
+    org: &Organization,
+    tag: &str,
+    co_owner_id: &Id,
+) -> Result<()> {
+    let moderated_tag = owned_tag(org, tag)?;
+    let co_owner = load_new_owner(repo, tag, co_owner_id)?;
+    info!(
+        "Sharing tag '{}' of organization {} with {}",
+        tag, org.id, co_owner.id
+    );
+    add_moderated_tag(repo, org, &co_owner.id, moderated_tag)?;
+    Ok(())
+}
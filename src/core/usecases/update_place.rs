@@ -1,8 +1,8 @@
-use super::{parse_custom_link_param, CustomLinkParam};
+use super::{check_category_ids, parse_custom_link_param, CustomLinkParam};
 
 use crate::core::{
     prelude::*,
-    util::{parse::parse_url_param, validate::Validate},
+    util::{address::normalize_address, parse::parse_url_param, validate::Validate},
 };
 
 use chrono::NaiveDate;
@@ -44,6 +44,7 @@ impl From<Place> for UpdatePlace {
             license: _,
             links,
             location: Location { address, pos },
+            address_auto_filled: _,
             opening_hours,
             founded_on,
             revision,
@@ -96,6 +97,13 @@ pub struct Storable {
     place: Place,
     clearance_org_ids: Vec<Id>,
     last_cleared_revision: Revision,
+    old_tags: Vec<String>,
+}
+
+impl Storable {
+    pub fn old_tags(&self) -> &[String] {
+        &self.old_tags
+    }
 }
 
 pub fn prepare_updated_place<D: Db>(
@@ -132,42 +140,52 @@ pub fn prepare_updated_place<D: Db>(
     } = e;
     let pos =
         MapPoint::try_from_lat_lng_deg(lat, lng).map_err(|_| ParameterError::InvalidPosition)?;
-    let address = Address {
+    let address = normalize_address(Address {
         street,
         zip,
         city,
         country,
         state,
-    };
+    });
     let address = if address.is_empty() {
         None
     } else {
         Some(address)
     };
 
-    let (revision, last_cleared_revision, old_tags, license) = {
-        let (old_place, _review_status) = db.get_place(place_id.as_str())?;
-        // Check for revision conflict (optimistic locking)
-        let revision = Revision::from(version);
-        if old_place.revision.next() != revision {
-            return Err(RepoError::InvalidVersion.into());
-        }
-        let last_cleared_revision = old_place.revision;
-        // The license is immutable
-        let license = old_place.license;
-        // The existing tags are needed for authorization
-        let old_tags = old_place.tags;
-        (revision, last_cleared_revision, old_tags, license)
+    let (current, review_status) = db.get_place_by_id(place_id.as_str())?;
+    // Drafts need to be published before they can be updated
+    if review_status == ReviewStatus::Draft {
+        return Err(Error::Parameter(ParameterError::Forbidden));
+    }
+    // Check for revision conflict (optimistic locking)
+    let base = if current.revision.next() == Revision::from(version) {
+        None
+    } else if version > 0 && Revision::from(version) <= current.revision {
+        // The place has been modified in the meantime. The edit is
+        // merged into the current revision if possible.
+        let (base, _) = db
+            .load_place_revision(place_id.as_str(), Revision::from(version - 1))
+            .map_err(|err| match err {
+                RepoError::NotFound => RepoError::InvalidVersion,
+                err => err,
+            })?;
+        Some(base)
+    } else {
+        return Err(RepoError::InvalidVersion.into());
     };
+    let revision = current.revision.next();
+    let last_cleared_revision = current.revision;
+    // The license is immutable
+    let license = current.license.clone();
 
+    check_category_ids(db, &categories)?;
     let categories: Vec<_> = categories.into_iter().map(Id::from).collect();
     let new_tags = super::prepare_tag_list(
         Category::merge_ids_into_tags(&categories, tags)
             .iter()
             .map(String::as_str),
     );
-    let clearance_org_ids =
-        super::authorize_editing_of_tagged_entry(db, &old_tags, &new_tags, created_by_org)?;
 
     let homepage = homepage
         .and_then(|ref url| parse_url_param(url).transpose())
@@ -195,6 +213,9 @@ pub fn prepare_updated_place<D: Db>(
             })
         };
 
+    // An auto-filled address remains flagged until it is edited
+    let address_auto_filled =
+        current.address_auto_filled && non_empty(&address) == non_empty(&current.location.address);
     let place = Place {
         id: place_id,
         license,
@@ -203,6 +224,7 @@ pub fn prepare_updated_place<D: Db>(
         title,
         description,
         location: Location { pos, address },
+        address_auto_filled,
         contact: Some(Contact {
             name: contact_name,
             email: email.map(Into::into),
@@ -218,6 +240,14 @@ pub fn prepare_updated_place<D: Db>(
         links,
         tags: new_tags,
     };
+    let place = match base {
+        Some(base) => merge_concurrent_edit(&base, &current, place)?,
+        None => place,
+    };
+    // The existing tags are needed for authorization
+    let old_tags = current.tags;
+    let clearance_org_ids =
+        super::authorize_editing_of_tagged_entry(db, &old_tags, &place.tags, created_by_org)?;
     place.validate()?;
     if !accepted_licenses.contains(&place.license) {
         return Err(Error::Parameter(ParameterError::License));
@@ -226,6 +256,91 @@ pub fn prepare_updated_place<D: Db>(
         place,
         clearance_org_ids,
         last_cleared_revision,
+        old_tags,
+    })
+}
+
+fn merge_field<T: Clone + PartialEq>(base: &T, current: &T, edited: T) -> Result<T> {
+    if edited == *base || edited == *current {
+        Ok(current.clone())
+    } else if current == base {
+        Ok(edited)
+    } else {
+        Err(RepoError::InvalidVersion.into())
+    }
+}
+
+// Missing and empty sections are considered as equal
+fn non_empty<T: Clone + Default + PartialEq>(section: &Option<T>) -> Option<T> {
+    section.clone().filter(|s| *s != T::default())
+}
+
+fn sorted_tags(tags: &[String]) -> Vec<String> {
+    let mut tags = tags.to_vec();
+    tags.sort_unstable();
+    tags
+}
+
+/// Applies the changes of an edit that is based on an outdated
+/// revision (`base`) to the current revision. Fails if both the
+/// edit and the current revision have changed the same field.
+fn merge_concurrent_edit(base: &Place, current: &Place, edited: Place) -> Result<Place> {
+    let Place {
+        id,
+        license,
+        revision,
+        created,
+        title,
+        description,
+        location: Location { pos, address },
+        address_auto_filled: _,
+        contact,
+        opening_hours,
+        founded_on,
+        links,
+        tags,
+    } = edited;
+    debug!(
+        "Merging concurrent edit of place {} into revision {}",
+        id,
+        u64::from(current.revision)
+    );
+    let address = merge_field(
+        &non_empty(&base.location.address),
+        &non_empty(&current.location.address),
+        non_empty(&address),
+    )?;
+    let address_auto_filled =
+        current.address_auto_filled && address == non_empty(&current.location.address);
+    Ok(Place {
+        id,
+        license,
+        revision,
+        created,
+        title: merge_field(&base.title, &current.title, title)?,
+        description: merge_field(&base.description, &current.description, description)?,
+        location: Location {
+            pos: merge_field(&base.location.pos, &current.location.pos, pos)?,
+            address,
+        },
+        address_auto_filled,
+        contact: merge_field(
+            &non_empty(&base.contact),
+            &non_empty(&current.contact),
+            non_empty(&contact),
+        )?,
+        opening_hours: merge_field(&base.opening_hours, &current.opening_hours, opening_hours)?,
+        founded_on: merge_field(&base.founded_on, &current.founded_on, founded_on)?,
+        links: merge_field(
+            &non_empty(&base.links),
+            &non_empty(&current.links),
+            non_empty(&links),
+        )?,
+        tags: merge_field(
+            &sorted_tags(&base.tags),
+            &sorted_tags(&current.tags),
+            sorted_tags(&tags),
+        )?,
     })
 }
 
@@ -234,6 +349,7 @@ pub fn store_updated_place<D: Db>(db: &D, s: Storable) -> Result<(Place, Vec<Rat
         place,
         clearance_org_ids,
         last_cleared_revision,
+        old_tags: _,
     } = s;
     debug!("Storing updated place revision: {:?}", place);
     for t in &place.tags {
@@ -503,8 +619,43 @@ mod tests {
         )
         .unwrap();
         assert!(store_updated_place(&mock_db, storable).is_ok());
-        let (e, _) = mock_db.get_place(id.as_ref()).unwrap();
+        let (e, _) = mock_db.get_place_by_id(id.as_ref()).unwrap();
         assert_eq!(e.tags, vec!["vegan"]);
         assert_eq!(mock_db.tags.borrow().len(), 3);
     }
+
+    #[test]
+    fn merge_concurrent_edits_of_different_fields() {
+        let base = Place::build()
+            .revision(1)
+            .title("foo")
+            .description("bar")
+            .tags(vec!["a", "b"])
+            .finish();
+        let current = Place {
+            revision: 2.into(),
+            title: "new foo".into(),
+            ..base.clone()
+        };
+        let edited = Place {
+            revision: 3.into(),
+            description: "new bar".into(),
+            tags: vec!["b".into(), "a".into()],
+            ..base.clone()
+        };
+        let merged = merge_concurrent_edit(&base, &current, edited.clone()).unwrap();
+        assert_eq!(Revision::from(3), merged.revision);
+        assert_eq!("new foo", merged.title);
+        assert_eq!("new bar", merged.description);
+        assert_eq!(vec!["a", "b"], merged.tags);
+
+        let edited = Place {
+            title: "other foo".into(),
+            ..edited
+        };
+        assert!(matches!(
+            merge_concurrent_edit(&base, &current, edited),
+            Err(Error::Repo(RepoError::InvalidVersion))
+        ));
+    }
 }
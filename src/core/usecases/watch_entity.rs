@@ -0,0 +1,76 @@
+use crate::core::prelude::*;
+
+fn check_entity_exists<D: Db>(db: &D, entity_id: &str) -> Result<()> {
+    match db.get_place_by_id(entity_id) {
+        Err(RepoError::NotFound) => {
+            db.get_event(entity_id)?;
+        }
+        Err(err) => return Err(err.into()),
+        Ok(_) => {}
+    }
+    Ok(())
+}
+
+/// Notifies the user about all changes and new ratings
+/// of the place or event with this id.
+pub fn watch_entity<D: Db>(db: &D, email: &str, entity_id: &str) -> Result<()> {
+    check_entity_exists(db, entity_id)?;
+    Ok(db.watch_entity(email, entity_id, Timestamp::now())?)
+}
+
+pub fn unwatch_entity<D: Db>(db: &D, email: &str, entity_id: &str) -> Result<()> {
+    Ok(db.unwatch_entity(email, entity_id)?)
+}
+
+/// Adds the e-mail addresses of all users that watch
+/// the entity and are not yet included.
+pub fn add_email_addresses_of_watchers(
+    db: &dyn Db,
+    entity_id: &str,
+    email_addresses: &mut Vec<String>,
+) -> Result<()> {
+    for email in db.emails_watching_entity(entity_id)? {
+        if !email_addresses.contains(&email) {
+            email_addresses.push(email);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockDb;
+    use super::*;
+
+    #[test]
+    fn watch_existing_places_only() {
+        let db = MockDb::default();
+        db.create_user(&User {
+            email: "user@example.com".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::User,
+        })
+        .unwrap();
+        db.entries.borrow_mut().push((
+            Place::build().id("a").title("a").finish(),
+            ReviewStatus::Created,
+        ));
+        assert!(matches!(
+            watch_entity(&db, "user@example.com", "b"),
+            Err(Error::Repo(RepoError::NotFound))
+        ));
+        watch_entity(&db, "user@example.com", "a").unwrap();
+        watch_entity(&db, "user@example.com", "a").unwrap();
+
+        let mut email_addresses = vec!["user@example.com".to_string()];
+        add_email_addresses_of_watchers(&db, "a", &mut email_addresses).unwrap();
+        assert_eq!(vec!["user@example.com"], email_addresses);
+
+        unwatch_entity(&db, "user@example.com", "a").unwrap();
+        assert!(db
+            .entity_ids_watched_by_email("user@example.com")
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,232 @@
+use crate::core::entities::Address;
+
+// Words that are not capitalized within street and city names,
+// e.g. "Frankfurt am Main" or "Rue de la Paix"
+const LOWERCASE_PARTICLES: &[&str] = &[
+    "am", "an", "auf", "bei", "de", "dem", "den", "der", "des", "die", "du", "im", "in", "la",
+    "le", "les", "of", "on", "the", "unter", "vom", "von", "zu", "zum", "zur",
+];
+
+// Abbreviations like "BW" or "NRW" are kept in uppercase
+const MAX_ABBREVIATION_LEN: usize = 3;
+
+/// Countries with a known format of their zip codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ZipFormat {
+    // Only digits, e.g. "70173" in Germany
+    Digits(usize),
+    // 4 digits and 2 letters, e.g. "1012 AB"
+    Netherlands,
+}
+
+fn zip_format(country: &str) -> Option<ZipFormat> {
+    let format = match &*country.to_lowercase() {
+        "de" | "deu" | "germany" | "deutschland" => ZipFormat::Digits(5),
+        "at" | "aut" | "austria" | "österreich" => ZipFormat::Digits(4),
+        "ch" | "che" | "switzerland" | "schweiz" | "suisse" | "svizzera" => ZipFormat::Digits(4),
+        "fr" | "fra" | "france" | "frankreich" => ZipFormat::Digits(5),
+        "nl" | "nld" | "netherlands" | "niederlande" | "nederland" => ZipFormat::Netherlands,
+        _ => return None,
+    };
+    Some(format)
+}
+
+// Trims and collapses inner whitespace
+fn normalize_whitespace(text: &str) -> Option<String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+// Names that have been entered in all lowercase or all uppercase
+// letters are capitalized word by word. Mixed case is preserved.
+fn normalize_casing(name: String) -> String {
+    let letters = || name.chars().filter(|c| c.is_alphabetic());
+    let is_lowercase = letters().all(char::is_lowercase);
+    let is_uppercase = letters().all(char::is_uppercase);
+    if letters().next().is_none() || !(is_lowercase || is_uppercase) {
+        return name;
+    }
+    if is_uppercase
+        && name
+            .split(|c: char| c.is_whitespace() || c == '-')
+            .all(|word| word.chars().count() <= MAX_ABBREVIATION_LEN)
+    {
+        return name;
+    }
+    name.to_lowercase()
+        .split(' ')
+        .enumerate()
+        .map(|(i, word)| {
+            if i > 0 && LOWERCASE_PARTICLES.contains(&word) {
+                word.to_string()
+            } else {
+                word.split('-')
+                    .map(capitalize)
+                    .collect::<Vec<_>>()
+                    .join("-")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn normalize_zip(zip: String, format: Option<ZipFormat>) -> String {
+    let compact: String = zip.chars().filter(|c| !c.is_whitespace()).collect();
+    match format {
+        Some(ZipFormat::Digits(len))
+            if compact.len() == len && compact.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            compact
+        }
+        Some(ZipFormat::Netherlands)
+            if compact.len() == 6
+                && compact.is_ascii()
+                && compact[..4].chars().all(|c| c.is_ascii_digit())
+                && compact[4..].chars().all(|c| c.is_ascii_alphabetic()) =>
+        {
+            format!("{} {}", &compact[..4], compact[4..].to_uppercase())
+        }
+        _ => zip.to_uppercase(),
+    }
+}
+
+fn normalize_country(country: String) -> String {
+    if country.chars().count() == 2 && country.chars().all(char::is_alphabetic) {
+        // ISO 3166-1 alpha-2 code
+        country.to_uppercase()
+    } else {
+        normalize_casing(country)
+    }
+}
+
+/// Removes redundant whitespace, fixes the casing of names
+/// and formats the zip code according to the country.
+///
+/// Empty fields are removed. Zip codes that don't match
+/// the format of the country are only uppercased.
+pub fn normalize_address(address: Address) -> Address {
+    let Address {
+        street,
+        zip,
+        city,
+        country,
+        state,
+    } = address;
+    let name = |name: Option<String>| {
+        name.as_deref()
+            .and_then(normalize_whitespace)
+            .map(normalize_casing)
+    };
+    let country = country
+        .as_deref()
+        .and_then(normalize_whitespace)
+        .map(normalize_country);
+    let zip_format = country.as_deref().and_then(zip_format);
+    Address {
+        street: name(street),
+        zip: zip
+            .as_deref()
+            .and_then(normalize_whitespace)
+            .map(|zip| normalize_zip(zip, zip_format)),
+        city: name(city),
+        country,
+        state: name(state),
+    }
+}
+
+/// Checks if both addresses refer to the same building, i.e.
+/// the street and either the zip code or the city are equal.
+pub fn is_same_address(a1: &Address, a2: &Address) -> bool {
+    let (a1, a2) = (normalize_address(a1.clone()), normalize_address(a2.clone()));
+    let equal = |f1: &Option<String>, f2: &Option<String>| match (f1, f2) {
+        (Some(f1), Some(f2)) => f1.to_lowercase() == f2.to_lowercase(),
+        _ => false,
+    };
+    equal(&a1.street, &a2.street) && (equal(&a1.zip, &a2.zip) || equal(&a1.city, &a2.city))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(street: &str, zip: &str, city: &str, country: &str) -> Address {
+        let field = |value: &str| Some(value.to_string());
+        Address {
+            street: field(street),
+            zip: field(zip),
+            city: field(city),
+            country: field(country),
+            state: None,
+        }
+    }
+
+    #[test]
+    fn normalize_whitespace_and_casing() {
+        assert_eq!(
+            address("Königstraße 1", "70173", "Frankfurt am Main", "DE"),
+            normalize_address(address(
+                "  königstraße  1 ",
+                "70173",
+                "FRANKFURT AM MAIN",
+                "de"
+            ))
+        );
+        assert_eq!(
+            Some("Baden-Württemberg"),
+            normalize_address(Address {
+                state: Some("baden-württemberg".into()),
+                ..Default::default()
+            })
+            .state
+            .as_deref()
+        );
+        // Abbreviations and mixed case are preserved
+        let normalized = normalize_address(Address {
+            street: Some("McAllister Road".into()),
+            state: Some("NRW".into()),
+            ..Default::default()
+        });
+        assert_eq!(Some("McAllister Road"), normalized.street.as_deref());
+        assert_eq!(Some("NRW"), normalized.state.as_deref());
+    }
+
+    #[test]
+    fn remove_empty_fields() {
+        assert!(normalize_address(address(" ", "", "\t", "")).is_empty());
+    }
+
+    #[test]
+    fn format_zip_codes_per_country() {
+        let zip = |zip: &str, country: &str| normalize_address(address("", zip, "", country)).zip;
+        assert_eq!(Some("70173".into()), zip("701 73", "Deutschland"));
+        assert_eq!(Some("1010".into()), zip(" 10 10", "Austria"));
+        assert_eq!(Some("1012 AB".into()), zip("1012ab", "NL"));
+        // Invalid zip codes and unknown formats
+        assert_eq!(Some("7017".into()), zip("7017", "DE"));
+        assert_eq!(Some("SW1A 1AA".into()), zip("sw1a 1aa", "United Kingdom"));
+    }
+
+    #[test]
+    fn compare_normalized_addresses() {
+        let a1 = address("Königstraße 1", "70173", "Stuttgart", "DE");
+        let a2 = address("königstraße 1", "701 73", "", "Germany");
+        assert!(is_same_address(&a1, &a2));
+        let a3 = address("Königstraße 1", "", "STUTTGART", "");
+        assert!(is_same_address(&a1, &a3));
+        assert!(!is_same_address(&a2, &a3));
+        let a4 = address("Königstraße 2", "70173", "Stuttgart", "DE");
+        assert!(!is_same_address(&a1, &a4));
+    }
+}
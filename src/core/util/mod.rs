@@ -1,4 +1,6 @@
+pub mod address;
 pub mod parse;
+pub mod time_zone;
 pub mod validate;
 
 use regex::Regex;
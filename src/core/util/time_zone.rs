@@ -0,0 +1,130 @@
+use chrono::{prelude::*, Duration};
+use chrono_tz::Tz;
+use ofdb_entities::{event::Event, geo::MapPoint, recurrence::Recurrence};
+
+/// Looks up the IANA time zone at the given position,
+/// e.g. "Europe/Berlin".
+pub fn time_zone_at(pos: MapPoint) -> Option<String> {
+    if !pos.is_valid() {
+        return None;
+    }
+    tz_search::lookup(pos.lat().to_deg(), pos.lng().to_deg())
+}
+
+pub fn is_valid_time_zone(tz: &str) -> bool {
+    tz.parse::<chrono_tz::Tz>().is_ok()
+}
+
+/// The start and end (UTC) of all occurrences of an event.
+///
+/// Recurrence rules are expanded in the time zone of the event,
+/// i.e. the occurrences keep their local time when daylight saving
+/// time begins or ends. Events without a valid time zone are
+/// expanded in UTC.
+pub fn occurrence_times(
+    event: &Event,
+) -> Box<dyn Iterator<Item = (NaiveDateTime, Option<NaiveDateTime>)>> {
+    let tz = event
+        .time_zone
+        .as_deref()
+        .and_then(|tz| tz.parse::<Tz>().ok());
+    let (recurrence, tz) = match (&event.recurrence, tz) {
+        (Some(recurrence), Some(tz)) => (recurrence, tz),
+        _ => return Box::new(event.occurrence_times()),
+    };
+    let local_recurrence = Recurrence {
+        // The end of a recurrence rule is given in UTC
+        until: recurrence
+            .until
+            .map(|until| tz.from_utc_datetime(&until).naive_local()),
+        ..recurrence.clone()
+    };
+    let local_start = tz.from_utc_datetime(&event.start).naive_local();
+    let duration = event.end.map(|end| end - event.start);
+    Box::new(
+        local_recurrence
+            .starts(local_start)
+            .filter_map(move |start| {
+                // Local times that are skipped when daylight saving
+                // time begins are moved forward by the gap
+                tz.from_local_datetime(&start)
+                    .earliest()
+                    .or_else(|| {
+                        tz.from_local_datetime(&(start + Duration::hours(1)))
+                            .earliest()
+                    })
+                    .map(|start| start.naive_utc())
+            })
+            .map(move |start| (start, duration.map(|duration| start + duration))),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_time_zone_by_position() {
+        assert_eq!(
+            Some("Europe/Berlin"),
+            time_zone_at(MapPoint::from_lat_lng_deg(48.7758, 9.1829)).as_deref()
+        );
+        assert_eq!(
+            Some("America/New_York"),
+            time_zone_at(MapPoint::from_lat_lng_deg(40.7128, -74.006)).as_deref()
+        );
+        assert_eq!(None, time_zone_at(MapPoint::default()));
+    }
+
+    #[test]
+    fn validate_time_zone_names() {
+        assert!(is_valid_time_zone("Europe/Berlin"));
+        assert!(!is_valid_time_zone("Europe/Stuttgart"));
+        assert!(!is_valid_time_zone(""));
+    }
+
+    #[test]
+    fn expand_recurring_events_in_local_time() {
+        let start = NaiveDate::from_ymd(2021, 3, 22).and_hms(17, 0, 0);
+        let mut event = Event {
+            id: "e".into(),
+            title: "weekly".into(),
+            description: None,
+            start,
+            end: Some(start + Duration::hours(2)),
+            location: None,
+            contact: None,
+            tags: vec![],
+            homepage: None,
+            created_by: None,
+            registration: None,
+            archived: None,
+            image_url: None,
+            image_link_url: None,
+            time_zone: Some("Europe/Berlin".into()),
+            recurrence: Some("FREQ=WEEKLY;COUNT=2".parse().unwrap()),
+            address_auto_filled: false,
+            publish_at: None,
+            created_at: None,
+            updated_at: None,
+        };
+        // 18:00 CET and 18:00 CEST after the begin of daylight saving time
+        let second_start = NaiveDate::from_ymd(2021, 3, 29).and_hms(16, 0, 0);
+        assert_eq!(
+            vec![
+                (start, Some(start + Duration::hours(2))),
+                (second_start, Some(second_start + Duration::hours(2))),
+            ],
+            occurrence_times(&event).collect::<Vec<_>>()
+        );
+
+        event.time_zone = None;
+        let second_start = NaiveDate::from_ymd(2021, 3, 29).and_hms(17, 0, 0);
+        assert_eq!(
+            vec![start, second_start],
+            occurrence_times(&event)
+                .map(|(start, _)| start)
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -118,6 +118,11 @@ impl Validate for Event {
                 return Err(ParameterError::EndDateBeforeStart);
             }
         }
+        if let Some(until) = self.recurrence.as_ref().and_then(|r| r.until) {
+            if until < self.start {
+                return Err(ParameterError::EndDateBeforeStart);
+            }
+        }
         Ok(())
     }
 }
@@ -203,6 +208,12 @@ mod tests {
             archived: None,
             image_url: None,
             image_link_url: None,
+            time_zone: None,
+            recurrence: None,
+            address_auto_filled: false,
+            publish_at: None,
+            created_at: None,
+            updated_at: None,
         };
 
         let mut x = e.clone();
@@ -307,6 +318,12 @@ mod tests {
             archived: None,
             image_url: None,
             image_link_url: None,
+            time_zone: None,
+            recurrence: None,
+            address_auto_filled: false,
+            publish_at: None,
+            created_at: None,
+            updated_at: None,
         };
         assert!(e.validate().is_ok());
         assert!(Event {
@@ -352,8 +369,49 @@ mod tests {
             archived: None,
             image_url: None,
             image_link_url: None,
+            time_zone: None,
+            recurrence: None,
+            address_auto_filled: false,
+            publish_at: None,
+            created_at: None,
+            updated_at: None,
+        };
+        assert!(e.validate().is_err());
+    }
+
+    #[test]
+    fn event_with_recurrence_until_before_start() {
+        let now = Utc::now().naive_utc();
+        let until = (now - Duration::days(1)).format("%Y%m%d");
+        let e = Event {
+            id: "x".into(),
+            title: "foo".into(),
+            description: None,
+            start: now,
+            end: None,
+            location: None,
+            contact: None,
+            tags: vec![],
+            homepage: None,
+            created_by: None,
+            registration: None,
+            archived: None,
+            image_url: None,
+            image_link_url: None,
+            time_zone: None,
+            recurrence: Some(format!("FREQ=WEEKLY;UNTIL={}", until).parse().unwrap()),
+            address_auto_filled: false,
+            publish_at: None,
+            created_at: None,
+            updated_at: None,
         };
         assert!(e.validate().is_err());
+        assert!(Event {
+            recurrence: Some("FREQ=WEEKLY;COUNT=3".parse().unwrap()),
+            ..e
+        }
+        .validate()
+        .is_ok());
     }
 
     #[test]
@@ -1,9 +1,234 @@
-use std::{collections::HashSet, env};
+use crate::{
+    core::{
+        entities::DailyQuotas,
+        prelude::ParameterError,
+        usecases::{CommentRules, EmailDomainPolicy},
+    },
+    infrastructure::db::tantivy::TextLanguage,
+};
+use std::{collections::HashSet, env, fs, path::PathBuf, str::FromStr, time::Duration};
 
 const DEFAULT_ACCEPTED_LICENSES: &str = "CC0-1.0,ODbL-1.0";
 const DEFAULT_DB_URL: &str = "openfair.db";
 const DB_CONNECTION_POOL_SIZE: u32 = 10;
 const DEFAULT_PROTECT_WITH_CAPTCHA: bool = false;
+const DEFAULT_AUTO_FILL_ADDRESS: bool = false;
+const DEFAULT_COUNT_VIEWS: bool = false;
+const DEFAULT_MIRROR_SYNC_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const DEFAULT_CORS_ALLOWED_METHODS: &str = "GET,POST,PUT,DELETE";
+const DEFAULT_MAX_JSON_BODY_SIZE: u64 = 512 * 1024;
+const DEFAULT_MAX_IMPORT_BODY_SIZE: u64 = 1024 * 1024;
+// Nominatim and the free OpenCage plan allow a single request per second
+const DEFAULT_GEOCODING_MIN_REQUEST_INTERVAL_MS: u64 = 1000;
+const DEFAULT_CONFIRMATION_ANSWER_PERIOD_DAYS: u64 = 30;
+const DEFAULT_USER_DELETION_GRACE_PERIOD_DAYS: u64 = 30;
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+const DEFAULT_EMAIL_OUTBOX_BATCH_SIZE: usize = 50;
+const DEFAULT_EMAIL_OUTBOX_INTERVAL: Duration = Duration::from_secs(60);
+const DEFAULT_INDEX_SNAPSHOT_INTERVAL_HOURS: u64 = 24;
+const DEFAULT_SEARCH_LIMIT: ResultLimit = ResultLimit {
+    default: 100,
+    max: 2000,
+};
+const DEFAULT_EVENTS_LIMIT: ResultLimit = ResultLimit {
+    default: 100,
+    max: 2000,
+};
+const DEFAULT_EXPORT_LIMIT: ResultLimit = ResultLimit {
+    default: 100_000,
+    max: 100_000,
+};
+const DEFAULT_CHANGES_LIMIT: ResultLimit = ResultLimit {
+    default: 1000,
+    max: 1000,
+};
+const DEFAULT_SUGGESTIONS_LIMIT: ResultLimit = ResultLimit {
+    default: 10,
+    max: 20,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeoCodingProvider {
+    OpenCage { api_key: String },
+    Nominatim { api_url: Option<String> },
+    Photon { api_url: Option<String> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeoCodingProviderCfg {
+    pub provider: GeoCodingProvider,
+    pub min_request_interval: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CorsOrigin {
+    Any,
+    /// e.g. `https://kartevonmorgen.org`
+    Exact(String),
+    /// e.g. `https://*.kartevonmorgen.org`
+    Subdomains {
+        scheme: String,
+        domain: String,
+    },
+}
+
+impl FromStr for CorsOrigin {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().trim_end_matches('/').to_lowercase();
+        if s == "*" {
+            return Ok(Self::Any);
+        }
+        let (scheme, host) = match s.find("://") {
+            Some(idx) => (&s[..idx], &s[idx + 3..]),
+            None => return Err(format!("Missing scheme of origin '{}'", s)),
+        };
+        if host.is_empty() || host.contains('/') {
+            return Err(format!("Invalid host of origin '{}'", s));
+        }
+        if let Some(domain) = host.strip_prefix("*.") {
+            if domain.is_empty() || domain.contains('*') {
+                return Err(format!("Invalid wildcard origin '{}'", s));
+            }
+            return Ok(Self::Subdomains {
+                scheme: scheme.to_string(),
+                domain: domain.to_string(),
+            });
+        }
+        if host.contains('*') {
+            return Err(format!("Invalid wildcard origin '{}'", s));
+        }
+        Ok(Self::Exact(s))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorsCfg {
+    pub allowed_origins: Vec<CorsOrigin>,
+    /// Methods that are allowed for all routes without a custom rule
+    pub allowed_methods: Vec<String>,
+    /// Methods that are allowed for all routes starting with the
+    /// given path, the longest matching path wins
+    pub route_methods: Vec<(String, Vec<String>)>,
+}
+
+impl Default for CorsCfg {
+    fn default() -> Self {
+        Self {
+            allowed_origins: vec![CorsOrigin::Any],
+            allowed_methods: parse_methods(DEFAULT_CORS_ALLOWED_METHODS),
+            route_methods: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BodySizeLimits {
+    /// Max. size of JSON request bodies in bytes
+    pub json: u64,
+    /// Max. size of import request bodies in bytes
+    pub import: u64,
+}
+
+impl Default for BodySizeLimits {
+    fn default() -> Self {
+        Self {
+            json: DEFAULT_MAX_JSON_BODY_SIZE,
+            import: DEFAULT_MAX_IMPORT_BODY_SIZE,
+        }
+    }
+}
+
+/// Queued e-mails are sent in batches to avoid exceeding
+/// the rate limits of the e-mail provider
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailOutboxCfg {
+    pub batch_size: usize,
+    /// Pause between two batches
+    pub interval: Duration,
+}
+
+impl Default for EmailOutboxCfg {
+    fn default() -> Self {
+        Self {
+            batch_size: DEFAULT_EMAIL_OUTBOX_BATCH_SIZE,
+            interval: DEFAULT_EMAIL_OUTBOX_INTERVAL,
+        }
+    }
+}
+
+/// The number of results that are returned if the
+/// client doesn't request a limit and the max. number
+/// of results that are returned at all
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResultLimit {
+    pub default: usize,
+    pub max: usize,
+}
+
+impl ResultLimit {
+    /// The limit that is actually applied to a request
+    pub fn effective(self, requested: Option<usize>) -> Result<usize, ParameterError> {
+        match requested {
+            Some(0) => Err(ParameterError::InvalidLimit),
+            Some(limit) if limit > self.max => {
+                info!(
+                    "Requested limit {} exceeds maximum limit {}",
+                    limit, self.max
+                );
+                Ok(self.max)
+            }
+            Some(limit) => Ok(limit),
+            None => Ok(self.default.min(self.max)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResultLimits {
+    /// `GET /search`
+    pub search: ResultLimit,
+    /// `GET /search/nearby`
+    pub nearby_search: ResultLimit,
+    /// `GET /events`
+    pub events: ResultLimit,
+    /// `GET /export/entries.csv` and `GET /export/events.csv`
+    pub export: ResultLimit,
+    /// `GET /changes`
+    pub changes: ResultLimit,
+    /// `GET /search/suggest`
+    pub suggestions: ResultLimit,
+}
+
+impl Default for ResultLimits {
+    fn default() -> Self {
+        Self {
+            search: DEFAULT_SEARCH_LIMIT,
+            nearby_search: DEFAULT_SEARCH_LIMIT,
+            events: DEFAULT_EVENTS_LIMIT,
+            export: DEFAULT_EXPORT_LIMIT,
+            changes: DEFAULT_CHANGES_LIMIT,
+            suggestions: DEFAULT_SUGGESTIONS_LIMIT,
+        }
+    }
+}
+
+/// A copy of the full-text search index that is restored
+/// if the index is corrupt, e.g. after a crash
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexSnapshotCfg {
+    pub dir: PathBuf,
+    pub interval: Duration,
+}
+
+/// Asks for the confirmation of places that have been
+/// neither updated nor confirmed for a long time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfirmationCampaignCfg {
+    pub stale_after: Duration,
+    /// Unanswered requests are flagged for scouts after this period
+    pub answer_period: Duration,
+}
 
 #[derive(Debug, Clone)]
 pub struct Cfg {
@@ -11,6 +236,58 @@ pub struct Cfg {
     pub db_url: String,
     pub db_connection_pool_size: u32,
     pub protect_with_captcha: bool,
+    /// Ordered list of providers, later providers are used as fallback
+    pub geocoding_providers: Vec<GeoCodingProviderCfg>,
+    /// Reverse geocode the coordinates of new entries without an address
+    pub auto_fill_address: bool,
+    /// Overpass API for importing places from OpenStreetMap
+    pub overpass_api_url: Option<String>,
+    /// Re-sync imported places with OpenStreetMap periodically if set
+    pub osm_resync_interval: Option<Duration>,
+    /// Run as a read-only mirror of the OpenFairDB API at this URL if set
+    pub mirror_upstream_url: Option<String>,
+    pub mirror_sync_interval: Duration,
+    /// Count the daily views of places and events
+    pub count_views: bool,
+    /// Allow cross-origin requests if set
+    pub cors: Option<CorsCfg>,
+    pub body_size_limits: BodySizeLimits,
+    pub result_limits: ResultLimits,
+    /// Default upload quotas of organizations that
+    /// don't have individual quotas
+    pub org_daily_quotas: DailyQuotas,
+    /// Disabled if not set
+    pub confirmation_campaign: Option<ConfirmationCampaignCfg>,
+    /// Disabled if not set
+    pub link_check_interval: Option<Duration>,
+    /// Disabled if not set
+    pub homepage_preview_interval: Option<Duration>,
+    pub email_outbox: EmailOutboxCfg,
+    /// Deleted users are anonymized after this period
+    pub user_deletion_grace_period: Duration,
+    pub registration_email_domains: EmailDomainPolicy,
+    /// New ratings with comments that violate these
+    /// rules are held back for moderation
+    pub comment_rules: CommentRules,
+    /// Disabled if not set
+    pub review_digest_interval: Option<Duration>,
+    /// Disabled if not set
+    pub index_snapshot: Option<IndexSnapshotCfg>,
+    /// Language of the titles and descriptions in the
+    /// full-text search index. Words are not stemmed if
+    /// not set. The index is rebuilt when changed.
+    pub index_language: Option<TextLanguage>,
+    /// Outside edits of moderated tags are summarized per
+    /// organization within this window if set instead of
+    /// sending an e-mail per edit
+    pub moderated_tags_notification_window: Option<Duration>,
+    /// Database for locating the IP addresses of clients that
+    /// search without a location. Disabled if not set.
+    pub ip_geolocation_db: Option<PathBuf>,
+    /// URL template of the map tiles on printed entries,
+    /// e.g. `https://tile.example.org/{z}/{x}/{y}.png`.
+    /// Disabled if not set.
+    pub print_map_tile_url: Option<String>,
 }
 
 impl Cfg {
@@ -25,10 +302,310 @@ impl Cfg {
         if let Ok(p) = env::var("PROTECT_WITH_CAPTCHA").map(|s| s.to_lowercase()) {
             cfg.protect_with_captcha = p == "true" || p == "1" || p == "yes";
         }
+        cfg.geocoding_providers = geocoding_providers_from_env();
+        if let Ok(a) = env::var("AUTO_FILL_ADDRESS").map(|s| s.to_lowercase()) {
+            cfg.auto_fill_address = a == "true" || a == "1" || a == "yes";
+        }
+        cfg.overpass_api_url = env::var("OVERPASS_API_URL").ok();
+        cfg.osm_resync_interval = env::var("OSM_RESYNC_INTERVAL_HOURS")
+            .ok()
+            .and_then(|hours| hours.parse::<u64>().ok())
+            .filter(|hours| *hours > 0)
+            .map(|hours| Duration::from_secs(hours * 3600));
+        cfg.link_check_interval = env::var("LINK_CHECK_INTERVAL_HOURS")
+            .ok()
+            .and_then(|hours| hours.parse::<u64>().ok())
+            .filter(|hours| *hours > 0)
+            .map(|hours| Duration::from_secs(hours * 3600));
+        cfg.homepage_preview_interval = env::var("HOMEPAGE_PREVIEW_INTERVAL_HOURS")
+            .ok()
+            .and_then(|hours| hours.parse::<u64>().ok())
+            .filter(|hours| *hours > 0)
+            .map(|hours| Duration::from_secs(hours * 3600));
+        cfg.mirror_upstream_url = env::var("MIRROR_UPSTREAM_URL").ok();
+        if let Some(minutes) = env::var("MIRROR_SYNC_INTERVAL_MINUTES")
+            .ok()
+            .and_then(|minutes| minutes.parse::<u64>().ok())
+            .filter(|minutes| *minutes > 0)
+        {
+            cfg.mirror_sync_interval = Duration::from_secs(minutes * 60);
+        }
+        if let Ok(c) = env::var("COUNT_VIEWS").map(|s| s.to_lowercase()) {
+            cfg.count_views = c == "true" || c == "1" || c == "yes";
+        }
+        cfg.cors = cors_from_env();
+        if let Some(size) = env::var("MAX_JSON_BODY_SIZE")
+            .ok()
+            .and_then(|size| size.parse().ok())
+        {
+            cfg.body_size_limits.json = size;
+        }
+        if let Some(size) = env::var("MAX_IMPORT_BODY_SIZE")
+            .ok()
+            .and_then(|size| size.parse().ok())
+        {
+            cfg.body_size_limits.import = size;
+        }
+        cfg.result_limits = ResultLimits {
+            search: result_limit_from_env("SEARCH", cfg.result_limits.search),
+            nearby_search: result_limit_from_env("NEARBY_SEARCH", cfg.result_limits.nearby_search),
+            events: result_limit_from_env("EVENTS", cfg.result_limits.events),
+            export: result_limit_from_env("EXPORT", cfg.result_limits.export),
+            changes: result_limit_from_env("CHANGES", cfg.result_limits.changes),
+            suggestions: result_limit_from_env("SUGGESTIONS", cfg.result_limits.suggestions),
+        };
+        let quota_from_env =
+            |key: &str| -> Option<u32> { env::var(key).ok().and_then(|quota| quota.parse().ok()) };
+        // Applies to both places and events unless overridden
+        let upload_quota = quota_from_env("ORG_DAILY_UPLOAD_QUOTA");
+        cfg.org_daily_quotas = DailyQuotas {
+            events: quota_from_env("ORG_DAILY_EVENT_QUOTA").or(upload_quota),
+            places: quota_from_env("ORG_DAILY_PLACE_QUOTA").or(upload_quota),
+        };
+        // A month is approximated by 30 days
+        cfg.confirmation_campaign = env::var("STALE_PLACE_MONTHS")
+            .ok()
+            .and_then(|months| months.parse::<u64>().ok())
+            .filter(|months| *months > 0)
+            .map(|months| ConfirmationCampaignCfg {
+                stale_after: Duration::from_secs(months * 30 * SECONDS_PER_DAY),
+                answer_period: Duration::from_secs(
+                    env::var("CONFIRMATION_ANSWER_PERIOD_DAYS")
+                        .ok()
+                        .and_then(|days| days.parse().ok())
+                        .unwrap_or(DEFAULT_CONFIRMATION_ANSWER_PERIOD_DAYS)
+                        * SECONDS_PER_DAY,
+                ),
+            });
+        if let Some(batch_size) = env::var("EMAIL_OUTBOX_BATCH_SIZE")
+            .ok()
+            .and_then(|size| size.parse::<usize>().ok())
+            .filter(|size| *size > 0)
+        {
+            cfg.email_outbox.batch_size = batch_size;
+        }
+        if let Some(seconds) = env::var("EMAIL_OUTBOX_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|seconds| seconds.parse::<u64>().ok())
+            .filter(|seconds| *seconds > 0)
+        {
+            cfg.email_outbox.interval = Duration::from_secs(seconds);
+        }
+        if let Some(days) = env::var("USER_DELETION_GRACE_PERIOD_DAYS")
+            .ok()
+            .and_then(|days| days.parse::<u64>().ok())
+        {
+            cfg.user_deletion_grace_period = Duration::from_secs(days * SECONDS_PER_DAY);
+        }
+        cfg.review_digest_interval = env::var("REVIEW_DIGEST_INTERVAL_DAYS")
+            .ok()
+            .and_then(|days| days.parse::<u64>().ok())
+            .filter(|days| *days > 0)
+            .map(|days| Duration::from_secs(days * SECONDS_PER_DAY));
+        cfg.index_snapshot = env::var("INDEX_SNAPSHOT_DIR")
+            .ok()
+            .filter(|dir| !dir.trim().is_empty())
+            .map(|dir| {
+                let hours = env::var("INDEX_SNAPSHOT_INTERVAL_HOURS")
+                    .ok()
+                    .and_then(|hours| hours.parse::<u64>().ok())
+                    .filter(|hours| *hours > 0)
+                    .unwrap_or(DEFAULT_INDEX_SNAPSHOT_INTERVAL_HOURS);
+                IndexSnapshotCfg {
+                    dir: dir.into(),
+                    interval: Duration::from_secs(hours * 3600),
+                }
+            });
+        cfg.index_language = env::var("INDEX_LANGUAGE")
+            .ok()
+            .filter(|language| !language.trim().is_empty())
+            .and_then(|language| match language.parse() {
+                Ok(language) => Some(language),
+                Err(_) => {
+                    warn!("Unknown index language '{}'", language);
+                    None
+                }
+            });
+        cfg.moderated_tags_notification_window = moderated_tags_notification_window_from_env();
+        cfg.ip_geolocation_db = env::var("IP_GEOLOCATION_DB")
+            .ok()
+            .filter(|path| !path.trim().is_empty())
+            .map(Into::into);
+        cfg.print_map_tile_url = env::var("PRINT_MAP_TILE_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty());
+        cfg.registration_email_domains = EmailDomainPolicy {
+            allowed: list_from_env("REGISTRATION_ALLOWED_EMAIL_DOMAINS"),
+            denied: list_from_env("REGISTRATION_DENIED_EMAIL_DOMAINS"),
+        };
+        cfg.comment_rules = CommentRules {
+            max_links: env::var("COMMENT_MAX_LINKS")
+                .ok()
+                .and_then(|links| links.parse().ok()),
+            banned_phrases: list_from_env("COMMENT_BANNED_PHRASES"),
+            min_account_age: env::var("COMMENT_MIN_ACCOUNT_AGE_DAYS")
+                .ok()
+                .and_then(|days| days.parse::<u64>().ok())
+                .filter(|days| *days > 0)
+                .map(|days| Duration::from_secs(days * SECONDS_PER_DAY)),
+        };
         cfg
     }
 }
 
+/// Reads the limits of an endpoint from `<name>_DEFAULT_LIMIT`
+/// and `<name>_MAX_LIMIT`, e.g. `SEARCH_MAX_LIMIT`.
+fn result_limit_from_env(name: &str, default: ResultLimit) -> ResultLimit {
+    let limit_from_env = |key: String| -> Option<usize> {
+        env::var(key)
+            .ok()
+            .and_then(|limit| limit.parse::<usize>().ok())
+            .filter(|limit| *limit > 0)
+    };
+    let max = limit_from_env(format!("{}_MAX_LIMIT", name)).unwrap_or(default.max);
+    let default = limit_from_env(format!("{}_DEFAULT_LIMIT", name))
+        .unwrap_or(default.default)
+        .min(max);
+    ResultLimit { default, max }
+}
+
+/// Reads a comma-separated list from the variable `name` and a
+/// list with one item per line from the file `<name>_FILE`, e.g.
+/// a list of disposable e-mail providers. All items are lowercase.
+fn list_from_env(name: &str) -> Vec<String> {
+    let mut items = env::var(name).ok().map(|i| parse_list(&i, ','));
+    if let Ok(path) = env::var(format!("{}_FILE", name)) {
+        match fs::read_to_string(&path) {
+            Ok(i) => items
+                .get_or_insert_with(Vec::new)
+                .extend(parse_list(&i, '\n')),
+            Err(err) => warn!("Unable to read {} from {}: {}", name, path, err),
+        }
+    }
+    items.unwrap_or_default()
+}
+
+fn parse_list(items: &str, separator: char) -> Vec<String> {
+    items
+        .split(separator)
+        .map(|i| i.trim().to_lowercase())
+        .filter(|i| !i.is_empty() && !i.starts_with('#'))
+        .collect()
+}
+
+fn parse_methods(methods: &str) -> Vec<String> {
+    methods
+        .split(',')
+        .map(|m| m.trim().to_uppercase())
+        .filter(|m| !m.is_empty())
+        .collect()
+}
+
+/// Reads the allowed origins from `CORS_ALLOWED_ORIGINS`, e.g.
+/// `https://kartevonmorgen.org,https://*.example.org`.
+///
+/// The allowed methods can be restricted with `CORS_ALLOWED_METHODS`
+/// and per route with `CORS_ROUTE_METHODS`, e.g.
+/// `/api/users=GET;/api/login=POST`.
+pub fn cors_from_env() -> Option<CorsCfg> {
+    let origins = env::var("CORS_ALLOWED_ORIGINS").ok()?;
+    let allowed_origins: Vec<_> = origins
+        .split(',')
+        .filter(|origin| !origin.trim().is_empty())
+        .filter_map(|origin| match origin.parse() {
+            Ok(origin) => Some(origin),
+            Err(err) => {
+                warn!("Ignoring CORS origin: {}", err);
+                None
+            }
+        })
+        .collect();
+    if allowed_origins.is_empty() {
+        warn!("No valid CORS origins configured");
+        return None;
+    }
+    let allowed_methods = env::var("CORS_ALLOWED_METHODS")
+        .map(|methods| parse_methods(&methods))
+        .unwrap_or_else(|_| parse_methods(DEFAULT_CORS_ALLOWED_METHODS));
+    let route_methods = env::var("CORS_ROUTE_METHODS")
+        .unwrap_or_default()
+        .split(';')
+        .filter(|rule| !rule.trim().is_empty())
+        .filter_map(|rule| {
+            let mut parts = rule.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(path), Some(methods)) if path.trim().starts_with('/') => {
+                    Some((path.trim().to_string(), parse_methods(methods)))
+                }
+                _ => {
+                    warn!("Ignoring invalid CORS route rule '{}'", rule);
+                    None
+                }
+            }
+        })
+        .collect();
+    Some(CorsCfg {
+        allowed_origins,
+        allowed_methods,
+        route_methods,
+    })
+}
+
+pub fn moderated_tags_notification_window_from_env() -> Option<Duration> {
+    env::var("MODERATED_TAGS_NOTIFICATION_WINDOW_MINUTES")
+        .ok()
+        .and_then(|minutes| minutes.parse::<u64>().ok())
+        .filter(|minutes| *minutes > 0)
+        .map(|minutes| Duration::from_secs(minutes * 60))
+}
+
+pub fn geocoding_providers_from_env() -> Vec<GeoCodingProviderCfg> {
+    let names = env::var("GEOCODING_PROVIDERS").unwrap_or_else(|_| {
+        // Backwards compatibility: Only OpenCage was supported before
+        if env::var("OPENCAGE_API_KEY").is_ok() {
+            "opencage".to_string()
+        } else {
+            warn!("No geocoding provider configured");
+            String::new()
+        }
+    });
+    names
+        .split(',')
+        .map(|name| name.trim().to_lowercase())
+        .filter(|name| !name.is_empty())
+        .filter_map(|name| {
+            let provider = match name.as_str() {
+                "opencage" => match env::var("OPENCAGE_API_KEY") {
+                    Ok(api_key) => GeoCodingProvider::OpenCage { api_key },
+                    Err(_) => {
+                        warn!("No OpenCage API key found");
+                        return None;
+                    }
+                },
+                "nominatim" => GeoCodingProvider::Nominatim {
+                    api_url: env::var("NOMINATIM_API_URL").ok(),
+                },
+                "photon" => GeoCodingProvider::Photon {
+                    api_url: env::var("PHOTON_API_URL").ok(),
+                },
+                _ => {
+                    warn!("Unknown geocoding provider '{}'", name);
+                    return None;
+                }
+            };
+            let min_request_interval =
+                env::var(format!("{}_MIN_REQUEST_INTERVAL_MS", name.to_uppercase()))
+                    .ok()
+                    .and_then(|ms| ms.parse().ok())
+                    .unwrap_or(DEFAULT_GEOCODING_MIN_REQUEST_INTERVAL_MS);
+            Some(GeoCodingProviderCfg {
+                provider,
+                min_request_interval: Duration::from_millis(min_request_interval),
+            })
+        })
+        .collect()
+}
+
 impl Default for Cfg {
     fn default() -> Self {
         let accepted_licenses = DEFAULT_ACCEPTED_LICENSES
@@ -43,6 +620,32 @@ impl Default for Cfg {
             db_url,
             db_connection_pool_size,
             protect_with_captcha,
+            geocoding_providers: vec![],
+            auto_fill_address: DEFAULT_AUTO_FILL_ADDRESS,
+            overpass_api_url: None,
+            osm_resync_interval: None,
+            mirror_upstream_url: None,
+            mirror_sync_interval: DEFAULT_MIRROR_SYNC_INTERVAL,
+            count_views: DEFAULT_COUNT_VIEWS,
+            cors: None,
+            body_size_limits: BodySizeLimits::default(),
+            result_limits: ResultLimits::default(),
+            org_daily_quotas: DailyQuotas::default(),
+            confirmation_campaign: None,
+            link_check_interval: None,
+            homepage_preview_interval: None,
+            email_outbox: EmailOutboxCfg::default(),
+            user_deletion_grace_period: Duration::from_secs(
+                DEFAULT_USER_DELETION_GRACE_PERIOD_DAYS * SECONDS_PER_DAY,
+            ),
+            registration_email_domains: EmailDomainPolicy::default(),
+            comment_rules: CommentRules::default(),
+            review_digest_interval: None,
+            index_snapshot: None,
+            index_language: None,
+            moderated_tags_notification_window: None,
+            ip_geolocation_db: None,
+            print_map_tile_url: None,
         }
     }
 }
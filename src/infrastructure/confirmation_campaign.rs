@@ -0,0 +1,32 @@
+//! Ask for the confirmation of places that have been
+//! neither updated nor confirmed for a long time.
+
+use super::{cfg::ConfirmationCampaignCfg, db::sqlite, flows::prelude as flows};
+use crate::core::prelude::*;
+use ofdb_core::gateways::notify::NotificationGateway;
+use std::{ops::Deref, thread, time::Duration};
+
+const INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+fn before(now: TimestampMs, period: Duration) -> TimestampMs {
+    TimestampMs::from_inner(now.into_inner() - period.as_millis() as i64)
+}
+
+pub fn spawn<N>(connections: sqlite::Connections, notify: N, cfg: ConfirmationCampaignCfg)
+where
+    N: Deref<Target = dyn NotificationGateway> + Send + 'static,
+{
+    thread::spawn(move || loop {
+        thread::sleep(INTERVAL);
+        let now = TimestampMs::now();
+        match flows::run_confirmation_campaign(
+            &connections,
+            &*notify,
+            before(now, cfg.stale_after),
+            before(now, cfg.answer_period),
+        ) {
+            Ok(report) => info!("Requested confirmations of stale places: {:?}", report),
+            Err(err) => warn!("Failed to request confirmations of stale places: {}", err),
+        }
+    });
+}
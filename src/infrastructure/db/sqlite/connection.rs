@@ -1,4 +1,7 @@
-use super::{util::load_url, *};
+use super::{
+    util::{load_recurrence, load_url},
+    *,
+};
 use crate::core::prelude::*;
 use anyhow::anyhow;
 use chrono::prelude::*;
@@ -67,6 +70,21 @@ fn load_place_revision_custom_links(
         .collect())
 }
 
+// Corrupt coordinates are loaded as an invalid position that
+// is excluded from all geo queries instead of failing.
+fn load_place_pos(place_id: &str, lat: f64, lon: f64) -> MapPoint {
+    MapPoint::try_from_lat_lng_deg(lat, lon).unwrap_or_else(|err| {
+        log::warn!(
+            "Invalid position of place '{}': lat = {}, lng = {} ({})",
+            place_id,
+            lat,
+            lon,
+            err
+        );
+        MapPoint::default()
+    })
+}
+
 fn load_place(
     conn: &SqliteConnection,
     place: models::JoinedPlaceRevision,
@@ -96,11 +114,12 @@ fn load_place(
         founded_on,
         image_url,
         image_link_url,
+        address_auto_filled,
         ..
     } = place;
 
     let location = Location {
-        pos: MapPoint::try_from_lat_lng_deg(lat, lon).unwrap_or_default(),
+        pos: load_place_pos(&place_id, lat, lon),
         address: Some(Address {
             street,
             zip,
@@ -137,6 +156,7 @@ fn load_place(
         title,
         description,
         location,
+        address_auto_filled,
         contact: Some(Contact {
             name: contact_name,
             email: email.map(Into::into),
@@ -156,6 +176,95 @@ fn load_place(
     Ok((place, load_review_status(current_status)?))
 }
 
+enum Drafts {
+    Exclude,
+    Include,
+    // Only the drafts that have been created by the given user
+    CreatedBy(i64),
+}
+
+// Only the current revisions of places with the given ids or all places
+fn load_current_places(
+    conn: &SqliteConnection,
+    place_ids: Option<&[&str]>,
+    drafts: Drafts,
+    pagination: Option<&Pagination>,
+) -> Result<Vec<(Place, ReviewStatus)>> {
+    use schema::place::dsl;
+    use schema::place_revision::dsl as rev_dsl;
+
+    let mut query = schema::place_revision::table
+        .inner_join(
+            schema::place::table.on(rev_dsl::parent_rowid
+                .eq(dsl::rowid)
+                .and(rev_dsl::rev.eq(dsl::current_rev))),
+        )
+        .select((
+            rev_dsl::rowid,
+            rev_dsl::rev,
+            rev_dsl::created_at,
+            rev_dsl::created_by,
+            rev_dsl::current_status,
+            rev_dsl::title,
+            rev_dsl::description,
+            rev_dsl::lat,
+            rev_dsl::lon,
+            rev_dsl::street,
+            rev_dsl::zip,
+            rev_dsl::city,
+            rev_dsl::country,
+            rev_dsl::state,
+            rev_dsl::contact_name,
+            rev_dsl::email,
+            rev_dsl::phone,
+            rev_dsl::homepage,
+            rev_dsl::opening_hours,
+            rev_dsl::founded_on,
+            rev_dsl::image_url,
+            rev_dsl::image_link_url,
+            rev_dsl::address_auto_filled,
+            dsl::id,
+            dsl::license,
+        ))
+        .into_boxed();
+    if let Some(place_ids) = place_ids {
+        query = query.filter(dsl::id.eq_any(place_ids));
+    } else if pagination.is_none() && !matches!(drafts, Drafts::CreatedBy(_)) {
+        warn!("Loading all entries at once");
+    }
+    let draft_status = ReviewStatusPrimitive::from(ReviewStatus::Draft);
+    match drafts {
+        Drafts::Exclude => {
+            query = query.filter(rev_dsl::current_status.ne(draft_status));
+        }
+        Drafts::Include => {}
+        Drafts::CreatedBy(user_id) => {
+            query = query
+                .filter(rev_dsl::current_status.eq(draft_status))
+                .filter(rev_dsl::created_by.eq(user_id));
+        }
+    }
+
+    if let Some(pagination) = pagination {
+        // Ordered by creation
+        query = query.order_by(dsl::rowid);
+        let offset = pagination.offset.unwrap_or(0);
+        if offset > 0 {
+            query = query.offset(offset as i64);
+        }
+        if let Some(limit) = pagination.limit {
+            query = query.limit(limit as i64);
+        }
+    }
+
+    let rows = query.load::<models::JoinedPlaceRevision>(conn)?;
+    let mut results = Vec::with_capacity(rows.len());
+    for row in rows {
+        results.push(load_place(conn, row)?);
+    }
+    Ok(results)
+}
+
 fn load_place_with_status_review(
     conn: &SqliteConnection,
     place_with_status_review: models::JoinedPlaceRevisionWithStatusReview,
@@ -182,6 +291,7 @@ fn load_place_with_status_review(
         founded_on,
         image_url,
         image_link_url,
+        address_auto_filled,
         place_id,
         place_license: license,
         review_created_at,
@@ -193,7 +303,7 @@ fn load_place_with_status_review(
     } = place_with_status_review;
 
     let location = Location {
-        pos: MapPoint::try_from_lat_lng_deg(lat, lon).unwrap_or_default(),
+        pos: load_place_pos(&place_id, lat, lon),
         address: Some(Address {
             street,
             zip,
@@ -257,6 +367,7 @@ fn load_place_with_status_review(
         title,
         description,
         location,
+        address_auto_filled,
         contact: Some(contact),
         opening_hours: opening_hours.map(Into::into),
         founded_on,
@@ -285,6 +396,183 @@ struct TagCountRow {
     count: i64,
 }
 
+#[derive(QueryableByName)]
+struct PlaceQualityIssueRow {
+    #[sql_type = "diesel::sql_types::Text"]
+    id: String,
+
+    #[sql_type = "diesel::sql_types::Text"]
+    title: String,
+
+    #[sql_type = "diesel::sql_types::Nullable<diesel::sql_types::Text>"]
+    country: Option<String>,
+
+    #[sql_type = "diesel::sql_types::Nullable<diesel::sql_types::Text>"]
+    state: Option<String>,
+}
+
+// Matches URLs that are neither HTTP nor HTTPS or contain whitespace
+fn invalid_url_sql(column: &str) -> String {
+    format!(
+        "({col} IS NOT NULL AND ((lower({col}) NOT LIKE 'http://%' AND lower({col}) NOT LIKE 'https://%') OR instr({col}, ' ') > 0))",
+        col = column
+    )
+}
+
+// Recomputes the aggregates of the unarchived ratings
+// of the given places or of all places
+fn update_rating_aggregates(
+    conn: &SqliteConnection,
+    place_rowids: Option<&[i64]>,
+) -> Result<usize> {
+    let filter = match place_rowids {
+        Some(rowids) if rowids.is_empty() => return Ok(0),
+        Some(rowids) => format!(
+            "WHERE p.rowid IN ({})",
+            rowids
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        None => String::new(),
+    };
+    let contexts: Vec<_> = [
+        RatingContext::Diversity,
+        RatingContext::Fairness,
+        RatingContext::Humanity,
+        RatingContext::Renewable,
+        RatingContext::Solidarity,
+        RatingContext::Transparency,
+    ]
+    .iter()
+    .map(|ctx| util::rating_context_to_string(*ctx))
+    .collect();
+    let columns = contexts
+        .iter()
+        .map(|ctx| format!("{ctx}_sum, {ctx}_count", ctx = ctx))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let aggregates = contexts
+        .iter()
+        .map(|ctx| {
+            format!(
+                "coalesce(sum(CASE r.context WHEN '{ctx}' THEN r.value END), 0), \
+                 count(CASE r.context WHEN '{ctx}' THEN 1 END)",
+                ctx = ctx
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql = format!(
+        "INSERT OR REPLACE INTO place_rating_aggregates (place_rowid, {}) \
+         SELECT p.rowid, {} FROM place p \
+         LEFT JOIN place_rating r ON r.parent_rowid = p.rowid AND r.archived_at IS NULL \
+         {} GROUP BY p.rowid",
+        columns, aggregates, filter
+    );
+    Ok(diesel::dsl::sql_query(sql).execute(conn)?)
+}
+
+fn load_organization(conn: &SqliteConnection, org: models::Organization) -> Result<Organization> {
+    use schema::{org_tag_policy::dsl as policy_dsl, organization_tag::dsl as org_tag_dsl};
+
+    let models::Organization {
+        rowid,
+        id,
+        name,
+        api_token,
+        notification_email,
+        email_sender_name,
+        email_reply_to,
+        email_footer,
+    } = org;
+
+    let moderated_tags = org_tag_dsl::organization_tag
+        .left_join(
+            policy_dsl::org_tag_policy.on(policy_dsl::org_rowid
+                .eq(org_tag_dsl::org_rowid)
+                .and(policy_dsl::tag_label.eq(org_tag_dsl::tag_label))),
+        )
+        .select((
+            org_tag_dsl::org_rowid,
+            org_tag_dsl::tag_label,
+            org_tag_dsl::tag_allow_add,
+            org_tag_dsl::tag_allow_remove,
+            org_tag_dsl::require_clearance,
+            policy_dsl::policy.nullable(),
+        ))
+        .filter(org_tag_dsl::org_rowid.eq(rowid))
+        .load::<models::OrganizationTag>(conn)?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok(Organization {
+        id: id.into(),
+        name,
+        api_token,
+        moderated_tags,
+        notification_email,
+        email_branding: EmailBranding {
+            sender_name: email_sender_name,
+            reply_to: email_reply_to.map(Into::into),
+            footer: email_footer,
+        },
+    })
+}
+
+fn view_counter_entity_kind(entity: ViewedEntity) -> i16 {
+    match entity {
+        ViewedEntity::Place => 0,
+        ViewedEntity::Event => 1,
+    }
+}
+
+fn event_change_kind(kind: ChangeKind) -> i16 {
+    match kind {
+        ChangeKind::Created => 0,
+        ChangeKind::Updated => 1,
+        ChangeKind::Reviewed => 2,
+        ChangeKind::Archived => 3,
+        ChangeKind::Deleted => 4,
+    }
+}
+
+fn load_event_change_kind(kind: i16) -> Result<ChangeKind> {
+    match kind {
+        0 => Ok(ChangeKind::Created),
+        1 => Ok(ChangeKind::Updated),
+        2 => Ok(ChangeKind::Reviewed),
+        3 => Ok(ChangeKind::Archived),
+        4 => Ok(ChangeKind::Deleted),
+        _ => Err(RepoError::Other(anyhow!("Invalid change kind: {}", kind))),
+    }
+}
+
+fn record_event_change(
+    conn: &SqliteConnection,
+    event_uid: &str,
+    changed_by: Option<i64>,
+    kind: ChangeKind,
+) -> result::Result<(), DieselError> {
+    let new_change = models::NewEventChange {
+        event_uid,
+        changed_at: Timestamp::now().into_inner(),
+        changed_by,
+        kind: event_change_kind(kind),
+    };
+    diesel::insert_into(schema::event_changes::table)
+        .values(&new_change)
+        .execute(conn)?;
+    Ok(())
+}
+
+fn load_role(role: Option<i16>) -> Option<Role> {
+    use num_traits::FromPrimitive;
+    role.and_then(Role::from_i16)
+}
+
 fn resolve_organization_rowid(conn: &SqliteConnection, id: &Id) -> Result<i64> {
     use schema::organization::dsl;
     Ok(schema::organization::table
@@ -374,6 +662,7 @@ fn into_new_place_revision(
         title,
         description,
         location: Location { pos, address },
+        address_auto_filled,
         contact,
         opening_hours,
         founded_on,
@@ -458,12 +747,17 @@ fn into_new_place_revision(
         founded_on,
         image_url: image_url.map(Into::into),
         image_link_url: image_link_url.map(Into::into),
+        address_auto_filled,
     };
     Ok((place_id, new_place, tags, custom_links))
 }
 
 impl PlaceRepo for SqliteConnection {
     fn create_or_update_place(&self, place: Place) -> Result<()> {
+        self.create_or_update_place_with_comment(place, "created")
+    }
+
+    fn create_or_update_place_with_comment(&self, place: Place, comment: &str) -> Result<()> {
         let (_place_id, new_place, tags, custom_links) = into_new_place_revision(self, place)?;
         diesel::insert_into(schema::place_revision::table)
             .values(&new_place)
@@ -493,7 +787,7 @@ impl PlaceRepo for SqliteConnection {
             created_by: new_place.created_by,
             status: new_place.current_status,
             context: None,
-            comment: Some("created"),
+            comment: Some(comment),
         };
         diesel::insert_into(schema::place_revision_review::table)
             .values(new_review)
@@ -603,16 +897,47 @@ impl PlaceRepo for SqliteConnection {
         Ok(total_update_count)
     }
 
-    fn get_places(&self, place_ids: &[&str]) -> Result<Vec<(Place, ReviewStatus)>> {
+    fn get_places_by_ids(&self, place_ids: &[&str]) -> Result<Vec<(Place, ReviewStatus)>> {
+        if place_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        // TODO: Split loading into chunks of fixed size
+        info!("Loading multiple ({}) entries at once", place_ids.len());
+        load_current_places(self, Some(place_ids), Drafts::Exclude, None)
+    }
+
+    fn get_place_by_id(&self, place_id: &str) -> Result<(Place, ReviewStatus)> {
+        let places = load_current_places(self, Some(&[place_id][..]), Drafts::Include, None)?;
+        debug_assert!(places.len() <= 1);
+        places.into_iter().next().ok_or(RepoError::NotFound)
+    }
+
+    fn all_places(&self) -> Result<Vec<(Place, ReviewStatus)>> {
+        load_current_places(self, None, Drafts::Exclude, None)
+    }
+
+    fn list_places(&self, pagination: &Pagination) -> Result<Vec<(Place, ReviewStatus)>> {
+        load_current_places(self, None, Drafts::Exclude, Some(pagination))
+    }
+
+    fn drafts_created_by(&self, email: &str) -> Result<Vec<Place>> {
+        let user_id = resolve_user_created_by_email(self, email)?;
+        Ok(
+            load_current_places(self, None, Drafts::CreatedBy(user_id), None)?
+                .into_iter()
+                .map(|(place, _)| place)
+                .collect(),
+        )
+    }
+
+    fn all_places_as_of(&self, as_of: TimestampMs) -> Result<Vec<(Place, ReviewStatus)>> {
         use schema::place::dsl;
         use schema::place_revision::dsl as rev_dsl;
+        use schema::place_revision_review::dsl as review_dsl;
 
-        let mut query = schema::place_revision::table
-            .inner_join(
-                schema::place::table.on(rev_dsl::parent_rowid
-                    .eq(dsl::rowid)
-                    .and(rev_dsl::rev.eq(dsl::current_rev))),
-            )
+        let as_of = as_of.into_inner();
+        let rows = schema::place_revision::table
+            .inner_join(schema::place::table.on(rev_dsl::parent_rowid.eq(dsl::rowid)))
             .select((
                 rev_dsl::rowid,
                 rev_dsl::rev,
@@ -636,36 +961,42 @@ impl PlaceRepo for SqliteConnection {
                 rev_dsl::founded_on,
                 rev_dsl::image_url,
                 rev_dsl::image_link_url,
+                rev_dsl::address_auto_filled,
                 dsl::id,
                 dsl::license,
             ))
-            .into_boxed();
-        if place_ids.is_empty() {
-            warn!("Loading all entries at once");
-        } else {
-            // TODO: Split loading into chunks of fixed size
-            info!("Loading multiple ({}) entries at once", place_ids.len());
-            query = query.filter(dsl::id.eq_any(place_ids));
-        }
-
-        let rows = query.load::<models::JoinedPlaceRevision>(self)?;
-        let mut results = Vec::with_capacity(rows.len());
+            .filter(rev_dsl::created_at.le(as_of))
+            .order_by(rev_dsl::parent_rowid)
+            .then_order_by(rev_dsl::rev.desc())
+            .load::<models::JoinedPlaceRevision>(self)?;
+        let mut results = Vec::new();
+        let mut last_place_id = None;
         for row in rows {
-            results.push(load_place(self, row)?);
+            // Only the most recent revision of each place
+            if last_place_id.as_ref() == Some(&row.place_id) {
+                continue;
+            }
+            last_place_id = Some(row.place_id.clone());
+            let rev_rowid = row.id;
+            let (place, _) = load_place(self, row)?;
+            // The current status of the revision might have
+            // been changed by later reviews
+            let status = schema::place_revision_review::table
+                .select(review_dsl::status)
+                .filter(review_dsl::parent_rowid.eq(rev_rowid))
+                .filter(review_dsl::created_at.le(as_of))
+                .order_by(review_dsl::rev.desc())
+                .first::<ReviewStatusPrimitive>(self)
+                .optional()?;
+            let status = match status {
+                Some(status) => load_review_status(status)?,
+                None => ReviewStatus::Created,
+            };
+            results.push((place, status));
         }
         Ok(results)
     }
 
-    fn get_place(&self, place_id: &str) -> Result<(Place, ReviewStatus)> {
-        let places = self.get_places(&[place_id])?;
-        debug_assert!(places.len() <= 1);
-        places.into_iter().next().ok_or(RepoError::NotFound)
-    }
-
-    fn all_places(&self) -> Result<Vec<(Place, ReviewStatus)>> {
-        self.get_places(&[])
-    }
-
     fn recently_changed_places(
         &self,
         params: &RecentlyChangedEntriesParams,
@@ -707,6 +1038,7 @@ impl PlaceRepo for SqliteConnection {
                 rev_dsl::founded_on,
                 rev_dsl::image_url,
                 rev_dsl::image_link_url,
+                rev_dsl::address_auto_filled,
                 dsl::id,
                 dsl::license,
                 review_dsl::rev,
@@ -716,6 +1048,7 @@ impl PlaceRepo for SqliteConnection {
                 review_dsl::context,
                 review_dsl::comment,
             ))
+            .filter(rev_dsl::current_status.ne(ReviewStatusPrimitive::from(ReviewStatus::Draft)))
             .order_by(review_dsl::created_at.desc())
             .then_order_by(review_dsl::rev.desc()) // disambiguation of equal time stamps
             .into_boxed();
@@ -830,6 +1163,7 @@ impl PlaceRepo for SqliteConnection {
                 rev_dsl::founded_on,
                 rev_dsl::image_url,
                 rev_dsl::image_link_url,
+                rev_dsl::address_auto_filled,
                 dsl::id,
                 dsl::license,
             ))
@@ -928,6 +1262,7 @@ impl PlaceRepo for SqliteConnection {
                 rev_dsl::founded_on,
                 rev_dsl::image_url,
                 rev_dsl::image_link_url,
+                rev_dsl::address_auto_filled,
                 dsl::id,
                 dsl::license,
             ))
@@ -935,6 +1270,54 @@ impl PlaceRepo for SqliteConnection {
         let row = query.first::<models::JoinedPlaceRevision>(self)?;
         load_place(self, row)
     }
+
+    fn link_place_to_osm_node(&self, place_id: &str, osm_node_id: u64) -> Result<()> {
+        let place_rowid = resolve_place_rowid(self, &Id::from(place_id))?;
+        let insertable = models::NewPlaceOsmNode {
+            place_rowid,
+            osm_node_id: osm_node_id as i64,
+        };
+        diesel::insert_into(schema::place_osm_node::table)
+            .values(&insertable)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn last_confirmed_at(&self, id: &str) -> Result<Option<TimestampMs>> {
+        use schema::place::dsl;
+        use schema::place_revision_review::dsl as review_dsl;
+        Ok(schema::place_revision_review::table
+            .inner_join(schema::place_revision::table.inner_join(schema::place::table))
+            .select(diesel::dsl::max(review_dsl::created_at))
+            .filter(dsl::id.eq(id))
+            .filter(review_dsl::status.eq(ReviewStatusPrimitive::from(ReviewStatus::Confirmed)))
+            .first::<Option<i64>>(self)?
+            .map(TimestampMs::from_inner))
+    }
+
+    fn find_place_id_by_osm_node(&self, osm_node_id: u64) -> Result<Option<Id>> {
+        use schema::place::dsl;
+        use schema::place_osm_node::dsl as osm_dsl;
+        Ok(schema::place_osm_node::table
+            .inner_join(schema::place::table)
+            .select(dsl::id)
+            .filter(osm_dsl::osm_node_id.eq(osm_node_id as i64))
+            .first::<String>(self)
+            .optional()?
+            .map(Into::into))
+    }
+
+    fn all_osm_node_links(&self) -> Result<Vec<(Id, u64)>> {
+        use schema::place::dsl;
+        use schema::place_osm_node::dsl as osm_dsl;
+        Ok(schema::place_osm_node::table
+            .inner_join(schema::place::table)
+            .select((dsl::id, osm_dsl::osm_node_id))
+            .load::<(String, i64)>(self)?
+            .into_iter()
+            .map(|(place_id, osm_node_id)| (place_id.into(), osm_node_id as u64))
+            .collect())
+    }
 }
 
 fn into_new_event_with_tags(
@@ -955,6 +1338,10 @@ fn into_new_event_with_tags(
         archived,
         image_url,
         image_link_url,
+        time_zone,
+        recurrence,
+        address_auto_filled,
+        publish_at,
         tags,
         ..
     } = event;
@@ -1021,6 +1408,13 @@ fn into_new_event_with_tags(
             archived: archived.map(Timestamp::into_inner),
             image_url: image_url.map(Into::into),
             image_link_url: image_link_url.map(Into::into),
+            time_zone,
+            recurrence: recurrence.as_ref().map(ToString::to_string),
+            publish_at: publish_at.map(Timestamp::into_inner),
+            // Maintained by create_event() and update_event()
+            created_at: None,
+            updated_at: None,
+            address_auto_filled,
         },
         tags,
     ))
@@ -1036,7 +1430,10 @@ fn resolve_event_id(conn: &SqliteConnection, uid: &str) -> Result<i64> {
 
 impl EventGateway for SqliteConnection {
     fn create_event(&self, e: Event) -> Result<()> {
-        let (new_event, tags) = into_new_event_with_tags(self, e)?;
+        let (mut new_event, tags) = into_new_event_with_tags(self, e)?;
+        let now = Timestamp::now().into_inner();
+        new_event.created_at = Some(now);
+        new_event.updated_at = Some(now);
         self.transaction::<_, diesel::result::Error, _>(|| {
             // Insert event
             diesel::insert_into(schema::events::table)
@@ -1060,14 +1457,22 @@ impl EventGateway for SqliteConnection {
             diesel::insert_or_ignore_into(schema::event_tags::table)
                 .values(&tags)
                 .execute(self)?;
-            Ok(())
+            record_event_change(
+                self,
+                &new_event.uid,
+                new_event.created_by,
+                ChangeKind::Created,
+            )
         })?;
         Ok(())
     }
 
     fn update_event(&self, event: &Event) -> Result<()> {
         let id = resolve_event_id(self, event.id.as_ref())?;
-        let (new_event, new_tags) = into_new_event_with_tags(self, event.clone())?;
+        let (mut new_event, new_tags) = into_new_event_with_tags(self, event.clone())?;
+        // The creation time stamp is preserved, because None values
+        // are skipped when updating the event.
+        new_event.updated_at = Some(Timestamp::now().into_inner());
         self.transaction::<_, diesel::result::Error, _>(|| {
             use schema::event_tags::dsl as et_dsl;
             use schema::events::dsl as e_dsl;
@@ -1102,7 +1507,12 @@ impl EventGateway for SqliteConnection {
                     .values(&new_tags)
                     .execute(self)?;
             }
-            Ok(())
+            record_event_change(
+                self,
+                &new_event.uid,
+                new_event.created_by,
+                ChangeKind::Updated,
+            )
         })?;
         Ok(())
     }
@@ -1135,6 +1545,12 @@ impl EventGateway for SqliteConnection {
                 e_dsl::archived,
                 e_dsl::image_url,
                 e_dsl::image_link_url,
+                e_dsl::time_zone,
+                e_dsl::recurrence,
+                e_dsl::publish_at,
+                e_dsl::created_at,
+                e_dsl::updated_at,
+                e_dsl::address_auto_filled,
                 u_dsl::email.nullable(),
             ))
             .filter(e_dsl::uid.eq_any(ids))
@@ -1166,6 +1582,12 @@ impl EventGateway for SqliteConnection {
                 archived,
                 image_url,
                 image_link_url,
+                time_zone,
+                recurrence,
+                publish_at,
+                created_at,
+                updated_at,
+                address_auto_filled,
                 created_by_email,
                 ..
             } = row;
@@ -1231,6 +1653,12 @@ impl EventGateway for SqliteConnection {
                 archived: archived.map(Timestamp::from_inner),
                 image_url: image_url.and_then(load_url),
                 image_link_url: image_link_url.and_then(load_url),
+                time_zone,
+                recurrence: recurrence.and_then(load_recurrence),
+                address_auto_filled,
+                publish_at: publish_at.map(Timestamp::from_inner),
+                created_at: created_at.map(Timestamp::from_inner),
+                updated_at: updated_at.map(Timestamp::from_inner),
             };
             events.push(event);
         }
@@ -1271,6 +1699,12 @@ impl EventGateway for SqliteConnection {
                 e_dsl::archived,
                 e_dsl::image_url,
                 e_dsl::image_link_url,
+                e_dsl::time_zone,
+                e_dsl::recurrence,
+                e_dsl::publish_at,
+                e_dsl::created_at,
+                e_dsl::updated_at,
+                e_dsl::address_auto_filled,
                 u_dsl::email.nullable(),
             ))
             .filter(e_dsl::archived.is_null())
@@ -1283,6 +1717,55 @@ impl EventGateway for SqliteConnection {
             .collect())
     }
 
+    fn list_events_chronologically(
+        &self,
+        published_until: Timestamp,
+        modified_since: Option<Timestamp>,
+        pagination: &Pagination,
+    ) -> Result<Vec<Event>> {
+        use schema::events::dsl;
+        let mut query = dsl::events
+            .select(dsl::uid)
+            .filter(dsl::archived.is_null())
+            .filter(
+                dsl::publish_at
+                    .is_null()
+                    .or(dsl::publish_at.le(published_until.into_inner())),
+            )
+            .order_by(dsl::start)
+            .then_order_by(dsl::id)
+            .into_boxed();
+        if let Some(since) = modified_since {
+            let since = since.into_inner();
+            query = query.filter(
+                dsl::updated_at
+                    .ge(since)
+                    .or(dsl::updated_at.is_null().and(dsl::created_at.ge(since))),
+            );
+        }
+        let offset = pagination.offset.unwrap_or(0);
+        if offset > 0 {
+            query = query.offset(offset as i64);
+        }
+        if let Some(limit) = pagination.limit {
+            query = query.limit(limit as i64);
+        }
+        let uids = query.load::<String>(self)?;
+        let uids: Vec<_> = uids.iter().map(String::as_str).collect();
+        self.get_events_chronologically(&uids)
+    }
+
+    fn events_published_between(&self, since: Timestamp, until: Timestamp) -> Result<Vec<Event>> {
+        use schema::events::dsl;
+        let uids = dsl::events
+            .select(dsl::uid)
+            .filter(dsl::publish_at.gt(since.into_inner()))
+            .filter(dsl::publish_at.le(until.into_inner()))
+            .load::<String>(self)?;
+        let uids: Vec<_> = uids.iter().map(String::as_str).collect();
+        self.get_events_chronologically(&uids)
+    }
+
     fn count_events(&self) -> Result<usize> {
         use schema::events::dsl;
         Ok(dsl::events
@@ -1320,8 +1803,13 @@ impl EventGateway for SqliteConnection {
             }
             debug_assert_eq!(id, *ids.first().unwrap());
         }
+        let uid = e_dsl::events
+            .select(e_dsl::uid)
+            .filter(e_dsl::id.eq(id))
+            .first::<String>(self)?;
         diesel::delete(et_dsl::event_tags.filter(et_dsl::event_id.eq(id))).execute(self)?;
         diesel::delete(e_dsl::events.filter(e_dsl::id.eq(id))).execute(self)?;
+        record_event_change(self, &uid, None, ChangeKind::Deleted)?;
         Ok(true)
     }
 
@@ -1358,9 +1846,10 @@ fn resolve_user_created_by_email(conn: &SqliteConnection, email: &str) -> Result
 
 impl UserGateway for SqliteConnection {
     fn create_user(&self, u: &User) -> Result<()> {
+        use schema::users::dsl;
         let new_user = models::NewUser::from(u);
         diesel::insert_into(schema::users::table)
-            .values(&new_user)
+            .values((&new_user, dsl::created_at.eq(Timestamp::now().into_inner())))
             .execute(self)?;
         Ok(())
     }
@@ -1374,10 +1863,236 @@ impl UserGateway for SqliteConnection {
         Ok(())
     }
 
-    fn delete_user_by_email(&self, email: &str) -> Result<()> {
-        use schema::users::dsl;
-        diesel::delete(dsl::users.filter(dsl::email.eq(email))).execute(self)?;
-        Ok(())
+    fn anonymize_user_by_email(&self, email: &str) -> Result<AnonymizedUserRecords> {
+        use schema::{
+            bbox_subscriptions::dsl as s_dsl, email_outbox::dsl as eo_dsl,
+            entity_watches::dsl as w_dsl, event_changes::dsl as ec_dsl, events::dsl as e_dsl,
+            organization_subscriptions::dsl as os_dsl, osm_node_proposal::dsl as onp_dsl,
+            pending_notifications::dsl as pn_dsl, pending_ratings::dsl as pr_dsl,
+            personal_api_tokens::dsl as pat_dsl, place_note::dsl as note_dsl,
+            place_rating::dsl as r_dsl, place_rating_comment::dsl as c_dsl,
+            place_revision::dsl as rev_dsl, place_revision_review::dsl as review_dsl,
+            user_tokens::dsl as t_dsl, users::dsl as u_dsl,
+        };
+        let user_id = resolve_user_created_by_email(self, email)?;
+
+        let place_revisions =
+            diesel::update(rev_dsl::place_revision.filter(rev_dsl::created_by.eq(user_id)))
+                .set(rev_dsl::created_by.eq(None::<i64>))
+                .execute(self)?;
+        let place_reviews = diesel::update(
+            review_dsl::place_revision_review.filter(review_dsl::created_by.eq(user_id)),
+        )
+        .set(review_dsl::created_by.eq(None::<i64>))
+        .execute(self)?;
+        diesel::update(note_dsl::place_note.filter(note_dsl::created_by.eq(user_id)))
+            .set(note_dsl::created_by.eq(None::<i64>))
+            .execute(self)?;
+        diesel::update(onp_dsl::osm_node_proposal.filter(onp_dsl::reviewed_by.eq(user_id)))
+            .set(onp_dsl::reviewed_by.eq(None::<i64>))
+            .execute(self)?;
+
+        // Ratings and comments might have been created and archived
+        // by the same user and should only be counted once
+        let ratings = r_dsl::place_rating
+            .select(diesel::dsl::count(r_dsl::rowid))
+            .filter(
+                r_dsl::created_by
+                    .eq(user_id)
+                    .or(r_dsl::archived_by.eq(user_id)),
+            )
+            .first::<i64>(self)? as usize;
+        diesel::update(r_dsl::place_rating.filter(r_dsl::created_by.eq(user_id)))
+            .set(r_dsl::created_by.eq(None::<i64>))
+            .execute(self)?;
+        diesel::update(r_dsl::place_rating.filter(r_dsl::archived_by.eq(user_id)))
+            .set(r_dsl::archived_by.eq(None::<i64>))
+            .execute(self)?;
+        let comments = c_dsl::place_rating_comment
+            .select(diesel::dsl::count(c_dsl::rowid))
+            .filter(
+                c_dsl::created_by
+                    .eq(user_id)
+                    .or(c_dsl::archived_by.eq(user_id)),
+            )
+            .first::<i64>(self)? as usize;
+        diesel::update(c_dsl::place_rating_comment.filter(c_dsl::created_by.eq(user_id)))
+            .set(c_dsl::created_by.eq(None::<i64>))
+            .execute(self)?;
+        diesel::update(c_dsl::place_rating_comment.filter(c_dsl::archived_by.eq(user_id)))
+            .set(c_dsl::archived_by.eq(None::<i64>))
+            .execute(self)?;
+
+        let events = diesel::update(e_dsl::events.filter(e_dsl::created_by.eq(user_id)))
+            .set(e_dsl::created_by.eq(None::<i64>))
+            .execute(self)?;
+        diesel::update(ec_dsl::event_changes.filter(ec_dsl::changed_by.eq(user_id)))
+            .set(ec_dsl::changed_by.eq(None::<i64>))
+            .execute(self)?;
+
+        diesel::delete(t_dsl::user_tokens.filter(t_dsl::user_id.eq(user_id))).execute(self)?;
+        diesel::delete(s_dsl::bbox_subscriptions.filter(s_dsl::user_id.eq(user_id)))
+            .execute(self)?;
+        diesel::delete(os_dsl::organization_subscriptions.filter(os_dsl::user_id.eq(user_id)))
+            .execute(self)?;
+        diesel::delete(w_dsl::entity_watches.filter(w_dsl::user_id.eq(user_id))).execute(self)?;
+        diesel::delete(pn_dsl::pending_notifications.filter(pn_dsl::user_id.eq(user_id)))
+            .execute(self)?;
+        diesel::delete(pat_dsl::personal_api_tokens.filter(pat_dsl::user_id.eq(user_id)))
+            .execute(self)?;
+        // Queued e-mails contain personal data of the recipient
+        diesel::delete(eo_dsl::email_outbox.filter(eo_dsl::recipient.eq(email))).execute(self)?;
+        diesel::update(pr_dsl::pending_ratings.filter(pr_dsl::created_by.eq(email)))
+            .set(pr_dsl::created_by.eq(None::<String>))
+            .execute(self)?;
+        diesel::delete(u_dsl::users.filter(u_dsl::id.eq(user_id))).execute(self)?;
+
+        Ok(AnonymizedUserRecords {
+            place_revisions,
+            place_reviews,
+            ratings,
+            comments,
+            events,
+        })
+    }
+
+    fn merge_users_by_email(
+        &self,
+        merged_email: &str,
+        surviving_email: &str,
+    ) -> Result<MergedUserRecords> {
+        use schema::{
+            bbox_subscriptions::dsl as s_dsl, entity_watches::dsl as w_dsl,
+            event_changes::dsl as ec_dsl, events::dsl as e_dsl,
+            organization_subscriptions::dsl as os_dsl, osm_node_proposal::dsl as onp_dsl,
+            pending_notifications::dsl as pn_dsl, pending_ratings::dsl as pr_dsl,
+            personal_api_tokens::dsl as pat_dsl, place_note::dsl as note_dsl,
+            place_rating::dsl as r_dsl, place_rating_comment::dsl as c_dsl,
+            place_revision::dsl as rev_dsl, place_revision_review::dsl as review_dsl,
+            user_tokens::dsl as t_dsl,
+        };
+        let merged_id = resolve_user_created_by_email(self, merged_email)?;
+        let surviving_id = resolve_user_created_by_email(self, surviving_email)?;
+
+        let place_revisions =
+            diesel::update(rev_dsl::place_revision.filter(rev_dsl::created_by.eq(merged_id)))
+                .set(rev_dsl::created_by.eq(surviving_id))
+                .execute(self)?;
+        let place_reviews = diesel::update(
+            review_dsl::place_revision_review.filter(review_dsl::created_by.eq(merged_id)),
+        )
+        .set(review_dsl::created_by.eq(surviving_id))
+        .execute(self)?;
+        diesel::update(note_dsl::place_note.filter(note_dsl::created_by.eq(merged_id)))
+            .set(note_dsl::created_by.eq(surviving_id))
+            .execute(self)?;
+        diesel::update(onp_dsl::osm_node_proposal.filter(onp_dsl::reviewed_by.eq(merged_id)))
+            .set(onp_dsl::reviewed_by.eq(surviving_id))
+            .execute(self)?;
+
+        // Ratings and comments might have been created and archived
+        // by the same user and should only be counted once
+        let ratings = r_dsl::place_rating
+            .select(diesel::dsl::count(r_dsl::rowid))
+            .filter(
+                r_dsl::created_by
+                    .eq(merged_id)
+                    .or(r_dsl::archived_by.eq(merged_id)),
+            )
+            .first::<i64>(self)? as usize;
+        diesel::update(r_dsl::place_rating.filter(r_dsl::created_by.eq(merged_id)))
+            .set(r_dsl::created_by.eq(surviving_id))
+            .execute(self)?;
+        diesel::update(r_dsl::place_rating.filter(r_dsl::archived_by.eq(merged_id)))
+            .set(r_dsl::archived_by.eq(surviving_id))
+            .execute(self)?;
+        let comments = c_dsl::place_rating_comment
+            .select(diesel::dsl::count(c_dsl::rowid))
+            .filter(
+                c_dsl::created_by
+                    .eq(merged_id)
+                    .or(c_dsl::archived_by.eq(merged_id)),
+            )
+            .first::<i64>(self)? as usize;
+        diesel::update(c_dsl::place_rating_comment.filter(c_dsl::created_by.eq(merged_id)))
+            .set(c_dsl::created_by.eq(surviving_id))
+            .execute(self)?;
+        diesel::update(c_dsl::place_rating_comment.filter(c_dsl::archived_by.eq(merged_id)))
+            .set(c_dsl::archived_by.eq(surviving_id))
+            .execute(self)?;
+        diesel::update(pr_dsl::pending_ratings.filter(pr_dsl::created_by.eq(merged_email)))
+            .set(pr_dsl::created_by.eq(surviving_email))
+            .execute(self)?;
+
+        let events = diesel::update(e_dsl::events.filter(e_dsl::created_by.eq(merged_id)))
+            .set(e_dsl::created_by.eq(surviving_id))
+            .execute(self)?;
+        diesel::update(ec_dsl::event_changes.filter(ec_dsl::changed_by.eq(merged_id)))
+            .set(ec_dsl::changed_by.eq(surviving_id))
+            .execute(self)?;
+
+        // Both users might have subscribed to the same organization
+        let subscribed_org_ids = os_dsl::organization_subscriptions
+            .select(os_dsl::org_rowid)
+            .filter(os_dsl::user_id.eq(surviving_id))
+            .load::<i64>(self)?;
+        diesel::delete(
+            os_dsl::organization_subscriptions
+                .filter(os_dsl::user_id.eq(merged_id))
+                .filter(os_dsl::org_rowid.eq_any(subscribed_org_ids)),
+        )
+        .execute(self)?;
+        let org_subscriptions = diesel::update(
+            os_dsl::organization_subscriptions.filter(os_dsl::user_id.eq(merged_id)),
+        )
+        .set(os_dsl::user_id.eq(surviving_id))
+        .execute(self)?;
+        let bbox_subscriptions =
+            diesel::update(s_dsl::bbox_subscriptions.filter(s_dsl::user_id.eq(merged_id)))
+                .set(s_dsl::user_id.eq(surviving_id))
+                .execute(self)?;
+
+        // Both users might watch the same entities
+        let watched_entity_ids = w_dsl::entity_watches
+            .select(w_dsl::entity_id)
+            .filter(w_dsl::user_id.eq(surviving_id))
+            .load::<String>(self)?;
+        diesel::delete(
+            w_dsl::entity_watches
+                .filter(w_dsl::user_id.eq(merged_id))
+                .filter(w_dsl::entity_id.eq_any(watched_entity_ids)),
+        )
+        .execute(self)?;
+        diesel::update(w_dsl::entity_watches.filter(w_dsl::user_id.eq(merged_id)))
+            .set(w_dsl::user_id.eq(surviving_id))
+            .execute(self)?;
+        diesel::update(pn_dsl::pending_notifications.filter(pn_dsl::user_id.eq(merged_id)))
+            .set(pn_dsl::user_id.eq(surviving_id))
+            .execute(self)?;
+        diesel::update(pat_dsl::personal_api_tokens.filter(pat_dsl::user_id.eq(merged_id)))
+            .set(pat_dsl::user_id.eq(surviving_id))
+            .execute(self)?;
+
+        // Each user has at most one token and the
+        // token of the surviving user takes precedence
+        let surviving_tokens = t_dsl::user_tokens
+            .select(diesel::dsl::count(t_dsl::id))
+            .filter(t_dsl::user_id.eq(surviving_id))
+            .first::<i64>(self)?;
+        if surviving_tokens == 0 {
+            diesel::update(t_dsl::user_tokens.filter(t_dsl::user_id.eq(merged_id)))
+                .set(t_dsl::user_id.eq(surviving_id))
+                .execute(self)?;
+        }
+
+        Ok(MergedUserRecords {
+            place_revisions,
+            place_reviews,
+            ratings,
+            comments,
+            events,
+            subscriptions: org_subscriptions + bbox_subscriptions,
+        })
     }
 
     fn get_user_by_email(&self, email: &str) -> Result<User> {
@@ -1412,23 +2127,97 @@ impl UserGateway for SqliteConnection {
             .select(diesel::dsl::count(dsl::id))
             .first::<i64>(self)? as usize)
     }
-}
 
-impl RatingRepository for SqliteConnection {
-    fn create_rating(&self, rating: Rating) -> Result<()> {
-        let Rating {
-            id,
-            place_id,
-            created_at,
-            archived_at,
-            title,
-            value,
-            context,
-            source,
-        } = rating;
-        let parent_rowid = resolve_place_rowid(self, &place_id)?;
-        let new_place_rating = models::NewPlaceRating {
-            id: id.into(),
+    fn wants_announcements(&self, email: &str) -> Result<bool> {
+        use schema::users::dsl;
+        Ok(dsl::users
+            .select(dsl::announcements)
+            .filter(dsl::email.eq(email))
+            .first(self)?)
+    }
+
+    fn set_wants_announcements(&self, email: &str, wants_announcements: bool) -> Result<()> {
+        use schema::users::dsl;
+        let count = diesel::update(dsl::users.filter(dsl::email.eq(email)))
+            .set(dsl::announcements.eq(wants_announcements))
+            .execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+
+    fn announcement_recipients(&self) -> Result<Vec<Email>> {
+        use schema::users::dsl;
+        Ok(dsl::users
+            .select(dsl::email)
+            .filter(dsl::email_confirmed.eq(true))
+            .filter(dsl::announcements.eq(true))
+            .order_by(dsl::id)
+            .load::<String>(self)?
+            .into_iter()
+            .map(Email::from)
+            .collect())
+    }
+
+    fn user_created_at(&self, email: &str) -> Result<Option<Timestamp>> {
+        use schema::users::dsl;
+        Ok(dsl::users
+            .select(dsl::created_at)
+            .filter(dsl::email.eq(email))
+            .first::<Option<i64>>(self)?
+            .map(Timestamp::from_inner))
+    }
+
+    fn user_deactivated_at(&self, email: &str) -> Result<Option<Timestamp>> {
+        use schema::users::dsl;
+        Ok(dsl::users
+            .select(dsl::deactivated_at)
+            .filter(dsl::email.eq(email))
+            .first::<Option<i64>>(self)?
+            .map(Timestamp::from_inner))
+    }
+
+    fn set_user_deactivated_at(
+        &self,
+        email: &str,
+        deactivated_at: Option<Timestamp>,
+    ) -> Result<()> {
+        use schema::users::dsl;
+        let count = diesel::update(dsl::users.filter(dsl::email.eq(email)))
+            .set(dsl::deactivated_at.eq(deactivated_at.map(Timestamp::into_inner)))
+            .execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+
+    fn users_deactivated_before(&self, deactivated_before: Timestamp) -> Result<Vec<String>> {
+        use schema::users::dsl;
+        Ok(dsl::users
+            .select(dsl::email)
+            .filter(dsl::deactivated_at.lt(deactivated_before.into_inner()))
+            .order_by(dsl::id)
+            .load::<String>(self)?)
+    }
+}
+
+impl RatingRepository for SqliteConnection {
+    fn create_rating(&self, rating: Rating) -> Result<()> {
+        let Rating {
+            id,
+            place_id,
+            created_at,
+            archived_at,
+            title,
+            value,
+            context,
+            source,
+        } = rating;
+        let parent_rowid = resolve_place_rowid(self, &place_id)?;
+        let new_place_rating = models::NewPlaceRating {
+            id: id.into(),
             parent_rowid,
             created_at: created_at.into_inner(),
             created_by: None,
@@ -1443,6 +2232,7 @@ impl RatingRepository for SqliteConnection {
             .values(&new_place_rating)
             .execute(self)?;
         debug_assert_eq!(1, _count);
+        update_rating_aggregates(self, Some(&[parent_rowid][..]))?;
         Ok(())
     }
 
@@ -1514,7 +2304,68 @@ impl RatingRepository for SqliteConnection {
             .load::<String>(self)?)
     }
 
-    fn archive_ratings(&self, ids: &[&str], activity: &Activity) -> Result<usize> {
+    fn count_ratings_and_comments_of_places(
+        &self,
+        place_ids: &[&str],
+    ) -> Result<Vec<(String, RatingCounts)>> {
+        use schema::place::dsl;
+        use schema::place_rating::dsl as rating_dsl;
+        use schema::place_rating_comment::dsl as comment_dsl;
+        // Stay below the maximum number of host parameters
+        // that are supported by SQLite
+        const CHUNK_SIZE: usize = 500;
+        let mut results: Vec<(String, RatingCounts)> = vec![];
+        for place_ids in place_ids.chunks(CHUNK_SIZE) {
+            let rating_counts = schema::place_rating::table
+                .inner_join(schema::place::table)
+                .filter(dsl::id.eq_any(place_ids))
+                .filter(rating_dsl::archived_at.is_null())
+                .group_by((dsl::id, rating_dsl::context))
+                .select((dsl::id, rating_dsl::context, diesel::dsl::count_star()))
+                .load::<(String, String, i64)>(self)?;
+            let comment_counts = schema::place_rating_comment::table
+                .inner_join(schema::place_rating::table.inner_join(schema::place::table))
+                .filter(dsl::id.eq_any(place_ids))
+                .filter(rating_dsl::archived_at.is_null())
+                .filter(comment_dsl::archived_at.is_null())
+                .group_by(dsl::id)
+                .select((dsl::id, diesel::dsl::count_star()))
+                .load::<(String, i64)>(self)?;
+            let first_index = results.len();
+            for (place_id, context, count) in rating_counts {
+                let context = util::rating_context_from_str(&context).map_err(|_| {
+                    RepoError::Other(anyhow!("Invalid rating context: {}", context))
+                })?;
+                let index = match results[first_index..]
+                    .iter()
+                    .position(|(id, _)| *id == place_id)
+                {
+                    Some(index) => first_index + index,
+                    None => {
+                        results.push((place_id, Default::default()));
+                        results.len() - 1
+                    }
+                };
+                results[index].1.add_ratings(context, count as u32);
+            }
+            for (place_id, count) in comment_counts {
+                if let Some((_, counts)) = results[first_index..]
+                    .iter_mut()
+                    .find(|(id, _)| *id == place_id)
+                {
+                    counts.comments = count as u32;
+                }
+            }
+        }
+        Ok(results)
+    }
+
+    fn archive_ratings(
+        &self,
+        ids: &[&str],
+        activity: &Activity,
+        reason: ArchiveReason,
+    ) -> Result<usize> {
         use schema::place_rating::dsl;
         let archived_at = Some(activity.at.into_inner());
         let archived_by = if let Some(ref email) = activity.by {
@@ -1530,9 +2381,16 @@ impl RatingRepository for SqliteConnection {
         .set((
             dsl::archived_at.eq(archived_at),
             dsl::archived_by.eq(archived_by),
+            dsl::archived_reason.eq(util::archive_reason_into_i16(reason)),
         ))
         .execute(self)?;
         debug_assert!(count <= ids.len());
+        let place_rowids = schema::place_rating::table
+            .select(dsl::parent_rowid)
+            .filter(dsl::id.eq_any(ids))
+            .distinct()
+            .load::<i64>(self)?;
+        update_rating_aggregates(self, Some(&place_rowids[..]))?;
         Ok(count)
     }
 
@@ -1545,22 +2403,77 @@ impl RatingRepository for SqliteConnection {
         } else {
             None
         };
-        Ok(diesel::update(
+        let place_rowids = schema::place::table
+            .select(dsl::rowid)
+            .filter(dsl::id.eq_any(place_ids))
+            .load::<i64>(self)?;
+        let count = diesel::update(
             schema::place_rating::table
-                .filter(
-                    rating_dsl::parent_rowid.eq_any(
-                        schema::place::table
-                            .select(dsl::rowid)
-                            .filter(dsl::id.eq_any(place_ids)),
-                    ),
-                )
+                .filter(rating_dsl::parent_rowid.eq_any(&place_rowids))
                 .filter(rating_dsl::archived_at.is_null()),
         )
         .set((
             rating_dsl::archived_at.eq(archived_at),
             rating_dsl::archived_by.eq(archived_by),
         ))
-        .execute(self)?)
+        .execute(self)?;
+        update_rating_aggregates(self, Some(&place_rowids[..]))?;
+        Ok(count)
+    }
+
+    fn load_archived_ratings_of_place(
+        &self,
+        place_id: &str,
+    ) -> Result<Vec<(Rating, Option<ArchiveReason>)>> {
+        use schema::place::dsl;
+        use schema::place_rating::dsl as rating_dsl;
+        Ok(schema::place_rating::table
+            .inner_join(schema::place::table)
+            .select((
+                (
+                    rating_dsl::rowid,
+                    rating_dsl::created_at,
+                    rating_dsl::created_by,
+                    rating_dsl::archived_at,
+                    rating_dsl::archived_by,
+                    rating_dsl::id,
+                    rating_dsl::title,
+                    rating_dsl::value,
+                    rating_dsl::context,
+                    rating_dsl::source,
+                    dsl::id,
+                ),
+                rating_dsl::archived_reason,
+            ))
+            .filter(dsl::id.eq(place_id))
+            .filter(rating_dsl::archived_at.is_not_null())
+            .order_by(rating_dsl::archived_at.desc())
+            .load::<(models::PlaceRating, Option<i16>)>(self)?
+            .into_iter()
+            .map(|(rating, reason)| {
+                (
+                    rating.into(),
+                    reason.and_then(util::archive_reason_from_i16),
+                )
+            })
+            .collect())
+    }
+
+    fn count_archived_ratings_by_reason(&self) -> Result<Vec<(Option<ArchiveReason>, usize)>> {
+        use schema::place_rating::dsl;
+        Ok(schema::place_rating::table
+            .filter(dsl::archived_at.is_not_null())
+            .group_by(dsl::archived_reason)
+            .select((dsl::archived_reason, diesel::dsl::count_star()))
+            .load::<(Option<i16>, i64)>(self)?
+            .into_iter()
+            .map(|(reason, count)| {
+                (
+                    reason.and_then(util::archive_reason_from_i16),
+                    count as usize,
+                )
+            })
+            .collect())
     }
 }
 
@@ -1645,7 +2558,12 @@ impl CommentRepository for SqliteConnection {
             .collect())
     }
 
-    fn archive_comments(&self, ids: &[&str], activity: &Activity) -> Result<usize> {
+    fn archive_comments(
+        &self,
+        ids: &[&str],
+        activity: &Activity,
+        reason: ArchiveReason,
+    ) -> Result<usize> {
         use schema::place_rating_comment::dsl;
         let archived_at = Some(activity.at.into_inner());
         let archived_by = if let Some(ref email) = activity.by {
@@ -1661,6 +2579,7 @@ impl CommentRepository for SqliteConnection {
         .set((
             dsl::archived_at.eq(archived_at),
             dsl::archived_by.eq(archived_by),
+            dsl::archived_reason.eq(util::archive_reason_into_i16(reason)),
         ))
         .execute(self)?;
         debug_assert!(count <= ids.len());
@@ -1671,6 +2590,7 @@ impl CommentRepository for SqliteConnection {
         &self,
         rating_ids: &[&str],
         activity: &Activity,
+        reason: ArchiveReason,
     ) -> Result<usize> {
         use schema::place_rating::dsl as rating_dsl;
         use schema::place_rating_comment::dsl as comment_dsl;
@@ -1694,6 +2614,7 @@ impl CommentRepository for SqliteConnection {
         .set((
             comment_dsl::archived_at.eq(archived_at),
             comment_dsl::archived_by.eq(archived_by),
+            comment_dsl::archived_reason.eq(util::archive_reason_into_i16(reason)),
         ))
         .execute(self)?)
     }
@@ -1733,6 +2654,59 @@ impl CommentRepository for SqliteConnection {
         .optional()?
         .unwrap_or_default())
     }
+
+    fn load_archived_comments_of_place(
+        &self,
+        place_id: &str,
+    ) -> Result<Vec<(Comment, Option<ArchiveReason>)>> {
+        use schema::place::dsl;
+        use schema::place_rating::dsl as rating_dsl;
+        use schema::place_rating_comment::dsl as comment_dsl;
+        Ok(schema::place_rating_comment::table
+            .inner_join(schema::place_rating::table.inner_join(schema::place::table))
+            .select((
+                (
+                    comment_dsl::rowid,
+                    comment_dsl::created_at,
+                    comment_dsl::created_by,
+                    comment_dsl::archived_at,
+                    comment_dsl::archived_by,
+                    comment_dsl::id,
+                    comment_dsl::text,
+                    rating_dsl::id,
+                ),
+                comment_dsl::archived_reason,
+            ))
+            .filter(dsl::id.eq(place_id))
+            .filter(comment_dsl::archived_at.is_not_null())
+            .order_by(comment_dsl::archived_at.desc())
+            .load::<(models::PlaceRatingComment, Option<i16>)>(self)?
+            .into_iter()
+            .map(|(comment, reason)| {
+                (
+                    comment.into(),
+                    reason.and_then(util::archive_reason_from_i16),
+                )
+            })
+            .collect())
+    }
+
+    fn count_archived_comments_by_reason(&self) -> Result<Vec<(Option<ArchiveReason>, usize)>> {
+        use schema::place_rating_comment::dsl;
+        Ok(schema::place_rating_comment::table
+            .filter(dsl::archived_at.is_not_null())
+            .group_by(dsl::archived_reason)
+            .select((dsl::archived_reason, diesel::dsl::count_star()))
+            .load::<(Option<i16>, i64)>(self)?
+            .into_iter()
+            .map(|(reason, count)| {
+                (
+                    reason.and_then(util::archive_reason_from_i16),
+                    count as usize,
+                )
+            })
+            .collect())
+    }
 }
 
 impl Db for SqliteConnection {
@@ -1764,6 +2738,7 @@ impl Db for SqliteConnection {
             south_west_lng,
             north_east_lat,
             north_east_lng,
+            digest: new.digest.map(util::notification_digest_into_i16),
         };
         diesel::insert_into(schema::bbox_subscriptions::table)
             .values(&insertable)
@@ -1784,6 +2759,7 @@ impl Db for SqliteConnection {
                 s_dsl::south_west_lng,
                 s_dsl::north_east_lat,
                 s_dsl::north_east_lng,
+                s_dsl::digest,
                 u_dsl::email,
             ))
             .load::<models::BboxSubscriptionEntity>(self)?
@@ -1805,6 +2781,7 @@ impl Db for SqliteConnection {
                 s_dsl::south_west_lng,
                 s_dsl::north_east_lat,
                 s_dsl::north_east_lng,
+                s_dsl::digest,
                 u_dsl::email,
             ))
             .load::<models::BboxSubscriptionEntity>(self)?
@@ -1822,6 +2799,74 @@ impl Db for SqliteConnection {
             .execute(self)?;
         Ok(())
     }
+
+    fn create_org_subscription(&self, new: &OrganizationSubscription) -> Result<()> {
+        let user_id = resolve_user_created_by_email(self, &new.user_email)?;
+        let org_rowid = resolve_organization_rowid(self, &new.org_id)?;
+        let insertable = models::NewOrganizationSubscription {
+            uid: new.id.as_ref(),
+            user_id,
+            org_rowid,
+        };
+        // Subscribing twice has no effect
+        diesel::insert_or_ignore_into(schema::organization_subscriptions::table)
+            .values(&insertable)
+            .execute(self)?;
+        Ok(())
+    }
+    fn all_org_subscriptions_by_org_ids(
+        &self,
+        org_ids: &[&Id],
+    ) -> Result<Vec<OrganizationSubscription>> {
+        use schema::organization::dsl as o_dsl;
+        use schema::organization_subscriptions::dsl as s_dsl;
+        use schema::users::dsl as u_dsl;
+        if org_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let org_ids: Vec<_> = org_ids.iter().map(|id| id.as_str()).collect();
+        Ok(s_dsl::organization_subscriptions
+            .inner_join(u_dsl::users)
+            .inner_join(o_dsl::organization)
+            .filter(o_dsl::id.eq_any(org_ids))
+            .select((s_dsl::uid, u_dsl::email, o_dsl::id))
+            .load::<models::OrganizationSubscriptionEntity>(self)?
+            .into_iter()
+            .map(OrganizationSubscription::from)
+            .collect())
+    }
+    fn all_org_subscriptions_by_email(&self, email: &str) -> Result<Vec<OrganizationSubscription>> {
+        use schema::organization::dsl as o_dsl;
+        use schema::organization_subscriptions::dsl as s_dsl;
+        use schema::users::dsl as u_dsl;
+        Ok(s_dsl::organization_subscriptions
+            .inner_join(u_dsl::users)
+            .inner_join(o_dsl::organization)
+            .filter(u_dsl::email.eq(email))
+            .select((s_dsl::uid, u_dsl::email, o_dsl::id))
+            .load::<models::OrganizationSubscriptionEntity>(self)?
+            .into_iter()
+            .map(OrganizationSubscription::from)
+            .collect())
+    }
+    fn delete_org_subscription(&self, email: &str, org_id: &Id) -> Result<()> {
+        use schema::organization::dsl as o_dsl;
+        use schema::organization_subscriptions::dsl as s_dsl;
+        use schema::users::dsl as u_dsl;
+        let users_id = u_dsl::users
+            .select(u_dsl::id)
+            .filter(u_dsl::email.eq(email));
+        let org_rowids = o_dsl::organization
+            .select(o_dsl::rowid)
+            .filter(o_dsl::id.eq(org_id.as_str()));
+        diesel::delete(
+            s_dsl::organization_subscriptions
+                .filter(s_dsl::user_id.eq_any(users_id))
+                .filter(s_dsl::org_rowid.eq_any(org_rowids)),
+        )
+        .execute(self)?;
+        Ok(())
+    }
     fn all_tags(&self) -> Result<Vec<Tag>> {
         use schema::tags::dsl::*;
         Ok(tags
@@ -1830,10 +2875,73 @@ impl Db for SqliteConnection {
             .map(Tag::from)
             .collect())
     }
+    fn list_tags(&self, filter: &TagFilter, pagination: &Pagination) -> Result<Vec<Tag>> {
+        use schema::tags::dsl::*;
+        let mut query = tags.order_by(id).into_boxed();
+        if let Some(prefix) = &filter.prefix {
+            query = query.filter(id.like(like_prefix_pattern(prefix)).escape('\\'));
+        }
+        if let Some(min_count) = filter.min_count {
+            query = query.filter(diesel::dsl::sql::<diesel::sql_types::Bool>(
+                &tag_min_count_condition(min_count),
+            ));
+        }
+        let offset = pagination.offset.unwrap_or(0);
+        if offset > 0 {
+            query = query.offset(offset as i64);
+        }
+        if let Some(limit) = pagination.limit {
+            query = query.limit(limit as i64);
+        }
+        Ok(query
+            .load::<models::Tag>(self)?
+            .into_iter()
+            .map(Tag::from)
+            .collect())
+    }
     fn count_tags(&self) -> Result<usize> {
         use schema::tags::dsl::*;
         Ok(tags.select(diesel::dsl::count(id)).first::<i64>(self)? as usize)
     }
+    fn count_filtered_tags(&self, filter: &TagFilter) -> Result<usize> {
+        use schema::tags::dsl::*;
+        let mut query = tags.select(diesel::dsl::count(id)).into_boxed();
+        if let Some(prefix) = &filter.prefix {
+            query = query.filter(id.like(like_prefix_pattern(prefix)).escape('\\'));
+        }
+        if let Some(min_count) = filter.min_count {
+            query = query.filter(diesel::dsl::sql::<diesel::sql_types::Bool>(
+                &tag_min_count_condition(min_count),
+            ));
+        }
+        Ok(query.first::<i64>(self)? as usize)
+    }
+}
+
+/// Matches all strings that start with the prefix, wildcards
+/// in the prefix are escaped with `\`.
+fn like_prefix_pattern(prefix: &str) -> String {
+    let mut pattern = String::with_capacity(prefix.len() + 1);
+    for c in prefix.chars() {
+        if c == '%' || c == '_' || c == '\\' {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+// TODO: Diesel 1.4.x does not support the HAVING clause
+// that is required to filter the aggregated column.
+fn tag_min_count_condition(min_count: u64) -> String {
+    format!(
+        "id IN (SELECT tag FROM place_revision_tag \
+         WHERE parent_rowid IN \
+         (SELECT rowid FROM place_revision WHERE (parent_rowid, rev) IN (SELECT rowid, current_rev FROM place) AND current_status > 0) \
+         GROUP BY tag HAVING COUNT(*)>={})",
+        min_count
+    )
 }
 
 impl OrganizationRepo for SqliteConnection {
@@ -1875,59 +2983,82 @@ impl OrganizationRepo for SqliteConnection {
         Ok(())
     }
 
-    fn get_org_by_api_token(&self, token: &str) -> Result<Organization> {
-        use schema::{organization::dsl as org_dsl, organization_tag::dsl as org_tag_dsl};
-
-        let models::Organization {
-            rowid,
-            id,
-            name,
-            api_token,
-        } = org_dsl::organization
-            .filter(org_dsl::api_token.eq(token))
+    fn get_org_by_id(&self, id: &Id) -> Result<Organization> {
+        use schema::organization::dsl;
+        let org = dsl::organization
+            .filter(dsl::id.eq(id.as_str()))
             .first(self)?;
+        load_organization(self, org)
+    }
 
-        let moderated_tags = org_tag_dsl::organization_tag
-            .filter(org_tag_dsl::org_rowid.eq(rowid))
-            .load::<models::OrganizationTag>(self)?
-            .into_iter()
-            .map(Into::into)
-            .collect();
+    fn get_org_by_api_token(&self, token: &str) -> Result<Organization> {
+        use schema::organization::dsl;
+        let org = dsl::organization
+            .filter(dsl::api_token.eq(token))
+            .first(self)?;
+        load_organization(self, org)
+    }
 
-        Ok(Organization {
-            id: id.into(),
-            name,
-            api_token,
-            moderated_tags,
-        })
+    fn replace_org_api_token(&mut self, org_id: &Id, api_token: &str) -> Result<()> {
+        use schema::organization::dsl;
+        let count = diesel::update(dsl::organization.filter(dsl::id.eq(org_id.as_str())))
+            .set(dsl::api_token.eq(api_token))
+            .execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
     }
 
     fn map_tag_to_clearance_org_id(&self, tag: &str) -> Result<Option<Id>> {
-        use schema::{organization::dsl, organization_tag::dsl as tag_dsl};
-        Ok(schema::organization::table
+        use schema::{
+            org_tag_policy::dsl as policy_dsl, organization::dsl, organization_tag::dsl as tag_dsl,
+        };
+        let moderated_tags = schema::organization::table
             .inner_join(schema::organization_tag::table)
-            .select(dsl::id)
+            .left_join(
+                policy_dsl::org_tag_policy.on(policy_dsl::org_rowid
+                    .eq(tag_dsl::org_rowid)
+                    .and(policy_dsl::tag_label.eq(tag_dsl::tag_label))),
+            )
+            .select((
+                dsl::id,
+                tag_dsl::tag_label,
+                tag_dsl::tag_allow_add,
+                tag_dsl::tag_allow_remove,
+                tag_dsl::require_clearance,
+                policy_dsl::policy.nullable(),
+            ))
             .filter(tag_dsl::tag_label.eq(tag))
-            .filter(tag_dsl::require_clearance.ne(0))
-            .first::<String>(self)
-            .optional()?
-            .map(Into::into))
+            .load::<models::OrganizationTagWithId>(self)?;
+        Ok(moderated_tags
+            .into_iter()
+            .map(<(Id, ModeratedTag)>::from)
+            .find(|(_, moderated_tag)| moderated_tag.require_clearance)
+            .map(|(org_id, _)| org_id))
     }
 
     fn get_moderated_tags_by_org(
         &self,
         excluded_org_id: Option<&Id>,
     ) -> Result<Vec<(Id, ModeratedTag)>> {
+        use schema::org_tag_policy::dsl as policy_dsl;
         use schema::organization::dsl as org_dsl;
         use schema::organization_tag::dsl as org_tag_dsl;
         let query = org_tag_dsl::organization_tag
             .inner_join(org_dsl::organization)
+            .left_join(
+                policy_dsl::org_tag_policy.on(policy_dsl::org_rowid
+                    .eq(org_tag_dsl::org_rowid)
+                    .and(policy_dsl::tag_label.eq(org_tag_dsl::tag_label))),
+            )
             .select((
                 org_dsl::id,
                 org_tag_dsl::tag_label,
                 org_tag_dsl::tag_allow_add,
                 org_tag_dsl::tag_allow_remove,
                 org_tag_dsl::require_clearance,
+                policy_dsl::policy.nullable(),
             ))
             .order_by(org_dsl::id);
         let moderated_tags = if let Some(excluded_org_id) = excluded_org_id {
@@ -1939,41 +3070,154 @@ impl OrganizationRepo for SqliteConnection {
         };
         Ok(moderated_tags.into_iter().map(Into::into).collect())
     }
-}
 
-impl PlaceClearanceRepo for SqliteConnection {
-    fn add_pending_clearance_for_places(
+    fn set_tag_moderation_policy(
         &self,
-        org_ids: &[Id],
-        pending_clearance: &PendingClearanceForPlace,
-    ) -> Result<usize> {
-        let PendingClearanceForPlace {
-            place_id,
-            created_at,
-            last_cleared_revision,
-        } = pending_clearance;
-        let place_rowid = resolve_place_rowid(self, place_id)?;
-        let created_at = created_at.into_inner();
-        let last_cleared_revision =
-            last_cleared_revision.map(|rev| RevisionValue::from(rev) as i64);
-        let mut insert_count = 0;
-        for org_id in org_ids {
-            let org_rowid = resolve_organization_rowid(self, org_id)?;
-            let insertable = models::NewPendingClearanceForPlace {
-                org_rowid,
-                place_rowid,
-                created_at,
-                last_cleared_revision,
-            };
-            insert_count +=
-                diesel::insert_or_ignore_into(schema::organization_place_clearance::table)
-                    .values(&insertable)
-                    .execute(self)?;
+        org_id: &Id,
+        tag: &str,
+        policy: TagModerationPolicy,
+    ) -> Result<()> {
+        use schema::organization_tag::dsl as org_tag_dsl;
+        let org_rowid = resolve_organization_rowid(self, org_id)?;
+        let moderated_tag_count = org_tag_dsl::organization_tag
+            .filter(org_tag_dsl::org_rowid.eq(org_rowid))
+            .filter(org_tag_dsl::tag_label.eq(tag))
+            .count()
+            .get_result::<i64>(self)?;
+        if moderated_tag_count == 0 {
+            return Err(RepoError::NotFound);
         }
-        Ok(insert_count)
+        let new_policy = models::NewOrgTagPolicy {
+            org_rowid,
+            tag_label: tag,
+            policy: policy.into(),
+        };
+        diesel::replace_into(schema::org_tag_policy::table)
+            .values(&new_policy)
+            .execute(self)?;
+        Ok(())
     }
 
-    fn count_pending_clearances_for_places(&self, org_id: &Id) -> Result<u64> {
+    fn get_tag_moderation_policy(
+        &self,
+        org_id: &Id,
+        tag: &str,
+    ) -> Result<Option<TagModerationPolicy>> {
+        use schema::org_tag_policy::dsl as policy_dsl;
+        let org_rowid = resolve_organization_rowid(self, org_id)?;
+        let policy = policy_dsl::org_tag_policy
+            .select(policy_dsl::policy)
+            .filter(policy_dsl::org_rowid.eq(org_rowid))
+            .filter(policy_dsl::tag_label.eq(tag))
+            .first::<i16>(self)
+            .optional()?;
+        Ok(policy.and_then(TagModerationPolicy::try_from))
+    }
+
+    fn add_moderated_tag(&self, org_id: &Id, tag: &ModeratedTag) -> Result<()> {
+        let org_rowid = resolve_organization_rowid(self, org_id)?;
+        let ModeratedTag {
+            label,
+            allow_add,
+            allow_remove,
+            require_clearance,
+        } = tag;
+        let org_tag = models::NewOrganizationTag {
+            org_rowid,
+            tag_label: label,
+            tag_allow_add: if *allow_add { 1 } else { 0 },
+            tag_allow_remove: if *allow_remove { 1 } else { 0 },
+            require_clearance: if *require_clearance { 1 } else { 0 },
+        };
+        diesel::insert_into(schema::organization_tag::table)
+            .values(&org_tag)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn remove_moderated_tag(&self, org_id: &Id, tag: &str) -> Result<()> {
+        use schema::{org_tag_policy::dsl as policy_dsl, organization_tag::dsl as org_tag_dsl};
+        let org_rowid = resolve_organization_rowid(self, org_id)?;
+        let rows_affected = diesel::delete(
+            org_tag_dsl::organization_tag
+                .filter(org_tag_dsl::org_rowid.eq(org_rowid))
+                .filter(org_tag_dsl::tag_label.eq(tag)),
+        )
+        .execute(self)?;
+        if rows_affected == 0 {
+            return Err(RepoError::NotFound);
+        }
+        diesel::delete(
+            policy_dsl::org_tag_policy
+                .filter(policy_dsl::org_rowid.eq(org_rowid))
+                .filter(policy_dsl::tag_label.eq(tag)),
+        )
+        .execute(self)?;
+        Ok(())
+    }
+
+    fn get_org_daily_quotas(&self, org_id: &Id) -> Result<DailyQuotas> {
+        use schema::org_daily_quotas::dsl;
+        let org_rowid = resolve_organization_rowid(self, org_id)?;
+        let quotas = dsl::org_daily_quotas
+            .filter(dsl::org_rowid.eq(org_rowid))
+            .first::<models::OrgDailyQuotas>(self)
+            .optional()?;
+        Ok(quotas
+            .map(|quotas| DailyQuotas {
+                events: quotas.events.map(|quota| quota as u32),
+                places: quotas.places.map(|quota| quota as u32),
+            })
+            .unwrap_or_default())
+    }
+
+    fn set_org_daily_quotas(&self, org_id: &Id, quotas: &DailyQuotas) -> Result<()> {
+        let org_rowid = resolve_organization_rowid(self, org_id)?;
+        let quotas = models::OrgDailyQuotas {
+            org_rowid,
+            events: quotas.events.map(i64::from),
+            places: quotas.places.map(i64::from),
+        };
+        diesel::replace_into(schema::org_daily_quotas::table)
+            .values(&quotas)
+            .execute(self)?;
+        Ok(())
+    }
+}
+
+impl PlaceClearanceRepo for SqliteConnection {
+    fn add_pending_clearance_for_places(
+        &self,
+        org_ids: &[Id],
+        pending_clearance: &PendingClearanceForPlace,
+    ) -> Result<usize> {
+        let PendingClearanceForPlace {
+            place_id,
+            created_at,
+            last_cleared_revision,
+        } = pending_clearance;
+        let place_rowid = resolve_place_rowid(self, place_id)?;
+        let created_at = created_at.into_inner();
+        let last_cleared_revision =
+            last_cleared_revision.map(|rev| RevisionValue::from(rev) as i64);
+        let mut insert_count = 0;
+        for org_id in org_ids {
+            let org_rowid = resolve_organization_rowid(self, org_id)?;
+            let insertable = models::NewPendingClearanceForPlace {
+                org_rowid,
+                place_rowid,
+                created_at,
+                last_cleared_revision,
+            };
+            insert_count +=
+                diesel::insert_or_ignore_into(schema::organization_place_clearance::table)
+                    .values(&insertable)
+                    .execute(self)?;
+        }
+        Ok(insert_count)
+    }
+
+    fn count_pending_clearances_for_places(&self, org_id: &Id) -> Result<u64> {
         use schema::organization::dsl as org_dsl;
         use schema::organization_place_clearance::dsl;
         Ok(schema::organization_place_clearance::table
@@ -2124,6 +3368,26 @@ impl PlaceClearanceRepo for SqliteConnection {
         .execute(self)?;
         Ok(delete_count as u64)
     }
+
+    fn remove_pending_clearances_for_places(
+        &self,
+        org_id: &Id,
+        place_ids: &[&str],
+    ) -> Result<usize> {
+        use schema::organization_place_clearance::dsl;
+        use schema::place::dsl as place_dsl;
+        let org_rowid = resolve_organization_rowid(self, org_id)?;
+        let place_rowids = schema::place::table
+            .select(place_dsl::rowid)
+            .filter(place_dsl::id.eq_any(place_ids))
+            .load::<i64>(self)?;
+        Ok(diesel::delete(
+            dsl::organization_place_clearance
+                .filter(dsl::org_rowid.eq(org_rowid))
+                .filter(dsl::place_rowid.eq_any(place_rowids)),
+        )
+        .execute(self)?)
+    }
 }
 
 impl UserTokenRepo for SqliteConnection {
@@ -2189,3 +3453,1439 @@ impl UserTokenRepo for SqliteConnection {
             .into())
     }
 }
+
+impl PlaceNoteRepo for SqliteConnection {
+    fn create_place_note(&self, note: &PlaceNote) -> Result<()> {
+        let place_rowid = resolve_place_rowid(self, &note.place_id)?;
+        let created_by = if let Some(ref email) = note.created.by {
+            Some(resolve_user_created_by_email(self, email.as_ref())?)
+        } else {
+            None
+        };
+        let new_note = models::NewPlaceNote {
+            uid: note.id.as_str(),
+            place_rowid,
+            parent_uid: note.parent_id.as_ref().map(Id::as_str),
+            created_at: note.created.at.into_inner(),
+            created_by,
+            text: &note.text,
+        };
+        diesel::insert_into(schema::place_note::table)
+            .values(&new_note)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn load_place_notes(&self, place_id: &str) -> Result<Vec<PlaceNote>> {
+        use schema::place::dsl;
+        use schema::place_note::dsl as note_dsl;
+        use schema::users::dsl as user_dsl;
+        Ok(schema::place_note::table
+            .inner_join(schema::place::table)
+            .left_outer_join(
+                schema::users::table.on(note_dsl::created_by.eq(user_dsl::id.nullable())),
+            )
+            .select((
+                note_dsl::uid,
+                note_dsl::parent_uid,
+                note_dsl::created_at,
+                note_dsl::text,
+                dsl::id,
+                user_dsl::email.nullable(),
+            ))
+            .filter(dsl::id.eq(place_id))
+            .order_by(note_dsl::created_at)
+            .then_order_by(note_dsl::rowid)
+            .load::<models::PlaceNoteEntity>(self)?
+            .into_iter()
+            .map(PlaceNote::from)
+            .collect())
+    }
+}
+
+fn load_osm_node_proposals(
+    conn: &SqliteConnection,
+    id: Option<&str>,
+    place_id: Option<&str>,
+    status: Option<i16>,
+    pagination: &Pagination,
+) -> Result<Vec<models::OsmNodeProposalEntity>> {
+    use schema::osm_node_proposal::dsl;
+    use schema::place::dsl as place_dsl;
+    use schema::users::dsl as u_dsl;
+    let mut query = schema::osm_node_proposal::table
+        .inner_join(schema::place::table)
+        .left_outer_join(schema::users::table.on(dsl::reviewed_by.eq(u_dsl::id.nullable())))
+        .select((
+            dsl::id,
+            dsl::osm_node_id,
+            dsl::place_rev,
+            dsl::created_at,
+            dsl::title,
+            dsl::lat,
+            dsl::lon,
+            dsl::street,
+            dsl::zip,
+            dsl::city,
+            dsl::country,
+            dsl::state,
+            dsl::status,
+            dsl::reviewed_at,
+            place_dsl::id,
+            u_dsl::email.nullable(),
+        ))
+        .order_by(dsl::created_at)
+        .then_order_by(dsl::rowid)
+        .into_boxed();
+    if let Some(id) = id {
+        query = query.filter(dsl::id.eq(id));
+    }
+    if let Some(place_id) = place_id {
+        query = query.filter(place_dsl::id.eq(place_id));
+    }
+    if let Some(status) = status {
+        query = query.filter(dsl::status.eq(status));
+    }
+
+    // Pagination
+    let offset = pagination.offset.unwrap_or(0);
+    if offset > 0 {
+        query = query.offset(offset as i64);
+    }
+    if let Some(limit) = pagination.limit {
+        query = query.limit(limit as i64);
+    }
+
+    Ok(query.load(conn)?)
+}
+
+impl OsmNodeProposalRepo for SqliteConnection {
+    fn add_osm_node_proposal(&self, proposal: &OsmNodeProposal) -> Result<()> {
+        let OsmNodeProposal {
+            id,
+            place_id,
+            osm_node_id,
+            place_revision,
+            created_at,
+            title,
+            pos,
+            address,
+            status,
+            reviewed,
+        } = proposal;
+        let place_rowid = resolve_place_rowid(self, place_id)?;
+        let reviewed_by = if let Some(email) = reviewed.as_ref().and_then(|r| r.by.as_ref()) {
+            Some(resolve_user_created_by_email(self, email.as_ref())?)
+        } else {
+            None
+        };
+        let address = address.as_ref();
+        let new_proposal = models::NewOsmNodeProposal {
+            id: id.as_str(),
+            place_rowid,
+            osm_node_id: *osm_node_id as i64,
+            place_rev: RevisionValue::from(*place_revision) as i64,
+            created_at: created_at.into_inner(),
+            title: title.as_deref(),
+            lat: pos.map(|pos| pos.lat().to_deg()),
+            lon: pos.map(|pos| pos.lng().to_deg()),
+            street: address.and_then(|a| a.street.as_deref()),
+            zip: address.and_then(|a| a.zip.as_deref()),
+            city: address.and_then(|a| a.city.as_deref()),
+            country: address.and_then(|a| a.country.as_deref()),
+            state: address.and_then(|a| a.state.as_deref()),
+            status: util::osm_node_proposal_status_into_i16(*status),
+            reviewed_at: reviewed.as_ref().map(|r| r.at.into_inner()),
+            reviewed_by,
+        };
+        diesel::insert_into(schema::osm_node_proposal::table)
+            .values(&new_proposal)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn get_osm_node_proposal(&self, id: &str) -> Result<OsmNodeProposal> {
+        load_osm_node_proposals(self, Some(id), None, None, &Default::default())?
+            .into_iter()
+            .next()
+            .map(Into::into)
+            .ok_or(RepoError::NotFound)
+    }
+
+    fn load_osm_node_proposals_of_place(&self, place_id: &str) -> Result<Vec<OsmNodeProposal>> {
+        Ok(
+            load_osm_node_proposals(self, None, Some(place_id), None, &Default::default())?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        )
+    }
+
+    fn list_pending_osm_node_proposals(
+        &self,
+        pagination: &Pagination,
+    ) -> Result<Vec<OsmNodeProposal>> {
+        let pending = util::osm_node_proposal_status_into_i16(OsmNodeProposalStatus::Pending);
+        Ok(
+            load_osm_node_proposals(self, None, None, Some(pending), pagination)?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        )
+    }
+
+    fn review_osm_node_proposal(
+        &self,
+        id: &str,
+        status: OsmNodeProposalStatus,
+        reviewed: &Activity,
+    ) -> Result<()> {
+        use schema::osm_node_proposal::dsl;
+        let reviewed_by = if let Some(ref email) = reviewed.by {
+            Some(resolve_user_created_by_email(self, email.as_ref())?)
+        } else {
+            None
+        };
+        let pending = util::osm_node_proposal_status_into_i16(OsmNodeProposalStatus::Pending);
+        let count = diesel::update(
+            dsl::osm_node_proposal
+                .filter(dsl::id.eq(id))
+                .filter(dsl::status.eq(pending)),
+        )
+        .set((
+            dsl::status.eq(util::osm_node_proposal_status_into_i16(status)),
+            dsl::reviewed_at.eq(reviewed.at.into_inner()),
+            dsl::reviewed_by.eq(reviewed_by),
+        ))
+        .execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+
+    fn delete_pending_osm_node_proposals_of_place(&self, place_id: &str) -> Result<usize> {
+        use schema::osm_node_proposal::dsl;
+        let place_rowid = resolve_place_rowid(self, &Id::from(place_id))?;
+        let pending = util::osm_node_proposal_status_into_i16(OsmNodeProposalStatus::Pending);
+        Ok(diesel::delete(
+            dsl::osm_node_proposal
+                .filter(dsl::place_rowid.eq(place_rowid))
+                .filter(dsl::status.eq(pending)),
+        )
+        .execute(self)?)
+    }
+}
+
+impl PlaceConfirmationRepo for SqliteConnection {
+    fn create_place_confirmation_request(&self, request: &PlaceConfirmationRequest) -> Result<()> {
+        let place_rowid = resolve_place_rowid(self, &request.place_id)?;
+        let nonce = request.nonce.to_string();
+        let new_request = models::NewPlaceConfirmationRequest {
+            place_rowid,
+            nonce: &nonce,
+            sent_to: request.sent_to.as_ref(),
+            sent_at: request.sent_at.into_inner(),
+            answered_at: request.answered_at.map(TimestampMs::into_inner),
+            flagged_at: request.flagged_at.map(TimestampMs::into_inner),
+        };
+        diesel::insert_into(schema::place_confirmation_request::table)
+            .values(&new_request)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn load_place_confirmation_requests(
+        &self,
+        place_id: &str,
+    ) -> Result<Vec<PlaceConfirmationRequest>> {
+        use schema::place::dsl;
+        use schema::place_confirmation_request::dsl as req_dsl;
+        Ok(schema::place_confirmation_request::table
+            .inner_join(schema::place::table)
+            .select((
+                req_dsl::nonce,
+                req_dsl::sent_to,
+                req_dsl::sent_at,
+                req_dsl::answered_at,
+                req_dsl::flagged_at,
+                dsl::id,
+            ))
+            .filter(dsl::id.eq(place_id))
+            .order_by(req_dsl::sent_at)
+            .then_order_by(req_dsl::rowid)
+            .load::<models::PlaceConfirmationRequestEntity>(self)?
+            .into_iter()
+            .map(PlaceConfirmationRequest::from)
+            .collect())
+    }
+
+    fn answer_place_confirmation_request(
+        &self,
+        place_id: &str,
+        nonce: &Nonce,
+        answered_at: TimestampMs,
+    ) -> Result<()> {
+        use schema::place_confirmation_request::dsl as req_dsl;
+        let place_rowid = resolve_place_rowid(self, &Id::from(place_id))?;
+        let count = diesel::update(
+            schema::place_confirmation_request::table
+                .filter(req_dsl::place_rowid.eq(place_rowid))
+                .filter(req_dsl::nonce.eq(nonce.to_string()))
+                .filter(req_dsl::answered_at.is_null()),
+        )
+        .set(req_dsl::answered_at.eq(answered_at.into_inner()))
+        .execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+
+    fn flag_unanswered_place_confirmation_requests(
+        &self,
+        sent_before: TimestampMs,
+        flagged_at: TimestampMs,
+    ) -> Result<usize> {
+        use schema::place_confirmation_request::dsl as req_dsl;
+        Ok(diesel::update(
+            schema::place_confirmation_request::table
+                .filter(req_dsl::sent_at.lt(sent_before.into_inner()))
+                .filter(req_dsl::answered_at.is_null())
+                .filter(req_dsl::flagged_at.is_null()),
+        )
+        .set(req_dsl::flagged_at.eq(flagged_at.into_inner()))
+        .execute(self)?)
+    }
+
+    fn load_flagged_place_confirmation_requests(&self) -> Result<Vec<PlaceConfirmationRequest>> {
+        use schema::place::dsl;
+        use schema::place_confirmation_request::dsl as req_dsl;
+        Ok(schema::place_confirmation_request::table
+            .inner_join(schema::place::table)
+            .select((
+                req_dsl::nonce,
+                req_dsl::sent_to,
+                req_dsl::sent_at,
+                req_dsl::answered_at,
+                req_dsl::flagged_at,
+                dsl::id,
+            ))
+            .filter(req_dsl::answered_at.is_null())
+            .filter(req_dsl::flagged_at.is_not_null())
+            .order_by(req_dsl::flagged_at)
+            .then_order_by(req_dsl::rowid)
+            .load::<models::PlaceConfirmationRequestEntity>(self)?
+            .into_iter()
+            .map(PlaceConfirmationRequest::from)
+            .collect())
+    }
+}
+
+impl TagSuggestionRepo for SqliteConnection {
+    fn replace_tag_cooccurrences(&self, cooccurrences: &[TagCooccurrence]) -> Result<()> {
+        let rows: Vec<_> = cooccurrences
+            .iter()
+            .map(|c| models::TagCooccurrence {
+                word: c.word.clone(),
+                tag: c.tag.clone(),
+                count: c.count as i64,
+                word_count: c.word_count as i64,
+            })
+            .collect();
+        self.transaction::<_, diesel::result::Error, _>(|| {
+            diesel::delete(schema::tag_cooccurrence::table).execute(self)?;
+            // Stay below the maximum number of SQL variables
+            for chunk in rows.chunks(200) {
+                diesel::insert_into(schema::tag_cooccurrence::table)
+                    .values(chunk)
+                    .execute(self)?;
+            }
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    fn load_tag_cooccurrences(&self, words: &[&str]) -> Result<Vec<TagCooccurrence>> {
+        use schema::tag_cooccurrence::dsl;
+        Ok(dsl::tag_cooccurrence
+            .filter(dsl::word.eq_any(words))
+            .load::<models::TagCooccurrence>(self)?
+            .into_iter()
+            .map(|c| TagCooccurrence {
+                word: c.word,
+                tag: c.tag,
+                count: c.count as u64,
+                word_count: c.word_count as u64,
+            })
+            .collect())
+    }
+}
+
+impl LinkCheckRepo for SqliteConnection {
+    fn record_link_check(
+        &self,
+        url: &str,
+        error: Option<&str>,
+        checked_at: Timestamp,
+    ) -> Result<()> {
+        use schema::link_check::dsl;
+        let checked_at = checked_at.into_inner();
+        self.transaction::<_, diesel::result::Error, _>(|| {
+            let new_check = models::NewLinkCheck {
+                url,
+                failures: 0,
+                checked_at,
+                last_error: None,
+            };
+            diesel::insert_or_ignore_into(schema::link_check::table)
+                .values(&new_check)
+                .execute(self)?;
+            let query = dsl::link_check.filter(dsl::url.eq(url));
+            if error.is_some() {
+                diesel::update(query)
+                    .set((
+                        dsl::failures.eq(dsl::failures + 1),
+                        dsl::checked_at.eq(checked_at),
+                        dsl::last_error.eq(error),
+                    ))
+                    .execute(self)?;
+            } else {
+                diesel::update(query)
+                    .set((
+                        dsl::failures.eq(0),
+                        dsl::checked_at.eq(checked_at),
+                        dsl::last_error.eq(None::<String>),
+                    ))
+                    .execute(self)?;
+            }
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    fn load_broken_links(&self, min_failures: u32) -> Result<Vec<LinkCheck>> {
+        use schema::link_check::dsl;
+        Ok(dsl::link_check
+            .filter(dsl::failures.ge(min_failures as i32))
+            .order_by(dsl::failures.desc())
+            .then_order_by(dsl::url)
+            .load::<models::LinkCheck>(self)?
+            .into_iter()
+            .map(|c| LinkCheck {
+                url: c.url,
+                failures: c.failures as u32,
+                checked_at: Timestamp::from_inner(c.checked_at),
+                last_error: c.last_error,
+            })
+            .collect())
+    }
+
+    fn filter_broken_links(&self, urls: &[&str], min_failures: u32) -> Result<Vec<String>> {
+        use schema::link_check::dsl;
+        if urls.is_empty() {
+            return Ok(vec![]);
+        }
+        Ok(dsl::link_check
+            .select(dsl::url)
+            .filter(dsl::url.eq_any(urls))
+            .filter(dsl::failures.ge(min_failures as i32))
+            .load(self)?)
+    }
+}
+
+impl DataQualityRepo for SqliteConnection {
+    fn find_places_with_quality_issues(
+        &self,
+        unreviewed_since: TimestampMs,
+        limit_per_issue: u64,
+    ) -> Result<Vec<PlaceWithQualityIssue>> {
+        let checks = vec![
+            (
+                PlaceQualityIssue::MissingAddress,
+                "(coalesce(trim(r.street), '') = '' OR coalesce(trim(r.city), '') = '')"
+                    .to_string(),
+            ),
+            (
+                PlaceQualityIssue::InvalidUrl,
+                format!(
+                    "({} OR {} OR {})",
+                    invalid_url_sql("r.homepage"),
+                    invalid_url_sql("r.image_url"),
+                    invalid_url_sql("r.image_link_url")
+                ),
+            ),
+            (
+                PlaceQualityIssue::ZeroCoordinates,
+                "(r.lat = 0 AND r.lon = 0)".to_string(),
+            ),
+            (
+                PlaceQualityIssue::NoTags,
+                format!(
+                    "NOT EXISTS (SELECT 1 FROM place_revision_tag t WHERE t.parent_rowid = r.rowid AND t.tag NOT IN ('{}', '{}', '{}'))",
+                    Category::TAG_NON_PROFIT,
+                    Category::TAG_COMMERCIAL,
+                    Category::TAG_EVENT
+                ),
+            ),
+            (
+                PlaceQualityIssue::Unreviewed,
+                format!(
+                    "(SELECT max(v.created_at) FROM place_revision_review v \
+                     JOIN place_revision pr ON v.parent_rowid = pr.rowid \
+                     WHERE pr.parent_rowid = p.rowid) < {}",
+                    unreviewed_since.into_inner()
+                ),
+            ),
+        ];
+        let mut results = vec![];
+        for (issue, condition) in checks {
+            let sql = format!(
+                "SELECT p.id AS id, r.title AS title, r.country AS country, r.state AS state \
+                 FROM place p \
+                 JOIN place_revision r ON r.parent_rowid = p.rowid AND r.rev = p.current_rev \
+                 WHERE r.current_status >= {} AND {} \
+                 ORDER BY r.title LIMIT {}",
+                ReviewStatusPrimitive::from(ReviewStatus::Created),
+                condition,
+                limit_per_issue
+            );
+            let rows = diesel::dsl::sql_query(sql).load::<PlaceQualityIssueRow>(self)?;
+            results.extend(rows.into_iter().map(
+                |PlaceQualityIssueRow {
+                     id,
+                     title,
+                     country,
+                     state,
+                 }| PlaceWithQualityIssue {
+                    id: id.into(),
+                    title,
+                    country,
+                    state,
+                    issue,
+                },
+            ));
+        }
+        Ok(results)
+    }
+}
+
+impl RatingAggregateRepo for SqliteConnection {
+    fn load_avg_ratings_of_all_places(&self) -> Result<Vec<(Id, AvgRatings)>> {
+        use schema::place::dsl;
+        use schema::place_rating_aggregates::dsl as a_dsl;
+        Ok(schema::place_rating_aggregates::table
+            .inner_join(schema::place::table)
+            .select((
+                dsl::id,
+                a_dsl::diversity_sum,
+                a_dsl::diversity_count,
+                a_dsl::fairness_sum,
+                a_dsl::fairness_count,
+                a_dsl::humanity_sum,
+                a_dsl::humanity_count,
+                a_dsl::renewable_sum,
+                a_dsl::renewable_count,
+                a_dsl::solidarity_sum,
+                a_dsl::solidarity_count,
+                a_dsl::transparency_sum,
+                a_dsl::transparency_count,
+            ))
+            .load::<models::PlaceRatingAggregate>(self)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    fn recompute_rating_aggregates(&self) -> Result<usize> {
+        update_rating_aggregates(self, None)
+    }
+}
+
+impl EmailOutboxRepo for SqliteConnection {
+    fn enqueue_emails(&self, emails: &[QueuedEmail]) -> Result<()> {
+        let new_emails: Vec<_> = emails
+            .iter()
+            .map(|email| models::QueuedEmail {
+                id: email.id.to_string(),
+                recipient: email.recipient.to_string(),
+                subject: email.subject.clone(),
+                body: email.body.clone(),
+                created_at: email.created_at.into_inner(),
+                sent_at: None,
+            })
+            .collect();
+        self.transaction::<_, diesel::result::Error, _>(|| {
+            // SQLite limits the number of variables per statement
+            for chunk in new_emails.chunks(100) {
+                diesel::insert_into(schema::email_outbox::table)
+                    .values(chunk)
+                    .execute(self)?;
+            }
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    fn load_unsent_emails(&self, limit: usize) -> Result<Vec<QueuedEmail>> {
+        use schema::email_outbox::dsl;
+        Ok(dsl::email_outbox
+            .filter(dsl::sent_at.is_null())
+            .order_by(dsl::created_at)
+            .limit(limit as i64)
+            .load::<models::QueuedEmail>(self)?
+            .into_iter()
+            .map(|email| QueuedEmail {
+                id: email.id.into(),
+                recipient: email.recipient.into(),
+                subject: email.subject,
+                body: email.body,
+                created_at: Timestamp::from_inner(email.created_at),
+            })
+            .collect())
+    }
+
+    fn mark_email_as_sent(&self, id: &Id, sent_at: Timestamp) -> Result<()> {
+        use schema::email_outbox::dsl;
+        let count = diesel::update(dsl::email_outbox.filter(dsl::id.eq(id.as_str())))
+            .set(dsl::sent_at.eq(Some(sent_at.into_inner())))
+            .execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+}
+
+impl NotificationConsentRepo for SqliteConnection {
+    fn request_notification_consent(
+        &self,
+        email_nonce: &EmailNonce,
+        requested_at: Timestamp,
+    ) -> Result<()> {
+        let new_consent = models::NewNotificationConsent {
+            email: &email_nonce.email,
+            nonce: email_nonce.nonce.to_string(),
+            requested_at: requested_at.into_inner(),
+            confirmed_at: None,
+        };
+        diesel::replace_into(schema::notification_consent::table)
+            .values(&new_consent)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn confirm_notification_consent(
+        &self,
+        email_nonce: &EmailNonce,
+        confirmed_at: Timestamp,
+    ) -> Result<()> {
+        use schema::notification_consent::dsl;
+        let count = diesel::update(
+            dsl::notification_consent
+                .filter(dsl::email.eq(&email_nonce.email))
+                .filter(dsl::nonce.eq(email_nonce.nonce.to_string())),
+        )
+        .set(dsl::confirmed_at.eq(Some(confirmed_at.into_inner())))
+        .execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+
+    fn has_notification_consent(&self, email: &str) -> Result<bool> {
+        Ok(!self.emails_with_notification_consent(&[email])?.is_empty())
+    }
+
+    fn emails_with_notification_consent(&self, emails: &[&str]) -> Result<Vec<String>> {
+        use schema::notification_consent::dsl;
+        if emails.is_empty() {
+            return Ok(vec![]);
+        }
+        Ok(dsl::notification_consent
+            .select(dsl::email)
+            .filter(dsl::email.eq_any(emails))
+            .filter(dsl::confirmed_at.is_not_null())
+            .load::<String>(self)?)
+    }
+}
+
+impl EntityWatchRepo for SqliteConnection {
+    fn watch_entity(&self, email: &str, entity_id: &str, created_at: Timestamp) -> Result<()> {
+        let user_id = resolve_user_created_by_email(self, email)?;
+        let new_watch = models::NewEntityWatch {
+            user_id,
+            entity_id,
+            created_at: created_at.into_inner(),
+        };
+        diesel::insert_or_ignore_into(schema::entity_watches::table)
+            .values(&new_watch)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn unwatch_entity(&self, email: &str, entity_id: &str) -> Result<()> {
+        use schema::{entity_watches::dsl as w_dsl, users::dsl as u_dsl};
+        let users_id = u_dsl::users
+            .select(u_dsl::id)
+            .filter(u_dsl::email.eq(email));
+        diesel::delete(
+            w_dsl::entity_watches
+                .filter(w_dsl::user_id.eq_any(users_id))
+                .filter(w_dsl::entity_id.eq(entity_id)),
+        )
+        .execute(self)?;
+        Ok(())
+    }
+
+    fn emails_watching_entity(&self, entity_id: &str) -> Result<Vec<String>> {
+        use schema::{entity_watches::dsl as w_dsl, users::dsl as u_dsl};
+        Ok(w_dsl::entity_watches
+            .inner_join(u_dsl::users)
+            .select(u_dsl::email)
+            .filter(w_dsl::entity_id.eq(entity_id))
+            .load::<String>(self)?)
+    }
+
+    fn entity_ids_watched_by_email(&self, email: &str) -> Result<Vec<String>> {
+        use schema::{entity_watches::dsl as w_dsl, users::dsl as u_dsl};
+        Ok(w_dsl::entity_watches
+            .inner_join(u_dsl::users)
+            .select(w_dsl::entity_id)
+            .filter(u_dsl::email.eq(email))
+            .order_by(w_dsl::created_at)
+            .load::<String>(self)?)
+    }
+}
+
+impl PendingNotificationRepo for SqliteConnection {
+    fn add_pending_notifications(&self, notifications: &[PendingNotification]) -> Result<()> {
+        for n in notifications {
+            let user_id = resolve_user_created_by_email(self, &n.user_email)?;
+            let new_notification = models::NewPendingNotification {
+                uid: n.id.as_str(),
+                user_id,
+                digest: util::notification_digest_into_i16(n.digest),
+                kind: util::notification_change_into_i16(n.change),
+                entity_id: n.entity_id.as_str(),
+                title: &n.title,
+                created_at: n.created_at.into_inner(),
+            };
+            diesel::insert_into(schema::pending_notifications::table)
+                .values(&new_notification)
+                .execute(self)?;
+        }
+        Ok(())
+    }
+
+    fn all_pending_notifications(&self) -> Result<Vec<PendingNotification>> {
+        use schema::{pending_notifications::dsl as n_dsl, users::dsl as u_dsl};
+        Ok(n_dsl::pending_notifications
+            .inner_join(u_dsl::users)
+            .select((
+                n_dsl::uid,
+                n_dsl::digest,
+                n_dsl::kind,
+                n_dsl::entity_id,
+                n_dsl::title,
+                n_dsl::created_at,
+                u_dsl::email,
+            ))
+            .order_by(n_dsl::created_at)
+            .then_order_by(n_dsl::id)
+            .load::<models::PendingNotificationEntity>(self)?
+            .into_iter()
+            .map(PendingNotification::from)
+            .collect())
+    }
+
+    fn delete_pending_notifications(&self, ids: &[&str]) -> Result<usize> {
+        use schema::pending_notifications::dsl;
+        Ok(
+            diesel::delete(dsl::pending_notifications.filter(dsl::uid.eq_any(ids)))
+                .execute(self)?,
+        )
+    }
+}
+
+impl PendingModeratedTagsNotificationRepo for SqliteConnection {
+    fn add_pending_moderated_tags_notifications(
+        &self,
+        notifications: &[PendingModeratedTagsNotification],
+    ) -> Result<()> {
+        for n in notifications {
+            let org_rowid = resolve_organization_rowid(self, &n.org_id)?;
+            let new_notification = models::NewPendingModeratedTagsNotification {
+                id: n.id.as_str(),
+                org_rowid,
+                place_id: n.place_id.as_str(),
+                place_title: &n.place_title,
+                added_tags: n.added_tags.join(","),
+                removed_tags: n.removed_tags.join(","),
+                created_at: n.created_at.into_inner(),
+            };
+            diesel::insert_into(schema::pending_moderated_tags_notifications::table)
+                .values(&new_notification)
+                .execute(self)?;
+        }
+        Ok(())
+    }
+
+    fn all_pending_moderated_tags_notifications(
+        &self,
+    ) -> Result<Vec<PendingModeratedTagsNotification>> {
+        use schema::{
+            organization::dsl as org_dsl, pending_moderated_tags_notifications::dsl as n_dsl,
+        };
+        Ok(n_dsl::pending_moderated_tags_notifications
+            .inner_join(org_dsl::organization)
+            .select((
+                n_dsl::id,
+                n_dsl::place_id,
+                n_dsl::place_title,
+                n_dsl::added_tags,
+                n_dsl::removed_tags,
+                n_dsl::created_at,
+                org_dsl::id,
+            ))
+            .order_by(n_dsl::created_at)
+            .then_order_by(n_dsl::rowid)
+            .load::<models::PendingModeratedTagsNotificationEntity>(self)?
+            .into_iter()
+            .map(PendingModeratedTagsNotification::from)
+            .collect())
+    }
+
+    fn delete_pending_moderated_tags_notifications(&self, ids: &[&str]) -> Result<usize> {
+        use schema::pending_moderated_tags_notifications::dsl;
+        Ok(
+            diesel::delete(dsl::pending_moderated_tags_notifications.filter(dsl::id.eq_any(ids)))
+                .execute(self)?,
+        )
+    }
+}
+
+impl PendingRatingRepo for SqliteConnection {
+    fn add_pending_rating(&self, pending_rating: &PendingRating) -> Result<()> {
+        let PendingRating {
+            rating,
+            comment,
+            created_by,
+            violations,
+        } = pending_rating;
+        debug_assert_eq!(rating.id, comment.rating_id);
+        let new_pending_rating = models::NewPendingRating {
+            id: rating.id.as_str(),
+            place_id: rating.place_id.as_str(),
+            created_at: rating.created_at.into_inner(),
+            created_by: created_by.as_deref(),
+            title: &rating.title,
+            value: i8::from(rating.value).into(),
+            context: util::rating_context_to_string(rating.context),
+            source: rating.source.as_deref(),
+            comment_id: comment.id.as_str(),
+            comment_text: &comment.text,
+            violations: violations
+                .iter()
+                .copied()
+                .map(util::comment_rule_violation_to_str)
+                .collect::<Vec<_>>()
+                .join(","),
+        };
+        diesel::insert_into(schema::pending_ratings::table)
+            .values(&new_pending_rating)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn list_pending_ratings(&self, pagination: &Pagination) -> Result<Vec<PendingRating>> {
+        use schema::pending_ratings::dsl;
+        let mut query = dsl::pending_ratings
+            .order_by(dsl::created_at)
+            .then_order_by(dsl::rowid)
+            .into_boxed();
+        let offset = pagination.offset.unwrap_or(0);
+        if offset > 0 {
+            query = query.offset(offset as i64);
+        }
+        if let Some(limit) = pagination.limit {
+            query = query.limit(limit as i64);
+        }
+        Ok(query
+            .load::<models::PendingRatingEntity>(self)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    fn load_pending_ratings(&self, ids: &[&str]) -> Result<Vec<PendingRating>> {
+        use schema::pending_ratings::dsl;
+        Ok(dsl::pending_ratings
+            .filter(dsl::id.eq_any(ids))
+            .order_by(dsl::created_at)
+            .then_order_by(dsl::rowid)
+            .load::<models::PendingRatingEntity>(self)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    fn delete_pending_ratings(&self, ids: &[&str]) -> Result<usize> {
+        use schema::pending_ratings::dsl;
+        Ok(diesel::delete(dsl::pending_ratings.filter(dsl::id.eq_any(ids))).execute(self)?)
+    }
+}
+
+impl PersonalApiTokenRepo for SqliteConnection {
+    fn create_personal_api_token(&self, token: &PersonalApiToken) -> Result<()> {
+        use num_traits::ToPrimitive;
+        let user_id = resolve_user_created_by_email(self, &token.user_email)?;
+        let new_token = models::NewPersonalApiToken {
+            id: token.id.as_str(),
+            user_id,
+            scope: token.scope.to_i16().unwrap_or_default(),
+            label: token.label.as_deref(),
+            secret_hash: &token.secret_hash,
+            created_at: token.created_at.into_inner(),
+        };
+        diesel::insert_into(schema::personal_api_tokens::table)
+            .values(&new_token)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn load_personal_api_token(&self, id: &str) -> Result<PersonalApiToken> {
+        use schema::{personal_api_tokens::dsl as t_dsl, users::dsl as u_dsl};
+        Ok(t_dsl::personal_api_tokens
+            .inner_join(u_dsl::users)
+            .select((
+                t_dsl::id,
+                t_dsl::scope,
+                t_dsl::label,
+                t_dsl::secret_hash,
+                t_dsl::created_at,
+                u_dsl::email,
+            ))
+            .filter(t_dsl::id.eq(id))
+            .first::<models::PersonalApiTokenEntity>(self)?
+            .into())
+    }
+
+    fn personal_api_tokens_of_user(&self, email: &str) -> Result<Vec<PersonalApiToken>> {
+        use schema::{personal_api_tokens::dsl as t_dsl, users::dsl as u_dsl};
+        Ok(t_dsl::personal_api_tokens
+            .inner_join(u_dsl::users)
+            .select((
+                t_dsl::id,
+                t_dsl::scope,
+                t_dsl::label,
+                t_dsl::secret_hash,
+                t_dsl::created_at,
+                u_dsl::email,
+            ))
+            .filter(u_dsl::email.eq(email))
+            .order_by(t_dsl::created_at)
+            .then_order_by(t_dsl::rowid)
+            .load::<models::PersonalApiTokenEntity>(self)?
+            .into_iter()
+            .map(PersonalApiToken::from)
+            .collect())
+    }
+
+    fn delete_personal_api_token(&self, email: &str, id: &str) -> Result<()> {
+        use schema::{personal_api_tokens::dsl as t_dsl, users::dsl as u_dsl};
+        let users_id = u_dsl::users
+            .select(u_dsl::id)
+            .filter(u_dsl::email.eq(email));
+        let count = diesel::delete(
+            t_dsl::personal_api_tokens
+                .filter(t_dsl::user_id.eq_any(users_id))
+                .filter(t_dsl::id.eq(id)),
+        )
+        .execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+}
+
+impl OrganizationApiTokenRepo for SqliteConnection {
+    fn create_org_api_token(&self, token: &OrganizationApiToken) -> Result<()> {
+        let org_rowid = resolve_organization_rowid(self, &token.org_id)?;
+        let new_token = models::NewOrganizationApiToken {
+            id: token.id.as_str(),
+            org_rowid,
+            label: token.label.as_deref(),
+            secret_hash: &token.secret_hash,
+            created_at: token.created_at.into_inner(),
+            expires_at: token.expires_at.map(Timestamp::into_inner),
+            last_used_at: token.last_used_at.map(Timestamp::into_inner),
+        };
+        diesel::insert_into(schema::organization_api_tokens::table)
+            .values(&new_token)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn load_org_api_token(&self, id: &str) -> Result<OrganizationApiToken> {
+        use schema::{organization::dsl as org_dsl, organization_api_tokens::dsl as t_dsl};
+        Ok(t_dsl::organization_api_tokens
+            .inner_join(org_dsl::organization)
+            .select((
+                t_dsl::id,
+                t_dsl::label,
+                t_dsl::secret_hash,
+                t_dsl::created_at,
+                t_dsl::expires_at,
+                t_dsl::last_used_at,
+                org_dsl::id,
+            ))
+            .filter(t_dsl::id.eq(id))
+            .first::<models::OrganizationApiTokenEntity>(self)?
+            .into())
+    }
+
+    fn org_api_tokens(&self, org_id: &Id) -> Result<Vec<OrganizationApiToken>> {
+        use schema::{organization::dsl as org_dsl, organization_api_tokens::dsl as t_dsl};
+        Ok(t_dsl::organization_api_tokens
+            .inner_join(org_dsl::organization)
+            .select((
+                t_dsl::id,
+                t_dsl::label,
+                t_dsl::secret_hash,
+                t_dsl::created_at,
+                t_dsl::expires_at,
+                t_dsl::last_used_at,
+                org_dsl::id,
+            ))
+            .filter(org_dsl::id.eq(org_id.as_str()))
+            .order_by(t_dsl::created_at)
+            .then_order_by(t_dsl::rowid)
+            .load::<models::OrganizationApiTokenEntity>(self)?
+            .into_iter()
+            .map(OrganizationApiToken::from)
+            .collect())
+    }
+
+    fn update_org_api_token_last_used(&self, id: &str, last_used_at: Timestamp) -> Result<()> {
+        use schema::organization_api_tokens::dsl as t_dsl;
+        let count = diesel::update(t_dsl::organization_api_tokens.filter(t_dsl::id.eq(id)))
+            .set(t_dsl::last_used_at.eq(last_used_at.into_inner()))
+            .execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+
+    fn delete_org_api_token(&self, org_id: &Id, id: &str) -> Result<()> {
+        use schema::organization_api_tokens::dsl as t_dsl;
+        let org_rowid = resolve_organization_rowid(self, org_id)?;
+        let count = diesel::delete(
+            t_dsl::organization_api_tokens
+                .filter(t_dsl::org_rowid.eq(org_rowid))
+                .filter(t_dsl::id.eq(id)),
+        )
+        .execute(self)?;
+        if count == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+}
+
+impl HomepagePreviewRepo for SqliteConnection {
+    fn store_homepage_preview(&self, preview: &HomepagePreview) -> Result<()> {
+        let new_preview = models::NewHomepagePreview {
+            url: &preview.url,
+            title: preview.title.as_deref(),
+            image_url: preview.image_url.as_deref(),
+            fetched_at: preview.fetched_at.into_inner(),
+        };
+        diesel::replace_into(schema::homepage_preview::table)
+            .values(&new_preview)
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn load_homepage_previews(&self, urls: &[&str]) -> Result<Vec<HomepagePreview>> {
+        use schema::homepage_preview::dsl;
+        if urls.is_empty() {
+            return Ok(vec![]);
+        }
+        Ok(dsl::homepage_preview
+            .filter(dsl::url.eq_any(urls))
+            .load::<models::HomepagePreview>(self)?
+            .into_iter()
+            .map(|p| HomepagePreview {
+                url: p.url,
+                title: p.title,
+                image_url: p.image_url,
+                fetched_at: Timestamp::from_inner(p.fetched_at),
+            })
+            .collect())
+    }
+}
+
+impl ChangeLogRepo for SqliteConnection {
+    fn load_changes(
+        &self,
+        since: Timestamp,
+        until: Timestamp,
+        limit: u64,
+    ) -> Result<Vec<ChangeRecord>> {
+        use schema::event_changes::dsl as ec_dsl;
+        use schema::events::dsl as e_dsl;
+        use schema::place::dsl;
+        use schema::place_rating::dsl as r_dsl;
+        use schema::place_rating_comment::dsl as c_dsl;
+        use schema::place_revision::dsl as rev_dsl;
+        use schema::place_revision_review::dsl as review_dsl;
+        use schema::users::dsl as u_dsl;
+
+        let limit = limit as i64;
+        // Places are revisioned with millisecond precision
+        let (since_ms, until_ms) = (
+            TimestampMs::from_seconds(since.into_seconds()).into_inner(),
+            TimestampMs::from_seconds(until.into_seconds()).into_inner(),
+        );
+        let from_ms = |at| Timestamp::from_seconds(TimestampMs::from_inner(at).into_seconds());
+        let (since, until) = (since.into_inner(), until.into_inner());
+        let mut changes = vec![];
+        let draft_status = ReviewStatusPrimitive::from(ReviewStatus::Draft);
+
+        let revisions = schema::place_revision::table
+            .inner_join(schema::place::table.on(rev_dsl::parent_rowid.eq(dsl::rowid)))
+            .left_outer_join(schema::users::table.on(rev_dsl::created_by.eq(u_dsl::id.nullable())))
+            .select((
+                dsl::id,
+                rev_dsl::rev,
+                rev_dsl::created_at,
+                u_dsl::role.nullable(),
+            ))
+            .filter(rev_dsl::created_at.ge(since_ms))
+            .filter(rev_dsl::created_at.lt(until_ms))
+            // Drafts appear as soon as they have been published
+            .filter(rev_dsl::current_status.ne(draft_status))
+            .order_by(rev_dsl::created_at)
+            .limit(limit)
+            .load::<(String, i64, i64, Option<i16>)>(self)?;
+        changes.extend(
+            revisions
+                .into_iter()
+                .map(|(id, rev, created_at, role)| ChangeRecord {
+                    entity: ChangedEntity::Place,
+                    id: id.into(),
+                    kind: if rev == 0 {
+                        ChangeKind::Created
+                    } else {
+                        ChangeKind::Updated
+                    },
+                    at: from_ms(created_at),
+                    actor_role: load_role(role),
+                }),
+        );
+
+        // The initial review of each revision is part of the revision
+        let reviews = schema::place_revision_review::table
+            .inner_join(
+                schema::place_revision::table.on(review_dsl::parent_rowid.eq(rev_dsl::rowid)),
+            )
+            .inner_join(schema::place::table.on(rev_dsl::parent_rowid.eq(dsl::rowid)))
+            .left_outer_join(
+                schema::users::table.on(review_dsl::created_by.eq(u_dsl::id.nullable())),
+            )
+            .select((
+                dsl::id,
+                review_dsl::status,
+                review_dsl::created_at,
+                u_dsl::role.nullable(),
+            ))
+            .filter(review_dsl::rev.gt(0))
+            .filter(review_dsl::status.ne(draft_status))
+            .filter(review_dsl::created_at.ge(since_ms))
+            .filter(review_dsl::created_at.lt(until_ms))
+            .order_by(review_dsl::created_at)
+            .limit(limit)
+            .load::<(String, i16, i64, Option<i16>)>(self)?;
+        for (id, status, created_at, role) in reviews {
+            let kind = match load_review_status(status)? {
+                ReviewStatus::Archived => ChangeKind::Archived,
+                _ => ChangeKind::Reviewed,
+            };
+            changes.push(ChangeRecord {
+                entity: ChangedEntity::Place,
+                id: id.into(),
+                kind,
+                at: from_ms(created_at),
+                actor_role: load_role(role),
+            });
+        }
+
+        let ratings = schema::place_rating::table
+            .left_outer_join(schema::users::table.on(r_dsl::created_by.eq(u_dsl::id.nullable())))
+            .select((r_dsl::id, r_dsl::created_at, u_dsl::role.nullable()))
+            .filter(r_dsl::created_at.ge(since))
+            .filter(r_dsl::created_at.lt(until))
+            .order_by(r_dsl::created_at)
+            .limit(limit)
+            .load::<(String, i64, Option<i16>)>(self)?;
+        let archived_ratings: Vec<_> = schema::place_rating::table
+            .left_outer_join(schema::users::table.on(r_dsl::archived_by.eq(u_dsl::id.nullable())))
+            .select((r_dsl::id, r_dsl::archived_at, u_dsl::role.nullable()))
+            .filter(r_dsl::archived_at.ge(since))
+            .filter(r_dsl::archived_at.lt(until))
+            .order_by(r_dsl::archived_at)
+            .limit(limit)
+            .load::<(String, Option<i64>, Option<i16>)>(self)?
+            .into_iter()
+            .filter_map(|(id, at, role)| at.map(|at| (id, at, role)))
+            .collect();
+        let comments = schema::place_rating_comment::table
+            .left_outer_join(schema::users::table.on(c_dsl::created_by.eq(u_dsl::id.nullable())))
+            .select((c_dsl::id, c_dsl::created_at, u_dsl::role.nullable()))
+            .filter(c_dsl::created_at.ge(since))
+            .filter(c_dsl::created_at.lt(until))
+            .order_by(c_dsl::created_at)
+            .limit(limit)
+            .load::<(String, i64, Option<i16>)>(self)?;
+        let archived_comments: Vec<_> = schema::place_rating_comment::table
+            .left_outer_join(schema::users::table.on(c_dsl::archived_by.eq(u_dsl::id.nullable())))
+            .select((c_dsl::id, c_dsl::archived_at, u_dsl::role.nullable()))
+            .filter(c_dsl::archived_at.ge(since))
+            .filter(c_dsl::archived_at.lt(until))
+            .order_by(c_dsl::archived_at)
+            .limit(limit)
+            .load::<(String, Option<i64>, Option<i16>)>(self)?
+            .into_iter()
+            .filter_map(|(id, at, role)| at.map(|at| (id, at, role)))
+            .collect();
+        for (entity, kind, rows) in vec![
+            (ChangedEntity::Rating, ChangeKind::Created, ratings),
+            (
+                ChangedEntity::Rating,
+                ChangeKind::Archived,
+                archived_ratings,
+            ),
+            (ChangedEntity::Comment, ChangeKind::Created, comments),
+            (
+                ChangedEntity::Comment,
+                ChangeKind::Archived,
+                archived_comments,
+            ),
+        ] {
+            changes.extend(rows.into_iter().map(|(id, at, role)| ChangeRecord {
+                entity,
+                id: id.into(),
+                kind,
+                at: Timestamp::from_inner(at),
+                actor_role: load_role(role),
+            }));
+        }
+
+        let event_changes = schema::event_changes::table
+            .left_outer_join(schema::users::table.on(ec_dsl::changed_by.eq(u_dsl::id.nullable())))
+            .left_outer_join(schema::events::table.on(ec_dsl::event_uid.eq(e_dsl::uid)))
+            .select((
+                ec_dsl::event_uid,
+                ec_dsl::kind,
+                ec_dsl::changed_at,
+                u_dsl::role.nullable(),
+                e_dsl::publish_at.nullable(),
+            ))
+            .filter(ec_dsl::changed_at.ge(since))
+            .filter(ec_dsl::changed_at.lt(until))
+            .order_by(ec_dsl::changed_at)
+            .limit(limit)
+            .load::<(String, i16, i64, Option<i16>, Option<i64>)>(self)?;
+        for (id, kind, changed_at, role, publish_at) in event_changes {
+            // Changes of scheduled events are hidden until they get
+            // published, see below
+            if publish_at.map(|at| changed_at < at).unwrap_or(false) {
+                continue;
+            }
+            changes.push(ChangeRecord {
+                entity: ChangedEntity::Event,
+                id: id.into(),
+                kind: load_event_change_kind(kind)?,
+                at: Timestamp::from_inner(changed_at),
+                actor_role: load_role(role),
+            });
+        }
+        // Scheduled events appear as created when they get published
+        let published_events = e_dsl::events
+            .select((e_dsl::uid, e_dsl::publish_at, e_dsl::created_at))
+            .filter(e_dsl::publish_at.ge(since))
+            .filter(e_dsl::publish_at.lt(until))
+            .filter(e_dsl::publish_at.le(Timestamp::now().into_inner()))
+            .order_by(e_dsl::publish_at)
+            .limit(limit)
+            .load::<(String, Option<i64>, Option<i64>)>(self)?;
+        changes.extend(
+            published_events
+                .into_iter()
+                .filter_map(|(id, publish_at, created_at)| {
+                    publish_at.map(|at| (id, at, created_at))
+                })
+                // Events that have not been scheduled are already
+                // listed with their creation
+                .filter(|(_, at, created_at)| {
+                    created_at.map_or(true, |created_at| created_at < *at)
+                })
+                .map(|(id, at, _)| ChangeRecord {
+                    entity: ChangedEntity::Event,
+                    id: id.into(),
+                    kind: ChangeKind::Created,
+                    at: Timestamp::from_inner(at),
+                    actor_role: None,
+                }),
+        );
+        // The user who archived an event is not recorded
+        let archived_events = e_dsl::events
+            .select((e_dsl::uid, e_dsl::archived, e_dsl::publish_at))
+            .filter(e_dsl::archived.ge(since))
+            .filter(e_dsl::archived.lt(until))
+            .order_by(e_dsl::archived)
+            .limit(limit)
+            .load::<(String, Option<i64>, Option<i64>)>(self)?;
+        changes.extend(
+            archived_events
+                .into_iter()
+                .filter_map(|(id, archived, publish_at)| {
+                    archived.map(|archived| (id, archived, publish_at))
+                })
+                // Scheduled events that have never been published
+                .filter(|(_, archived, publish_at)| publish_at.map_or(true, |at| *archived >= at))
+                .map(|(id, archived, _)| ChangeRecord {
+                    entity: ChangedEntity::Event,
+                    id: id.into(),
+                    kind: ChangeKind::Archived,
+                    at: Timestamp::from_inner(archived),
+                    actor_role: None,
+                }),
+        );
+
+        // Each query is limited separately
+        changes.sort_by_key(|c| c.at);
+        changes.truncate(limit as usize);
+        Ok(changes)
+    }
+}
+
+impl ClearanceLogRepo for SqliteConnection {
+    fn add_clearance_log_entries(&self, entries: &[ClearanceLogEntry]) -> Result<()> {
+        for entry in entries {
+            let org_rowid = resolve_organization_rowid(self, &entry.org_id)?;
+            let new_entry = models::NewClearanceLogEntry {
+                org_rowid,
+                place_id: entry.place_id.as_str(),
+                place_rev: RevisionValue::from(entry.place_revision) as i64,
+                cleared_rev: RevisionValue::from(entry.cleared_revision) as i64,
+                decision: util::clearance_decision_into_i16(entry.decision),
+                decided_at: entry.decided_at.into_inner(),
+                api_token_id: entry.api_token_id.as_ref().map(Id::as_str),
+                api_token_label: entry.api_token_label.as_deref(),
+            };
+            diesel::insert_into(schema::organization_place_clearance_log::table)
+                .values(&new_entry)
+                .execute(self)?;
+        }
+        Ok(())
+    }
+
+    fn list_clearance_log_entries(
+        &self,
+        org_id: Option<&Id>,
+        place_id: Option<&Id>,
+        pagination: &Pagination,
+    ) -> Result<Vec<ClearanceLogEntry>> {
+        use schema::organization::dsl as org_dsl;
+        use schema::organization_place_clearance_log::dsl;
+        let mut query = schema::organization_place_clearance_log::table
+            .inner_join(schema::organization::table)
+            .select((
+                dsl::place_id,
+                dsl::place_rev,
+                dsl::cleared_rev,
+                dsl::decision,
+                dsl::decided_at,
+                dsl::api_token_id,
+                dsl::api_token_label,
+                org_dsl::id,
+            ))
+            .order_by(dsl::decided_at.desc())
+            .then_order_by(dsl::rowid.desc())
+            .into_boxed();
+        if let Some(org_id) = org_id {
+            query = query.filter(org_dsl::id.eq(org_id.as_str()));
+        }
+        if let Some(place_id) = place_id {
+            query = query.filter(dsl::place_id.eq(place_id.as_str()));
+        }
+
+        // Pagination
+        let offset = pagination.offset.unwrap_or(0);
+        if offset > 0 {
+            query = query.offset(offset as i64);
+        }
+        if let Some(limit) = pagination.limit {
+            query = query.limit(limit as i64);
+        }
+
+        Ok(query
+            .load::<models::ClearanceLogEntry>(self)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+}
+
+impl ViewCounterRepo for SqliteConnection {
+    fn increment_view_count(&self, entity: ViewedEntity, id: &str, day: Timestamp) -> Result<()> {
+        use schema::view_counter::dsl;
+        let entity_kind = view_counter_entity_kind(entity);
+        let day = day.into_inner();
+        self.transaction::<_, diesel::result::Error, _>(|| {
+            let new_counter = models::NewViewCounter {
+                entity_kind,
+                entity_id: id,
+                day,
+                count: 0,
+            };
+            diesel::insert_or_ignore_into(schema::view_counter::table)
+                .values(&new_counter)
+                .execute(self)?;
+            diesel::update(
+                dsl::view_counter
+                    .filter(dsl::entity_kind.eq(entity_kind))
+                    .filter(dsl::entity_id.eq(id))
+                    .filter(dsl::day.eq(day)),
+            )
+            .set(dsl::count.eq(dsl::count + 1))
+            .execute(self)?;
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    fn count_views(&self, entity: ViewedEntity, id: &str) -> Result<u64> {
+        use schema::view_counter::dsl;
+        let count = dsl::view_counter
+            .select(diesel::dsl::sum(dsl::count))
+            .filter(dsl::entity_kind.eq(view_counter_entity_kind(entity)))
+            .filter(dsl::entity_id.eq(id))
+            .first::<Option<i64>>(self)?;
+        Ok(count.unwrap_or_default() as u64)
+    }
+
+    fn count_all_views_since(&self, since: Timestamp) -> Result<u64> {
+        use schema::view_counter::dsl;
+        let count = dsl::view_counter
+            .select(diesel::dsl::sum(dsl::count))
+            .filter(dsl::day.ge(since.into_inner()))
+            .first::<Option<i64>>(self)?;
+        Ok(count.unwrap_or_default() as u64)
+    }
+}
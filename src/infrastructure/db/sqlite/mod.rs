@@ -3,6 +3,9 @@ mod models;
 mod schema;
 mod util;
 
+#[cfg(test)]
+mod tests;
+
 use anyhow::Result as Fallible;
 use diesel::{r2d2, sqlite::SqliteConnection};
 use owning_ref::{RwLockReadGuardRef, RwLockWriteGuardRefMut};
@@ -13,6 +16,9 @@ use std::{
 
 pub type Connection = SqliteConnection;
 
+/// Selects an ephemeral database instead of a database file
+pub const IN_MEMORY_DB_URL: &str = "memory://";
+
 pub type ConnectionManager = r2d2::ConnectionManager<Connection>;
 pub type ConnectionPool = r2d2::Pool<ConnectionManager>;
 pub type PooledConnection = r2d2::PooledConnection<ConnectionManager>;
@@ -105,6 +111,21 @@ impl Connections {
         Ok(Self::new(pool))
     }
 
+    /// An ephemeral database that only lives as long as the
+    /// returned connections. Each SQLite in-memory connection
+    /// opens a separate database. Therefore the pool is limited
+    /// to a single connection that must never be closed and
+    /// replaced by the pool.
+    pub fn init_in_memory() -> Fallible<Self> {
+        let manager = ConnectionManager::new(":memory:");
+        let pool = ConnectionPool::builder()
+            .max_size(1)
+            .max_lifetime(None)
+            .idle_timeout(None)
+            .build(manager)?;
+        Ok(Self::new(pool))
+    }
+
     pub fn new(pool: ConnectionPool) -> Self {
         Self {
             pool: Arc::new(RwLock::new(pool)),
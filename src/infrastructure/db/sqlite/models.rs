@@ -43,6 +43,7 @@ pub struct NewPlaceRevision {
     pub founded_on: Option<NaiveDate>,
     pub image_url: Option<String>,
     pub image_link_url: Option<String>,
+    pub address_auto_filled: bool,
 }
 
 #[derive(Queryable)]
@@ -69,6 +70,7 @@ pub struct JoinedPlaceRevision {
     pub founded_on: Option<NaiveDate>,
     pub image_url: Option<String>,
     pub image_link_url: Option<String>,
+    pub address_auto_filled: bool,
     // Joined columns
     pub place_id: String,
     pub place_license: String,
@@ -97,6 +99,7 @@ pub struct JoinedPlaceRevisionWithStatusReview {
     pub founded_on: Option<NaiveDate>,
     pub image_url: Option<String>,
     pub image_link_url: Option<String>,
+    pub address_auto_filled: bool,
     // Joined columns
     pub place_id: String,
     pub place_license: String,
@@ -131,6 +134,92 @@ pub struct PlaceReviewedRevision {
     pub comment: Option<String>,
 }
 
+#[derive(Insertable)]
+#[table_name = "place_note"]
+pub struct NewPlaceNote<'a> {
+    pub uid: &'a str,
+    pub place_rowid: i64,
+    pub parent_uid: Option<&'a str>,
+    pub created_at: i64,
+    pub created_by: Option<i64>,
+    pub text: &'a str,
+}
+
+#[derive(Queryable)]
+pub struct PlaceNoteEntity {
+    pub uid: String,
+    pub parent_uid: Option<String>,
+    pub created_at: i64,
+    pub text: String,
+    // Joined columns
+    pub place_id: String,
+    pub created_by_email: Option<String>,
+}
+
+#[derive(Insertable)]
+#[table_name = "place_confirmation_request"]
+pub struct NewPlaceConfirmationRequest<'a> {
+    pub place_rowid: i64,
+    pub nonce: &'a str,
+    pub sent_to: &'a str,
+    pub sent_at: i64,
+    pub answered_at: Option<i64>,
+    pub flagged_at: Option<i64>,
+}
+
+#[derive(Queryable)]
+pub struct PlaceConfirmationRequestEntity {
+    pub nonce: String,
+    pub sent_to: String,
+    pub sent_at: i64,
+    pub answered_at: Option<i64>,
+    pub flagged_at: Option<i64>,
+    // Joined columns
+    pub place_id: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "osm_node_proposal"]
+pub struct NewOsmNodeProposal<'a> {
+    pub id: &'a str,
+    pub place_rowid: i64,
+    pub osm_node_id: i64,
+    pub place_rev: i64,
+    pub created_at: i64,
+    pub title: Option<&'a str>,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    pub street: Option<&'a str>,
+    pub zip: Option<&'a str>,
+    pub city: Option<&'a str>,
+    pub country: Option<&'a str>,
+    pub state: Option<&'a str>,
+    pub status: i16,
+    pub reviewed_at: Option<i64>,
+    pub reviewed_by: Option<i64>,
+}
+
+#[derive(Queryable)]
+pub struct OsmNodeProposalEntity {
+    pub id: String,
+    pub osm_node_id: i64,
+    pub place_rev: i64,
+    pub created_at: i64,
+    pub title: Option<String>,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    pub street: Option<String>,
+    pub zip: Option<String>,
+    pub city: Option<String>,
+    pub country: Option<String>,
+    pub state: Option<String>,
+    pub status: i16,
+    pub reviewed_at: Option<i64>,
+    // Joined columns
+    pub place_id: String,
+    pub reviewed_by_email: Option<String>,
+}
+
 #[derive(Queryable)]
 pub struct PlaceRevisionTag {
     pub parent_rowid: i64,
@@ -192,6 +281,24 @@ pub struct PlaceRating {
     pub place_id: String,
 }
 
+#[derive(Queryable)]
+pub struct PlaceRatingAggregate {
+    // Joined columns
+    pub place_id: String,
+    pub diversity_sum: i64,
+    pub diversity_count: i64,
+    pub fairness_sum: i64,
+    pub fairness_count: i64,
+    pub humanity_sum: i64,
+    pub humanity_count: i64,
+    pub renewable_sum: i64,
+    pub renewable_count: i64,
+    pub solidarity_sum: i64,
+    pub solidarity_count: i64,
+    pub transparency_sum: i64,
+    pub transparency_count: i64,
+}
+
 #[derive(Insertable)]
 #[table_name = "place_rating_comment"]
 pub struct NewPlaceRatingComment {
@@ -241,6 +348,12 @@ pub struct NewEvent {
     pub archived: Option<i64>,
     pub image_url: Option<String>,
     pub image_link_url: Option<String>,
+    pub time_zone: Option<String>,
+    pub recurrence: Option<String>,
+    pub publish_at: Option<i64>,
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+    pub address_auto_filled: bool,
 }
 
 #[derive(Queryable)]
@@ -267,6 +380,12 @@ pub struct EventEntity {
     pub archived: Option<i64>,
     pub image_url: Option<String>,
     pub image_link_url: Option<String>,
+    pub time_zone: Option<String>,
+    pub recurrence: Option<String>,
+    pub publish_at: Option<i64>,
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+    pub address_auto_filled: bool,
     // Joined columns
     pub created_by_email: Option<String>,
 }
@@ -277,6 +396,10 @@ pub struct NewOrganization {
     pub id: String,
     pub name: String,
     pub api_token: String,
+    pub notification_email: Option<String>,
+    pub email_sender_name: Option<String>,
+    pub email_reply_to: Option<String>,
+    pub email_footer: Option<String>,
 }
 
 #[derive(Queryable)]
@@ -285,6 +408,10 @@ pub struct Organization {
     pub id: String,
     pub name: String,
     pub api_token: String,
+    pub notification_email: Option<String>,
+    pub email_sender_name: Option<String>,
+    pub email_reply_to: Option<String>,
+    pub email_footer: Option<String>,
 }
 
 #[derive(Queryable)]
@@ -293,6 +420,15 @@ pub struct EventTag {
     pub tag: String,
 }
 
+#[derive(Insertable)]
+#[table_name = "event_changes"]
+pub struct NewEventChange<'a> {
+    pub event_uid: &'a str,
+    pub changed_at: i64,
+    pub changed_by: Option<i64>,
+    pub kind: i16,
+}
+
 #[derive(Insertable)]
 #[table_name = "event_tags"]
 pub struct NewEventTag<'a> {
@@ -307,6 +443,8 @@ pub struct OrganizationTag {
     pub tag_allow_add: i16,
     pub tag_allow_remove: i16,
     pub require_clearance: i16,
+    // Joined columns
+    pub policy: Option<i16>,
 }
 
 #[derive(Queryable)]
@@ -316,6 +454,24 @@ pub struct OrganizationTagWithId {
     pub tag_allow_add: i16,
     pub tag_allow_remove: i16,
     pub require_clearance: i16,
+    // Joined columns
+    pub policy: Option<i16>,
+}
+
+#[derive(Insertable)]
+#[table_name = "org_tag_policy"]
+pub struct NewOrgTagPolicy<'a> {
+    pub org_rowid: i64,
+    pub tag_label: &'a str,
+    pub policy: i16,
+}
+
+#[derive(Queryable, Insertable)]
+#[table_name = "org_daily_quotas"]
+pub struct OrgDailyQuotas {
+    pub org_rowid: i64,
+    pub events: Option<i64>,
+    pub places: Option<i64>,
 }
 
 #[derive(Insertable)]
@@ -350,6 +506,9 @@ pub struct UserEntity {
     pub email_confirmed: bool,
     pub password: String,
     pub role: i16,
+    pub announcements: bool,
+    pub deactivated_at: Option<i64>,
+    pub created_at: Option<i64>,
 }
 
 #[derive(Insertable)]
@@ -361,6 +520,7 @@ pub struct NewBboxSubscription<'a> {
     pub south_west_lng: f64,
     pub north_east_lat: f64,
     pub north_east_lng: f64,
+    pub digest: Option<i16>,
 }
 
 #[derive(Queryable)]
@@ -372,10 +532,137 @@ pub struct BboxSubscriptionEntity {
     pub south_west_lng: f64,
     pub north_east_lat: f64,
     pub north_east_lng: f64,
+    pub digest: Option<i16>,
+    // Joined columns
+    pub user_email: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "organization_subscriptions"]
+pub struct NewOrganizationSubscription<'a> {
+    pub uid: &'a str,
+    pub user_id: i64,
+    pub org_rowid: i64,
+}
+
+#[derive(Insertable)]
+#[table_name = "entity_watches"]
+pub struct NewEntityWatch<'a> {
+    pub user_id: i64,
+    pub entity_id: &'a str,
+    pub created_at: i64,
+}
+
+#[derive(Insertable)]
+#[table_name = "pending_notifications"]
+pub struct NewPendingNotification<'a> {
+    pub uid: &'a str,
+    pub user_id: i64,
+    pub digest: i16,
+    pub kind: i16,
+    pub entity_id: &'a str,
+    pub title: &'a str,
+    pub created_at: i64,
+}
+
+#[derive(Queryable)]
+pub struct PendingNotificationEntity {
+    pub uid: String,
+    pub digest: i16,
+    pub kind: i16,
+    pub entity_id: String,
+    pub title: String,
+    pub created_at: i64,
     // Joined columns
     pub user_email: String,
 }
 
+#[derive(Insertable)]
+#[table_name = "pending_moderated_tags_notifications"]
+pub struct NewPendingModeratedTagsNotification<'a> {
+    pub id: &'a str,
+    pub org_rowid: i64,
+    pub place_id: &'a str,
+    pub place_title: &'a str,
+    pub added_tags: String,
+    pub removed_tags: String,
+    pub created_at: i64,
+}
+
+#[derive(Queryable)]
+pub struct PendingModeratedTagsNotificationEntity {
+    pub id: String,
+    pub place_id: String,
+    pub place_title: String,
+    pub added_tags: String,
+    pub removed_tags: String,
+    pub created_at: i64,
+    // Joined columns
+    pub org_id: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "pending_ratings"]
+pub struct NewPendingRating<'a> {
+    pub id: &'a str,
+    pub place_id: &'a str,
+    pub created_at: i64,
+    pub created_by: Option<&'a str>,
+    pub title: &'a str,
+    pub value: i16,
+    pub context: String,
+    pub source: Option<&'a str>,
+    pub comment_id: &'a str,
+    pub comment_text: &'a str,
+    pub violations: String,
+}
+
+#[derive(Queryable)]
+pub struct PendingRatingEntity {
+    pub rowid: i64,
+    pub id: String,
+    pub place_id: String,
+    pub created_at: i64,
+    pub created_by: Option<String>,
+    pub title: String,
+    pub value: i16,
+    pub context: String,
+    pub source: Option<String>,
+    pub comment_id: String,
+    pub comment_text: String,
+    pub violations: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "personal_api_tokens"]
+pub struct NewPersonalApiToken<'a> {
+    pub id: &'a str,
+    pub user_id: i64,
+    pub scope: i16,
+    pub label: Option<&'a str>,
+    pub secret_hash: &'a str,
+    pub created_at: i64,
+}
+
+#[derive(Queryable)]
+pub struct PersonalApiTokenEntity {
+    pub id: String,
+    pub scope: i16,
+    pub label: Option<String>,
+    pub secret_hash: String,
+    pub created_at: i64,
+    // Joined columns
+    pub user_email: String,
+}
+
+#[derive(Queryable)]
+pub struct OrganizationSubscriptionEntity {
+    pub uid: String,
+    // Joined columns
+    pub user_email: String,
+    pub org_id: String,
+}
+
 #[derive(Insertable, AsChangeset)]
 #[table_name = "user_tokens"]
 pub struct NewUserToken {
@@ -393,6 +680,13 @@ pub struct UserTokenEntity {
     pub user_email: String,
 }
 
+#[derive(Insertable)]
+#[table_name = "place_osm_node"]
+pub struct NewPlaceOsmNode {
+    pub place_rowid: i64,
+    pub osm_node_id: i64,
+}
+
 #[derive(Insertable, AsChangeset)]
 #[table_name = "organization_place_clearance"]
 #[changeset_options(treat_none_as_null = "true")]
@@ -409,3 +703,125 @@ pub struct PendingClearanceForPlace {
     pub created_at: i64,
     pub last_cleared_revision: Option<i64>,
 }
+
+#[derive(Insertable)]
+#[table_name = "organization_place_clearance_log"]
+pub struct NewClearanceLogEntry<'a> {
+    pub org_rowid: i64,
+    pub place_id: &'a str,
+    pub place_rev: i64,
+    pub cleared_rev: i64,
+    pub decision: i16,
+    pub decided_at: i64,
+    pub api_token_id: Option<&'a str>,
+    pub api_token_label: Option<&'a str>,
+}
+
+#[derive(Queryable)]
+pub struct ClearanceLogEntry {
+    pub place_id: String,
+    pub place_rev: i64,
+    pub cleared_rev: i64,
+    pub decision: i16,
+    pub decided_at: i64,
+    pub api_token_id: Option<String>,
+    pub api_token_label: Option<String>,
+    // Joined columns
+    pub org_id: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "organization_api_tokens"]
+pub struct NewOrganizationApiToken<'a> {
+    pub id: &'a str,
+    pub org_rowid: i64,
+    pub label: Option<&'a str>,
+    pub secret_hash: &'a str,
+    pub created_at: i64,
+    pub expires_at: Option<i64>,
+    pub last_used_at: Option<i64>,
+}
+
+#[derive(Queryable)]
+pub struct OrganizationApiTokenEntity {
+    pub id: String,
+    pub label: Option<String>,
+    pub secret_hash: String,
+    pub created_at: i64,
+    pub expires_at: Option<i64>,
+    pub last_used_at: Option<i64>,
+    // Joined columns
+    pub org_id: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "view_counter"]
+pub struct NewViewCounter<'a> {
+    pub entity_kind: i16,
+    pub entity_id: &'a str,
+    pub day: i64,
+    pub count: i32,
+}
+
+#[derive(Queryable, Insertable)]
+#[table_name = "tag_cooccurrence"]
+pub struct TagCooccurrence {
+    pub word: String,
+    pub tag: String,
+    pub count: i64,
+    pub word_count: i64,
+}
+
+#[derive(Queryable)]
+pub struct LinkCheck {
+    pub url: String,
+    pub failures: i32,
+    pub checked_at: i64,
+    pub last_error: Option<String>,
+}
+
+#[derive(Insertable)]
+#[table_name = "link_check"]
+pub struct NewLinkCheck<'a> {
+    pub url: &'a str,
+    pub failures: i32,
+    pub checked_at: i64,
+    pub last_error: Option<&'a str>,
+}
+
+#[derive(Queryable)]
+pub struct HomepagePreview {
+    pub url: String,
+    pub title: Option<String>,
+    pub image_url: Option<String>,
+    pub fetched_at: i64,
+}
+
+#[derive(Insertable)]
+#[table_name = "homepage_preview"]
+pub struct NewHomepagePreview<'a> {
+    pub url: &'a str,
+    pub title: Option<&'a str>,
+    pub image_url: Option<&'a str>,
+    pub fetched_at: i64,
+}
+
+#[derive(Queryable, Insertable)]
+#[table_name = "email_outbox"]
+pub struct QueuedEmail {
+    pub id: String,
+    pub recipient: String,
+    pub subject: String,
+    pub body: String,
+    pub created_at: i64,
+    pub sent_at: Option<i64>,
+}
+
+#[derive(Insertable)]
+#[table_name = "notification_consent"]
+pub struct NewNotificationConsent<'a> {
+    pub email: &'a str,
+    pub nonce: String,
+    pub requested_at: i64,
+    pub confirmed_at: Option<i64>,
+}
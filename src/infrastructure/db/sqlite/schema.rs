@@ -18,6 +18,10 @@ table! {
         id -> Text,
         name -> Text,
         api_token -> Text,
+        notification_email -> Nullable<Text>,
+        email_sender_name -> Nullable<Text>,
+        email_reply_to -> Nullable<Text>,
+        email_footer -> Nullable<Text>,
     }
 }
 
@@ -33,6 +37,26 @@ table! {
 
 joinable!(organization_tag -> organization (org_rowid));
 
+table! {
+    org_tag_policy (org_rowid, tag_label) {
+        org_rowid -> BigInt,
+        tag_label -> Text,
+        policy -> SmallInt,
+    }
+}
+
+joinable!(org_tag_policy -> organization (org_rowid));
+
+table! {
+    org_daily_quotas (org_rowid) {
+        org_rowid -> BigInt,
+        events -> Nullable<BigInt>,
+        places -> Nullable<BigInt>,
+    }
+}
+
+joinable!(org_daily_quotas -> organization (org_rowid));
+
 table! {
     organization_place_clearance (org_rowid, place_rowid) {
         rowid -> BigInt,
@@ -47,6 +71,37 @@ table! {
 joinable!(organization_place_clearance -> organization (org_rowid));
 joinable!(organization_place_clearance -> place (place_rowid));
 
+table! {
+    organization_place_clearance_log (rowid) {
+        rowid -> BigInt,
+        org_rowid -> BigInt,
+        place_id -> Text,
+        place_rev -> BigInt,
+        cleared_rev -> BigInt,
+        decision -> SmallInt,
+        decided_at -> BigInt,
+        api_token_id -> Nullable<Text>,
+        api_token_label -> Nullable<Text>,
+    }
+}
+
+joinable!(organization_place_clearance_log -> organization (org_rowid));
+
+table! {
+    organization_api_tokens (rowid) {
+        rowid -> BigInt,
+        id -> Text,
+        org_rowid -> BigInt,
+        label -> Nullable<Text>,
+        secret_hash -> Text,
+        created_at -> BigInt,
+        expires_at -> Nullable<BigInt>,
+        last_used_at -> Nullable<BigInt>,
+    }
+}
+
+joinable!(organization_api_tokens -> organization (org_rowid));
+
 ///////////////////////////////////////////////////////////////////////
 // Users
 ///////////////////////////////////////////////////////////////////////
@@ -58,6 +113,9 @@ table! {
         email_confirmed -> Bool,
         password -> Text,
         role -> SmallInt,
+        announcements -> Bool,
+        deactivated_at -> Nullable<BigInt>,
+        created_at -> Nullable<BigInt>,
     }
 }
 
@@ -110,6 +168,7 @@ table! {
         founded_on -> Nullable<Date>,
         image_url -> Nullable<Text>,
         image_link_url -> Nullable<Text>,
+        address_auto_filled -> Bool,
     }
 }
 
@@ -150,6 +209,34 @@ table! {
 
 joinable!(place_revision_review -> place_revision (parent_rowid));
 
+table! {
+    place_note (rowid) {
+        rowid -> BigInt,
+        uid -> Text,
+        place_rowid -> BigInt,
+        parent_uid -> Nullable<Text>,
+        created_at -> BigInt,
+        created_by -> Nullable<BigInt>,
+        text -> Text,
+    }
+}
+
+joinable!(place_note -> place (place_rowid));
+
+table! {
+    place_confirmation_request (rowid) {
+        rowid -> BigInt,
+        place_rowid -> BigInt,
+        nonce -> Text,
+        sent_to -> Text,
+        sent_at -> BigInt,
+        answered_at -> Nullable<BigInt>,
+        flagged_at -> Nullable<BigInt>,
+    }
+}
+
+joinable!(place_confirmation_request -> place (place_rowid));
+
 table! {
     place_rating (rowid) {
         rowid -> BigInt,
@@ -163,6 +250,7 @@ table! {
         value -> SmallInt,
         context -> Text,
         source -> Nullable<Text>,
+        archived_reason -> Nullable<SmallInt>,
     }
 }
 
@@ -178,11 +266,82 @@ table! {
         archived_by -> Nullable<BigInt>,
         id -> Text,
         text -> Text,
+        archived_reason -> Nullable<SmallInt>,
     }
 }
 
 joinable!(place_rating_comment -> place_rating (parent_rowid));
 
+table! {
+    place_rating_aggregates (place_rowid) {
+        place_rowid -> BigInt,
+        diversity_sum -> BigInt,
+        diversity_count -> BigInt,
+        fairness_sum -> BigInt,
+        fairness_count -> BigInt,
+        humanity_sum -> BigInt,
+        humanity_count -> BigInt,
+        renewable_sum -> BigInt,
+        renewable_count -> BigInt,
+        solidarity_sum -> BigInt,
+        solidarity_count -> BigInt,
+        transparency_sum -> BigInt,
+        transparency_count -> BigInt,
+    }
+}
+
+joinable!(place_rating_aggregates -> place (place_rowid));
+
+table! {
+    pending_ratings (rowid) {
+        rowid -> BigInt,
+        id -> Text,
+        place_id -> Text,
+        created_at -> BigInt,
+        created_by -> Nullable<Text>,
+        title -> Text,
+        value -> SmallInt,
+        context -> Text,
+        source -> Nullable<Text>,
+        comment_id -> Text,
+        comment_text -> Text,
+        violations -> Text,
+    }
+}
+
+table! {
+    place_osm_node (place_rowid) {
+        place_rowid -> BigInt,
+        osm_node_id -> BigInt,
+    }
+}
+
+joinable!(place_osm_node -> place (place_rowid));
+
+table! {
+    osm_node_proposal (rowid) {
+        rowid -> BigInt,
+        id -> Text,
+        place_rowid -> BigInt,
+        osm_node_id -> BigInt,
+        place_rev -> BigInt,
+        created_at -> BigInt,
+        title -> Nullable<Text>,
+        lat -> Nullable<Double>,
+        lon -> Nullable<Double>,
+        street -> Nullable<Text>,
+        zip -> Nullable<Text>,
+        city -> Nullable<Text>,
+        country -> Nullable<Text>,
+        state -> Nullable<Text>,
+        status -> SmallInt,
+        reviewed_at -> Nullable<BigInt>,
+        reviewed_by -> Nullable<BigInt>,
+    }
+}
+
+joinable!(osm_node_proposal -> place (place_rowid));
+
 ///////////////////////////////////////////////////////////////////////
 // Events
 ///////////////////////////////////////////////////////////////////////
@@ -213,6 +372,12 @@ table! {
         archived -> Nullable<BigInt>,
         image_url -> Nullable<Text>,
         image_link_url -> Nullable<Text>,
+        time_zone -> Nullable<Text>,
+        recurrence -> Nullable<Text>,
+        publish_at -> Nullable<BigInt>,
+        created_at -> Nullable<BigInt>,
+        updated_at -> Nullable<BigInt>,
+        address_auto_filled -> Bool,
     }
 }
 
@@ -227,6 +392,16 @@ table! {
 
 joinable!(event_tags -> events (event_id));
 
+table! {
+    event_changes (rowid) {
+        rowid -> BigInt,
+        event_uid -> Text,
+        changed_at -> BigInt,
+        changed_by -> Nullable<BigInt>,
+        kind -> SmallInt,
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////
 // Subscriptions
 ///////////////////////////////////////////////////////////////////////
@@ -243,28 +418,174 @@ table! {
         south_west_lng -> Double,
         north_east_lat -> Double,
         north_east_lng -> Double,
+        digest -> Nullable<SmallInt>,
     }
 }
 
 joinable!(bbox_subscriptions -> users (user_id));
 
+table! {
+    organization_subscriptions (id) {
+        id -> BigInt,
+        uid -> Text,
+        user_id -> BigInt,
+        org_rowid -> BigInt,
+    }
+}
+
+joinable!(organization_subscriptions -> users (user_id));
+
+table! {
+    entity_watches (id) {
+        id -> BigInt,
+        user_id -> BigInt,
+        entity_id -> Text,
+        created_at -> BigInt,
+    }
+}
+
+joinable!(entity_watches -> users (user_id));
+
+table! {
+    pending_notifications (id) {
+        id -> BigInt,
+        uid -> Text,
+        user_id -> BigInt,
+        digest -> SmallInt,
+        kind -> SmallInt,
+        entity_id -> Text,
+        title -> Text,
+        created_at -> BigInt,
+    }
+}
+
+joinable!(pending_notifications -> users (user_id));
+
+table! {
+    personal_api_tokens (rowid) {
+        rowid -> BigInt,
+        id -> Text,
+        user_id -> BigInt,
+        scope -> SmallInt,
+        label -> Nullable<Text>,
+        secret_hash -> Text,
+        created_at -> BigInt,
+    }
+}
+
+joinable!(personal_api_tokens -> users (user_id));
+
+table! {
+    pending_moderated_tags_notifications (rowid) {
+        rowid -> BigInt,
+        id -> Text,
+        org_rowid -> BigInt,
+        place_id -> Text,
+        place_title -> Text,
+        added_tags -> Text,
+        removed_tags -> Text,
+        created_at -> BigInt,
+    }
+}
+
+joinable!(pending_moderated_tags_notifications -> organization (org_rowid));
+joinable!(organization_subscriptions -> organization (org_rowid));
+
+table! {
+    view_counter (entity_kind, entity_id, day) {
+        entity_kind -> SmallInt,
+        entity_id -> Text,
+        day -> BigInt,
+        count -> Integer,
+    }
+}
+
+table! {
+    tag_cooccurrence (word, tag) {
+        word -> Text,
+        tag -> Text,
+        count -> BigInt,
+        word_count -> BigInt,
+    }
+}
+
+table! {
+    link_check (url) {
+        url -> Text,
+        failures -> Integer,
+        checked_at -> BigInt,
+        last_error -> Nullable<Text>,
+    }
+}
+
+table! {
+    email_outbox (id) {
+        id -> Text,
+        recipient -> Text,
+        subject -> Text,
+        body -> Text,
+        created_at -> BigInt,
+        sent_at -> Nullable<BigInt>,
+    }
+}
+
+table! {
+    notification_consent (email) {
+        email -> Text,
+        nonce -> Text,
+        requested_at -> BigInt,
+        confirmed_at -> Nullable<BigInt>,
+    }
+}
+
+table! {
+    homepage_preview (url) {
+        url -> Text,
+        title -> Nullable<Text>,
+        image_url -> Nullable<Text>,
+        fetched_at -> BigInt,
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////
 
 allow_tables_to_appear_in_same_query!(
     bbox_subscriptions,
     events,
+    event_changes,
+    link_check,
+    homepage_preview,
+    email_outbox,
+    entity_watches,
+    notification_consent,
+    osm_node_proposal,
+    pending_notifications,
+    pending_moderated_tags_notifications,
+    pending_ratings,
+    personal_api_tokens,
     event_tags,
     place,
+    place_osm_node,
     place_rating,
+    place_rating_aggregates,
     place_rating_comment,
     place_revision,
     place_revision_review,
     place_revision_tag,
+    place_note,
+    place_confirmation_request,
     place_revision_custom_link,
     organization,
     organization_tag,
     organization_place_clearance,
+    organization_place_clearance_log,
+    organization_api_tokens,
+    organization_subscriptions,
+    org_daily_quotas,
+    org_tag_policy,
     tags,
+    tag_cooccurrence,
     users,
     user_tokens,
+    view_counter,
 );
@@ -0,0 +1,35 @@
+use super::Connections;
+use crate::core::repositories::conformance;
+
+embed_migrations!();
+
+fn connections() -> Connections {
+    let connections = Connections::init_in_memory().unwrap();
+    embedded_migrations::run(&*connections.exclusive().unwrap()).unwrap();
+    connections
+}
+
+#[test]
+fn place_repo_conformance() {
+    conformance::place_repo(&*connections().exclusive().unwrap());
+}
+
+#[test]
+fn event_gateway_conformance() {
+    conformance::event_gateway(&*connections().exclusive().unwrap());
+}
+
+#[test]
+fn rating_repo_conformance() {
+    conformance::rating_repo(&*connections().exclusive().unwrap());
+}
+
+#[test]
+fn archived_ratings_and_comments_conformance() {
+    conformance::archived_ratings_and_comments(&*connections().exclusive().unwrap());
+}
+
+#[test]
+fn rating_aggregate_repo_conformance() {
+    conformance::rating_aggregate_repo(&*connections().exclusive().unwrap());
+}
@@ -21,6 +21,20 @@ pub(crate) fn load_url(url: String) -> Option<e::Url> {
     }
 }
 
+pub(crate) fn load_recurrence(recurrence: String) -> Option<e::Recurrence> {
+    match recurrence.parse() {
+        Ok(recurrence) => Some(recurrence),
+        Err(_) => {
+            // The database should only contain valid recurrence rules
+            log::error!(
+                "Failed to load recurrence rule '{}' from database",
+                recurrence
+            );
+            None
+        }
+    }
+}
+
 pub(crate) fn registration_type_from_i16(i: i16) -> e::RegistrationType {
     use crate::core::entities::RegistrationType::*;
     match i {
@@ -73,6 +87,122 @@ mod tests {
     }
 }
 
+pub(crate) fn notification_digest_from_i16(i: i16) -> e::NotificationDigest {
+    use crate::core::entities::NotificationDigest::*;
+    match i {
+        1 => Daily,
+        2 => Weekly,
+        _ => {
+            error!("Invalid notification digest {}: Use 'Daily' instead", i);
+            Daily
+        }
+    }
+}
+
+pub(crate) fn notification_digest_into_i16(x: e::NotificationDigest) -> i16 {
+    use crate::core::entities::NotificationDigest::*;
+    match x {
+        Daily => 1,
+        Weekly => 2,
+    }
+}
+
+pub(crate) fn archive_reason_from_i16(i: i16) -> Option<e::ArchiveReason> {
+    use crate::core::entities::ArchiveReason::*;
+    match i {
+        1 => Some(Spam),
+        2 => Some(OffTopic),
+        3 => Some(Abusive),
+        4 => Some(AuthorRequest),
+        _ => {
+            warn!("Invalid archive reason {}", i);
+            None
+        }
+    }
+}
+
+pub(crate) fn archive_reason_into_i16(x: e::ArchiveReason) -> i16 {
+    use crate::core::entities::ArchiveReason::*;
+    match x {
+        Spam => 1,
+        OffTopic => 2,
+        Abusive => 3,
+        AuthorRequest => 4,
+    }
+}
+
+pub(crate) fn notification_change_from_i16(i: i16) -> e::NotificationChange {
+    use crate::core::entities::NotificationChange::*;
+    match i {
+        1 => PlaceAdded,
+        2 => PlaceUpdated,
+        3 => EventCreated,
+        4 => EventUpdated,
+        _ => {
+            error!(
+                "Invalid notification change {}: Use 'PlaceUpdated' instead",
+                i
+            );
+            PlaceUpdated
+        }
+    }
+}
+
+pub(crate) fn notification_change_into_i16(x: e::NotificationChange) -> i16 {
+    use crate::core::entities::NotificationChange::*;
+    match x {
+        PlaceAdded => 1,
+        PlaceUpdated => 2,
+        EventCreated => 3,
+        EventUpdated => 4,
+    }
+}
+
+pub(crate) fn clearance_decision_from_i16(i: i16) -> e::ClearanceDecision {
+    use crate::core::entities::ClearanceDecision::*;
+    match i {
+        1 => Approved,
+        2 => Rejected,
+        _ => {
+            error!("Invalid clearance decision {}: Use 'Rejected' instead", i);
+            Rejected
+        }
+    }
+}
+
+pub(crate) fn clearance_decision_into_i16(x: e::ClearanceDecision) -> i16 {
+    use crate::core::entities::ClearanceDecision::*;
+    match x {
+        Approved => 1,
+        Rejected => 2,
+    }
+}
+
+pub(crate) fn osm_node_proposal_status_from_i16(i: i16) -> e::OsmNodeProposalStatus {
+    use crate::core::entities::OsmNodeProposalStatus::*;
+    match i {
+        0 => Pending,
+        1 => Accepted,
+        2 => Rejected,
+        _ => {
+            error!(
+                "Invalid OSM node proposal status {}: Use 'Rejected' instead",
+                i
+            );
+            Rejected
+        }
+    }
+}
+
+pub(crate) fn osm_node_proposal_status_into_i16(x: e::OsmNodeProposalStatus) -> i16 {
+    use crate::core::entities::OsmNodeProposalStatus::*;
+    match x {
+        Pending => 0,
+        Accepted => 1,
+        Rejected => 2,
+    }
+}
+
 pub(crate) fn event_from_event_entity_and_tags(e: EventEntity, tag_rels: &[EventTag]) -> e::Event {
     let EventEntity {
         id,
@@ -96,6 +226,12 @@ pub(crate) fn event_from_event_entity_and_tags(e: EventEntity, tag_rels: &[Event
         archived,
         image_url,
         image_link_url,
+        time_zone,
+        recurrence,
+        publish_at,
+        created_at,
+        updated_at,
+        address_auto_filled,
         created_by_email,
         ..
     } = e;
@@ -163,6 +299,12 @@ pub(crate) fn event_from_event_entity_and_tags(e: EventEntity, tag_rels: &[Event
         archived: archived.map(Timestamp::from_inner),
         image_url: image_url.and_then(load_url),
         image_link_url: image_link_url.and_then(load_url),
+        time_zone,
+        recurrence: recurrence.and_then(load_recurrence),
+        address_auto_filled,
+        publish_at: publish_at.map(Timestamp::from_inner),
+        created_at: created_at.map(Timestamp::from_inner),
+        updated_at: updated_at.map(Timestamp::from_inner),
     }
 }
 
@@ -265,6 +407,267 @@ impl From<PlaceRating> for e::Rating {
     }
 }
 
+// Same as e::AvgRatingValueBuilder
+fn avg_rating_value(sum: i64, count: i64) -> e::AvgRatingValue {
+    if count > 0 {
+        e::AvgRatingValue::from(sum as f64 / count as f64).clamp()
+    } else {
+        Default::default()
+    }
+}
+
+impl From<PlaceRatingAggregate> for (e::Id, e::AvgRatings) {
+    fn from(from: PlaceRatingAggregate) -> Self {
+        let PlaceRatingAggregate {
+            place_id,
+            diversity_sum,
+            diversity_count,
+            fairness_sum,
+            fairness_count,
+            humanity_sum,
+            humanity_count,
+            renewable_sum,
+            renewable_count,
+            solidarity_sum,
+            solidarity_count,
+            transparency_sum,
+            transparency_count,
+        } = from;
+        let count = diversity_count
+            + fairness_count
+            + humanity_count
+            + renewable_count
+            + solidarity_count
+            + transparency_count;
+        (
+            place_id.into(),
+            e::AvgRatings {
+                count: count as u32,
+                diversity: avg_rating_value(diversity_sum, diversity_count),
+                fairness: avg_rating_value(fairness_sum, fairness_count),
+                humanity: avg_rating_value(humanity_sum, humanity_count),
+                renewable: avg_rating_value(renewable_sum, renewable_count),
+                solidarity: avg_rating_value(solidarity_sum, solidarity_count),
+                transparency: avg_rating_value(transparency_sum, transparency_count),
+            },
+        )
+    }
+}
+
+impl From<OrganizationSubscriptionEntity> for e::OrganizationSubscription {
+    fn from(from: OrganizationSubscriptionEntity) -> Self {
+        let OrganizationSubscriptionEntity {
+            uid,
+            user_email,
+            org_id,
+        } = from;
+        Self {
+            id: uid.into(),
+            user_email,
+            org_id: org_id.into(),
+        }
+    }
+}
+
+impl From<PlaceNoteEntity> for e::PlaceNote {
+    fn from(from: PlaceNoteEntity) -> Self {
+        let PlaceNoteEntity {
+            uid,
+            parent_uid,
+            created_at,
+            text,
+            place_id,
+            created_by_email,
+        } = from;
+        Self {
+            id: uid.into(),
+            place_id: place_id.into(),
+            parent_id: parent_uid.map(Into::into),
+            created: e::Activity {
+                at: e::TimestampMs::from_inner(created_at),
+                by: created_by_email.map(Into::into),
+            },
+            text,
+        }
+    }
+}
+
+impl From<OsmNodeProposalEntity> for e::OsmNodeProposal {
+    fn from(from: OsmNodeProposalEntity) -> Self {
+        let OsmNodeProposalEntity {
+            id,
+            osm_node_id,
+            place_rev,
+            created_at,
+            title,
+            lat,
+            lon,
+            street,
+            zip,
+            city,
+            country,
+            state,
+            status,
+            reviewed_at,
+            place_id,
+            reviewed_by_email,
+        } = from;
+        let pos = if let (Some(lat), Some(lng)) = (lat, lon) {
+            MapPoint::try_from_lat_lng_deg(lat, lng).ok()
+        } else {
+            None
+        };
+        let address = e::Address {
+            street,
+            zip,
+            city,
+            country,
+            state,
+        };
+        Self {
+            id: id.into(),
+            place_id: place_id.into(),
+            osm_node_id: osm_node_id as u64,
+            place_revision: e::Revision::from(place_rev as u64),
+            created_at: e::TimestampMs::from_inner(created_at),
+            title,
+            pos,
+            address: if address.is_empty() {
+                None
+            } else {
+                Some(address)
+            },
+            status: osm_node_proposal_status_from_i16(status),
+            reviewed: reviewed_at.map(|at| e::Activity {
+                at: e::TimestampMs::from_inner(at),
+                by: reviewed_by_email.map(Into::into),
+            }),
+        }
+    }
+}
+
+impl From<PlaceConfirmationRequestEntity> for e::PlaceConfirmationRequest {
+    fn from(from: PlaceConfirmationRequestEntity) -> Self {
+        let PlaceConfirmationRequestEntity {
+            nonce,
+            sent_to,
+            sent_at,
+            answered_at,
+            flagged_at,
+            place_id,
+        } = from;
+        Self {
+            place_id: place_id.into(),
+            nonce: nonce.parse::<Nonce>().unwrap_or_default(),
+            sent_to: sent_to.into(),
+            sent_at: e::TimestampMs::from_inner(sent_at),
+            answered_at: answered_at.map(e::TimestampMs::from_inner),
+            flagged_at: flagged_at.map(e::TimestampMs::from_inner),
+        }
+    }
+}
+
+impl From<PendingNotificationEntity> for e::PendingNotification {
+    fn from(from: PendingNotificationEntity) -> Self {
+        let PendingNotificationEntity {
+            uid,
+            digest,
+            kind,
+            entity_id,
+            title,
+            created_at,
+            user_email,
+        } = from;
+        Self {
+            id: uid.into(),
+            user_email,
+            digest: notification_digest_from_i16(digest),
+            change: notification_change_from_i16(kind),
+            entity_id: entity_id.into(),
+            title,
+            created_at: Timestamp::from_inner(created_at),
+        }
+    }
+}
+
+fn split_tags(tags: &str) -> Vec<String> {
+    tags.split(',')
+        .filter(|t| !t.is_empty())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+impl From<PendingModeratedTagsNotificationEntity> for e::PendingModeratedTagsNotification {
+    fn from(from: PendingModeratedTagsNotificationEntity) -> Self {
+        let PendingModeratedTagsNotificationEntity {
+            id,
+            place_id,
+            place_title,
+            added_tags,
+            removed_tags,
+            created_at,
+            org_id,
+        } = from;
+        Self {
+            id: id.into(),
+            org_id: org_id.into(),
+            place_id: place_id.into(),
+            place_title,
+            added_tags: split_tags(&added_tags),
+            removed_tags: split_tags(&removed_tags),
+            created_at: Timestamp::from_inner(created_at),
+        }
+    }
+}
+
+impl From<ClearanceLogEntry> for e::ClearanceLogEntry {
+    fn from(from: ClearanceLogEntry) -> Self {
+        let ClearanceLogEntry {
+            place_id,
+            place_rev,
+            cleared_rev,
+            decision,
+            decided_at,
+            api_token_id,
+            api_token_label,
+            org_id,
+        } = from;
+        Self {
+            org_id: org_id.into(),
+            place_id: place_id.into(),
+            place_revision: e::Revision::from(place_rev as u64),
+            cleared_revision: e::Revision::from(cleared_rev as u64),
+            decision: clearance_decision_from_i16(decision),
+            decided_at: e::TimestampMs::from_inner(decided_at),
+            api_token_id: api_token_id.map(Into::into),
+            api_token_label,
+        }
+    }
+}
+
+impl From<OrganizationApiTokenEntity> for e::OrganizationApiToken {
+    fn from(from: OrganizationApiTokenEntity) -> Self {
+        let OrganizationApiTokenEntity {
+            id,
+            label,
+            secret_hash,
+            created_at,
+            expires_at,
+            last_used_at,
+            org_id,
+        } = from;
+        Self {
+            id: id.into(),
+            org_id: org_id.into(),
+            label,
+            secret_hash,
+            created_at: Timestamp::from_inner(created_at),
+            expires_at: expires_at.map(Timestamp::from_inner),
+            last_used_at: last_used_at.map(Timestamp::from_inner),
+        }
+    }
+}
+
 impl From<BboxSubscriptionEntity> for e::BboxSubscription {
     fn from(from: BboxSubscriptionEntity) -> Self {
         let BboxSubscriptionEntity {
@@ -274,6 +677,7 @@ impl From<BboxSubscriptionEntity> for e::BboxSubscription {
             south_west_lng,
             north_east_lat,
             north_east_lng,
+            digest,
             ..
         } = from;
         let south_west =
@@ -285,6 +689,32 @@ impl From<BboxSubscriptionEntity> for e::BboxSubscription {
             id: uid.into(),
             user_email,
             bbox,
+            digest: digest.map(notification_digest_from_i16),
+        }
+    }
+}
+
+impl From<PersonalApiTokenEntity> for e::PersonalApiToken {
+    fn from(from: PersonalApiTokenEntity) -> Self {
+        use num_traits::FromPrimitive;
+        let PersonalApiTokenEntity {
+            id,
+            scope,
+            label,
+            secret_hash,
+            created_at,
+            user_email,
+        } = from;
+        Self {
+            id: id.into(),
+            user_email,
+            scope: e::ApiTokenScope::from_i16(scope).unwrap_or_else(|| {
+                error!("Invalid API token scope {}: Use 'Read' instead", scope);
+                e::ApiTokenScope::Read
+            }),
+            label,
+            secret_hash,
+            created_at: Timestamp::from_inner(created_at),
         }
     }
 }
@@ -313,7 +743,7 @@ pub(crate) fn rating_context_to_string(context: e::RatingContext) -> String {
     .into()
 }
 
-fn rating_context_from_str(context: &str) -> Result<e::RatingContext> {
+pub(crate) fn rating_context_from_str(context: &str) -> Result<e::RatingContext> {
     Ok(match context {
         "diversity" => e::RatingContext::Diversity,
         "renewable" => e::RatingContext::Renewable,
@@ -327,6 +757,71 @@ fn rating_context_from_str(context: &str) -> Result<e::RatingContext> {
     })
 }
 
+pub(crate) fn comment_rule_violation_to_str(violation: e::CommentRuleViolation) -> &'static str {
+    match violation {
+        e::CommentRuleViolation::TooManyLinks => "too_many_links",
+        e::CommentRuleViolation::BannedPhrase => "banned_phrase",
+        e::CommentRuleViolation::AccountTooNew => "account_too_new",
+    }
+}
+
+fn comment_rule_violation_from_str(violation: &str) -> Option<e::CommentRuleViolation> {
+    Some(match violation {
+        "too_many_links" => e::CommentRuleViolation::TooManyLinks,
+        "banned_phrase" => e::CommentRuleViolation::BannedPhrase,
+        "account_too_new" => e::CommentRuleViolation::AccountTooNew,
+        _ => {
+            warn!("Unknown comment rule violation '{}'", violation);
+            return None;
+        }
+    })
+}
+
+impl From<PendingRatingEntity> for e::PendingRating {
+    fn from(from: PendingRatingEntity) -> Self {
+        let PendingRatingEntity {
+            id,
+            place_id,
+            created_at,
+            created_by,
+            title,
+            value,
+            context,
+            source,
+            comment_id,
+            comment_text,
+            violations,
+            ..
+        } = from;
+        let created_at = Timestamp::from_inner(created_at);
+        Self {
+            rating: e::Rating {
+                id: id.as_str().into(),
+                place_id: place_id.into(),
+                created_at,
+                archived_at: None,
+                title,
+                value: (value as i8).into(),
+                context: rating_context_from_str(&context).unwrap(),
+                source,
+            },
+            comment: e::Comment {
+                id: comment_id.into(),
+                rating_id: id.into(),
+                created_at,
+                archived_at: None,
+                text: comment_text,
+            },
+            created_by,
+            violations: violations
+                .split(',')
+                .filter(|v| !v.is_empty())
+                .filter_map(comment_rule_violation_from_str)
+                .collect(),
+        }
+    }
+}
+
 impl From<e::Organization> for NewOrganization {
     fn from(o: e::Organization) -> Self {
         let e::Organization {
@@ -334,15 +829,56 @@ impl From<e::Organization> for NewOrganization {
             name,
             api_token,
             moderated_tags: _,
+            notification_email,
+            email_branding,
         } = o;
+        let e::EmailBranding {
+            sender_name,
+            reply_to,
+            footer,
+        } = email_branding;
         NewOrganization {
             id: id.into(),
             name,
             api_token,
+            notification_email,
+            email_sender_name: sender_name,
+            email_reply_to: reply_to.map(Into::into),
+            email_footer: footer,
         }
     }
 }
 
+fn load_moderated_tag(
+    label: String,
+    allow_add: i16,
+    allow_remove: i16,
+    require_clearance: i16,
+    policy: Option<i16>,
+) -> e::ModeratedTag {
+    let moderated_tag = e::ModeratedTag {
+        label,
+        allow_add: allow_add != 0,
+        allow_remove: allow_remove != 0,
+        require_clearance: require_clearance != 0,
+    };
+    // A configured policy overrides the individual permissions
+    match policy {
+        Some(policy) => match e::TagModerationPolicy::try_from(policy) {
+            Some(policy) => moderated_tag.with_policy(policy),
+            None => {
+                log::warn!(
+                    "Ignoring invalid policy {} of moderated tag '{}'",
+                    policy,
+                    moderated_tag.label
+                );
+                moderated_tag
+            }
+        },
+        None => moderated_tag,
+    }
+}
+
 impl From<OrganizationTag> for e::ModeratedTag {
     fn from(from: OrganizationTag) -> Self {
         let OrganizationTag {
@@ -351,17 +887,15 @@ impl From<OrganizationTag> for e::ModeratedTag {
             tag_allow_add,
             tag_allow_remove,
             require_clearance,
+            policy,
         } = from;
-        let label = tag_label;
-        let allow_add = tag_allow_add != 0;
-        let allow_remove = tag_allow_remove != 0;
-        let require_clearance = require_clearance != 0;
-        Self {
-            label,
-            allow_add,
-            allow_remove,
+        load_moderated_tag(
+            tag_label,
+            tag_allow_add,
+            tag_allow_remove,
             require_clearance,
-        }
+            policy,
+        )
     }
 }
 
@@ -373,19 +907,17 @@ impl From<OrganizationTagWithId> for (e::Id, e::ModeratedTag) {
             tag_allow_add,
             tag_allow_remove,
             require_clearance,
+            policy,
         } = from;
-        let label = tag_label;
-        let allow_add = tag_allow_add != 0;
-        let allow_remove = tag_allow_remove != 0;
-        let require_clearance = require_clearance != 0;
         (
             org_id.into(),
-            e::ModeratedTag {
-                label,
-                allow_add,
-                allow_remove,
+            load_moderated_tag(
+                tag_label,
+                tag_allow_add,
+                tag_allow_remove,
                 require_clearance,
-            },
+                policy,
+            ),
         )
     }
 }
@@ -0,0 +1,178 @@
+//! Language-specific analysis of titles and descriptions
+
+use super::MAX_TOKEN_LEN;
+use std::str::FromStr;
+use tantivy::tokenizer::{
+    BoxTokenStream, Language, LowerCaser, RemoveLongFilter, SimpleTokenizer, Stemmer, TextAnalyzer,
+    Token, TokenFilter, TokenStream,
+};
+
+// Only words with at least this length are considered as compounds
+const MIN_COMPOUND_LEN: usize = 7;
+
+// The minimum length of the leading and trailing part of a compound
+const MIN_COMPOUND_HEAD_LEN: usize = 3;
+const MIN_COMPOUND_TAIL_LEN: usize = 4;
+
+/// The language of the titles and descriptions in the index.
+///
+/// Words are reduced to their stem, e.g. "shops" matches "shop".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextLanguage {
+    English,
+    German,
+}
+
+#[derive(Debug)]
+pub struct TextLanguageParseError;
+
+impl FromStr for TextLanguage {
+    type Err = TextLanguageParseError;
+    fn from_str(s: &str) -> Result<TextLanguage, Self::Err> {
+        match &*s.trim().to_lowercase() {
+            "en" | "english" => Ok(TextLanguage::English),
+            "de" | "german" => Ok(TextLanguage::German),
+            _ => Err(TextLanguageParseError),
+        }
+    }
+}
+
+impl TextLanguage {
+    // The name of the tokenizer is stored in the schema, i.e.
+    // the index is rebuilt when the language is changed
+    pub(super) fn tokenizer_name(self) -> &'static str {
+        match self {
+            TextLanguage::English => "text_en",
+            TextLanguage::German => "text_de",
+        }
+    }
+
+    // Compounds are only split when indexing, otherwise
+    // the parts of a compound in a query would match
+    // other compounds with the same parts
+    pub(super) fn text_analyzer(self, split_compounds: bool) -> TextAnalyzer {
+        let analyzer = TextAnalyzer::from(SimpleTokenizer)
+            .filter(LowerCaser)
+            .filter(RemoveLongFilter::limit(MAX_TOKEN_LEN));
+        match self {
+            TextLanguage::English => analyzer.filter(Stemmer::new(Language::English)),
+            TextLanguage::German => {
+                let analyzer = if split_compounds {
+                    analyzer.filter(CompoundTailFilter)
+                } else {
+                    analyzer
+                };
+                analyzer.filter(Stemmer::new(Language::German))
+            }
+        }
+    }
+}
+
+/// Adds the trailing parts of long words at the same position.
+///
+/// The last part of a German compound determines its meaning,
+/// e.g. a "Bioladen" is a "Laden" and should be found when
+/// searching for it. Without a dictionary all trailing parts
+/// with a minimum length are added.
+#[derive(Clone)]
+struct CompoundTailFilter;
+
+impl TokenFilter for CompoundTailFilter {
+    fn transform<'a>(&self, token_stream: BoxTokenStream<'a>) -> BoxTokenStream<'a> {
+        BoxTokenStream::from(CompoundTailTokenStream {
+            tail: token_stream,
+            token: Token::default(),
+            part_offsets: vec![],
+        })
+    }
+}
+
+struct CompoundTailTokenStream<'a> {
+    tail: BoxTokenStream<'a>,
+    token: Token,
+    // Byte offsets of the remaining parts of the current word
+    part_offsets: Vec<usize>,
+}
+
+impl<'a> TokenStream for CompoundTailTokenStream<'a> {
+    fn advance(&mut self) -> bool {
+        if let Some(offset) = self.part_offsets.pop() {
+            let word = &self.tail.token().text;
+            self.token.text.clear();
+            self.token.text.push_str(&word[offset..]);
+            return true;
+        }
+        if !self.tail.advance() {
+            return false;
+        }
+        self.token.clone_from(self.tail.token());
+        self.part_offsets = compound_tail_offsets(&self.token.text);
+        true
+    }
+
+    fn token(&self) -> &Token {
+        &self.token
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.token
+    }
+}
+
+// The longest part is returned last
+fn compound_tail_offsets(word: &str) -> Vec<usize> {
+    let len = word.chars().count();
+    if len < MIN_COMPOUND_LEN {
+        return vec![];
+    }
+    word.char_indices()
+        .map(|(offset, _)| offset)
+        .skip(MIN_COMPOUND_HEAD_LEN)
+        .take(len + 1 - MIN_COMPOUND_HEAD_LEN - MIN_COMPOUND_TAIL_LEN)
+        .rev()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(analyzer: &TextAnalyzer, text: &str) -> Vec<String> {
+        let mut tokens = vec![];
+        analyzer
+            .token_stream(text)
+            .process(&mut |token| tokens.push(token.text.clone()));
+        tokens
+    }
+
+    #[test]
+    fn parse_language() {
+        assert_eq!(TextLanguage::German, "de".parse().unwrap());
+        assert_eq!(TextLanguage::English, " English".parse().unwrap());
+        assert!("fr".parse::<TextLanguage>().is_err());
+    }
+
+    #[test]
+    fn split_trailing_parts_of_compounds() {
+        assert!(compound_tail_offsets("laden").is_empty());
+        let word = "bioladen";
+        let parts: Vec<_> = compound_tail_offsets(word)
+            .into_iter()
+            .map(|offset| &word[offset..])
+            .collect();
+        assert_eq!(vec!["aden", "laden"], parts);
+    }
+
+    #[test]
+    fn stem_words_and_split_compounds_only_when_indexing() {
+        let indexing = TextLanguage::German.text_analyzer(true);
+        let querying = TextLanguage::German.text_analyzer(false);
+        let indexed = tokens(&indexing, "Bioläden");
+        let queried = tokens(&querying, "Läden");
+        assert_eq!(1, queried.len());
+        assert!(indexed.contains(&queried[0]));
+        assert_eq!(1, tokens(&querying, "Bioläden").len());
+        let english = TextLanguage::English.text_analyzer(false);
+        assert_eq!(tokens(&english, "shop"), tokens(&english, "Shops"));
+    }
+}
@@ -5,34 +5,51 @@ use crate::core::{
     },
     entities::{
         Address, AvgRatingValue, AvgRatings, Category, Contact, Event, Id, Place, RatingContext,
-        ReviewStatus, ReviewStatusPrimitive,
+        RegistrationType, ReviewStatus, ReviewStatusPrimitive, TagFrequency,
     },
     util::{
         geo::{LatCoord, LngCoord, MapPoint},
-        time::Timestamp,
+        time::{Timestamp, TimestampMs},
+        time_zone,
     },
 };
 
+mod language;
+
+pub use self::language::TextLanguage;
+
 use anyhow::{bail, Result as Fallible};
 use failure::Fail;
 use num_traits::ToPrimitive;
+use ofdb_core::quality::place_quality_score;
 use std::{
+    fs,
     ops::Bound,
-    path::Path,
-    sync::{Arc, Mutex},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
 };
 use strum::IntoEnumIterator as _;
 use tantivy::{
-    collector::TopDocs,
-    query::{BooleanQuery, Occur, Query, QueryParser, RangeQuery, TermQuery},
+    collector::{FacetCollector, TopDocs},
+    query::{BooleanQuery, FuzzyTermQuery, Occur, Query, QueryParser, RangeQuery, TermQuery},
     schema::*,
-    tokenizer::{LowerCaser, RawTokenizer, RemoveLongFilter, SimpleTokenizer, TextAnalyzer},
+    tokenizer::{
+        LowerCaser, RawTokenizer, RemoveLongFilter, SimpleTokenizer, TextAnalyzer, TokenizerManager,
+    },
     DocAddress, DocId, Document, Index, IndexReader, IndexWriter, ReloadPolicy, Score,
     SegmentReader,
 };
 
 const OVERALL_INDEX_HEAP_SIZE_IN_BYTES: usize = 50_000_000;
 
+// Merging segments in the background can't keep
+// up with the commits if there are more segments
+const MAX_SEGMENTS_WHILE_MERGING: usize = 40;
+
+const INDEX_META_FILE: &str = "meta.json";
+const INDEX_LOCK_FILES: [&str; 2] = [".tantivy-writer.lock", ".tantivy-meta.lock"];
+
 const PLACE_KIND_FLAG: i64 = 1;
 const EVENT_KIND_FLAG: i64 = 2;
 const ALL_KINDS_MASK: i64 = PLACE_KIND_FLAG | EVENT_KIND_FLAG;
@@ -45,6 +62,18 @@ fn get_category_kind_flag(category: &Category) -> i64 {
     }
 }
 
+fn address_facet(value: &str) -> Facet {
+    Facet::from_path(vec![value.trim().to_lowercase()])
+}
+
+fn registration_type_into_i64(registration: RegistrationType) -> i64 {
+    match registration {
+        RegistrationType::Email => 1,
+        RegistrationType::Phone => 2,
+        RegistrationType::Homepage => 3,
+    }
+}
+
 // Shared fields for both places and events
 struct IndexedFields {
     kind: Field,
@@ -61,7 +90,12 @@ struct IndexedFields {
     address_zip: Field,
     address_country: Field,
     address_state: Field,
+    country: Field, // facet for filtering by country
+    region: Field,  // facet for filtering by region, i.e. the state
     contact_name: Field,
+    organizer: Field, // facet for filtering events by organizer, i.e. the contact name
+    tag_facet: Field, // facet for counting the tags of places
+    registration: Field,
     tag: Field,
     ratings_diversity: Field,
     ratings_fairness: Field,
@@ -70,10 +104,28 @@ struct IndexedFields {
     ratings_solidarity: Field,
     ratings_transparency: Field,
     total_rating: Field,
+    rating_count: Field,
+    confirmed_at: Field, // time stamp with millisecond precision of the latest confirmation
+    quality: Field,      // completeness of places (0 - 100)
 }
 
 impl IndexedFields {
-    fn build_schema() -> (Self, Schema) {
+    // The fields that are searched for text
+    fn text_fields(&self) -> Vec<Field> {
+        vec![
+            self.title,
+            self.description,
+            self.address_street,
+            self.address_city,
+            self.address_zip,
+            self.address_country,
+            self.address_state,
+            self.contact_name,
+        ]
+    }
+
+    // The title and description are analyzed according to the language (if any)
+    fn build_schema(language: Option<TextLanguage>) -> (Self, Schema) {
         let id_options = TextOptions::default()
             .set_indexing_options(
                 TextFieldIndexing::default()
@@ -96,7 +148,16 @@ impl IndexedFields {
         );
         // Text fields that are returned as part of the search result
         // additionally need to be stored explicitly
-        let stored_text_options = indexed_text_options.clone().set_stored();
+        let stored_text_options = TextOptions::default()
+            .set_indexing_options(
+                TextFieldIndexing::default()
+                    .set_tokenizer(language.map_or(TEXT_TOKENIZER, TextLanguage::tokenizer_name))
+                    .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+            )
+            .set_stored();
+        // The street, city and zip of places are compared
+        // when searching for duplicates
+        let stored_address_options = indexed_text_options.clone().set_stored();
         let mut schema_builder = SchemaBuilder::default();
         let fields = Self {
             kind: schema_builder.add_i64_field("kind", INDEXED),
@@ -109,13 +170,18 @@ impl IndexedFields {
             title: schema_builder.add_text_field("tit", stored_text_options.clone()),
             description: schema_builder.add_text_field("dsc", stored_text_options),
             contact_name: schema_builder.add_text_field("cnt_name", indexed_text_options.clone()),
+            organizer: schema_builder.add_facet_field("organizer"),
+            tag_facet: schema_builder.add_facet_field("tag_facet"),
+            registration: schema_builder.add_i64_field("registration", INDEXED),
             address_street: schema_builder
-                .add_text_field("adr_street", indexed_text_options.clone()),
-            address_city: schema_builder.add_text_field("adr_city", indexed_text_options.clone()),
-            address_zip: schema_builder.add_text_field("adr_zip", indexed_text_options.clone()),
+                .add_text_field("adr_street", stored_address_options.clone()),
+            address_city: schema_builder.add_text_field("adr_city", stored_address_options.clone()),
+            address_zip: schema_builder.add_text_field("adr_zip", stored_address_options),
             address_country: schema_builder
                 .add_text_field("adr_country", indexed_text_options.clone()),
             address_state: schema_builder.add_text_field("adr_state", indexed_text_options),
+            country: schema_builder.add_facet_field("country"),
+            region: schema_builder.add_facet_field("region"),
             tag: schema_builder.add_text_field("tag", tag_options),
             ratings_diversity: schema_builder.add_f64_field("rat_diversity", STORED),
             ratings_fairness: schema_builder.add_f64_field("rat_fairness", STORED),
@@ -123,7 +189,10 @@ impl IndexedFields {
             ratings_renewable: schema_builder.add_f64_field("rat_renewable", STORED),
             ratings_solidarity: schema_builder.add_f64_field("rat_solidarity", STORED),
             ratings_transparency: schema_builder.add_f64_field("rat_transparency", STORED),
-            total_rating: schema_builder.add_u64_field("rat_total", STORED | FAST),
+            total_rating: schema_builder.add_u64_field("rat_total", INDEXED | STORED | FAST),
+            rating_count: schema_builder.add_u64_field("rat_count", INDEXED | STORED),
+            confirmed_at: schema_builder.add_i64_field("confirmed_at", INDEXED | FAST),
+            quality: schema_builder.add_u64_field("quality", INDEXED | STORED | FAST),
         };
         (fields, schema_builder.build())
     }
@@ -203,10 +272,31 @@ impl IndexedFields {
                     place.ratings.transparency = fv.value().f64_value().into();
                 }
                 fv if fv.field() == self.total_rating => (),
-                // Address fields are currently not stored
-                //fv if fv.field() == self.address_street => (),
-                //fv if fv.field() == self.address_city => (),
-                //fv if fv.field() == self.address_zip => (),
+                fv if fv.field() == self.rating_count => {
+                    place.ratings.count = fv.value().u64_value() as u32;
+                }
+                fv if fv.field() == self.quality => {
+                    place.quality = fv.value().u64_value() as u8;
+                }
+                fv if fv.field() == self.country => (),
+                fv if fv.field() == self.region => (),
+                fv if fv.field() == self.organizer => (),
+                fv if fv.field() == self.address_street => {
+                    let address = place.address.get_or_insert_with(Default::default);
+                    debug_assert!(address.street.is_none());
+                    address.street = fv.value().text().map(Into::into);
+                }
+                fv if fv.field() == self.address_city => {
+                    let address = place.address.get_or_insert_with(Default::default);
+                    debug_assert!(address.city.is_none());
+                    address.city = fv.value().text().map(Into::into);
+                }
+                fv if fv.field() == self.address_zip => {
+                    let address = place.address.get_or_insert_with(Default::default);
+                    debug_assert!(address.zip.is_none());
+                    address.zip = fv.value().text().map(Into::into);
+                }
+                // The country and state are currently not stored
                 //fv if fv.field() == self.address_country => (),
                 //fv if fv.field() == self.address_state => (),
                 fv => {
@@ -221,6 +311,33 @@ impl IndexedFields {
         }
         place
     }
+
+    fn add_address(&self, doc: &mut Document, address: &Address) {
+        let Address {
+            street,
+            city,
+            zip,
+            country,
+            state,
+        } = address;
+        if let Some(street) = street {
+            doc.add_text(self.address_street, street);
+        }
+        if let Some(city) = city {
+            doc.add_text(self.address_city, city);
+        }
+        if let Some(zip) = zip {
+            doc.add_text(self.address_zip, zip);
+        }
+        if let Some(country) = country {
+            doc.add_text(self.address_country, country);
+            doc.add_facet(self.country, address_facet(country));
+        }
+        if let Some(state) = state {
+            doc.add_text(self.address_state, state);
+            doc.add_facet(self.region, address_facet(state));
+        }
+    }
 }
 
 pub(crate) struct TantivyIndex {
@@ -228,6 +345,43 @@ pub(crate) struct TantivyIndex {
     index_reader: IndexReader,
     index_writer: IndexWriter,
     text_query_parser: QueryParser,
+    // Analyzes the words of the title and description in queries
+    text_language_analyzer: Option<TextAnalyzer>,
+    metrics: Arc<Mutex<IndexMetrics>>,
+}
+
+/// Health of the full-text search index
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexMetrics {
+    pub commits: u64,
+    pub last_commit_duration: Duration,
+    pub max_commit_duration: Duration,
+    /// Searchable segments after the last commit
+    pub segments: usize,
+    /// Added, updated or removed documents that
+    /// have not been committed yet
+    pub pending_docs: usize,
+}
+
+impl IndexMetrics {
+    pub fn merges_behind(&self) -> bool {
+        self.segments > MAX_SEGMENTS_WHILE_MERGING
+    }
+
+    fn record_commit(&mut self, duration: Duration, segments: usize) {
+        self.commits += 1;
+        self.last_commit_duration = duration;
+        self.max_commit_duration = self.max_commit_duration.max(duration);
+        self.segments = segments;
+        self.pending_docs = 0;
+    }
+}
+
+fn lock_metrics(metrics: &Mutex<IndexMetrics>) -> MutexGuard<IndexMetrics> {
+    match metrics.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
 }
 
 const ID_TOKENIZER: &str = "raw";
@@ -236,20 +390,54 @@ const TEXT_TOKENIZER: &str = "default";
 
 const MAX_TOKEN_LEN: usize = 40;
 
-fn register_tokenizers(index: &Index) {
+// Typos are only tolerated in words with at least this length
+const MIN_FUZZY_WORD_LEN: usize = 4;
+
+// Two typos are only tolerated in words with at least this length
+const MIN_FUZZY_WORD_LEN_FOR_TWO_TYPOS: usize = 8;
+
+// The Levenshtein automatons of Tantivy support up to 2 edits
+const MAX_FUZZINESS: u8 = 2;
+
+fn fuzzy_edit_distance(word: &str, fuzziness: u8) -> u8 {
+    let len = word.chars().count();
+    let max_distance = if len < MIN_FUZZY_WORD_LEN {
+        0
+    } else if len < MIN_FUZZY_WORD_LEN_FOR_TWO_TYPOS {
+        1
+    } else {
+        MAX_FUZZINESS
+    };
+    fuzziness.min(max_distance)
+}
+
+fn suggestion_edit_distance(word: &str) -> u8 {
+    fuzzy_edit_distance(word, 1)
+}
+
+// Indexing and querying use different tokenizers
+// for text in a language with compounds
+fn register_tokenizers(
+    tokenizers: &TokenizerManager,
+    language: Option<TextLanguage>,
+    indexing: bool,
+) {
     // Predefined tokenizers
-    debug_assert!(index.tokenizers().get(ID_TOKENIZER).is_some());
-    debug_assert!(index.tokenizers().get(TEXT_TOKENIZER).is_some());
+    debug_assert!(tokenizers.get(ID_TOKENIZER).is_some());
+    debug_assert!(tokenizers.get(TEXT_TOKENIZER).is_some());
     // Custom tokenizer(s)
-    debug_assert!(index.tokenizers().get(TAG_TOKENIZER).is_none());
+    debug_assert!(tokenizers.get(TAG_TOKENIZER).is_none());
     let tag_tokenizer = TextAnalyzer::from(RawTokenizer)
         .filter(LowerCaser)
         .filter(RemoveLongFilter::limit(MAX_TOKEN_LEN));
-    index.tokenizers().register(TAG_TOKENIZER, tag_tokenizer);
+    tokenizers.register(TAG_TOKENIZER, tag_tokenizer);
     let text_tokenizer = TextAnalyzer::from(SimpleTokenizer)
         .filter(LowerCaser)
         .filter(RemoveLongFilter::limit(MAX_TOKEN_LEN));
-    index.tokenizers().register(TEXT_TOKENIZER, text_tokenizer);
+    tokenizers.register(TEXT_TOKENIZER, text_tokenizer);
+    if let Some(language) = language {
+        tokenizers.register(language.tokenizer_name(), language.text_analyzer(indexing));
+    }
 }
 
 fn f64_to_u64(val: f64, min: f64, max: f64) -> u64 {
@@ -295,24 +483,40 @@ fn u64_to_avg_rating(val: u64) -> AvgRatingValue {
     .into()
 }
 
+const MILLIS_PER_YEAR: f64 = 365.0 * 24.0 * 60.0 * 60.0 * 1000.0;
+
+// Places that have just been confirmed get a boost factor of 2 that
+// is halved after one year and approaches 1 for older confirmations.
+// Places that have never been confirmed are not boosted at all.
+fn freshness_boost_factor(now: TimestampMs, confirmed_at: i64) -> f32 {
+    if confirmed_at <= 0 {
+        return 1.0;
+    }
+    let age = now.into_inner().saturating_sub(confirmed_at).max(0) as f64;
+    (1.0 + 1.0 / (1.0 + age / MILLIS_PER_YEAR)) as f32
+}
+
 #[derive(Copy, Clone, Debug)]
 enum TopDocsMode {
     Score,
     Rating,
     ScoreBoostedByRating,
+    Quality,
 }
 
 impl TantivyIndex {
     #[allow(dead_code)]
-    pub fn create_in_ram() -> Fallible<Self> {
+    pub fn create_in_ram(language: Option<TextLanguage>) -> Fallible<Self> {
         let no_path: Option<&Path> = None;
-        Self::create(no_path)
+        Self::create(no_path, language)
     }
 
-    pub fn create<P: AsRef<Path>>(path: Option<P>) -> Fallible<Self> {
-        let (fields, schema) = IndexedFields::build_schema();
+    pub fn create<P: AsRef<Path>>(
+        path: Option<P>,
+        language: Option<TextLanguage>,
+    ) -> Fallible<Self> {
+        let (fields, schema) = IndexedFields::build_schema(language);
 
-        // TODO: Open index from existing directory
         let index = if let Some(path) = path {
             info!(
                 "Creating full-text search index in directory: {}",
@@ -323,8 +527,72 @@ impl TantivyIndex {
             warn!("Creating full-text search index in RAM");
             Index::create_in_ram(schema)
         };
+        Self::with_index(index, fields, language)
+    }
 
-        register_tokenizers(&index);
+    /// Opens the index in the directory or restores the snapshot
+    /// if the index is missing or corrupt. A new index is created
+    /// if neither the index nor the snapshot could be opened.
+    ///
+    /// Indexes that have been created for a different language
+    /// are incompatible and replaced by a new index.
+    pub fn open_or_restore(
+        path: &Path,
+        snapshot_dir: Option<&Path>,
+        language: Option<TextLanguage>,
+    ) -> Fallible<Self> {
+        match Self::open(path, language) {
+            Ok(Some(index)) => return Ok(index),
+            Ok(None) => {}
+            Err(err) => warn!(
+                "Discarding corrupt full-text search index in directory {}: {}",
+                path.display(),
+                err
+            ),
+        }
+        if let Some(snapshot_dir) = snapshot_dir.filter(|dir| dir.join(INDEX_META_FILE).exists()) {
+            info!(
+                "Restoring full-text search index from snapshot: {}",
+                snapshot_dir.display()
+            );
+            reset_dir(path)?;
+            copy_index_files(snapshot_dir, path)?;
+            match Self::open(path, language) {
+                Ok(Some(index)) => return Ok(index),
+                Ok(None) => {}
+                Err(err) => warn!(
+                    "Discarding corrupt full-text search index snapshot {}: {}",
+                    snapshot_dir.display(),
+                    err
+                ),
+            }
+        }
+        reset_dir(path)?;
+        Self::create(Some(path), language)
+    }
+
+    fn open(path: &Path, language: Option<TextLanguage>) -> Fallible<Option<Self>> {
+        if !path.join(INDEX_META_FILE).exists() {
+            return Ok(None);
+        }
+        let (fields, schema) = IndexedFields::build_schema(language);
+        let index = Index::open_in_dir(path).map_err(Fail::compat)?;
+        if index.schema() != schema {
+            bail!("Incompatible schema");
+        }
+        info!(
+            "Opening full-text search index in directory: {}",
+            path.display()
+        );
+        Self::with_index(index, fields, language).map(Some)
+    }
+
+    fn with_index(
+        index: Index,
+        fields: IndexedFields,
+        language: Option<TextLanguage>,
+    ) -> Fallible<Self> {
+        register_tokenizers(index.tokenizers(), language, true);
 
         // Prefer to manually reload the index reader during `flush()`
         // to ensure that all committed changes become visible immediately.
@@ -338,27 +606,25 @@ impl TantivyIndex {
         let index_writer = index
             .writer(OVERALL_INDEX_HEAP_SIZE_IN_BYTES)
             .map_err(Fail::compat)?;
-        let text_query_parser = QueryParser::for_index(
-            &index,
-            vec![
-                fields.title,
-                fields.description,
-                fields.address_street,
-                fields.address_city,
-                fields.address_zip,
-                fields.address_country,
-                fields.address_state,
-                fields.contact_name,
-            ],
-        );
+        let query_tokenizers = TokenizerManager::default();
+        register_tokenizers(&query_tokenizers, language, false);
+        let text_query_parser =
+            QueryParser::new(index.schema(), fields.text_fields(), query_tokenizers);
+        let text_language_analyzer = language.map(|language| language.text_analyzer(false));
         Ok(Self {
             fields,
             index_reader,
             index_writer,
             text_query_parser,
+            text_language_analyzer,
+            metrics: Default::default(),
         })
     }
 
+    fn count_pending_doc(&self) {
+        lock_metrics(&self.metrics).pending_docs += 1;
+    }
+
     fn build_query(
         &self,
         query_mode: IndexQueryMode,
@@ -520,6 +786,41 @@ impl TantivyIndex {
             }
         }
 
+        // Country
+        if let Some(ref country) = query.country {
+            debug!("Query country: {}", country);
+            let country_term = Term::from_facet(self.fields.country, &address_facet(country));
+            let country_query = TermQuery::new(country_term, IndexRecordOption::Basic);
+            sub_queries.push((Occur::Must, Box::new(country_query)));
+        }
+
+        // Region
+        if let Some(ref region) = query.region {
+            debug!("Query region: {}", region);
+            let region_term = Term::from_facet(self.fields.region, &address_facet(region));
+            let region_query = TermQuery::new(region_term, IndexRecordOption::Basic);
+            sub_queries.push((Occur::Must, Box::new(region_query)));
+        }
+
+        // Organizer
+        if let Some(ref organizer) = query.organizer {
+            debug!("Query organizer: {}", organizer);
+            let organizer_term = Term::from_facet(self.fields.organizer, &address_facet(organizer));
+            let organizer_query = TermQuery::new(organizer_term, IndexRecordOption::Basic);
+            sub_queries.push((Occur::Must, Box::new(organizer_query)));
+        }
+
+        // Registration
+        if let Some(registration) = query.registration {
+            debug!("Query registration: {:?}", registration);
+            let registration_term = Term::from_field_i64(
+                self.fields.registration,
+                registration_type_into_i64(registration),
+            );
+            let registration_query = TermQuery::new(registration_term, IndexRecordOption::Basic);
+            sub_queries.push((Occur::Must, Box::new(registration_query)));
+        }
+
         let merged_tags = Category::merge_ids_into_tags(
             &query
                 .categories
@@ -587,6 +888,33 @@ impl TantivyIndex {
             };
         }
 
+        // Excluded categories and hash tags
+        let (excluded_tags, excluded_categories) =
+            Category::split_from_tags(Category::merge_ids_into_tags(
+                &query
+                    .excluded_categories
+                    .iter()
+                    .map(|c| Id::from(*c))
+                    .collect::<Vec<_>>(),
+                query.excluded_hash_tags.clone(),
+            ));
+        for category in &excluded_categories {
+            debug!("Query excluded category: {:?}", category);
+            let term = match get_category_kind_flag(category) {
+                EVENT_KIND_FLAG => Term::from_field_i64(self.fields.kind, EVENT_KIND_FLAG),
+                _ => Term::from_field_text(self.fields.tag, &category.tag),
+            };
+            let query = TermQuery::new(term, IndexRecordOption::Basic);
+            sub_queries.push((Occur::MustNot, Box::new(query)));
+        }
+        for tag in &excluded_tags {
+            debug!("Query excluded hash tag: {}", tag);
+            debug_assert!(!tag.trim().is_empty());
+            let tag_term = Term::from_field_text(self.fields.tag, &tag.to_lowercase());
+            let tag_query = TermQuery::new(tag_term, IndexRecordOption::Basic);
+            sub_queries.push((Occur::MustNot, Box::new(tag_query)));
+        }
+
         // Hash tags (mandatory)
         for tag in &tags {
             debug!("Query hash tag (mandatory): {}", tag);
@@ -606,6 +934,21 @@ impl TantivyIndex {
             let text = text.to_lowercase();
             match self.text_query_parser.parse_query(&text) {
                 Ok(text_query) => {
+                    let fuzzy_text_query = query
+                        .fuzziness
+                        .filter(|fuzziness| *fuzziness > 0)
+                        .and_then(|fuzziness| self.build_fuzzy_text_query(&text, fuzziness));
+                    let text_query: Box<dyn Query> =
+                        if let Some(fuzzy_text_query) = fuzzy_text_query {
+                            debug!("Query text with fuzziness: {:?}", query.fuzziness);
+                            // Exact matches are scored higher than fuzzy matches
+                            Box::new(BooleanQuery::from(vec![
+                                (Occur::Should, text_query),
+                                (Occur::Should, Box::new(fuzzy_text_query) as Box<dyn Query>),
+                            ]))
+                        } else {
+                            text_query
+                        };
                     if query.hash_tags.is_empty() && query.text_tags.is_empty() {
                         sub_queries.push((Occur::Must, Box::new(text_query)));
                     } else {
@@ -655,12 +998,48 @@ impl TantivyIndex {
             sub_queries.push((Occur::Must, Box::new(ts_max_query)));
         }
 
+        // Ratings
+        if let Some(min_avg_rating) = query.min_avg_rating {
+            let rating_query = RangeQuery::new_u64_bounds(
+                self.fields.total_rating,
+                Bound::Included(avg_rating_to_u64(min_avg_rating.clamp())),
+                Bound::Unbounded,
+            );
+            sub_queries.push((Occur::Must, Box::new(rating_query)));
+        }
+        if let Some(min_rating_count) = query.min_rating_count {
+            let rating_count_query = RangeQuery::new_u64_bounds(
+                self.fields.rating_count,
+                Bound::Included(min_rating_count.into()),
+                Bound::Unbounded,
+            );
+            sub_queries.push((Occur::Must, Box::new(rating_count_query)));
+        }
+
+        // Review recency
+        if let Some(confirmed_since) = query.confirmed_since {
+            let confirmed_query = RangeQuery::new_i64_bounds(
+                self.fields.confirmed_at,
+                Bound::Included(confirmed_since.into_inner()),
+                Bound::Unbounded,
+            );
+            sub_queries.push((Occur::Must, Box::new(confirmed_query)));
+        }
+
         // Boosting the score by the rating does only make sense if the
         // query actually contains search terms or tags. Otherwise the
         // results are sorted only by their rating, e.g. if the query
         // contains just the bounding box or ids.
+        // The freshness boost is applied to the score in any case.
+        // Ordering places by their quality overrides all other modes.
+        let order_by_quality =
+            query.order_by_quality && matches!(query_mode, IndexQueryMode::WithRating);
         if text_and_tags_queries.is_empty() {
             let mode = match query_mode {
+                _ if order_by_quality => TopDocsMode::Quality,
+                IndexQueryMode::WithRating if query.boost_freshness => {
+                    TopDocsMode::ScoreBoostedByRating
+                }
                 IndexQueryMode::WithRating => TopDocsMode::Rating,
                 IndexQueryMode::WithoutRating => TopDocsMode::Score,
             };
@@ -671,6 +1050,7 @@ impl TantivyIndex {
                 Box::new(BooleanQuery::from(text_and_tags_queries)),
             ));
             let mode = match query_mode {
+                _ if order_by_quality => TopDocsMode::Quality,
                 IndexQueryMode::WithRating => TopDocsMode::ScoreBoostedByRating,
                 IndexQueryMode::WithoutRating => TopDocsMode::Score,
             };
@@ -678,6 +1058,95 @@ impl TantivyIndex {
         }
     }
 
+    // Words in the title and description are matched
+    // by their stem if the language is known
+    fn build_text_term(&self, field: Field, word: &str) -> Term {
+        if let Some(analyzer) = &self.text_language_analyzer {
+            if field == self.fields.title || field == self.fields.description {
+                let mut token_stream = analyzer.token_stream(word);
+                if token_stream.advance() {
+                    return Term::from_field_text(field, &token_stream.token().text);
+                }
+            }
+        }
+        Term::from_field_text(field, word)
+    }
+
+    // Tolerates typos in the words of the text while
+    // preserving the semantics of the +/- operators
+    fn build_fuzzy_text_query(&self, text: &str, fuzziness: u8) -> Option<BooleanQuery> {
+        let text_fields = self.fields.text_fields();
+        let mut word_queries: Vec<(Occur, Box<dyn Query>)> = vec![];
+        for token in text.split_whitespace() {
+            let (occur, token) = if let Some(token) = token.strip_prefix('+') {
+                (Occur::Must, token)
+            } else if let Some(token) = token.strip_prefix('-') {
+                (Occur::MustNot, token)
+            } else {
+                (Occur::Should, token)
+            };
+            for word in token
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+            {
+                // Excluded words must not match exactly
+                let distance = match occur {
+                    Occur::MustNot => 0,
+                    _ => fuzzy_edit_distance(word, fuzziness),
+                };
+                let field_queries: Vec<(Occur, Box<dyn Query>)> = text_fields
+                    .iter()
+                    .map(|field| {
+                        let term = self.build_text_term(*field, word);
+                        let query = FuzzyTermQuery::new(term, distance, true);
+                        (Occur::Should, Box::new(query) as Box<dyn Query>)
+                    })
+                    .collect();
+                word_queries.push((occur, Box::new(BooleanQuery::from(field_queries))));
+            }
+        }
+        if word_queries
+            .iter()
+            .all(|(occur, _)| *occur == Occur::MustNot)
+        {
+            return None;
+        }
+        Some(BooleanQuery::from(word_queries))
+    }
+
+    fn build_suggestion_query(&self, text: &str) -> Option<BooleanQuery> {
+        let text = text.to_lowercase();
+        let words: Vec<_> = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect();
+        let (last_word, complete_words) = words.split_last()?;
+        let mut title_queries: Vec<(Occur, Box<dyn Query>)> = complete_words
+            .iter()
+            .map(|word| {
+                let term = self.build_text_term(self.fields.title, word);
+                let query = FuzzyTermQuery::new(term, suggestion_edit_distance(word), true);
+                (Occur::Must, Box::new(query) as Box<dyn Query>)
+            })
+            .collect();
+        let last_term = self.build_text_term(self.fields.title, last_word);
+        let last_query =
+            FuzzyTermQuery::new_prefix(last_term, suggestion_edit_distance(last_word), true);
+        title_queries.push((Occur::Must, Box::new(last_query)));
+        // Tags are indexed as a whole and consist of words
+        // that are separated by dashes
+        let tag = words.join("-");
+        let tag_term = Term::from_field_text(self.fields.tag, &tag);
+        let tag_query = FuzzyTermQuery::new_prefix(tag_term, suggestion_edit_distance(&tag), true);
+        Some(BooleanQuery::from(vec![
+            (
+                Occur::Should,
+                Box::new(BooleanQuery::from(title_queries)) as Box<dyn Query>,
+            ),
+            (Occur::Should, Box::new(tag_query)),
+        ]))
+    }
+
     #[allow(clippy::absurd_extreme_comparisons)]
     fn query_documents<D>(
         &self,
@@ -714,9 +1183,12 @@ impl TantivyIndex {
                 }
                 Ok(doc_collector)
             }
-            TopDocsMode::Rating => {
-                let collector =
-                    TopDocs::with_limit(limit).order_by_u64_field(self.fields.total_rating);
+            TopDocsMode::Rating | TopDocsMode::Quality => {
+                let order_field = match top_docs_mode {
+                    TopDocsMode::Quality => self.fields.quality,
+                    _ => self.fields.total_rating,
+                };
+                let collector = TopDocs::with_limit(limit).order_by_u64_field(order_field);
                 searcher
                     .search(&search_query, &collector)
                     .map_err(Fail::compat)?;
@@ -738,11 +1210,18 @@ impl TantivyIndex {
             TopDocsMode::ScoreBoostedByRating => {
                 let collector = {
                     let total_rating_field = self.fields.total_rating;
+                    let confirmed_at_field = self.fields.confirmed_at;
+                    let boost_freshness = query.boost_freshness;
+                    let now = TimestampMs::now();
                     TopDocs::with_limit(limit).tweak_score(move |segment_reader: &SegmentReader| {
                         let total_rating_reader = segment_reader
                             .fast_fields()
                             .u64(total_rating_field)
                             .unwrap();
+                        let confirmed_at_reader = segment_reader
+                            .fast_fields()
+                            .i64(confirmed_at_field)
+                            .unwrap();
 
                         move |doc: DocId, original_score: Score| {
                             let total_rating =
@@ -766,7 +1245,12 @@ impl TantivyIndex {
                             // rated entries over entries that received a much higher score.
                             debug_assert!(original_score >= 0.0);
                             let unboosted_score = (1.0 + original_score).log2();
-                            unboosted_score * (boost_factor as f32)
+                            let freshness_factor = if boost_freshness {
+                                freshness_boost_factor(now, confirmed_at_reader.get(doc))
+                            } else {
+                                1.0
+                            };
+                            unboosted_score * (boost_factor as f32) * freshness_factor
                         }
                     })
                 };
@@ -868,10 +1352,21 @@ impl IdIndex for TantivyIndex {
 
 impl Indexer for TantivyIndex {
     fn flush_index(&mut self) -> Fallible<()> {
+        let started = Instant::now();
         self.index_writer.commit().map_err(Fail::compat)?;
         // Manually reload the reader to ensure that all committed changes
         // become visible immediately.
         self.index_reader.reload().map_err(Fail::compat)?;
+        let segments = self.index_reader.searcher().segment_readers().len();
+        let mut metrics = lock_metrics(&self.metrics);
+        let merges_behind = metrics.merges_behind();
+        metrics.record_commit(started.elapsed(), segments);
+        if metrics.merges_behind() && !merges_behind {
+            warn!(
+                "Merging of the full-text search index falls behind: {} segments",
+                segments
+            );
+        }
         Ok(())
     }
 }
@@ -880,6 +1375,7 @@ impl IdIndexer for TantivyIndex {
     fn remove_by_id(&self, id: &Id) -> Fallible<()> {
         let id_term = Term::from_field_text(self.fields.id, id.as_str());
         self.index_writer.delete_term(id_term);
+        self.count_pending_doc();
         Ok(())
     }
 }
@@ -889,6 +1385,7 @@ impl PlaceIndexer for TantivyIndex {
         &self,
         place: &Place,
         status: ReviewStatus,
+        last_confirmed_at: Option<TimestampMs>,
         ratings: &AvgRatings,
     ) -> Fallible<()> {
         let id_term = Term::from_field_text(self.fields.id, place.id.as_ref());
@@ -899,33 +1396,17 @@ impl PlaceIndexer for TantivyIndex {
             doc.add_i64(self.fields.status, status);
         }
         doc.add_text(self.fields.id, place.id.as_ref());
-        doc.add_f64(self.fields.lat, place.location.pos.lat().to_deg());
-        doc.add_f64(self.fields.lng, place.location.pos.lng().to_deg());
+        // Places with an invalid position are excluded from all geo queries
+        if place.location.pos.is_valid() {
+            doc.add_f64(self.fields.lat, place.location.pos.lat().to_deg());
+            doc.add_f64(self.fields.lng, place.location.pos.lng().to_deg());
+        } else {
+            warn!("Indexing place {} without a valid position", place.id);
+        }
         doc.add_text(self.fields.title, &place.title);
         doc.add_text(self.fields.description, &place.description);
         if let Some(ref address) = place.location.address {
-            let Address {
-                street,
-                city,
-                zip,
-                country,
-                state,
-            } = address;
-            if let Some(street) = street {
-                doc.add_text(self.fields.address_street, street);
-            }
-            if let Some(city) = city {
-                doc.add_text(self.fields.address_city, city);
-            }
-            if let Some(zip) = zip {
-                doc.add_text(self.fields.address_zip, zip);
-            }
-            if let Some(country) = country {
-                doc.add_text(self.fields.address_country, country);
-            }
-            if let Some(state) = state {
-                doc.add_text(self.fields.address_country, state);
-            }
+            self.fields.add_address(&mut doc, address);
         }
         if let Some(ref contact) = place.contact {
             let Contact { name, .. } = contact;
@@ -935,8 +1416,16 @@ impl PlaceIndexer for TantivyIndex {
         }
         for tag in &place.tags {
             doc.add_text(self.fields.tag, tag);
+            doc.add_facet(self.fields.tag_facet, Facet::from_path(vec![tag]));
         }
         doc.add_u64(self.fields.total_rating, avg_rating_to_u64(ratings.total()));
+        doc.add_u64(self.fields.rating_count, ratings.count.into());
+        if let Some(last_confirmed_at) = last_confirmed_at {
+            doc.add_i64(self.fields.confirmed_at, last_confirmed_at.into_inner());
+        }
+        let quality =
+            place_quality_score(place, last_confirmed_at, ratings.count, TimestampMs::now());
+        doc.add_u64(self.fields.quality, quality.into());
         doc.add_f64(self.fields.ratings_diversity, ratings.diversity.into());
         doc.add_f64(self.fields.ratings_fairness, ratings.fairness.into());
         doc.add_f64(self.fields.ratings_humanity, ratings.humanity.into());
@@ -947,6 +1436,7 @@ impl PlaceIndexer for TantivyIndex {
             ratings.transparency.into(),
         );
         self.index_writer.add_document(doc);
+        self.count_pending_doc();
         Ok(())
     }
 }
@@ -962,37 +1452,16 @@ impl EventIndexer for TantivyIndex {
             doc.add_f64(self.fields.lat, location.pos.lat().to_deg());
             doc.add_f64(self.fields.lng, location.pos.lng().to_deg());
             if let Some(address) = &location.address {
-                let Address {
-                    street,
-                    city,
-                    zip,
-                    country,
-                    state,
-                } = address;
-                if let Some(street) = street {
-                    doc.add_text(self.fields.address_street, street);
-                }
-                if let Some(city) = city {
-                    doc.add_text(self.fields.address_city, city);
-                }
-                if let Some(zip) = zip {
-                    doc.add_text(self.fields.address_zip, zip);
-                }
-                if let Some(country) = country {
-                    doc.add_text(self.fields.address_country, country);
-                }
-                if let Some(state) = state {
-                    doc.add_text(self.fields.address_country, state);
-                }
+                self.fields.add_address(&mut doc, address);
             }
         }
-        doc.add_i64(
-            self.fields.ts_min,
-            Timestamp::from(event.start).into_inner(),
-        );
-        if let Some(end) = event.end {
-            debug_assert!(event.start <= end);
-            doc.add_i64(self.fields.ts_max, Timestamp::from(end).into_inner());
+        // Recurring events are found by the start and end of any occurrence
+        for (start, end) in time_zone::occurrence_times(event) {
+            doc.add_i64(self.fields.ts_min, Timestamp::from(start).into_inner());
+            if let Some(end) = end {
+                debug_assert!(start <= end);
+                doc.add_i64(self.fields.ts_max, Timestamp::from(end).into_inner());
+            }
         }
         doc.add_text(self.fields.title, &event.title);
         if let Some(ref description) = event.description {
@@ -1002,12 +1471,20 @@ impl EventIndexer for TantivyIndex {
             let Contact { name, .. } = contact;
             if let Some(contact_name) = name {
                 doc.add_text(self.fields.contact_name, contact_name);
+                doc.add_facet(self.fields.organizer, address_facet(contact_name));
             }
         }
+        if let Some(registration) = event.registration {
+            doc.add_i64(
+                self.fields.registration,
+                registration_type_into_i64(registration),
+            );
+        }
         for tag in &event.tags {
             doc.add_text(self.fields.tag, tag);
         }
         self.index_writer.add_document(doc);
+        self.count_pending_doc();
         Ok(())
     }
 }
@@ -1018,23 +1495,155 @@ impl PlaceIndex for TantivyIndex {
         self.query_documents(IndexQueryMode::WithRating, query, limit, collector)
             .map(Into::into)
     }
+
+    fn count_place_tags(&self, query: &IndexQuery) -> Fallible<Vec<TagFrequency>> {
+        let (search_query, _) = self.build_query(IndexQueryMode::WithRating, query);
+        let mut collector = FacetCollector::for_field(self.fields.tag_facet);
+        collector.add_facet(Facet::root());
+        let searcher = self.index_reader.searcher();
+        let facet_counts = searcher
+            .search(&search_query, &collector)
+            .map_err(Fail::compat)?;
+        let mut tag_counts: Vec<_> = facet_counts
+            .get(Facet::root())
+            // Tags are indexed as facets with a single path segment
+            .map(|(facet, count)| TagFrequency(facet.encoded_str().to_owned(), count))
+            .collect();
+        tag_counts.sort_by(|t1, t2| t2.1.cmp(&t1.1).then_with(|| t1.0.cmp(&t2.0)));
+        Ok(tag_counts)
+    }
+
+    fn query_place_suggestions(
+        &self,
+        query: &IndexQuery,
+        text: &str,
+        limit: usize,
+    ) -> Fallible<Vec<IndexedPlace>> {
+        let suggestion_query = match self.build_suggestion_query(text) {
+            Some(suggestion_query) => suggestion_query,
+            None => return Ok(vec![]),
+        };
+        let (filter_query, _) = self.build_query(IndexQueryMode::WithoutRating, query);
+        let search_query = BooleanQuery::from(vec![
+            (Occur::Must, Box::new(filter_query) as Box<dyn Query>),
+            (Occur::Must, Box::new(suggestion_query)),
+        ]);
+        // Fuzzy matches are not scored
+        let collector = TopDocs::with_limit(limit).order_by_u64_field(self.fields.total_rating);
+        let searcher = self.index_reader.searcher();
+        let top_docs = searcher
+            .search(&search_query, &collector)
+            .map_err(Fail::compat)?;
+        let mut doc_collector = IndexedPlaceCollector::with_capacity(&self.fields, top_docs.len());
+        for (_, doc_addr) in top_docs {
+            match searcher.doc(doc_addr) {
+                Ok(doc) => {
+                    doc_collector.collect_document(doc_addr, doc);
+                }
+                Err(err) => {
+                    warn!("Failed to load document {:?}: {}", doc_addr, err);
+                }
+            }
+        }
+        Ok(doc_collector.into())
+    }
 }
 
 impl EventAndPlaceIndexer for TantivyIndex {}
 
+fn reset_dir(path: &Path) -> Fallible<()> {
+    if path.exists() {
+        fs::remove_dir_all(path)?;
+    }
+    fs::create_dir_all(path)?;
+    Ok(())
+}
+
+fn copy_index_files(from_dir: &Path, to_dir: &Path) -> Fallible<()> {
+    for entry in fs::read_dir(from_dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let file_name = entry.file_name();
+        if INDEX_LOCK_FILES.iter().any(|lock| file_name == *lock) {
+            continue;
+        }
+        fs::copy(entry.path(), to_dir.join(file_name))?;
+    }
+    Ok(())
+}
+
+/// The index, the directory where it is stored (if any)
+/// and its metrics
 #[derive(Clone)]
-pub struct SearchEngine(Arc<Mutex<Box<dyn EventAndPlaceIndexer + Send>>>);
+pub struct SearchEngine(
+    Arc<Mutex<Box<dyn EventAndPlaceIndexer + Send>>>,
+    Option<PathBuf>,
+    Arc<Mutex<IndexMetrics>>,
+);
 
 impl SearchEngine {
     #[allow(dead_code)]
     pub fn init_in_ram() -> Fallible<SearchEngine> {
-        let index = TantivyIndex::create_in_ram()?;
-        Ok(SearchEngine(Arc::new(Mutex::new(Box::new(index)))))
+        Self::init_in_ram_with_language(None)
+    }
+
+    pub fn init_in_ram_with_language(language: Option<TextLanguage>) -> Fallible<SearchEngine> {
+        let index = TantivyIndex::create_in_ram(language)?;
+        let metrics = Arc::clone(&index.metrics);
+        Ok(SearchEngine(
+            Arc::new(Mutex::new(Box::new(index))),
+            None,
+            metrics,
+        ))
+    }
+
+    pub fn open_or_restore(
+        path: &Path,
+        snapshot_dir: Option<&Path>,
+        language: Option<TextLanguage>,
+    ) -> Fallible<SearchEngine> {
+        let index = TantivyIndex::open_or_restore(path, snapshot_dir, language)?;
+        let metrics = Arc::clone(&index.metrics);
+        Ok(SearchEngine(
+            Arc::new(Mutex::new(Box::new(index))),
+            Some(path.to_path_buf()),
+            metrics,
+        ))
+    }
+
+    pub fn index_dir(&self) -> Option<&Path> {
+        self.1.as_deref()
+    }
+
+    /// Can be read while the index is locked
+    pub fn metrics(&self) -> IndexMetrics {
+        lock_metrics(&self.2).clone()
     }
 
-    pub fn init_with_path<P: AsRef<Path>>(path: Option<P>) -> Fallible<SearchEngine> {
-        let index = TantivyIndex::create(path)?;
-        Ok(SearchEngine(Arc::new(Mutex::new(Box::new(index)))))
+    /// Replaces the snapshot in the directory with a copy
+    /// of the committed state of the index.
+    pub fn snapshot(&self, snapshot_dir: &Path) -> Fallible<()> {
+        let index_dir = match self.index_dir() {
+            Some(dir) => dir,
+            None => bail!("The index is not stored in a directory"),
+        };
+        let tmp_dir = snapshot_dir.with_extension("tmp");
+        reset_dir(&tmp_dir)?;
+        {
+            // No changes are committed while the index is locked
+            let _locked = match self.0.lock() {
+                Ok(guard) => guard,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            copy_index_files(index_dir, &tmp_dir)?;
+        }
+        if snapshot_dir.exists() {
+            fs::remove_dir_all(snapshot_dir)?;
+        }
+        fs::rename(&tmp_dir, snapshot_dir)?;
+        Ok(())
     }
 }
 
@@ -1081,6 +1690,27 @@ impl PlaceIndex for SearchEngine {
         };
         inner.query_places(query, limit)
     }
+
+    fn count_place_tags(&self, query: &IndexQuery) -> Fallible<Vec<TagFrequency>> {
+        let inner = match self.0.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        inner.count_place_tags(query)
+    }
+
+    fn query_place_suggestions(
+        &self,
+        query: &IndexQuery,
+        text: &str,
+        limit: usize,
+    ) -> Fallible<Vec<IndexedPlace>> {
+        let inner = match self.0.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        inner.query_place_suggestions(query, text, limit)
+    }
 }
 
 impl PlaceIndexer for SearchEngine {
@@ -1088,13 +1718,14 @@ impl PlaceIndexer for SearchEngine {
         &self,
         place: &Place,
         status: ReviewStatus,
+        last_confirmed_at: Option<TimestampMs>,
         ratings: &AvgRatings,
     ) -> Fallible<()> {
         let inner = match self.0.lock() {
             Ok(guard) => guard,
             Err(poisoned) => poisoned.into_inner(),
         };
-        inner.add_or_update_place(place, status, ratings)
+        inner.add_or_update_place(place, status, last_confirmed_at, ratings)
     }
 }
 
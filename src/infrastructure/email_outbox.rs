@@ -0,0 +1,20 @@
+//! Send the queued e-mails of the outbox in throttled batches.
+
+use super::{cfg::EmailOutboxCfg, db::sqlite, flows::prelude as flows};
+use ofdb_core::gateways::email::EmailGateway;
+use std::thread;
+
+pub fn spawn(
+    connections: sqlite::Connections,
+    gateway: Box<dyn EmailGateway + Send + Sync>,
+    cfg: EmailOutboxCfg,
+) {
+    thread::spawn(move || loop {
+        thread::sleep(cfg.interval);
+        match flows::send_queued_emails(&connections, &*gateway, cfg.batch_size) {
+            Ok(0) => {}
+            Ok(count) => info!("Sent {} queued e-mails", count),
+            Err(err) => warn!("Failed to send queued e-mails: {}", err),
+        }
+    });
+}
@@ -1,4 +1,7 @@
-use crate::core::error::{Error as BError, RepoError};
+use crate::core::{
+    entities::{Activity, Revision},
+    error::{Error as BError, ParameterError, RepoError},
+};
 use diesel::r2d2;
 use diesel::result::Error as DieselError;
 use diesel_migrations::RunMigrationsError;
@@ -11,6 +14,12 @@ impl From<RepoError> for AppError {
     }
 }
 
+impl From<ParameterError> for AppError {
+    fn from(err: ParameterError) -> AppError {
+        AppError::Business(BError::Parameter(err))
+    }
+}
+
 impl From<DieselError> for RepoError {
     fn from(err: DieselError) -> RepoError {
         match err {
@@ -31,6 +40,11 @@ impl From<RunMigrationsError> for AppError {
 pub enum AppError {
     #[error(transparent)]
     Business(#[from] BError),
+    #[error("The place has been modified in the meantime")]
+    RevisionConflict {
+        current_revision: Revision,
+        last_change: Activity,
+    },
     #[error(transparent)]
     Serialize(#[from] serde_json::Error),
     #[error(transparent)]
@@ -0,0 +1,47 @@
+//! Publish events that have been uploaded in advance
+//! as soon as their scheduled time has come.
+
+use super::{
+    db::{sqlite, tantivy},
+    flows::prelude as flows,
+};
+use crate::core::prelude::*;
+use ofdb_core::gateways::notify::NotificationGateway;
+use std::{ops::Deref, thread, time::Duration};
+
+const INTERVAL: Duration = Duration::from_secs(60);
+
+/// Events that are scheduled after `since` are published
+/// periodically, i.e. `since` should be the time when all
+/// events have been indexed.
+pub fn spawn<N>(
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    notify: N,
+    mut since: Timestamp,
+) where
+    N: Deref<Target = dyn NotificationGateway> + Send + 'static,
+{
+    thread::spawn(move || {
+        loop {
+            thread::sleep(INTERVAL);
+            let until = Timestamp::now();
+            match flows::publish_scheduled_events(
+                &connections,
+                &mut search_engine,
+                &*notify,
+                since,
+                until,
+            ) {
+                Ok(0) => {}
+                Ok(count) => info!("Published {} scheduled event(s)", count),
+                Err(err) => {
+                    warn!("Failed to publish scheduled events: {}", err);
+                    // Retry with the same time range
+                    continue;
+                }
+            }
+            since = until;
+        }
+    });
+}
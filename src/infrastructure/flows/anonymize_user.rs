@@ -0,0 +1,128 @@
+use super::*;
+use diesel::connection::Connection;
+
+pub fn anonymize_user(
+    connections: &sqlite::Connections,
+    email: &str,
+) -> Result<AnonymizedUserRecords> {
+    let mut repo_err = None;
+    let connection = connections.exclusive()?;
+    Ok(connection
+        .transaction::<_, diesel::result::Error, _>(|| {
+            usecases::anonymize_user(&*connection, email).map_err(|err| {
+                warn!("Failed to anonymize user {}: {}", email, err);
+                repo_err = Some(err);
+                diesel::result::Error::RollbackTransaction
+            })
+        })
+        .map_err(|err| {
+            if let Some(repo_err) = repo_err {
+                repo_err
+            } else {
+                RepoError::from(err).into()
+            }
+        })?)
+}
+
+/// Anonymizes all users that have been deactivated before
+/// the end of the grace period and returns their number.
+pub fn anonymize_deactivated_users(
+    connections: &sqlite::Connections,
+    deactivated_before: Timestamp,
+) -> Result<usize> {
+    let emails = connections
+        .shared()?
+        .users_deactivated_before(deactivated_before)?;
+    let mut count = 0;
+    for email in emails {
+        // A single failure must not prevent the anonymization of other users
+        if anonymize_user(connections, &email).is_ok() {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    #[test]
+    fn should_anonymize_user_and_keep_places() {
+        let fixture = BackendFixture::new();
+        fixture.create_user(
+            usecases::NewUser {
+                email: "user@example.com".into(),
+                password: "123456".into(),
+            },
+            None,
+        );
+        let place_id = fixture.create_place(1.into(), Some("user@example.com"));
+        let queued_email = |recipient: &str| QueuedEmail {
+            id: Id::new(),
+            recipient: recipient.into(),
+            subject: "subject".into(),
+            body: "body".into(),
+            created_at: Timestamp::now(),
+        };
+        fixture
+            .db_connections
+            .exclusive()
+            .unwrap()
+            .enqueue_emails(&[
+                queued_email("user@example.com"),
+                queued_email("other@example.com"),
+            ])
+            .unwrap();
+
+        let records = flows::anonymize_user(&fixture.db_connections, "user@example.com").unwrap();
+        assert_eq!(1, records.place_revisions);
+        assert_eq!(1, records.place_reviews);
+        assert!(fixture.try_get_user("user@example.com").is_none());
+        assert!(fixture.place_exists(&place_id));
+        let unsent_emails = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .load_unsent_emails(10)
+            .unwrap();
+        assert_eq!(1, unsent_emails.len());
+        assert_eq!("other@example.com", unsent_emails[0].recipient.as_str());
+    }
+
+    #[test]
+    fn should_anonymize_users_after_the_grace_period() {
+        let fixture = BackendFixture::new();
+        for email in &["deleted@example.com", "active@example.com"] {
+            fixture.create_user(
+                usecases::NewUser {
+                    email: (*email).into(),
+                    password: "123456".into(),
+                },
+                None,
+            );
+        }
+        let deactivated_at = Timestamp::now();
+        fixture
+            .db_connections
+            .exclusive()
+            .unwrap()
+            .set_user_deactivated_at("deleted@example.com", Some(deactivated_at))
+            .unwrap();
+
+        // Still within the grace period
+        assert_eq!(
+            0,
+            flows::anonymize_deactivated_users(&fixture.db_connections, deactivated_at).unwrap()
+        );
+        assert!(fixture.try_get_user("deleted@example.com").is_some());
+
+        let grace_period_end = Timestamp::from_inner(deactivated_at.into_inner() + 1);
+        assert_eq!(
+            1,
+            flows::anonymize_deactivated_users(&fixture.db_connections, grace_period_end).unwrap()
+        );
+        assert!(fixture.try_get_user("deleted@example.com").is_none());
+        assert!(fixture.try_get_user("active@example.com").is_some());
+    }
+}
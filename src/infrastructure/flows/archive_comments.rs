@@ -5,12 +5,13 @@ pub fn archive_comments(
     connections: &sqlite::Connections,
     account_email: &str,
     ids: &[&str],
+    reason: ArchiveReason,
 ) -> Result<usize> {
     let mut repo_err = None;
     let connection = connections.exclusive()?;
     Ok(connection
         .transaction::<_, diesel::result::Error, _>(|| {
-            usecases::archive_comments(&*connection, account_email, ids).map_err(|err| {
+            usecases::archive_comments(&*connection, account_email, ids, reason).map_err(|err| {
                 warn!("Failed to archive {} comments: {}", ids.len(), err);
                 repo_err = Some(err);
                 diesel::result::Error::RollbackTransaction
@@ -34,7 +35,12 @@ mod tests {
         account_email: &str,
         ids: &[&str],
     ) -> super::Result<usize> {
-        super::archive_comments(&fixture.db_connections, account_email, ids)
+        super::archive_comments(
+            &fixture.db_connections,
+            account_email,
+            ids,
+            ArchiveReason::OffTopic,
+        )
     }
 
     #[test]
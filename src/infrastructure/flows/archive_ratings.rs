@@ -6,13 +6,14 @@ pub fn exec_archive_ratings(
     connections: &sqlite::Connections,
     account_email: &str,
     ids: &[&str],
+    reason: ArchiveReason,
 ) -> Result<usize> {
     //TODO: check if user is allowed to archive the ratings
     let mut repo_err = None;
     let connection = connections.exclusive()?;
     Ok(connection
         .transaction::<_, diesel::result::Error, _>(|| {
-            usecases::archive_ratings(&*connection, account_email, ids).map_err(|err| {
+            usecases::archive_ratings(&*connection, account_email, ids, reason).map_err(|err| {
                 warn!("Failed to archive {} ratings: {}", ids.len(), err);
                 repo_err = Some(err);
                 diesel::result::Error::RollbackTransaction
@@ -35,7 +36,7 @@ pub fn post_archive_ratings(
     let connection = connections.shared()?;
     let place_ids = connection.load_place_ids_of_ratings(ids)?;
     for place_id in place_ids {
-        let (place, status) = match connection.get_place(&place_id) {
+        let (place, status) = match connection.get_place_by_id(&place_id) {
             Ok(place) => place,
             Err(err) => {
                 error!(
@@ -57,7 +58,10 @@ pub fn post_archive_ratings(
                 continue;
             }
         };
-        if let Err(err) = usecases::reindex_place(indexer, &place, status, &ratings) {
+        let last_confirmed_at = connection.last_confirmed_at(place.id.as_str())?;
+        if let Err(err) =
+            usecases::reindex_place(indexer, &place, status, last_confirmed_at, &ratings)
+        {
             error!(
                 "Failed to reindex place {} after archiving ratings: {}",
                 place.id, err
@@ -78,8 +82,9 @@ pub fn archive_ratings(
     indexer: &mut dyn PlaceIndexer,
     account_email: &str,
     ids: &[&str],
+    reason: ArchiveReason,
 ) -> Result<usize> {
-    let count = exec_archive_ratings(connections, account_email, ids)?;
+    let count = exec_archive_ratings(connections, account_email, ids, reason)?;
     post_archive_ratings(connections, indexer, ids)?;
     Ok(count)
 }
@@ -94,6 +99,7 @@ mod tests {
             &mut *fixture.search_engine.borrow_mut(),
             "scout@foo.tld",
             ids,
+            ArchiveReason::Spam,
         )
     }
 
@@ -0,0 +1,78 @@
+use super::*;
+use ofdb_core::gateways::link_check::LinkChecker;
+
+/// Checks the homepage and image links of all places and events
+/// and records the results.
+pub fn check_links(
+    connections: &sqlite::Connections,
+    checker: &dyn LinkChecker,
+) -> Result<usecases::LinkCheckReport> {
+    let links = usecases::links_to_check(&*connections.shared()?)?;
+    let mut report = usecases::LinkCheckReport::default();
+    for url in &links {
+        // The database is not locked while waiting for a response
+        let error = checker.check_link(url).err().map(|err| err.to_string());
+        if let Some(ref error) = error {
+            debug!("Broken link {}: {}", url, error);
+            report.failed += 1;
+        }
+        connections
+            .exclusive()?
+            .record_link_check(url, error.as_deref(), Timestamp::now())?;
+        report.checked += 1;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+    use ofdb_core::gateways::link_check::{LinkCheckError, LinkChecker};
+
+    struct OnlyHomepages;
+
+    impl LinkChecker for OnlyHomepages {
+        fn check_link(&self, url: &str) -> std::result::Result<(), LinkCheckError> {
+            if url.ends_with(".png") {
+                return Err(LinkCheckError("404 Not Found".into()));
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn should_record_broken_links() {
+        let fixture = BackendFixture::new();
+        let mut new_place = usecases::NewPlace::from(NewPlace::from(0));
+        new_place.homepage = Some("https://example.com/".into());
+        new_place.image_url = Some("https://example.com/image.png".into());
+        flows::create_place(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            new_place,
+            None,
+            None,
+            &Cfg::default(),
+        )
+        .unwrap();
+
+        for _ in 0..usecases::MIN_FAILURES_OF_BROKEN_LINKS {
+            let report = flows::check_links(&fixture.db_connections, &OnlyHomepages).unwrap();
+            assert_eq!(2, report.checked);
+            assert_eq!(1, report.failed);
+        }
+        let broken = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .load_broken_links(usecases::MIN_FAILURES_OF_BROKEN_LINKS)
+            .unwrap();
+        assert_eq!(1, broken.len());
+        assert_eq!("https://example.com/image.png", broken[0].url);
+        assert_eq!(
+            Some("Link check failed: 404 Not Found"),
+            broken[0].last_error.as_deref()
+        );
+    }
+}
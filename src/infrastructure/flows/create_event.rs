@@ -1,7 +1,7 @@
 use super::*;
 use crate::core::error::RepoError;
 use diesel::Connection;
-use ofdb_core::gateways::notify::NotificationGateway;
+use ofdb_core::gateways::{geocode::GeoCodingGateway, notify::NotificationGateway};
 
 pub fn create_event(
     connections: &sqlite::Connections,
@@ -9,6 +9,7 @@ pub fn create_event(
     notify: &dyn NotificationGateway,
     token: Option<&str>,
     new_event: usecases::NewEvent,
+    auto_fill_address: Option<&dyn GeoCodingGateway>,
 ) -> Result<Event> {
     // Create and add new event
     let event = {
@@ -21,6 +22,7 @@ pub fn create_event(
                     token,
                     new_event,
                     usecases::NewEventMode::Create,
+                    auto_fill_address,
                 ) {
                     Ok(storable) => {
                         let event = usecases::store_created_event(&*connection, storable).map_err(
@@ -54,6 +56,10 @@ pub fn create_event(
 
     // Send subscription e-mails
     // TODO: Move to a separate task/thread that doesn't delay this request
+    if event.is_scheduled(Timestamp::now()) {
+        // Subscribers are notified when the event gets published
+        return Ok(event);
+    }
     if let Err(err) = notify_event_created(connections, notify, &event) {
         error!(
             "Failed to send notifications for newly added event {}: {}",
@@ -64,16 +70,27 @@ pub fn create_event(
     Ok(event)
 }
 
-fn notify_event_created(
+pub(super) fn notify_event_created(
     connections: &sqlite::Connections,
     notify: &dyn NotificationGateway,
     event: &Event,
 ) -> Result<()> {
-    if let Some(ref location) = event.location {
-        let email_addresses = {
-            let conn = connections.shared()?;
-            usecases::email_addresses_by_coordinate(&*conn, location.pos)?
-        };
+    let pos = event.location.as_ref().map(|location| location.pos);
+    let email_addresses = {
+        let conn = connections.shared()?;
+        usecases::email_addresses_of_subscribers(&*conn, pos, &event.tags)?
+    };
+    if let Some(pos) = pos {
+        usecases::queue_digest_notifications(
+            &*connections.exclusive()?,
+            pos,
+            NotificationChange::EventCreated,
+            &event.id,
+            &event.title,
+            &email_addresses,
+        )?;
+    }
+    if !email_addresses.is_empty() {
         notify.event_created(&email_addresses, event);
     }
     Ok(())
@@ -1,5 +1,8 @@
 use super::*;
-use crate::{core::error::RepoError, infrastructure::cfg::Cfg};
+use crate::{
+    core::error::RepoError,
+    infrastructure::{auto_fill_address_gateway, cfg::Cfg},
+};
 use diesel::Connection;
 use ofdb_core::gateways::notify::NotificationGateway;
 
@@ -24,6 +27,7 @@ pub fn create_place(
                     created_by_email,
                     created_by_org,
                     &cfg.accepted_licenses,
+                    auto_fill_address_gateway(cfg),
                 ) {
                     Ok(storable) => {
                         let (place, ratings) = usecases::store_new_place(&*connection, storable)
@@ -51,8 +55,9 @@ pub fn create_place(
 
     // Index newly added place
     // TODO: Move to a separate task/thread that doesn't delay this request
-    if let Err(err) = usecases::reindex_place(indexer, &place, ReviewStatus::Created, &ratings)
-        .and_then(|_| indexer.flush_index())
+    if let Err(err) =
+        usecases::reindex_place(indexer, &place, ReviewStatus::Created, None, &ratings)
+            .and_then(|_| indexer.flush_index())
     {
         error!("Failed to index newly added place {}: {}", place.id, err);
     }
@@ -65,22 +70,80 @@ pub fn create_place(
             place.id, err
         );
     }
+    if let Err(err) = super::notify_moderated_tags::notify_moderated_tags_changed(
+        connections,
+        notify,
+        &[],
+        &place,
+        created_by_org,
+    ) {
+        error!(
+            "Failed to send notifications about moderated tags of newly added place {}: {}",
+            place.id, err
+        );
+    }
 
     Ok(place)
 }
 
-fn notify_place_added(
+/// Drafts are neither indexed nor announced to subscribers
+pub fn create_draft(
+    connections: &sqlite::Connections,
+    new_place: usecases::NewPlace,
+    created_by_email: &str,
+    cfg: &Cfg,
+) -> Result<Place> {
+    let connection = connections.exclusive()?;
+    let mut prepare_err = None;
+    Ok(connection
+        .transaction::<_, diesel::result::Error, _>(|| {
+            usecases::prepare_new_place(
+                &*connection,
+                new_place,
+                Some(created_by_email),
+                None,
+                &cfg.accepted_licenses,
+                auto_fill_address_gateway(cfg),
+            )
+            .and_then(|storable| usecases::store_new_draft(&*connection, storable))
+            .map_err(|err| {
+                log::info!("Failed to create draft: {}", err);
+                prepare_err = Some(err);
+                diesel::result::Error::RollbackTransaction
+            })
+        })
+        .map_err(|err| {
+            if let Some(err) = prepare_err {
+                err
+            } else {
+                RepoError::from(err).into()
+            }
+        })?)
+}
+
+pub(super) fn notify_place_added(
     connections: &sqlite::Connections,
     notify: &dyn NotificationGateway,
     place: &Place,
 ) -> Result<()> {
     let (email_addresses, all_categories) = {
         let connection = connections.shared()?;
-        let email_addresses =
-            usecases::email_addresses_by_coordinate(&*connection, place.location.pos)?;
+        let email_addresses = usecases::email_addresses_of_subscribers(
+            &*connection,
+            Some(place.location.pos),
+            &place.tags,
+        )?;
         let all_categories = connection.all_categories()?;
         (email_addresses, all_categories)
     };
+    usecases::queue_digest_notifications(
+        &*connections.exclusive()?,
+        place.location.pos,
+        NotificationChange::PlaceAdded,
+        &place.id,
+        &place.title,
+        &email_addresses,
+    )?;
     notify.place_added(&email_addresses, place, all_categories);
     Ok(())
 }
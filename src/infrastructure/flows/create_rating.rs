@@ -1,10 +1,12 @@
 use super::*;
 
 use diesel::connection::Connection;
+use ofdb_core::gateways::notify::NotificationGateway;
 
 pub fn create_rating(
     connections: &sqlite::Connections,
     indexer: &mut dyn PlaceIndexer,
+    notify: &dyn NotificationGateway,
     rate_entry: usecases::NewPlaceRating,
 ) -> Result<(String, String)> {
     // Add new rating to existing entry
@@ -39,9 +41,120 @@ pub fn create_rating(
             })
     }?;
 
+    after_rating_created(
+        connections,
+        indexer,
+        notify,
+        &place,
+        status,
+        &ratings,
+        &rating_id,
+    )?;
+
+    Ok((rating_id, comment_id))
+}
+
+/// A new rating is either published immediately or held
+/// back for moderation if it violates the comment rules
+#[derive(Debug)]
+pub enum SubmittedRating {
+    Published {
+        rating_id: String,
+        comment_id: String,
+    },
+    Pending(PendingRating),
+}
+
+pub fn submit_rating(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn PlaceIndexer,
+    notify: &dyn NotificationGateway,
+    rate_entry: usecases::NewPlaceRating,
+    author_email: Option<&str>,
+    rules: &usecases::CommentRules,
+) -> Result<SubmittedRating> {
+    let violations = usecases::check_comment_rules(
+        &*connections.shared()?,
+        rules,
+        &rate_entry.comment,
+        author_email,
+        Timestamp::now(),
+    )?;
+    if !violations.is_empty() {
+        let connection = connections.exclusive()?;
+        let storable = usecases::prepare_new_rating(&*connection, rate_entry)?;
+        info!(
+            "Holding back new rating {} for moderation: {:?}",
+            storable.rating_id(),
+            violations
+        );
+        let pending_rating = usecases::hold_new_rating(
+            &*connection,
+            storable,
+            author_email.map(Into::into),
+            violations,
+        )?;
+        return Ok(SubmittedRating::Pending(pending_rating));
+    }
+    let (rating_id, comment_id) = create_rating(connections, indexer, notify, rate_entry)?;
+    Ok(SubmittedRating::Published {
+        rating_id,
+        comment_id,
+    })
+}
+
+/// Publishes ratings that have been held back for moderation
+pub fn publish_pending_ratings(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn PlaceIndexer,
+    notify: &dyn NotificationGateway,
+    ids: &[&str],
+) -> Result<usize> {
+    // Unknown ids are ignored
+    let pending_ids: Vec<_> = connections
+        .shared()?
+        .load_pending_ratings(ids)?
+        .into_iter()
+        .map(|p| p.rating.id)
+        .collect();
+    for id in &pending_ids {
+        let id = id.as_str();
+        let (place, status, ratings) = {
+            let connection = connections.exclusive()?;
+            let mut usecase_err = None;
+            connection
+                .transaction::<_, diesel::result::Error, _>(|| {
+                    usecases::publish_pending_rating(&*connection, id).map_err(|err| {
+                        usecase_err = Some(err);
+                        diesel::result::Error::RollbackTransaction
+                    })
+                })
+                .map_err(|err| {
+                    if let Some(err) = usecase_err {
+                        err
+                    } else {
+                        RepoError::from(err).into()
+                    }
+                })
+        }?;
+        after_rating_created(connections, indexer, notify, &place, status, &ratings, id)?;
+    }
+    Ok(pending_ids.len())
+}
+
+fn after_rating_created(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn PlaceIndexer,
+    notify: &dyn NotificationGateway,
+    place: &Place,
+    status: ReviewStatus,
+    ratings: &[Rating],
+    rating_id: &str,
+) -> Result<()> {
     // Reindex entry after adding the new rating
     // TODO: Move to a separate task/thread that doesn't delay this request
-    if let Err(err) = usecases::reindex_place(indexer, &place, status, &ratings)
+    let last_confirmed_at = connections.shared()?.last_confirmed_at(place.id.as_str())?;
+    if let Err(err) = usecases::reindex_place(indexer, place, status, last_confirmed_at, ratings)
         .and_then(|_| indexer.flush_index())
     {
         error!(
@@ -50,5 +163,33 @@ pub fn create_rating(
         );
     }
 
-    Ok((rating_id, comment_id))
+    // Send e-mails to the watchers of the place
+    // TODO: Move to a separate task/thread that doesn't delay this request
+    if let Some(rating) = ratings.iter().find(|r| r.id.as_str() == rating_id) {
+        if let Err(err) = notify_place_rated(connections, notify, place, rating) {
+            error!(
+                "Failed to send notifications for new rating of place {}: {}",
+                place.id, err
+            );
+        }
+    }
+    Ok(())
+}
+
+fn notify_place_rated(
+    connections: &sqlite::Connections,
+    notify: &dyn NotificationGateway,
+    place: &Place,
+    rating: &Rating,
+) -> Result<()> {
+    let mut email_addresses = vec![];
+    usecases::add_email_addresses_of_watchers(
+        &*connections.shared()?,
+        place.id.as_str(),
+        &mut email_addresses,
+    )?;
+    if !email_addresses.is_empty() {
+        notify.place_rated(&email_addresses, place, rating);
+    }
+    Ok(())
 }
@@ -0,0 +1,114 @@
+use super::*;
+use ofdb_core::gateways::preview::PreviewGateway;
+
+/// Fetches and stores the previews of all place homepages
+/// that have not been previewed since `fetched_since`.
+pub fn fetch_homepage_previews(
+    connections: &sqlite::Connections,
+    gateway: &dyn PreviewGateway,
+    fetched_since: Timestamp,
+) -> Result<usecases::HomepagePreviewReport> {
+    let homepages = usecases::homepages_to_preview(&*connections.shared()?, fetched_since)?;
+    let mut report = usecases::HomepagePreviewReport::default();
+    for url in homepages {
+        // The database is not locked while waiting for a response
+        let page = match gateway.fetch_preview(&url) {
+            Ok(Some(page)) => {
+                report.fetched += 1;
+                page
+            }
+            Ok(None) => {
+                debug!("Not allowed to fetch the preview of {}", url);
+                report.skipped += 1;
+                // Previous previews must not be shown anymore
+                Default::default()
+            }
+            Err(err) => {
+                debug!("Failed to fetch the preview of {}: {}", url, err);
+                report.failed += 1;
+                // The previous preview is kept until the next attempt
+                continue;
+            }
+        };
+        let preview = HomepagePreview {
+            url,
+            title: page.title,
+            image_url: page.image_url,
+            fetched_at: Timestamp::now(),
+        };
+        connections.exclusive()?.store_homepage_preview(&preview)?;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+    use ofdb_core::gateways::preview::{PagePreview, PreviewError, PreviewGateway};
+
+    struct DummyPreviews;
+
+    impl PreviewGateway for DummyPreviews {
+        fn fetch_preview(
+            &self,
+            url: &str,
+        ) -> std::result::Result<Option<PagePreview>, PreviewError> {
+            match url {
+                "https://open.example.com/" => Ok(Some(PagePreview {
+                    title: Some("Open".into()),
+                    image_url: Some("https://open.example.com/logo.png".into()),
+                })),
+                "https://private.example.com/" => Ok(None),
+                _ => Err(PreviewError("404 Not Found".into())),
+            }
+        }
+    }
+
+    fn create_place_with_homepage(fixture: &BackendFixture, i: i32, homepage: &str) -> String {
+        let mut new_place = usecases::NewPlace::from(NewPlace::from(i));
+        new_place.homepage = Some(homepage.into());
+        flows::create_place(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            new_place,
+            None,
+            None,
+            &Cfg::default(),
+        )
+        .unwrap()
+        .id
+        .to_string()
+    }
+
+    #[test]
+    fn should_store_previews_of_homepages() {
+        let fixture = BackendFixture::new();
+        let open = create_place_with_homepage(&fixture, 0, "https://open.example.com/");
+        let private = create_place_with_homepage(&fixture, 1, "https://private.example.com/");
+        create_place_with_homepage(&fixture, 2, "https://missing.example.com/");
+
+        let since = Timestamp::now();
+        let report =
+            flows::fetch_homepage_previews(&fixture.db_connections, &DummyPreviews, since).unwrap();
+        assert_eq!(1, report.fetched);
+        assert_eq!(1, report.skipped);
+        assert_eq!(1, report.failed);
+
+        // Only the failed page is fetched again
+        let report =
+            flows::fetch_homepage_previews(&fixture.db_connections, &DummyPreviews, since).unwrap();
+        assert_eq!(0, report.fetched);
+        assert_eq!(0, report.skipped);
+        assert_eq!(1, report.failed);
+
+        let previews = usecases::load_homepage_previews_of_places(
+            &*fixture.db_connections.shared().unwrap(),
+            &[&open, &private],
+        )
+        .unwrap();
+        assert_eq!(1, previews.len());
+        let preview = previews.values().next().unwrap();
+        assert_eq!(Some("Open"), preview.title.as_deref());
+    }
+}
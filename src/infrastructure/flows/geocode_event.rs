@@ -0,0 +1,35 @@
+use super::*;
+use ofdb_core::gateways::geocode::GeoCodingGateway;
+
+pub fn geocode_event(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn EventIndexer,
+    gw: &dyn GeoCodingGateway,
+    id: &str,
+    auto_fill_address: bool,
+) -> Result<usecases::GeoCodingOutcome> {
+    // Resolve the location without locking the database
+    // while waiting for the geocoding provider
+    let old_event = connections.shared()?.get_event(id)?;
+    let mut event = old_event.clone();
+    let outcome = usecases::geocode_event_location(gw, &mut event, auto_fill_address);
+    if outcome != usecases::GeoCodingOutcome::Updated {
+        return Ok(outcome);
+    }
+    {
+        let connection = connections.exclusive()?;
+        if connection.get_event(id)? != old_event {
+            // Don't overwrite concurrent modifications
+            info!(
+                "Event {} has been modified while resolving its location",
+                id
+            );
+            return Ok(usecases::GeoCodingOutcome::Unresolved);
+        }
+        connection.update_event(&event)?;
+    }
+    if let Err(err) = usecases::index_event(indexer, &event).and_then(|_| indexer.flush_index()) {
+        error!("Failed to reindex geocoded event {}: {}", event.id, err);
+    }
+    Ok(outcome)
+}
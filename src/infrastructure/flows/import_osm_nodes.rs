@@ -0,0 +1,340 @@
+use super::*;
+use crate::{core::error::RepoError, infrastructure::cfg::Cfg};
+use diesel::Connection;
+use ofdb_core::gateways::osm::{OsmGateway, OsmNode};
+
+/// Imports all mapped OSM nodes that are neither imported
+/// yet nor duplicates of existing places.
+///
+/// Nodes that exceed `max_imports` are not imported and
+/// reported as failed.
+///
+/// A dry run validates the mapped places and detects duplicates
+/// without storing anything.
+#[allow(clippy::too_many_arguments)]
+pub fn import_osm_nodes<I: PlaceIndexer>(
+    connections: &sqlite::Connections,
+    indexer: &mut I,
+    osm: &dyn OsmGateway,
+    query: &str,
+    mapping: &usecases::OsmTagMapping,
+    created_by_org: Option<&Organization>,
+    cfg: &Cfg,
+    max_imports: Option<usize>,
+    dry_run: bool,
+) -> Result<usecases::OsmImportReport> {
+    use usecases::{OsmImportOutcome as Outcome, OsmImportRow as Row};
+    let nodes = osm
+        .query_nodes(query)
+        .map_err(|err| Error::Internal(err.to_string()))?;
+    let mut report = usecases::OsmImportReport::default();
+    // The places of a dry run are not indexed
+    let mut pending_places = vec![];
+    for node in nodes {
+        if let Some(place_id) = connections.shared()?.find_place_id_by_osm_node(node.id)? {
+            // TODO: Re-sync already imported places
+            let mut row = Row::new(node.id, Outcome::AlreadyImported, None);
+            row.place_ids.push(place_id);
+            report.add_row(row);
+            continue;
+        }
+        let new_place = match usecases::new_place_from_osm_node(&node, mapping) {
+            Some(new_place) => new_place,
+            None => {
+                report.add_row(Row::new(node.id, Outcome::Unmapped, None));
+                continue;
+            }
+        };
+        let mut duplicates = usecases::search_duplicates(&*indexer, &new_place)?;
+        duplicates.extend(usecases::retain_duplicates_of(
+            pending_places.clone(),
+            &new_place,
+        ));
+        if !duplicates.is_empty() {
+            debug!("Skipping duplicate OSM node {}", node.id);
+            let mut row = Row::new(node.id, Outcome::Duplicate, Some(&new_place));
+            row.place_ids = duplicates.into_iter().map(|p| p.id.into()).collect();
+            report.add_row(row);
+            continue;
+        }
+        let mut row = Row::new(node.id, Outcome::Imported, Some(&new_place));
+        if dry_run {
+            let (_, validated) = usecases::validate_new_place(
+                &*connections.shared()?,
+                new_place,
+                None,
+                created_by_org,
+                &cfg.accepted_licenses,
+            );
+            match validated {
+                Ok(place) => {
+                    // The generated ID is only used for referencing duplicates
+                    pending_places.push(IndexedPlace {
+                        id: place.id.to_string(),
+                        title: place.title.clone(),
+                        pos: place.location.pos,
+                        address: place.location.address.clone(),
+                        ..Default::default()
+                    });
+                    row.place_ids.push(place.id);
+                }
+                Err(err) => {
+                    row.outcome = Outcome::Failed;
+                    row.error = Some(err.to_string());
+                }
+            }
+            report.add_row(row);
+            continue;
+        }
+        if max_imports.map_or(false, |max_imports| report.imported >= max_imports) {
+            row.outcome = Outcome::Failed;
+            row.error = Some(ParameterError::UploadQuotaExceeded.to_string());
+            report.add_row(row);
+            continue;
+        }
+        match store_osm_node(connections, &node, new_place, created_by_org, cfg) {
+            Ok((place, ratings)) => {
+                // Flushing immediately allows to detect duplicates within the same import
+                if let Err(err) = usecases::reindex_place(
+                    &*indexer,
+                    &place,
+                    ReviewStatus::Created,
+                    None,
+                    &ratings,
+                )
+                .and_then(|_| indexer.flush_index())
+                {
+                    error!("Failed to index imported place {}: {}", place.id, err);
+                }
+                row.place_ids.push(place.id);
+            }
+            Err(err) => {
+                warn!("Failed to import OSM node {}: {}", node.id, err);
+                row.outcome = Outcome::Failed;
+                row.error = Some(err.to_string());
+            }
+        }
+        report.add_row(row);
+    }
+    Ok(report)
+}
+
+fn store_osm_node(
+    connections: &sqlite::Connections,
+    node: &OsmNode,
+    new_place: usecases::NewPlace,
+    created_by_org: Option<&Organization>,
+    cfg: &Cfg,
+) -> Result<(Place, Vec<Rating>)> {
+    let connection = connections.exclusive()?;
+    let mut prepare_err = None;
+    connection
+        .transaction::<_, diesel::result::Error, _>(|| {
+            match usecases::prepare_new_place(
+                &*connection,
+                new_place,
+                None,
+                created_by_org,
+                &cfg.accepted_licenses,
+                // The address is mapped from the tags of the node
+                None,
+            ) {
+                Ok(storable) => {
+                    let (place, ratings) = usecases::store_new_place(&*connection, storable)
+                        .and_then(|(place, ratings)| {
+                            connection.link_place_to_osm_node(place.id.as_str(), node.id)?;
+                            Ok((place, ratings))
+                        })
+                        .map_err(|err| {
+                            warn!("Failed to store imported place: {}", err);
+                            diesel::result::Error::RollbackTransaction
+                        })?;
+                    Ok((place, ratings))
+                }
+                Err(err) => {
+                    prepare_err = Some(err);
+                    Err(diesel::result::Error::RollbackTransaction)
+                }
+            }
+        })
+        .map_err(|err| {
+            if let Some(err) = prepare_err {
+                err
+            } else {
+                RepoError::from(err).into()
+            }
+        })
+        .map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+    use ofdb_core::gateways::osm::{OsmGateway, OsmNode, OsmQueryError};
+
+    struct FixedNodes(Vec<OsmNode>);
+
+    impl OsmGateway for FixedNodes {
+        fn query_nodes(&self, _query: &str) -> std::result::Result<Vec<OsmNode>, OsmQueryError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn node(id: u64, name: &str, lat: f64) -> OsmNode {
+        OsmNode {
+            id,
+            lat,
+            lon: 9.1,
+            tags: vec![("name", name), ("shop", "organic")]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    fn organic_shop_mapping() -> usecases::OsmTagMapping {
+        usecases::OsmTagMapping {
+            rules: vec![usecases::OsmTagMappingRule {
+                key: "shop".into(),
+                value: None,
+                categories: vec![],
+                tags: vec!["organic".into()],
+            }],
+        }
+    }
+
+    #[test]
+    fn should_import_mapped_nodes_only_once() {
+        let fixture = BackendFixture::new();
+        let mapping = organic_shop_mapping();
+        let osm = FixedNodes(vec![
+            node(1, "Bioladen", 48.7),
+            // Same name at the same position
+            node(2, "Bioladen", 48.7),
+            node(3, "Unverpackt", 48.8),
+            OsmNode {
+                tags: Default::default(),
+                ..node(4, "", 48.9)
+            },
+        ]);
+
+        let report = flows::import_osm_nodes(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &osm,
+            "node[shop=organic];out;",
+            &mapping,
+            None,
+            &Cfg::default(),
+            None,
+            false,
+        )
+        .unwrap();
+        assert_eq!(2, report.imported);
+        assert_eq!(1, report.duplicates);
+        assert_eq!(1, report.unmapped);
+        assert_eq!(0, report.failed);
+        assert_eq!(4, report.rows.len());
+        let place_id = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .find_place_id_by_osm_node(1)
+            .unwrap()
+            .unwrap();
+        let (place, _) = fixture.try_get_place(place_id.as_str()).unwrap();
+        assert_eq!("Bioladen", place.title);
+        assert_eq!(vec!["organic"], place.tags);
+
+        let report = flows::import_osm_nodes(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &osm,
+            "node[shop=organic];out;",
+            &mapping,
+            None,
+            &Cfg::default(),
+            None,
+            false,
+        )
+        .unwrap();
+        assert_eq!(2, report.already_imported);
+        assert_eq!(0, report.imported);
+    }
+
+    #[test]
+    fn should_not_import_more_than_max_imports() {
+        let fixture = BackendFixture::new();
+        let osm = FixedNodes(vec![
+            node(1, "Bioladen", 48.7),
+            node(2, "Unverpackt", 48.8),
+            node(3, "Wochenmarkt", 48.9),
+        ]);
+        let report = flows::import_osm_nodes(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &osm,
+            "node[shop=organic];out;",
+            &organic_shop_mapping(),
+            None,
+            &Cfg::default(),
+            Some(2),
+            false,
+        )
+        .unwrap();
+        assert_eq!(2, report.imported);
+        assert_eq!(1, report.failed);
+        assert_eq!(usecases::OsmImportOutcome::Failed, report.rows[2].outcome);
+        assert!(fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .find_place_id_by_osm_node(3)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn should_not_store_anything_in_a_dry_run() {
+        let fixture = BackendFixture::new();
+        let osm = FixedNodes(vec![
+            node(1, "Bioladen", 48.7),
+            node(2, "Bioladen", 48.7),
+            node(3, "Unverpackt", 48.8),
+        ]);
+        let report = flows::import_osm_nodes(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &osm,
+            "node[shop=organic];out;",
+            &organic_shop_mapping(),
+            None,
+            &Cfg::default(),
+            None,
+            true,
+        )
+        .unwrap();
+        assert_eq!(2, report.imported);
+        assert_eq!(1, report.duplicates);
+        let rows = &report.rows;
+        assert_eq!(usecases::OsmImportOutcome::Duplicate, rows[1].outcome);
+        // The duplicate refers to the place that would have been imported
+        assert_eq!(rows[0].place_ids, rows[1].place_ids);
+        assert_eq!(Some("Unverpackt"), rows[2].title.as_deref());
+        assert_eq!(vec!["organic"], rows[2].tags);
+        assert!(fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .find_place_id_by_osm_node(1)
+            .unwrap()
+            .is_none());
+        assert!(fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .all_places()
+            .unwrap()
+            .is_empty());
+    }
+}
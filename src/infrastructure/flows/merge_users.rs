@@ -0,0 +1,68 @@
+use super::*;
+use diesel::connection::Connection;
+
+pub fn merge_users(
+    connections: &sqlite::Connections,
+    merged_email: &str,
+    surviving_email: &str,
+) -> Result<MergedUserRecords> {
+    let mut repo_err = None;
+    let connection = connections.exclusive()?;
+    Ok(connection
+        .transaction::<_, diesel::result::Error, _>(|| {
+            usecases::merge_users(&*connection, merged_email, surviving_email).map_err(|err| {
+                warn!(
+                    "Failed to merge user {} into {}: {}",
+                    merged_email, surviving_email, err
+                );
+                repo_err = Some(err);
+                diesel::result::Error::RollbackTransaction
+            })
+        })
+        .map_err(|err| {
+            if let Some(repo_err) = repo_err {
+                repo_err
+            } else {
+                RepoError::from(err).into()
+            }
+        })?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    #[test]
+    fn should_reassign_places_and_anonymize_merged_user() {
+        let fixture = BackendFixture::new();
+        for email in &["old@example.com", "new@example.com"] {
+            fixture.create_user(
+                usecases::NewUser {
+                    email: (*email).into(),
+                    password: "123456".into(),
+                },
+                None,
+            );
+        }
+        fixture.create_place(1.into(), Some("old@example.com"));
+        fixture.create_place(2.into(), Some("new@example.com"));
+
+        let records = flows::merge_users(
+            &fixture.db_connections,
+            "old@example.com",
+            "new@example.com",
+        )
+        .unwrap();
+        assert_eq!(1, records.place_revisions);
+        assert!(fixture.try_get_user("old@example.com").is_none());
+        assert!(fixture.try_get_user("new@example.com").is_some());
+
+        // The merged user no longer exists
+        assert!(flows::merge_users(
+            &fixture.db_connections,
+            "old@example.com",
+            "new@example.com"
+        )
+        .is_err());
+    }
+}
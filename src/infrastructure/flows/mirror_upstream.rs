@@ -0,0 +1,273 @@
+use super::*;
+
+use diesel::connection::Connection;
+use ofdb_core::gateways::upstream::{UpstreamChange, UpstreamChanges, UpstreamGateway};
+use std::collections::BTreeSet;
+
+fn exec_in_transaction<T>(
+    connections: &sqlite::Connections,
+    exec: impl FnOnce(&sqlite::Connection) -> Result<T>,
+) -> Result<T> {
+    let mut repo_err = None;
+    let connection = connections.exclusive()?;
+    Ok(connection
+        .transaction::<_, diesel::result::Error, _>(|| {
+            exec(&*connection).map_err(|err| {
+                repo_err = Some(err);
+                diesel::result::Error::RollbackTransaction
+            })
+        })
+        .map_err(|err| {
+            if let Some(repo_err) = repo_err {
+                repo_err
+            } else {
+                RepoError::from(err).into()
+            }
+        })?)
+}
+
+fn exec_mirror_change(
+    connections: &sqlite::Connections,
+    upstream: &dyn UpstreamGateway,
+    change: &UpstreamChange,
+) -> Result<usecases::MirrorOutcome> {
+    // The upstream state is fetched before locking the database
+    match change {
+        UpstreamChange::Place(id) => {
+            let place = upstream
+                .place(id)
+                .map_err(|err| Error::Internal(err.to_string()))?;
+            exec_in_transaction(connections, |db| usecases::mirror_place(db, id, place))
+        }
+        UpstreamChange::Event(id) => {
+            let event = upstream
+                .event(id)
+                .map_err(|err| Error::Internal(err.to_string()))?;
+            exec_in_transaction(connections, |db| usecases::mirror_event(db, id, event))
+        }
+    }
+}
+
+fn post_mirror_change<I: EventAndPlaceIndexer>(
+    connections: &sqlite::Connections,
+    indexer: &mut I,
+    change: &UpstreamChange,
+    outcome: usecases::MirrorOutcome,
+) -> Result<()> {
+    use usecases::MirrorOutcome as O;
+    let db = connections.shared()?;
+    match (change, outcome) {
+        (_, O::Unchanged) | (_, O::Conflict) => {}
+        (UpstreamChange::Place(id), _) => {
+            let (place, status) = db.get_place_by_id(id.as_str())?;
+            let ratings = db.load_ratings_of_place(id.as_str())?;
+            let last_confirmed_at = db.last_confirmed_at(id.as_str())?;
+            if let Err(err) =
+                usecases::reindex_place(indexer, &place, status, last_confirmed_at, &ratings)
+            {
+                error!("Failed to re-index mirrored place {}: {}", id, err);
+            }
+        }
+        (UpstreamChange::Event(id), O::Deleted) => {
+            if let Err(err) = usecases::unindex_event(indexer, id) {
+                error!("Failed to remove mirrored event {} from index: {}", id, err);
+            }
+        }
+        (UpstreamChange::Event(id), _) => {
+            let event = db.get_event(id.as_str())?;
+            if let Err(err) = usecases::index_event(indexer, &event) {
+                error!("Failed to re-index mirrored event {}: {}", id, err);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Applies all changes of places and events that happened upstream
+/// since the given time. Returns the time of the most recent change.
+pub fn mirror_upstream<I: EventAndPlaceIndexer>(
+    connections: &sqlite::Connections,
+    indexer: &mut I,
+    upstream: &dyn UpstreamGateway,
+    since: Timestamp,
+) -> Result<(usecases::MirrorReport, Timestamp)> {
+    let UpstreamChanges {
+        changes,
+        last_change_at,
+    } = upstream
+        .changes_since(since)
+        .map_err(|err| Error::Internal(err.to_string()))?;
+    // Continue after all changes of the batch, including
+    // those that are not mirrored
+    let last_change_at = last_change_at.map_or(since, |at| at.max(since));
+    let mut report = usecases::MirrorReport::default();
+    // Only the current state is mirrored, even if
+    // an entity has been changed multiple times
+    let mut mirrored = BTreeSet::new();
+    for (_, change) in changes {
+        if !mirrored.insert(change.clone()) {
+            continue;
+        }
+        let outcome = match exec_mirror_change(connections, upstream, &change) {
+            Ok(outcome) => outcome,
+            Err(err) => {
+                warn!("Failed to mirror {:?}: {}", change, err);
+                report.failed += 1;
+                continue;
+            }
+        };
+        if outcome == usecases::MirrorOutcome::Conflict {
+            warn!("Local copy of {:?} conflicts with upstream", change);
+        }
+        report.count(outcome);
+        post_mirror_change(connections, indexer, &change, outcome)?;
+    }
+    if let Err(err) = indexer.flush_index() {
+        error!("Failed to flush search index after mirroring: {}", err);
+    }
+    Ok((report, last_change_at))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+    use ofdb_core::gateways::upstream::{
+        UpstreamChange, UpstreamChanges, UpstreamError, UpstreamGateway,
+    };
+
+    struct FixedUpstream {
+        changes: Vec<(Timestamp, UpstreamChange)>,
+        // The most recent change of an entity that is not mirrored
+        last_change_at: Option<Timestamp>,
+        places: Vec<(Place, ReviewStatus)>,
+        events: Vec<Event>,
+    }
+
+    impl UpstreamGateway for FixedUpstream {
+        fn changes_since(
+            &self,
+            since: Timestamp,
+        ) -> std::result::Result<UpstreamChanges, UpstreamError> {
+            let changes: Vec<_> = self
+                .changes
+                .iter()
+                .filter(|(at, _)| *at >= since)
+                .cloned()
+                .collect();
+            let last_change_at = changes
+                .iter()
+                .map(|(at, _)| *at)
+                .chain(self.last_change_at.filter(|at| *at >= since))
+                .max();
+            Ok(UpstreamChanges {
+                changes,
+                last_change_at,
+            })
+        }
+        fn place(
+            &self,
+            id: &Id,
+        ) -> std::result::Result<Option<(Place, ReviewStatus)>, UpstreamError> {
+            Ok(self.places.iter().find(|(p, _)| p.id == *id).cloned())
+        }
+        fn event(&self, id: &Id) -> std::result::Result<Option<Event>, UpstreamError> {
+            Ok(self.events.iter().find(|e| e.id == *id).cloned())
+        }
+    }
+
+    fn event(id: &str, title: &str) -> Event {
+        Event {
+            id: id.into(),
+            title: title.into(),
+            description: None,
+            start: chrono::NaiveDateTime::from_timestamp(0, 0),
+            end: None,
+            location: None,
+            contact: None,
+            tags: vec!["mirrored".into()],
+            homepage: None,
+            created_by: None,
+            registration: None,
+            archived: None,
+            image_url: None,
+            image_link_url: None,
+            time_zone: None,
+            recurrence: None,
+            address_auto_filled: false,
+            publish_at: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn should_mirror_places_and_events_from_upstream() {
+        let fixture = BackendFixture::new();
+        let local_id = fixture.create_place(0.into(), None);
+        let (mut upstream_place, _) = fixture.try_get_place(&local_id).unwrap();
+        upstream_place.id = "upstream".into();
+        upstream_place.title = "Upstream".into();
+        upstream_place.tags = vec!["mirrored".into()];
+        upstream_place.revision = 7.into();
+
+        let at = |s| Timestamp::from_seconds(s);
+        let upstream = FixedUpstream {
+            changes: vec![
+                (at(10), UpstreamChange::Place("upstream".into())),
+                (at(11), UpstreamChange::Event("e".into())),
+                (at(12), UpstreamChange::Place("upstream".into())),
+                // Exists only locally
+                (at(13), UpstreamChange::Place(local_id.as_str().into())),
+            ],
+            // A rating or comment
+            last_change_at: Some(at(15)),
+            places: vec![(upstream_place, ReviewStatus::Confirmed)],
+            events: vec![event("e", "Upstream event")],
+        };
+
+        let (report, last_change_at) = flows::mirror_upstream(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &upstream,
+            at(0),
+        )
+        .unwrap();
+        assert_eq!(at(15), last_change_at);
+        assert_eq!(
+            usecases::MirrorReport {
+                created: 2,
+                conflicts: 1,
+                ..Default::default()
+            },
+            report
+        );
+        let (place, status) = fixture.try_get_place("upstream").unwrap();
+        assert_eq!("Upstream", place.title);
+        assert_eq!(0, u64::from(place.revision));
+        assert_eq!(ReviewStatus::Confirmed, status);
+        assert_eq!(1, fixture.query_places_by_tag("mirrored").len());
+        assert!(fixture.try_get_place(&local_id).is_some());
+
+        // The event has been deleted upstream
+        let upstream = FixedUpstream {
+            changes: vec![(at(16), UpstreamChange::Event("e".into()))],
+            last_change_at: None,
+            events: vec![],
+            ..upstream
+        };
+        let (report, _) = flows::mirror_upstream(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &upstream,
+            last_change_at,
+        )
+        .unwrap();
+        assert_eq!(1, report.deleted);
+        assert!(fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .get_event("e")
+            .is_err());
+    }
+}
@@ -1,20 +1,44 @@
+mod anonymize_user;
 mod archive_comments;
 mod archive_events;
 mod archive_ratings;
 mod change_user_role;
+mod check_links;
 mod create_event;
 mod create_place;
 mod create_rating;
+mod fetch_homepage_previews;
+mod geocode_event;
+mod import_osm_nodes;
+mod merge_users;
+mod mirror_upstream;
+mod notify_moderated_tags;
+mod publish_draft;
+mod publish_scheduled_events;
+mod queue_notification_digests;
+mod queue_review_digests;
+mod rename_tag;
+mod request_place_confirmations;
 mod reset_password;
+mod resync_osm_nodes;
 mod review_places;
+mod send_moderated_tags_summaries;
+mod send_queued_emails;
+mod transfer_moderated_tag;
 mod update_event;
 mod update_place;
+mod validate_event;
 
 pub mod prelude {
     pub use super::{
-        archive_comments::*, archive_events::*, archive_ratings::*, change_user_role::*,
-        create_event::*, create_place::*, create_rating::*, reset_password::*, review_places::*,
-        update_event::*, update_place::*,
+        anonymize_user::*, archive_comments::*, archive_events::*, archive_ratings::*,
+        change_user_role::*, check_links::*, create_event::*, create_place::*, create_rating::*,
+        fetch_homepage_previews::*, geocode_event::*, import_osm_nodes::*, merge_users::*,
+        mirror_upstream::*, publish_draft::*, publish_scheduled_events::*,
+        queue_notification_digests::*, queue_review_digests::*, rename_tag::*,
+        request_place_confirmations::*, reset_password::*, resync_osm_nodes::*, review_places::*,
+        send_moderated_tags_summaries::*, send_queued_emails::*, transfer_moderated_tag::*,
+        update_event::*, update_place::*, validate_event::*,
     };
 }
 
@@ -0,0 +1,45 @@
+use super::*;
+use crate::infrastructure::MODERATED_TAGS_NOTIFICATION_WINDOW;
+use ofdb_core::gateways::notify::NotificationGateway;
+
+/// Informs organizations about outside edits of their
+/// moderated tags.
+///
+/// The notifications are collected for a summary if a
+/// notification window is configured.
+pub fn notify_moderated_tags_changed(
+    connections: &sqlite::Connections,
+    notify: &dyn NotificationGateway,
+    old_tags: &[String],
+    place: &Place,
+    created_by_org: Option<&Organization>,
+) -> Result<()> {
+    let notifications = usecases::moderated_tags_notifications(
+        &*connections.shared()?,
+        old_tags,
+        &place.tags,
+        created_by_org,
+    )?;
+    if notifications.is_empty() {
+        return Ok(());
+    }
+    if MODERATED_TAGS_NOTIFICATION_WINDOW.is_some() {
+        usecases::queue_moderated_tags_notifications(
+            &*connections.exclusive()?,
+            place,
+            notifications,
+        )?;
+        return Ok(());
+    }
+    for usecases::ModeratedTagsNotification {
+        email,
+        added_tags,
+        removed_tags,
+        branding,
+        ..
+    } in notifications
+    {
+        notify.place_moderated_tags_changed(&[email], place, &added_tags, &removed_tags, &branding);
+    }
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use super::*;
+use diesel::Connection;
+use ofdb_core::gateways::notify::NotificationGateway;
+
+pub fn publish_draft(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn PlaceIndexer,
+    notify: &dyn NotificationGateway,
+    id: &str,
+    published_by_email: &str,
+) -> Result<Place> {
+    let place = {
+        let connection = connections.exclusive()?;
+        let mut publish_err = None;
+        connection
+            .transaction::<_, diesel::result::Error, _>(|| {
+                usecases::publish_draft(&*connection, id, published_by_email).map_err(|err| {
+                    publish_err = Some(err);
+                    diesel::result::Error::RollbackTransaction
+                })
+            })
+            .map_err(|err| {
+                if let Some(err) = publish_err {
+                    err
+                } else {
+                    RepoError::from(err).into()
+                }
+            })
+    }?;
+
+    // A draft doesn't have any ratings
+    if let Err(err) = usecases::reindex_place(indexer, &place, ReviewStatus::Created, None, &[])
+        .and_then(|_| indexer.flush_index())
+    {
+        error!("Failed to index published draft {}: {}", place.id, err);
+    }
+
+    if let Err(err) = super::create_place::notify_place_added(connections, notify, &place) {
+        error!(
+            "Failed to send notifications for published draft {}: {}",
+            place.id, err
+        );
+    }
+    if let Err(err) = super::notify_moderated_tags::notify_moderated_tags_changed(
+        connections,
+        notify,
+        &[],
+        &place,
+        None,
+    ) {
+        error!(
+            "Failed to send notifications about moderated tags of published draft {}: {}",
+            place.id, err
+        );
+    }
+
+    Ok(place)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    #[test]
+    fn should_index_a_draft_only_after_publishing_it() {
+        let fixture = BackendFixture::new();
+        fixture.create_user(
+            usecases::NewUser {
+                email: "draft@example.com".into(),
+                password: "secret123".into(),
+            },
+            None,
+        );
+        let mut new_place = usecases::NewPlace::from(NewPlace::from(0));
+        new_place.tags = vec!["draft".into()];
+        let draft = flows::create_draft(
+            &fixture.db_connections,
+            new_place,
+            "draft@example.com",
+            &Cfg::default(),
+        )
+        .unwrap();
+        let (_, status) = fixture.try_get_place(draft.id.as_str()).unwrap();
+        assert_eq!(ReviewStatus::Draft, status);
+        assert!(fixture.query_places_by_tag("draft").is_empty());
+        let db = fixture.db_connections.shared().unwrap();
+        assert!(db.all_places().unwrap().is_empty());
+        assert!(db
+            .get_places_by_ids(&[draft.id.as_str()])
+            .unwrap()
+            .is_empty());
+        assert_eq!(1, db.drafts_created_by("draft@example.com").unwrap().len());
+        drop(db);
+
+        assert!(flows::publish_draft(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            draft.id.as_str(),
+            "other@example.com",
+        )
+        .is_err());
+        flows::publish_draft(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            draft.id.as_str(),
+            "draft@example.com",
+        )
+        .unwrap();
+        let (_, status) = fixture.try_get_place(draft.id.as_str()).unwrap();
+        assert_eq!(ReviewStatus::Created, status);
+        assert_eq!(1, fixture.query_places_by_tag("draft").len());
+        let db = fixture.db_connections.shared().unwrap();
+        assert!(db
+            .drafts_created_by("draft@example.com")
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,106 @@
+use super::*;
+use ofdb_core::gateways::notify::NotificationGateway;
+
+/// Publishes all events that have been scheduled for a time
+/// after `since` and not later than `until`, i.e. adds them to
+/// the search index and notifies their subscribers.
+/// Returns the number of published events.
+pub fn publish_scheduled_events(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn EventIndexer,
+    notify: &dyn NotificationGateway,
+    since: Timestamp,
+    until: Timestamp,
+) -> Result<usize> {
+    let events = connections
+        .shared()?
+        .events_published_between(since, until)?;
+    for event in &events {
+        if let Err(err) = usecases::index_event(indexer, event) {
+            error!("Failed to index published event {}: {}", event.id, err);
+        }
+    }
+    if let Err(err) = indexer.flush_index() {
+        error!(
+            "Failed to flush search index after publishing events: {}",
+            err
+        );
+    }
+    for event in &events {
+        if let Err(err) = notify_event_created(connections, notify, event) {
+            error!(
+                "Failed to send notifications for published event {}: {}",
+                event.id, err
+            );
+        }
+    }
+    Ok(events.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    #[test]
+    fn should_index_scheduled_events_after_publishing_them() {
+        let fixture = BackendFixture::new();
+        let now = Timestamp::now().into_seconds();
+        let new_event = usecases::NewEvent {
+            title: "Scheduled".into(),
+            start: now + 3600,
+            tags: Some(vec!["scheduled".into()]),
+            created_by: Some("creator@example.com".into()),
+            publish_at: Some(now + 60),
+            ..Default::default()
+        };
+        let mut event = flows::create_event(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            None,
+            new_event,
+            None,
+        )
+        .unwrap();
+        let query = usecases::EventQuery {
+            tags: Some(vec!["scheduled".into()]),
+            ..Default::default()
+        };
+        let query_events = || {
+            usecases::query_events(
+                &*fixture.db_connections.shared().unwrap(),
+                &*fixture.search_engine.borrow(),
+                query.clone(),
+            )
+            .unwrap()
+        };
+        assert!(query_events().is_empty());
+
+        // Pretend that the time of publication has passed
+        event.publish_at = Some(Timestamp::from_seconds(now - 1));
+        fixture
+            .db_connections
+            .exclusive()
+            .unwrap()
+            .update_event(&event)
+            .unwrap();
+        assert!(query_events().is_empty());
+
+        let publish = |since, until| {
+            flows::publish_scheduled_events(
+                &fixture.db_connections,
+                &mut *fixture.search_engine.borrow_mut(),
+                &fixture.notify,
+                Timestamp::from_seconds(since),
+                Timestamp::from_seconds(until),
+            )
+            .unwrap()
+        };
+        assert_eq!(0, publish(now - 1, now));
+        assert_eq!(1, publish(now - 2, now));
+        assert_eq!(
+            vec![event.id],
+            query_events().into_iter().map(|e| e.id).collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use super::*;
+use ofdb_gateways::user_communication;
+
+/// Queues one summary e-mail per subscriber for all
+/// digests that are due at `now` and removes the
+/// summarized notifications.
+///
+/// Returns the number of queued e-mails.
+pub fn queue_notification_digests(
+    connections: &sqlite::Connections,
+    now: Timestamp,
+) -> Result<usize> {
+    let digests = usecases::due_digests(&*connections.shared()?, now)?;
+    let created_at = Timestamp::now();
+    let emails: Vec<_> = digests
+        .iter()
+        .map(|digest| {
+            let content =
+                user_communication::notification_digest_email(digest.digest, &digest.notifications);
+            QueuedEmail {
+                id: Id::new(),
+                recipient: Email::from(digest.email.clone()),
+                subject: content.subject,
+                body: content.body,
+                created_at,
+            }
+        })
+        .collect();
+    let ids: Vec<_> = digests
+        .iter()
+        .flat_map(|digest| digest.notifications.iter().map(|n| n.id.as_str()))
+        .collect();
+    let connection = connections.exclusive()?;
+    connection.enqueue_emails(&emails)?;
+    connection.delete_pending_notifications(&ids)?;
+    Ok(emails.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+    use crate::core::util::geo::MapBbox;
+
+    #[test]
+    fn should_queue_due_digests_once() {
+        let fixture = BackendFixture::new();
+        let email = "daily@example.com";
+        fixture.create_user(
+            usecases::NewUser {
+                email: email.into(),
+                password: "secret".into(),
+            },
+            None,
+        );
+        {
+            let db = fixture.db_connections.exclusive().unwrap();
+            usecases::subscribe_to_bbox(
+                &*db,
+                email.to_string(),
+                MapBbox::new(
+                    MapPoint::from_lat_lng_deg(-10.0, -10.0),
+                    MapPoint::from_lat_lng_deg(10.0, 10.0),
+                ),
+                Some(NotificationDigest::Daily),
+            )
+            .unwrap();
+            let email_nonce = usecases::request_notification_consent(&*db, email)
+                .unwrap()
+                .unwrap();
+            usecases::confirm_notification_consent(&*db, &email_nonce.encode_to_string()).unwrap();
+        }
+        fixture.create_place(0.into(), None);
+        fixture.create_place(1.into(), None);
+
+        let now = Timestamp::now();
+        assert_eq!(
+            0,
+            flows::queue_notification_digests(&fixture.db_connections, now).unwrap()
+        );
+        let day_later =
+            Timestamp::from_inner(now.into_inner() + NotificationDigest::Daily.interval_seconds());
+        assert_eq!(
+            1,
+            flows::queue_notification_digests(&fixture.db_connections, day_later).unwrap()
+        );
+        assert_eq!(
+            0,
+            flows::queue_notification_digests(&fixture.db_connections, day_later).unwrap()
+        );
+        let emails = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .load_unsent_emails(10)
+            .unwrap();
+        assert_eq!(1, emails.len());
+        assert_eq!(email, emails[0].recipient.as_str());
+        assert!(emails[0].subject.contains("2 Änderungen"));
+    }
+}
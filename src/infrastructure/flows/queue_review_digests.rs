@@ -0,0 +1,76 @@
+use super::*;
+use ofdb_gateways::user_communication;
+
+const MAX_PLACES_PER_DIGEST: usize = 50;
+
+/// Queues a digest of the pending places for every
+/// scout with pending places in the subscribed areas.
+///
+/// Returns the number of queued e-mails.
+pub fn queue_review_digests(connections: &sqlite::Connections) -> Result<usize> {
+    let digests = usecases::review_digests(&*connections.shared()?)?;
+    let created_at = Timestamp::now();
+    let emails: Vec<_> = digests
+        .into_iter()
+        .map(|digest| {
+            let content =
+                user_communication::review_digest_email(&digest.places, MAX_PLACES_PER_DIGEST);
+            QueuedEmail {
+                id: Id::new(),
+                recipient: Email::from(digest.email),
+                subject: content.subject,
+                body: content.body,
+                created_at,
+            }
+        })
+        .collect();
+    connections.exclusive()?.enqueue_emails(&emails)?;
+    Ok(emails.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+    use crate::core::util::geo::MapBbox;
+
+    #[test]
+    fn should_queue_digests_for_scouts_only() {
+        let fixture = BackendFixture::new();
+        for (email, role) in &[
+            ("scout@example.com", Role::Scout),
+            ("user@example.com", Role::User),
+        ] {
+            fixture.create_user(
+                usecases::NewUser {
+                    email: (*email).into(),
+                    password: "secret".into(),
+                },
+                Some(*role),
+            );
+            usecases::subscribe_to_bbox(
+                &*fixture.db_connections.exclusive().unwrap(),
+                email.to_string(),
+                MapBbox::new(
+                    MapPoint::from_lat_lng_deg(-10.0, -10.0),
+                    MapPoint::from_lat_lng_deg(10.0, 10.0),
+                ),
+                None,
+            )
+            .unwrap();
+        }
+        fixture.create_place(0.into(), None);
+
+        assert_eq!(
+            1,
+            flows::queue_review_digests(&fixture.db_connections).unwrap()
+        );
+        let emails = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .load_unsent_emails(10)
+            .unwrap();
+        assert_eq!(1, emails.len());
+        assert_eq!("scout@example.com", emails[0].recipient.as_str());
+    }
+}
@@ -0,0 +1,156 @@
+use super::*;
+
+use diesel::connection::Connection;
+
+fn exec_rename_tag(
+    connections: &sqlite::Connections,
+    old_tag: &str,
+    new_tag: &str,
+    renamed_by: &Email,
+) -> Result<usecases::RenamedTag> {
+    let mut repo_err = None;
+    let connection = connections.exclusive()?;
+    Ok(connection
+        .transaction::<_, diesel::result::Error, _>(|| {
+            usecases::rename_tag(&*connection, old_tag, new_tag, renamed_by).map_err(|err| {
+                warn!("Failed to rename tag '{}': {}", old_tag, err);
+                repo_err = Some(err);
+                diesel::result::Error::RollbackTransaction
+            })
+        })
+        .map_err(|err| {
+            if let Some(repo_err) = repo_err {
+                repo_err
+            } else {
+                RepoError::from(err).into()
+            }
+        })?)
+}
+
+fn post_rename_tag<I: EventAndPlaceIndexer>(
+    connections: &sqlite::Connections,
+    indexer: &mut I,
+    renamed: &usecases::RenamedTag,
+) -> Result<()> {
+    let db = connections.shared()?;
+    let place_ids: Vec<_> = renamed.places.iter().map(Id::as_str).collect();
+    for (place, status) in db.get_places_by_ids(&place_ids)? {
+        let ratings = match db.load_ratings_of_place(place.id.as_str()) {
+            Ok(ratings) => ratings,
+            Err(err) => {
+                error!(
+                    "Failed to load ratings of place {} after renaming a tag: {}",
+                    place.id, err
+                );
+                continue;
+            }
+        };
+        let last_confirmed_at = db.last_confirmed_at(place.id.as_str())?;
+        if let Err(err) =
+            usecases::reindex_place(indexer, &place, status, last_confirmed_at, &ratings)
+        {
+            error!(
+                "Failed to re-index place {} after renaming a tag: {}",
+                place.id, err
+            );
+        }
+    }
+    let event_ids: Vec<_> = renamed.events.iter().map(Id::as_str).collect();
+    for event in db.get_events_chronologically(&event_ids)? {
+        if let Err(err) = usecases::index_event(indexer, &event) {
+            error!(
+                "Failed to re-index event {} after renaming a tag: {}",
+                event.id, err
+            );
+        }
+    }
+    if let Err(err) = indexer.flush_index() {
+        error!("Failed to flush search index after renaming a tag: {}", err);
+    }
+    Ok(())
+}
+
+pub fn rename_tag<I: EventAndPlaceIndexer>(
+    connections: &sqlite::Connections,
+    indexer: &mut I,
+    old_tag: &str,
+    new_tag: &str,
+    renamed_by: &Email,
+) -> Result<usecases::RenamedTag> {
+    let renamed = exec_rename_tag(connections, old_tag, new_tag, renamed_by)?;
+    post_rename_tag(connections, indexer, &renamed)?;
+    Ok(renamed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    #[test]
+    fn should_rename_tag_and_keep_review_status() {
+        let fixture = BackendFixture::new();
+        fixture.create_user(
+            usecases::NewUser {
+                email: "admin@example.com".into(),
+                password: "secret".into(),
+            },
+            Some(Role::Admin),
+        );
+        let place_ids: Vec<_> = vec!["bio-laden", "unverpackt"]
+            .into_iter()
+            .zip(0..)
+            .map(|(tag, i)| {
+                fixture.create_place(
+                    NewPlace {
+                        tags: vec![tag.into()],
+                        ..i.into()
+                    },
+                    None,
+                )
+            })
+            .collect();
+        let reviewed_by = "admin@example.com".parse().unwrap();
+        fixture
+            .db_connections
+            .exclusive()
+            .unwrap()
+            .review_places(
+                &[place_ids[0].as_str()],
+                ReviewStatus::Confirmed,
+                &ActivityLog {
+                    activity: Activity::now(Some(reviewed_by)),
+                    context: None,
+                    comment: None,
+                },
+            )
+            .unwrap();
+
+        let renamed = flows::rename_tag(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            "#Bio-Laden",
+            "bioladen",
+            &"admin@example.com".parse().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(vec![Id::from(place_ids[0].as_str())], renamed.places);
+
+        let (place, status) = fixture.try_get_place(&place_ids[0]).unwrap();
+        assert!(place.tags.iter().any(|t| t == "bioladen"));
+        assert!(!place.tags.iter().any(|t| t == "bio-laden"));
+        assert_eq!(1, u64::from(place.revision));
+        assert_eq!(ReviewStatus::Confirmed, status);
+        assert_eq!(1, fixture.query_places_by_tag("bioladen").len());
+        let (place, _) = fixture.try_get_place(&place_ids[1]).unwrap();
+        assert_eq!(0, u64::from(place.revision));
+
+        assert!(flows::rename_tag(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            "bio laden",
+            "bioladen",
+            &"admin@example.com".parse().unwrap(),
+        )
+        .is_err());
+    }
+}
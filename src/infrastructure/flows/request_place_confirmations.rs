@@ -0,0 +1,104 @@
+use super::*;
+use ofdb_core::gateways::notify::NotificationGateway;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ConfirmationCampaignReport {
+    pub requested: usize,
+    pub flagged: usize,
+}
+
+/// Asks the creators or owners of all places that have neither
+/// been updated nor confirmed since `stale_before` to confirm
+/// that their places are still valid. Requests that have been
+/// sent before `unanswered_before` and are still unanswered are
+/// flagged for scouts.
+pub fn run_confirmation_campaign(
+    connections: &sqlite::Connections,
+    notify: &dyn NotificationGateway,
+    stale_before: TimestampMs,
+    unanswered_before: TimestampMs,
+) -> Result<ConfirmationCampaignReport> {
+    let (requests, flagged) = {
+        let db = connections.exclusive()?;
+        let flagged = usecases::flag_unanswered_place_confirmations(&*db, unanswered_before)?;
+        let requests = usecases::request_place_confirmations(&*db, stale_before)?;
+        (requests, flagged)
+    };
+    for (place, request) in &requests {
+        notify.place_confirmation_requested(place, request);
+    }
+    Ok(ConfirmationCampaignReport {
+        requested: requests.len(),
+        flagged,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    #[test]
+    fn should_request_confirmations_and_flag_unanswered_requests() {
+        let fixture = BackendFixture::new();
+        let mut new_place = usecases::NewPlace::from(NewPlace::from(0));
+        new_place.email = Some("owner@example.com".into());
+        let place = flows::create_place(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            new_place,
+            None,
+            None,
+            &Cfg::default(),
+        )
+        .unwrap();
+
+        let now = TimestampMs::now().into_inner();
+        let before_now = TimestampMs::from_inner(now - 1);
+        let after_now = TimestampMs::from_inner(now + 1000);
+        let report = flows::run_confirmation_campaign(
+            &fixture.db_connections,
+            &fixture.notify,
+            before_now,
+            before_now,
+        )
+        .unwrap();
+        assert_eq!(flows::ConfirmationCampaignReport::default(), report);
+
+        let report = flows::run_confirmation_campaign(
+            &fixture.db_connections,
+            &fixture.notify,
+            after_now,
+            before_now,
+        )
+        .unwrap();
+        assert_eq!(1, report.requested);
+        assert_eq!(0, report.flagged);
+        let db = fixture.db_connections.shared().unwrap();
+        let requests = db
+            .load_place_confirmation_requests(place.id.as_str())
+            .unwrap();
+        assert_eq!(1, requests.len());
+        assert_eq!("owner@example.com", requests[0].sent_to.as_str());
+        drop(db);
+
+        let far_future = TimestampMs::from_inner(now + 60_000);
+        let report = flows::run_confirmation_campaign(
+            &fixture.db_connections,
+            &fixture.notify,
+            after_now,
+            far_future,
+        )
+        .unwrap();
+        assert_eq!(0, report.requested);
+        assert_eq!(1, report.flagged);
+        let flagged = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .load_flagged_place_confirmation_requests()
+            .unwrap();
+        assert_eq!(1, flagged.len());
+        assert_eq!(place.id, flagged[0].place_id);
+    }
+}
@@ -0,0 +1,364 @@
+use super::*;
+use crate::infrastructure::cfg::Cfg;
+use ofdb_core::gateways::{
+    notify::NotificationGateway,
+    osm::{OsmGateway, OsmNode},
+};
+use ofdb_gateways::user_communication;
+use std::collections::HashMap;
+
+// Keeps the Overpass queries reasonably short
+const MAX_NODES_PER_QUERY: usize = 100;
+
+const MAX_PLACES_PER_EMAIL: usize = 50;
+
+/// Stores the changes of all imported places that have been
+/// changed upstream as proposals. The places are not modified
+/// until a scout accepts the proposed changes. Scouts are
+/// notified by e-mail.
+pub fn resync_osm_nodes(
+    connections: &sqlite::Connections,
+    osm: &dyn OsmGateway,
+) -> Result<usecases::OsmResyncReport> {
+    let links = connections.shared()?.all_osm_node_links()?;
+    let mut report = usecases::OsmResyncReport::default();
+    let mut proposed_places = vec![];
+    for chunk in links.chunks(MAX_NODES_PER_QUERY) {
+        let node_ids: Vec<_> = chunk.iter().map(|(_, node_id)| *node_id).collect();
+        let nodes: HashMap<_, _> = osm
+            .query_nodes(&usecases::osm_nodes_by_id_query(&node_ids))
+            .map_err(|err| Error::Internal(err.to_string()))?
+            .into_iter()
+            .map(|node| (node.id, node))
+            .collect();
+        for (place_id, node_id) in chunk {
+            let node = match nodes.get(node_id) {
+                Some(node) => node,
+                None => {
+                    // TODO: Propose to archive places that have been deleted upstream
+                    warn!(
+                        "OSM node {} of place {} has been deleted",
+                        node_id, place_id
+                    );
+                    report.deleted += 1;
+                    continue;
+                }
+            };
+            match resync_place(connections, place_id, node) {
+                Ok((place, usecases::OsmNodeProposalOutcome::Proposed(proposal))) => {
+                    debug!(
+                        "Place {} has been changed upstream: {:?}",
+                        place.id, proposal
+                    );
+                    report.proposed += 1;
+                    proposed_places.push(place);
+                }
+                Ok((_, usecases::OsmNodeProposalOutcome::Rejected)) => report.rejected += 1,
+                Ok((_, usecases::OsmNodeProposalOutcome::AlreadyProposed))
+                | Ok((_, usecases::OsmNodeProposalOutcome::Unchanged)) => report.unchanged += 1,
+                Err(err) => {
+                    warn!("Failed to re-sync place {}: {}", place_id, err);
+                    report.failed += 1;
+                }
+            }
+        }
+    }
+    if let Err(err) = notify_scouts(connections, &proposed_places) {
+        error!(
+            "Failed to notify scouts about {} re-synced places: {}",
+            proposed_places.len(),
+            err
+        );
+    }
+    Ok(report)
+}
+
+fn resync_place(
+    connections: &sqlite::Connections,
+    place_id: &Id,
+    node: &OsmNode,
+) -> Result<(Place, usecases::OsmNodeProposalOutcome)> {
+    let connection = connections.exclusive()?;
+    let (place, status) = connection.get_place_by_id(place_id.as_str())?;
+    if !status.exists() {
+        return Ok((place, usecases::OsmNodeProposalOutcome::Unchanged));
+    }
+    let outcome = usecases::propose_osm_node_changes(&*connection, &place, node)?;
+    Ok((place, outcome))
+}
+
+fn notify_scouts(connections: &sqlite::Connections, places: &[Place]) -> Result<()> {
+    if places.is_empty() {
+        return Ok(());
+    }
+    let scouts: Vec<_> = connections
+        .shared()?
+        .all_users()?
+        .into_iter()
+        .filter(|user| user.role == Role::Scout)
+        .collect();
+    let content = user_communication::osm_node_proposals_email(places, MAX_PLACES_PER_EMAIL);
+    let created_at = Timestamp::now();
+    let emails: Vec<_> = scouts
+        .into_iter()
+        .map(|scout| QueuedEmail {
+            id: Id::new(),
+            recipient: Email::from(scout.email),
+            subject: content.subject.clone(),
+            body: content.body.clone(),
+            created_at,
+        })
+        .collect();
+    connections.exclusive()?.enqueue_emails(&emails)?;
+    Ok(())
+}
+
+/// Applies the proposed changes to the current revision
+/// of the place on behalf of the scout.
+pub fn accept_osm_node_proposal(
+    connections: &sqlite::Connections,
+    indexer: &mut dyn PlaceIndexer,
+    notify: &dyn NotificationGateway,
+    scout_email: &str,
+    proposal_id: &str,
+    cfg: &Cfg,
+) -> Result<Place> {
+    let (proposal, place) = {
+        let connection = connections.shared()?;
+        let proposal = connection.get_osm_node_proposal(proposal_id)?;
+        if proposal.status != OsmNodeProposalStatus::Pending {
+            return Err(RepoError::NotFound.into());
+        }
+        let (place, _) = connection.get_place_by_id(proposal.place_id.as_str())?;
+        (proposal, place)
+    };
+    let update = usecases::update_place_from_osm_proposal(place, &proposal);
+    let place = super::update_place::update_place(
+        connections,
+        indexer,
+        notify,
+        proposal.place_id.clone(),
+        update,
+        Some(scout_email),
+        None,
+        cfg,
+    )?;
+    connections.exclusive()?.review_osm_node_proposal(
+        proposal_id,
+        OsmNodeProposalStatus::Accepted,
+        &Activity::now(Some(scout_email.into())),
+    )?;
+    Ok(place)
+}
+
+/// The rejected changes will not be proposed again.
+pub fn reject_osm_node_proposal(
+    connections: &sqlite::Connections,
+    scout_email: &str,
+    proposal_id: &str,
+) -> Result<()> {
+    connections.exclusive()?.review_osm_node_proposal(
+        proposal_id,
+        OsmNodeProposalStatus::Rejected,
+        &Activity::now(Some(scout_email.into())),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+    use ofdb_core::gateways::osm::{OsmGateway, OsmNode, OsmQueryError};
+
+    struct FixedNodes(Vec<OsmNode>);
+
+    impl OsmGateway for FixedNodes {
+        fn query_nodes(&self, _query: &str) -> std::result::Result<Vec<OsmNode>, OsmQueryError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn node(id: u64, name: &str, lat: f64) -> OsmNode {
+        OsmNode {
+            id,
+            lat,
+            lon: 9.1,
+            tags: vec![("name", name), ("shop", "organic")]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn should_propose_changes_of_places_upstream() {
+        let fixture = BackendFixture::new();
+        fixture.create_user(
+            usecases::NewUser {
+                email: "scout@example.com".into(),
+                password: "secret".into(),
+            },
+            Some(Role::Scout),
+        );
+        let mapping = usecases::OsmTagMapping {
+            rules: vec![usecases::OsmTagMappingRule {
+                key: "shop".into(),
+                value: None,
+                categories: vec![],
+                tags: vec!["organic".into()],
+            }],
+        };
+        flows::import_osm_nodes(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &FixedNodes(vec![
+                node(1, "Bioladen", 48.7),
+                node(2, "Unverpackt", 48.8),
+                node(3, "Wochenmarkt", 48.9),
+            ]),
+            "node[shop=organic];out;",
+            &mapping,
+            None,
+            &Cfg::default(),
+            None,
+            false,
+        )
+        .unwrap();
+
+        // Node 3 has been deleted upstream
+        let upstream = FixedNodes(vec![node(1, "Bioladen", 48.7), node(2, "Zero Waste", 48.8)]);
+        let report = flows::resync_osm_nodes(&fixture.db_connections, &upstream).unwrap();
+        assert_eq!(
+            usecases::OsmResyncReport {
+                proposed: 1,
+                unchanged: 1,
+                deleted: 1,
+                ..Default::default()
+            },
+            report
+        );
+
+        let place_id = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .find_place_id_by_osm_node(2)
+            .unwrap()
+            .unwrap();
+        // The place is not modified until the changes are accepted
+        let (place, _) = fixture.try_get_place(place_id.as_str()).unwrap();
+        assert_eq!("Unverpackt", place.title);
+        assert_eq!(0, u64::from(place.revision));
+        let proposals = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .list_pending_osm_node_proposals(&Default::default())
+            .unwrap();
+        assert_eq!(1, proposals.len());
+        assert_eq!(place_id, proposals[0].place_id);
+        assert_eq!(Some("Zero Waste"), proposals[0].title.as_deref());
+        let emails = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .load_unsent_emails(10)
+            .unwrap();
+        assert_eq!(1, emails.len());
+        assert_eq!("scout@example.com", emails[0].recipient.as_str());
+
+        // Pending changes are not proposed twice
+        let report = flows::resync_osm_nodes(&fixture.db_connections, &upstream).unwrap();
+        assert_eq!(0, report.proposed);
+
+        let place = flows::accept_osm_node_proposal(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            "scout@example.com",
+            proposals[0].id.as_str(),
+            &Cfg::default(),
+        )
+        .unwrap();
+        assert_eq!("Zero Waste", place.title);
+        assert_eq!(1, u64::from(place.revision));
+        assert_eq!(
+            OsmNodeProposalStatus::Accepted,
+            fixture
+                .db_connections
+                .shared()
+                .unwrap()
+                .get_osm_node_proposal(proposals[0].id.as_str())
+                .unwrap()
+                .status
+        );
+    }
+
+    #[test]
+    fn should_not_propose_rejected_changes_again() {
+        let fixture = BackendFixture::new();
+        fixture.create_user(
+            usecases::NewUser {
+                email: "scout@example.com".into(),
+                password: "secret".into(),
+            },
+            Some(Role::Scout),
+        );
+        let mapping = usecases::OsmTagMapping {
+            rules: vec![usecases::OsmTagMappingRule {
+                key: "shop".into(),
+                value: None,
+                categories: vec![],
+                tags: vec!["organic".into()],
+            }],
+        };
+        flows::import_osm_nodes(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &FixedNodes(vec![node(1, "Bioladen", 48.7)]),
+            "node[shop=organic];out;",
+            &mapping,
+            None,
+            &Cfg::default(),
+            None,
+            false,
+        )
+        .unwrap();
+
+        let upstream = FixedNodes(vec![node(1, "Zero Waste", 48.7)]);
+        flows::resync_osm_nodes(&fixture.db_connections, &upstream).unwrap();
+        let proposals = fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .list_pending_osm_node_proposals(&Default::default())
+            .unwrap();
+        assert_eq!(1, proposals.len());
+        flows::reject_osm_node_proposal(
+            &fixture.db_connections,
+            "scout@example.com",
+            proposals[0].id.as_str(),
+        )
+        .unwrap();
+
+        let report = flows::resync_osm_nodes(&fixture.db_connections, &upstream).unwrap();
+        assert_eq!(
+            usecases::OsmResyncReport {
+                rejected: 1,
+                ..Default::default()
+            },
+            report
+        );
+        assert!(fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .list_pending_osm_node_proposals(&Default::default())
+            .unwrap()
+            .is_empty());
+        let (place, _) = fixture
+            .try_get_place(proposals[0].place_id.as_str())
+            .unwrap();
+        assert_eq!("Bioladen", place.title);
+    }
+}
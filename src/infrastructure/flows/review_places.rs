@@ -32,7 +32,7 @@ fn post_review_places(
     ids: &[&str],
 ) -> Result<()> {
     let db = connections.shared()?;
-    let places_with_status = db.get_places(ids)?;
+    let places_with_status = db.get_places_by_ids(ids)?;
     for (place, status) in places_with_status {
         let ratings = match db.load_ratings_of_place(place.id.as_str()) {
             Ok(ratings) => ratings,
@@ -45,7 +45,10 @@ fn post_review_places(
                 continue;
             }
         };
-        if let Err(err) = usecases::reindex_place(indexer, &place, status, &ratings) {
+        let last_confirmed_at = db.last_confirmed_at(place.id.as_str())?;
+        if let Err(err) =
+            usecases::reindex_place(indexer, &place, status, last_confirmed_at, &ratings)
+        {
             error!(
                 "Failed to (re-)index place {} after reviewing: {}",
                 place.id, err
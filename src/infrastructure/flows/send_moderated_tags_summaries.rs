@@ -0,0 +1,114 @@
+use super::*;
+use ofdb_core::gateways::notify::NotificationGateway;
+use std::time::Duration;
+
+/// Sends one summary per organization for all outside edits
+/// of moderated tags that were collected during the window
+/// and removes the summarized notifications.
+///
+/// Returns the number of sent summaries.
+pub fn send_moderated_tags_summaries(
+    connections: &sqlite::Connections,
+    notify: &dyn NotificationGateway,
+    window: Duration,
+    now: Timestamp,
+) -> Result<usize> {
+    let (summaries, obsolete) = usecases::due_moderated_tags_summaries(
+        &*connections.shared()?,
+        window.as_secs() as i64,
+        now,
+    )?;
+    for summary in &summaries {
+        notify.moderated_tags_summary(
+            &[summary.email.clone()],
+            &summary.notifications,
+            &summary.branding,
+        );
+    }
+    let ids: Vec<_> = summaries
+        .iter()
+        .flat_map(|summary| summary.notifications.iter())
+        .chain(obsolete.iter())
+        .map(|n| n.id.as_str())
+        .collect();
+    connections
+        .exclusive()?
+        .delete_pending_moderated_tags_notifications(&ids)?;
+    Ok(summaries.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+    use std::time::Duration;
+
+    #[test]
+    fn should_send_due_summaries_once() {
+        let fixture = BackendFixture::new();
+        fixture
+            .db_connections
+            .exclusive()
+            .unwrap()
+            .create_org(Organization {
+                id: "org".into(),
+                name: "org".into(),
+                api_token: "org".into(),
+                moderated_tags: vec!["org-tag".into()],
+                notification_email: Some("org@example.com".into()),
+                email_branding: Default::default(),
+            })
+            .unwrap();
+        for id in &["a", "b"] {
+            let place = Place::build()
+                .id(id)
+                .title(id)
+                .tags(vec!["org-tag"])
+                .finish();
+            let db = fixture.db_connections.exclusive().unwrap();
+            let notifications =
+                usecases::moderated_tags_notifications(&*db, &[], &place.tags, None).unwrap();
+            usecases::queue_moderated_tags_notifications(&*db, &place, notifications).unwrap();
+        }
+
+        let window = Duration::from_secs(600);
+        let now = Timestamp::now();
+        assert_eq!(
+            0,
+            flows::send_moderated_tags_summaries(
+                &fixture.db_connections,
+                &fixture.notify,
+                window,
+                now
+            )
+            .unwrap()
+        );
+        let later = Timestamp::from_inner(now.into_inner() + window.as_secs() as i64);
+        assert_eq!(
+            1,
+            flows::send_moderated_tags_summaries(
+                &fixture.db_connections,
+                &fixture.notify,
+                window,
+                later
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            0,
+            flows::send_moderated_tags_summaries(
+                &fixture.db_connections,
+                &fixture.notify,
+                window,
+                later
+            )
+            .unwrap()
+        );
+        assert!(fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .all_pending_moderated_tags_notifications()
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,93 @@
+use super::*;
+use ofdb_core::gateways::email::EmailGateway;
+
+/// Sends the oldest e-mails of the outbox.
+///
+/// Returns the number of sent e-mails.
+pub fn send_queued_emails(
+    connections: &sqlite::Connections,
+    gateway: &dyn EmailGateway,
+    batch_size: usize,
+) -> Result<usize> {
+    let emails = connections.shared()?.load_unsent_emails(batch_size)?;
+    for email in &emails {
+        gateway.compose_and_send(
+            std::slice::from_ref(&email.recipient),
+            &email.subject,
+            &email.body,
+        );
+        connections
+            .exclusive()?
+            .mark_email_as_sent(&email.id, Timestamp::now())?;
+    }
+    Ok(emails.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+    use ofdb_core::gateways::email::EmailGateway;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct RecordingEmailGateway {
+        recipients: RefCell<Vec<Email>>,
+    }
+
+    impl EmailGateway for RecordingEmailGateway {
+        fn compose_and_send(&self, recipients: &[Email], _: &str, _: &str) {
+            self.recipients.borrow_mut().extend_from_slice(recipients);
+        }
+    }
+
+    #[test]
+    fn should_send_queued_emails_in_batches() {
+        let fixture = BackendFixture::new();
+        for email in &["a@example.com", "b@example.com", "c@example.com"] {
+            fixture.create_user(
+                usecases::NewUser {
+                    email: email.to_string(),
+                    password: "secret".into(),
+                },
+                None,
+            );
+        }
+        {
+            let db = fixture.db_connections.exclusive().unwrap();
+            for mut user in db.all_users().unwrap() {
+                user.email_confirmed = true;
+                db.update_user(&user).unwrap();
+                db.set_wants_announcements(&user.email, true).unwrap();
+            }
+            assert_eq!(
+                3,
+                usecases::queue_announcement(&*db, "News", "Hello").unwrap()
+            );
+        }
+
+        let gateway = RecordingEmailGateway::default();
+        assert_eq!(
+            2,
+            flows::send_queued_emails(&fixture.db_connections, &gateway, 2).unwrap()
+        );
+        assert_eq!(
+            1,
+            flows::send_queued_emails(&fixture.db_connections, &gateway, 2).unwrap()
+        );
+        assert_eq!(
+            0,
+            flows::send_queued_emails(&fixture.db_connections, &gateway, 2).unwrap()
+        );
+        let mut recipients: Vec<_> = gateway
+            .recipients
+            .into_inner()
+            .into_iter()
+            .map(String::from)
+            .collect();
+        recipients.sort();
+        assert_eq!(
+            vec!["a@example.com", "b@example.com", "c@example.com"],
+            recipients
+        );
+    }
+}
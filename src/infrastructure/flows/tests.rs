@@ -28,7 +28,7 @@ pub mod prelude {
 
     impl BackendFixture {
         pub fn new() -> Self {
-            let db_connections = sqlite::Connections::init(":memory:", 1).unwrap();
+            let db_connections = sqlite::Connections::init_in_memory().unwrap();
             embedded_migrations::run(&*db_connections.exclusive().unwrap()).unwrap();
             let search_engine = tantivy::SearchEngine::init_in_ram().unwrap();
             Self {
@@ -77,7 +77,7 @@ pub mod prelude {
         }
 
         pub fn try_get_place(&self, id: &str) -> Option<(Place, ReviewStatus)> {
-            match self.db_connections.shared().unwrap().get_place(id) {
+            match self.db_connections.shared().unwrap().get_place_by_id(id) {
                 Ok(x) => Some(x),
                 Err(RepoError::NotFound) => None,
                 x => x.map(|_| None).unwrap(),
@@ -92,6 +92,7 @@ pub mod prelude {
             flows::create_rating(
                 &self.db_connections,
                 &mut *self.search_engine.borrow_mut(),
+                &self.notify,
                 rate_entry,
             )
             .unwrap()
@@ -154,7 +155,6 @@ pub mod prelude {
             let pos = MapPoint::from_lat_lng_deg(f64::from(lat_deg), f64::from(lng_deg));
             let title = format!("Title {}", i);
             let description = format!("Description {}", i);
-            let categories = vec![format!("category_{}", i)];
             let tags = vec![format!("tag-{}", i)];
             let custom_links = vec![CustomLink {
                 url: format!("https://www.example{}.com", i).parse().unwrap(),
@@ -165,7 +165,7 @@ pub mod prelude {
                 pos,
                 title,
                 description,
-                categories,
+                categories: vec![],
                 tags,
                 custom_links,
             }
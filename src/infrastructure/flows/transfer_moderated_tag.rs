@@ -0,0 +1,52 @@
+use super::*;
+
+use diesel::connection::Connection;
+
+fn exec_in_transaction(
+    connections: &sqlite::Connections,
+    exec: impl FnOnce(&sqlite::Connection) -> Result<()>,
+) -> Result<()> {
+    let mut repo_err = None;
+    let connection = connections.exclusive()?;
+    Ok(connection
+        .transaction::<_, diesel::result::Error, _>(|| {
+            exec(&*connection).map_err(|err| {
+                repo_err = Some(err);
+                diesel::result::Error::RollbackTransaction
+            })
+        })
+        .map_err(|err| {
+            if let Some(repo_err) = repo_err {
+                repo_err
+            } else {
+                RepoError::from(err).into()
+            }
+        })?)
+}
+
+pub fn transfer_moderated_tag(
+    connections: &sqlite::Connections,
+    org: &Organization,
+    tag: &str,
+    new_owner_id: &Id,
+) -> Result<()> {
+    exec_in_transaction(connections, |db| {
+        Ok(usecases::transfer_moderated_tag(
+            db,
+            org,
+            tag,
+            new_owner_id,
+        )?)
+    })
+}
+
+pub fn share_moderated_tag(
+    connections: &sqlite::Connections,
+    org: &Organization,
+    tag: &str,
+    co_owner_id: &Id,
+) -> Result<()> {
+    exec_in_transaction(connections, |db| {
+        Ok(usecases::share_moderated_tag(db, org, tag, co_owner_id)?)
+    })
+}
@@ -1,7 +1,7 @@
 use super::*;
 use crate::core::error::RepoError;
 use diesel::Connection;
-use ofdb_core::gateways::notify::NotificationGateway;
+use ofdb_core::gateways::{geocode::GeoCodingGateway, notify::NotificationGateway};
 
 pub fn update_event(
     connections: &sqlite::Connections,
@@ -10,6 +10,7 @@ pub fn update_event(
     token: Option<&str>,
     id: Id,
     new_event: usecases::NewEvent,
+    auto_fill_address: Option<&dyn GeoCodingGateway>,
 ) -> Result<Event> {
     // Create and add new event
     let event = {
@@ -22,6 +23,7 @@ pub fn update_event(
                     token,
                     new_event,
                     usecases::NewEventMode::Update(id.as_str()),
+                    auto_fill_address,
                 ) {
                     Ok(storable) => {
                         let event = usecases::store_updated_event(&*connection, storable).map_err(
@@ -70,11 +72,25 @@ fn notify_event_updated(
     notify: &dyn NotificationGateway,
     event: &Event,
 ) -> Result<()> {
-    if let Some(ref location) = event.location {
-        let email_addresses = {
-            let conn = connections.shared()?;
-            usecases::email_addresses_by_coordinate(&*conn, location.pos)?
-        };
+    let pos = event.location.as_ref().map(|location| location.pos);
+    let email_addresses = {
+        let conn = connections.shared()?;
+        let mut email_addresses =
+            usecases::email_addresses_of_subscribers(&*conn, pos, &event.tags)?;
+        usecases::add_email_addresses_of_watchers(&*conn, event.id.as_str(), &mut email_addresses)?;
+        email_addresses
+    };
+    if let Some(pos) = pos {
+        usecases::queue_digest_notifications(
+            &*connections.exclusive()?,
+            pos,
+            NotificationChange::EventUpdated,
+            &event.id,
+            &event.title,
+            &email_addresses,
+        )?;
+    }
+    if !email_addresses.is_empty() {
         notify.event_updated(&email_addresses, event);
     }
     Ok(())
@@ -14,7 +14,7 @@ pub fn update_place(
     cfg: &Cfg,
 ) -> Result<Place> {
     // Update existing entry
-    let (place, ratings) = {
+    let (place, ratings, old_tags) = {
         let connection = connections.exclusive()?;
         let mut prepare_err = None;
         connection
@@ -28,6 +28,7 @@ pub fn update_place(
                     &cfg.accepted_licenses,
                 ) {
                     Ok(storable) => {
+                        let old_tags = storable.old_tags().to_vec();
                         let (place, ratings) =
                             usecases::store_updated_place(&*connection, storable).map_err(
                                 |err| {
@@ -35,7 +36,7 @@ pub fn update_place(
                                     diesel::result::Error::RollbackTransaction
                                 },
                             )?;
-                        Ok((place, ratings))
+                        Ok((place, ratings, old_tags))
                     }
                     Err(err) => {
                         prepare_err = Some(err);
@@ -54,20 +55,40 @@ pub fn update_place(
 
     // Reindex updated place
     // TODO: Move to a separate task/thread that doesn't delay this request
-    if let Err(err) = usecases::reindex_place(indexer, &place, ReviewStatus::Created, &ratings)
-        .and_then(|_| indexer.flush_index())
+    // Confirmations of previous revisions are still considered
+    let last_confirmed_at = connections.shared()?.last_confirmed_at(place.id.as_str())?;
+    if let Err(err) = usecases::reindex_place(
+        indexer,
+        &place,
+        ReviewStatus::Created,
+        last_confirmed_at,
+        &ratings,
+    )
+    .and_then(|_| indexer.flush_index())
     {
         error!("Failed to reindex updated place {}: {}", place.id, err);
     }
 
     // Send subscription e-mails
     // TODO: Move to a separate task/thread that doesn't delay this request
-    if let Err(err) = notify_place_updated(connections, notify, &place) {
+    if let Err(err) = notify_place_updated(connections, notify, &old_tags, &place) {
         error!(
             "Failed to send notifications for updated place {}: {}",
             place.id, err
         );
     }
+    if let Err(err) = super::notify_moderated_tags::notify_moderated_tags_changed(
+        connections,
+        notify,
+        &old_tags,
+        &place,
+        created_by_org,
+    ) {
+        error!(
+            "Failed to send notifications about moderated tags of updated place {}: {}",
+            place.id, err
+        );
+    }
 
     Ok(place)
 }
@@ -75,15 +96,35 @@ pub fn update_place(
 fn notify_place_updated(
     connections: &sqlite::Connections,
     notify: &dyn NotificationGateway,
+    old_tags: &[String],
     place: &Place,
 ) -> Result<()> {
     let (email_addresses, all_categories) = {
         let connection = connections.shared()?;
-        let email_addresses =
-            usecases::email_addresses_by_coordinate(&*connection, place.location.pos)?;
+        // Subscribers of organizations are also notified about removed tags
+        let mut tags = old_tags.to_vec();
+        tags.extend(place.tags.iter().cloned());
+        let mut email_addresses = usecases::email_addresses_of_subscribers(
+            &*connection,
+            Some(place.location.pos),
+            &tags,
+        )?;
+        usecases::add_email_addresses_of_watchers(
+            &*connection,
+            place.id.as_str(),
+            &mut email_addresses,
+        )?;
         let all_categories = connection.all_categories()?;
         (email_addresses, all_categories)
     };
+    usecases::queue_digest_notifications(
+        &*connections.exclusive()?,
+        place.location.pos,
+        NotificationChange::PlaceUpdated,
+        &place.id,
+        &place.title,
+        &email_addresses,
+    )?;
     notify.place_updated(&email_addresses, &place, all_categories);
     Ok(())
 }
@@ -0,0 +1,79 @@
+use super::*;
+use crate::core::error::RepoError;
+use diesel::Connection;
+
+/// Validates a new event without storing anything.
+pub fn validate_new_event(
+    connections: &sqlite::Connections,
+    token: Option<&str>,
+    new_event: usecases::NewEvent,
+) -> Result<(Vec<String>, usecases::Result<Event>)> {
+    let connection = connections.exclusive()?;
+    let mut validation = None;
+    let rollback = connection.transaction::<(), _, _>(|| {
+        validation = Some(usecases::validate_new_event(
+            &*connection,
+            token,
+            new_event,
+            usecases::NewEventMode::Create,
+        ));
+        // Discard all side effects, e.g. a newly registered creator
+        Err(diesel::result::Error::RollbackTransaction)
+    });
+    match rollback {
+        Err(diesel::result::Error::RollbackTransaction) => {}
+        Err(err) => return Err(RepoError::from(err).into()),
+        Ok(()) => unreachable!(),
+    }
+    Ok(validation.expect("validated"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::prelude::*;
+
+    fn new_event(tags: Vec<&str>, homepage: &str) -> usecases::NewEvent {
+        usecases::NewEvent {
+            title: "Repair Café".into(),
+            start: 1_600_000_000,
+            tags: Some(tags.into_iter().map(Into::into).collect()),
+            homepage: Some(homepage.into()),
+            created_by: Some("creator@example.com".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_validate_new_event_without_storing_anything() {
+        let fixture = BackendFixture::new();
+
+        let (tags, result) = flows::validate_new_event(
+            &fixture.db_connections,
+            None,
+            new_event(vec!["#Repair", "cafe", "repair"], "example.com"),
+        )
+        .unwrap();
+        assert_eq!(vec!["cafe", "repair"], tags);
+        let event = result.unwrap();
+        assert_eq!(
+            "https://www.example.com/",
+            event.homepage.unwrap().to_string()
+        );
+        assert!(fixture.try_get_user("creator@example.com").is_none());
+        assert!(fixture
+            .db_connections
+            .shared()
+            .unwrap()
+            .get_event(event.id.as_str())
+            .is_err());
+
+        let (tags, result) = flows::validate_new_event(
+            &fixture.db_connections,
+            None,
+            new_event(vec!["repair"], "not a url"),
+        )
+        .unwrap();
+        assert_eq!(vec!["repair"], tags);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,151 @@
+//! Resolve the locations of events in the background instead
+//! of delaying requests by waiting for external geocoding providers.
+
+use super::{
+    db::{sqlite, tantivy},
+    error::AppError,
+    flows::prelude as flows,
+    GEO_CODING_GW,
+};
+use crate::core::{prelude::*, usecases::GeoCodingOutcome};
+use std::{
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+const MAX_ATTEMPTS: u32 = 5;
+const RETRY_DELAY: Duration = Duration::from_secs(60);
+const IDLE_TIMEOUT: Duration = Duration::from_secs(3600);
+
+struct Task {
+    event_id: Id,
+    attempt: u32,
+    not_before: Instant,
+}
+
+pub struct GeoCodingQueue {
+    sender: Mutex<mpsc::Sender<Task>>,
+}
+
+impl GeoCodingQueue {
+    pub fn spawn(
+        connections: sqlite::Connections,
+        search_engine: tantivy::SearchEngine,
+        auto_fill_address: bool,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let mut worker = Worker {
+                connections,
+                search_engine,
+                auto_fill_address,
+                retries: vec![],
+            };
+            worker.run(receiver);
+        });
+        Self {
+            sender: Mutex::new(sender),
+        }
+    }
+
+    pub fn enqueue_event(&self, event_id: Id) {
+        let task = Task {
+            event_id,
+            attempt: 1,
+            not_before: Instant::now(),
+        };
+        let sender = self.sender.lock().unwrap_or_else(|err| err.into_inner());
+        if let Err(err) = sender.send(task) {
+            error!(
+                "Failed to enqueue geocoding of event {}: Queue has been closed",
+                err.0.event_id
+            );
+        }
+    }
+}
+
+struct Worker {
+    connections: sqlite::Connections,
+    search_engine: tantivy::SearchEngine,
+    auto_fill_address: bool,
+    retries: Vec<Task>,
+}
+
+impl Worker {
+    fn run(&mut self, receiver: mpsc::Receiver<Task>) {
+        loop {
+            let timeout = self
+                .retries
+                .iter()
+                .map(|task| task.not_before.saturating_duration_since(Instant::now()))
+                .min()
+                .unwrap_or(IDLE_TIMEOUT);
+            match receiver.recv_timeout(timeout) {
+                Ok(task) => self.process(task),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    debug!("Geocoding queue has been closed");
+                    return;
+                }
+            }
+            let now = Instant::now();
+            let (due, pending) = self
+                .retries
+                .drain(..)
+                .partition::<Vec<_>, _>(|task| task.not_before <= now);
+            self.retries = pending;
+            for task in due {
+                self.process(task);
+            }
+        }
+    }
+
+    fn process(&mut self, task: Task) {
+        let res = flows::geocode_event(
+            &self.connections,
+            &mut self.search_engine,
+            &*GEO_CODING_GW,
+            task.event_id.as_ref(),
+            self.auto_fill_address,
+        );
+        match res {
+            Ok(GeoCodingOutcome::Updated) => {
+                info!("Updated location of event {}", task.event_id);
+            }
+            Ok(GeoCodingOutcome::Unchanged) => {}
+            Ok(GeoCodingOutcome::Unresolved) => self.retry(task),
+            Err(AppError::Business(Error::Repo(RepoError::NotFound))) => {
+                debug!("Event {} has been deleted", task.event_id);
+            }
+            Err(err) => {
+                warn!(
+                    "Failed to resolve location of event {}: {}",
+                    task.event_id, err
+                );
+                self.retry(task);
+            }
+        }
+    }
+
+    fn retry(&mut self, task: Task) {
+        if task.attempt >= MAX_ATTEMPTS {
+            warn!(
+                "Giving up to resolve location of event {} after {} attempts",
+                task.event_id, task.attempt
+            );
+            return;
+        }
+        let Task {
+            event_id, attempt, ..
+        } = task;
+        self.retries.push(Task {
+            event_id,
+            attempt: attempt + 1,
+            not_before: Instant::now() + RETRY_DELAY * attempt,
+        });
+    }
+}
@@ -0,0 +1,24 @@
+//! Fetch the previews of place homepages periodically.
+
+use super::{db::sqlite, flows::prelude as flows};
+use crate::core::prelude::*;
+use ofdb_gateways::opengraph::OpenGraph;
+use std::{thread, time::Duration};
+
+// Previews are refreshed monthly, independent of the interval
+const MAX_PREVIEW_AGE: Duration = Duration::from_secs(30 * 24 * 3600);
+
+pub fn spawn(connections: sqlite::Connections, interval: Duration) {
+    thread::spawn(move || loop {
+        thread::sleep(interval);
+        // Each run respects the current robots.txt of the sites
+        let gateway = OpenGraph::default();
+        let fetched_since =
+            Timestamp::from_inner(Timestamp::now().into_inner() - MAX_PREVIEW_AGE.as_secs() as i64);
+        info!("Fetching previews of place homepages");
+        match flows::fetch_homepage_previews(&connections, &gateway, fetched_since) {
+            Ok(report) => info!("Fetched previews of place homepages: {:?}", report),
+            Err(err) => warn!("Failed to fetch previews of place homepages: {}", err),
+        }
+    });
+}
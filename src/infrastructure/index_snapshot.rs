@@ -0,0 +1,22 @@
+//! Copy the full-text search index periodically to restore
+//! it on startup if the index has been corrupted.
+
+use super::{cfg::IndexSnapshotCfg, db::tantivy};
+use std::thread;
+
+pub fn spawn(search_engine: tantivy::SearchEngine, cfg: IndexSnapshotCfg) {
+    if search_engine.index_dir().is_none() {
+        warn!("The full-text search index in RAM is not snapshotted");
+        return;
+    }
+    thread::spawn(move || loop {
+        thread::sleep(cfg.interval);
+        match search_engine.snapshot(&cfg.dir) {
+            Ok(()) => info!(
+                "Snapshotted full-text search index to {}",
+                cfg.dir.display()
+            ),
+            Err(err) => warn!("Failed to snapshot full-text search index: {}", err),
+        }
+    });
+}
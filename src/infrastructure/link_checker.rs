@@ -0,0 +1,20 @@
+//! Check the homepage and image links of places and events
+//! periodically to detect broken links.
+
+use super::{db::sqlite, flows::prelude as flows};
+use ofdb_gateways::link_checker::HttpLinkChecker;
+use std::{thread, time::Duration};
+
+pub fn spawn(connections: sqlite::Connections, interval: Duration) {
+    thread::spawn(move || {
+        let checker = HttpLinkChecker::default();
+        loop {
+            thread::sleep(interval);
+            info!("Checking links of places and events");
+            match flows::check_links(&connections, &checker) {
+                Ok(report) => info!("Checked links of places and events: {:?}", report),
+                Err(err) => warn!("Failed to check links of places and events: {}", err),
+            }
+        }
+    });
+}
@@ -0,0 +1,38 @@
+//! Replicate the places and events of an upstream
+//! OpenFairDB instance into a read-only mirror.
+
+use super::{
+    db::{sqlite, tantivy},
+    flows::prelude as flows,
+};
+use crate::core::prelude::*;
+use ofdb_gateways::openfairdb::OpenFairDb;
+use std::{thread, time::Duration};
+
+pub fn spawn(
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    upstream_url: String,
+    interval: Duration,
+) {
+    thread::spawn(move || {
+        let upstream = OpenFairDb::new(upstream_url);
+        // Replaying the whole change log after a restart
+        // doesn't modify places and events that are up to date
+        let mut since = Timestamp::from_seconds(0);
+        loop {
+            match flows::mirror_upstream(&connections, &mut search_engine, &upstream, since) {
+                Ok((report, last_change_at)) => {
+                    info!("Mirrored upstream changes: {:?}", report);
+                    if last_change_at > since {
+                        // Fetch the remaining changes without delay
+                        since = last_change_at;
+                        continue;
+                    }
+                }
+                Err(err) => warn!("Failed to mirror upstream changes: {}", err),
+            }
+            thread::sleep(interval);
+        }
+    });
+}
@@ -1,24 +1,83 @@
 pub mod cfg;
+pub mod confirmation_campaign;
 pub mod db;
+pub mod email_outbox;
 pub mod error;
+pub mod event_scheduler;
 pub mod flows;
+pub mod geocoding_queue;
+pub mod homepage_previews;
+pub mod index_snapshot;
+pub mod link_checker;
+pub mod mirror;
+pub mod moderated_tags_summaries;
+pub mod notification_digests;
+pub mod osm_resync;
+pub mod review_digest;
+pub mod user_deletion;
 
+use self::cfg::{GeoCodingProvider, GeoCodingProviderCfg};
+use ofdb_core::gateways::geocode::{
+    GeoCodingGateway, GeoCodingGatewayChain, RateLimitedGeoCodingGateway,
+};
 use ofdb_entities::email::*;
-use ofdb_gateways::{mailgun::*, opencage::*, sendmail::*};
-use std::env;
+use ofdb_gateways::{
+    mailgun::*, maxmind::*, nominatim::*, opencage::*, photon::*, sendmail::*, smtp::*,
+};
+use std::{env, path::Path, time::Duration};
+
+const DEFAULT_SMTP_TIMEOUT_SECONDS: u64 = 30;
+
+fn geocoding_gateway(providers: &[GeoCodingProviderCfg]) -> GeoCodingGatewayChain {
+    let gateways = providers
+        .iter()
+        .map(|cfg| {
+            let min_interval = cfg.min_request_interval;
+            let gw: Box<dyn GeoCodingGateway + Send + Sync> = match &cfg.provider {
+                GeoCodingProvider::OpenCage { api_key } => {
+                    Box::new(RateLimitedGeoCodingGateway::new(
+                        OpenCage::new(Some(api_key.clone())),
+                        min_interval,
+                    ))
+                }
+                GeoCodingProvider::Nominatim { api_url } => Box::new(
+                    RateLimitedGeoCodingGateway::new(Nominatim::new(api_url.clone()), min_interval),
+                ),
+                GeoCodingProvider::Photon { api_url } => Box::new(
+                    RateLimitedGeoCodingGateway::new(Photon::new(api_url.clone()), min_interval),
+                ),
+            };
+            gw
+        })
+        .collect();
+    GeoCodingGatewayChain::new(gateways)
+}
+
+pub fn ip_geolocation_gateway(db_path: &Path) -> Option<MaxMindDb> {
+    match MaxMindDb::open(db_path) {
+        Ok(gateway) => {
+            info!("Locating IP addresses with {}", db_path.display());
+            Some(gateway)
+        }
+        Err(err) => {
+            error!(
+                "Failed to open IP geolocation database {}: {}",
+                db_path.display(),
+                err
+            );
+            None
+        }
+    }
+}
 
 lazy_static! {
 
-    // TODO: move this to crate::cfg
-    pub static ref GEO_CODING_GW: OpenCage = {
-        let key = match env::var("OPENCAGE_API_KEY") {
-            Ok(key) => Some(key),
-            Err(_) => {
-                warn!("No OpenCage API key found");
-                None
-            }
-        };
-        OpenCage::new(key)
+    pub static ref GEO_CODING_GW: GeoCodingGatewayChain = {
+        geocoding_gateway(&cfg::geocoding_providers_from_env())
+    };
+
+    pub static ref MODERATED_TAGS_NOTIFICATION_WINDOW: Option<Duration> = {
+        cfg::moderated_tags_notification_window_from_env()
     };
 
     pub static ref MAILGUN_GW: Option<Mailgun> = {
@@ -43,6 +102,55 @@ lazy_static! {
 
     };
 
+    pub static ref SMTP_GW: Option<Smtp> = {
+        let host = env::var("SMTP_HOST").ok().filter(|host| !host.trim().is_empty());
+        let from = env::var("MAIL_GATEWAY_SENDER_ADDRESS");
+
+        if let (Some(host), Ok(mail)) = (host, from) {
+            let security = env::var("SMTP_SECURITY")
+                .ok()
+                .and_then(|security| {
+                    security
+                        .parse()
+                        .map_err(|err| warn!("{}: falling back to STARTTLS", err))
+                        .ok()
+                })
+                .unwrap_or(SmtpSecurity::StartTls);
+            let credentials = match (env::var("SMTP_USERNAME"), env::var("SMTP_PASSWORD")) {
+                (Ok(user), Ok(password)) => Some((user, password)),
+                _ => None,
+            };
+            let seconds_from_env = |key: &str| -> Option<u64> {
+                env::var(key).ok().and_then(|seconds| seconds.parse().ok())
+            };
+            let default_retry = RetryPolicy::default();
+            let retry = RetryPolicy {
+                max_retries: env::var("SMTP_MAX_RETRIES")
+                    .ok()
+                    .and_then(|retries| retries.parse().ok())
+                    .unwrap_or(default_retry.max_retries),
+                initial_backoff: seconds_from_env("SMTP_RETRY_BACKOFF_SECONDS")
+                    .map(Duration::from_secs)
+                    .unwrap_or(default_retry.initial_backoff),
+            };
+            Some(Smtp {
+                from: Email::from(mail),
+                host,
+                port: env::var("SMTP_PORT").ok().and_then(|port| port.parse().ok()),
+                security,
+                credentials,
+                timeout: Duration::from_secs(
+                    seconds_from_env("SMTP_TIMEOUT_SECONDS")
+                        .filter(|seconds| *seconds > 0)
+                        .unwrap_or(DEFAULT_SMTP_TIMEOUT_SECONDS),
+                ),
+                retry,
+            })
+        } else {
+            None
+        }
+    };
+
     pub static ref SENDMAIL_GW: Option<Sendmail> = {
         let from = env::var("MAIL_GATEWAY_SENDER_ADDRESS");
         if let Ok(mail) = from {
@@ -56,5 +164,15 @@ lazy_static! {
     };
 }
 
+/// The geocoding gateway for resolving missing addresses
+/// of new places and events, if enabled.
+pub fn auto_fill_address_gateway(cfg: &cfg::Cfg) -> Option<&'static dyn GeoCodingGateway> {
+    if cfg.auto_fill_address {
+        Some(&*GEO_CODING_GW)
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests;
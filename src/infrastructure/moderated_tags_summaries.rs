@@ -0,0 +1,27 @@
+//! Send the collected outside edits of moderated tags
+//! to the organizations as one summary per window.
+
+use super::{db::sqlite, flows::prelude as flows};
+use crate::core::prelude::*;
+use ofdb_core::gateways::notify::NotificationGateway;
+use std::{ops::Deref, thread, time::Duration};
+
+const INTERVAL: Duration = Duration::from_secs(60);
+
+pub fn spawn<N>(connections: sqlite::Connections, notify: N, window: Duration)
+where
+    N: Deref<Target = dyn NotificationGateway> + Send + 'static,
+{
+    thread::spawn(move || loop {
+        thread::sleep(INTERVAL.min(window));
+        match flows::send_moderated_tags_summaries(&connections, &*notify, window, Timestamp::now())
+        {
+            Ok(0) => {}
+            Ok(count) => info!("Sent {} summaries of moderated tags changes", count),
+            Err(err) => warn!(
+                "Failed to send summaries of moderated tags changes: {}",
+                err
+            ),
+        }
+    });
+}
@@ -0,0 +1,19 @@
+//! Send the collected changes to subscribers
+//! that receive daily or weekly digests.
+
+use super::{db::sqlite, flows::prelude as flows};
+use crate::core::prelude::*;
+use std::{thread, time::Duration};
+
+const INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+pub fn spawn(connections: sqlite::Connections) {
+    thread::spawn(move || loop {
+        thread::sleep(INTERVAL);
+        match flows::queue_notification_digests(&connections, Timestamp::now()) {
+            Ok(0) => {}
+            Ok(count) => info!("Queued {} notification digest(s)", count),
+            Err(err) => warn!("Failed to queue notification digests: {}", err),
+        }
+    });
+}
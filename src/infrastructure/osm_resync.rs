@@ -0,0 +1,24 @@
+//! Keep places that have been imported from OpenStreetMap
+//! aligned with their upstream nodes.
+
+use super::{db::sqlite, flows::prelude as flows};
+use ofdb_gateways::overpass::Overpass;
+use std::{thread, time::Duration};
+
+pub fn spawn(
+    connections: sqlite::Connections,
+    overpass_api_url: Option<String>,
+    interval: Duration,
+) {
+    thread::spawn(move || {
+        let osm = Overpass::new(overpass_api_url);
+        loop {
+            thread::sleep(interval);
+            info!("Re-syncing places with OpenStreetMap");
+            match flows::resync_osm_nodes(&connections, &osm) {
+                Ok(report) => info!("Re-synced places with OpenStreetMap: {:?}", report),
+                Err(err) => warn!("Failed to re-sync places with OpenStreetMap: {}", err),
+            }
+        }
+    });
+}
@@ -0,0 +1,16 @@
+//! Remind scouts of the places in their areas
+//! that are waiting for a review.
+
+use super::{db::sqlite, flows::prelude as flows};
+use std::{thread, time::Duration};
+
+pub fn spawn(connections: sqlite::Connections, interval: Duration) {
+    thread::spawn(move || loop {
+        thread::sleep(interval);
+        match flows::queue_review_digests(&connections) {
+            Ok(0) => {}
+            Ok(count) => info!("Queued {} review digest(s) for scouts", count),
+            Err(err) => warn!("Failed to queue review digests for scouts: {}", err),
+        }
+    });
+}
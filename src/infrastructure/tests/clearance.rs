@@ -38,6 +38,7 @@ impl PlaceClearanceFixture {
                 email: &user_email,
                 password: "password",
             },
+            &Default::default(),
         )
         .unwrap();
 
@@ -116,12 +117,16 @@ impl PlaceClearanceFixture {
             id: Id::new(),
             name: "organization_without_moderated_tags".into(),
             api_token: "organization_without_moderated_tags".into(),
+            notification_email: None,
+            email_branding: Default::default(),
             moderated_tags: vec![],
         };
         let organization_with_add_clearance_tag = Organization {
             id: Id::new(),
             name: "organization_with_add_clearance_tag".into(),
             api_token: "organization_with_add_clearance_tag".into(),
+            notification_email: None,
+            email_branding: Default::default(),
             moderated_tags: vec![ModeratedTag {
                 label: "add_clearance".into(),
                 allow_add: true,
@@ -133,6 +138,8 @@ impl PlaceClearanceFixture {
             id: Id::new(),
             name: "organization_with_remove_clearance_tag".into(),
             api_token: "organization_with_remove_clearance_tag".into(),
+            notification_email: None,
+            email_branding: Default::default(),
             moderated_tags: vec![ModeratedTag {
                 label: "remove_clearance".into(),
                 allow_add: false,
@@ -144,6 +151,8 @@ impl PlaceClearanceFixture {
             id: Id::new(),
             name: "organization_with_add_remove_clearance_tag".into(),
             api_token: "organization_with_add_remove_clearance_tag".into(),
+            notification_email: None,
+            email_branding: Default::default(),
             moderated_tags: vec![ModeratedTag {
                 label: "add_remove_clearance".into(),
                 allow_add: true,
@@ -628,6 +637,7 @@ fn should_return_the_last_cleared_revision_when_loading_or_searching_cleared_pla
         usecases::clearance::place::update_pending_clearances(
             &*fixture.backend.db_connections.exclusive()?,
             &org,
+            None,
             &[ClearanceForPlace {
                 place_id: place_id.clone(),
                 cleared_revision: None,
@@ -866,6 +876,7 @@ fn should_fail_when_trying_to_clear_future_revisions_of_places() -> flows::Resul
     assert!(usecases::clearance::place::update_pending_clearances(
         &*fixture.backend.db_connections.exclusive()?,
         &org,
+        None,
         &[ClearanceForPlace {
             place_id: place_id.clone(),
             cleared_revision: Some(new_revision.next()),
@@ -907,6 +918,7 @@ fn should_do_nothing_when_clearing_places_without_pending_clearances() -> flows:
         usecases::clearance::place::update_pending_clearances(
             &*fixture.backend.db_connections.exclusive()?,
             &org,
+            None,
             &[
                 ClearanceForPlace {
                     place_id: fixture.created_place.id.clone(),
@@ -932,3 +944,96 @@ fn should_do_nothing_when_clearing_places_without_pending_clearances() -> flows:
 
     Ok(())
 }
+
+#[test]
+fn should_record_decisions_about_pending_clearances() -> flows::Result<()> {
+    let mut fixture = PlaceClearanceFixture::new();
+    let org = fixture.organization_with_add_remove_clearance_tag;
+    let tag = &org.moderated_tags.first().unwrap().label;
+    let old_place = &fixture.created_place;
+    let place_id = &old_place.id;
+    let last_cleared_revision = old_place.revision;
+
+    let mut update_place = usecases::UpdatePlace::from(old_place.clone());
+    update_place.tags = vec![tag.clone()];
+    update_place.version = old_place.revision.next().into();
+    let new_place = flows::update_place(
+        &fixture.backend.db_connections,
+        fixture.backend.search_engine.get_mut(),
+        &fixture.backend.notify,
+        place_id.clone(),
+        update_place,
+        None,
+        None,
+        &Cfg::default(),
+    )?;
+
+    // Reject by keeping the last cleared revision
+    assert_eq!(
+        1,
+        usecases::clearance::place::update_pending_clearances(
+            &*fixture.backend.db_connections.exclusive()?,
+            &org,
+            None,
+            &[
+                ClearanceForPlace {
+                    place_id: place_id.clone(),
+                    cleared_revision: Some(last_cleared_revision),
+                },
+                // Not pending and not recorded
+                ClearanceForPlace {
+                    place_id: fixture.confirmed_place.id.clone(),
+                    cleared_revision: None,
+                }
+            ],
+        )?
+    );
+    // Approve the current revision with a revocable API token
+    let (api_token, _) =
+        OrganizationApiToken::generate(org.id.clone(), Some("CMS".into()), None, Timestamp::now());
+    assert_eq!(
+        1,
+        usecases::clearance::place::update_pending_clearances(
+            &*fixture.backend.db_connections.exclusive()?,
+            &org,
+            Some(&api_token),
+            &[ClearanceForPlace {
+                place_id: place_id.clone(),
+                cleared_revision: None,
+            }],
+        )?
+    );
+
+    let log = usecases::clearance::place::list_clearance_log(
+        &*fixture.backend.db_connections.shared()?,
+        Some(&org),
+        None,
+        &Default::default(),
+    )?;
+    assert_eq!(2, log.len());
+    assert!(log.iter().all(|entry| entry.org_id == org.id
+        && entry.place_id == *place_id
+        && entry.place_revision == new_place.revision));
+    assert_eq!(ClearanceDecision::Approved, log[0].decision);
+    assert_eq!(new_place.revision, log[0].cleared_revision);
+    assert_eq!(ClearanceDecision::Rejected, log[1].decision);
+    assert_eq!(last_cleared_revision, log[1].cleared_revision);
+    assert_eq!(Some(api_token.id), log[0].api_token_id);
+    assert_eq!(Some("CMS"), log[0].api_token_label.as_deref());
+    assert_eq!(
+        Some(usecases::LEGACY_ORGANIZATION_API_TOKEN_ID),
+        log[1].api_token_id.as_ref().map(Id::as_str)
+    );
+    assert!(log[1].api_token_label.is_none());
+
+    // Other organizations don't see these decisions
+    assert!(usecases::clearance::place::list_clearance_log(
+        &*fixture.backend.db_connections.shared()?,
+        Some(&fixture.organization_with_add_clearance_tag),
+        None,
+        &Default::default(),
+    )?
+    .is_empty());
+
+    Ok(())
+}
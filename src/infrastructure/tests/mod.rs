@@ -48,5 +48,16 @@ fn default_search_request<'a>() -> usecases::SearchRequest<'a> {
         ids: vec![],
         status: vec![],
         text: None,
+        country: None,
+        region: None,
+        excluded_categories: vec![],
+        excluded_hash_tags: vec![],
+        min_avg_rating: None,
+        min_rating_count: None,
+        confirmed_since: None,
+        boost_freshness: false,
+        order_by_quality: false,
+        fuzziness: None,
+        area: None,
     }
 }
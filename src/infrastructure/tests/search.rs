@@ -151,3 +151,438 @@ fn should_find_places_by_tags() -> flows::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn should_find_places_by_country_and_region() -> flows::Result<()> {
+    let fixture = flows::BackendFixture::new();
+
+    let create_place = |title: &str, country: Option<&str>, state: Option<&str>| {
+        flows::create_place(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            usecases::NewPlace {
+                title: title.into(),
+                description: title.into(),
+                country: country.map(Into::into),
+                state: state.map(Into::into),
+                ..default_new_place()
+            },
+            None,
+            None,
+            &Cfg::default(),
+        )
+        .unwrap()
+    };
+
+    let place_without_address = create_place("place", None, None);
+    let place_de_by = create_place("place_de_by", Some("DE"), Some("Bayern"));
+    let place_de_be = create_place("place_de_be", Some("DE"), Some("Berlin"));
+    let place_at = create_place("place_at", Some("AT"), None);
+
+    let search_ids = |country, region| -> flows::Result<Vec<Id>> {
+        Ok(usecases::search(
+            &*fixture.db_connections.shared()?,
+            &*fixture.search_engine.borrow(),
+            usecases::SearchRequest {
+                country,
+                region,
+                ..default_search_request()
+            },
+            100,
+        )?
+        .0
+        .into_iter()
+        .map(|p| p.id.into())
+        .collect())
+    };
+
+    let search_de_ids = search_ids(Some("de"), None)?;
+    assert_eq!(2, search_de_ids.len());
+    assert!(!search_de_ids.contains(&place_without_address.id));
+    assert!(search_de_ids.contains(&place_de_by.id));
+    assert!(search_de_ids.contains(&place_de_be.id));
+    assert!(!search_de_ids.contains(&place_at.id));
+
+    let search_de_berlin_ids = search_ids(Some("DE"), Some("berlin"))?;
+    assert_eq!(vec![place_de_be.id.clone()], search_de_berlin_ids);
+
+    let search_at_berlin_ids = search_ids(Some("AT"), Some("Berlin"))?;
+    assert!(search_at_berlin_ids.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn should_exclude_places_by_tags_and_categories() -> flows::Result<()> {
+    let fixture = flows::BackendFixture::new();
+
+    let create_place = |title: &str, categories: Vec<&str>, tags: Vec<&str>| {
+        flows::create_place(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            usecases::NewPlace {
+                title: title.into(),
+                description: title.into(),
+                categories: categories.into_iter().map(Into::into).collect(),
+                tags: tags.into_iter().map(Into::into).collect(),
+                ..default_new_place()
+            },
+            None,
+            None,
+            &Cfg::default(),
+        )
+        .unwrap()
+    };
+
+    let place_foo = create_place("place_foo", vec![Category::ID_NON_PROFIT], vec!["foo"]);
+    let place_bar = create_place("place_bar", vec![Category::ID_COMMERCIAL], vec!["bar"]);
+    let place_foo_bar = create_place(
+        "place_foo_bar",
+        vec![Category::ID_NON_PROFIT],
+        vec!["foo", "bar"],
+    );
+
+    let search_ids = |excluded_categories, excluded_hash_tags| -> flows::Result<Vec<Id>> {
+        Ok(usecases::search(
+            &*fixture.db_connections.shared()?,
+            &*fixture.search_engine.borrow(),
+            usecases::SearchRequest {
+                excluded_categories,
+                excluded_hash_tags,
+                ..default_search_request()
+            },
+            100,
+        )?
+        .0
+        .into_iter()
+        .map(|p| p.id.into())
+        .collect())
+    };
+
+    let search_without_bar_ids = search_ids(vec![], vec!["Bar"])?;
+    assert_eq!(vec![place_foo.id.clone()], search_without_bar_ids);
+
+    let search_without_commercial_ids = search_ids(vec![Category::ID_COMMERCIAL], vec![])?;
+    assert_eq!(2, search_without_commercial_ids.len());
+    assert!(search_without_commercial_ids.contains(&place_foo.id));
+    assert!(!search_without_commercial_ids.contains(&place_bar.id));
+    assert!(search_without_commercial_ids.contains(&place_foo_bar.id));
+
+    let search_without_non_profit_and_bar_ids =
+        search_ids(vec![Category::ID_NON_PROFIT], vec!["bar"])?;
+    assert!(search_without_non_profit_and_bar_ids.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn should_find_nearby_places_sorted_by_distance() -> flows::Result<()> {
+    let fixture = flows::BackendFixture::new();
+
+    let create_place = |title: &str, lat: f64, tags: Vec<String>| {
+        flows::create_place(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            usecases::NewPlace {
+                title: title.into(),
+                description: title.into(),
+                lat,
+                lng: 9.0,
+                tags,
+                ..default_new_place()
+            },
+            None,
+            None,
+            &Cfg::default(),
+        )
+        .unwrap()
+    };
+
+    // 0.001° latitude are about 111 m
+    let place_far = create_place("far", 48.009, vec!["foo".into()]);
+    let place_near = create_place("near", 48.001, vec![]);
+    let place_nearer = create_place("nearer", 48.0005, vec!["foo".into()]);
+    let _place_outside = create_place("outside", 48.02, vec!["foo".into()]);
+
+    let center = MapPoint::from_lat_lng_deg(48.0, 9.0);
+    let search_nearby = |hash_tags: Vec<String>| -> flows::Result<Vec<(Id, Distance)>> {
+        Ok(usecases::search_nearby(
+            &*fixture.search_engine.borrow(),
+            center,
+            Distance::from_meters(1_500.0),
+            hash_tags,
+            100,
+        )?
+        .into_iter()
+        .map(|(p, d)| (p.id.into(), d))
+        .collect())
+    };
+
+    let results = search_nearby(vec![])?;
+    let ids: Vec<_> = results.iter().map(|(id, _)| id.clone()).collect();
+    assert_eq!(
+        vec![place_nearer.id, place_near.id, place_far.id.clone()],
+        ids
+    );
+    assert!(results[0].1 < results[1].1);
+    assert!(results[2].1 < Distance::from_meters(1_500.0));
+
+    let results = search_nearby(vec!["foo".into()])?;
+    assert_eq!(2, results.len());
+    assert_eq!(place_far.id, results[1].0);
+
+    Ok(())
+}
+
+#[test]
+fn should_find_places_by_rating_thresholds() -> flows::Result<()> {
+    let fixture = flows::BackendFixture::new();
+
+    let create_place = |title: &str| {
+        flows::create_place(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            usecases::NewPlace {
+                title: title.into(),
+                description: title.into(),
+                ..default_new_place()
+            },
+            None,
+            None,
+            &Cfg::default(),
+        )
+        .unwrap()
+    };
+    let rate_place = |place: &Place, value: i8| {
+        fixture.create_rating(usecases::NewPlaceRating {
+            entry: place.id.to_string(),
+            title: "rating".into(),
+            value: ofdb_boundary::RatingValue::from(value),
+            context: ofdb_boundary::RatingContext::Fairness,
+            comment: "comment".into(),
+            source: None,
+            user: None,
+        });
+    };
+
+    let unrated = create_place("unrated");
+    let rated_once = create_place("rated_once");
+    rate_place(&rated_once, 2);
+    let rated_twice = create_place("rated_twice");
+    rate_place(&rated_twice, 2);
+    rate_place(&rated_twice, 1);
+    let rated_badly = create_place("rated_badly");
+    rate_place(&rated_badly, -1);
+    rate_place(&rated_badly, -1);
+
+    let search_ids = |min_avg_rating: Option<f64>, min_rating_count| -> flows::Result<Vec<Id>> {
+        let mut ids: Vec<Id> = usecases::search(
+            &*fixture.db_connections.shared()?,
+            &*fixture.search_engine.borrow(),
+            usecases::SearchRequest {
+                min_avg_rating: min_avg_rating.map(Into::into),
+                min_rating_count,
+                ..default_search_request()
+            },
+            100,
+        )?
+        .0
+        .into_iter()
+        .map(|p| p.id.into())
+        .collect();
+        ids.sort_unstable();
+        Ok(ids)
+    };
+    let sorted_ids = |places: Vec<&Place>| {
+        let mut ids: Vec<_> = places.into_iter().map(|p| p.id.clone()).collect();
+        ids.sort_unstable();
+        ids
+    };
+
+    assert_eq!(4, search_ids(None, None)?.len());
+    assert_eq!(
+        sorted_ids(vec![&unrated, &rated_once, &rated_twice]),
+        search_ids(Some(0.0), None)?
+    );
+    assert_eq!(
+        sorted_ids(vec![&rated_twice, &rated_badly]),
+        search_ids(None, Some(2))?
+    );
+    assert_eq!(
+        sorted_ids(vec![&rated_once, &rated_twice]),
+        search_ids(Some(0.01), Some(1))?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn should_find_and_boost_recently_confirmed_places() -> flows::Result<()> {
+    let fixture = flows::BackendFixture::new();
+
+    let create_place = |title: &str| {
+        flows::create_place(
+            &fixture.db_connections,
+            &mut *fixture.search_engine.borrow_mut(),
+            &fixture.notify,
+            usecases::NewPlace {
+                title: title.into(),
+                description: title.into(),
+                ..default_new_place()
+            },
+            None,
+            None,
+            &Cfg::default(),
+        )
+        .unwrap()
+    };
+    let confirm_place = |place: &Place, at: TimestampMs| -> flows::Result<()> {
+        let db = fixture.db_connections.exclusive()?;
+        db.review_places(
+            &[place.id.as_str()],
+            ReviewStatus::Confirmed,
+            &ActivityLog {
+                activity: Activity { at, by: None },
+                context: None,
+                comment: None,
+            },
+        )?;
+        let last_confirmed_at = db.last_confirmed_at(place.id.as_str())?;
+        assert_eq!(Some(at), last_confirmed_at);
+        let mut search_engine = fixture.search_engine.borrow_mut();
+        usecases::reindex_place(
+            &*search_engine,
+            place,
+            ReviewStatus::Confirmed,
+            last_confirmed_at,
+            &[],
+        )
+        .and_then(|_| search_engine.flush_index())
+        .unwrap();
+        Ok(())
+    };
+
+    let days_ago = |days| TimestampMs::from(chrono::Utc::now() - chrono::Duration::days(days));
+    let unconfirmed = create_place("unconfirmed");
+    let confirmed_long_ago = create_place("confirmed_long_ago");
+    confirm_place(&confirmed_long_ago, days_ago(5 * 365))?;
+    let confirmed_recently = create_place("confirmed_recently");
+    confirm_place(&confirmed_recently, days_ago(1))?;
+
+    let search_ids = |confirmed_since, boost_freshness| -> flows::Result<Vec<Id>> {
+        Ok(usecases::search(
+            &*fixture.db_connections.shared()?,
+            &*fixture.search_engine.borrow(),
+            usecases::SearchRequest {
+                confirmed_since,
+                boost_freshness,
+                ..default_search_request()
+            },
+            100,
+        )?
+        .0
+        .into_iter()
+        .map(|p| p.id.into())
+        .collect())
+    };
+
+    assert_eq!(3, search_ids(None, false)?.len());
+    assert_eq!(
+        vec![confirmed_recently.id.clone()],
+        search_ids(Some(days_ago(30)), false)?
+    );
+    let mut ids = search_ids(Some(days_ago(10 * 365)), false)?;
+    ids.sort_unstable();
+    let mut expected = vec![confirmed_long_ago.id.clone(), confirmed_recently.id.clone()];
+    expected.sort_unstable();
+    assert_eq!(expected, ids);
+    assert_eq!(
+        vec![confirmed_recently.id, confirmed_long_ago.id, unconfirmed.id],
+        search_ids(None, true)?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn should_restore_corrupt_index_from_snapshot() {
+    use crate::infrastructure::db::tantivy::SearchEngine;
+    use std::fs;
+
+    let dir = std::env::temp_dir().join(format!("openfairdb-{}", Id::new()));
+    let index_dir = dir.join("index");
+    let snapshot_dir = dir.join("snapshot");
+    let place = Place::build().id("snapshot").title("snapshot").finish();
+    let query = IndexQuery {
+        ids: vec!["snapshot"],
+        ..Default::default()
+    };
+
+    {
+        let mut search_engine =
+            SearchEngine::open_or_restore(&index_dir, Some(&snapshot_dir), None).unwrap();
+        search_engine
+            .add_or_update_place(&place, ReviewStatus::Created, None, &Default::default())
+            .unwrap();
+        search_engine.flush_index().unwrap();
+        search_engine.snapshot(&snapshot_dir).unwrap();
+    }
+
+    fs::write(index_dir.join("meta.json"), "corrupt").unwrap();
+    let search_engine =
+        SearchEngine::open_or_restore(&index_dir, Some(&snapshot_dir), None).unwrap();
+    assert_eq!(1, search_engine.query_places(&query, 10).unwrap().len());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn should_find_places_by_stems_and_parts_of_compounds() {
+    use crate::infrastructure::db::tantivy::{SearchEngine, TextLanguage};
+
+    let search_ids = |search_engine: &SearchEngine, text: &str| -> Vec<String> {
+        let query = IndexQuery {
+            text: Some(text.into()),
+            ..Default::default()
+        };
+        search_engine
+            .query_places(&query, 10)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.id)
+            .collect()
+    };
+    let add_place = |search_engine: &mut SearchEngine, id: &str, title: &str| {
+        let place = Place::build().id(id).title(title).finish();
+        search_engine
+            .add_or_update_place(&place, ReviewStatus::Created, None, &Default::default())
+            .unwrap();
+    };
+
+    let mut german = SearchEngine::init_in_ram_with_language(Some(TextLanguage::German)).unwrap();
+    add_place(&mut german, "bioladen", "Bioladen am Markt");
+    add_place(&mut german, "laden", "Unverpackt-Laden");
+    german.flush_index().unwrap();
+    let mut expected = vec!["bioladen".to_string(), "laden".to_string()];
+    expected.sort_unstable();
+    let mut ids = search_ids(&german, "Laden");
+    ids.sort_unstable();
+    assert_eq!(expected, ids);
+    assert_eq!(vec!["bioladen"], search_ids(&german, "Bioläden"));
+    assert_eq!(vec!["bioladen"], search_ids(&german, "Markts"));
+
+    let mut english = SearchEngine::init_in_ram_with_language(Some(TextLanguage::English)).unwrap();
+    add_place(&mut english, "shop", "Organic shops");
+    english.flush_index().unwrap();
+    assert_eq!(vec!["shop"], search_ids(&english, "shop"));
+
+    // Without a language words must match exactly
+    let mut unknown = SearchEngine::init_in_ram().unwrap();
+    add_place(&mut unknown, "bioladen", "Bioladen am Markt");
+    unknown.flush_index().unwrap();
+    assert!(search_ids(&unknown, "laden").is_empty());
+}
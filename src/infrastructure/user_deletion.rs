@@ -0,0 +1,20 @@
+//! Anonymize deleted users after the grace period.
+
+use super::{db::sqlite, flows::prelude as flows};
+use crate::core::prelude::*;
+use std::{thread, time::Duration};
+
+const INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+pub fn spawn(connections: sqlite::Connections, grace_period: Duration) {
+    thread::spawn(move || loop {
+        thread::sleep(INTERVAL);
+        let deactivated_before =
+            Timestamp::from_inner(Timestamp::now().into_inner() - grace_period.as_secs() as i64);
+        match flows::anonymize_deactivated_users(&connections, deactivated_before) {
+            Ok(0) => {}
+            Ok(count) => info!("Anonymized {} deleted users", count),
+            Err(err) => warn!("Failed to anonymize deleted users: {}", err),
+        }
+    });
+}
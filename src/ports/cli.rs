@@ -1,17 +1,20 @@
 use crate::{
-    core::prelude::*,
+    adapters::json,
+    core::{prelude::*, usecases},
     infrastructure::{
         cfg::Cfg,
         db::{sqlite, tantivy},
+        flows::prelude as flows,
         GEO_CODING_GW,
     },
     ports::web,
 };
 
-use clap::{crate_authors, App, Arg};
+use clap::{crate_authors, App, Arg, ArgMatches, SubCommand};
 use dotenv::dotenv;
 use ofdb_core::gateways::geocode::GeoCodingGateway;
-use std::{env, path::Path};
+use ofdb_gateways::overpass::Overpass;
+use std::{env, fs, path::Path};
 
 embed_migrations!();
 
@@ -37,6 +40,163 @@ fn update_event_locations<D: Db>(db: &mut D) -> Result<()> {
     Ok(())
 }
 
+fn anonymize_user(connections: &sqlite::Connections, matches: &ArgMatches) {
+    let email = matches.value_of("email").expect("e-mail address");
+    match flows::anonymize_user(connections, email) {
+        Ok(records) => {
+            let AnonymizedUserRecords {
+                place_revisions,
+                place_reviews,
+                ratings,
+                comments,
+                events,
+            } = records;
+            println!("Anonymized user {}", email);
+            println!("  place revisions: {}", place_revisions);
+            println!("  place reviews:   {}", place_reviews);
+            println!("  ratings:         {}", ratings);
+            println!("  comments:        {}", comments);
+            println!("  events:          {}", events);
+        }
+        Err(err) => {
+            error!("Failed to anonymize user {}: {}", email, err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn reactivate_user(connections: &sqlite::Connections, matches: &ArgMatches) {
+    let email = matches.value_of("email").expect("e-mail address");
+    let reactivated = connections
+        .exclusive()
+        .map_err(|err| Error::Repo(RepoError::Other(err)))
+        .and_then(|db| usecases::reactivate_user(&*db, email));
+    match reactivated {
+        Ok(()) => println!("Reactivated user {}", email),
+        Err(err) => {
+            error!("Failed to reactivate user {}: {}", email, err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn check_db(connections: &sqlite::Connections) {
+    let places = connections
+        .shared()
+        .map_err(|err| Error::Repo(RepoError::Other(err)))
+        .and_then(|db| usecases::find_places_with_position_issues(&*db));
+    match places {
+        Ok(places) => {
+            for (place, issue) in &places {
+                let issue = match issue {
+                    usecases::PositionIssue::Invalid => "invalid position",
+                    usecases::PositionIssue::NullIsland => "position 0/0",
+                };
+                println!("Place {} ({}): {}", place.id, place.title, issue);
+            }
+            println!("Found {} place(s) with a suspicious position", places.len());
+        }
+        Err(err) => {
+            error!("Failed to check the database: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn compute_tag_suggestions(connections: &sqlite::Connections) {
+    let count = connections
+        .exclusive()
+        .map_err(|err| Error::Repo(RepoError::Other(err)))
+        .and_then(|db| usecases::compute_tag_cooccurrences(&*db));
+    match count {
+        Ok(count) => println!("Stored {} combination(s) of words and tags", count),
+        Err(err) => {
+            error!("Failed to compute tag suggestions: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn recompute_ratings(connections: &sqlite::Connections) {
+    let count = connections
+        .exclusive()
+        .map_err(|err| Error::Repo(RepoError::Other(err)))
+        .and_then(|db| Ok(db.recompute_rating_aggregates()?));
+    match count {
+        Ok(count) => println!("Recomputed the aggregated ratings of {} place(s)", count),
+        Err(err) => {
+            error!("Failed to recompute ratings: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn import_osm(
+    connections: &sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    cfg: &Cfg,
+    matches: &ArgMatches,
+) {
+    let read_file = |name: &str| {
+        let path = matches.value_of(name).expect("file path");
+        fs::read_to_string(path).unwrap_or_else(|err| {
+            error!("Failed to read {}: {}", path, err);
+            std::process::exit(1);
+        })
+    };
+    let query = read_file("query");
+    let mapping: json::OsmTagMapping =
+        serde_json::from_str(&read_file("mapping")).unwrap_or_else(|err| {
+            error!("Invalid OSM tag mapping: {}", err);
+            std::process::exit(1);
+        });
+    let org = matches.value_of("org-token").map(|token| {
+        connections
+            .shared()
+            .map_err(|err| Error::Repo(RepoError::Other(err)))
+            .and_then(|db| Ok(db.get_org_by_api_token(token)?))
+            .unwrap_or_else(|err| {
+                error!("Failed to load organization: {}", err);
+                std::process::exit(1);
+            })
+    });
+    info!("Indexing all places...");
+    web::index_all_places(&*connections.shared().unwrap(), &mut search_engine).unwrap();
+    let osm = Overpass::new(cfg.overpass_api_url.clone());
+    match flows::import_osm_nodes(
+        connections,
+        &mut search_engine,
+        &osm,
+        &query,
+        &mapping.into(),
+        org.as_ref(),
+        cfg,
+        None,
+        false,
+    ) {
+        Ok(report) => {
+            let usecases::OsmImportReport {
+                imported,
+                already_imported,
+                duplicates,
+                unmapped,
+                failed,
+                ..
+            } = report;
+            println!("Imported OSM nodes");
+            println!("  imported:         {}", imported);
+            println!("  already imported: {}", already_imported);
+            println!("  duplicates:       {}", duplicates);
+            println!("  unmapped:         {}", unmapped);
+            println!("  failed:           {}", failed);
+        }
+        Err(err) => {
+            error!("Failed to import OSM nodes: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
 #[allow(deprecated)]
 pub fn run() {
     dotenv().ok(); // TODO: either use environment variables XOR cli arguments
@@ -47,7 +207,7 @@ pub fn run() {
             Arg::with_name("db-url")
                 .long("db-url")
                 .value_name("DATABASE_URL")
-                .help("URL to the database"),
+                .help("URL to the database or `memory://` for an ephemeral database"),
         )
         .arg(
             Arg::with_name("idx-dir")
@@ -56,15 +216,74 @@ pub fn run() {
                 .help("File system directory for the full-text search index"),
         )
         .arg(
-            Arg::with_name("enable-cors")
-                .long("enable-cors")
-                .help("Allow requests from any origin"),
+            Arg::with_name("enable-cors").long("enable-cors").help(
+                "Allow cross-origin requests from any origin unless CORS_ALLOWED_ORIGINS is set",
+            ),
         )
         .arg(
             Arg::with_name("fix-event-address-location")
                 .long("fix-event-address-location")
                 .help("Update the location of ALL events by resolving their address"),
         )
+        .subcommand(SubCommand::with_name("check").about("Check the database for inconsistencies"))
+        .subcommand(
+            SubCommand::with_name("tag-suggestions")
+                .about("Compute the statistics for suggesting tags from confirmed places"),
+        )
+        .subcommand(
+            SubCommand::with_name("recompute-ratings")
+                .about("Recompute the aggregated ratings of all places"),
+        )
+        .subcommand(
+            SubCommand::with_name("import-osm")
+                .about("Import places from OpenStreetMap")
+                .arg(
+                    Arg::with_name("query")
+                        .long("query")
+                        .value_name("FILE")
+                        .required(true)
+                        .help("File with the Overpass QL query"),
+                )
+                .arg(
+                    Arg::with_name("mapping")
+                        .long("mapping")
+                        .value_name("FILE")
+                        .required(true)
+                        .help("JSON file that maps OSM tags to categories and tags"),
+                )
+                .arg(
+                    Arg::with_name("org-token")
+                        .long("org-token")
+                        .value_name("TOKEN")
+                        .help("API token of the organization that imports the places"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("user")
+                .about("Manage user accounts")
+                .subcommand(
+                    SubCommand::with_name("anonymize")
+                        .about("Delete a user and detach all authored records")
+                        .arg(
+                            Arg::with_name("email")
+                                .long("email")
+                                .value_name("EMAIL")
+                                .required(true)
+                                .help("E-mail address of the user"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("reactivate")
+                        .about("Restore a deleted user during the grace period")
+                        .arg(
+                            Arg::with_name("email")
+                                .long("email")
+                                .value_name("EMAIL")
+                                .required(true)
+                                .help("E-mail address of the user"),
+                        ),
+                ),
+        )
         .get_matches();
 
     let mut cfg = Cfg::from_env_or_default();
@@ -72,36 +291,76 @@ pub fn run() {
     if let Some(db_url) = matches.value_of("db-url").map(ToString::to_string) {
         cfg.db_url = db_url
     }
-    info!(
-        "Connecting to SQLite database '{}' (pool size = {})",
-        cfg.db_url, cfg.db_connection_pool_size
-    );
-    let connections = sqlite::Connections::init(&cfg.db_url, cfg.db_connection_pool_size).unwrap();
+    let in_memory = cfg.db_url == sqlite::IN_MEMORY_DB_URL;
+    let connections = if in_memory {
+        warn!("Using an in-memory database: All data will be lost on exit!");
+        sqlite::Connections::init_in_memory().unwrap()
+    } else {
+        info!(
+            "Connecting to SQLite database '{}' (pool size = {})",
+            cfg.db_url, cfg.db_connection_pool_size
+        );
+        sqlite::Connections::init(&cfg.db_url, cfg.db_connection_pool_size).unwrap()
+    };
 
     info!("Running embedded database migrations");
     embedded_migrations::run(&*connections.exclusive().unwrap()).unwrap();
 
-    let idx_dir = matches
-        .value_of("idx-dir")
-        .map(ToString::to_string)
-        .or_else(|| env::var("INDEX_DIR").map(Option::Some).unwrap_or(None));
-    let idx_path = idx_dir.as_ref().map(|dir| Path::new(dir));
-    info!("Initializing Tantivy full-text search engine");
-    let search_engine = tantivy::SearchEngine::init_with_path(idx_path).unwrap();
-
-    #[allow(clippy::match_single_binding)]
     match matches.subcommand() {
+        ("check", Some(_)) => {
+            check_db(&connections);
+        }
+        ("tag-suggestions", Some(_)) => {
+            compute_tag_suggestions(&connections);
+        }
+        ("recompute-ratings", Some(_)) => {
+            recompute_ratings(&connections);
+        }
+        ("user", Some(user_matches)) => match user_matches.subcommand() {
+            ("anonymize", Some(anonymize_matches)) => {
+                anonymize_user(&connections, anonymize_matches);
+            }
+            ("reactivate", Some(reactivate_matches)) => {
+                reactivate_user(&connections, reactivate_matches);
+            }
+            _ => {
+                println!("{}", user_matches.usage());
+            }
+        },
+        ("import-osm", Some(import_matches)) => {
+            info!("Initializing Tantivy full-text search engine");
+            let search_engine =
+                tantivy::SearchEngine::init_in_ram_with_language(cfg.index_language).unwrap();
+            import_osm(&connections, search_engine, &cfg, import_matches);
+        }
         _ => {
+            let idx_dir = matches
+                .value_of("idx-dir")
+                .map(ToString::to_string)
+                .or_else(|| env::var("INDEX_DIR").map(Option::Some).unwrap_or(None));
+            info!("Initializing Tantivy full-text search engine");
+            let search_engine = if in_memory {
+                // A persistent index would outlive the indexed data
+                tantivy::SearchEngine::init_in_ram_with_language(cfg.index_language).unwrap()
+            } else if let Some(idx_dir) = idx_dir {
+                let snapshot_dir = cfg.index_snapshot.as_ref().map(|s| s.dir.as_path());
+                tantivy::SearchEngine::open_or_restore(
+                    Path::new(&idx_dir),
+                    snapshot_dir,
+                    cfg.index_language,
+                )
+                .unwrap()
+            } else {
+                tantivy::SearchEngine::init_in_ram_with_language(cfg.index_language).unwrap()
+            };
             if matches.is_present("fix-event-address-location") {
                 info!("Updating all event locations...");
                 update_event_locations(&mut *connections.exclusive().unwrap()).unwrap();
             }
-            web::run(
-                connections,
-                search_engine,
-                matches.is_present("enable-cors"),
-                cfg,
-            );
+            if matches.is_present("enable-cors") && cfg.cors.is_none() {
+                cfg.cors = Some(Default::default());
+            }
+            web::run(connections, search_engine, cfg);
         }
     }
 }
@@ -0,0 +1,26 @@
+use super::*;
+
+/// Lists the changes of places, events, ratings and comments
+/// for replicating them incrementally.
+#[get("/changes?<since>&<until>&<limit>")]
+pub fn get_changes(
+    db: sqlite::Connections,
+    cfg: State<Cfg>,
+    since: i64,         // in seconds
+    until: Option<i64>, // in seconds
+    limit: Option<usize>,
+) -> LimitedResult<Json<Vec<json::ChangeRecord>>> {
+    let since = Timestamp::from_seconds(since);
+    let until = until
+        .map(Timestamp::from_seconds)
+        .unwrap_or_else(Timestamp::now);
+    if until < since {
+        return Err(Error::Parameter(ParameterError::EndDateBeforeStart).into());
+    }
+    let limit = cfg.result_limits.changes.effective(limit)?;
+    let changes = db.shared()?.load_changes(since, until, limit as u64)?;
+    Ok(Limited {
+        body: Json(changes.into_iter().map(Into::into).collect()),
+        limit,
+    })
+}
@@ -1,6 +1,13 @@
-use super::{super::guards::*, Result};
+use super::{
+    super::guards::*,
+    limits::{self, LimitedJson, Reservation, Upload, UploadQuotas},
+    AppError, Result,
+};
 use crate::{
-    adapters::json,
+    adapters::{
+        export::{ExportedPlace, PlacesExport},
+        json,
+    },
     core::{prelude::*, usecases, util},
     infrastructure::{
         cfg::Cfg,
@@ -11,37 +18,69 @@ use crate::{
 };
 use rocket::{self, request::Form, State};
 use rocket_contrib::json::Json;
-use std::time::Duration;
+use std::{collections::HashMap, result, time::Duration};
 
 #[derive(FromForm, Clone)]
 pub struct GetEntryQuery {
     org_tag: Option<String>,
+    status: Option<String>,
 }
 
 #[get("/entries/<ids>?<query..>")]
 pub fn get_entry(
     db: sqlite::Connections,
+    auth: Auth,
     ids: String,
     query: Form<GetEntryQuery>,
-) -> Result<Vec<json::Entry>> {
-    // TODO: Only lookup and return a single entity
-    // TODO: Add a new method for searching multiple ids
+) -> result::Result<PlacesExport, AppError> {
     let ids = util::split_ids(&ids);
     if ids.is_empty() {
-        return Ok(Json(vec![]));
+        return Ok(PlacesExport(vec![]));
     }
-    let GetEntryQuery { ref org_tag } = query.into_inner();
+    let GetEntryQuery {
+        ref org_tag,
+        ref status,
+    } = query.into_inner();
     let results = {
         let db = db.shared()?;
-        let places = usecases::load_places(&*db, &ids, org_tag.as_ref().map(String::as_str))?;
+        // The review status is only revealed to scouts and admins
+        let is_scout = auth.user_with_min_role(&*db, Role::Scout).is_ok();
+        let status_filter = status
+            .as_deref()
+            .map(super::search::parse_review_status_list)
+            .unwrap_or_default();
+        if !status_filter.is_empty() && !is_scout {
+            return Err(Error::Parameter(ParameterError::Unauthorized).into());
+        }
+        let mut places = usecases::load_places(&*db, &ids, org_tag.as_ref().map(String::as_str))?;
+        usecases::hide_broken_place_images(&*db, places.iter_mut().map(|(place, _)| place))?;
+        if !status_filter.is_empty() {
+            places.retain(|(_, status)| status_filter.contains(status));
+        }
+        let place_ids: Vec<_> = places.iter().map(|(place, _)| place.id.as_str()).collect();
+        let mut rating_counts: HashMap<_, _> = db
+            .count_ratings_and_comments_of_places(&place_ids)?
+            .into_iter()
+            .collect();
         let mut results = Vec::with_capacity(places.len());
-        for (place, _) in places.into_iter() {
-            let r = db.load_ratings_of_place(place.id.as_ref())?;
-            results.push(json::entry_from_place_with_ratings(place, r));
+        for (mut place, status) in places.into_iter() {
+            // The e-mail address of the creator is exported as CSV
+            // and must not be revealed to anonymous users
+            if !is_scout {
+                place.created.by = None;
+            }
+            let ratings = db.load_ratings_of_place(place.id.as_ref())?;
+            let rating_counts = rating_counts.remove(place.id.as_str()).unwrap_or_default();
+            results.push(ExportedPlace {
+                place,
+                ratings,
+                rating_counts,
+                status: if is_scout { Some(status) } else { None },
+            });
         }
         results
     };
-    Ok(Json(results))
+    Ok(PlacesExport(results))
 }
 
 // Limit the total number of recently changed entries to avoid cloning
@@ -163,61 +202,230 @@ pub fn get_entries_most_popular_tags(
     Ok(Json(results))
 }
 
-#[post("/entries", format = "application/json", data = "<body>")]
+// Uploads of organizations are counted
+fn reserve_place_upload<'a>(
+    connections: &sqlite::Connections,
+    quotas: &'a UploadQuotas,
+    org: Option<&Organization>,
+    cfg: &Cfg,
+) -> result::Result<Option<Reservation<'a>>, AppError> {
+    let org = match org {
+        Some(org) => org,
+        None => return Ok(None),
+    };
+    let daily_quotas = limits::daily_quotas(&*connections.shared()?, &org.id, cfg)?;
+    let reservation = quotas.reserve(&org.id, Upload::Place, daily_quotas.places)?;
+    Ok(Some(reservation))
+}
+
+#[post(
+    "/entries?<confirm_position>",
+    format = "application/json",
+    data = "<body>"
+)]
 pub fn post_entry(
     auth: Auth,
     connections: sqlite::Connections,
     notify: Notify,
     mut search_engine: tantivy::SearchEngine,
-    body: Json<json::NewPlace>,
+    quotas: State<UploadQuotas>,
+    body: LimitedJson<json::NewPlace>,
+    confirm_position: Option<bool>,
     cfg: State<Cfg>,
 ) -> Result<String> {
     let org = auth.organization(&*connections.shared()?).ok();
     if org.is_none() && auth.account_email().is_err() && cfg.protect_with_captcha {
         auth.has_captcha()?;
     }
-    let new_place = body.into_inner().into();
+    let reservation = reserve_place_upload(&connections, &quotas, org.as_ref(), &cfg)?;
+    let new_place: usecases::NewPlace = body.into_inner().into();
+    usecases::check_submitted_position(
+        new_place.lat,
+        new_place.lng,
+        confirm_position.unwrap_or(false),
+    )?;
+    let place = flows::create_place(
+        &connections,
+        &mut search_engine,
+        &*notify,
+        new_place,
+        auth.account_email().ok(),
+        org.as_ref(),
+        &cfg,
+    )?;
+    if let Some(reservation) = reservation {
+        reservation.commit();
+    }
+    Ok(Json(place.id.to_string()))
+}
+
+#[post(
+    "/entries/validate?<confirm_position>",
+    format = "application/json",
+    data = "<body>"
+)]
+pub fn post_entry_validation(
+    auth: Auth,
+    connections: sqlite::Connections,
+    body: LimitedJson<json::NewPlace>,
+    confirm_position: Option<bool>,
+    cfg: State<Cfg>,
+) -> Result<json::Validation> {
+    let db = connections.shared()?;
+    let org = auth.organization(&*db).ok();
+    let new_place: usecases::NewPlace = body.into_inner().into();
+    let mut errors = vec![];
+    if let Err(err) = usecases::check_submitted_position(
+        new_place.lat,
+        new_place.lng,
+        confirm_position.unwrap_or(false),
+    ) {
+        errors.push(err);
+    }
+    let (tags, result) = usecases::validate_new_place(
+        &*db,
+        new_place,
+        auth.account_email().ok(),
+        org.as_ref(),
+        &cfg.accepted_licenses,
+    );
+    let place = match result {
+        Ok(place) => Some(place),
+        Err(err) => {
+            errors.push(err);
+            None
+        }
+    };
+    let links = place.as_ref().and_then(|p| p.links.as_ref());
+    Ok(Json(super::validation(
+        tags,
+        [
+            links.and_then(|l| l.homepage.as_ref()),
+            links.and_then(|l| l.image.as_ref()),
+            links.and_then(|l| l.image_href.as_ref()),
+        ],
+        errors,
+    )?))
+}
+
+fn revision_conflict(
+    connections: &sqlite::Connections,
+    auth: &Auth,
+    org: Option<&Organization>,
+    id: &str,
+) -> result::Result<AppError, AppError> {
+    let db = connections.shared()?;
+    let (place, _) = db.get_place_by_id(id)?;
+    let mut last_change = place.created;
+    // Only disclose e-mail addresses of editors if permitted
+    if org.is_none() && auth.user_with_min_role(&*db, Role::Scout).is_err() {
+        last_change.by = None;
+    }
+    Ok(AppError::RevisionConflict {
+        current_revision: place.revision,
+        last_change,
+    })
+}
+
+#[post(
+    "/entries/drafts?<confirm_position>",
+    format = "application/json",
+    data = "<body>"
+)]
+pub fn post_entry_draft(
+    account: Account,
+    connections: sqlite::Connections,
+    body: LimitedJson<json::NewPlace>,
+    confirm_position: Option<bool>,
+    cfg: State<Cfg>,
+) -> Result<String> {
+    let new_place: usecases::NewPlace = body.into_inner().into();
+    usecases::check_submitted_position(
+        new_place.lat,
+        new_place.lng,
+        confirm_position.unwrap_or(false),
+    )?;
+    let place = flows::create_draft(&connections, new_place, account.email(), &cfg)?;
+    Ok(Json(place.id.to_string()))
+}
+
+#[get("/entries/drafts")]
+pub fn get_entry_drafts(
+    account: Account,
+    connections: sqlite::Connections,
+) -> Result<Vec<json::Entry>> {
+    let drafts = connections.shared()?.drafts_created_by(account.email())?;
     Ok(Json(
-        flows::create_place(
-            &connections,
-            &mut search_engine,
-            &*notify,
-            new_place,
-            auth.account_email().ok(),
-            org.as_ref(),
-            &cfg,
-        )?
-        .id
-        .to_string(),
+        drafts
+            .into_iter()
+            .map(|place| json::entry_from_place_with_ratings(place, vec![]))
+            .collect(),
     ))
 }
 
-#[put("/entries/<id>", format = "application/json", data = "<data>")]
+#[post("/entries/drafts/<id>/publish")]
+pub fn post_entry_draft_publish(
+    account: Account,
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    notify: Notify,
+    id: String,
+) -> Result<()> {
+    flows::publish_draft(
+        &connections,
+        &mut search_engine,
+        &*notify,
+        &id,
+        account.email(),
+    )?;
+    Ok(Json(()))
+}
+
+#[put(
+    "/entries/<id>?<confirm_position>",
+    format = "application/json",
+    data = "<data>"
+)]
 pub fn put_entry(
     auth: Auth,
     connections: sqlite::Connections,
     mut search_engine: tantivy::SearchEngine,
     notify: Notify,
+    quotas: State<UploadQuotas>,
     id: String,
-    data: Json<json::UpdatePlace>,
+    data: LimitedJson<json::UpdatePlace>,
+    confirm_position: Option<bool>,
     cfg: State<Cfg>,
 ) -> Result<String> {
     let org = auth.organization(&*connections.shared()?).ok();
     if org.is_none() && auth.account_email().is_err() && cfg.protect_with_captcha {
         auth.has_captcha()?;
     }
-    Ok(Json(
-        flows::update_place(
-            &connections,
-            &mut search_engine,
-            &*notify,
-            id.into(),
-            data.into_inner().into(),
-            auth.account_email().ok(),
-            org.as_ref(),
-            &cfg,
-        )?
-        .id
-        .into(),
-    ))
+    let reservation = reserve_place_upload(&connections, &quotas, org.as_ref(), &cfg)?;
+    let update_place: usecases::UpdatePlace = data.into_inner().into();
+    usecases::check_submitted_position(
+        update_place.lat,
+        update_place.lng,
+        confirm_position.unwrap_or(false),
+    )?;
+    let place = match flows::update_place(
+        &connections,
+        &mut search_engine,
+        &*notify,
+        id.as_str().into(),
+        update_place,
+        auth.account_email().ok(),
+        org.as_ref(),
+        &cfg,
+    ) {
+        Ok(place) => place,
+        Err(AppError::Business(Error::Repo(RepoError::InvalidVersion))) => {
+            return Err(revision_conflict(&connections, &auth, org.as_ref(), &id)?);
+        }
+        Err(err) => return Err(err),
+    };
+    if let Some(reservation) = reservation {
+        reservation.commit();
+    }
+    Ok(Json(place.id.into()))
 }
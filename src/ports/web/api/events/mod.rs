@@ -1,91 +1,88 @@
 use super::*;
 use crate::{
     adapters,
-    core::{
-        prelude::Result as CoreResult,
-        util::{geo::MapBbox, validate},
+    core::util::{geo::MapBbox, validate},
+    infrastructure::{
+        auto_fill_address_gateway, cfg::Cfg, flows::prelude as flows,
+        geocoding_queue::GeoCodingQueue,
     },
-    infrastructure::{flows::prelude as flows, GEO_CODING_GW},
 };
-use ofdb_core::gateways::geocode::GeoCodingGateway;
 
 use rocket::{
     http::{RawStr, Status as HttpStatus},
     request::{FromQuery, Query},
+    State,
 };
 
 #[cfg(test)]
 mod tests;
 
-fn check_and_set_address_location(e: &mut usecases::NewEvent) -> Option<MapPoint> {
-    let pos = if let (Some(lat), Some(lng)) = (e.lat, e.lng) {
-        MapPoint::try_from_lat_lng_deg(lat, lng)
-            .map(Some)
-            .unwrap_or_default()
-    } else {
-        None
-    };
-    if pos.unwrap_or_default().is_valid() {
-        // Preserve valid geo locations
-        return pos;
-    }
-    // TODO: Parse logical parts of NewEvent earlier
-    let addr = Address {
-        street: e.street.clone(),
-        zip: e.zip.clone(),
-        city: e.city.clone(),
-        country: e.country.clone(),
-        state: e.state.clone(),
-    };
-
-    GEO_CODING_GW
-        .resolve_address_lat_lng(&addr)
-        .and_then(|(lat, lng)| {
-            if let Ok(pos) = MapPoint::try_from_lat_lng_deg(lat, lng) {
-                log::debug!(
-                    "Updating event location: ({:?}, {:?}) -> {:?}",
-                    e.lat,
-                    e.lng,
-                    pos
-                );
-                e.lat = Some(lat);
-                e.lng = Some(lng);
-            }
-            pos
-        })
-}
-
 #[post("/events", format = "application/json", data = "<e>")]
 pub fn post_event_with_token(
     connections: sqlite::Connections,
     mut search_engine: tantivy::SearchEngine,
     notify: Notify,
     auth: Auth,
-    e: Json<usecases::NewEvent>,
+    quotas: State<limits::UploadQuotas>,
+    e: limits::LimitedJson<json::NewEvent>,
+    geocoding_queue: State<GeoCodingQueue>,
+    cfg: State<Cfg>,
 ) -> Result<String> {
-    let org = auth.organization(&*connections.shared()?)?;
-    let mut e = e.into_inner();
-    check_and_set_address_location(&mut e);
+    let (org, daily_quotas) = {
+        let db = connections.shared()?;
+        let org = auth.organization(&*db)?;
+        let daily_quotas = limits::daily_quotas(&*db, &org.id, &cfg)?;
+        (org, daily_quotas)
+    };
+    let reservation = quotas.reserve(&org.id, limits::Upload::Event, daily_quotas.events)?;
     let event = flows::create_event(
         &connections,
         &mut search_engine,
         &*notify,
         Some(&org.api_token),
-        e,
+        e.into_inner().into(),
+        auto_fill_address_gateway(&cfg),
     )?;
+    reservation.commit();
+    geocoding_queue.enqueue_event(event.id.clone());
     Ok(Json(event.id.to_string()))
 }
 
+#[post("/events/validate", format = "application/json", data = "<e>")]
+pub fn post_event_validation(
+    connections: sqlite::Connections,
+    auth: Auth,
+    e: limits::LimitedJson<json::NewEvent>,
+) -> Result<json::Validation> {
+    let org = auth.organization(&*connections.shared()?)?;
+    let (tags, result) =
+        flows::validate_new_event(&connections, Some(&org.api_token), e.into_inner().into())?;
+    let (event, errors) = match result {
+        Ok(event) => (Some(event), vec![]),
+        Err(err) => (None, vec![err]),
+    };
+    let event = event.as_ref();
+    Ok(Json(super::validation(
+        tags,
+        [
+            event.and_then(|e| e.homepage.as_ref()),
+            event.and_then(|e| e.image_url.as_ref()),
+            event.and_then(|e| e.image_link_url.as_ref()),
+        ],
+        errors,
+    )?))
+}
+
 #[post("/events", format = "application/json", data = "<_e>", rank = 2)]
 // NOTE:
 // At the moment we don't want to allow anonymous event creation.
 // So for now we assure that it's blocked:
-pub fn post_event(mut _db: sqlite::Connections, _e: Json<usecases::NewEvent>) -> HttpStatus {
+pub fn post_event(mut _db: sqlite::Connections, _e: Json<json::NewEvent>) -> HttpStatus {
     HttpStatus::Unauthorized
 }
 // But in the future we might allow anonymous event creation:
 //
-// pub fn post_event(mut db: sqlite::Connections, e: Json<usecases::NewEvent>) -> Result<String> {
+// pub fn post_event(mut db: sqlite::Connections, e: Json<json::NewEvent>) -> Result<String> {
 //     let mut e = e.into_inner();
 //     e.created_by = None; // ignore because of missing authorization
 //     e.token = None; // ignore token
@@ -95,18 +92,28 @@ pub fn post_event(mut _db: sqlite::Connections, _e: Json<usecases::NewEvent>) ->
 
 #[get("/events/<id>")]
 pub fn get_event(db: sqlite::Connections, id: String) -> Result<json::Event> {
-    let mut ev = usecases::get_event(&*db.shared()?, &id)?;
+    let db = db.shared()?;
+    let mut ev = usecases::get_event(&*db, &id)?;
     ev.created_by = None; // don't show creators email to unregistered users
+    usecases::hide_broken_event_images(&*db, Some(&mut ev))?;
     Ok(Json(ev.into()))
 }
 
+#[post("/events/<id>/views")]
+pub fn post_event_view(db: sqlite::Connections, id: String, cfg: State<Cfg>) -> StatusResult {
+    if cfg.count_views {
+        usecases::count_event_view(&*db.exclusive()?, &id)?;
+    }
+    Ok(HttpStatus::NoContent)
+}
+
 #[put("/events/<_id>", format = "application/json", data = "<_e>", rank = 2)]
 // At the moment we don't want to allow anonymous event creation.
 // So for now we assure that it's blocked:
 pub fn put_event(
     mut _db: sqlite::Connections,
     _id: &RawStr,
-    _e: Json<usecases::NewEvent>,
+    _e: Json<json::NewEvent>,
 ) -> HttpStatus {
     HttpStatus::Unauthorized
 }
@@ -117,20 +124,30 @@ pub fn put_event_with_token(
     mut search_engine: tantivy::SearchEngine,
     notify: Notify,
     auth: Auth,
+    quotas: State<limits::UploadQuotas>,
     id: &RawStr,
-    e: Json<usecases::NewEvent>,
+    e: limits::LimitedJson<json::NewEvent>,
+    geocoding_queue: State<GeoCodingQueue>,
+    cfg: State<Cfg>,
 ) -> Result<()> {
-    let org = auth.organization(&*connections.shared()?)?;
-    let mut e = e.into_inner();
-    check_and_set_address_location(&mut e);
-    flows::update_event(
+    let (org, daily_quotas) = {
+        let db = connections.shared()?;
+        let org = auth.organization(&*db)?;
+        let daily_quotas = limits::daily_quotas(&*db, &org.id, &cfg)?;
+        (org, daily_quotas)
+    };
+    let reservation = quotas.reserve(&org.id, limits::Upload::Event, daily_quotas.events)?;
+    let event = flows::update_event(
         &connections,
         &mut search_engine,
         &*notify,
         Some(&org.api_token),
         id.to_string().into(),
-        e,
+        e.into_inner().into(),
+        auto_fill_address_gateway(&cfg),
     )?;
+    reservation.commit();
+    geocoding_queue.enqueue_event(event.id);
     Ok(Json(()))
 }
 
@@ -162,13 +179,24 @@ impl<'q> FromQuery<'q> for usecases::EventQuery {
             None
         };
 
+        let offset = if let Some(offset) = query
+            .clone()
+            .filter(|i| i.key == "offset")
+            .map(|i| i.value.url_decode_lossy())
+            .find(|v| !v.is_empty())
+        {
+            Some(offset.parse()?)
+        } else {
+            None
+        };
+
         let limit = if let Some(limit) = query
             .clone()
             .filter(|i| i.key == "limit")
             .map(|i| i.value.url_decode_lossy())
             .find(|v| !v.is_empty())
         {
-            Some(validate_and_adjust_query_limit(limit.parse()?)?)
+            Some(limit.parse()?)
         } else {
             None
         };
@@ -195,6 +223,17 @@ impl<'q> FromQuery<'q> for usecases::EventQuery {
             None
         };
 
+        let since = if let Some(since) = query
+            .clone()
+            .filter(|i| i.key == "since")
+            .map(|i| i.value.url_decode_lossy())
+            .find(|v| !v.is_empty())
+        {
+            Some(Timestamp::from_inner(since.parse()?))
+        } else {
+            None
+        };
+
         let tags: Vec<_> = query
             .clone()
             .filter(|i| i.key == "tag")
@@ -209,6 +248,20 @@ impl<'q> FromQuery<'q> for usecases::EventQuery {
             .map(|i| i.value.url_decode_lossy())
             .find(|v| !v.is_empty());
 
+        let organizer = query
+            .clone()
+            .filter(|i| i.key == "organizer")
+            .map(|i| i.value.url_decode_lossy())
+            .find(|v| !v.trim().is_empty());
+
+        let registration = query
+            .clone()
+            .filter(|i| i.key == "registration")
+            .map(|i| i.value.url_decode_lossy())
+            .find(|v| !v.is_empty())
+            .map(|r| r.parse::<RegistrationType>())
+            .transpose()?;
+
         drop(query); // silence clippy warning
         Ok(usecases::EventQuery {
             bbox,
@@ -217,46 +270,39 @@ impl<'q> FromQuery<'q> for usecases::EventQuery {
             start_max,
             tags,
             text,
+            organizer,
+            registration,
+            since,
+            offset,
             limit,
         })
     }
 }
 
-const MAX_RESULT_LIMIT: usize = 2000;
-
-#[allow(clippy::absurd_extreme_comparisons)]
-fn validate_and_adjust_query_limit(limit: usize) -> CoreResult<usize> {
-    if limit > MAX_RESULT_LIMIT {
-        info!(
-            "Requested limit {} exceeds maximum limit {} for event search results",
-            limit, MAX_RESULT_LIMIT
-        );
-        Ok(MAX_RESULT_LIMIT)
-    } else if limit <= 0 {
-        warn!("Invalid search limit: {}", limit);
-        Err(Error::Parameter(ParameterError::InvalidLimit))
-    } else {
-        Ok(limit)
-    }
-}
-
 #[get("/events?<query..>")]
 pub fn get_events_with_token(
     connections: sqlite::Connections,
     search_engine: tantivy::SearchEngine,
     auth: Auth,
+    cfg: State<Cfg>,
     query: usecases::EventQuery,
-) -> Result<Vec<json::Event>> {
+) -> LimitedResult<Json<Vec<json::Event>>> {
     let db = connections.shared()?;
     let org = match auth.organization(&*db) {
         Ok(org) => org,
         Err(AppError::Business(Error::Parameter(ParameterError::Unauthorized))) => {
             drop(db);
-            return get_events_chronologically(connections, search_engine, query);
+            return get_events_chronologically(connections, search_engine, cfg, query);
         }
         Err(e) => return Err(e),
     };
-    let events = usecases::query_events(&*db, &search_engine, query)?;
+    let limit = cfg.result_limits.events.effective(query.limit)?;
+    let query = usecases::EventQuery {
+        limit: Some(limit),
+        ..query
+    };
+    let mut events = usecases::query_events(&*db, &search_engine, query)?;
+    usecases::hide_broken_event_images(&*db, &mut events)?;
     // Release the database connection asap
     drop(db);
 
@@ -274,21 +320,31 @@ pub fn get_events_with_token(
         .map(json::Event::from)
         .collect();
 
-    Ok(Json(events))
+    Ok(Limited {
+        body: Json(events),
+        limit,
+    })
 }
 
 #[get("/events?<query..>", rank = 2)]
 pub fn get_events_chronologically(
     connections: sqlite::Connections,
     search_engine: tantivy::SearchEngine,
+    cfg: State<Cfg>,
     query: usecases::EventQuery,
-) -> Result<Vec<json::Event>> {
+) -> LimitedResult<Json<Vec<json::Event>>> {
     if query.created_by.is_some() {
         return Err(Error::Parameter(ParameterError::Unauthorized).into());
     }
+    let limit = cfg.result_limits.events.effective(query.limit)?;
+    let query = usecases::EventQuery {
+        limit: Some(limit),
+        ..query
+    };
 
     let db = connections.shared()?;
-    let events = usecases::query_events(&*db, &search_engine, query)?;
+    let mut events = usecases::query_events(&*db, &search_engine, query)?;
+    usecases::hide_broken_event_images(&*db, &mut events)?;
     // Release the database connection asap
     drop(db);
 
@@ -299,7 +355,10 @@ pub fn get_events_chronologically(
         .map(json::Event::from)
         .collect();
 
-    Ok(Json(events))
+    Ok(Limited {
+        body: Json(events),
+        limit,
+    })
 }
 
 #[get("/export/events.csv?<query..>")]
@@ -307,8 +366,9 @@ pub fn csv_export(
     connections: sqlite::Connections,
     search_engine: tantivy::SearchEngine,
     auth: Auth,
+    cfg: State<Cfg>,
     query: usecases::EventQuery,
-) -> result::Result<Content<String>, AppError> {
+) -> LimitedResult<Content<String>> {
     let db = connections.shared()?;
 
     let moderated_tags = if let Ok(org) = auth.organization(&*db) {
@@ -319,18 +379,13 @@ pub fn csv_export(
 
     let user = auth.user_with_min_role(&*db, Role::Scout)?;
 
-    let limit = if let Some(limit) = query.limit {
-        // Limited
-        limit
-    } else {
-        // Unlimited
-        db.count_events()? + 100
-    };
+    let limit = cfg.result_limits.export.effective(query.limit)?;
     let query = usecases::EventQuery {
         limit: Some(limit),
         ..query
     };
-    let events = usecases::query_events(&*db, &search_engine, query)?;
+    let mut events = usecases::query_events(&*db, &search_engine, query)?;
+    usecases::hide_broken_event_images(&*db, &mut events)?;
     // Release the database connection asap
     drop(db);
 
@@ -355,7 +410,10 @@ pub fn csv_export(
     wtr.flush()?;
     let data = String::from_utf8(wtr.into_inner()?)?;
 
-    Ok(Content(ContentType::CSV, data))
+    Ok(Limited {
+        body: Content(ContentType::CSV, data),
+        limit,
+    })
 }
 
 #[post("/events/<ids>/archive")]
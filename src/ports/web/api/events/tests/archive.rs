@@ -37,7 +37,10 @@ fn only_scouts_and_admins_can_archive_events() {
         .body(r#"{"email": "user@example.com", "password": "secret"}"#)
         .dispatch();
     assert_eq!(login.status(), Status::Ok);
-    let response = client.post("/events/foo/archive").dispatch();
+    let response = client
+        .post("/events/foo/archive")
+        .header(csrf_token_header(&login))
+        .dispatch();
     assert_eq!(response.status(), Status::Unauthorized);
 
     let login = client
@@ -46,7 +49,10 @@ fn only_scouts_and_admins_can_archive_events() {
         .body(r#"{"email": "scout@example.com", "password": "secret"}"#)
         .dispatch();
     assert_eq!(login.status(), Status::Ok);
-    let response = client.post("/events/foo/archive").dispatch();
+    let response = client
+        .post("/events/foo/archive")
+        .header(csrf_token_header(&login))
+        .dispatch();
     assert_eq!(response.status(), Status::NoContent);
 
     let login = client
@@ -55,7 +61,10 @@ fn only_scouts_and_admins_can_archive_events() {
         .body(r#"{"email": "admin@example.com", "password": "secret"}"#)
         .dispatch();
     assert_eq!(login.status(), Status::Ok);
-    let response = client.post("/events/foo/archive").dispatch();
+    let response = client
+        .post("/events/foo/archive")
+        .header(csrf_token_header(&login))
+        .dispatch();
     assert_eq!(response.status(), Status::NoContent);
 }
 
@@ -79,6 +88,8 @@ fn archive_events() {
             name: "bar".into(),
             moderated_tags: vec!["tag".into()],
             api_token: "foo".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
     let e1 = usecases::NewEvent {
@@ -88,7 +99,7 @@ fn archive_events() {
         created_by: Some("foo@bar.com".into()),
         ..Default::default()
     };
-    let id1 = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e1)
+    let id1 = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e1, None)
         .unwrap()
         .id;
     let e2 = usecases::NewEvent {
@@ -98,7 +109,7 @@ fn archive_events() {
         created_by: Some("foo@bar.com".into()),
         ..Default::default()
     };
-    let id2 = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e2)
+    let id2 = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e2, None)
         .unwrap()
         .id;
 
@@ -114,8 +125,12 @@ fn archive_events() {
         .body(r#"{"email": "admin@example.com", "password": "secret"}"#)
         .dispatch();
     assert_eq!(login.status(), Status::Ok);
+    let csrf_token = csrf_token_header(&login);
 
-    let response = client.post(format!("/events/{}/archive", id2)).dispatch();
+    let response = client
+        .post(format!("/events/{}/archive", id2))
+        .header(csrf_token.clone())
+        .dispatch();
     assert_eq!(response.status(), Status::NoContent);
 
     let mut response = client.get("/events").dispatch();
@@ -126,6 +141,7 @@ fn archive_events() {
 
     let response = client
         .post(format!("/events/{},{}/archive", id1, id2))
+        .header(csrf_token)
         .dispatch();
     assert_eq!(response.status(), Status::NoContent);
 
@@ -74,6 +74,8 @@ mod with_api_token {
                 name: "bar".into(),
                 moderated_tags: vec![],
                 api_token: "foo".into(),
+                notification_email: None,
+                email_branding: Default::default(),
             })
             .unwrap();
         let mut res = client
@@ -101,6 +103,8 @@ mod with_api_token {
                 name: "bar".into(),
                 moderated_tags: vec!["org-tag".into()],
                 api_token: "foo".into(),
+                notification_email: None,
+                email_branding: Default::default(),
             })
             .unwrap();
         let mut res = client
@@ -128,6 +132,8 @@ mod with_api_token {
                 name: "bar".into(),
                 moderated_tags: vec!["org-tag".into()],
                 api_token: "foo".into(),
+                notification_email: None,
+                email_branding: Default::default(),
             })
             .unwrap();
         let res = client
@@ -154,6 +160,8 @@ mod with_api_token {
                 name: "bar".into(),
                 moderated_tags: vec!["org-tag".into()],
                 api_token: "foo".into(),
+                notification_email: None,
+                email_branding: Default::default(),
             })
             .unwrap();
         let res = client
@@ -180,6 +188,8 @@ mod with_api_token {
                 name: "bar".into(),
                 moderated_tags: vec!["org-tag".into()],
                 api_token: "foo".into(),
+                notification_email: None,
+                email_branding: Default::default(),
             })
             .unwrap();
         let res = client
@@ -194,6 +204,31 @@ mod with_api_token {
         assert_eq!(ev.registration.unwrap(), RegistrationType::Phone);
     }
 
+    #[test]
+    fn with_recurrence() {
+        let (client, db) = setup();
+        db.exclusive()
+            .unwrap()
+            .create_org(Organization {
+                id: "foo".into(),
+                name: "bar".into(),
+                moderated_tags: vec!["org-tag".into()],
+                api_token: "foo".into(),
+                notification_email: None,
+                email_branding: Default::default(),
+            })
+            .unwrap();
+        let res = client
+                    .post("/events")
+                    .header(ContentType::JSON)
+                    .header(Header::new("Authorization", "Bearer foo"))
+                    .body(r#"{"title":"x","start":4132508400,"created_by":"foo@bar.com","recurrence":"RRULE:FREQ=monthly;COUNT=6"}"#)
+                    .dispatch();
+        assert_eq!(res.status(), HttpStatus::Ok);
+        let ev = db.shared().unwrap().all_events_chronologically().unwrap()[0].clone();
+        assert_eq!("FREQ=MONTHLY;COUNT=6", ev.recurrence.unwrap().to_string());
+    }
+
     #[test]
     fn with_reseved_tag_from_foreign_org() {
         let (client, db) = setup();
@@ -204,6 +239,8 @@ mod with_api_token {
                 name: "a".into(),
                 moderated_tags: vec!["a".into()],
                 api_token: "a".into(),
+                notification_email: None,
+                email_branding: Default::default(),
             })
             .unwrap();
         db.exclusive()
@@ -213,6 +250,8 @@ mod with_api_token {
                 name: "b".into(),
                 moderated_tags: vec!["b".into()],
                 api_token: "b".into(),
+                notification_email: None,
+                email_branding: Default::default(),
             })
             .unwrap();
         let res = client
@@ -248,6 +287,8 @@ mod with_api_token {
                 name: "bar".into(),
                 moderated_tags: vec!["org-tag".into()],
                 api_token: "foo".into(),
+                notification_email: None,
+                email_branding: Default::default(),
             })
             .unwrap();
         let res = client
@@ -282,6 +323,8 @@ mod with_api_token {
                 name: "bar".into(),
                 moderated_tags: vec!["org-tag".into()],
                 api_token: "foo".into(),
+                notification_email: None,
+                email_branding: Default::default(),
             })
             .unwrap();
         let res = client
@@ -293,6 +336,29 @@ mod with_api_token {
         assert_eq!(res.status(), HttpStatus::BadRequest);
     }
 
+    #[test]
+    fn with_invalid_recurrence() {
+        let (client, db) = setup();
+        db.exclusive()
+            .unwrap()
+            .create_org(Organization {
+                id: "foo".into(),
+                name: "bar".into(),
+                moderated_tags: vec!["org-tag".into()],
+                api_token: "foo".into(),
+                notification_email: None,
+                email_branding: Default::default(),
+            })
+            .unwrap();
+        let res = client
+                    .post("/events")
+                    .header(ContentType::JSON)
+                    .header(Header::new("Authorization", "Bearer foo"))
+                    .body(r#"{"title":"x","start":4132508400,"created_by":"foo@bar.com","recurrence":"FREQ=DAILY"}"#)
+                    .dispatch();
+        assert_eq!(res.status(), HttpStatus::BadRequest);
+    }
+
     #[test]
     fn without_creator_email() {
         let (client, db) = setup();
@@ -303,6 +369,8 @@ mod with_api_token {
                 name: "bar".into(),
                 moderated_tags: vec!["org-tag".into()],
                 api_token: "foo".into(),
+                notification_email: None,
+                email_branding: Default::default(),
             })
             .unwrap();
         let res = client
@@ -324,6 +392,8 @@ mod with_api_token {
                 name: "bar".into(),
                 moderated_tags: vec!["org-tag".into()],
                 api_token: "foo".into(),
+                notification_email: None,
+                email_branding: Default::default(),
             })
             .unwrap();
         let res = client
@@ -345,6 +415,8 @@ mod with_api_token {
                 name: "bar".into(),
                 moderated_tags: vec!["org-tag".into()],
                 api_token: "foo".into(),
+                notification_email: None,
+                email_branding: Default::default(),
             })
             .unwrap();
         let res = client
@@ -20,6 +20,8 @@ fn with_invalid_api_token() {
             name: "bar".into(),
             moderated_tags: vec!["org-tag".into()],
             api_token: "foo".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
     let res = client
@@ -40,6 +42,8 @@ fn with_api_token() {
             name: "bar".into(),
             moderated_tags: vec!["tag".into()],
             api_token: "foo".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
     let e1 = usecases::NewEvent {
@@ -49,7 +53,7 @@ fn with_api_token() {
         created_by: Some("foo@bar.com".into()),
         ..Default::default()
     };
-    let id1 = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e1)
+    let id1 = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e1, None)
         .unwrap()
         .id;
     let e2 = usecases::NewEvent {
@@ -59,7 +63,7 @@ fn with_api_token() {
         created_by: Some("foo@bar.com".into()),
         ..Default::default()
     };
-    let id2 = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e2)
+    let id2 = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e2, None)
         .unwrap()
         .id;
     // Manually delete the implicitly added org tag from the 2nd event!
@@ -96,6 +100,8 @@ fn with_api_token_by_organization_without_any_moderated_tags() {
             name: "bar".into(),
             moderated_tags: vec![],
             api_token: "foo".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
     let e = usecases::NewEvent {
@@ -105,7 +111,7 @@ fn with_api_token_by_organization_without_any_moderated_tags() {
         created_by: Some("foo@bar.com".into()),
         ..Default::default()
     };
-    let id = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e)
+    let id = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e, None)
         .unwrap()
         .id;
     assert_eq!(db.shared().unwrap().count_events().unwrap(), 1);
@@ -129,6 +135,8 @@ fn with_api_token_from_different_org_unauthorized() {
             name: "creator".into(),
             moderated_tags: vec!["creator".into()],
             api_token: "creator".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
     let _deleter_org = db
@@ -139,6 +147,8 @@ fn with_api_token_from_different_org_unauthorized() {
             name: "deleter".into(),
             moderated_tags: vec!["deleter".into()],
             api_token: "deleter".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
     let e = usecases::NewEvent {
@@ -148,7 +158,7 @@ fn with_api_token_from_different_org_unauthorized() {
         start: Utc::now().naive_utc().timestamp(),
         ..Default::default()
     };
-    let id = flows::create_event(&db, &mut search_engine, &notify, Some("creator"), e)
+    let id = flows::create_event(&db, &mut search_engine, &notify, Some("creator"), e, None)
         .unwrap()
         .id;
     assert!(db.shared().unwrap().get_event(id.as_ref()).is_ok());
@@ -160,3 +170,52 @@ fn with_api_token_from_different_org_unauthorized() {
         .dispatch();
     assert_eq!(res.status(), HttpStatus::Forbidden);
 }
+
+#[test]
+fn with_api_token_if_removal_of_moderated_tag_is_not_allowed() {
+    let (client, db, mut search_engine, notify) = setup2();
+    db.exclusive()
+        .unwrap()
+        .create_org(Organization {
+            id: "moderator".into(),
+            name: "moderator".into(),
+            moderated_tags: vec![ModeratedTag {
+                label: "moderated".into(),
+                allow_add: true,
+                allow_remove: false,
+                require_clearance: false,
+            }],
+            api_token: "moderator".into(),
+            notification_email: None,
+            email_branding: Default::default(),
+        })
+        .unwrap();
+    db.exclusive()
+        .unwrap()
+        .create_org(Organization {
+            id: "deleter".into(),
+            name: "deleter".into(),
+            moderated_tags: vec!["deleter".into()],
+            api_token: "deleter".into(),
+            notification_email: None,
+            email_branding: Default::default(),
+        })
+        .unwrap();
+    let e = usecases::NewEvent {
+        title: "x".into(),
+        tags: Some(vec!["moderated".into()]), // org tag will be added implicitly!
+        created_by: Some("deleter@example.com".into()),
+        start: Utc::now().naive_utc().timestamp(),
+        ..Default::default()
+    };
+    let id = flows::create_event(&db, &mut search_engine, &notify, Some("deleter"), e, None)
+        .unwrap()
+        .id;
+    let res = client
+        .delete(format!("/events/{}", id))
+        .header(ContentType::JSON)
+        .header(Header::new("Authorization", "Bearer deleter"))
+        .dispatch();
+    assert_eq!(res.status(), HttpStatus::Forbidden);
+    assert!(db.shared().unwrap().get_event(id.as_ref()).is_ok());
+}
@@ -36,6 +36,8 @@ fn export_csv() {
             name: "foo_name".into(),
             moderated_tags: vec!["tag".into()],
             api_token: "foo".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
     db.exclusive()
@@ -45,6 +47,8 @@ fn export_csv() {
             name: "bar_name".into(),
             moderated_tags: vec!["tag2".into()],
             api_token: "bar".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
     let start1 = Utc::now().naive_utc().timestamp();
@@ -55,10 +59,10 @@ fn export_csv() {
         created_by: Some("createdby1@example.com".into()),
         email: Some("email1@example.com".into()),
         telephone: Some("phone1".into()),
-        state: Some("state".into()),
+        state: Some("State".into()),
         ..Default::default()
     };
-    let id1 = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e1)
+    let id1 = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e1, None)
         .unwrap()
         .id;
     let start2 = Utc::now().naive_utc().timestamp();
@@ -71,7 +75,7 @@ fn export_csv() {
         telephone: Some("phone2".into()),
         ..Default::default()
     };
-    let id2 = flows::create_event(&db, &mut search_engine, &notify, Some("bar"), e2)
+    let id2 = flows::create_event(&db, &mut search_engine, &notify, Some("bar"), e2, None)
         .unwrap()
         .id;
 
@@ -102,7 +106,7 @@ fn export_csv() {
     eprintln!("{}", body_str);
     assert!(body_str.starts_with("id,created_by,organizer,title,description,start,end,lat,lng,street,zip,city,country,state,email,phone,homepage,image_url,image_link_url,tags\n"));
     assert!(body_str.contains(&format!(
-        "{},,,title1,,{},,,,,,,,state,email1@example.com,phone1,,,,\"bla,tag\"\n",
+        "{},,,title1,,{},,,,,,,,State,email1@example.com,phone1,,,,\"bla,tag\"\n",
         id1, start1
     )));
     assert!(body_str.contains(&format!(
@@ -126,7 +130,7 @@ fn export_csv() {
     assert_eq!(response.status(), Status::Ok);
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
     assert!(body_str.starts_with("id,created_by,organizer,title,description,start,end,lat,lng,street,zip,city,country,state,email,phone,homepage,image_url,image_link_url,tags\n"));
-    assert!(body_str.contains(&format!("{},createdby1@example.com,,title1,,{},,,,,,,,state,email1@example.com,phone1,,,,\"bla,tag\"\n", id1, start1)));
+    assert!(body_str.contains(&format!("{},createdby1@example.com,,title1,,{},,,,,,,,State,email1@example.com,phone1,,,,\"bla,tag\"\n", id1, start1)));
     assert!(body_str.contains(&format!(
         "{},,,title2,,{},,,,,,,,,email2@example.com,phone2,,,,\"bli,tag2\"\n",
         id2, start2
@@ -144,7 +148,7 @@ fn export_csv() {
     assert_eq!(response.status(), Status::Ok);
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
     assert!(body_str.starts_with("id,created_by,organizer,title,description,start,end,lat,lng,street,zip,city,country,state,email,phone,homepage,image_url,image_link_url,tags\n"));
-    assert!(body_str.contains(&format!("{},createdby1@example.com,,title1,,{},,,,,,,,state,email1@example.com,phone1,,,,\"bla,tag\"\n", id1, start1)));
+    assert!(body_str.contains(&format!("{},createdby1@example.com,,title1,,{},,,,,,,,State,email1@example.com,phone1,,,,\"bla,tag\"\n", id1, start1)));
     assert!(body_str.contains(&format!(
         "{},createdby2@example.com,,title2,,{},,,,,,,,,email2@example.com,phone2,,,,\"bli,tag2\"\n",
         id2, start2
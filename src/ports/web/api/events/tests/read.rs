@@ -13,7 +13,15 @@ fn by_id() {
         created_by: Some("test@example.com".into()),
         ..Default::default()
     };
-    let e = flows::create_event(&db, &mut search_engine, &notify, None, e).unwrap();
+    let e = flows::create_event(&db, &mut search_engine, &notify, None, e, None).unwrap();
+    let created_at = db
+        .shared()
+        .unwrap()
+        .get_event(e.id.as_ref())
+        .unwrap()
+        .created_at
+        .unwrap()
+        .into_seconds();
     let req = client
         .get(format!("/events/{}", e.id))
         .header(ContentType::JSON);
@@ -23,7 +31,7 @@ fn by_id() {
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
     assert_eq!(
                 body_str,
-                format!("{{\"id\":\"{}\",\"title\":\"x\",\"start\":{},\"email\":\"test@example.com\",\"tags\":[\"bla\"],\"registration\":\"email\"}}", e.id, now)
+                format!("{{\"id\":\"{}\",\"title\":\"x\",\"start\":{},\"email\":\"test@example.com\",\"tags\":[\"bla\"],\"registration\":\"email\",\"created_at\":{},\"updated_at\":{}}}", e.id, now, created_at, created_at)
             );
 }
 
@@ -49,6 +57,12 @@ fn all() {
                 archived: None,
                 image_url: None,
                 image_link_url: None,
+                time_zone: None,
+                recurrence: None,
+                address_auto_filled: false,
+                publish_at: None,
+                created_at: None,
+                updated_at: None,
             })
             .unwrap();
     }
@@ -73,7 +87,7 @@ fn sorted_by_start() {
             created_by: Some("test@example.com".into()),
             ..Default::default()
         };
-        flows::create_event(&db, &mut search_engine, &notify, None, e).unwrap();
+        flows::create_event(&db, &mut search_engine, &notify, None, e, None).unwrap();
     }
     let mut res = client.get("/events").header(ContentType::JSON).dispatch();
     assert_eq!(res.status(), HttpStatus::Ok);
@@ -87,6 +101,52 @@ fn sorted_by_start() {
     assert!(objects[4].contains(&format!("\"start\":{}", now + 300)));
 }
 
+#[test]
+fn paginated() {
+    let (client, db, mut search_engine, notify) = setup2();
+    let now = Utc::now().naive_utc().timestamp();
+    for start_offset in &[100, 0, 300, 50, 200] {
+        let e = usecases::NewEvent {
+            title: start_offset.to_string(),
+            start: now + start_offset,
+            tags: Some(vec!["page".into()]),
+            created_by: Some("test@example.com".into()),
+            ..Default::default()
+        };
+        flows::create_event(&db, &mut search_engine, &notify, None, e, None).unwrap();
+    }
+    let mut res = client
+        .get("/events?offset=1&limit=2")
+        .header(ContentType::JSON)
+        .dispatch();
+    assert_eq!(res.status(), HttpStatus::Ok);
+    let events: Vec<json::Event> = serde_json::from_str(&res.body_string().unwrap()).unwrap();
+    assert_eq!(
+        vec![now + 50, now + 100],
+        events.iter().map(|e| e.start).collect::<Vec<_>>()
+    );
+
+    // Search results are paged in the order of the index
+    let mut titles = vec![];
+    for offset in 0..3 {
+        let mut res = client
+            .get(format!("/events?tag=page&offset={}&limit=2", offset * 2))
+            .header(ContentType::JSON)
+            .dispatch();
+        assert_eq!(res.status(), HttpStatus::Ok);
+        let events: Vec<json::Event> = serde_json::from_str(&res.body_string().unwrap()).unwrap();
+        titles.extend(events.into_iter().map(|e| e.title));
+    }
+    titles.sort();
+    assert_eq!(vec!["0", "100", "200", "300", "50"], titles);
+
+    let res = client
+        .get("/events?tag=page&offset=1000000")
+        .header(ContentType::JSON)
+        .dispatch();
+    assert_eq!(res.status(), HttpStatus::BadRequest);
+}
+
 #[test]
 fn filtered_by_tags() {
     let (client, db, mut search_engine, notify) = setup2();
@@ -99,7 +159,7 @@ fn filtered_by_tags() {
             created_by: Some("test@example.com".into()),
             ..Default::default()
         };
-        flows::create_event(&db, &mut search_engine, &notify, None, e).unwrap();
+        flows::create_event(&db, &mut search_engine, &notify, None, e, None).unwrap();
     }
 
     let req = client.get("/events?tag=a").header(ContentType::JSON);
@@ -140,6 +200,178 @@ fn filtered_by_tags() {
     assert!(body_str.contains("\"tags\":[\"a\",\"b\"]"));
 }
 
+#[test]
+fn filtered_by_organizer_and_registration() {
+    let (client, db, mut search_engine, notify) = setup2();
+    let events = vec![
+        ("a", Some("Bioland e.V."), Some("email")),
+        ("b", Some("Bioland e.V."), None),
+        ("c", Some("Bioland"), Some("telephone")),
+        ("d", None, Some("email")),
+    ];
+    for (title, organizer, registration) in events {
+        let e = usecases::NewEvent {
+            title: title.into(),
+            start: Utc::now().naive_utc().timestamp(),
+            organizer: organizer.map(Into::into),
+            registration: registration.map(Into::into),
+            email: Some("test@example.com".into()),
+            telephone: Some("0123".into()),
+            created_by: Some("test@example.com".into()),
+            ..Default::default()
+        };
+        flows::create_event(&db, &mut search_engine, &notify, None, e, None).unwrap();
+    }
+    let titles = |url: &str| -> Vec<String> {
+        let mut response = client.get(url).header(ContentType::JSON).dispatch();
+        assert_eq!(response.status(), HttpStatus::Ok);
+        let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+        let events: Vec<json::Event> = serde_json::from_str(&body_str).unwrap();
+        let mut titles: Vec<_> = events.into_iter().map(|e| e.title).collect();
+        titles.sort();
+        titles
+    };
+
+    assert_eq!(vec!["a", "b"], titles("/events?organizer=bioland%20E.V."));
+    assert_eq!(vec!["a", "d"], titles("/events?registration=email"));
+    assert_eq!(
+        vec!["a"],
+        titles("/events?organizer=Bioland%20e.V.&registration=email")
+    );
+    assert!(titles("/events?organizer=Bioland&registration=email").is_empty());
+
+    let response = client
+        .get("/events?registration=fax")
+        .header(ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), HttpStatus::BadRequest);
+}
+
+#[test]
+fn filtered_by_text() {
+    let (client, db, mut search_engine, notify) = setup2();
+    db.exclusive()
+        .unwrap()
+        .create_user(&User {
+            email: "admin@example.com".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::Admin,
+        })
+        .unwrap();
+    let events = vec![
+        ("Repair Café", "Fix broken devices", "repair", "Berlin"),
+        ("Flea market", "Second hand clothes", "market", "Stuttgart"),
+        ("Garden party", "Urban gardening", "garden", "Berlin"),
+    ];
+    let mut ids = vec![];
+    for (title, description, tag, city) in events {
+        let e = usecases::NewEvent {
+            title: title.into(),
+            description: Some(description.into()),
+            start: Utc::now().naive_utc().timestamp(),
+            lat: Some(48.7),
+            lng: Some(9.1),
+            city: Some(city.into()),
+            tags: Some(vec![tag.into()]),
+            created_by: Some("test@example.com".into()),
+            ..Default::default()
+        };
+        let id = flows::create_event(&db, &mut search_engine, &notify, None, e, None)
+            .unwrap()
+            .id;
+        ids.push(id);
+    }
+    let titles = |url: &str| -> Vec<String> {
+        let mut response = client.get(url).header(ContentType::JSON).dispatch();
+        assert_eq!(response.status(), HttpStatus::Ok);
+        let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+        let events: Vec<json::Event> = serde_json::from_str(&body_str).unwrap();
+        let mut titles: Vec<_> = events.into_iter().map(|e| e.title).collect();
+        titles.sort();
+        titles
+    };
+
+    assert_eq!(vec!["Flea market"], titles("/events?text=market"));
+    assert_eq!(vec!["Repair Café"], titles("/events?text=devices"));
+    assert_eq!(
+        vec!["Garden party", "Repair Café"],
+        titles("/events?text=berlin")
+    );
+    assert_eq!(vec!["Garden party"], titles("/events?text=%23garden"));
+    assert!(titles("/events?text=concert").is_empty());
+
+    // Updated events are reindexed
+    let e = usecases::NewEvent {
+        title: "Summer concert".into(),
+        description: Some("Second hand clothes".into()),
+        start: Utc::now().naive_utc().timestamp(),
+        tags: Some(vec!["market".into()]),
+        created_by: Some("test@example.com".into()),
+        ..Default::default()
+    };
+    flows::update_event(
+        &db,
+        &mut search_engine,
+        &notify,
+        None,
+        ids[1].clone(),
+        e,
+        None,
+    )
+    .unwrap();
+    assert_eq!(vec!["Summer concert"], titles("/events?text=concert"));
+    assert!(titles("/events?text=flea").is_empty());
+
+    // Archived events are removed from the index
+    flows::archive_events(
+        &db,
+        &mut search_engine,
+        &[ids[0].as_str()],
+        "admin@example.com",
+    )
+    .unwrap();
+    assert_eq!(vec!["Garden party"], titles("/events?text=berlin"));
+}
+
+#[test]
+fn filtered_by_modification_time() {
+    let (client, db, mut search_engine, notify) = setup2();
+    for title in &["a", "b"] {
+        let e = usecases::NewEvent {
+            title: (*title).into(),
+            start: Utc::now().naive_utc().timestamp(),
+            email: Some("test@example.com".into()),
+            created_by: Some("test@example.com".into()),
+            ..Default::default()
+        };
+        flows::create_event(&db, &mut search_engine, &notify, None, e, None).unwrap();
+    }
+    let now = Timestamp::now().into_seconds();
+    let titles = |url: &str| -> Vec<String> {
+        let mut response = client.get(url).header(ContentType::JSON).dispatch();
+        assert_eq!(response.status(), HttpStatus::Ok);
+        let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+        let events: Vec<json::Event> = serde_json::from_str(&body_str).unwrap();
+        let mut titles: Vec<_> = events.into_iter().map(|e| e.title).collect();
+        titles.sort();
+        titles
+    };
+
+    assert_eq!(
+        vec!["a", "b"],
+        titles(&format!("/events?since={}", now - 60))
+    );
+    assert!(titles(&format!("/events?since={}", now + 60)).is_empty());
+    assert!(titles(&format!("/events?since={}&text=a", now + 60)).is_empty());
+
+    let response = client
+        .get("/events?since=yesterday")
+        .header(ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), HttpStatus::BadRequest);
+}
+
 #[test]
 fn filtered_by_creator_without_api_token() {
     let (client, _db) = setup();
@@ -160,6 +392,8 @@ fn filtered_by_creator_with_valid_api_token() {
             name: "bar".into(),
             moderated_tags: vec!["org-tag".into()],
             api_token: "foo".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
     let ids: Vec<_> = ["foo@bar.com", "test@test.com", "bla@bla.bla"]
@@ -172,9 +406,16 @@ fn filtered_by_creator_with_valid_api_token() {
                 start: Utc::now().naive_utc().timestamp(),
                 ..Default::default()
             };
-            flows::create_event(&db, &mut search_engine, &notify, Some("foo"), new_event)
-                .unwrap()
-                .id
+            flows::create_event(
+                &db,
+                &mut search_engine,
+                &notify,
+                Some("foo"),
+                new_event,
+                None,
+            )
+            .unwrap()
+            .id
         })
         .collect();
     let mut res = client
@@ -199,6 +440,8 @@ fn filtered_by_creator_with_invalid_api_token() {
             name: "bar".into(),
             moderated_tags: vec!["org-tag".into()],
             api_token: "foo".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
 
@@ -223,7 +466,7 @@ fn filtered_by_start_min() {
             created_by: Some("test@example.com".into()),
             ..Default::default()
         };
-        flows::create_event(&db, &mut search_engine, &notify, None, e).unwrap();
+        flows::create_event(&db, &mut search_engine, &notify, None, e, None).unwrap();
     }
     let mut res = client
         .get(format!("/events?start_min={}", now + 150))
@@ -251,7 +494,7 @@ fn filtered_by_start_max() {
             created_by: Some("test@example.com".into()),
             ..Default::default()
         };
-        flows::create_event(&db, &mut search_engine, &notify, None, e).unwrap();
+        flows::create_event(&db, &mut search_engine, &notify, None, e, None).unwrap();
     }
     let mut res = client
         .get(format!("/events?start_max={}", now + 250))
@@ -268,6 +511,47 @@ fn filtered_by_start_max() {
     assert!(objects[3].contains(&format!("\"start\":{}", now + 200)));
 }
 
+#[test]
+fn recurring_events_within_time_window() {
+    let (client, db, mut search_engine, notify) = setup2();
+    let now = Utc::now().naive_utc().timestamp();
+    let day = 24 * 60 * 60;
+    let weekly = usecases::NewEvent {
+        title: "weekly".into(),
+        start: now + 100,
+        end: Some(now + 200),
+        recurrence: Some("FREQ=WEEKLY;COUNT=4".into()),
+        created_by: Some("test@example.com".into()),
+        ..Default::default()
+    };
+    flows::create_event(&db, &mut search_engine, &notify, None, weekly, None).unwrap();
+    let once = usecases::NewEvent {
+        title: "once".into(),
+        start: now + 8 * day,
+        created_by: Some("test@example.com".into()),
+        ..Default::default()
+    };
+    flows::create_event(&db, &mut search_engine, &notify, None, once, None).unwrap();
+    let mut res = client
+        .get(format!(
+            "/events?start_min={}&start_max={}",
+            now + day,
+            now + 15 * day
+        ))
+        .header(ContentType::JSON)
+        .dispatch();
+    assert_eq!(res.status(), HttpStatus::Ok);
+    test_json(&res);
+    let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+    let objects: Vec<_> = body_str.split("},{").collect();
+    assert_eq!(objects.len(), 3);
+    assert!(objects[0].contains(&format!("\"start\":{}", now + 7 * day + 100)));
+    assert!(objects[0].contains(&format!("\"end\":{}", now + 7 * day + 200)));
+    assert!(objects[0].contains("\"recurrence\":\"FREQ=WEEKLY;COUNT=4\""));
+    assert!(objects[1].contains(&format!("\"start\":{}", now + 8 * day)));
+    assert!(objects[2].contains(&format!("\"start\":{}", now + 14 * day + 100)));
+}
+
 #[test]
 fn filtered_by_bounding_box() {
     let (client, db, mut search_engine, notify) = setup2();
@@ -281,7 +565,7 @@ fn filtered_by_bounding_box() {
             created_by: Some("test@example.com".into()),
             ..Default::default()
         };
-        flows::create_event(&db, &mut search_engine, &notify, None, e).unwrap();
+        flows::create_event(&db, &mut search_engine, &notify, None, e, None).unwrap();
     }
     let mut res = client
         .get("/events?bbox=-8,-5,10,7.9")
@@ -21,6 +21,8 @@ fn with_invalid_api_token() {
             name: "bar".into(),
             moderated_tags: vec!["org-tag".into()],
             api_token: "foo".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
     let res = client
@@ -42,6 +44,8 @@ fn with_api_token() {
             name: "bar".into(),
             moderated_tags: vec!["org-tag".into()],
             api_token: "foo".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
     let e = usecases::NewEvent {
@@ -51,7 +55,7 @@ fn with_api_token() {
         start: Utc::now().naive_utc().timestamp(),
         ..Default::default()
     };
-    let id = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e)
+    let id = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e, None)
         .unwrap()
         .id;
     assert!(db.shared().unwrap().get_event(id.as_ref()).is_ok());
@@ -78,6 +82,8 @@ fn with_api_token_for_organization_without_any_moderated_tags() {
             name: "bar".into(),
             moderated_tags: vec![],
             api_token: "foo".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
     let e = usecases::NewEvent {
@@ -87,7 +93,7 @@ fn with_api_token_for_organization_without_any_moderated_tags() {
         start: Utc::now().naive_utc().timestamp(),
         ..Default::default()
     };
-    let id = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e)
+    let id = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e, None)
         .unwrap()
         .id;
     assert!(db.shared().unwrap().get_event(id.as_ref()).is_ok());
@@ -114,6 +120,8 @@ fn with_api_token_but_mismatching_tag() {
             name: "bar".into(),
             moderated_tags: vec!["org-tag".into()],
             api_token: "foo".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
     // The events needs an owner, otherwise the test may fail
@@ -125,6 +133,8 @@ fn with_api_token_but_mismatching_tag() {
             name: "foo".into(),
             moderated_tags: vec!["bla".into()],
             api_token: "bar".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
     let e = usecases::NewEvent {
@@ -134,7 +144,7 @@ fn with_api_token_but_mismatching_tag() {
         start: Utc::now().naive_utc().timestamp(),
         ..Default::default()
     };
-    let id = flows::create_event(&db, &mut search_engine, &notify, Some("bar"), e)
+    let id = flows::create_event(&db, &mut search_engine, &notify, Some("bar"), e, None)
         .unwrap()
         .id;
     assert!(db.shared().unwrap().get_event(id.as_ref()).is_ok());
@@ -157,6 +167,8 @@ fn with_api_token_keep_org_tag() {
             name: "bar".into(),
             moderated_tags: vec!["org-tag".into()],
             api_token: "foo".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
     let e = usecases::NewEvent {
@@ -166,7 +178,7 @@ fn with_api_token_keep_org_tag() {
         start: Utc::now().naive_utc().timestamp(),
         ..Default::default()
     };
-    let id = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e)
+    let id = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e, None)
         .unwrap()
         .id;
     assert!(db.shared().unwrap().get_event(id.as_ref()).is_ok());
@@ -193,6 +205,8 @@ fn with_api_token_and_removing_tag() {
             name: "bar".into(),
             moderated_tags: vec!["org-tag1".into(), "org-tag2".into()],
             api_token: "foo".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
     let e = usecases::NewEvent {
@@ -208,7 +222,7 @@ fn with_api_token_and_removing_tag() {
         start: Utc::now().naive_utc().timestamp(),
         ..Default::default()
     };
-    let id = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e)
+    let id = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e, None)
         .unwrap()
         .id;
     assert!(db.shared().unwrap().get_event(id.as_ref()).is_ok());
@@ -237,6 +251,8 @@ fn with_api_token_created_by() {
             name: "bar".into(),
             moderated_tags: vec!["bla".into()],
             api_token: "foo".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
     let created_by = Some("foo@bar.com".into());
@@ -248,7 +264,7 @@ fn with_api_token_created_by() {
         start,
         ..Default::default()
     };
-    let id = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e)
+    let id = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e, None)
         .unwrap()
         .id;
     assert!(db.shared().unwrap().get_event(id.as_ref()).is_ok());
@@ -294,6 +310,8 @@ fn with_api_token_from_different_org_unauthorized() {
             name: "creator".into(),
             moderated_tags: vec!["creator".into()],
             api_token: "creator".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
     let _updater_org = db
@@ -304,6 +322,8 @@ fn with_api_token_from_different_org_unauthorized() {
             name: "updater".into(),
             moderated_tags: vec!["updater".into()],
             api_token: "updater".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
     let e = usecases::NewEvent {
@@ -313,7 +333,7 @@ fn with_api_token_from_different_org_unauthorized() {
         start: Utc::now().naive_utc().timestamp(),
         ..Default::default()
     };
-    let id = flows::create_event(&db, &mut search_engine, &notify, Some("creator"), e)
+    let id = flows::create_event(&db, &mut search_engine, &notify, Some("creator"), e, None)
         .unwrap()
         .id;
     assert!(db.shared().unwrap().get_event(id.as_ref()).is_ok());
@@ -340,6 +360,8 @@ fn update_geo_location() {
             name: "bar".into(),
             moderated_tags: vec!["org-tag".into()],
             api_token: "foo".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
     let e = usecases::NewEvent {
@@ -351,7 +373,7 @@ fn update_geo_location() {
         lng: Some(2.0),
         ..Default::default()
     };
-    let id = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e)
+    let id = flows::create_event(&db, &mut search_engine, &notify, Some("foo"), e, None)
         .unwrap()
         .id;
     let created = db.shared().unwrap().get_event(id.as_ref()).unwrap();
@@ -0,0 +1,112 @@
+use super::Result;
+use crate::{adapters::json, core::prelude::*, infrastructure::GEO_CODING_GW};
+use ofdb_core::gateways::geocode::{AddressSuggestion, GeoCodingGateway};
+use rocket::State;
+use rocket_contrib::json::Json;
+use std::{
+    collections::HashMap,
+    sync::{PoisonError, RwLock},
+    time::{Duration, Instant},
+};
+
+const MIN_QUERY_LEN: usize = 3;
+const DEFAULT_LIMIT: usize = 5;
+const MAX_LIMIT: usize = 10;
+
+const MAX_CACHE_AGE: Duration = Duration::from_secs(24 * 3600);
+// Bounds the memory consumption of the cache
+const MAX_CACHE_ENTRIES: usize = 10_000;
+
+type Cache = HashMap<(String, usize), (Instant, Vec<AddressSuggestion>)>;
+
+/// Avoids asking the geocoding providers for the same
+/// query repeatedly while users are typing.
+#[derive(Default)]
+pub struct AddressCompletionCache(RwLock<Cache>);
+
+impl AddressCompletionCache {
+    pub fn complete_address(
+        &self,
+        gw: &dyn GeoCodingGateway,
+        query: &str,
+        limit: usize,
+    ) -> Vec<AddressSuggestion> {
+        let key = (query.trim().to_lowercase(), limit);
+        if let Some((created_at, suggestions)) = self
+            .0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&key)
+        {
+            if created_at.elapsed() < MAX_CACHE_AGE {
+                return suggestions.clone();
+            }
+        }
+        let suggestions = gw.complete_address(&key.0, limit);
+        let mut cache = self.0.write().unwrap_or_else(PoisonError::into_inner);
+        if cache.len() >= MAX_CACHE_ENTRIES {
+            cache.retain(|_, (created_at, _)| created_at.elapsed() < MAX_CACHE_AGE);
+            if cache.len() >= MAX_CACHE_ENTRIES {
+                cache.clear();
+            }
+        }
+        cache.insert(key, (Instant::now(), suggestions.clone()));
+        suggestions
+    }
+}
+
+#[get("/geocoding/complete?<q>&<limit>")]
+pub fn get_complete_address(
+    cache: State<AddressCompletionCache>,
+    q: String,
+    limit: Option<usize>,
+) -> Result<Vec<json::AddressSuggestion>> {
+    if q.trim().chars().count() < MIN_QUERY_LEN {
+        return Ok(Json(vec![]));
+    }
+    let limit = match limit {
+        Some(0) => return Err(Error::Parameter(ParameterError::InvalidLimit).into()),
+        Some(limit) => limit.min(MAX_LIMIT),
+        None => DEFAULT_LIMIT,
+    };
+    let suggestions = cache.complete_address(&*GEO_CODING_GW, &q, limit);
+    Ok(Json(suggestions.into_iter().map(Into::into).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ofdb_entities::address::Address;
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    struct CountingGateway(Cell<usize>);
+
+    impl GeoCodingGateway for CountingGateway {
+        fn resolve_address_lat_lng(&self, _: &Address) -> Option<(f64, f64)> {
+            None
+        }
+
+        fn complete_address(&self, query: &str, _limit: usize) -> Vec<AddressSuggestion> {
+            self.0.set(self.0.get() + 1);
+            vec![AddressSuggestion {
+                label: query.to_string(),
+                lat: 48.7,
+                lng: 9.1,
+                address: Address::default(),
+            }]
+        }
+    }
+
+    #[test]
+    fn cache_suggestions_of_normalized_queries() {
+        let gw = CountingGateway::default();
+        let cache = AddressCompletionCache::default();
+        let suggestions = cache.complete_address(&gw, "Schlossplatz", 5);
+        assert_eq!("schlossplatz", suggestions[0].label);
+        assert_eq!(suggestions, cache.complete_address(&gw, " schlossPLATZ", 5));
+        assert_eq!(1, gw.0.get());
+        cache.complete_address(&gw, "Schlossplatz", 10);
+        assert_eq!(2, gw.0.get());
+    }
+}
@@ -0,0 +1,409 @@
+//! Protects small deployments against oversized requests and
+//! organizations that upload more data than expected.
+
+use crate::{core::prelude::*, infrastructure::cfg::Cfg};
+use chrono::{NaiveDate, Utc};
+use rocket::{
+    data::{self, Data, FromDataSimple},
+    http::Status,
+    request::Request,
+    Catcher, Outcome, State,
+};
+use rocket_contrib::json::Json;
+use serde::de::DeserializeOwned;
+use std::{
+    collections::HashMap,
+    io::Read,
+    ops::Deref,
+    sync::{Mutex, PoisonError},
+};
+
+// The body is read without relying on the Content-Length
+// header, i.e. chunked bodies are limited as well.
+fn read_json<T: DeserializeOwned>(data: Data, max_size: u64) -> data::Outcome<T, ()> {
+    let mut body = String::new();
+    // One more byte than allowed reveals an oversized body
+    if let Err(err) = data.open().take(max_size + 1).read_to_string(&mut body) {
+        log::debug!("Failed to read request body: {}", err);
+        return Outcome::Failure((Status::BadRequest, ()));
+    }
+    if body.len() as u64 > max_size {
+        return Outcome::Failure((Status::PayloadTooLarge, ()));
+    }
+    match serde_json::from_str(&body) {
+        Ok(value) => Outcome::Success(value),
+        Err(err) => {
+            log::debug!("Failed to parse JSON body: {}", err);
+            // Same status codes as for rocket_contrib::json::Json
+            let status = if err.is_data() {
+                Status::UnprocessableEntity
+            } else {
+                Status::BadRequest
+            };
+            Outcome::Failure((status, ()))
+        }
+    }
+}
+
+/// A JSON body that is rejected if it exceeds the configured size.
+pub struct LimitedJson<T>(pub T);
+
+impl<T> LimitedJson<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for LimitedJson<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: DeserializeOwned> FromDataSimple for LimitedJson<T> {
+    type Error = ();
+    fn from_data(request: &Request, data: Data) -> data::Outcome<Self, Self::Error> {
+        let max_size = match request.guard::<State<Cfg>>().succeeded() {
+            Some(cfg) => cfg.body_size_limits.json,
+            None => return Outcome::Failure((Status::InternalServerError, ())),
+        };
+        read_json(data, max_size).map(LimitedJson)
+    }
+}
+
+/// A JSON body of an import that is rejected if it exceeds
+/// the configured size.
+pub struct ImportJson<T>(pub T);
+
+impl<T> ImportJson<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for ImportJson<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: DeserializeOwned> FromDataSimple for ImportJson<T> {
+    type Error = ();
+    fn from_data(request: &Request, data: Data) -> data::Outcome<Self, Self::Error> {
+        let max_size = match request.guard::<State<Cfg>>().succeeded() {
+            Some(cfg) => cfg.body_size_limits.import,
+            None => return Outcome::Failure((Status::InternalServerError, ())),
+        };
+        read_json(data, max_size).map(ImportJson)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Upload {
+    Event,
+    Place,
+}
+
+/// Number of uploads of an organization on a single day
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Uploads {
+    pub events: u32,
+    pub places: u32,
+}
+
+impl Uploads {
+    pub fn count(&self, upload: Upload) -> u32 {
+        match upload {
+            Upload::Event => self.events,
+            Upload::Place => self.places,
+        }
+    }
+
+    fn count_mut(&mut self, upload: Upload) -> &mut u32 {
+        match upload {
+            Upload::Event => &mut self.events,
+            Upload::Place => &mut self.places,
+        }
+    }
+}
+
+/// The individual quotas of the organization or the
+/// configured defaults.
+pub fn daily_quotas<R: OrganizationRepo>(db: &R, org_id: &Id, cfg: &Cfg) -> Result<DailyQuotas> {
+    Ok(db.get_org_daily_quotas(org_id)?.or(cfg.org_daily_quotas))
+}
+
+/// Counts the places and events that have been uploaded
+/// by organizations today.
+///
+/// The counters are only kept in memory and start
+/// from scratch after a restart.
+#[derive(Default)]
+pub struct UploadQuotas(Mutex<HashMap<String, (NaiveDate, Uploads)>>);
+
+/// An upload that has already been counted before it is
+/// stored. It is released again unless it is committed,
+/// e.g. if storing fails.
+#[must_use]
+pub struct Reservation<'a> {
+    quotas: &'a UploadQuotas,
+    org_id: Id,
+    upload: Upload,
+    day: NaiveDate,
+    count: u32,
+    limit: Option<u32>,
+}
+
+impl Reservation<'_> {
+    /// The maximum number of uploads that may be committed
+    pub fn limit(&self) -> Option<u32> {
+        self.limit
+    }
+
+    pub fn commit(self) {
+        self.commit_count(1);
+    }
+
+    /// Replaces the reserved upload by the actual number of uploads
+    pub fn commit_count(mut self, count: u32) {
+        self.quotas
+            .adjust_at(&self.org_id, self.upload, self.day, self.count, count);
+        self.count = 0;
+    }
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        if self.count > 0 {
+            self.quotas
+                .adjust_at(&self.org_id, self.upload, self.day, self.count, 0);
+        }
+    }
+}
+
+impl UploadQuotas {
+    fn uploads_at(&self, org_id: &Id, today: NaiveDate) -> Uploads {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(org_id.as_str())
+            .filter(|(day, _)| *day == today)
+            .map(|(_, uploads)| *uploads)
+            .unwrap_or_default()
+    }
+
+    fn reserve_at(
+        &self,
+        org_id: &Id,
+        upload: Upload,
+        quota: Option<u32>,
+        today: NaiveDate,
+    ) -> Result<Reservation> {
+        self.reserve_many_at(org_id, upload, quota, today, Some(1))
+    }
+
+    // Checking and counting the uploads happens while holding
+    // the lock, i.e. concurrent uploads cannot exceed the quota.
+    // All remaining uploads are reserved if `max_count` is `None`.
+    fn reserve_many_at(
+        &self,
+        org_id: &Id,
+        upload: Upload,
+        quota: Option<u32>,
+        today: NaiveDate,
+        max_count: Option<u32>,
+    ) -> Result<Reservation> {
+        let mut uploads = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        // Counters of previous days are no longer needed
+        uploads.retain(|_, (day, _)| *day == today);
+        let (_, uploads_today) = uploads
+            .entry(org_id.as_str().to_owned())
+            .or_insert((today, Uploads::default()));
+        let uploaded = uploads_today.count_mut(upload);
+        let remaining = quota.map(|quota| quota.saturating_sub(*uploaded));
+        if remaining == Some(0) {
+            return Err(Error::Parameter(ParameterError::UploadQuotaExceeded));
+        }
+        let limit = match (remaining, max_count) {
+            (Some(remaining), Some(max_count)) => Some(remaining.min(max_count)),
+            (remaining, max_count) => remaining.or(max_count),
+        };
+        // Unlimited uploads are only counted when committed
+        let count = limit.unwrap_or(0);
+        *uploaded = uploaded.saturating_add(count);
+        Ok(Reservation {
+            quotas: self,
+            org_id: org_id.clone(),
+            upload,
+            day: today,
+            count,
+            limit,
+        })
+    }
+
+    fn adjust_at(&self, org_id: &Id, upload: Upload, day: NaiveDate, reserved: u32, count: u32) {
+        let mut uploads = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        // The counters are reset if the day has changed in the meantime
+        if let Some((_, uploads_today)) = uploads
+            .get_mut(org_id.as_str())
+            .filter(|(today, _)| *today == day)
+        {
+            let uploaded = uploads_today.count_mut(upload);
+            *uploaded = uploaded.saturating_sub(reserved).saturating_add(count);
+        }
+    }
+
+    /// Reserves a single upload.
+    ///
+    /// Fails if the organization has already used up its quota for today.
+    pub fn reserve(&self, org_id: &Id, upload: Upload, quota: Option<u32>) -> Result<Reservation> {
+        self.reserve_at(org_id, upload, quota, Utc::today().naive_utc())
+    }
+
+    /// Reserves all remaining uploads of today, e.g. for
+    /// a bulk import.
+    ///
+    /// Fails if the organization has already used up its quota for today.
+    pub fn reserve_remaining(
+        &self,
+        org_id: &Id,
+        upload: Upload,
+        quota: Option<u32>,
+    ) -> Result<Reservation> {
+        self.reserve_many_at(org_id, upload, quota, Utc::today().naive_utc(), None)
+    }
+
+    pub fn uploads_today(&self, org_id: &Id) -> Uploads {
+        self.uploads_at(org_id, Utc::today().naive_utc())
+    }
+}
+
+#[catch(413)]
+fn payload_too_large() -> Json<ofdb_boundary::Error> {
+    Json(ofdb_boundary::Error {
+        http_status: 413,
+        message: "The request body is too large".to_string(),
+    })
+}
+
+#[catch(429)]
+fn too_many_requests() -> Json<ofdb_boundary::Error> {
+    Json(ofdb_boundary::Error {
+        http_status: 429,
+        message: ParameterError::UploadQuotaExceeded.to_string(),
+    })
+}
+
+pub fn catchers() -> Vec<Catcher> {
+    catchers![payload_too_large, too_many_requests]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reset_upload_quotas_every_day() {
+        let quotas = UploadQuotas::default();
+        let org_id = Id::from("org");
+        let today = NaiveDate::from_ymd(2021, 6, 27);
+        let tomorrow = today.succ();
+        let event = Upload::Event;
+        quotas
+            .reserve_at(&org_id, event, Some(2), today)
+            .unwrap()
+            .commit();
+        quotas
+            .reserve_at(&org_id, event, Some(2), today)
+            .unwrap()
+            .commit();
+        assert!(quotas.reserve_at(&org_id, event, Some(2), today).is_err());
+        assert!(quotas
+            .reserve_at(&"other".into(), event, Some(2), today)
+            .is_ok());
+        quotas
+            .reserve_at(&org_id, event, Some(2), tomorrow)
+            .unwrap()
+            .commit();
+        assert_eq!(1, quotas.uploads_at(&org_id, tomorrow).events);
+    }
+
+    #[test]
+    fn release_uncommitted_reservations() {
+        let quotas = UploadQuotas::default();
+        let org_id = Id::from("org");
+        let today = NaiveDate::from_ymd(2021, 6, 28);
+        let reservation = quotas
+            .reserve_at(&org_id, Upload::Event, Some(1), today)
+            .unwrap();
+        // Concurrent uploads are rejected while the reservation is pending
+        assert!(quotas
+            .reserve_at(&org_id, Upload::Event, Some(1), today)
+            .is_err());
+        drop(reservation);
+        assert_eq!(0, quotas.uploads_at(&org_id, today).events);
+        assert!(quotas
+            .reserve_at(&org_id, Upload::Event, Some(1), today)
+            .is_ok());
+    }
+
+    #[test]
+    fn reserve_remaining_uploads() {
+        let quotas = UploadQuotas::default();
+        let org_id = Id::from("org");
+        let today = NaiveDate::from_ymd(2021, 7, 1);
+        quotas
+            .reserve_at(&org_id, Upload::Place, Some(5), today)
+            .unwrap()
+            .commit();
+        let reservation = quotas
+            .reserve_many_at(&org_id, Upload::Place, Some(5), today, None)
+            .unwrap();
+        assert_eq!(Some(4), reservation.limit());
+        // Concurrent uploads are rejected while the reservation is pending
+        assert!(quotas
+            .reserve_at(&org_id, Upload::Place, Some(5), today)
+            .is_err());
+        reservation.commit_count(2);
+        assert_eq!(3, quotas.uploads_at(&org_id, today).places);
+
+        let reservation = quotas
+            .reserve_many_at(&org_id, Upload::Event, None, today, None)
+            .unwrap();
+        assert_eq!(None, reservation.limit());
+        reservation.commit_count(7);
+        assert_eq!(7, quotas.uploads_at(&org_id, today).events);
+    }
+
+    #[test]
+    fn count_uploads_of_places_and_events_separately() {
+        let quotas = UploadQuotas::default();
+        let org_id = Id::from("org");
+        let today = NaiveDate::from_ymd(2021, 6, 30);
+        quotas
+            .reserve_at(&org_id, Upload::Place, None, today)
+            .unwrap()
+            .commit_count(3);
+        quotas
+            .reserve_at(&org_id, Upload::Event, None, today)
+            .unwrap()
+            .commit();
+        assert_eq!(
+            Uploads {
+                events: 1,
+                places: 3
+            },
+            quotas.uploads_at(&org_id, today)
+        );
+        assert!(quotas
+            .reserve_at(&org_id, Upload::Place, Some(3), today)
+            .is_err());
+        assert!(quotas
+            .reserve_at(&org_id, Upload::Event, Some(3), today)
+            .is_ok());
+    }
+}
@@ -7,6 +7,7 @@ use crate::{
         util::{self, geo},
     },
     infrastructure::{
+        cfg::Cfg,
         db::{sqlite, tantivy},
         error::AppError,
         flows::prelude as flows,
@@ -21,12 +22,21 @@ use rocket::{
     Route, State,
 };
 use rocket_contrib::json::Json;
-use std::result;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    io::Cursor,
+    result,
+};
 
 pub mod captcha;
+mod changes;
 mod count;
 mod entries;
 pub mod events;
+pub mod geocoding;
+pub mod limits;
+mod organizations;
 mod places;
 mod ratings;
 mod search;
@@ -36,27 +46,68 @@ mod users;
 
 type Result<T> = result::Result<Json<T>, AppError>;
 type StatusResult = result::Result<Status, AppError>;
+type PaginatedResult<T> = result::Result<Paginated<T>, AppError>;
+type LimitedResult<T> = result::Result<Limited<T>, AppError>;
+
+pub const TOTAL_COUNT_HEADER: &str = "X-Total-Count";
+pub const RESULT_LIMIT_HEADER: &str = "X-Result-Limit";
+
+// Clients may reuse a cached response after revalidating its ETag
+const CACHE_CONTROL: &str = "public, no-cache";
+
+/// A page of a list with the total number of
+/// items in the [`TOTAL_COUNT_HEADER`].
+pub struct Paginated<T> {
+    items: Json<Vec<T>>,
+    total_count: usize,
+}
+
+/// A list of results with the limit that has been
+/// applied to the request in the [`RESULT_LIMIT_HEADER`].
+pub struct Limited<T> {
+    body: T,
+    limit: usize,
+}
+
+/// A response that clients may cache with an ETag
+/// derived from its content.
+///
+/// The content of a response changes with every write,
+/// rendering previous ETags invalid.
+pub struct Cached<T>(T);
 
 pub fn routes() -> Vec<Route> {
     routes![
         post_login,
         post_logout,
+        get_csrf_token,
         confirm_email_address,
+        confirm_subscriptions,
         subscribe_to_bbox,
         get_bbox_subscriptions,
         unsubscribe_all_bboxes,
+        organizations::subscribe_to_org,
+        organizations::unsubscribe_from_org,
+        organizations::get_org_subscriptions,
         entries::get_entry,
         entries::get_entries_recently_changed,
         entries::get_entries_most_popular_tags,
         entries::post_entry,
+        entries::post_entry_validation,
+        entries::post_entry_draft,
+        entries::get_entry_drafts,
+        entries::post_entry_draft_publish,
         entries::put_entry,
         get_place,
         get_place_history,
         get_place_history_revision,
+        post_place_note,
         post_places_review,
         events::post_event,
         events::post_event_with_token,
+        events::post_event_validation,
         events::get_event,
+        events::post_event_view,
         events::get_events_chronologically,
         events::get_events_with_token,
         events::put_event,
@@ -70,23 +121,71 @@ pub fn routes() -> Vec<Route> {
         users::post_user,
         ratings::post_rating,
         ratings::load_rating,
+        ratings::get_pending_ratings,
+        ratings::post_pending_ratings_publish,
+        ratings::delete_pending_ratings,
         users::get_user,
         users::get_current_user,
         users::delete_user,
+        users::get_current_user_preferences,
+        users::put_current_user_preferences,
+        users::get_current_user_watches,
+        users::put_current_user_watch,
+        users::delete_current_user_watch,
+        users::get_current_user_tokens,
+        users::post_current_user_token,
+        users::delete_current_user_token,
         get_categories,
         get_category,
         get_tags,
+        get_tag_usage,
+        post_suggest_tags,
+        post_rename_tag,
+        post_announcement,
+        get_data_quality_report,
+        get_metrics,
+        post_merge_users,
         search::get_search,
+        search::get_search_nearby,
+        search::get_search_suggest,
+        geocoding::get_complete_address,
         get_duplicates,
         search::post_search_duplicates,
+        changes::get_changes,
         count::get_count_entries,
         count::get_count_tags,
         get_version,
         get_api,
         entries_csv_export,
+        ratings_csv_export,
+        organizations::put_tag_moderation_policy,
+        organizations::post_tag_transfer,
+        organizations::post_tag_owner,
+        organizations::get_org_api_tokens,
+        organizations::post_org_api_token,
+        organizations::delete_org_api_token,
+        organizations::get_org_api_tokens_as_admin,
+        organizations::post_org_api_token_as_admin,
+        organizations::delete_org_api_token_as_admin,
+        organizations::post_compare_places,
+        organizations::post_enrich_places,
+        organizations::get_org_usage,
+        organizations::get_org_daily_quotas_as_admin,
+        organizations::put_org_daily_quotas_as_admin,
+        organizations::get_org_search,
         places::count_pending_clearances,
         places::list_pending_clearances,
         places::update_pending_clearances,
+        places::list_clearance_log,
+        places::list_clearance_log_as_admin,
+        places::post_osm_import,
+        places::get_osm_node_proposals,
+        places::post_osm_node_proposal_accept,
+        places::post_osm_node_proposal_reject,
+        places::get_place_stats,
+        places::post_place_view,
+        places::post_place_confirm_still_valid,
+        places::get_unanswered_confirmation_requests,
         captcha::post_captcha,
         captcha::get_captcha,
         captcha::post_captcha_verify,
@@ -100,8 +199,11 @@ pub fn get_place(
 ) -> Result<(json::PlaceRoot, json::PlaceRevision, json::ReviewStatus)> {
     let (place, status) = {
         let db = db.shared()?;
-        db.get_place(&id)?
+        db.get_place_by_id(&id)?
     };
+    if status == ReviewStatus::Draft {
+        return Err(Error::Repo(RepoError::NotFound).into());
+    }
     let (place_root, place_revision) = place.into();
     Ok(Json((
         place_root.into(),
@@ -117,18 +219,7 @@ pub fn get_place_history_revision(
     id: String,
     revision: RevisionValue,
 ) -> Result<json::PlaceHistory> {
-    let place_history = {
-        let db = db.shared()?;
-
-        // The history contains e-mail addresses of registered users
-        // is only permitted for scouts and admins or organizations!
-        if auth.user_with_min_role(&*db, Role::Scout).is_err() {
-            auth.organization(&*db)?;
-        }
-
-        db.get_place_history(&id, Some(revision.into()))?
-    };
-    Ok(Json(place_history.into()))
+    load_place_history(&db, &auth, &id, Some(revision.into()))
 }
 
 #[get("/places/<id>/history", rank = 2)]
@@ -137,18 +228,56 @@ pub fn get_place_history(
     auth: Auth,
     id: String,
 ) -> Result<json::PlaceHistory> {
-    let place_history = {
-        let db = db.shared()?;
+    load_place_history(&db, &auth, &id, None)
+}
 
-        // The history contains e-mail addresses of registered users
-        // is only permitted for scouts and admins or for organizations!
-        if auth.user_with_min_role(&*db, Role::Scout).is_err() {
-            auth.organization(&*db)?;
-        }
+fn load_place_history(
+    db: &sqlite::Connections,
+    auth: &Auth,
+    id: &str,
+    revision: Option<Revision>,
+) -> Result<json::PlaceHistory> {
+    let db = db.shared()?;
 
-        db.get_place_history(&id, None)?
-    };
-    Ok(Json(place_history.into()))
+    // The history contains e-mail addresses of registered users
+    // is only permitted for scouts and admins or for organizations!
+    let scout = auth.user_with_min_role(&*db, Role::Scout).is_ok();
+    if !scout {
+        auth.organization(&*db)?;
+    }
+
+    let mut place_history = json::PlaceHistory::from(db.get_place_history(id, revision)?);
+    // Internal notes are not shared with organizations
+    if scout {
+        place_history.notes = db
+            .load_place_notes(id)?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+    }
+    Ok(Json(place_history))
+}
+
+#[post("/places/<id>/notes", data = "<note>")]
+pub fn post_place_note(
+    db: sqlite::Connections,
+    auth: Auth,
+    id: String,
+    note: limits::LimitedJson<json::NewPlaceNote>,
+) -> Result<json::PlaceNote> {
+    let db = db.exclusive()?;
+    let user = auth.user_with_min_role(&*db, Role::Scout)?;
+    let json::NewPlaceNote { parent, text } = note.into_inner();
+    let note = usecases::add_place_note(
+        &*db,
+        usecases::NewPlaceNote {
+            place_id: id,
+            parent_id: parent,
+            text,
+        },
+        &Email::from(user.email),
+    )?;
+    Ok(Json(note.into()))
 }
 
 #[post("/places/<ids>/review", data = "<review>")]
@@ -157,7 +286,7 @@ pub fn post_places_review(
     db: sqlite::Connections,
     mut search_engine: tantivy::SearchEngine,
     ids: String,
-    review: Json<json::Review>,
+    review: limits::LimitedJson<json::Review>,
 ) -> Result<()> {
     let ids = util::split_ids(&ids);
     if ids.is_empty() {
@@ -199,7 +328,7 @@ pub fn get_duplicates(
     if ids.is_empty() {
         return Ok(Json(vec![]));
     }
-    let places = connections.shared()?.get_places(&ids)?;
+    let places = connections.shared()?.get_places_by_ids(&ids)?;
     let results = usecases::find_duplicates(&search_engine, &places)?;
     Ok(Json(
         results
@@ -248,6 +377,7 @@ fn post_login(
                 .same_site(rocket::http::SameSite::None)
                 .finish(),
         );
+        add_csrf_token_cookie(&mut cookies, rocket::http::SameSite::None);
     }
     Ok(Json(response))
 }
@@ -255,6 +385,7 @@ fn post_login(
 #[post("/logout", format = "application/json")]
 fn post_logout(auth: Auth, mut cookies: Cookies, jwt_state: State<jwt::JwtState>) -> Json<()> {
     cookies.remove_private(Cookie::named(COOKIE_EMAIL_KEY));
+    cookies.remove(Cookie::build(COOKIE_CSRF_TOKEN_KEY, "").path("/").finish());
     if cfg!(feature = "jwt") {
         for bearer in auth.bearer_tokens() {
             jwt_state.blacklist_token(bearer.to_owned());
@@ -263,6 +394,18 @@ fn post_logout(auth: Auth, mut cookies: Cookies, jwt_state: State<jwt::JwtState>
     Json(())
 }
 
+// Cookie-authenticated clients have to send this token
+// in a header with all requests that change the state
+#[get("/csrf-token")]
+fn get_csrf_token(auth: Auth) -> Result<ofdb_boundary::CsrfToken> {
+    auth.account_email()?;
+    let token = auth
+        .csrf_token()
+        .ok_or(Error::Parameter(ParameterError::Unauthorized))?
+        .to_owned();
+    Ok(Json(ofdb_boundary::CsrfToken { token }))
+}
+
 #[derive(Deserialize, Debug, Clone)]
 struct ConfirmationToken {
     token: String,
@@ -280,14 +423,27 @@ fn confirm_email_address(db: sqlite::Connections, token: Json<ConfirmationToken>
 }
 
 #[post(
-    "/subscribe-to-bbox",
+    "/confirm-subscriptions",
+    format = "application/json",
+    data = "<token>"
+)]
+fn confirm_subscriptions(db: sqlite::Connections, token: Json<ConfirmationToken>) -> Result<()> {
+    let token = token.into_inner().token;
+    usecases::confirm_notification_consent(&*db.exclusive()?, &token)?;
+    Ok(Json(()))
+}
+
+#[post(
+    "/subscribe-to-bbox?<digest>",
     format = "application/json",
     data = "<coordinates>"
 )]
 fn subscribe_to_bbox(
     db: sqlite::Connections,
+    notify: Notify,
     auth: Auth,
-    coordinates: Json<Vec<json::Coordinate>>,
+    coordinates: limits::LimitedJson<Vec<json::Coordinate>>,
+    digest: Option<String>,
 ) -> Result<()> {
     let sw_ne: Vec<_> = coordinates
         .into_inner()
@@ -298,8 +454,19 @@ fn subscribe_to_bbox(
         return Err(Error::Parameter(ParameterError::Bbox).into());
     }
     let bbox = geo::MapBbox::new(sw_ne[0], sw_ne[1]);
+    let digest = digest
+        .map(|d| d.parse::<NotificationDigest>())
+        .transpose()
+        .map_err(Error::from)?;
     let email = auth.account_email()?;
-    usecases::subscribe_to_bbox(&*db.exclusive()?, email.to_string(), bbox)?;
+    let consent_request = {
+        let db = db.exclusive()?;
+        usecases::subscribe_to_bbox(&*db, email.to_string(), bbox, digest)?;
+        usecases::request_notification_consent(&*db, email)?
+    };
+    if let Some(email_nonce) = consent_request {
+        notify.subscription_consent_requested(&email_nonce);
+    }
     Ok(Json(()))
 }
 
@@ -324,53 +491,169 @@ fn get_bbox_subscriptions(
             south_west_lng: s.bbox.southwest().lng().to_deg(),
             north_east_lat: s.bbox.northeast().lat().to_deg(),
             north_east_lng: s.bbox.northeast().lng().to_deg(),
+            digest: s.digest.map(Into::into),
         })
         .collect();
     Ok(Json(user_subscriptions))
 }
 
-#[get("/tags")]
-fn get_tags(connections: sqlite::Connections) -> Result<Vec<String>> {
-    let tags = connections.shared()?.all_tags()?;
-    Ok(Json(tags.into_iter().map(|t| t.id).collect()))
+#[get("/tags?<offset>&<limit>&<prefix>&<min_count>")]
+fn get_tags(
+    connections: sqlite::Connections,
+    offset: Option<u64>,
+    limit: Option<u64>,
+    prefix: Option<String>,
+    min_count: Option<u64>,
+) -> result::Result<Cached<Paginated<String>>, AppError> {
+    let pagination = Pagination { offset, limit };
+    let filter = TagFilter {
+        // Tags are stored in lowercase
+        prefix: prefix
+            .map(|prefix| prefix.trim().to_lowercase())
+            .filter(|prefix| !prefix.is_empty()),
+        min_count,
+    };
+    let db = connections.shared()?;
+    let tags = db.list_tags(&filter, &pagination)?;
+    let total_count = db.count_filtered_tags(&filter)?;
+    Ok(Cached(Paginated {
+        items: Json(tags.into_iter().map(|t| t.id).collect()),
+        total_count,
+    }))
+}
+
+#[post("/tags/suggest", format = "application/json", data = "<request>")]
+fn post_suggest_tags(
+    connections: sqlite::Connections,
+    request: limits::LimitedJson<json::TagSuggestionRequest>,
+) -> Result<Vec<json::TagSuggestion>> {
+    let json::TagSuggestionRequest {
+        title,
+        description,
+        limit,
+    } = request.into_inner();
+    let suggestions = usecases::suggest_tags(&*connections.shared()?, &title, &description, limit)?;
+    Ok(Json(suggestions.into_iter().map(Into::into).collect()))
+}
+
+#[get("/tags/<tag>/usage")]
+fn get_tag_usage(
+    connections: sqlite::Connections,
+    auth: Auth,
+    tag: String,
+) -> Result<json::TagUsage> {
+    let db = connections.shared()?;
+    // The usage includes archived and rejected places
+    auth.user_with_min_role(&*db, Role::Scout)?;
+    Ok(Json(usecases::tag_usage(&*db, &tag)?.into()))
+}
+
+#[post("/admin/tags/rename", format = "application/json", data = "<renaming>")]
+fn post_rename_tag(
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    auth: Auth,
+    renaming: limits::LimitedJson<json::TagRenaming>,
+) -> Result<json::RenamedTag> {
+    let admin = auth.user_with_min_role(&*connections.shared()?, Role::Admin)?;
+    let json::TagRenaming { from, to } = renaming.into_inner();
+    let renamed_by = Email::from(admin.email);
+    let renamed = flows::rename_tag(&connections, &mut search_engine, &from, &to, &renamed_by)?;
+    Ok(Json(renamed.into()))
+}
+
+#[post("/admin/users/merge", format = "application/json", data = "<merge>")]
+fn post_merge_users(
+    connections: sqlite::Connections,
+    auth: Auth,
+    merge: limits::LimitedJson<json::UserMerge>,
+) -> Result<json::MergedUser> {
+    auth.user_with_min_role(&*connections.shared()?, Role::Admin)?;
+    let json::UserMerge { from, into } = merge.into_inner();
+    let merged = flows::merge_users(&connections, &from, &into)?;
+    Ok(Json(merged.into()))
+}
+
+#[post(
+    "/admin/announcements",
+    format = "application/json",
+    data = "<announcement>"
+)]
+fn post_announcement(
+    connections: sqlite::Connections,
+    auth: Auth,
+    announcement: limits::LimitedJson<json::Announcement>,
+) -> Result<json::QueuedAnnouncement> {
+    let db = connections.exclusive()?;
+    auth.user_with_min_role(&*db, Role::Admin)?;
+    let json::Announcement { subject, body } = announcement.into_inner();
+    let recipients = usecases::queue_announcement(&*db, &subject, &body)?;
+    Ok(Json(json::QueuedAnnouncement { recipients }))
+}
+
+#[get("/admin/data-quality")]
+fn get_data_quality_report(
+    connections: sqlite::Connections,
+    auth: Auth,
+) -> Result<Vec<json::RegionQualityIssues>> {
+    let db = connections.shared()?;
+    auth.user_with_min_role(&*db, Role::Admin)?;
+    let report = usecases::data_quality_report(&*db, TimestampMs::now())?;
+    Ok(Json(report.into_iter().map(Into::into).collect()))
+}
+
+#[get("/admin/metrics")]
+fn get_metrics(
+    connections: sqlite::Connections,
+    auth: Auth,
+    search_engine: tantivy::SearchEngine,
+) -> Result<json::ServerMetrics> {
+    auth.user_with_min_role(&*connections.shared()?, Role::Admin)?;
+    let index = search_engine.metrics();
+    Ok(Json(json::ServerMetrics {
+        search_index: json::SearchIndexMetrics {
+            commits: index.commits,
+            last_commit_duration_ms: index.last_commit_duration.as_millis() as u64,
+            max_commit_duration_ms: index.max_commit_duration.as_millis() as u64,
+            segments: index.segments,
+            pending_docs: index.pending_docs,
+            merges_behind: index.merges_behind(),
+        },
+    }))
 }
 
 #[get("/categories")]
-fn get_categories(connections: sqlite::Connections) -> Result<Vec<json::Category>> {
+fn get_categories(
+    connections: sqlite::Connections,
+) -> result::Result<Cached<Json<Vec<json::Category>>>, AppError> {
     let categories = connections
         .shared()?
         .all_categories()?
         .into_iter()
         .map(Into::into)
         .collect();
-    Ok(Json(categories))
+    Ok(Cached(Json(categories)))
 }
 
 #[get("/categories/<ids>")]
 fn get_category(connections: sqlite::Connections, ids: String) -> Result<Vec<json::Category>> {
-    // TODO: Only lookup and return a single entity
-    // TODO: Add a new method for searching multiple ids
     let uids = util::split_ids(&ids);
     if uids.is_empty() {
         return Ok(Json(vec![]));
     }
-    let categories = connections
-        .shared()?
-        .all_categories()?
-        .into_iter()
-        .filter(|c| uids.iter().any(|id| c.id.as_str() == *id))
-        .map(Into::into)
-        .collect();
-    Ok(Json(categories))
+    let categories = connections.shared()?.get_categories_by_ids(&uids)?;
+    Ok(Json(categories.into_iter().map(Into::into).collect()))
 }
 
-#[get("/export/entries.csv?<query..>")]
+#[get("/export/entries.csv?<as_of>&<query..>")]
 fn entries_csv_export(
     connections: sqlite::Connections,
     search_engine: tantivy::SearchEngine,
     auth: Auth,
+    cfg: State<Cfg>,
+    as_of: Option<i64>, // in seconds
     query: Form<search::SearchQuery>,
-) -> result::Result<Content<String>, AppError> {
+) -> LimitedResult<Content<String>> {
     let db = connections.shared()?;
 
     let moderated_tags = match auth.organization(&*db) {
@@ -380,18 +663,58 @@ fn entries_csv_export(
 
     let user = auth.user_with_min_role(&*db, Role::Scout)?;
 
-    let (req, limit) = search::parse_search_query(&query)?;
-    let limit = if let Some(limit) = limit {
-        // Limited
-        limit
-    } else {
-        // Unlimited
-        db.count_places()? + 100
+    let (req, limit) = search::parse_search_query(&query, None)?;
+    let limit = cfg.result_limits.export.effective(limit)?;
+
+    let all_categories: Vec<_> = db.all_categories()?;
+    let export_place = |mut place: Place| {
+        let (tags, categories) = Category::split_from_tags(place.tags);
+        place.tags = tags;
+        let categories = all_categories
+            .iter()
+            .filter(|c1| categories.iter().any(|c2| c1.id == c2.id))
+            .cloned()
+            .collect::<Vec<Category>>();
+        let place = usecases::export_place(
+            place,
+            user.role,
+            moderated_tags
+                .iter()
+                .map(|moderated_tag| moderated_tag.label.as_str()),
+        );
+        (place, categories)
     };
-
-    let entries_categories_and_ratings = {
-        let all_categories: Vec<_> = db.all_categories()?;
-        usecases::search(&*db, &search_engine, req, limit)?
+    let entries_categories_and_ratings = if let Some(as_of) = as_of {
+        // The dataset as it has existed at the given time
+        use ofdb_core::rating::Rated;
+        let as_of_seconds = Timestamp::from_seconds(as_of);
+        let as_of = TimestampMs::from_seconds(as_of);
+        let mut results = vec![];
+        for (place, _) in usecases::snapshot_places(&*db, as_of, &req, limit)? {
+            let ratings: Vec<_> = db
+                .load_ratings_of_place(place.id.as_ref())?
+                .into_iter()
+                .filter(|r| {
+                    r.created_at <= as_of_seconds
+                        && r.archived_at.map(|at| at > as_of_seconds).unwrap_or(true)
+                })
+                .collect();
+            let avg_rating = place.avg_ratings(&ratings).total();
+            let mut rating_counts = RatingCounts::default();
+            for rating in ratings {
+                rating_counts.add_ratings(rating.context, 1);
+                rating_counts.comments += db
+                    .load_comments_of_rating(rating.id.as_ref())?
+                    .into_iter()
+                    .filter(|c| c.created_at <= as_of_seconds)
+                    .count() as u32;
+            }
+            let (place, categories) = export_place(place);
+            results.push((place, categories, avg_rating, rating_counts));
+        }
+        results
+    } else {
+        let places_categories_and_ratings = usecases::search(&*db, &search_engine, req, limit)?
             .0
             .into_iter()
             .filter_map(|indexed_entry| {
@@ -400,27 +723,29 @@ fn entries_csv_export(
                     ref ratings,
                     ..
                 } = indexed_entry;
-                if let Ok((mut place, _)) = db.get_place(id) {
-                    let (tags, categories) = Category::split_from_tags(place.tags);
-                    place.tags = tags;
-                    let categories = all_categories
-                        .iter()
-                        .filter(|c1| categories.iter().any(|c2| c1.id == c2.id))
-                        .cloned()
-                        .collect::<Vec<Category>>();
-                    let place = usecases::export_place(
-                        place,
-                        user.role,
-                        moderated_tags
-                            .iter()
-                            .map(|moderated_tag| moderated_tag.label.as_str()),
-                    );
+                if let Ok((place, _)) = db.get_place_by_id(id) {
+                    let (place, categories) = export_place(place);
                     Some((place, categories, ratings.total()))
                 } else {
                     None
                 }
             })
-            .collect::<Vec<_>>()
+            .collect::<Vec<_>>();
+        let place_ids: Vec<_> = places_categories_and_ratings
+            .iter()
+            .map(|(place, _, _)| place.id.as_str())
+            .collect();
+        let mut rating_counts: HashMap<_, _> = db
+            .count_ratings_and_comments_of_places(&place_ids)?
+            .into_iter()
+            .collect();
+        places_categories_and_ratings
+            .into_iter()
+            .map(|(place, categories, avg_rating)| {
+                let counts = rating_counts.remove(place.id.as_str()).unwrap_or_default();
+                (place, categories, avg_rating, counts)
+            })
+            .collect()
     };
     // Release the database connection asap
     drop(db);
@@ -439,27 +764,153 @@ fn entries_csv_export(
     wtr.flush()?;
     let data = String::from_utf8(wtr.into_inner()?)?;
 
+    Ok(Limited {
+        body: Content(ContentType::CSV, data),
+        limit,
+    })
+}
+
+#[get("/export/ratings.csv?<bbox>")]
+fn ratings_csv_export(
+    connections: sqlite::Connections,
+    search_engine: tantivy::SearchEngine,
+    auth: Auth,
+    bbox: String,
+) -> result::Result<Content<String>, AppError> {
+    let db = connections.shared()?;
+    if auth.organization(&*db).is_err() {
+        auth.user_with_min_role(&*db, Role::Scout)?;
+    }
+    let bbox = bbox
+        .parse::<geo::MapBbox>()
+        .map_err(|_| Error::Parameter(ParameterError::Bbox))?;
+    let places_with_ratings = usecases::export_ratings(&*db, &search_engine, bbox)?;
+    // Release the database connection asap
+    drop(db);
+
+    let buf: Vec<u8> = vec![];
+    let mut wtr = csv::Writer::from_writer(buf);
+    for (place, ratings) in places_with_ratings {
+        for (rating, comments) in ratings {
+            wtr.serialize(adapters::csv::RatingRecord::from((
+                &place, rating, comments,
+            )))?;
+        }
+    }
+    wtr.flush()?;
+    let data = String::from_utf8(wtr.into_inner()?)?;
+
     Ok(Content(ContentType::CSV, data))
 }
 
+fn parameter_error_status(err: &ParameterError) -> Status {
+    match *err {
+        ParameterError::Credentials | ParameterError::Unauthorized => Status::Unauthorized,
+        ParameterError::UserExists => <Status>::new(400, "UserExists"),
+        ParameterError::EmailDomainNotAllowed => <Status>::new(400, "EmailDomainNotAllowed"),
+        ParameterError::EmailNotConfirmed => <Status>::new(403, "EmailNotConfirmed"),
+        ParameterError::UserDeactivated => <Status>::new(403, "UserDeactivated"),
+        ParameterError::Forbidden | ParameterError::ModeratedTag => Status::Forbidden,
+        ParameterError::UploadQuotaExceeded => Status::TooManyRequests,
+        _ => Status::BadRequest,
+    }
+}
+
+/// Reports the outcome of a validation together with the
+/// errors that would have been returned by storing it.
+fn validation(
+    tags: Vec<String>,
+    urls: [Option<&Url>; 3],
+    errors: Vec<Error>,
+) -> result::Result<json::Validation, AppError> {
+    let [homepage, image_url, image_link_url] = urls;
+    let mut validation = json::Validation {
+        tags,
+        homepage: homepage.map(ToString::to_string),
+        image_url: image_url.map(ToString::to_string),
+        image_link_url: image_link_url.map(ToString::to_string),
+        errors: Vec::with_capacity(errors.len()),
+    };
+    for err in errors {
+        match err {
+            Error::Parameter(err) => validation.errors.push(json::Error {
+                http_status: parameter_error_status(&err).code,
+                message: err.to_string(),
+            }),
+            err => return Err(err.into()),
+        }
+    }
+    Ok(validation)
+}
+
+impl<'r, T: serde::Serialize> Responder<'r> for Paginated<T> {
+    fn respond_to(self, req: &rocket::Request) -> result::Result<Response<'r>, Status> {
+        let mut res = self.items.respond_to(req)?;
+        res.set_raw_header(TOTAL_COUNT_HEADER, self.total_count.to_string());
+        Ok(res)
+    }
+}
+
+impl<'r, T: Responder<'r>> Responder<'r> for Limited<T> {
+    fn respond_to(self, req: &rocket::Request) -> result::Result<Response<'r>, Status> {
+        let mut res = self.body.respond_to(req)?;
+        res.set_raw_header(RESULT_LIMIT_HEADER, self.limit.to_string());
+        Ok(res)
+    }
+}
+
+fn content_etag(content: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    format!("\"{:016x}\"", hasher.finish())
+}
+
+impl<'r, T: Responder<'r>> Responder<'r> for Cached<T> {
+    fn respond_to(self, req: &rocket::Request) -> result::Result<Response<'r>, Status> {
+        let mut res = self.0.respond_to(req)?;
+        let body = res.body_bytes().unwrap_or_default();
+        let etag = content_etag(&body);
+        let not_modified = req
+            .headers()
+            .get("If-None-Match")
+            .flat_map(|tags| tags.split(','))
+            .any(|tag| {
+                let tag = tag.trim();
+                tag == "*" || tag == etag || tag.trim_start_matches("W/") == etag
+            });
+        if not_modified {
+            res.set_status(Status::NotModified);
+        } else {
+            res.set_sized_body(Cursor::new(body));
+        }
+        res.set_raw_header("ETag", etag);
+        res.set_raw_header("Cache-Control", CACHE_CONTROL);
+        Ok(res)
+    }
+}
+
 impl<'r> Responder<'r> for AppError {
-    fn respond_to(self, _: &rocket::Request) -> result::Result<Response<'r>, Status> {
+    fn respond_to(self, req: &rocket::Request) -> result::Result<Response<'r>, Status> {
+        if let AppError::RevisionConflict {
+            current_revision,
+            last_change,
+        } = self
+        {
+            let status = Status::Conflict;
+            let conflict = json::RevisionConflict {
+                http_status: status.code,
+                message: status.reason.to_string(),
+                current_version: current_revision.into(),
+                last_change: last_change.into(),
+            };
+            let mut response = Json(conflict).respond_to(req)?;
+            response.set_status(status);
+            return Ok(response);
+        }
         if let AppError::Business(ref err) = self {
             match *err {
                 Error::Parameter(ref err) => {
-                    return Err(match *err {
-                        ParameterError::Credentials | ParameterError::Unauthorized => {
-                            Status::Unauthorized
-                        }
-                        ParameterError::UserExists => <Status>::new(400, "UserExists"),
-                        ParameterError::EmailNotConfirmed => {
-                            <Status>::new(403, "EmailNotConfirmed")
-                        }
-                        ParameterError::Forbidden | ParameterError::ModeratedTag => {
-                            Status::Forbidden
-                        }
-                        _ => Status::BadRequest,
-                    });
+                    return Err(parameter_error_status(err));
                 }
                 Error::Repo(RepoError::NotFound) => {
                     return Err(Status::NotFound);
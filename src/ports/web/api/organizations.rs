@@ -0,0 +1,317 @@
+use super::*;
+use crate::infrastructure::{cfg::Cfg, GEO_CODING_GW};
+
+#[put(
+    "/organizations/tags/<tag>/policy",
+    format = "application/json",
+    data = "<policy>"
+)]
+pub fn put_tag_moderation_policy(
+    db: sqlite::Connections,
+    auth: Auth,
+    tag: String,
+    policy: limits::LimitedJson<json::TagModerationPolicy>,
+) -> Result<()> {
+    let org = auth.organization(&*db.shared()?)?;
+    usecases::set_tag_moderation_policy(&*db.exclusive()?, &org, &tag, policy.into_inner().into())?;
+    Ok(Json(()))
+}
+
+#[post(
+    "/organizations/tags/<tag>/transfer",
+    format = "application/json",
+    data = "<owner>"
+)]
+pub fn post_tag_transfer(
+    db: sqlite::Connections,
+    auth: Auth,
+    tag: String,
+    owner: limits::LimitedJson<json::TagOwner>,
+) -> Result<()> {
+    let org = auth.organization(&*db.shared()?)?;
+    let new_owner_id = Id::from(owner.into_inner().org_id);
+    flows::transfer_moderated_tag(&db, &org, &tag, &new_owner_id)?;
+    Ok(Json(()))
+}
+
+#[post(
+    "/organizations/tags/<tag>/owners",
+    format = "application/json",
+    data = "<owner>"
+)]
+pub fn post_tag_owner(
+    db: sqlite::Connections,
+    auth: Auth,
+    tag: String,
+    owner: limits::LimitedJson<json::TagOwner>,
+) -> Result<()> {
+    let org = auth.organization(&*db.shared()?)?;
+    let co_owner_id = Id::from(owner.into_inner().org_id);
+    flows::share_moderated_tag(&db, &org, &tag, &co_owner_id)?;
+    Ok(Json(()))
+}
+
+#[get("/organizations/tokens", format = "application/json")]
+pub fn get_org_api_tokens(
+    db: sqlite::Connections,
+    auth: Auth,
+) -> Result<Vec<json::OrganizationApiToken>> {
+    let db = db.shared()?;
+    let org = auth.organization(&*db)?;
+    let tokens = db.org_api_tokens(&org.id)?;
+    Ok(Json(tokens.into_iter().map(Into::into).collect()))
+}
+
+#[post("/organizations/tokens", format = "application/json", data = "<token>")]
+pub fn post_org_api_token(
+    db: sqlite::Connections,
+    auth: Auth,
+    token: limits::LimitedJson<json::NewOrganizationApiToken>,
+) -> Result<json::OrganizationApiToken> {
+    let org = auth.organization(&*db.shared()?)?;
+    create_org_api_token(&db, &org.id, token.into_inner())
+}
+
+#[delete("/organizations/tokens/<id>")]
+pub fn delete_org_api_token(db: sqlite::Connections, auth: Auth, id: String) -> Result<()> {
+    let org = auth.organization(&*db.shared()?)?;
+    usecases::revoke_organization_api_token(&mut *db.exclusive()?, &org.id, &id)?;
+    Ok(Json(()))
+}
+
+#[get("/admin/organizations/<org_id>/tokens", format = "application/json")]
+pub fn get_org_api_tokens_as_admin(
+    db: sqlite::Connections,
+    auth: Auth,
+    org_id: String,
+) -> Result<Vec<json::OrganizationApiToken>> {
+    let db = db.shared()?;
+    auth.user_with_min_role(&*db, Role::Admin)?;
+    let org = db.get_org_by_id(&org_id.into())?;
+    let tokens = db.org_api_tokens(&org.id)?;
+    Ok(Json(tokens.into_iter().map(Into::into).collect()))
+}
+
+#[post(
+    "/admin/organizations/<org_id>/tokens",
+    format = "application/json",
+    data = "<token>"
+)]
+pub fn post_org_api_token_as_admin(
+    db: sqlite::Connections,
+    auth: Auth,
+    org_id: String,
+    token: limits::LimitedJson<json::NewOrganizationApiToken>,
+) -> Result<json::OrganizationApiToken> {
+    auth.user_with_min_role(&*db.shared()?, Role::Admin)?;
+    create_org_api_token(&db, &org_id.into(), token.into_inner())
+}
+
+#[delete("/admin/organizations/<org_id>/tokens/<id>")]
+pub fn delete_org_api_token_as_admin(
+    db: sqlite::Connections,
+    auth: Auth,
+    org_id: String,
+    id: String,
+) -> Result<()> {
+    auth.user_with_min_role(&*db.shared()?, Role::Admin)?;
+    usecases::revoke_organization_api_token(&mut *db.exclusive()?, &org_id.into(), &id)?;
+    Ok(Json(()))
+}
+
+fn create_org_api_token(
+    db: &sqlite::Connections,
+    org_id: &Id,
+    token: json::NewOrganizationApiToken,
+) -> Result<json::OrganizationApiToken> {
+    let json::NewOrganizationApiToken { label, expires_at } = token;
+    let (token, bearer_token) = usecases::create_organization_api_token(
+        &*db.exclusive()?,
+        org_id,
+        label,
+        expires_at.map(Timestamp::from_seconds),
+        Timestamp::now(),
+    )?;
+    Ok(Json(json::OrganizationApiToken {
+        token: Some(bearer_token),
+        ..token.into()
+    }))
+}
+
+#[get("/org/usage")]
+pub fn get_org_usage(
+    db: sqlite::Connections,
+    auth: Auth,
+    quotas: State<limits::UploadQuotas>,
+    cfg: State<Cfg>,
+) -> Result<json::OrganizationUsage> {
+    let db = db.shared()?;
+    let org = auth.organization(&*db)?;
+    let daily_quotas = limits::daily_quotas(&*db, &org.id, &cfg)?;
+    let uploads = quotas.uploads_today(&org.id);
+    let quota_usage = |upload, quota| json::QuotaUsage {
+        used: uploads.count(upload),
+        quota,
+    };
+    Ok(Json(json::OrganizationUsage {
+        events: quota_usage(limits::Upload::Event, daily_quotas.events),
+        places: quota_usage(limits::Upload::Place, daily_quotas.places),
+    }))
+}
+
+#[get("/admin/organizations/<org_id>/quotas", format = "application/json")]
+pub fn get_org_daily_quotas_as_admin(
+    db: sqlite::Connections,
+    auth: Auth,
+    org_id: String,
+) -> Result<json::DailyQuotas> {
+    let db = db.shared()?;
+    auth.user_with_min_role(&*db, Role::Admin)?;
+    let quotas = db.get_org_daily_quotas(&org_id.into())?;
+    Ok(Json(quotas.into()))
+}
+
+#[put(
+    "/admin/organizations/<org_id>/quotas",
+    format = "application/json",
+    data = "<quotas>"
+)]
+pub fn put_org_daily_quotas_as_admin(
+    db: sqlite::Connections,
+    auth: Auth,
+    org_id: String,
+    quotas: limits::LimitedJson<json::DailyQuotas>,
+) -> Result<()> {
+    auth.user_with_min_role(&*db.shared()?, Role::Admin)?;
+    let quotas = quotas.into_inner().into();
+    db.exclusive()?
+        .set_org_daily_quotas(&org_id.into(), &quotas)?;
+    Ok(Json(()))
+}
+
+#[derive(FromForm, Clone)]
+pub struct OrgSearchQuery {
+    bbox: Option<String>,
+    text: Option<String>,
+    limit: Option<usize>,
+}
+
+#[get("/org/search?<query..>")]
+pub fn get_org_search(
+    connections: sqlite::Connections,
+    search_engine: tantivy::SearchEngine,
+    auth: Auth,
+    cfg: State<Cfg>,
+    query: Form<OrgSearchQuery>,
+) -> LimitedResult<Json<json::OrgSearchResponse>> {
+    let OrgSearchQuery { bbox, text, limit } = query.into_inner();
+    let bbox = bbox
+        .as_deref()
+        .map(|bbox| {
+            bbox.parse::<geo::MapBbox>()
+                .map_err(|_| Error::Parameter(ParameterError::Bbox))
+        })
+        .transpose()?;
+    let limit = cfg.result_limits.search.effective(limit)?;
+    let db = connections.shared()?;
+    let org = auth.organization(&*db)?;
+    let req = usecases::OrgSearchRequest {
+        bbox,
+        text: text.as_deref(),
+    };
+    let usecases::OrgSearchResults { places, events } =
+        usecases::search_org(&*db, &search_engine, &search_engine, &org, req, limit)?;
+    // Release the database connection asap
+    drop(db);
+    let places = places
+        .into_iter()
+        .map(|(place, pending_clearance)| json::OrgPlaceSearchResult {
+            place: place.into(),
+            pending_clearance: pending_clearance.map(Into::into),
+        })
+        .collect();
+    let events = events.into_iter().map(Into::into).collect();
+    Ok(Limited {
+        body: Json(json::OrgSearchResponse { places, events }),
+        limit,
+    })
+}
+
+#[post("/org/places/compare", format = "application/json", data = "<places>")]
+pub fn post_compare_places(
+    db: sqlite::Connections,
+    search_engine: tantivy::SearchEngine,
+    auth: Auth,
+    places: limits::LimitedJson<Vec<json::ExternalPlace>>,
+) -> Result<Vec<Vec<json::PlaceMatch>>> {
+    let db = db.shared()?;
+    auth.organization(&*db)?;
+    let places: Vec<usecases::ExternalPlace> =
+        places.into_inner().into_iter().map(Into::into).collect();
+    let matches = usecases::compare_places(&*db, &search_engine, &places)?
+        .into_iter()
+        .map(|matches| matches.into_iter().map(Into::into).collect())
+        .collect();
+    Ok(Json(matches))
+}
+
+#[post("/org/places/enrich", format = "application/json", data = "<ids>")]
+pub fn post_enrich_places(
+    db: sqlite::Connections,
+    search_engine: tantivy::SearchEngine,
+    auth: Auth,
+    ids: limits::LimitedJson<Vec<String>>,
+) -> Result<Vec<json::EnrichedPlace>> {
+    let places = {
+        let db = db.shared()?;
+        auth.organization(&*db)?;
+        let ids = ids.into_inner();
+        let ids: Vec<_> = ids.iter().map(String::as_str).collect();
+        usecases::load_places_to_enrich(&*db, &ids)?
+    };
+    // The connection is released before requesting the geocoding provider
+    let enriched_places = usecases::enrich_places(&search_engine, &*GEO_CODING_GW, places)?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+    Ok(Json(enriched_places))
+}
+
+#[post("/organizations/<id>/subscription")]
+pub fn subscribe_to_org(
+    db: sqlite::Connections,
+    notify: Notify,
+    auth: Auth,
+    id: String,
+) -> Result<()> {
+    let email = auth.account_email()?;
+    let consent_request = {
+        let db = db.exclusive()?;
+        usecases::subscribe_to_org(&*db, email.to_string(), id.into())?;
+        usecases::request_notification_consent(&*db, email)?
+    };
+    if let Some(email_nonce) = consent_request {
+        notify.subscription_consent_requested(&email_nonce);
+    }
+    Ok(Json(()))
+}
+
+#[delete("/organizations/<id>/subscription")]
+pub fn unsubscribe_from_org(db: sqlite::Connections, auth: Auth, id: String) -> Result<()> {
+    let email = auth.account_email()?;
+    usecases::unsubscribe_from_org(&*db.exclusive()?, email, &id.into())?;
+    Ok(Json(()))
+}
+
+#[get("/organization-subscriptions")]
+pub fn get_org_subscriptions(
+    db: sqlite::Connections,
+    account: Account,
+) -> Result<Vec<json::OrganizationSubscription>> {
+    let email = account.email();
+    let subscriptions = usecases::get_org_subscriptions(&*db.shared()?, &email)?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+    Ok(Json(subscriptions))
+}
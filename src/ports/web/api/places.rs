@@ -1,4 +1,6 @@
 use super::*;
+use crate::infrastructure::cfg::Cfg;
+use ofdb_gateways::overpass::Overpass;
 
 #[get("/places/clearance/count")]
 pub fn count_pending_clearances(db: sqlite::Connections, auth: Auth) -> Result<json::ResultCount> {
@@ -31,7 +33,7 @@ pub fn list_pending_clearances(
 pub fn update_pending_clearances(
     db: sqlite::Connections,
     auth: Auth,
-    clearances: Json<Vec<json::ClearanceForPlace>>,
+    clearances: limits::LimitedJson<Vec<json::ClearanceForPlace>>,
 ) -> Result<json::ResultCount> {
     let clearances: Vec<_> = clearances
         .into_inner()
@@ -42,9 +44,188 @@ pub fn update_pending_clearances(
     let count = usecases::clearance::place::update_pending_clearances(
         &*db.exclusive()?,
         &org,
+        auth.organization_api_token(),
         &clearances,
     )?;
     Ok(Json(json::ResultCount {
         count: count as u64,
     }))
 }
+
+#[get("/places/clearance/log?<place_id>&<offset>&<limit>")]
+pub fn list_clearance_log(
+    db: sqlite::Connections,
+    auth: Auth,
+    place_id: Option<String>,
+    offset: Option<u64>,
+    limit: Option<u64>,
+) -> Result<Vec<json::ClearanceLogEntry>> {
+    let pagination = Pagination { offset, limit };
+    let db = db.shared()?;
+    let org = auth.organization(&*db)?;
+    let place_id = place_id.map(Id::from);
+    let entries = usecases::clearance::place::list_clearance_log(
+        &*db,
+        Some(&org),
+        place_id.as_ref(),
+        &pagination,
+    )?;
+    Ok(Json(entries.into_iter().map(Into::into).collect()))
+}
+
+#[get("/admin/clearance/log?<org_id>&<place_id>&<offset>&<limit>")]
+pub fn list_clearance_log_as_admin(
+    db: sqlite::Connections,
+    auth: Auth,
+    org_id: Option<String>,
+    place_id: Option<String>,
+    offset: Option<u64>,
+    limit: Option<u64>,
+) -> Result<Vec<json::ClearanceLogEntry>> {
+    let pagination = Pagination { offset, limit };
+    let db = db.shared()?;
+    auth.user_with_min_role(&*db, Role::Admin)?;
+    let org = org_id
+        .map(|org_id| db.get_org_by_id(&org_id.into()))
+        .transpose()?;
+    let place_id = place_id.map(Id::from);
+    let entries = usecases::clearance::place::list_clearance_log(
+        &*db,
+        org.as_ref(),
+        place_id.as_ref(),
+        &pagination,
+    )?;
+    Ok(Json(entries.into_iter().map(Into::into).collect()))
+}
+
+#[get("/places/<id>/stats")]
+pub fn get_place_stats(
+    db: sqlite::Connections,
+    id: String,
+    cfg: State<Cfg>,
+) -> Result<json::PlaceStats> {
+    let stats = usecases::place_stats(&*db.shared()?, &id, cfg.count_views)?;
+    Ok(Json(stats.into()))
+}
+
+#[post("/places/<id>/views")]
+pub fn post_place_view(db: sqlite::Connections, id: String, cfg: State<Cfg>) -> StatusResult {
+    if cfg.count_views {
+        usecases::count_place_view(&*db.exclusive()?, &id)?;
+    }
+    Ok(Status::NoContent)
+}
+
+#[allow(clippy::too_many_arguments)]
+#[post("/places/import/osm?<dry_run>", data = "<body>")]
+pub fn post_osm_import(
+    db: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    auth: Auth,
+    quotas: State<limits::UploadQuotas>,
+    dry_run: Option<bool>,
+    body: limits::ImportJson<json::OsmImport>,
+    cfg: State<Cfg>,
+) -> Result<json::OsmImportReport> {
+    let (org, daily_quotas) = {
+        let db = db.shared()?;
+        let org = auth.organization(&*db)?;
+        let daily_quotas = limits::daily_quotas(&*db, &org.id, &cfg)?;
+        (org, daily_quotas)
+    };
+    let dry_run = dry_run.unwrap_or(false);
+    let reservation = if dry_run {
+        None
+    } else {
+        Some(quotas.reserve_remaining(&org.id, limits::Upload::Place, daily_quotas.places)?)
+    };
+    let max_imports = reservation
+        .as_ref()
+        .and_then(limits::Reservation::limit)
+        .map(|limit| limit as usize);
+    let json::OsmImport { query, mapping } = body.into_inner();
+    let osm = Overpass::new(cfg.overpass_api_url.clone());
+    let report = flows::import_osm_nodes(
+        &db,
+        &mut search_engine,
+        &osm,
+        &query,
+        &mapping.into(),
+        Some(&org),
+        &cfg,
+        max_imports,
+        dry_run,
+    )?;
+    if let Some(reservation) = reservation {
+        reservation.commit_count(report.imported as u32);
+    }
+    Ok(Json(report.into()))
+}
+
+#[get("/places/osm/proposals?<offset>&<limit>")]
+pub fn get_osm_node_proposals(
+    db: sqlite::Connections,
+    auth: Auth,
+    offset: Option<u64>,
+    limit: Option<u64>,
+) -> Result<Vec<json::OsmNodeProposal>> {
+    let db = db.shared()?;
+    auth.user_with_min_role(&*db, Role::Scout)?;
+    let pagination = Pagination { offset, limit };
+    let proposals = db.list_pending_osm_node_proposals(&pagination)?;
+    Ok(Json(proposals.into_iter().map(Into::into).collect()))
+}
+
+#[post("/places/osm/proposals/<id>/accept")]
+pub fn post_osm_node_proposal_accept(
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    notify: Notify,
+    auth: Auth,
+    id: String,
+    cfg: State<Cfg>,
+) -> StatusResult {
+    let scout = auth.user_with_min_role(&*connections.shared()?, Role::Scout)?;
+    flows::accept_osm_node_proposal(
+        &connections,
+        &mut search_engine,
+        &notify,
+        &scout.email,
+        &id,
+        &cfg,
+    )?;
+    Ok(Status::NoContent)
+}
+
+#[post("/places/osm/proposals/<id>/reject")]
+pub fn post_osm_node_proposal_reject(
+    connections: sqlite::Connections,
+    auth: Auth,
+    id: String,
+) -> StatusResult {
+    let scout = auth.user_with_min_role(&*connections.shared()?, Role::Scout)?;
+    flows::reject_osm_node_proposal(&connections, &scout.email, &id)?;
+    Ok(Status::NoContent)
+}
+
+#[post("/places/<id>/confirm-still-valid", data = "<confirmation>")]
+pub fn post_place_confirm_still_valid(
+    db: sqlite::Connections,
+    id: String,
+    confirmation: limits::LimitedJson<json::ConfirmPlaceStillValid>,
+) -> StatusResult {
+    let json::ConfirmPlaceStillValid { token } = confirmation.into_inner();
+    usecases::confirm_place_still_valid(&*db.exclusive()?, &id, &token)?;
+    Ok(Status::NoContent)
+}
+
+#[get("/places/unconfirmed")]
+pub fn get_unanswered_confirmation_requests(
+    db: sqlite::Connections,
+    auth: Auth,
+) -> Result<Vec<json::UnansweredConfirmationRequest>> {
+    let db = db.shared()?;
+    auth.user_with_min_role(&*db, Role::Scout)?;
+    let requests = db.load_flagged_place_confirmation_requests()?;
+    Ok(Json(requests.into_iter().map(Into::into).collect()))
+}
@@ -1,15 +1,92 @@
 use super::*;
 
 use crate::{core::util, infrastructure::flows::prelude as flows};
+use rocket::response::status::Custom;
 
 #[post("/ratings", format = "application/json", data = "<data>")]
 pub fn post_rating(
     connections: sqlite::Connections,
     mut search_engine: tantivy::SearchEngine,
-    data: Json<usecases::NewPlaceRating>,
-) -> Result<()> {
-    let _ = flows::create_rating(&connections, &mut search_engine, data.into_inner())?;
-    Ok(Json(()))
+    notify: Notify,
+    auth: Auth,
+    cfg: State<Cfg>,
+    data: limits::LimitedJson<usecases::NewPlaceRating>,
+) -> result::Result<Custom<Json<json::Rating>>, AppError> {
+    let submitted = flows::submit_rating(
+        &connections,
+        &mut search_engine,
+        &notify,
+        data.into_inner(),
+        auth.account_email().ok(),
+        &cfg.comment_rules,
+    )?;
+    match submitted {
+        flows::SubmittedRating::Published { rating_id, .. } => {
+            let rating = usecases::load_rating_with_comments(&*connections.shared()?, &rating_id)?;
+            Ok(Custom(Status::Ok, Json(rating.into())))
+        }
+        // The rating is not visible until a scout publishes it
+        flows::SubmittedRating::Pending(pending_rating) => {
+            let PendingRating {
+                rating, comment, ..
+            } = pending_rating;
+            Ok(Custom(
+                Status::Accepted,
+                Json((rating, vec![comment]).into()),
+            ))
+        }
+    }
+}
+
+#[get("/ratings/pending?<offset>&<limit>")]
+pub fn get_pending_ratings(
+    db: sqlite::Connections,
+    auth: Auth,
+    offset: Option<u64>,
+    limit: Option<u64>,
+) -> Result<Vec<json::PendingRating>> {
+    let db = db.shared()?;
+    auth.user_with_min_role(&*db, Role::Scout)?;
+    let pagination = Pagination { offset, limit };
+    let pending_ratings = db.list_pending_ratings(&pagination)?;
+    Ok(Json(pending_ratings.into_iter().map(Into::into).collect()))
+}
+
+#[post("/ratings/pending/<ids>/publish")]
+pub fn post_pending_ratings_publish(
+    connections: sqlite::Connections,
+    mut search_engine: tantivy::SearchEngine,
+    notify: Notify,
+    auth: Auth,
+    ids: String,
+) -> Result<json::ResultCount> {
+    let ids = util::split_ids(&ids);
+    if ids.is_empty() {
+        return Err(Error::Parameter(ParameterError::EmptyIdList).into());
+    }
+    auth.user_with_min_role(&*connections.shared()?, Role::Scout)?;
+    let count = flows::publish_pending_ratings(&connections, &mut search_engine, &notify, &ids)?;
+    Ok(Json(json::ResultCount {
+        count: count as u64,
+    }))
+}
+
+#[delete("/ratings/pending/<ids>")]
+pub fn delete_pending_ratings(
+    db: sqlite::Connections,
+    auth: Auth,
+    ids: String,
+) -> Result<json::ResultCount> {
+    let ids = util::split_ids(&ids);
+    if ids.is_empty() {
+        return Err(Error::Parameter(ParameterError::EmptyIdList).into());
+    }
+    let db = db.exclusive()?;
+    auth.user_with_min_role(&*db, Role::Scout)?;
+    let count = db.delete_pending_ratings(&ids)?;
+    Ok(Json(json::ResultCount {
+        count: count as u64,
+    }))
 }
 
 #[get("/ratings/<ids>")]
@@ -24,25 +101,7 @@ pub fn load_rating(db: sqlite::Connections, ids: String) -> Result<Vec<json::Rat
     let ratings_with_comments = usecases::load_ratings_with_comments(&*db.shared()?, &ids)?;
     let result = ratings_with_comments
         .into_iter()
-        .map(|(r, cs)| {
-            let comments = cs
-                .into_iter()
-                .map(|c| json::Comment {
-                    id: c.id.clone().into(),
-                    created: c.created_at.into_seconds(),
-                    text: c.text,
-                })
-                .collect();
-            json::Rating {
-                id: r.id.into(),
-                created: r.created_at.into_seconds(),
-                title: r.title,
-                value: r.value.into(),
-                context: r.context.into(),
-                source: r.source.unwrap_or_default(),
-                comments,
-            }
-        })
+        .map(json::Rating::from)
         .collect();
     Ok(Json(result))
 }
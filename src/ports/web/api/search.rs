@@ -6,18 +6,21 @@ use crate::{
         util::{self, geo},
     },
     infrastructure::{
+        cfg::Cfg,
         db::{sqlite, tantivy},
         error::AppError,
     },
 };
 
-use rocket::{self, request::Form};
+use super::{ClientLocation, Limited, LimitedResult};
+use rocket::{self, request::Form, State};
 use rocket_contrib::json::Json;
 use std::result;
 
 #[derive(FromForm, Clone)]
 pub struct SearchQuery {
-    bbox: String,
+    bbox: Option<String>,
+    area: Option<String>,
     categories: Option<String>,
     ids: Option<String>,
     org_tag: Option<String>,
@@ -25,13 +28,91 @@ pub struct SearchQuery {
     text: Option<String>,
     status: Option<String>,
     limit: Option<usize>,
+    origin: Option<String>,
+    country: Option<String>,
+    region: Option<String>,
+    excluded_categories: Option<String>,
+    excluded_tags: Option<String>,
+    // Alias of excluded_tags
+    exclude_tags: Option<String>,
+    min_avg_rating: Option<f64>,
+    min_rating_count: Option<u32>,
+    max_age_days: Option<u32>,
+    boost_freshness: Option<bool>,
+    sort: Option<String>,
+    fuzzy: Option<bool>,
+    with_previews: Option<bool>,
+    with_facets: Option<bool>,
 }
 
+// Only the most frequent tags are counted
+const MAX_TAG_FACETS: usize = 50;
+
+// Tolerate up to two typos per word in long words
+const FUZZY_SEARCH_FUZZINESS: u8 = 2;
+
+/// Parses a comma-separated list of review status values.
+/// Invalid values are ignored.
+pub fn parse_review_status_list(status: &str) -> Vec<ReviewStatus> {
+    util::split_ids(status)
+        .into_iter()
+        .filter_map(|s| {
+            serde_json::from_str::<json::ReviewStatus>(&format!("\"{}\"", s))
+                .map_err(|e| {
+                    log::warn!("Failed to parse status '{}' from query: {}", s, e);
+                    e
+                })
+                .map(ReviewStatus::from)
+                .ok()
+        })
+        .collect()
+}
+
+/// Parses a GeoJSON polygon (geometry or feature) or an encoded
+/// polyline. Only the outer ring of a GeoJSON polygon is considered.
+fn parse_search_area(area: &str) -> Option<geo::MapPolygon> {
+    match serde_json::from_str::<serde_json::Value>(area) {
+        Ok(geojson) if geojson.is_object() => parse_geojson_polygon(&geojson),
+        _ => geo::MapPolygon::decode_polyline(area).ok(),
+    }
+}
+
+fn parse_geojson_polygon(geojson: &serde_json::Value) -> Option<geo::MapPolygon> {
+    let geometry = match geojson["type"].as_str()? {
+        "Feature" => &geojson["geometry"],
+        _ => geojson,
+    };
+    if geometry["type"].as_str()? != "Polygon" {
+        return None;
+    }
+    let outer_ring = geometry["coordinates"].get(0)?.as_array()?;
+    let vertices = outer_ring
+        .iter()
+        .map(|position| {
+            // Longitude first
+            let lng = position.get(0)?.as_f64()?;
+            let lat = position.get(1)?.as_f64()?;
+            MapPoint::try_from_lat_lng_deg(lat, lng).ok()
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let polygon = geo::MapPolygon::new(vertices);
+    if polygon.is_valid() {
+        Some(polygon)
+    } else {
+        None
+    }
+}
+
+/// Searches without a bounding box are restricted to
+/// the default bounding box if available. An area
+/// replaces the bounding box.
 pub fn parse_search_query(
     query: &'_ SearchQuery,
+    default_bbox: Option<geo::MapBbox>,
 ) -> result::Result<(usecases::SearchRequest<'_>, Option<usize>), AppError> {
     let SearchQuery {
         bbox,
+        area,
         ids,
         categories,
         org_tag,
@@ -39,13 +120,36 @@ pub fn parse_search_query(
         text,
         status,
         limit,
+        country,
+        region,
+        excluded_categories,
+        excluded_tags,
+        exclude_tags,
+        min_avg_rating,
+        min_rating_count,
+        max_age_days,
+        boost_freshness,
+        sort,
+        fuzzy,
+        ..
     } = query;
 
-    let bbox = bbox
-        .parse::<geo::MapBbox>()
-        .map_err(|_| ParameterError::Bbox)
-        .map_err(Error::Parameter)
-        .map_err(AppError::Business)?;
+    let area = area
+        .as_deref()
+        .map(|area| {
+            parse_search_area(area).ok_or(Error::Parameter(ParameterError::InvalidSearchArea))
+        })
+        .transpose()?;
+
+    let bbox = match &area {
+        Some(area) => area.bbox(),
+        None => bbox
+            .as_deref()
+            .map(|bbox| bbox.parse::<geo::MapBbox>().ok())
+            .unwrap_or(default_bbox)
+            .ok_or(Error::Parameter(ParameterError::Bbox))
+            .map_err(AppError::Business)?,
+    };
 
     let ids = ids.as_deref().map(util::split_ids).unwrap_or_default();
 
@@ -62,23 +166,47 @@ pub fn parse_search_query(
 
     let hash_tags = tags.as_deref().map(util::split_ids).unwrap_or_default();
 
+    let excluded_categories = excluded_categories
+        .as_deref()
+        .map(util::split_ids)
+        .unwrap_or_default();
+
+    let excluded_hash_tags = excluded_tags
+        .iter()
+        .chain(exclude_tags.iter())
+        .map(String::as_str)
+        .flat_map(util::split_ids)
+        .collect();
+
     let text = text.as_deref();
 
+    let min_avg_rating = min_avg_rating
+        .map(AvgRatingValue::from)
+        .map(|rating| {
+            if rating.is_valid() {
+                Ok(rating)
+            } else {
+                Err(Error::Parameter(ParameterError::RatingValue))
+            }
+        })
+        .transpose()?;
+
+    // Places that have never been confirmed are excluded
+    let confirmed_since = max_age_days.map(|days| {
+        let max_age = chrono::Duration::days(days.into());
+        TimestampMs::from(chrono::Utc::now() - max_age)
+    });
+
     let status = status
         .as_deref()
-        .map(util::split_ids)
-        .unwrap_or_default()
-        .into_iter()
-        .filter_map(|s| {
-            serde_json::from_str::<json::ReviewStatus>(&format!("\"{}\"", s))
-                .map_err(|e| {
-                    log::warn!("Failed to parse status '{}' from search query: {}", s, e);
-                    e
-                })
-                .map(ReviewStatus::from)
-                .ok()
-        })
-        .collect();
+        .map(parse_review_status_list)
+        .unwrap_or_default();
+
+    let order_by_quality = match sort.as_deref().map(str::trim) {
+        None | Some("") | Some("relevance") => false,
+        Some("quality") => true,
+        Some(_) => return Err(Error::Parameter(ParameterError::InvalidSortOrder).into()),
+    };
 
     Ok((
         usecases::SearchRequest {
@@ -89,6 +217,17 @@ pub fn parse_search_query(
             hash_tags,
             text,
             status,
+            country: country.as_deref().filter(|c| !c.trim().is_empty()),
+            region: region.as_deref().filter(|r| !r.trim().is_empty()),
+            excluded_categories,
+            excluded_hash_tags,
+            min_avg_rating,
+            min_rating_count: *min_rating_count,
+            confirmed_since,
+            boost_freshness: boost_freshness.unwrap_or(false),
+            order_by_quality,
+            fuzziness: fuzzy.filter(|fuzzy| *fuzzy).map(|_| FUZZY_SEARCH_FUZZINESS),
+            area,
         },
         *limit,
     ))
@@ -96,50 +235,167 @@ pub fn parse_search_query(
 
 type Result<T> = result::Result<Json<T>, AppError>;
 
-const DEFAULT_RESULT_LIMIT: usize = 100;
-const MAX_RESULT_LIMIT: usize = 2000;
+fn search_result_with_distance(
+    place: IndexedPlace,
+    origin: Option<MapPoint>,
+) -> json::PlaceSearchResult {
+    let distance = origin
+        .and_then(|origin| MapPoint::distance(origin, place.pos))
+        .map(Distance::to_meters);
+    json::PlaceSearchResult {
+        distance,
+        ..place.into()
+    }
+}
 
 #[get("/search?<query..>")]
-#[allow(clippy::absurd_extreme_comparisons)]
 pub fn get_search(
     connections: sqlite::Connections,
     search_engine: tantivy::SearchEngine,
+    cfg: State<Cfg>,
+    client_location: ClientLocation,
     query: Form<SearchQuery>,
-) -> Result<json::SearchResponse> {
+) -> LimitedResult<Json<json::SearchResponse>> {
     let query = query.into_inner();
-    let (req, limit) = parse_search_query(&query)?;
-
-    let limit = if let Some(limit) = limit {
-        if limit > MAX_RESULT_LIMIT {
-            info!(
-                "Requested limit {} exceeds maximum limit {} for search results",
-                limit, MAX_RESULT_LIMIT
-            );
-            MAX_RESULT_LIMIT
-        } else if limit <= 0 {
-            warn!("Invalid search limit: {}", limit);
-            return Err(AppError::Business(Error::Parameter(
-                ParameterError::InvalidLimit,
-            )));
-        } else {
-            limit
-        }
+    let (req, limit) = parse_search_query(&query, client_location.region())?;
+    let limit = cfg.result_limits.search.effective(limit)?;
+
+    let origin = query
+        .origin
+        .as_deref()
+        .map(|origin| {
+            origin
+                .parse::<MapPoint>()
+                .map_err(|_| Error::Parameter(ParameterError::InvalidPosition))
+        })
+        .transpose()?;
+
+    let facets = if query.with_facets.unwrap_or(false) {
+        let facets = usecases::search_facets(&search_engine, req.clone(), MAX_TAG_FACETS)?;
+        Some(facets.into())
     } else {
-        info!(
-            "No limit requested - Using default limit {} for search results",
-            DEFAULT_RESULT_LIMIT
-        );
-        DEFAULT_RESULT_LIMIT
+        None
     };
 
     let (visible, invisible) =
         usecases::search(&*connections.shared()?, &search_engine, req, limit)?;
 
-    let visible: Vec<json::PlaceSearchResult> = visible.into_iter().map(Into::into).collect();
+    let mut visible: Vec<json::PlaceSearchResult> = visible
+        .into_iter()
+        .map(|place| search_result_with_distance(place, origin))
+        .collect();
+
+    let mut invisible: Vec<json::PlaceSearchResult> = invisible
+        .into_iter()
+        .map(|place| search_result_with_distance(place, origin))
+        .collect();
 
-    let invisible: Vec<json::PlaceSearchResult> = invisible.into_iter().map(Into::into).collect();
+    if query.with_previews.unwrap_or(false) {
+        let ids: Vec<_> = visible
+            .iter()
+            .chain(invisible.iter())
+            .map(|place| place.id.as_str())
+            .collect();
+        let mut previews =
+            usecases::load_homepage_previews_of_places(&*connections.shared()?, &ids)?;
+        for place in visible.iter_mut().chain(invisible.iter_mut()) {
+            place.preview = previews.remove(place.id.as_str()).map(Into::into);
+        }
+    }
 
-    Ok(Json(json::SearchResponse { visible, invisible }))
+    Ok(Limited {
+        body: Json(json::SearchResponse {
+            visible,
+            invisible,
+            facets,
+        }),
+        limit,
+    })
+}
+
+#[derive(FromForm, Clone)]
+pub struct NearbyQuery {
+    lat: Option<f64>,
+    lng: Option<f64>,
+    radius_m: Option<f64>,
+    tags: Option<String>,
+    limit: Option<usize>,
+}
+
+const DEFAULT_NEARBY_RADIUS_METERS: f64 = 1_000.0;
+const MAX_NEARBY_RADIUS_METERS: f64 = 50_000.0;
+
+#[get("/search/nearby?<query..>")]
+pub fn get_search_nearby(
+    search_engine: tantivy::SearchEngine,
+    cfg: State<Cfg>,
+    client_location: ClientLocation,
+    query: Form<NearbyQuery>,
+) -> LimitedResult<Json<Vec<json::NearbyPlace>>> {
+    let NearbyQuery {
+        lat,
+        lng,
+        radius_m,
+        tags,
+        limit,
+    } = query.into_inner();
+    let center = match (lat, lng) {
+        (Some(lat), Some(lng)) => MapPoint::try_from_lat_lng_deg(lat, lng).ok(),
+        // Defaults to the approximate location of the client
+        (None, None) => client_location.pos(),
+        _ => None,
+    }
+    .ok_or(Error::Parameter(ParameterError::InvalidPosition))?;
+    let radius = radius_m.unwrap_or(DEFAULT_NEARBY_RADIUS_METERS);
+    if radius.is_nan() || radius <= 0.0 {
+        return Err(Error::Parameter(ParameterError::InvalidRadius).into());
+    }
+    let radius = Distance::from_meters(radius.min(MAX_NEARBY_RADIUS_METERS));
+    let hash_tags = tags
+        .as_deref()
+        .map(util::split_ids)
+        .unwrap_or_default()
+        .into_iter()
+        .map(ToOwned::to_owned)
+        .collect();
+    let limit = cfg.result_limits.nearby_search.effective(limit)?;
+    let places = usecases::search_nearby(&search_engine, center, radius, hash_tags, limit)?;
+    Ok(Limited {
+        body: Json(places.into_iter().map(Into::into).collect()),
+        limit,
+    })
+}
+
+#[derive(FromForm, Clone)]
+pub struct SuggestQuery {
+    q: String,
+    bbox: Option<String>,
+    limit: Option<usize>,
+}
+
+#[get("/search/suggest?<query..>")]
+pub fn get_search_suggest(
+    search_engine: tantivy::SearchEngine,
+    cfg: State<Cfg>,
+    client_location: ClientLocation,
+    query: Form<SuggestQuery>,
+) -> LimitedResult<Json<Vec<json::SearchSuggestion>>> {
+    let SuggestQuery { q, bbox, limit } = query.into_inner();
+    // Suggestions are not restricted to a region if
+    // the location of the client is unknown
+    let bbox = match bbox {
+        Some(bbox) => Some(
+            bbox.parse::<geo::MapBbox>()
+                .map_err(|_| Error::Parameter(ParameterError::Bbox))?,
+        ),
+        None => client_location.region(),
+    };
+    let limit = cfg.result_limits.suggestions.effective(limit)?;
+    let suggestions = usecases::suggest(&search_engine, &q, bbox, limit)?;
+    Ok(Limited {
+        body: Json(suggestions.into_iter().map(Into::into).collect()),
+        limit,
+    })
 }
 
 #[post("/search/duplicates", data = "<body>")]
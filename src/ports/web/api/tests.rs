@@ -42,7 +42,7 @@ pub mod prelude {
         );
     }
 
-    pub use super::cookie_from_response;
+    pub use super::{cookie_from_response, csrf_token_header};
 }
 
 use self::prelude::*;
@@ -50,9 +50,9 @@ use self::prelude::*;
 #[test]
 fn create_place() {
     let (client, db) = setup();
-    let req = client.post("/entries")
+    let req = client.post("/entries?confirm_position=true")
                     .header(ContentType::JSON)
-                    .body(r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":["x"],"license":"CC0-1.0","tags":[]}"#);
+                    .body(r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":[],"license":"CC0-1.0","tags":[]}"#);
     let mut response = req.dispatch();
     assert_eq!(response.status(), Status::Ok);
     test_json(&response);
@@ -64,6 +64,17 @@ fn create_place() {
     assert_eq!(body_str, format!("\"{}\"", eid));
 }
 
+#[test]
+fn create_place_at_null_island_without_confirmation() {
+    let (client, db) = setup();
+    let req = client.post("/entries")
+                    .header(ContentType::JSON)
+                    .body(r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":[],"license":"CC0-1.0","tags":[]}"#);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    assert!(db.exclusive().unwrap().all_places().unwrap().is_empty());
+}
+
 #[test]
 fn create_place_with_reserved_tag() {
     let (client, db) = setup();
@@ -74,23 +85,572 @@ fn create_place_with_reserved_tag() {
             name: "a".into(),
             moderated_tags: vec!["a".into()],
             api_token: "a".into(),
+            notification_email: None,
+            email_branding: Default::default(),
         })
         .unwrap();
     let cookie = get_captcha_cookie(&client).unwrap();
-    let res = client.post("/entries")
+    let res = client.post("/entries?confirm_position=true")
                     .header(ContentType::JSON)
                     .cookie(cookie)
-                    .body(r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":["x"],"license":"CC0-1.0","tags":["a"]}"#)
+                    .body(r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":[],"license":"CC0-1.0","tags":["a"]}"#)
                     .dispatch();
     assert_eq!(res.status(), Status::Forbidden);
 }
 
+#[test]
+fn validate_place_without_storing_it() {
+    let (client, db) = setup();
+    let mut res = client.post("/entries/validate")
+                    .header(ContentType::JSON)
+                    .body(r##"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":[],"license":"CC0-1.0","tags":["#Bio fair","bio"],"homepage":"example.com"}"##)
+                    .dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    test_json(&res);
+    let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+    let validation: ofdb_boundary::Validation = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(vec!["bio", "fair"], validation.tags);
+    assert_eq!(
+        Some("https://www.example.com/"),
+        validation.homepage.as_deref()
+    );
+    // The position 0/0 needs to be confirmed
+    assert_eq!(1, validation.errors.len());
+    assert_eq!(400, validation.errors[0].http_status);
+
+    let mut res = client.post("/entries/validate?confirm_position=true")
+                    .header(ContentType::JSON)
+                    .body(r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":[],"license":"unknown","tags":["bio"]}"#)
+                    .dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+    let validation: ofdb_boundary::Validation = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(vec!["bio"], validation.tags);
+    assert_eq!(1, validation.errors.len());
+    assert!(db.shared().unwrap().all_places().unwrap().is_empty());
+}
+
+#[test]
+fn create_place_with_unknown_categories() {
+    let (client, db) = setup();
+    let res = client.post("/entries?confirm_position=true")
+                    .header(ContentType::JSON)
+                    .body(r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":["2cd00bebec0c48ba9db761da48678134","x","y","x"],"license":"CC0-1.0","tags":[]}"#)
+                    .dispatch();
+    assert_eq!(res.status(), Status::BadRequest);
+    assert!(db.shared().unwrap().all_places().unwrap().is_empty());
+
+    let mut res = client.post("/entries/validate?confirm_position=true")
+                    .header(ContentType::JSON)
+                    .body(r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":["y","x"],"license":"CC0-1.0","tags":[]}"#)
+                    .dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+    let validation: ofdb_boundary::Validation = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(1, validation.errors.len());
+    assert_eq!("Unknown categories: x, y", validation.errors[0].message);
+
+    let res = client.post("/entries?confirm_position=true")
+                    .header(ContentType::JSON)
+                    .body(r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":["2cd00bebec0c48ba9db761da48678134"],"license":"CC0-1.0","tags":[]}"#)
+                    .dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    let (place, _) = db.shared().unwrap().all_places().unwrap().pop().unwrap();
+    assert_eq!(vec!["non-profit"], place.tags);
+}
+
+#[test]
+fn compare_external_places_with_existing_places() {
+    let (client, db, mut search_engine, _) = setup2();
+    db.exclusive()
+        .unwrap()
+        .create_org(Organization {
+            id: "org".into(),
+            name: "org".into(),
+            moderated_tags: vec![],
+            api_token: "secret".into(),
+            notification_email: None,
+            email_branding: Default::default(),
+        })
+        .unwrap();
+    let place = Place::build()
+        .id("bioladen")
+        .title("Bioladen am Markt")
+        .pos(MapPoint::from_lat_lng_deg(48.7755, 9.1827))
+        .finish();
+    db.exclusive()
+        .unwrap()
+        .create_or_update_place(place.clone())
+        .unwrap();
+    search_engine
+        .add_or_update_place(&place, ReviewStatus::Created, None, &place.avg_ratings(&[]))
+        .unwrap();
+    search_engine.flush_index().unwrap();
+
+    let body = r#"[
+        {"title":"Bioladen Markt","lat":48.7756,"lng":9.1827},
+        {"title":"Fahrradwerkstatt","lat":48.7755,"lng":9.1827}
+    ]"#;
+    let response = client
+        .post("/org/places/compare")
+        .header(ContentType::JSON)
+        .body(body)
+        .dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let mut response = client
+        .post("/org/places/compare")
+        .header(ContentType::JSON)
+        .header(rocket::http::Header::new("Authorization", "Bearer secret"))
+        .body(body)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let matches: Vec<Vec<json::PlaceMatch>> =
+        serde_json::from_str(&response.body_string().unwrap()).unwrap();
+    assert_eq!(2, matches.len());
+    assert_eq!(1, matches[0].len());
+    assert_eq!("bioladen", matches[0][0].id);
+    assert!(matches[0][0].confidence > 0.5);
+    assert!(matches[1].is_empty());
+}
+
+#[test]
+fn enrich_existing_places() {
+    let (client, db, mut search_engine, _) = setup2();
+    db.exclusive()
+        .unwrap()
+        .create_org(Organization {
+            id: "org".into(),
+            name: "org".into(),
+            moderated_tags: vec![],
+            api_token: "secret".into(),
+            notification_email: None,
+            email_branding: Default::default(),
+        })
+        .unwrap();
+    let address = Address {
+        street: Some("Königstraße 1".into()),
+        zip: Some("70173".into()),
+        city: Some("Stuttgart".into()),
+        country: Some("Germany".into()),
+        state: Some("BW".into()),
+    };
+    for (id, tags) in vec![("a", vec!["bio"]), ("b", vec!["bio", "vegan"])] {
+        let mut place = Place::build()
+            .id(id)
+            .title(id)
+            .tags(tags)
+            .pos(MapPoint::from_lat_lng_deg(48.7755, 9.1827))
+            .finish();
+        place.location.address = Some(address.clone());
+        db.exclusive()
+            .unwrap()
+            .create_or_update_place(place.clone())
+            .unwrap();
+        search_engine
+            .add_or_update_place(&place, ReviewStatus::Created, None, &place.avg_ratings(&[]))
+            .unwrap();
+    }
+    search_engine.flush_index().unwrap();
+
+    let response = client
+        .post("/org/places/enrich")
+        .header(ContentType::JSON)
+        .body(r#"["a"]"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let mut response = client
+        .post("/org/places/enrich")
+        .header(ContentType::JSON)
+        .header(rocket::http::Header::new("Authorization", "Bearer secret"))
+        .body(r#"["a"]"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let enriched: Vec<json::EnrichedPlace> =
+        serde_json::from_str(&response.body_string().unwrap()).unwrap();
+    assert_eq!(1, enriched.len());
+    assert_eq!("a", enriched[0].id);
+    assert!(!enriched[0].address_resolved);
+    assert_eq!(Some("BW"), enriched[0].state.as_deref());
+    assert_eq!(vec!["vegan".to_string()], enriched[0].suggested_tags);
+
+    let response = client
+        .post("/org/places/enrich")
+        .header(ContentType::JSON)
+        .header(rocket::http::Header::new("Authorization", "Bearer secret"))
+        .body(r#"["unknown"]"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[test]
+fn search_places_and_events_with_owned_tags() {
+    let (client, db, mut search_engine, notify) = setup2();
+    db.exclusive()
+        .unwrap()
+        .create_org(Organization {
+            id: "org".into(),
+            name: "org".into(),
+            moderated_tags: vec!["a".into(), "b".into()],
+            api_token: "secret".into(),
+            notification_email: None,
+            email_branding: Default::default(),
+        })
+        .unwrap();
+    for (id, tag, status) in &[
+        ("created", "a", ReviewStatus::Created),
+        ("archived", "b", ReviewStatus::Archived),
+        ("other", "c", ReviewStatus::Created),
+    ] {
+        let place = Place::build()
+            .id(id)
+            .title(id)
+            .tags(vec![tag])
+            .pos(MapPoint::from_lat_lng_deg(48.7755, 9.1827))
+            .finish();
+        db.exclusive()
+            .unwrap()
+            .create_or_update_place(place.clone())
+            .unwrap();
+        search_engine
+            .add_or_update_place(&place, *status, None, &place.avg_ratings(&[]))
+            .unwrap();
+    }
+    db.exclusive()
+        .unwrap()
+        .add_pending_clearance_for_places(
+            &["org".into()],
+            &PendingClearanceForPlace {
+                place_id: "created".into(),
+                created_at: TimestampMs::now(),
+                last_cleared_revision: None,
+            },
+        )
+        .unwrap();
+    let new_event = usecases::NewEvent {
+        title: "event".into(),
+        start: Timestamp::now().into_seconds(),
+        tags: Some(vec!["b".into()]),
+        created_by: Some("test@example.com".into()),
+        ..Default::default()
+    };
+    let event = flows::create_event(
+        &db,
+        &mut search_engine,
+        &notify,
+        Some("secret"),
+        new_event,
+        None,
+    )
+    .unwrap();
+    search_engine.flush_index().unwrap();
+
+    let response = client.get("/org/search").dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let mut response = client
+        .get("/org/search")
+        .header(rocket::http::Header::new("Authorization", "Bearer secret"))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let result: json::OrgSearchResponse =
+        serde_json::from_str(&response.body_string().unwrap()).unwrap();
+    let mut place_ids: Vec<_> = result.places.iter().map(|p| p.place.id.as_str()).collect();
+    place_ids.sort_unstable();
+    assert_eq!(vec!["archived", "created"], place_ids);
+    for place in &result.places {
+        assert_eq!(
+            place.place.id == "created",
+            place.pending_clearance.is_some()
+        );
+    }
+    assert_eq!(1, result.events.len());
+    assert_eq!(event.id.as_str(), result.events[0].id);
+
+    let mut response = client
+        .get("/org/search?text=archived&bbox=48,9,49,10")
+        .header(rocket::http::Header::new("Authorization", "Bearer secret"))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let result: json::OrgSearchResponse =
+        serde_json::from_str(&response.body_string().unwrap()).unwrap();
+    assert_eq!(1, result.places.len());
+    assert_eq!("archived", result.places[0].place.id);
+    assert!(result.events.is_empty());
+}
+
+#[test]
+fn create_place_with_reserved_tag_according_to_moderation_policy() {
+    let (client, db) = setup();
+    for id in &["a", "b"] {
+        db.exclusive()
+            .unwrap()
+            .create_org(Organization {
+                id: (*id).into(),
+                name: (*id).into(),
+                moderated_tags: vec![(*id).into()],
+                api_token: (*id).into(),
+                notification_email: None,
+                email_branding: Default::default(),
+            })
+            .unwrap();
+    }
+    let new_place = |tag: &str| {
+        let cookie = get_captcha_cookie(&client).unwrap();
+        client
+            .post("/entries?confirm_position=true")
+            .header(ContentType::JSON)
+            .cookie(cookie)
+            .body(format!(r#"{{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":[],"license":"CC0-1.0","tags":["{}"]}}"#, tag))
+            .dispatch()
+            .status()
+    };
+    let set_policy = |token: &str, tag: &str, policy: &str| {
+        client
+            .put(format!("/organizations/tags/{}/policy", tag))
+            .header(ContentType::JSON)
+            .header(rocket::http::Header::new(
+                "Authorization",
+                format!("Bearer {}", token),
+            ))
+            .body(format!("\"{}\"", policy))
+            .dispatch()
+            .status()
+    };
+
+    // Organizations are not allowed to configure tags of other organizations
+    assert_eq!(Status::Forbidden, set_policy("b", "a", "allow_freely"));
+    assert_eq!(Status::Forbidden, new_place("a"));
+
+    assert_eq!(Status::Ok, set_policy("a", "a", "allow_freely"));
+    assert_eq!(Status::Ok, new_place("a"));
+
+    assert_eq!(Status::Ok, set_policy("a", "a", "forbidden"));
+    assert_eq!(Status::Forbidden, new_place("a"));
+
+    assert_eq!(Status::Ok, set_policy("b", "b", "require_clearance"));
+    assert_eq!(Status::Ok, new_place("b"));
+    assert_eq!(
+        Some("b".into()),
+        db.shared()
+            .unwrap()
+            .map_tag_to_clearance_org_id("b")
+            .unwrap()
+    );
+}
+
+#[test]
+fn transfer_and_share_owned_tags() {
+    let (client, db) = setup();
+    for id in &["a", "b", "c"] {
+        db.exclusive()
+            .unwrap()
+            .create_org(Organization {
+                id: (*id).into(),
+                name: (*id).into(),
+                moderated_tags: vec![(*id).into()],
+                api_token: (*id).into(),
+                notification_email: None,
+                email_branding: Default::default(),
+            })
+            .unwrap();
+    }
+    let post_owner = |token: &str, tag: &str, action: &str, org_id: &str| {
+        client
+            .post(format!("/organizations/tags/{}/{}", tag, action))
+            .header(ContentType::JSON)
+            .header(rocket::http::Header::new(
+                "Authorization",
+                format!("Bearer {}", token),
+            ))
+            .body(format!(r#"{{"org_id":"{}"}}"#, org_id))
+            .dispatch()
+            .status()
+    };
+    let new_place = |tag: &str| {
+        let cookie = get_captcha_cookie(&client).unwrap();
+        client
+            .post("/entries?confirm_position=true")
+            .header(ContentType::JSON)
+            .cookie(cookie)
+            .body(format!(r#"{{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":[],"license":"CC0-1.0","tags":["{}"]}}"#, tag))
+            .dispatch()
+            .status()
+    };
+    let pending_clearances = |org_id: &str| {
+        db.shared()
+            .unwrap()
+            .count_pending_clearances_for_places(&org_id.into())
+            .unwrap()
+    };
+    let owned_tags = |org_id: &str| -> Vec<String> {
+        db.shared()
+            .unwrap()
+            .get_org_by_id(&org_id.into())
+            .unwrap()
+            .moderated_tags
+            .into_iter()
+            .map(|t| t.label)
+            .collect()
+    };
+
+    // Only owners can pass on their tags
+    assert_eq!(Status::Forbidden, post_owner("b", "a", "transfer", "b"));
+    assert_eq!(Status::NotFound, post_owner("a", "a", "transfer", "x"));
+    assert_eq!(Status::BadRequest, post_owner("a", "a", "transfer", "a"));
+
+    // The pending clearances and the policy are handed over with the tag
+    let set_policy = client
+        .put("/organizations/tags/a/policy")
+        .header(ContentType::JSON)
+        .header(rocket::http::Header::new("Authorization", "Bearer a"))
+        .body("\"require_clearance\"")
+        .dispatch()
+        .status();
+    assert_eq!(Status::Ok, set_policy);
+    assert_eq!(Status::Ok, new_place("a"));
+    assert_eq!(1, pending_clearances("a"));
+
+    assert_eq!(Status::Ok, post_owner("a", "a", "transfer", "b"));
+    assert!(owned_tags("a").is_empty());
+    assert_eq!(0, pending_clearances("a"));
+    assert_eq!(1, pending_clearances("b"));
+    assert_eq!(
+        Some(TagModerationPolicy::RequireClearance),
+        db.shared()
+            .unwrap()
+            .get_tag_moderation_policy(&"b".into(), "a")
+            .unwrap()
+    );
+    assert_eq!(vec!["a", "b"], {
+        let mut tags = owned_tags("b");
+        tags.sort_unstable();
+        tags
+    });
+
+    assert_eq!(Status::Ok, post_owner("b", "b", "owners", "c"));
+    assert_eq!(Status::BadRequest, post_owner("b", "b", "owners", "c"));
+    assert!(owned_tags("b").contains(&"b".to_string()));
+    assert!(owned_tags("c").contains(&"b".to_string()));
+
+    // Both co-owners are permitted to moderate the shared tag
+    let moderated_tags = db
+        .shared()
+        .unwrap()
+        .get_org_by_id(&"c".into())
+        .unwrap()
+        .moderated_tags;
+    let moderated_tag = moderated_tags.iter().find(|t| t.label == "b").unwrap();
+    assert!(!moderated_tag.allow_add);
+    let org = db.shared().unwrap().get_org_by_id(&"c".into()).unwrap();
+    assert!(usecases::authorize_editing_of_tagged_entry(
+        &*db.shared().unwrap(),
+        &[],
+        &["b".to_string()],
+        Some(&org)
+    )
+    .unwrap()
+    .is_empty());
+}
+
+#[test]
+fn rotate_organization_api_tokens() {
+    let (client, db) = setup();
+    db.exclusive()
+        .unwrap()
+        .create_org(Organization {
+            id: "org".into(),
+            name: "org".into(),
+            moderated_tags: vec![],
+            api_token: "initial".into(),
+            notification_email: None,
+            email_branding: Default::default(),
+        })
+        .unwrap();
+    let bearer =
+        |token: &str| rocket::http::Header::new("Authorization", format!("Bearer {}", token));
+    let org_usage = |token: &str| {
+        client
+            .get("/org/usage")
+            .header(bearer(token))
+            .dispatch()
+            .status()
+    };
+    let create_token = |token: &str, body: &str| {
+        client
+            .post("/organizations/tokens")
+            .header(ContentType::JSON)
+            .header(bearer(token))
+            .body(body.to_owned())
+            .dispatch()
+    };
+
+    let mut res = create_token("initial", r#"{"label":"new"}"#);
+    assert_eq!(res.status(), Status::Ok);
+    let new_token: json::OrganizationApiToken =
+        serde_json::from_str(&res.body_string().unwrap()).unwrap();
+    let new_bearer_token = new_token.token.clone().unwrap();
+    assert_eq!(Status::Ok, org_usage(&new_bearer_token));
+    assert_eq!(
+        Status::BadRequest,
+        create_token(&new_bearer_token, r#"{"expires_at":1}"#).status()
+    );
+
+    let mut res = client
+        .get("/organizations/tokens")
+        .header(ContentType::JSON)
+        .header(bearer(&new_bearer_token))
+        .dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    let tokens: Vec<json::OrganizationApiToken> =
+        serde_json::from_str(&res.body_string().unwrap()).unwrap();
+    assert_eq!(1, tokens.len());
+    assert_eq!(Some("new"), tokens[0].label.as_deref());
+    assert!(tokens[0].token.is_none());
+    assert!(tokens[0].last_used_at.is_some());
+
+    // Tokens of other organizations can't be revoked
+    db.exclusive()
+        .unwrap()
+        .create_org(Organization {
+            id: "other".into(),
+            name: "other".into(),
+            moderated_tags: vec![],
+            api_token: "other".into(),
+            notification_email: None,
+            email_branding: Default::default(),
+        })
+        .unwrap();
+    let res = client
+        .delete(format!("/organizations/tokens/{}", new_token.id))
+        .header(bearer("other"))
+        .dispatch();
+    assert_eq!(res.status(), Status::NotFound);
+
+    let res = client
+        .delete(format!("/organizations/tokens/{}", new_token.id))
+        .header(bearer("initial"))
+        .dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    assert_eq!(Status::Unauthorized, org_usage(&new_bearer_token));
+    assert_eq!(Status::Ok, org_usage("initial"));
+
+    // Only admins manage the tokens of all organizations
+    let res = client
+        .get("/admin/organizations/org/tokens")
+        .header(ContentType::JSON)
+        .header(bearer("initial"))
+        .dispatch();
+    assert_eq!(res.status(), Status::Unauthorized);
+}
+
 #[test]
 fn create_place_with_tag_duplicates() {
     let (client, db) = setup();
-    let req = client.post("/entries")
+    let req = client.post("/entries?confirm_position=true")
                     .header(ContentType::JSON)
-                    .body(r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":["x"],"license":"CC0-1.0","tags":["foo","foo"]}"#);
+                    .body(r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":[],"license":"CC0-1.0","tags":["foo","foo"]}"#);
     let mut response = req.dispatch();
     assert_eq!(response.status(), Status::Ok);
     test_json(&response);
@@ -105,9 +665,9 @@ fn create_place_with_tag_duplicates() {
 #[test]
 fn create_place_with_sharp_tag_and_custom_link() {
     let (client, db) = setup();
-    let json = r##"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":["x"],"license":"CC0-1.0","tags":["foo","#bar"],"links":[{"url":"example.com","title":"Auto-completed URL"}]}"##;
+    let json = r##"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":[],"license":"CC0-1.0","tags":["foo","#bar"],"links":[{"url":"example.com","title":"Auto-completed URL"}]}"##;
     let response = client
-        .post("/entries")
+        .post("/entries?confirm_position=true")
         .header(ContentType::JSON)
         .body(json)
         .dispatch();
@@ -136,9 +696,9 @@ fn create_place_with_sharp_tag_and_custom_link() {
 #[test]
 fn update_place_with_tag_duplicates() {
     let (client, db) = setup();
-    let req = client.post("/entries")
+    let req = client.post("/entries?confirm_position=true")
                     .header(ContentType::JSON)
-                    .body(r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":["x"],"license":"ODbL-1.0","tags":["foo","foo"]}"#);
+                    .body(r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":[],"license":"ODbL-1.0","tags":["foo","foo"]}"#);
     let _res = req.dispatch();
     let (place, _) = db.exclusive().unwrap().all_places().unwrap()[0].clone();
     let mut json = String::new();
@@ -147,8 +707,8 @@ fn update_place_with_tag_duplicates() {
         u64::from(place.revision.next()),
         place.id
     ));
-    json.push_str(r#","title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":["x"],"license":"CC0-1.0","tags":["bar","bar"]}"#);
-    let url = format!("/entries/{}", place.id);
+    json.push_str(r#","title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":[],"license":"CC0-1.0","tags":["bar","bar"]}"#);
+    let url = format!("/entries/{}?confirm_position=true", place.id);
     let req = client.put(url).header(ContentType::JSON).body(json);
     let response = req.dispatch();
     assert_eq!(response.status(), Status::Ok);
@@ -158,35 +718,146 @@ fn update_place_with_tag_duplicates() {
 }
 
 #[test]
-fn get_one_entry() {
-    let e = Place::build()
-        .id("get_one_entry_test")
-        .title("some")
-        .description("desc")
-        .finish();
+fn update_place_with_outdated_version() {
+    let (client, db) = setup();
+    let req = client.post("/entries?confirm_position=true")
+                    .header(ContentType::JSON)
+                    .body(r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":[],"license":"ODbL-1.0","tags":[]}"#);
+    let _res = req.dispatch();
+    let (place, _) = db.exclusive().unwrap().all_places().unwrap()[0].clone();
+    let update = |version: u64, title: &str, description: &str| {
+        format!(
+            r#"{{"version":{},"title":"{}","description":"{}","lat":0.0,"lng":0.0,"categories":[],"tags":[]}}"#,
+            version, title, description
+        )
+    };
+    let url = format!("/entries/{}?confirm_position=true", place.id);
+    let outdated_version = u64::from(place.revision.next());
+    let res = client
+        .put(&url)
+        .header(ContentType::JSON)
+        .body(update(outdated_version, "bar", "blablabla"))
+        .dispatch();
+    assert_eq!(res.status(), Status::Ok);
 
-    let (client, connections, mut search_engine, _) = setup2();
-    connections
-        .exclusive()
-        .unwrap()
-        .create_or_update_place(e.clone())
-        .unwrap();
-    flows::create_rating(
-        &connections,
-        &mut search_engine,
-        usecases::NewPlaceRating {
-            context: ofdb_boundary::RatingContext::Humanity,
-            value: ofdb_boundary::RatingValue::from(2),
-            title: "title".into(),
-            user: None,
-            entry: "get_one_entry_test".into(),
-            comment: "bla".into(),
-            source: Some("blabla".into()),
-        },
-    )
-    .unwrap();
-    let req = client.get("/entries/get_one_entry_test");
-    let mut response = req.dispatch();
+    // Another update that is based on the initial revision
+    // but modifies a different field is merged
+    let res = client
+        .put(&url)
+        .header(ContentType::JSON)
+        .body(update(outdated_version, "foo", "merged"))
+        .dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    let (merged, _) = db.exclusive().unwrap().all_places().unwrap()[0].clone();
+    assert_eq!(2, u64::from(merged.revision));
+    assert_eq!("bar", merged.title);
+    assert_eq!("merged", merged.description);
+
+    // The title has been modified twice
+    let mut res = client
+        .put(&url)
+        .header(ContentType::JSON)
+        .body(update(outdated_version, "baz", "blablabla"))
+        .dispatch();
+    assert_eq!(res.status(), Status::Conflict);
+    test_json(&res);
+    let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+    let conflict: ofdb_boundary::RevisionConflict = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(409, conflict.http_status);
+    assert_eq!(2, conflict.current_version);
+    assert!(conflict.last_change.at >= place.created.at.into_inner());
+    // The editor is not disclosed to anonymous users
+    assert!(conflict.last_change.by.is_none());
+}
+
+#[test]
+fn create_and_publish_draft() {
+    let (client, db) = setup();
+    let draft = r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":[],"license":"CC0-1.0","tags":["draft"]}"#;
+    let res = client
+        .post("/entries/drafts?confirm_position=true")
+        .header(ContentType::JSON)
+        .body(draft)
+        .dispatch();
+    assert_eq!(res.status(), Status::Unauthorized);
+
+    db.exclusive()
+        .unwrap()
+        .create_user(&User {
+            email: "foo@bar".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::Guest,
+        })
+        .unwrap();
+    let res = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email": "foo@bar", "password": "secret"}"#)
+        .dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    let mut res = client
+        .post("/entries/drafts?confirm_position=true")
+        .header(ContentType::JSON)
+        .body(draft)
+        .dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+    let id: String = serde_json::from_str(&body_str).unwrap();
+
+    // Drafts are hidden from everyone else
+    let mut res = client.get(format!("/entries/{}", id)).dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    assert_eq!("[]", res.body().and_then(|b| b.into_string()).unwrap());
+    let mut res = client.get("/entries/drafts").dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+    let drafts: Vec<json::Entry> = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(1, drafts.len());
+    assert_eq!(id, drafts[0].id);
+
+    let res = client
+        .post(format!("/entries/drafts/{}/publish", id))
+        .dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    let mut res = client.get(format!("/entries/{}", id)).dispatch();
+    let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains(&format!(r#""id":"{}""#, id)));
+    let mut res = client.get("/entries/drafts").dispatch();
+    assert_eq!("[]", res.body().and_then(|b| b.into_string()).unwrap());
+}
+
+#[test]
+fn get_one_entry() {
+    let e = Place::build()
+        .id("get_one_entry_test")
+        .title("some")
+        .description("desc")
+        .finish();
+
+    let (client, connections, mut search_engine, _) = setup2();
+    connections
+        .exclusive()
+        .unwrap()
+        .create_or_update_place(e.clone())
+        .unwrap();
+    flows::create_rating(
+        &connections,
+        &mut search_engine,
+        &DummyNotifyGW,
+        usecases::NewPlaceRating {
+            context: ofdb_boundary::RatingContext::Humanity,
+            value: ofdb_boundary::RatingValue::from(2),
+            title: "title".into(),
+            user: None,
+            entry: "get_one_entry_test".into(),
+            comment: "bla".into(),
+            source: Some("blabla".into()),
+        },
+    )
+    .unwrap();
+    let req = client.get("/entries/get_one_entry_test");
+    let mut response = req.dispatch();
     assert_eq!(response.status(), Status::Ok);
     test_json(&response);
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
@@ -242,6 +913,201 @@ fn get_multiple_places() {
         .any(|x| *x == json::entry_from_place_with_ratings(two.clone(), vec![])));
 }
 
+#[test]
+fn get_places_as_csv_or_geojson() {
+    let place = Place::build()
+        .id("foo")
+        .title("some")
+        .description("desc")
+        .pos(MapPoint::from_lat_lng_deg(48.7, 9.1))
+        .tags(vec!["bio", Category::TAG_NON_PROFIT])
+        .finish();
+    let (lat, lng) = (
+        place.location.pos.lat().to_deg(),
+        place.location.pos.lng().to_deg(),
+    );
+    let (client, db) = setup();
+    db.exclusive()
+        .unwrap()
+        .create_or_update_place(place)
+        .unwrap();
+    db.exclusive()
+        .unwrap()
+        .create_rating(Rating {
+            id: "rating".into(),
+            place_id: "foo".into(),
+            created_at: Timestamp::from_seconds(100),
+            archived_at: None,
+            title: "Fair".into(),
+            value: RatingValue::from(2),
+            context: RatingContext::Fairness,
+            source: None,
+        })
+        .unwrap();
+    db.exclusive()
+        .unwrap()
+        .create_comment(Comment {
+            id: "comment".into(),
+            rating_id: "rating".into(),
+            created_at: Timestamp::from_seconds(200),
+            archived_at: None,
+            text: "Fair prices".into(),
+        })
+        .unwrap();
+
+    let mut response = client
+        .get("/entries/foo")
+        .header(rocket::http::Accept::CSV)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(Some(ContentType::CSV), response.content_type());
+    assert_eq!(Some("Accept"), response.headers().get_one("Vary"));
+    let body_str = response.body_string().unwrap();
+    assert!(body_str.starts_with("id,created_at,created_by,version,title,"));
+    assert!(body_str.contains(&format!(
+        ",some,desc,{},{},,,,,,,,,,,,{},bio,",
+        lat,
+        lng,
+        Category::ID_NON_PROFIT
+    )));
+    assert!(body_str.ends_with(",0,1,0,0,0,0,1\n"));
+
+    let mut response = client
+        .get("/entries/foo")
+        .header(rocket::http::Header::new("Accept", "application/geo+json"))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        Some("application/geo+json"),
+        response.headers().get_one("Content-Type")
+    );
+    let collection: serde_json::Value =
+        serde_json::from_str(&response.body_string().unwrap()).unwrap();
+    assert_eq!("FeatureCollection", collection["type"]);
+    let feature = &collection["features"][0];
+    assert_eq!("foo", feature["id"]);
+    assert_eq!(
+        serde_json::json!([lng, lat]),
+        feature["geometry"]["coordinates"]
+    );
+    assert_eq!("some", feature["properties"]["title"]);
+    assert_eq!(1, feature["properties"]["ratings_fairness"]);
+    assert_eq!(0, feature["properties"]["ratings_diversity"]);
+    assert_eq!(1, feature["properties"]["comments"]);
+
+    let response = client.get("/entries/foo").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+}
+
+#[test]
+fn get_places_as_csv_without_creator_for_anonymous_users() {
+    let mut place = Place::build().id("foo").title("some").finish();
+    place.created.by = Some("creator@example.com".into());
+    let (client, db) = setup();
+    db.exclusive()
+        .unwrap()
+        .create_user(&User {
+            email: "scout@example.com".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::Scout,
+        })
+        .unwrap();
+    db.exclusive()
+        .unwrap()
+        .create_or_update_place(place)
+        .unwrap();
+
+    let mut response = client
+        .get("/entries/foo")
+        .header(rocket::http::Accept::CSV)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body_string().unwrap();
+    assert!(body_str.contains(",some,"));
+    assert!(!body_str.contains("creator@example.com"));
+
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email": "scout@example.com", "password": "secret"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let mut response = client
+        .get("/entries/foo")
+        .header(rocket::http::Accept::CSV)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body_string().unwrap();
+    assert!(body_str.contains("creator@example.com"));
+}
+
+#[test]
+fn get_entry_with_review_status_only_for_scouts() {
+    let (client, db) = setup();
+    db.exclusive()
+        .unwrap()
+        .create_user(&User {
+            email: "scout@example.com".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::Scout,
+        })
+        .unwrap();
+    let place = Place::build().id("reviewed_entry").title("some").finish();
+    db.exclusive()
+        .unwrap()
+        .create_or_update_place(place)
+        .unwrap();
+
+    let mut response = client.get("/entries/reviewed_entry").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(!body_str.contains(r#""status""#));
+    let response = client
+        .get("/entries/reviewed_entry?status=created")
+        .dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email": "scout@example.com", "password": "secret"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let mut response = client.get("/entries/reviewed_entry").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains(r#""status":"created""#));
+    let mut response = client
+        .get("/entries/reviewed_entry?status=confirmed,rejected")
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert_eq!("[]", body_str);
+}
+
+#[test]
+fn get_multiple_categories() {
+    let (client, _) = setup();
+    let req = client.get(format!(
+        "/categories/{},unknown,{}",
+        Category::ID_EVENT,
+        Category::ID_NON_PROFIT
+    ));
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let categories: Vec<json::Category> = serde_json::from_str(&body_str).unwrap();
+    let mut ids: Vec<_> = categories.into_iter().map(|c| c.id).collect();
+    ids.sort_unstable();
+    let mut expected_ids = vec![Category::ID_EVENT, Category::ID_NON_PROFIT];
+    expected_ids.sort_unstable();
+    assert_eq!(expected_ids, ids);
+}
+
 fn default_new_entry() -> usecases::NewPlace {
     usecases::NewPlace {
         title: Default::default(),
@@ -363,6 +1229,87 @@ fn search_with_categories_and_bbox() {
     assert!(!body_str.contains(&format!("\"{}\"", place_ids[2])));
 }
 
+#[test]
+fn search_without_location_if_ip_geolocation_is_disabled() {
+    let (client, _) = setup();
+    let response = client.get("/search?text=foo").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    let response = client.get("/search/nearby").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    let response = client.get("/search/nearby?lat=48.0").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    let response = client.get("/search/nearby?lat=48.0&lng=9.0").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+}
+
+#[test]
+fn suggest_titles_and_tags() {
+    let entries = vec![
+        usecases::NewPlace {
+            tags: vec!["bioladen".into(), "unverpackt".into()],
+            ..new_entry_with_text("Der Bioladen", "bla", 1.0, 1.0)
+        },
+        new_entry_with_text("Biohof Sonnenschein", "foo", 2.0, 2.0),
+        new_entry_with_text("Fahrradladen", "bioladen", 3.0, 3.0),
+    ];
+    let (client, connections, mut search_engine, notify) = setup2();
+    for e in entries {
+        flows::create_place(
+            &connections,
+            &mut search_engine,
+            &notify,
+            e,
+            None,
+            None,
+            &Cfg::default(),
+        )
+        .unwrap();
+    }
+    let suggest = |url: &str| -> Vec<(json::SearchSuggestionKind, String)> {
+        let mut response = client.get(url).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        test_json(&response);
+        let suggestions: Vec<json::SearchSuggestion> =
+            serde_json::from_str(&response.body_string().unwrap()).unwrap();
+        suggestions
+            .into_iter()
+            .map(|s| {
+                assert_eq!(s.kind == json::SearchSuggestionKind::Place, s.id.is_some());
+                (s.kind, s.text)
+            })
+            .collect()
+    };
+
+    assert_eq!(
+        vec![
+            (json::SearchSuggestionKind::Tag, "bioladen".to_string()),
+            (
+                json::SearchSuggestionKind::Place,
+                "Der Bioladen".to_string()
+            ),
+        ],
+        suggest("/search/suggest?q=biola")
+    );
+    // Typo
+    assert_eq!(
+        vec![(
+            json::SearchSuggestionKind::Place,
+            "Biohof Sonnenschein".to_string()
+        )],
+        suggest("/search/suggest?q=biohof%20sonnenchein")
+    );
+    // Only within the bounding box
+    assert!(suggest("/search/suggest?q=bio&bbox=2.5,2.5,4.0,4.0").is_empty());
+    assert_eq!(3, suggest("/search/suggest?q=bio").len());
+    assert_eq!(1, suggest("/search/suggest?q=bio&limit=1").len());
+    assert!(suggest("/search/suggest?q=%20").is_empty());
+
+    let response = client.get("/search/suggest").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+    let response = client.get("/search/suggest?q=bio&bbox=foo").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
 fn new_entry_with_text(title: &str, description: &str, lat: f64, lng: f64) -> usecases::NewPlace {
     usecases::NewPlace {
         title: title.into(),
@@ -458,43 +1405,397 @@ fn search_with_text() {
 }
 
 #[test]
-fn search_partial_text() {
-    let entries = vec![
-        new_entry_with_text("Foo", "bla", 1.0, 1.0),
-        new_entry_with_text("bar", "foo", 2.0, 2.0),
-        new_entry_with_text("baZ", "blub", 3.0, 3.0),
-        new_entry_with_text("foo-bar-BaZ", "blub-blub", 1.0, 1.0),
-    ];
+fn search_with_distance_from_origin() {
     let (client, connections, mut search_engine, notify) = setup2();
-    let place_ids: Vec<_> = entries
-        .into_iter()
-        .map(|e| {
-            flows::create_place(
-                &connections,
-                &mut search_engine,
-                &notify,
-                e,
-                None,
-                None,
-                &Cfg::default(),
-            )
-            .unwrap()
-            .id
-            .to_string()
-        })
-        .collect();
+    let place_id = flows::create_place(
+        &connections,
+        &mut search_engine,
+        &notify,
+        new_entry_with_text("Foo", "bla", 1.0, 1.0),
+        None,
+        None,
+        &Cfg::default(),
+    )
+    .unwrap()
+    .id
+    .to_string();
 
-    let req = client.get("/search?bbox=-10,-10,10,10&text=bar-baz");
+    let req = client.get("/search?bbox=-10,-10,10,10");
     let mut response = req.dispatch();
     assert_eq!(response.status(), Status::Ok);
-    test_json(&response);
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
-    assert!(!body_str.contains(&format!("\"{}\"", place_ids[0])));
-    assert!(!body_str.contains(&format!("\"{}\"", place_ids[1])));
-    assert!(!body_str.contains(&format!("\"{}\"", place_ids[2])));
-    assert!(body_str.contains(&format!("\"{}\"", place_ids[3])));
+    let res: json::SearchResponse = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(place_id, res.visible[0].id);
+    assert!(res.visible[0].distance.is_none());
 
-    let req = client.get("/search?bbox=-10,-10,10,10&text=blub-");
+    let req = client.get("/search?bbox=-10,-10,10,10&origin=1.0,2.0");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let res: json::SearchResponse = serde_json::from_str(&body_str).unwrap();
+    let distance = res.visible[0].distance.unwrap();
+    // One degree of longitude near the equator is about 111 km
+    assert!(distance > 110_000.0 && distance < 112_000.0);
+
+    let req = client.get("/search?bbox=-10,-10,10,10&origin=1.0");
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[test]
+fn search_with_homepage_previews() {
+    let (client, connections, mut search_engine, notify) = setup2();
+    let mut new_place = new_entry_with_text("Foo", "bla", 1.0, 1.0);
+    new_place.homepage = Some("https://foo.example.com/".into());
+    flows::create_place(
+        &connections,
+        &mut search_engine,
+        &notify,
+        new_place,
+        None,
+        None,
+        &Cfg::default(),
+    )
+    .unwrap();
+    connections
+        .exclusive()
+        .unwrap()
+        .store_homepage_preview(&HomepagePreview {
+            url: "https://foo.example.com/".into(),
+            title: Some("Foo Shop".into()),
+            image_url: Some("https://foo.example.com/logo.png".into()),
+            fetched_at: Timestamp::now(),
+        })
+        .unwrap();
+
+    let req = client.get("/search?bbox=-10,-10,10,10");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(!body_str.contains("preview"));
+
+    let req = client.get("/search?bbox=-10,-10,10,10&with_previews=true");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let res: json::SearchResponse = serde_json::from_str(&body_str).unwrap();
+    let preview = res.visible[0].preview.as_ref().unwrap();
+    assert_eq!(Some("Foo Shop"), preview.title.as_deref());
+    assert_eq!(
+        Some("https://foo.example.com/logo.png"),
+        preview.image_url.as_deref()
+    );
+}
+
+#[test]
+fn search_ordered_by_quality() {
+    let (client, connections, mut search_engine, notify) = setup2();
+    let sparse_place = new_entry_with_text("Foo", "bla", 1.0, 1.0);
+    let mut complete_place = new_entry_with_text(
+        "Bar",
+        "A shop that sells regional and organic food since many years",
+        2.0,
+        2.0,
+    );
+    complete_place.email = Some("bar@example.com".into());
+    complete_place.homepage = Some("https://bar.example.com/".into());
+    complete_place.tags = vec!["organic".into()];
+    let place_ids: Vec<_> = vec![sparse_place, complete_place]
+        .into_iter()
+        .map(|p| {
+            flows::create_place(
+                &connections,
+                &mut search_engine,
+                &notify,
+                p,
+                None,
+                None,
+                &Cfg::default(),
+            )
+            .unwrap()
+            .id
+            .to_string()
+        })
+        .collect();
+
+    let req = client.get("/search?bbox=-10,-10,10,10&sort=quality");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let res: json::SearchResponse = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(2, res.visible.len());
+    assert_eq!(place_ids[1], res.visible[0].id);
+    assert_eq!(place_ids[0], res.visible[1].id);
+    assert!(res.visible[0].quality > res.visible[1].quality);
+    assert_eq!(0, res.visible[1].quality);
+
+    let req = client.get("/search?bbox=-10,-10,10,10&sort=popularity");
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[test]
+fn search_with_facets() {
+    let mut entries = vec![
+        new_entry_with_category(Category::ID_NON_PROFIT, 1.0, 1.0),
+        new_entry_with_category(Category::ID_NON_PROFIT, 2.0, 2.0),
+        new_entry_with_category(Category::ID_COMMERCIAL, 3.0, 3.0),
+        // Outside of the bounding box
+        new_entry_with_category(Category::ID_COMMERCIAL, 20.0, 20.0),
+    ];
+    entries[0].tags = vec!["bio".into(), "fair".into()];
+    entries[1].tags = vec!["bio".into()];
+    entries[2].tags = vec!["fair".into()];
+    entries[3].tags = vec!["fair".into()];
+    let (client, connections, mut search_engine, notify) = setup2();
+    for e in entries {
+        flows::create_place(
+            &connections,
+            &mut search_engine,
+            &notify,
+            e,
+            None,
+            None,
+            &Cfg::default(),
+        )
+        .unwrap();
+    }
+
+    let req = client.get("/search?bbox=-10,-10,10,10");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let res: json::SearchResponse = serde_json::from_str(&body_str).unwrap();
+    assert!(res.facets.is_none());
+
+    let req = client.get("/search?bbox=-10,-10,10,10&with_facets=true");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let res: json::SearchResponse = serde_json::from_str(&body_str).unwrap();
+    let facets = res.facets.unwrap();
+    assert_eq!(
+        vec![
+            json::FacetCount {
+                value: Category::ID_NON_PROFIT.into(),
+                count: 2
+            },
+            json::FacetCount {
+                value: Category::ID_COMMERCIAL.into(),
+                count: 1
+            },
+        ],
+        facets.categories
+    );
+    assert_eq!(
+        vec![
+            json::FacetCount {
+                value: "bio".into(),
+                count: 2
+            },
+            json::FacetCount {
+                value: "fair".into(),
+                count: 2
+            },
+        ],
+        facets.tags
+    );
+
+    // The facets are restricted by the query
+    let req = client.get("/search?bbox=-10,-10,10,10&tags=fair&with_facets=true");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let res: json::SearchResponse = serde_json::from_str(&body_str).unwrap();
+    let facets = res.facets.unwrap();
+    assert_eq!(2, facets.categories.len());
+    assert_eq!(
+        vec![
+            json::FacetCount {
+                value: "fair".into(),
+                count: 2
+            },
+            json::FacetCount {
+                value: "bio".into(),
+                count: 1
+            },
+        ],
+        facets.tags
+    );
+}
+
+#[test]
+fn search_within_area() {
+    let entries = vec![
+        new_entry_with_category(Category::ID_NON_PROFIT, 1.0, 1.0),
+        // Within the bounding box but outside of the area
+        new_entry_with_category(Category::ID_NON_PROFIT, 8.0, 8.0),
+    ];
+    let (client, connections, mut search_engine, notify) = setup2();
+    let mut ids = vec![];
+    for e in entries {
+        ids.push(
+            flows::create_place(
+                &connections,
+                &mut search_engine,
+                &notify,
+                e,
+                None,
+                None,
+                &Cfg::default(),
+            )
+            .unwrap()
+            .id
+            .to_string(),
+        );
+    }
+
+    // The triangle (0,0), (0,10), (10,0) as an encoded polyline and as GeoJSON
+    let polyline = "%3F%3F%3F_c%60%7C%40_c%60%7C%40~b%60%7C%40";
+    let geojson = "%7B%22type%22%3A%22Polygon%22%2C%22coordinates%22%3A%5B%5B%5B0%2C0%5D%2C%5B10%2C0%5D%2C%5B0%2C10%5D%2C%5B0%2C0%5D%5D%5D%7D";
+    for area in &[polyline, geojson] {
+        let req = client.get(format!("/search?area={}", area));
+        let mut response = req.dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+        let res: json::SearchResponse = serde_json::from_str(&body_str).unwrap();
+        assert_eq!(1, res.visible.len());
+        assert_eq!(ids[0], res.visible[0].id);
+        assert_eq!(1, res.invisible.len());
+        assert_eq!(ids[1], res.invisible[0].id);
+    }
+
+    let req = client.get("/search?area=%7B%22type%22%3A%22Point%22%7D");
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[test]
+fn search_with_excluded_tags() {
+    let mut entries = vec![
+        new_entry_with_category(Category::ID_NON_PROFIT, 1.0, 1.0),
+        new_entry_with_category(Category::ID_COMMERCIAL, 2.0, 2.0),
+        new_entry_with_category(Category::ID_COMMERCIAL, 3.0, 3.0),
+    ];
+    entries[0].tags = vec!["bio".into()];
+    entries[1].tags = vec!["bio".into(), "chain".into()];
+    entries[2].tags = vec!["bio".into(), "closed".into()];
+    let (client, connections, mut search_engine, notify) = setup2();
+    let mut ids = vec![];
+    for e in entries {
+        ids.push(
+            flows::create_place(
+                &connections,
+                &mut search_engine,
+                &notify,
+                e,
+                None,
+                None,
+                &Cfg::default(),
+            )
+            .unwrap()
+            .id
+            .to_string(),
+        );
+    }
+    let visible_ids = |query: &str| -> Vec<String> {
+        let req = client.get(format!("/search?bbox=-10,-10,10,10&tags=bio&{}", query));
+        let mut response = req.dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+        let res: json::SearchResponse = serde_json::from_str(&body_str).unwrap();
+        let mut ids: Vec<_> = res.visible.into_iter().map(|p| p.id).collect();
+        ids.sort_unstable();
+        ids
+    };
+    let mut expected_ids = vec![ids[0].clone(), ids[2].clone()];
+    expected_ids.sort_unstable();
+    assert_eq!(expected_ids, visible_ids("exclude_tags=chain"));
+    assert_eq!(expected_ids, visible_ids("excluded_tags=chain"));
+    assert_eq!(
+        vec![ids[0].clone()],
+        visible_ids("exclude_tags=chain&excluded_tags=closed")
+    );
+}
+
+#[test]
+fn search_with_typos() {
+    let (client, connections, mut search_engine, notify) = setup2();
+    let place_id = flows::create_place(
+        &connections,
+        &mut search_engine,
+        &notify,
+        new_entry_with_text("Bioladen", "bla", 1.0, 1.0),
+        None,
+        None,
+        &Cfg::default(),
+    )
+    .unwrap()
+    .id
+    .to_string();
+
+    let req = client.get("/search?bbox=-10,-10,10,10&text=Bioladne");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let res: json::SearchResponse = serde_json::from_str(&body_str).unwrap();
+    assert!(res.visible.is_empty());
+
+    let req = client.get("/search?bbox=-10,-10,10,10&text=Bioladne&fuzzy=true");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let res: json::SearchResponse = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(1, res.visible.len());
+    assert_eq!(place_id, res.visible[0].id);
+
+    // Excluded words are still matched exactly
+    let req = client.get("/search?bbox=-10,-10,10,10&text=Bioladne%20-bla&fuzzy=true");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let res: json::SearchResponse = serde_json::from_str(&body_str).unwrap();
+    assert!(res.visible.is_empty());
+}
+
+#[test]
+fn search_partial_text() {
+    let entries = vec![
+        new_entry_with_text("Foo", "bla", 1.0, 1.0),
+        new_entry_with_text("bar", "foo", 2.0, 2.0),
+        new_entry_with_text("baZ", "blub", 3.0, 3.0),
+        new_entry_with_text("foo-bar-BaZ", "blub-blub", 1.0, 1.0),
+    ];
+    let (client, connections, mut search_engine, notify) = setup2();
+    let place_ids: Vec<_> = entries
+        .into_iter()
+        .map(|e| {
+            flows::create_place(
+                &connections,
+                &mut search_engine,
+                &notify,
+                e,
+                None,
+                None,
+                &Cfg::default(),
+            )
+            .unwrap()
+            .id
+            .to_string()
+        })
+        .collect();
+
+    let req = client.get("/search?bbox=-10,-10,10,10&text=bar-baz");
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[0])));
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[1])));
+    assert!(!body_str.contains(&format!("\"{}\"", place_ids[2])));
+    assert!(body_str.contains(&format!("\"{}\"", place_ids[3])));
+
+    let req = client.get("/search?bbox=-10,-10,10,10&text=blub-");
     let mut response = req.dispatch();
     assert_eq!(response.status(), Status::Ok);
     test_json(&response);
@@ -1154,11 +2455,13 @@ fn search_with_status() {
         .body(r#"{"email": "foo@bar", "password": "secret"}"#)
         .dispatch();
     assert_eq!(response.status(), Status::Ok);
+    let csrf_token = csrf_token_header(&response);
 
     for (id, status) in &places {
         let req = client
             .post(format!("/places/{}/review", id))
             .header(ContentType::JSON)
+            .header(csrf_token.clone())
             .body(&format!(
                 "{{\"status\":\"{}\",\"comment\":\"{}\"}}",
                 status, id
@@ -1229,72 +2532,397 @@ fn create_rating() {
     let req = client.post("/ratings")
         .header(ContentType::JSON)
         .body(r#"{"value": 1,"context":"fairness","entry":"foo","comment":"test", "title":"idontcare", "source":"source..."}"#);
-    let response = req.dispatch();
+    let mut response = req.dispatch();
     assert_eq!(response.status(), Status::Ok);
-    assert_eq!(
-        connections
-            .shared()
-            .unwrap()
-            .load_ratings_of_place("foo")
-            .unwrap()[0]
-            .value,
-        RatingValue::from(1)
-    );
     test_json(&response);
-}
-
-#[test]
-fn get_one_rating() {
-    let e = Place::build().id("foo").finish();
-    let (client, connections, mut search_engine, _) = setup2();
-    connections
-        .exclusive()
-        .unwrap()
-        .create_or_update_place(e)
-        .unwrap();
-    flows::create_rating(
-        &connections,
-        &mut search_engine,
-        usecases::NewPlaceRating {
-            context: ofdb_boundary::RatingContext::Humanity,
-            value: ofdb_boundary::RatingValue::from(2),
-            user: None,
-            title: "title".into(),
-            entry: "foo".into(),
-            comment: "bla".into(),
-            source: Some("blabla".into()),
-        },
-    )
-    .unwrap();
-    let rid = connections
+    let ratings = connections
         .shared()
         .unwrap()
         .load_ratings_of_place("foo")
-        .unwrap()[0]
-        .id
-        .clone();
-    let req = client.get(format!("/ratings/{}", rid));
-    let mut response = req.dispatch();
+        .unwrap();
+    assert_eq!(ratings[0].value, RatingValue::from(1));
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let rating: json::Rating = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(ratings[0].id.as_str(), rating.id);
+    assert_eq!("idontcare", rating.title);
+    assert_eq!(1, rating.comments.len());
+    assert_eq!("test", rating.comments[0].text);
+
+    let mut response = client.get(format!("/ratings/{}", rating.id)).dispatch();
     assert_eq!(response.status(), Status::Ok);
-    test_json(&response);
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
-    assert_eq!(body_str.as_str().chars().next().unwrap(), '[');
-    let ratings: Vec<json::Rating> = serde_json::from_str(&body_str).unwrap();
-    assert_eq!(ratings[0].comments.len(), 1);
-    assert_eq!(ratings[0].id, rid.to_string());
+    let loaded: Vec<json::Rating> = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(rating.comments[0].id, loaded[0].comments[0].id);
 }
 
 #[test]
-fn ratings_with_and_without_source() {
-    let e1 = Place::build().id("foo").finish();
-    let e2 = Place::build().id("bar").finish();
-    let (client, connections, mut search_engine, _) = setup2();
-    connections
-        .exclusive()
+fn hold_back_ratings_that_violate_the_comment_rules() {
+    let mut cfg = Cfg::default();
+    cfg.comment_rules.max_links = Some(1);
+    let (client, db) = setup_with_cfg(cfg);
+    db.exclusive()
         .unwrap()
-        .create_or_update_place(e1)
+        .create_or_update_place(Place::build().id("foo").finish())
         .unwrap();
-    connections
+    db.exclusive()
+        .unwrap()
+        .create_user(&User {
+            email: "scout@example.com".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::Scout,
+        })
+        .unwrap();
+
+    let mut response = client.post("/ratings")
+        .header(ContentType::JSON)
+        .body(r#"{"value": 1,"context":"fairness","entry":"foo","comment":"www.example.com and https://example.org", "title":"spam"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Accepted);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let rating: json::Rating = serde_json::from_str(&body_str).unwrap();
+    assert!(db
+        .shared()
+        .unwrap()
+        .load_ratings_of_place("foo")
+        .unwrap()
+        .is_empty());
+
+    let response = client.get("/ratings/pending").dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email": "scout@example.com", "password": "secret"}"#)
+        .dispatch();
+    let cookie = user_id_cookie(&response).unwrap();
+    let csrf_token = csrf_token_header(&response);
+    let mut response = client
+        .get("/ratings/pending")
+        .cookie(cookie.clone())
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let pending: Vec<json::PendingRating> = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(1, pending.len());
+    assert_eq!("foo", pending[0].place_id);
+    assert_eq!(rating.id, pending[0].rating.id);
+    assert_eq!(
+        vec![json::CommentRuleViolation::TooManyLinks],
+        pending[0].violations
+    );
+
+    let mut response = client
+        .post(format!("/ratings/pending/{}/publish", rating.id))
+        .header(csrf_token)
+        .cookie(cookie)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert_eq!(r#"{"count":1}"#, body_str);
+    let ratings = db.shared().unwrap().load_ratings_of_place("foo").unwrap();
+    assert_eq!(1, ratings.len());
+    assert_eq!(rating.id, ratings[0].id.as_str());
+    assert!(db
+        .shared()
+        .unwrap()
+        .list_pending_ratings(&Pagination::default())
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn list_changes_between_two_timestamps() {
+    let (client, db, mut search_engine, notify) = setup2();
+    let since = Timestamp::now().into_seconds();
+    db.exclusive()
+        .unwrap()
+        .create_or_update_place(Place::build().id("foo").finish())
+        .unwrap();
+    let response = client.post("/ratings")
+        .header(ContentType::JSON)
+        .body(r#"{"value": 1,"context":"fairness","entry":"foo","comment":"test", "title":"idontcare", "source":"source..."}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let new_event = usecases::NewEvent {
+        title: "x".into(),
+        start: since,
+        ..Default::default()
+    };
+    let event_id = flows::create_event(&db, &mut search_engine, &notify, None, new_event, None)
+        .unwrap()
+        .id;
+    assert!(db
+        .exclusive()
+        .unwrap()
+        .delete_event_with_matching_tags(event_id.as_str(), &[])
+        .unwrap());
+    let scheduled_event = usecases::NewEvent {
+        title: "scheduled".into(),
+        start: since,
+        publish_at: Some(since + 3600),
+        ..Default::default()
+    };
+    let scheduled_event_id = flows::create_event(
+        &db,
+        &mut search_engine,
+        &notify,
+        None,
+        scheduled_event,
+        None,
+    )
+    .unwrap()
+    .id;
+    let until = since + 10;
+
+    let mut response = client
+        .get(format!("/changes?since={}&until={}", since, until))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let changes: Vec<json::ChangeRecord> = serde_json::from_str(&body_str).unwrap();
+    let count = |entity, kind| {
+        changes
+            .iter()
+            .filter(|c| c.entity == entity && c.kind == kind)
+            .count()
+    };
+    assert_eq!(
+        1,
+        count(json::ChangedEntity::Place, json::ChangeKind::Created)
+    );
+    assert_eq!(
+        1,
+        count(json::ChangedEntity::Rating, json::ChangeKind::Created)
+    );
+    assert_eq!(
+        1,
+        count(json::ChangedEntity::Comment, json::ChangeKind::Created)
+    );
+    assert_eq!(
+        1,
+        count(json::ChangedEntity::Event, json::ChangeKind::Created)
+    );
+    assert_eq!(
+        1,
+        count(json::ChangedEntity::Event, json::ChangeKind::Deleted)
+    );
+    assert!(changes.windows(2).all(|w| w[0].at <= w[1].at));
+    assert!(changes.iter().any(|c| c.id == event_id.as_str()));
+    assert!(!changes.iter().any(|c| c.id == scheduled_event_id.as_str()));
+
+    let response = client
+        .get(format!("/changes?since={}&until={}", until, since))
+        .dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    let mut response = client
+        .get(format!("/changes?since={}&limit=2", since))
+        .dispatch();
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let changes: Vec<json::ChangeRecord> = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(2, changes.len());
+}
+
+#[test]
+fn reject_too_large_json_bodies() {
+    let mut cfg = Cfg::default();
+    cfg.body_size_limits.json = 64;
+    let (client, connections) = setup_with_cfg(cfg);
+    connections
+        .exclusive()
+        .unwrap()
+        .create_or_update_place(Place::build().id("foo").finish())
+        .unwrap();
+    let body = r#"{"value": 1,"context":"fairness","entry":"foo","comment":"test", "title":"idontcare", "source":"source..."}"#;
+    let mut response = client
+        .post("/ratings")
+        .header(ContentType::JSON)
+        .header(rocket::http::Header::new(
+            "Content-Length",
+            body.len().to_string(),
+        ))
+        .body(body)
+        .dispatch();
+    assert_eq!(response.status(), Status::PayloadTooLarge);
+    test_json(&response);
+    let error: ofdb_boundary::Error =
+        serde_json::from_str(&response.body_string().unwrap()).unwrap();
+    assert_eq!(413, error.http_status);
+    // Bodies without a Content-Length header, e.g. chunked
+    let response = client
+        .post("/ratings")
+        .header(ContentType::JSON)
+        .body(body)
+        .dispatch();
+    assert_eq!(response.status(), Status::PayloadTooLarge);
+    assert!(connections
+        .shared()
+        .unwrap()
+        .load_ratings_of_place("foo")
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn report_usage_of_daily_organization_quotas() {
+    let mut cfg = Cfg::default();
+    cfg.org_daily_quotas.events = Some(1);
+    let (client, connections) = setup_with_cfg(cfg);
+    connections
+        .exclusive()
+        .unwrap()
+        .create_org(Organization {
+            id: "org".into(),
+            name: "org".into(),
+            moderated_tags: vec![],
+            api_token: "secret".into(),
+            notification_email: None,
+            email_branding: Default::default(),
+        })
+        .unwrap();
+    let auth = || rocket::http::Header::new("Authorization", "Bearer secret");
+    let post_event = || {
+        client
+            .post("/events")
+            .header(ContentType::JSON)
+            .header(auth())
+            .body(r#"{"title":"x","start":4132508400,"created_by":"foo@bar.com"}"#)
+            .dispatch()
+            .status()
+    };
+    assert_eq!(Status::Ok, post_event());
+    assert_eq!(Status::TooManyRequests, post_event());
+
+    assert_eq!(
+        Status::Unauthorized,
+        client.get("/org/usage").dispatch().status()
+    );
+    let mut response = client.get("/org/usage").header(auth()).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+    let usage: json::OrganizationUsage =
+        serde_json::from_str(&response.body_string().unwrap()).unwrap();
+    assert_eq!(1, usage.events.used);
+    assert_eq!(Some(1), usage.events.quota);
+    assert_eq!(0, usage.places.used);
+    assert_eq!(None, usage.places.quota);
+}
+
+#[test]
+fn set_individual_daily_quotas_of_organizations() {
+    let mut cfg = Cfg::default();
+    cfg.org_daily_quotas.events = Some(1);
+    let (client, connections) = setup_with_cfg(cfg);
+    connections
+        .exclusive()
+        .unwrap()
+        .create_org(Organization {
+            id: "org".into(),
+            name: "org".into(),
+            moderated_tags: vec![],
+            api_token: "secret".into(),
+            notification_email: None,
+            email_branding: Default::default(),
+        })
+        .unwrap();
+    let admin = User {
+        email: "admin@example.com".into(),
+        email_confirmed: true,
+        password: "secret".parse::<Password>().unwrap(),
+        role: Role::Admin,
+    };
+    connections
+        .exclusive()
+        .unwrap()
+        .create_user(&admin)
+        .unwrap();
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email":"admin@example.com","password":"secret"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let csrf_token = csrf_token_header(&response);
+    let response = client
+        .put("/admin/organizations/org/quotas")
+        .header(ContentType::JSON)
+        .header(csrf_token)
+        .body(r#"{"places":2}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let mut response = client
+        .get("/admin/organizations/org/quotas")
+        .header(ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(r#"{"places":2}"#, response.body_string().unwrap());
+
+    let mut response = client
+        .get("/org/usage")
+        .header(rocket::http::Header::new("Authorization", "Bearer secret"))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let usage: json::OrganizationUsage =
+        serde_json::from_str(&response.body_string().unwrap()).unwrap();
+    // The default applies to missing quotas
+    assert_eq!(Some(1), usage.events.quota);
+    assert_eq!(Some(2), usage.places.quota);
+}
+
+#[test]
+fn get_one_rating() {
+    let e = Place::build().id("foo").finish();
+    let (client, connections, mut search_engine, _) = setup2();
+    connections
+        .exclusive()
+        .unwrap()
+        .create_or_update_place(e)
+        .unwrap();
+    flows::create_rating(
+        &connections,
+        &mut search_engine,
+        &DummyNotifyGW,
+        usecases::NewPlaceRating {
+            context: ofdb_boundary::RatingContext::Humanity,
+            value: ofdb_boundary::RatingValue::from(2),
+            user: None,
+            title: "title".into(),
+            entry: "foo".into(),
+            comment: "bla".into(),
+            source: Some("blabla".into()),
+        },
+    )
+    .unwrap();
+    let rid = connections
+        .shared()
+        .unwrap()
+        .load_ratings_of_place("foo")
+        .unwrap()[0]
+        .id
+        .clone();
+    let req = client.get(format!("/ratings/{}", rid));
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    test_json(&response);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert_eq!(body_str.as_str().chars().next().unwrap(), '[');
+    let ratings: Vec<json::Rating> = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(ratings[0].comments.len(), 1);
+    assert_eq!(ratings[0].id, rid.to_string());
+}
+
+#[test]
+fn ratings_with_and_without_source() {
+    let e1 = Place::build().id("foo").finish();
+    let e2 = Place::build().id("bar").finish();
+    let (client, connections, mut search_engine, _) = setup2();
+    connections
+        .exclusive()
+        .unwrap()
+        .create_or_update_place(e1)
+        .unwrap();
+    connections
         .exclusive()
         .unwrap()
         .create_or_update_place(e2)
@@ -1302,6 +2930,7 @@ fn ratings_with_and_without_source() {
     flows::create_rating(
         &connections,
         &mut search_engine,
+        &DummyNotifyGW,
         usecases::NewPlaceRating {
             context: ofdb_boundary::RatingContext::Humanity,
             value: ofdb_boundary::RatingValue::from(2),
@@ -1316,6 +2945,7 @@ fn ratings_with_and_without_source() {
     flows::create_rating(
         &connections,
         &mut search_engine,
+        &DummyNotifyGW,
         usecases::NewPlaceRating {
             context: ofdb_boundary::RatingContext::Humanity,
             value: ofdb_boundary::RatingValue::from(2),
@@ -1360,6 +2990,11 @@ fn user_id_cookie(response: &Response) -> Option<Cookie<'static>> {
     cookie_from_response(response, COOKIE_EMAIL_KEY)
 }
 
+pub fn csrf_token_header(response: &Response) -> rocket::http::Header<'static> {
+    let cookie = cookie_from_response(response, COOKIE_CSRF_TOKEN_KEY).expect("CSRF token");
+    rocket::http::Header::new(CSRF_TOKEN_HEADER, cookie.value().to_string())
+}
+
 #[test]
 fn post_user() {
     let (client, _) = setup();
@@ -1447,6 +3082,45 @@ fn login_logout_succeeds() {
     assert!(cookie.value().is_empty());
 }
 
+#[test]
+fn reject_session_of_deleted_user() {
+    let (client, db) = setup();
+    db.exclusive()
+        .unwrap()
+        .create_user(&User {
+            email: "foo@bar".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::Guest,
+        })
+        .unwrap();
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email": "foo@bar", "password": "secret"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let cookie = user_id_cookie(&response).expect("login cookie");
+    let csrf_token = csrf_token_header(&response);
+
+    let response = client
+        .delete("/users/foo@bar")
+        .header(csrf_token)
+        .cookie(cookie.clone())
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let removed_cookie = user_id_cookie(&response).expect("removed cookie");
+    assert!(removed_cookie.value().is_empty());
+
+    // Reuse the cookie of the deleted user
+    let response = client
+        .get("/users/current")
+        .header(ContentType::JSON)
+        .cookie(cookie)
+        .dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+}
+
 #[test]
 #[cfg(feature = "jwt")]
 fn login_logout_succeeds_jwt() {
@@ -1584,9 +3258,11 @@ fn subscribe_to_bbox() {
         .body(r#"{"email": "foo@bar", "password": "secret"}"#)
         .dispatch();
     let cookie = user_id_cookie(&response).unwrap();
+    let csrf_token = csrf_token_header(&response);
     let response = client
         .post("/subscribe-to-bbox")
         .header(ContentType::JSON)
+        .header(csrf_token)
         .cookie(cookie)
         .body(r#"[{"lat":-10.0,"lng":-10.0},{"lat":10.0,"lng":10.0}]"#)
         .dispatch();
@@ -1594,19 +3270,368 @@ fn subscribe_to_bbox() {
 }
 
 #[test]
-fn recently_changed_entries() {
-    // Check that the requests succeeds on an empty database just
-    // to verify that the literal SQL query that is not verified
-    // at compile-time still matches the current database schema!
+fn subscribe_to_bbox_with_weekly_digest() {
     let (client, db) = setup();
+    db.exclusive()
+        .unwrap()
+        .create_user(&User {
+            email: "foo@bar".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::Guest,
+        })
+        .unwrap();
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email": "foo@bar", "password": "secret"}"#)
+        .dispatch();
+    let cookie = user_id_cookie(&response).unwrap();
+    let csrf_token = csrf_token_header(&response);
+    let subscribe = |digest: &str| {
+        client
+            .post(format!("/subscribe-to-bbox?digest={}", digest))
+            .header(ContentType::JSON)
+            .header(csrf_token.clone())
+            .cookie(cookie.clone())
+            .body(r#"[{"lat":-10.0,"lng":-10.0},{"lat":10.0,"lng":10.0}]"#)
+            .dispatch()
+            .status()
+    };
+    assert_eq!(Status::BadRequest, subscribe("hourly"));
+    assert_eq!(Status::Ok, subscribe("weekly"));
 
-    let old_entries = vec![Place::build().id("old").finish()];
-    for e in old_entries {
-        db.exclusive().unwrap().create_or_update_place(e).unwrap();
-    }
-
-    // Resolution of time stamps in the query is 1 sec
-    // TODO: Don't waste time by sleeping
+    let mut response = client
+        .get("/bbox-subscriptions")
+        .cookie(cookie.clone())
+        .dispatch();
+    assert_eq!(Status::Ok, response.status());
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains(r#""digest":"weekly""#));
+}
+
+#[test]
+fn reject_cookie_authenticated_requests_without_csrf_token() {
+    let (client, db) = setup();
+    db.exclusive()
+        .unwrap()
+        .create_user(&User {
+            email: "foo@bar".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::Guest,
+        })
+        .unwrap();
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email": "foo@bar", "password": "secret"}"#)
+        .dispatch();
+    let csrf_token = csrf_token_header(&response);
+    let subscribe = |csrf_token: Option<rocket::http::Header<'static>>| {
+        let mut req = client
+            .post("/subscribe-to-bbox")
+            .header(ContentType::JSON)
+            .body(r#"[{"lat":-10.0,"lng":-10.0},{"lat":10.0,"lng":10.0}]"#);
+        if let Some(csrf_token) = csrf_token {
+            req.add_header(csrf_token);
+        }
+        req.dispatch().status()
+    };
+    assert_eq!(Status::Forbidden, subscribe(None));
+    assert_eq!(
+        Status::Forbidden,
+        subscribe(Some(rocket::http::Header::new(
+            CSRF_TOKEN_HEADER,
+            "invalid"
+        )))
+    );
+    assert_eq!(Status::Ok, subscribe(Some(csrf_token.clone())));
+
+    // Safe requests don't need a token
+    let response = client.get("/bbox-subscriptions").dispatch();
+    assert_eq!(Status::Ok, response.status());
+
+    let mut response = client.get("/csrf-token").dispatch();
+    assert_eq!(Status::Ok, response.status());
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let token: ofdb_boundary::CsrfToken = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(csrf_token.value(), token.token);
+}
+
+#[test]
+fn place_notes_are_only_visible_for_scouts() {
+    let (client, db) = setup();
+    db.exclusive()
+        .unwrap()
+        .create_user(&User {
+            email: "scout@example.com".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::Scout,
+        })
+        .unwrap();
+    db.exclusive()
+        .unwrap()
+        .create_org(Organization {
+            id: "org".into(),
+            name: "org".into(),
+            moderated_tags: vec![],
+            api_token: "org".into(),
+            notification_email: None,
+            email_branding: Default::default(),
+        })
+        .unwrap();
+    let place = Place::build().id("noted").title("noted").finish();
+    db.exclusive()
+        .unwrap()
+        .create_or_update_place(place)
+        .unwrap();
+
+    let response = client
+        .post("/places/noted/notes")
+        .header(ContentType::JSON)
+        .body(r#"{"text": "Owner contacted"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email": "scout@example.com", "password": "secret"}"#)
+        .dispatch();
+    let cookie = user_id_cookie(&response).unwrap();
+    let csrf_token = csrf_token_header(&response);
+    let mut response = client
+        .post("/places/noted/notes")
+        .header(ContentType::JSON)
+        .header(csrf_token.clone())
+        .cookie(cookie.clone())
+        .body(r#"{"text": "Owner contacted"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let note: json::PlaceNote = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(Some("scout@example.com".into()), note.created.by);
+    let response = client
+        .post("/places/noted/notes")
+        .header(ContentType::JSON)
+        .header(csrf_token)
+        .cookie(cookie.clone())
+        .body(format!(
+            r#"{{"parent": "{}", "text": "No answer yet"}}"#,
+            note.id
+        ))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    let mut response = client
+        .get("/places/noted/history")
+        .cookie(cookie)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let history: json::PlaceHistory = serde_json::from_str(&body_str).unwrap();
+    assert_eq!(2, history.notes.len());
+    assert_eq!("Owner contacted", history.notes[0].text);
+    assert_eq!(Some(note.id), history.notes[1].parent);
+
+    let mut response = client
+        .get("/places/noted/history")
+        .header(rocket::http::Header::new("Authorization", "Bearer org"))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(!body_str.contains("Owner contacted"));
+}
+
+#[test]
+fn confirm_that_a_place_is_still_valid() {
+    let (client, db) = setup();
+    db.exclusive()
+        .unwrap()
+        .create_user(&User {
+            email: "scout@example.com".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::Scout,
+        })
+        .unwrap();
+    let place = Place::build().id("stale").title("stale").finish();
+    db.exclusive()
+        .unwrap()
+        .create_or_update_place(place)
+        .unwrap();
+    let nonce = Nonce::new();
+    for (nonce, sent_at) in vec![(nonce.clone(), 1000), (Nonce::new(), 2000)] {
+        db.exclusive()
+            .unwrap()
+            .create_place_confirmation_request(&PlaceConfirmationRequest {
+                place_id: "stale".into(),
+                nonce,
+                sent_to: "owner@example.com".into(),
+                sent_at: TimestampMs::from_inner(sent_at),
+                answered_at: None,
+                flagged_at: None,
+            })
+            .unwrap();
+    }
+    db.exclusive()
+        .unwrap()
+        .flag_unanswered_place_confirmation_requests(
+            TimestampMs::from_inner(3000),
+            TimestampMs::from_inner(4000),
+        )
+        .unwrap();
+
+    let response = client
+        .post("/places/stale/confirm-still-valid")
+        .header(ContentType::JSON)
+        .body(r#"{"token": "invalid"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    let response = client
+        .post("/places/stale/confirm-still-valid")
+        .header(ContentType::JSON)
+        .body(format!(r#"{{"token": "{}"}}"#, nonce))
+        .dispatch();
+    assert_eq!(response.status(), Status::NoContent);
+
+    let response = client.get("/places/unconfirmed").dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email": "scout@example.com", "password": "secret"}"#)
+        .dispatch();
+    let cookie = user_id_cookie(&response).unwrap();
+    let mut response = client.get("/places/unconfirmed").cookie(cookie).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let unanswered: Vec<json::UnansweredConfirmationRequest> =
+        serde_json::from_str(&body_str).unwrap();
+    assert_eq!(1, unanswered.len());
+    assert_eq!("stale", unanswered[0].place_id);
+    assert_eq!(2000, unanswered[0].sent_at);
+}
+
+#[test]
+fn subscribe_to_organization() {
+    let (client, db) = setup();
+    db.exclusive()
+        .unwrap()
+        .create_user(&User {
+            email: "foo@bar".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::Guest,
+        })
+        .unwrap();
+    db.exclusive()
+        .unwrap()
+        .create_org(Organization {
+            id: "org".into(),
+            name: "org".into(),
+            moderated_tags: vec!["org-tag".into()],
+            api_token: "org".into(),
+            notification_email: None,
+            email_branding: Default::default(),
+        })
+        .unwrap();
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email": "foo@bar", "password": "secret"}"#)
+        .dispatch();
+    let cookie = user_id_cookie(&response).unwrap();
+    let csrf_token = csrf_token_header(&response);
+    let response = client
+        .post("/organizations/unknown/subscription")
+        .header(csrf_token.clone())
+        .cookie(cookie.clone())
+        .dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+    let response = client
+        .post("/organizations/org/subscription")
+        .header(csrf_token.clone())
+        .cookie(cookie.clone())
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    // Subscribing twice has no effect
+    let response = client
+        .post("/organizations/org/subscription")
+        .header(csrf_token.clone())
+        .cookie(cookie.clone())
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    let mut response = client
+        .get("/organization-subscriptions")
+        .cookie(cookie.clone())
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let subscriptions: Vec<json::OrganizationSubscription> =
+        serde_json::from_str(&body_str).unwrap();
+    assert_eq!(1, subscriptions.len());
+    assert_eq!("org", subscriptions[0].org_id);
+
+    // The subscription is inactive until the consent has been confirmed
+    let tags = vec!["org-tag".to_string()];
+    assert!(
+        usecases::email_addresses_of_subscribers(&*db.shared().unwrap(), None, &tags)
+            .unwrap()
+            .is_empty()
+    );
+    let email_nonce = EmailNonce {
+        email: "foo@bar".into(),
+        nonce: Nonce::new(),
+    };
+    db.exclusive()
+        .unwrap()
+        .request_notification_consent(&email_nonce, Timestamp::now())
+        .unwrap();
+    let response = client
+        .post("/confirm-subscriptions")
+        .header(ContentType::JSON)
+        .body(format!(
+            "{{\"token\":\"{}\"}}",
+            email_nonce.encode_to_string()
+        ))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        vec!["foo@bar".to_string()],
+        usecases::email_addresses_of_subscribers(&*db.shared().unwrap(), None, &tags).unwrap()
+    );
+
+    let response = client
+        .delete("/organizations/org/subscription")
+        .header(csrf_token)
+        .cookie(cookie)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert!(
+        usecases::email_addresses_of_subscribers(&*db.shared().unwrap(), None, &tags)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn recently_changed_entries() {
+    // Check that the requests succeeds on an empty database just
+    // to verify that the literal SQL query that is not verified
+    // at compile-time still matches the current database schema!
+    let (client, db) = setup();
+
+    let old_entries = vec![Place::build().id("old").finish()];
+    for e in old_entries {
+        db.exclusive().unwrap().create_or_update_place(e).unwrap();
+    }
+
+    // Resolution of time stamps in the query is 1 sec
+    // TODO: Don't waste time by sleeping
     std::thread::sleep(std::time::Duration::from_millis(1001));
     let since_inclusive = Timestamp::now();
     let recent_entries = vec![Place::build().id("recent").finish()];
@@ -1898,6 +3923,16 @@ fn entries_export_csv() {
             source: None,
         })
         .unwrap();
+    db.exclusive()
+        .unwrap()
+        .create_comment(Comment {
+            id: "567".into(),
+            rating_id: "123".into(),
+            created_at: Timestamp::from_seconds(124),
+            archived_at: None,
+            text: "comment1".into(),
+        })
+        .unwrap();
 
     let places = db.shared().unwrap().all_places().unwrap();
     for (place, status) in &places {
@@ -1907,7 +3942,7 @@ fn entries_export_csv() {
             .load_ratings_of_place(place.id.as_ref())
             .unwrap();
         search_engine
-            .add_or_update_place(&place, *status, &place.avg_ratings(&ratings))
+            .add_or_update_place(&place, *status, None, &place.avg_ratings(&ratings))
             .unwrap();
     }
     search_engine.flush_index().unwrap();
@@ -1930,10 +3965,10 @@ fn entries_export_csv() {
     }
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
     //eprintln!("{}", body_str);
-    assert!(body_str.starts_with("id,created_at,created_by,version,title,description,lat,lng,street,zip,city,country,state,homepage,contact_name,contact_email,contact_phone,opening_hours,founded_on,categories,tags,license,image_url,image_link_url,avg_rating\n"));
-    assert!(body_str.contains(&format!("entry1,1111,user@example.com,0,title1,desc1,{lat},{lng},street1,zip1,city1,country1,state1,http://homepage1/,John Smith,john.smith@example.com,0123456789,24/7,1945-10-24,\"{cat1},{cat2}\",\"bla,bli\",license1,https://img/,\"https://img,link/\",0.25\n", lat = LatCoord::from_deg(0.1).to_deg(), lng = LngCoord::from_deg(0.2).to_deg(), cat1 = Category::ID_NON_PROFIT, cat2 = Category::ID_COMMERCIAL)));
+    assert!(body_str.starts_with("id,created_at,created_by,version,title,description,lat,lng,street,zip,city,country,state,homepage,contact_name,contact_email,contact_phone,opening_hours,founded_on,categories,tags,license,image_url,image_link_url,avg_rating,ratings_diversity,ratings_fairness,ratings_humanity,ratings_renewable,ratings_solidarity,ratings_transparency,comments\n"));
+    assert!(body_str.contains(&format!("entry1,1111,user@example.com,0,title1,desc1,{lat},{lng},street1,zip1,city1,country1,state1,http://homepage1/,John Smith,john.smith@example.com,0123456789,24/7,1945-10-24,\"{cat1},{cat2}\",\"bla,bli\",license1,https://img/,\"https://img,link/\",0.25,2,0,0,0,0,0,1\n", lat = LatCoord::from_deg(0.1).to_deg(), lng = LngCoord::from_deg(0.2).to_deg(), cat1 = Category::ID_NON_PROFIT, cat2 = Category::ID_COMMERCIAL)));
     assert!(body_str.contains(&format!(
-        "entry2,2222,,0,,,0.0,0.0,,,,,,,,,,,,{cat},,,,,0.0\n",
+        "entry2,2222,,0,,,0.0,0.0,,,,,,,,,,,,{cat},,,,,0.0,0,0,0,0,0,0,0\n",
         cat = Category::ID_NON_PROFIT
     )));
     assert!(!body_str.contains("entry3"));
@@ -1956,10 +3991,10 @@ fn entries_export_csv() {
     }
     let body_str = response.body().and_then(|b| b.into_string()).unwrap();
     //eprintln!("{}", body_str);
-    assert!(body_str.starts_with("id,created_at,created_by,version,title,description,lat,lng,street,zip,city,country,state,homepage,contact_name,contact_email,contact_phone,opening_hours,founded_on,categories,tags,license,image_url,image_link_url,avg_rating\n"));
-    assert!(body_str.contains(&format!("entry1,1111,,0,title1,desc1,{lat},{lng},street1,zip1,city1,country1,state1,http://homepage1/,John Smith,john.smith@example.com,0123456789,24/7,1945-10-24,\"{cat1},{cat2}\",\"bla,bli\",license1,https://img/,\"https://img,link/\",0.25\n", lat = LatCoord::from_deg(0.1).to_deg(), lng = LngCoord::from_deg(0.2).to_deg(), cat1 = Category::ID_NON_PROFIT, cat2 = Category::ID_COMMERCIAL)));
+    assert!(body_str.starts_with("id,created_at,created_by,version,title,description,lat,lng,street,zip,city,country,state,homepage,contact_name,contact_email,contact_phone,opening_hours,founded_on,categories,tags,license,image_url,image_link_url,avg_rating,ratings_diversity,ratings_fairness,ratings_humanity,ratings_renewable,ratings_solidarity,ratings_transparency,comments\n"));
+    assert!(body_str.contains(&format!("entry1,1111,,0,title1,desc1,{lat},{lng},street1,zip1,city1,country1,state1,http://homepage1/,John Smith,john.smith@example.com,0123456789,24/7,1945-10-24,\"{cat1},{cat2}\",\"bla,bli\",license1,https://img/,\"https://img,link/\",0.25,2,0,0,0,0,0,1\n", lat = LatCoord::from_deg(0.1).to_deg(), lng = LngCoord::from_deg(0.2).to_deg(), cat1 = Category::ID_NON_PROFIT, cat2 = Category::ID_COMMERCIAL)));
     assert!(body_str.contains(&format!(
-        "entry2,2222,,0,,,0.0,0.0,,,,,,,,,,,,{cat},,,,,0.0\n",
+        "entry2,2222,,0,,,0.0,0.0,,,,,,,,,,,,{cat},,,,,0.0,0,0,0,0,0,0,0\n",
         cat = Category::ID_NON_PROFIT
     )));
     assert!(!body_str.contains("entry3"));
@@ -1977,12 +4012,142 @@ fn entries_export_csv() {
     assert_eq!(response.status(), Status::Unauthorized);
 }
 
+#[test]
+fn ratings_export_csv() {
+    let (client, db, mut search_engine, _) = setup2();
+    db.exclusive()
+        .unwrap()
+        .create_org(Organization {
+            id: "org".into(),
+            name: "org".into(),
+            moderated_tags: vec![],
+            api_token: "secret".into(),
+            notification_email: None,
+            email_branding: Default::default(),
+        })
+        .unwrap();
+    let places = vec![
+        Place::build()
+            .id("inside")
+            .title("Bioladen")
+            .pos(MapPoint::from_lat_lng_deg(0.1, 0.2))
+            .finish(),
+        Place::build()
+            .id("outside")
+            .pos(MapPoint::from_lat_lng_deg(2.0, 2.0))
+            .finish(),
+    ];
+    for place in places {
+        db.exclusive()
+            .unwrap()
+            .create_or_update_place(place.clone())
+            .unwrap();
+        db.exclusive()
+            .unwrap()
+            .create_rating(Rating {
+                id: format!("rating-{}", place.id).into(),
+                place_id: place.id.clone(),
+                created_at: Timestamp::from_seconds(100),
+                archived_at: None,
+                title: "Very fair".into(),
+                value: RatingValue::from(2),
+                context: RatingContext::Fairness,
+                source: Some("visit".into()),
+            })
+            .unwrap();
+        search_engine
+            .add_or_update_place(&place, ReviewStatus::Created, None, &Default::default())
+            .unwrap();
+    }
+    search_engine.flush_index().unwrap();
+    for (id, created_at, text) in &[("c2", 300, "Still fair"), ("c1", 200, "Fair prices")] {
+        db.exclusive()
+            .unwrap()
+            .create_comment(Comment {
+                id: (*id).into(),
+                rating_id: "rating-inside".into(),
+                created_at: Timestamp::from_seconds(*created_at),
+                archived_at: None,
+                text: (*text).into(),
+            })
+            .unwrap();
+    }
+
+    let response = client.get("/export/ratings.csv?bbox=-1,-1,1,1").dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let mut response = client
+        .get("/export/ratings.csv?bbox=-1,-1,1,1")
+        .header(rocket::http::Header::new("Authorization", "Bearer secret"))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert_eq!(
+        "id,place_id,place_title,created_at,title,context,value,source,comments\n\
+         rating-inside,inside,Bioladen,100,Very fair,fairness,2,visit,\"Fair prices\nStill fair\"\n",
+        body_str
+    );
+}
+
+#[test]
+fn entries_export_csv_as_of() {
+    let (client, db) = setup();
+    db.exclusive()
+        .unwrap()
+        .create_user(&User {
+            email: "scout@example.com".into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role: Role::Scout,
+        })
+        .unwrap();
+    let mut place = Place::build()
+        .id("entry")
+        .title("old title")
+        .tags(vec![Category::TAG_NON_PROFIT])
+        .finish();
+    place.created.at = TimestampMs::from_seconds(1000);
+    db.exclusive()
+        .unwrap()
+        .create_or_update_place(place.clone())
+        .unwrap();
+    place.title = "new title".into();
+    place.revision = place.revision.next();
+    place.created.at = TimestampMs::from_seconds(2000);
+    db.exclusive()
+        .unwrap()
+        .create_or_update_place(place)
+        .unwrap();
+
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email": "scout@example.com", "password": "secret"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    let mut response = client
+        .get("/export/entries.csv?bbox=-1,-1,1,1&as_of=1500")
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(body_str.contains("entry,1000,,0,old title,"));
+    assert!(!body_str.contains("new title"));
+
+    let mut response = client
+        .get("/export/entries.csv?bbox=-1,-1,1,1&as_of=500")
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    assert!(!body_str.contains("entry"));
+}
+
 #[test]
 fn search_duplicates() {
     let (client, db) = setup();
-    let res = client.post("/entries")
+    let res = client.post("/entries?confirm_position=true")
                     .header(ContentType::JSON)
-                    .body(r#"{"title":"foo","description":"bla","lat":0.0,"lng":0.0,"categories":["x"],"license":"CC0-1.0","tags":[]}"#)
+                    .body(r#"{"title":"foo","description":"bla","lat":0.0,"lng":0.0,"categories":[],"license":"CC0-1.0","tags":[]}"#)
                     .dispatch();
     assert_eq!(res.status(), Status::Ok);
     let (place, _) = db
@@ -2006,6 +4171,40 @@ fn search_duplicates() {
     assert_eq!(place.id.to_string(), duplicate_places.first().unwrap().id);
 }
 
+#[test]
+fn get_duplicates_of_places() {
+    let (client, db) = setup();
+    for (title, lat, lng) in &[("foo", 0.0, 0.0), ("foO", 0.0005, 0.0005), ("fo", 1.0, 1.0)] {
+        let res = client
+            .post("/entries?confirm_position=true")
+            .header(ContentType::JSON)
+            .body(format!(
+                r#"{{"title":"{}","description":"bla","lat":{},"lng":{},"categories":[],"license":"CC0-1.0","tags":[]}}"#,
+                title, lat, lng
+            ))
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+    }
+    let places = db.shared().unwrap().all_places().unwrap();
+    let id = |title: &str| {
+        places
+            .iter()
+            .find(|(p, _)| p.title == title)
+            .map(|(p, _)| p.id.to_string())
+            .unwrap()
+    };
+    let mut res = client
+        .get(format!("/duplicates/{},{}", id("foo"), id("fo")))
+        .dispatch();
+    assert_eq!(res.status(), Status::Ok);
+    test_json(&res);
+    let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+    assert_eq!(
+        format!(r#"[["{}","{}","SimilarChars"]]"#, id("foo"), id("foO")),
+        body_str
+    );
+}
+
 mod with_captcha_protection_enabled {
     use super::*;
 
@@ -2020,7 +4219,7 @@ mod with_captcha_protection_enabled {
         let (client, _) = captcha_setup();
         let req = client.post("/entries")
                         .header(ContentType::JSON)
-                        .body(r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":["x"],"license":"CC0-1.0","tags":[]}"#);
+                        .body(r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":[],"license":"CC0-1.0","tags":[]}"#);
         let response = req.dispatch();
         assert_eq!(response.status(), Status::Unauthorized);
     }
@@ -2029,10 +4228,10 @@ mod with_captcha_protection_enabled {
     fn create_place_with_valid_captcha_cookie() {
         let (client, db) = captcha_setup();
         let cookie = get_captcha_cookie(&client).unwrap();
-        let req = client.post("/entries")
+        let req = client.post("/entries?confirm_position=true")
                         .header(ContentType::JSON)
                         .cookie(cookie)
-                        .body(r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":["x"],"license":"CC0-1.0","tags":[]}"#);
+                        .body(r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":[],"license":"CC0-1.0","tags":[]}"#);
         let mut response = req.dispatch();
         assert_eq!(response.status(), Status::Ok);
         test_json(&response);
@@ -2044,3 +4243,433 @@ mod with_captcha_protection_enabled {
         assert_eq!(body_str, format!("\"{}\"", eid));
     }
 }
+
+#[test]
+fn suggest_tags_for_a_new_place() {
+    let (client, db) = setup();
+    db.exclusive()
+        .unwrap()
+        .replace_tag_cooccurrences(&[
+            TagCooccurrence {
+                word: "bioladen".into(),
+                tag: "bio".into(),
+                count: 4,
+                word_count: 4,
+            },
+            TagCooccurrence {
+                word: "bioladen".into(),
+                tag: "shop".into(),
+                count: 2,
+                word_count: 4,
+            },
+        ])
+        .unwrap();
+    let mut response = client
+        .post("/tags/suggest")
+        .header(ContentType::JSON)
+        .body(r#"{"title": "Bioladen", "limit": 1}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let suggestions: Vec<json::TagSuggestion> =
+        serde_json::from_str(&response.body_string().unwrap()).unwrap();
+    assert_eq!(1, suggestions.len());
+    assert_eq!("bio", suggestions[0].tag);
+    assert!((suggestions[0].score - 1.0).abs() < 0.001);
+}
+
+#[test]
+fn queue_announcement_for_users_who_opted_in() {
+    let (client, db) = setup();
+    for (email, role) in vec![
+        ("user@example.com", Role::User),
+        ("other@example.com", Role::User),
+        ("admin@example.com", Role::Admin),
+    ] {
+        let user = User {
+            email: email.into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role,
+        };
+        db.exclusive().unwrap().create_user(&user).unwrap();
+    }
+    db.exclusive()
+        .unwrap()
+        .set_wants_announcements("other@example.com", true)
+        .unwrap();
+    let announcement = r#"{"subject":"News","body":"Hello"}"#;
+
+    // Only admins are allowed to send announcements
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email":"user@example.com","password":"secret"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let csrf_token = csrf_token_header(&response);
+    let response = client
+        .post("/admin/announcements")
+        .header(ContentType::JSON)
+        .header(csrf_token)
+        .body(announcement)
+        .dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+    assert!(db
+        .shared()
+        .unwrap()
+        .load_unsent_emails(10)
+        .unwrap()
+        .is_empty());
+
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email":"admin@example.com","password":"secret"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let csrf_token = csrf_token_header(&response);
+    let mut response = client
+        .post("/admin/announcements")
+        .header(ContentType::JSON)
+        .header(csrf_token)
+        .body(announcement)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let queued: json::QueuedAnnouncement =
+        serde_json::from_str(&response.body_string().unwrap()).unwrap();
+    assert_eq!(1, queued.recipients);
+    let queued_emails = db.shared().unwrap().load_unsent_emails(10).unwrap();
+    assert_eq!(1, queued_emails.len());
+    assert_eq!("other@example.com", queued_emails[0].recipient.as_str());
+    assert_eq!("News", queued_emails[0].subject);
+}
+
+#[test]
+fn merge_duplicate_user_accounts() {
+    let (client, db) = setup();
+    for (email, role) in vec![
+        ("old@example.com", Role::User),
+        ("new@example.com", Role::User),
+        ("admin@example.com", Role::Admin),
+    ] {
+        let user = User {
+            email: email.into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role,
+        };
+        db.exclusive().unwrap().create_user(&user).unwrap();
+    }
+    let merge = r#"{"from":"old@example.com","into":"new@example.com"}"#;
+
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email":"new@example.com","password":"secret"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let response = client
+        .post("/admin/users/merge")
+        .header(ContentType::JSON)
+        .header(csrf_token_header(&response))
+        .body(merge)
+        .dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email":"admin@example.com","password":"secret"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let response = client
+        .post("/admin/users/merge")
+        .header(ContentType::JSON)
+        .header(csrf_token_header(&response))
+        .body(merge)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let db = db.shared().unwrap();
+    assert!(db
+        .try_get_user_by_email("old@example.com")
+        .unwrap()
+        .is_none());
+    assert!(db
+        .try_get_user_by_email("new@example.com")
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn get_tags_paginated() {
+    let (client, db) = setup();
+    for tag in &["foo", "bar", "baz"] {
+        db.exclusive()
+            .unwrap()
+            .create_tag_if_it_does_not_exist(&Tag { id: (*tag).into() })
+            .unwrap();
+    }
+    let mut response = client.get("/tags?offset=1&limit=1").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        Some("3"),
+        response.headers().get_one(super::TOTAL_COUNT_HEADER)
+    );
+    assert_eq!(r#"["baz"]"#, response.body_string().unwrap());
+
+    let mut response = client.get("/tags").dispatch();
+    assert_eq!(r#"["bar","baz","foo"]"#, response.body_string().unwrap());
+}
+
+#[test]
+fn get_tags_filtered_by_prefix_and_count() {
+    let (client, db) = setup();
+    for tag in &["foo", "bar", "foo_bar"] {
+        db.exclusive()
+            .unwrap()
+            .create_tag_if_it_does_not_exist(&Tag { id: (*tag).into() })
+            .unwrap();
+    }
+    let response = client
+        .post("/entries?confirm_position=true")
+        .header(ContentType::JSON)
+        .body(r#"{"title":"foo","description":"blablabla","lat":0.0,"lng":0.0,"categories":[],"license":"CC0-1.0","tags":["foobaz"]}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    let mut response = client.get("/tags?prefix=Foo").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        Some("3"),
+        response.headers().get_one(super::TOTAL_COUNT_HEADER)
+    );
+    assert_eq!(
+        r#"["foo","foo_bar","foobaz"]"#,
+        response.body_string().unwrap()
+    );
+
+    // The underscore is not a wildcard
+    let mut response = client.get("/tags?prefix=foo_").dispatch();
+    assert_eq!(r#"["foo_bar"]"#, response.body_string().unwrap());
+
+    let mut response = client.get("/tags?min_count=1").dispatch();
+    assert_eq!(
+        Some("1"),
+        response.headers().get_one(super::TOTAL_COUNT_HEADER)
+    );
+    assert_eq!(r#"["foobaz"]"#, response.body_string().unwrap());
+
+    let mut response = client.get("/tags?prefix=bar&min_count=1").dispatch();
+    assert_eq!(r#"[]"#, response.body_string().unwrap());
+}
+
+#[test]
+fn revalidate_cached_tags_and_categories() {
+    let (client, db) = setup();
+    let create_tag = |tag: &str| {
+        db.exclusive()
+            .unwrap()
+            .create_tag_if_it_does_not_exist(&Tag { id: tag.into() })
+            .unwrap();
+    };
+    create_tag("foo");
+    let response = client.get("/tags").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        Some("public, no-cache"),
+        response.headers().get_one("Cache-Control")
+    );
+    let etag = response.headers().get_one("ETag").unwrap().to_string();
+
+    let mut response = client
+        .get("/tags")
+        .header(rocket::http::Header::new("If-None-Match", etag.clone()))
+        .dispatch();
+    assert_eq!(response.status(), Status::NotModified);
+    assert_eq!(Some(etag.as_str()), response.headers().get_one("ETag"));
+    assert!(response.body_string().is_none());
+
+    create_tag("bar");
+    let mut response = client
+        .get("/tags")
+        .header(rocket::http::Header::new("If-None-Match", etag.clone()))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_ne!(Some(etag.as_str()), response.headers().get_one("ETag"));
+    assert_eq!(r#"["bar","foo"]"#, response.body_string().unwrap());
+
+    let response = client.get("/categories").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let etag = response.headers().get_one("ETag").unwrap().to_string();
+    let response = client
+        .get("/categories")
+        .header(rocket::http::Header::new("If-None-Match", etag))
+        .dispatch();
+    assert_eq!(response.status(), Status::NotModified);
+}
+
+#[test]
+fn get_search_index_metrics_as_admin() {
+    let (client, db, mut search_engine, _) = setup2();
+    for (email, role) in vec![
+        ("user@example.com", Role::User),
+        ("admin@example.com", Role::Admin),
+    ] {
+        let user = User {
+            email: email.into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role,
+        };
+        db.exclusive().unwrap().create_user(&user).unwrap();
+    }
+    let place = Place::build().id("metrics").title("metrics").finish();
+    search_engine
+        .add_or_update_place(&place, ReviewStatus::Created, None, &place.avg_ratings(&[]))
+        .unwrap();
+    search_engine.flush_index().unwrap();
+    search_engine.remove_by_id(&place.id).unwrap();
+
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email":"user@example.com","password":"secret"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let response = client.get("/admin/metrics").dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email":"admin@example.com","password":"secret"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let mut response = client.get("/admin/metrics").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let metrics: json::ServerMetrics =
+        serde_json::from_str(&response.body_string().unwrap()).unwrap();
+    assert!(metrics.search_index.commits >= 1);
+    assert!(metrics.search_index.segments >= 1);
+    assert_eq!(1, metrics.search_index.pending_docs);
+    assert!(!metrics.search_index.merges_behind);
+}
+
+#[test]
+fn search_with_configured_result_limits() {
+    use crate::infrastructure::cfg::{ResultLimit, ResultLimits};
+    let (client, _) = setup_with_cfg(Cfg {
+        result_limits: ResultLimits {
+            search: ResultLimit { default: 2, max: 3 },
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    let result_limit = |url: &str| -> Option<String> {
+        let response = client.get(url).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        response
+            .headers()
+            .get_one(RESULT_LIMIT_HEADER)
+            .map(ToString::to_string)
+    };
+    assert_eq!(
+        Some("2"),
+        result_limit("/search?bbox=-10,-10,10,10").as_deref()
+    );
+    assert_eq!(
+        Some("1"),
+        result_limit("/search?bbox=-10,-10,10,10&limit=1").as_deref()
+    );
+    assert_eq!(
+        Some("3"),
+        result_limit("/search?bbox=-10,-10,10,10&limit=10").as_deref()
+    );
+    assert_eq!(Some("1000"), result_limit("/changes?since=0").as_deref());
+    let response = client.get("/search?bbox=-10,-10,10,10&limit=0").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[test]
+fn get_data_quality_report_as_admin() {
+    let (client, db) = setup();
+    for (email, role) in vec![
+        ("user@example.com", Role::User),
+        ("admin@example.com", Role::Admin),
+    ] {
+        let user = User {
+            email: email.into(),
+            email_confirmed: true,
+            password: "secret".parse::<Password>().unwrap(),
+            role,
+        };
+        db.exclusive().unwrap().create_user(&user).unwrap();
+    }
+    let mut good_place = Place::build()
+        .id("good")
+        .title("good")
+        .pos(MapPoint::from_lat_lng_deg(48.7, 9.1))
+        .tags(vec!["organic"])
+        .finish();
+    good_place.location.address = Some(Address {
+        street: Some("Hauptstraße 1".into()),
+        zip: None,
+        city: Some("Stuttgart".into()),
+        country: Some("DE".into()),
+        state: Some("BW".into()),
+    });
+    let mut bad_place = Place::build()
+        .id("bad")
+        .title("bad")
+        .tags(vec![Category::TAG_NON_PROFIT])
+        .finish();
+    bad_place.links = Some(Links {
+        homepage: Some("ftp://example.com".parse().unwrap()),
+        ..Default::default()
+    });
+    let two_years_ago = chrono::Utc::now() - chrono::Duration::days(730);
+    bad_place.created.at = TimestampMs::from(two_years_ago);
+    db.exclusive()
+        .unwrap()
+        .create_or_update_place(good_place)
+        .unwrap();
+    db.exclusive()
+        .unwrap()
+        .create_or_update_place(bad_place)
+        .unwrap();
+
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email":"user@example.com","password":"secret"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let response = client.get("/admin/data-quality").dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(r#"{"email":"admin@example.com","password":"secret"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let mut response = client.get("/admin/data-quality").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let regions: Vec<json::RegionQualityIssues> =
+        serde_json::from_str(&response.body_string().unwrap()).unwrap();
+    assert_eq!(1, regions.len());
+    assert!(regions[0].state.is_none());
+    assert_eq!(1, regions[0].places.len());
+    let place = &regions[0].places[0];
+    assert_eq!("bad", place.id);
+    assert_eq!(
+        vec![
+            json::PlaceQualityIssue::MissingAddress,
+            json::PlaceQualityIssue::InvalidUrl,
+            json::PlaceQualityIssue::ZeroCoordinates,
+            json::PlaceQualityIssue::NoTags,
+            json::PlaceQualityIssue::Unreviewed,
+        ],
+        place.issues
+    );
+}
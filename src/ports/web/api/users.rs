@@ -1,11 +1,21 @@
 use super::*;
+use crate::infrastructure::cfg::Cfg;
 
 #[post("/users", format = "application/json", data = "<u>")]
-pub fn post_user(db: sqlite::Connections, n: Notify, u: Json<usecases::NewUser>) -> Result<()> {
+pub fn post_user(
+    db: sqlite::Connections,
+    n: Notify,
+    cfg: State<Cfg>,
+    u: Json<usecases::NewUser>,
+) -> Result<()> {
     let new_user = u.into_inner();
     let user = {
-        let db = db.exclusive()?;
-        usecases::create_new_user(&*db, new_user.clone())?;
+        let mut db = db.exclusive()?;
+        let credentials = usecases::Credentials {
+            email: &new_user.email,
+            password: &new_user.password,
+        };
+        usecases::register_with_email(&mut *db, &credentials, &cfg.registration_email_domains)?;
         db.get_user_by_email(&new_user.email)?
     };
     n.user_registered_kvm(&user);
@@ -43,8 +53,16 @@ pub fn post_reset_password(
 }
 
 #[delete("/users/<email>")]
-pub fn delete_user(db: sqlite::Connections, account: Account, email: String) -> Result<()> {
+pub fn delete_user(
+    db: sqlite::Connections,
+    account: Account,
+    mut cookies: Cookies,
+    email: String,
+) -> Result<()> {
     usecases::delete_user(&*db.exclusive()?, account.email(), &email)?;
+    // End the current session
+    cookies.remove_private(Cookie::named(COOKIE_EMAIL_KEY));
+    cookies.remove(Cookie::build(COOKIE_CSRF_TOKEN_KEY, "").path("/").finish());
     Ok(Json(()))
 }
 
@@ -54,6 +72,92 @@ pub fn get_current_user(db: sqlite::Connections, account: Account) -> Result<jso
     Ok(Json(user.into()))
 }
 
+#[get("/users/current/preferences", format = "application/json")]
+pub fn get_current_user_preferences(
+    db: sqlite::Connections,
+    account: Account,
+) -> Result<json::UserPreferences> {
+    let announcements = db.shared()?.wants_announcements(account.email())?;
+    Ok(Json(json::UserPreferences { announcements }))
+}
+
+#[put(
+    "/users/current/preferences",
+    format = "application/json",
+    data = "<preferences>"
+)]
+pub fn put_current_user_preferences(
+    db: sqlite::Connections,
+    account: Account,
+    preferences: Json<json::UserPreferences>,
+) -> Result<()> {
+    let json::UserPreferences { announcements } = preferences.into_inner();
+    db.exclusive()?
+        .set_wants_announcements(account.email(), announcements)?;
+    Ok(Json(()))
+}
+
+#[get("/users/current/watches", format = "application/json")]
+pub fn get_current_user_watches(db: sqlite::Connections, account: Account) -> Result<Vec<String>> {
+    Ok(Json(
+        db.shared()?.entity_ids_watched_by_email(account.email())?,
+    ))
+}
+
+#[put("/users/current/watches/<id>")]
+pub fn put_current_user_watch(db: sqlite::Connections, account: Account, id: String) -> Result<()> {
+    usecases::watch_entity(&*db.exclusive()?, account.email(), &id)?;
+    Ok(Json(()))
+}
+
+#[delete("/users/current/watches/<id>")]
+pub fn delete_current_user_watch(
+    db: sqlite::Connections,
+    account: Account,
+    id: String,
+) -> Result<()> {
+    usecases::unwatch_entity(&*db.exclusive()?, account.email(), &id)?;
+    Ok(Json(()))
+}
+
+#[get("/users/current/tokens", format = "application/json")]
+pub fn get_current_user_tokens(
+    db: sqlite::Connections,
+    account: Account,
+) -> Result<Vec<json::PersonalApiToken>> {
+    let tokens = db.shared()?.personal_api_tokens_of_user(account.email())?;
+    Ok(Json(tokens.into_iter().map(Into::into).collect()))
+}
+
+#[post("/users/current/tokens", format = "application/json", data = "<token>")]
+pub fn post_current_user_token(
+    db: sqlite::Connections,
+    account: Account,
+    token: Json<json::NewPersonalApiToken>,
+) -> Result<json::PersonalApiToken> {
+    let json::NewPersonalApiToken { scope, label } = token.into_inner();
+    let (token, bearer_token) = usecases::create_personal_api_token(
+        &*db.exclusive()?,
+        account.email(),
+        scope.into(),
+        label,
+    )?;
+    Ok(Json(json::PersonalApiToken {
+        token: Some(bearer_token),
+        ..token.into()
+    }))
+}
+
+#[delete("/users/current/tokens/<id>")]
+pub fn delete_current_user_token(
+    db: sqlite::Connections,
+    account: Account,
+    id: String,
+) -> Result<()> {
+    usecases::revoke_personal_api_token(&*db.exclusive()?, account.email(), &id)?;
+    Ok(Json(()))
+}
+
 #[get("/users/<email>", format = "application/json", rank = 2)]
 pub fn get_user(db: sqlite::Connections, account: Account, email: String) -> Result<json::User> {
     let user = usecases::get_user(&*db.shared()?, account.email(), &email)?;
@@ -155,4 +259,205 @@ mod tests {
         assert_eq!(email_confirmed, current_user.email_confirmed);
         assert_eq!(Role::User, current_user.role.into());
     }
+
+    #[test]
+    fn block_login_of_deleted_user() {
+        let (client, db) = setup();
+        register_user(&db, "user@example.com", "secret", true);
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"user@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let csrf_token = csrf_token_header(&res);
+        let res = client
+            .delete("/users/user@example.com")
+            .header(csrf_token)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        // The data is retained during the grace period
+        assert!(db
+            .shared()
+            .unwrap()
+            .try_get_user_by_email("user@example.com")
+            .unwrap()
+            .is_some());
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"user@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status().code, 403);
+    }
+
+    #[test]
+    fn opt_in_to_announcements() {
+        let (client, db) = setup();
+        register_user(&db, "user@example.com", "secret", true);
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"user@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let csrf_token = csrf_token_header(&res);
+
+        // Announcements are disabled by default
+        let mut res = client
+            .get("/users/current/preferences")
+            .header(ContentType::JSON)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let preferences: json::UserPreferences =
+            serde_json::from_str(&res.body_string().unwrap()).unwrap();
+        assert!(!preferences.announcements);
+
+        let res = client
+            .put("/users/current/preferences")
+            .header(ContentType::JSON)
+            .header(csrf_token)
+            .body(r#"{"announcements":true}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        assert_eq!(
+            vec![Email::from("user@example.com")],
+            db.shared().unwrap().announcement_recipients().unwrap()
+        );
+    }
+
+    #[test]
+    fn watch_place() {
+        let (client, db) = setup();
+        register_user(&db, "user@example.com", "secret", true);
+        db.exclusive()
+            .unwrap()
+            .create_or_update_place(Place::build().id("foo").finish())
+            .unwrap();
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"user@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let csrf_token = csrf_token_header(&res);
+
+        let res = client
+            .put("/users/current/watches/bar")
+            .header(csrf_token.clone())
+            .dispatch();
+        assert_eq!(res.status(), Status::NotFound);
+        let res = client
+            .put("/users/current/watches/foo")
+            .header(csrf_token.clone())
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let mut res = client
+            .get("/users/current/watches")
+            .header(ContentType::JSON)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        assert_eq!(r#"["foo"]"#, res.body_string().unwrap());
+
+        let res = client
+            .delete("/users/current/watches/foo")
+            .header(csrf_token)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        assert!(db
+            .shared()
+            .unwrap()
+            .entity_ids_watched_by_email("user@example.com")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn personal_api_tokens() {
+        let (client, db) = setup();
+        register_user(&db, "user@example.com", "secret", true);
+
+        let res = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(r#"{"email":"user@example.com","password":"secret"}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let csrf_token = csrf_token_header(&res);
+
+        let create_token = |scope: &str| -> json::PersonalApiToken {
+            let mut res = client
+                .post("/users/current/tokens")
+                .header(ContentType::JSON)
+                .header(csrf_token.clone())
+                .body(format!(r#"{{"scope":"{}","label":"script"}}"#, scope))
+                .dispatch();
+            assert_eq!(res.status(), Status::Ok);
+            serde_json::from_str(&res.body_string().unwrap()).unwrap()
+        };
+        let read_token = create_token("read");
+        let write_token = create_token("write");
+
+        let mut res = client
+            .get("/users/current/tokens")
+            .header(ContentType::JSON)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let tokens: Vec<json::PersonalApiToken> =
+            serde_json::from_str(&res.body_string().unwrap()).unwrap();
+        assert_eq!(2, tokens.len());
+        assert!(tokens.iter().all(|t| t.token.is_none()));
+
+        let res = client.post("/logout").header(ContentType::JSON).dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        let bearer = |token: &json::PersonalApiToken| {
+            rocket::http::Header::new(
+                "Authorization",
+                format!("Bearer {}", token.token.as_ref().unwrap()),
+            )
+        };
+        let res = client
+            .get("/users/current")
+            .header(ContentType::JSON)
+            .dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+        let res = client
+            .get("/users/current")
+            .header(ContentType::JSON)
+            .header(bearer(&read_token))
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        let res = client
+            .put("/users/current/preferences")
+            .header(ContentType::JSON)
+            .header(bearer(&read_token))
+            .body(r#"{"announcements":true}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Forbidden);
+        let res = client
+            .put("/users/current/preferences")
+            .header(ContentType::JSON)
+            .header(bearer(&write_token))
+            .body(r#"{"announcements":true}"#)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+
+        let res = client
+            .delete(format!("/users/current/tokens/{}", read_token.id))
+            .header(bearer(&write_token))
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let res = client
+            .get("/users/current")
+            .header(ContentType::JSON)
+            .header(bearer(&read_token))
+            .dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+    }
 }
@@ -0,0 +1,249 @@
+use crate::infrastructure::cfg::{CorsCfg, CorsOrigin};
+use rocket::{
+    fairing::{Fairing, Info, Kind},
+    http::{Method, Status},
+    Request, Response,
+};
+
+// Browsers cache the result of a preflight request
+const PREFLIGHT_MAX_AGE_SECS: u32 = 3600;
+
+/// Allows cross-origin requests from the configured origins.
+///
+/// Preflight requests are answered here because
+/// there are no `OPTIONS` routes.
+pub struct Cors(CorsCfg);
+
+impl Cors {
+    pub fn new(cfg: CorsCfg) -> Self {
+        Self(cfg)
+    }
+
+    fn allows_origin(&self, origin: &str) -> bool {
+        let origin = origin.to_lowercase();
+        self.0.allowed_origins.iter().any(|allowed| match allowed {
+            CorsOrigin::Any => true,
+            CorsOrigin::Exact(exact) => *exact == origin,
+            CorsOrigin::Subdomains { scheme, domain } => origin
+                .strip_prefix(scheme.as_str())
+                .and_then(|rest| rest.strip_prefix("://"))
+                .and_then(|host| host.strip_suffix(domain.as_str()))
+                .map(|subdomain| subdomain.len() > 1 && subdomain.ends_with('.'))
+                .unwrap_or(false),
+        })
+    }
+
+    fn allowed_methods(&self, path: &str) -> &[String] {
+        self.0
+            .route_methods
+            .iter()
+            .filter(|(prefix, _)| is_path_prefix(prefix, path))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, methods)| methods)
+            .unwrap_or(&self.0.allowed_methods)
+    }
+}
+
+fn is_path_prefix(prefix: &str, path: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    match path.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+impl Fairing for Cors {
+    fn info(&self) -> Info {
+        Info {
+            name: "CORS",
+            kind: Kind::Response,
+        }
+    }
+
+    fn on_response(&self, req: &Request, res: &mut Response) {
+        let origin = match req.headers().get_one("Origin") {
+            Some(origin) => origin,
+            None => return,
+        };
+        // The response might differ for other origins
+        res.adjoin_raw_header("Vary", "Origin");
+        if !self.allows_origin(origin) {
+            return;
+        }
+        let methods = self.allowed_methods(req.uri().path());
+        let requested_method = req.headers().get_one("Access-Control-Request-Method");
+        match (req.method(), requested_method) {
+            (Method::Options, Some(requested_method)) => {
+                if !methods
+                    .iter()
+                    .any(|m| m.eq_ignore_ascii_case(requested_method))
+                {
+                    return;
+                }
+                res.set_status(Status::NoContent);
+                res.take_body();
+                res.remove_header("Content-Type");
+                res.set_raw_header("Access-Control-Allow-Methods", methods.join(", "));
+                if let Some(headers) = req.headers().get_one("Access-Control-Request-Headers") {
+                    res.set_raw_header("Access-Control-Allow-Headers", headers.to_string());
+                }
+                res.set_raw_header("Access-Control-Max-Age", PREFLIGHT_MAX_AGE_SECS.to_string());
+            }
+            (method, _) => {
+                if !methods.iter().any(|m| m == method.as_str()) {
+                    return;
+                }
+            }
+        }
+        res.set_raw_header("Access-Control-Allow-Origin", origin.to_string());
+        res.set_raw_header(
+            "Access-Control-Expose-Headers",
+            format!(
+                "{}, {}",
+                super::api::TOTAL_COUNT_HEADER,
+                super::api::RESULT_LIMIT_HEADER
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::{http::Header, local::Client};
+
+    #[get("/places")]
+    fn get_places() -> &'static str {
+        "places"
+    }
+
+    #[post("/places")]
+    fn post_places() -> &'static str {
+        "created"
+    }
+
+    fn cors(origins: &[&str], route_methods: &[(&str, &str)]) -> Cors {
+        Cors::new(CorsCfg {
+            allowed_origins: origins.iter().map(|o| o.parse().unwrap()).collect(),
+            route_methods: route_methods
+                .iter()
+                .map(|(path, methods)| {
+                    (
+                        path.to_string(),
+                        methods.split(',').map(ToString::to_string).collect(),
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        })
+    }
+
+    fn client(cors: Cors) -> Client {
+        let rocket = rocket::ignite()
+            .mount("/api", routes![get_places, post_places])
+            .attach(cors);
+        Client::new(rocket).unwrap()
+    }
+
+    #[test]
+    fn parse_origins() {
+        assert_eq!(CorsOrigin::Any, "*".parse().unwrap());
+        assert_eq!(
+            CorsOrigin::Exact("https://example.org".into()),
+            "https://Example.org/".parse().unwrap()
+        );
+        assert_eq!(
+            CorsOrigin::Subdomains {
+                scheme: "https".into(),
+                domain: "example.org".into()
+            },
+            "https://*.example.org".parse().unwrap()
+        );
+        assert!("example.org".parse::<CorsOrigin>().is_err());
+        assert!("https://*".parse::<CorsOrigin>().is_err());
+        assert!("https://foo.*.org".parse::<CorsOrigin>().is_err());
+    }
+
+    #[test]
+    fn match_exact_and_wildcard_origins() {
+        let cors = cors(&["https://example.org", "https://*.partner.org"], &[]);
+        assert!(cors.allows_origin("https://example.org"));
+        assert!(!cors.allows_origin("http://example.org"));
+        assert!(!cors.allows_origin("https://www.example.org"));
+        assert!(cors.allows_origin("https://map.partner.org"));
+        assert!(cors.allows_origin("https://a.b.partner.org"));
+        assert!(!cors.allows_origin("https://partner.org"));
+        assert!(!cors.allows_origin("https://evilpartner.org"));
+        assert!(!cors.allows_origin("https://map.partner.org.evil.com"));
+    }
+
+    #[test]
+    fn match_longest_route_prefix() {
+        let cors = cors(&["*"], &[("/api", "GET"), ("/api/places/", "GET,POST")]);
+        assert_eq!(["GET", "POST"], cors.allowed_methods("/api/places"));
+        assert_eq!(["GET"], cors.allowed_methods("/api/placesfoo"));
+        assert_eq!(["GET"], cors.allowed_methods("/api/events"));
+        assert_eq!(4, cors.allowed_methods("/other").len());
+    }
+
+    #[test]
+    fn allow_requests_from_allowed_origins() {
+        let client = client(cors(&["https://example.org"], &[]));
+        let res = client
+            .get("/api/places")
+            .header(Header::new("Origin", "https://example.org"))
+            .dispatch();
+        assert_eq!(Status::Ok, res.status());
+        assert_eq!(
+            Some("https://example.org"),
+            res.headers().get_one("Access-Control-Allow-Origin")
+        );
+        let res = client
+            .get("/api/places")
+            .header(Header::new("Origin", "https://evil.com"))
+            .dispatch();
+        assert_eq!(None, res.headers().get_one("Access-Control-Allow-Origin"));
+    }
+
+    #[test]
+    fn answer_preflight_requests() {
+        let client = client(cors(&["https://*.example.org"], &[("/api/places", "GET")]));
+        let res = client
+            .options("/api/places")
+            .header(Header::new("Origin", "https://map.example.org"))
+            .header(Header::new("Access-Control-Request-Method", "GET"))
+            .header(Header::new(
+                "Access-Control-Request-Headers",
+                "Authorization",
+            ))
+            .dispatch();
+        assert_eq!(Status::NoContent, res.status());
+        assert_eq!(
+            Some("https://map.example.org"),
+            res.headers().get_one("Access-Control-Allow-Origin")
+        );
+        assert_eq!(
+            Some("GET"),
+            res.headers().get_one("Access-Control-Allow-Methods")
+        );
+        assert_eq!(
+            Some("Authorization"),
+            res.headers().get_one("Access-Control-Allow-Headers")
+        );
+
+        let res = client
+            .options("/api/places")
+            .header(Header::new("Origin", "https://map.example.org"))
+            .header(Header::new("Access-Control-Request-Method", "POST"))
+            .dispatch();
+        assert_eq!(None, res.headers().get_one("Access-Control-Allow-Origin"));
+
+        let mut res = client
+            .post("/api/places")
+            .header(Header::new("Origin", "https://map.example.org"))
+            .dispatch();
+        // The request itself is not blocked, only its response is hidden from browsers
+        assert_eq!(Some("created".to_string()), res.body_string());
+        assert_eq!(None, res.headers().get_one("Access-Control-Allow-Origin"));
+    }
+}
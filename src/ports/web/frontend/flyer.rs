@@ -0,0 +1,228 @@
+//! PDF flyers of places
+
+use crate::core::prelude::*;
+use anyhow::Result;
+use ofdb_core::gateways::map_tile::MapSnippet;
+use printpdf::{
+    BuiltinFont, Color, ColorBits, ColorSpace, Greyscale, Image, ImageXObject, Line, Mm,
+    PdfDocument, PdfLayerReference, Point, Px, Rgb,
+};
+use qrcode::QrCode;
+use std::io::BufWriter;
+
+// A4 portrait
+const PAGE_WIDTH: f64 = 210.0;
+const PAGE_HEIGHT: f64 = 297.0;
+const MARGIN: f64 = 20.0;
+
+const TITLE_FONT_SIZE: f64 = 22.0;
+const TEXT_FONT_SIZE: f64 = 11.0;
+const LINE_HEIGHT: f64 = 5.5;
+
+// The builtin fonts cannot be measured and Helvetica
+// is assumed to be at most this wide on average
+const MAX_CHARS_PER_LINE: usize = 85;
+const MAX_TITLE_CHARS_PER_LINE: usize = 42;
+
+const QR_CODE_SIZE: f64 = 45.0;
+const MAP_SIZE: f64 = 80.0;
+const MARKER_SIZE: f64 = 2.5;
+
+/// Renders a single page flyer with the title, the description,
+/// a QR code of the URL and the optional map snippet given as
+/// a PNG image of the tile.
+pub fn entry_flyer(
+    place: &Place,
+    url: &str,
+    map: Option<(MapSnippet, Vec<u8>)>,
+) -> Result<Vec<u8>> {
+    let (doc, page, layer) =
+        PdfDocument::new(&place.title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Flyer");
+    let layer = doc.get_page(page).get_layer(layer);
+    let font = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+    let bold_font = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+
+    let mut y = PAGE_HEIGHT - MARGIN - TITLE_FONT_SIZE * 0.35;
+    for line in wrap_text(&place.title, MAX_TITLE_CHARS_PER_LINE) {
+        layer.use_text(line, TITLE_FONT_SIZE, Mm(MARGIN), Mm(y), &bold_font);
+        y -= TITLE_FONT_SIZE * 0.45;
+    }
+    y -= LINE_HEIGHT;
+
+    let mut lines = wrap_text(&place.description, MAX_CHARS_PER_LINE);
+    lines.push(String::new());
+    if let Some(address) = &place.location.address {
+        lines.extend(address_lines(address));
+    }
+    if let Some(contact) = &place.contact {
+        if let Some(email) = &contact.email {
+            lines.push(email.to_string());
+        }
+        if let Some(phone) = &contact.phone {
+            lines.push(phone.clone());
+        }
+    }
+    if let Some(homepage) = place.links.as_ref().and_then(|l| l.homepage.as_ref()) {
+        lines.push(homepage.to_string());
+    }
+    if !place.tags.is_empty() {
+        lines.push(String::new());
+        let tags = place
+            .tags
+            .iter()
+            .map(|t| format!("#{}", t))
+            .collect::<Vec<_>>()
+            .join(" ");
+        lines.extend(wrap_text(&tags, MAX_CHARS_PER_LINE));
+    }
+    // The text must not overlap with the QR code and the map
+    let min_y = MARGIN + MAP_SIZE.max(QR_CODE_SIZE) + LINE_HEIGHT * 2.0;
+    for line in lines {
+        if y < min_y {
+            break;
+        }
+        layer.use_text(line, TEXT_FONT_SIZE, Mm(MARGIN), Mm(y), &font);
+        y -= LINE_HEIGHT;
+    }
+
+    if let Some((snippet, png)) = map {
+        draw_map(&layer, snippet, &png)?;
+    }
+    draw_qr_code(
+        &layer,
+        url,
+        PAGE_WIDTH - MARGIN - QR_CODE_SIZE,
+        MARGIN + LINE_HEIGHT,
+    )?;
+    layer.use_text(
+        url,
+        TEXT_FONT_SIZE * 0.7,
+        Mm(PAGE_WIDTH - MARGIN - QR_CODE_SIZE),
+        Mm(MARGIN),
+        &font,
+    );
+
+    let mut writer = BufWriter::new(Vec::new());
+    doc.save(&mut writer)?;
+    Ok(writer.into_inner()?)
+}
+
+fn address_lines(addr: &Address) -> Vec<String> {
+    let zip_city = [addr.zip.as_deref(), addr.city.as_deref()]
+        .iter()
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ");
+    vec![addr.street.clone(), Some(zip_city), addr.country.clone()]
+        .into_iter()
+        .flatten()
+        .filter(|line| !line.trim().is_empty())
+        .collect()
+}
+
+fn wrap_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = vec![];
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > max_chars {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+fn filled_rect(x: f64, y: f64, width: f64, height: f64) -> Line {
+    Line {
+        points: vec![
+            (Point::new(Mm(x), Mm(y)), false),
+            (Point::new(Mm(x + width), Mm(y)), false),
+            (Point::new(Mm(x + width), Mm(y + height)), false),
+            (Point::new(Mm(x), Mm(y + height)), false),
+        ],
+        is_closed: true,
+        has_fill: true,
+        has_stroke: false,
+        is_clipping_path: false,
+    }
+}
+
+fn draw_qr_code(layer: &PdfLayerReference, url: &str, x: f64, y: f64) -> Result<()> {
+    let code = QrCode::new(url.as_bytes())?;
+    let width = code.width();
+    let module_size = QR_CODE_SIZE / width as f64;
+    layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
+    for (i, color) in code.to_colors().into_iter().enumerate() {
+        if color != qrcode::Color::Dark {
+            continue;
+        }
+        let col = (i % width) as f64;
+        // The rows are counted from the top
+        let row = (width - 1 - i / width) as f64;
+        layer.add_shape(filled_rect(
+            x + col * module_size,
+            y + row * module_size,
+            module_size,
+            module_size,
+        ));
+    }
+    Ok(())
+}
+
+fn draw_map(layer: &PdfLayerReference, snippet: MapSnippet, png: &[u8]) -> Result<()> {
+    let tile = image::load_from_memory(png)?.to_rgb8();
+    let (width, height) = tile.dimensions();
+    let x = MARGIN;
+    let y = MARGIN + LINE_HEIGHT;
+    // The DPI determine the size of the image on the page
+    let dpi = f64::from(width) * 25.4 / MAP_SIZE;
+    let image = Image::from(ImageXObject {
+        width: Px(width as usize),
+        height: Px(height as usize),
+        color_space: ColorSpace::Rgb,
+        bits_per_component: ColorBits::Bit8,
+        interpolate: true,
+        image_data: tile.into_raw(),
+        image_filter: None,
+        clipping_bbox: None,
+    });
+    image.add_to_layer(
+        layer.clone(),
+        Some(Mm(x)),
+        Some(Mm(y)),
+        None,
+        None,
+        None,
+        Some(dpi),
+    );
+    let marker_x = x + snippet.offset_x * MAP_SIZE;
+    let marker_y = y + (1.0 - snippet.offset_y) * MAP_SIZE;
+    layer.set_fill_color(Color::Rgb(Rgb::new(0.8, 0.0, 0.0, None)));
+    layer.add_shape(filled_rect(
+        marker_x - MARKER_SIZE / 2.0,
+        marker_y - MARKER_SIZE / 2.0,
+        MARKER_SIZE,
+        MARKER_SIZE,
+    ));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_long_text() {
+        assert_eq!(
+            vec!["foo bar", "baz", "", "qux"],
+            wrap_text("foo bar baz\n\nqux", 8)
+        );
+    }
+}
@@ -59,6 +59,9 @@ pub fn post_login(
                         Error::Parameter(ParameterError::EmailNotConfirmed) => {
                             "You have to confirm your email address first."
                         }
+                        Error::Parameter(ParameterError::UserDeactivated) => {
+                            "Your account has been deleted."
+                        }
                         Error::Parameter(ParameterError::Credentials) => {
                             "Invalid email or password."
                         }
@@ -73,6 +76,7 @@ pub fn post_login(
                             .same_site(SameSite::Lax)
                             .finish(),
                     );
+                    add_csrf_token_cookie(&mut cookies, SameSite::Lax);
                     Ok(Redirect::to(uri!(super::get_index)))
                 }
             }
@@ -83,6 +87,7 @@ pub fn post_login(
 #[post("/logout")]
 pub fn post_logout(mut cookies: Cookies) -> Flash<Redirect> {
     cookies.remove_private(Cookie::named(COOKIE_EMAIL_KEY));
+    cookies.remove(Cookie::build(COOKIE_CSRF_TOKEN_KEY, "").path("/").finish());
     Flash::success(
         Redirect::to(uri!(super::get_index)),
         "Sie haben sich erfolgreich abgemeldet.",
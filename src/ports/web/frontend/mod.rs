@@ -4,22 +4,25 @@ use crate::{
         prelude::*,
         usecases,
     },
-    infrastructure::{db::sqlite, error::*, flows::prelude::*},
+    infrastructure::{cfg::Cfg, db::sqlite, error::*, flows::prelude::*},
     ports::web::{guards::*, tantivy::SearchEngine},
 };
 use maud::Markup;
 use num_traits::FromPrimitive;
+use ofdb_core::gateways::map_tile::{MapSnippet, MapTileGateway};
+use ofdb_gateways::tile_server::TileServer;
 use rocket::{
     self,
     http::{ContentType, RawStr},
-    request::Form,
+    request::{Form, FromParam},
     response::{
         content::{Content, Css, Html, JavaScript},
         Flash, Redirect,
     },
-    Route,
+    Route, State,
 };
 
+mod flyer;
 mod login;
 mod password;
 mod register;
@@ -34,6 +37,11 @@ const CLEARANCE_JS: &str = include_str!("../../../../ofdb-app-clearance/pkg/clea
 const CLEARANCE_WASM: &[u8] =
     include_bytes!("../../../../ofdb-app-clearance/pkg/clearance_bg.wasm");
 
+const RECENT_VIEWS_DAYS: u32 = 30;
+
+// Shows the surrounding streets on printed entries
+const PRINT_MAP_ZOOM: u8 = 16;
+
 type Result<T> = std::result::Result<T, AppError>;
 
 #[get("/")]
@@ -67,9 +75,19 @@ pub fn get_clearance_wasm() -> Content<&'static [u8]> {
 }
 
 #[get("/search?<q>&<limit>")]
-pub fn get_search(search_engine: SearchEngine, q: &RawStr, limit: Option<usize>) -> Result<Markup> {
+pub fn get_search(
+    search_engine: SearchEngine,
+    client_location: ClientLocation,
+    q: &RawStr,
+    limit: Option<usize>,
+) -> Result<Markup> {
     let q = q.url_decode()?;
-    let entries = usecases::global_search(&search_engine, &q, limit.unwrap_or(10))?;
+    let entries = usecases::global_search(
+        &search_engine,
+        &q,
+        client_location.region(),
+        limit.unwrap_or(10),
+    )?;
     Ok(view::search_results(None, &q, &entries))
 }
 
@@ -80,7 +98,11 @@ pub fn get_search_users(pool: sqlite::Connections, email: &RawStr, auth: Auth) -
         let db = pool.shared()?;
         let admin = auth.user_with_min_role(&*db, Role::Admin)?;
         let users: Vec<_> = db.try_get_user_by_email(&email)?.into_iter().collect();
-        Ok(view::user_search_result(&admin.email, &users))
+        Ok(view::user_search_result(
+            &admin.email,
+            auth.csrf_token(),
+            &users,
+        ))
     }
 }
 
@@ -88,15 +110,24 @@ pub fn get_search_users(pool: sqlite::Connections, email: &RawStr, auth: Auth) -
 pub struct ChangeUserRoleAction {
     email: String,
     role: u8,
+    csrf_token: Option<String>,
 }
 
 #[post("/change-user-role", data = "<data>")]
 pub fn post_change_user_role(
     db: sqlite::Connections,
-    account: Account,
+    account: UnverifiedAccount,
     data: Form<ChangeUserRoleAction>,
 ) -> std::result::Result<Redirect, Flash<Redirect>> {
     let d = data.into_inner();
+    let account = account
+        .verify_csrf_token(d.csrf_token.as_deref())
+        .map_err(|_| {
+            Flash::error(
+                Redirect::to(uri!(get_search_users:d.email.clone())),
+                "Failed to change user role: invalid form.",
+            )
+        })?;
     match Role::from_u8(d.role) {
         None => Err(Flash::error(
             Redirect::to(uri!(get_search_users:d.email)),
@@ -144,14 +175,20 @@ pub fn get_place_review(db: sqlite::Connections, id: &RawStr, account: Account)
     // Only scouts and admins are entitled to review places
     let reviewer_email =
         usecases::authorize_user_by_email(&*db, &account.email(), Role::Scout)?.email;
-    let (place, review_status) = db.get_place(&id)?;
-    Ok(view::place_review(&reviewer_email, &place, review_status))
+    let (place, review_status) = db.get_place_by_id(&id)?;
+    Ok(view::place_review(
+        &reviewer_email,
+        account.csrf_token(),
+        &place,
+        review_status,
+    ))
 }
 
 #[derive(FromForm)]
 pub struct Review {
     pub comment: String,
     pub status: i16,
+    pub csrf_token: Option<String>,
 }
 
 #[post("/places/<id>/review", data = "<review>")]
@@ -160,11 +197,17 @@ pub fn post_place_review(
     search_engine: SearchEngine,
     id: &RawStr,
     review: Form<Review>,
-    account: Account,
+    account: UnverifiedAccount,
 ) -> std::result::Result<Redirect, Flash<Redirect>> {
-    let Review { status, comment } = review.into_inner();
+    let Review {
+        status,
+        comment,
+        csrf_token,
+    } = review.into_inner();
     let id = id.as_str();
-    review_place(&db, account.email(), status, comment, id, search_engine)
+    account
+        .verify_csrf_token(csrf_token.as_deref())
+        .and_then(|account| review_place(&db, account.email(), status, comment, id, search_engine))
         .map(|_| Redirect::to(uri!(get_entry: id)))
         .map_err(|_| {
             Flash::error(
@@ -203,31 +246,103 @@ fn review_place(
     Ok(())
 }
 
-#[get("/entries/<id>")]
+#[get("/entries/<id>", rank = 2)]
 pub fn get_entry(
     pool: sqlite::Connections,
     id: &RawStr,
     account: Option<Account>,
 ) -> Result<Markup> {
     //TODO: dry out
-    let (user, place, ratings): (Option<User>, _, _) = {
+    let (user, place, ratings, archived): (Option<User>, _, _, _) = {
         let db = pool.shared()?;
-        let (place, _) = db.get_place(id.as_str())?;
+        let (place, _) = db.get_place_by_id(id.as_str())?;
         let ratings = db.load_ratings_of_place(place.id.as_ref())?;
         let ratings_with_comments = db.zip_ratings_with_comments(ratings)?;
-        let user = if let Some(a) = account {
+        let user = if let Some(a) = &account {
             db.try_get_user_by_email(a.email())?
         } else {
             None
         };
-        (user, place, ratings_with_comments)
+        let archived = if matches!(&user, Some(u) if u.role >= Role::Scout) {
+            Some((
+                db.load_archived_ratings_of_place(place.id.as_ref())?,
+                db.load_archived_comments_of_place(place.id.as_ref())?,
+            ))
+        } else {
+            None
+        };
+        (user, place, ratings_with_comments, archived)
     };
     Ok(match user {
-        Some(u) => view::entry(Some(&u.email), (place, ratings, u.role).into()),
+        Some(u) => {
+            let mut presenter: view::EntryPresenter = (place, ratings, u.role).into();
+            presenter.csrf_token = account
+                .as_ref()
+                .and_then(Account::csrf_token)
+                .map(ToOwned::to_owned);
+            if let Some((archived_ratings, archived_comments)) = archived {
+                presenter.archived_ratings = archived_ratings;
+                presenter.archived_comments = archived_comments;
+            }
+            view::entry(Some(&u.email), presenter)
+        }
         None => view::entry(None, (place, ratings).into()),
     })
 }
 
+fn print_map_tile_server(cfg: &Cfg) -> Option<TileServer> {
+    cfg.print_map_tile_url.clone().map(TileServer::new)
+}
+
+#[get("/entries/<id>/print")]
+pub fn get_entry_print(pool: sqlite::Connections, id: &RawStr, cfg: State<Cfg>) -> Result<Markup> {
+    let (place, _) = pool.shared()?.get_place_by_id(id.as_str())?;
+    let map = print_map_tile_server(&cfg).map(|tile_server| {
+        let snippet = MapSnippet::around(place.location.pos, PRINT_MAP_ZOOM);
+        (snippet, tile_server.tile_url(&snippet.tile))
+    });
+    Ok(view::entry_print(&place, map))
+}
+
+/// The id of a place in a path segment like `<id>.pdf`
+pub struct PdfFileName(String);
+
+impl<'a> FromParam<'a> for PdfFileName {
+    type Error = &'a RawStr;
+
+    fn from_param(param: &'a RawStr) -> std::result::Result<Self, Self::Error> {
+        param
+            .as_str()
+            .strip_suffix(".pdf")
+            .filter(|id| !id.is_empty())
+            .map(|id| Self(id.to_string()))
+            .ok_or(param)
+    }
+}
+
+#[get("/entries/<file_name>", rank = 1)]
+pub fn get_entry_pdf(
+    pool: sqlite::Connections,
+    file_name: PdfFileName,
+    cfg: State<Cfg>,
+) -> Result<Content<Vec<u8>>> {
+    let (place, _) = pool.shared()?.get_place_by_id(&file_name.0)?;
+    let map = print_map_tile_server(&cfg).and_then(|tile_server| {
+        let snippet = MapSnippet::around(place.location.pos, PRINT_MAP_ZOOM);
+        match tile_server.fetch_tile(&snippet.tile) {
+            Ok(png) => Some((snippet, png)),
+            Err(err) => {
+                // The flyer is still useful without a map
+                warn!("Failed to fetch the map of place {}: {}", place.id, err);
+                None
+            }
+        }
+    });
+    let url = view::entry_url(place.id.as_ref());
+    let pdf = flyer::entry_flyer(&place, &url, map)?;
+    Ok(Content(ContentType::PDF, pdf))
+}
+
 #[get("/events/<id>")]
 pub fn get_event(
     pool: sqlite::Connections,
@@ -237,7 +352,7 @@ pub fn get_event(
     let (user, mut ev): (Option<User>, _) = {
         let db = pool.shared()?;
         let ev = usecases::get_event(&*db, &id)?;
-        let user = if let Some(a) = account {
+        let user = if let Some(a) = &account {
             db.try_get_user_by_email(a.email())?
         } else {
             None
@@ -249,19 +364,27 @@ pub fn get_event(
     // is not shown to unregistered users
     ev.created_by = None;
 
-    Ok(view::event(user, ev))
+    let csrf_token = account.as_ref().and_then(Account::csrf_token);
+    Ok(view::event(user, csrf_token, ev))
+}
+
+#[derive(FromForm)]
+pub struct CsrfTokenForm {
+    csrf_token: Option<String>,
 }
 
-#[post("/events/<id>/archive")]
+#[post("/events/<id>/archive", data = "<data>")]
 pub fn post_archive_event(
-    account: Account,
+    account: UnverifiedAccount,
     pool: sqlite::Connections,
     mut search_engine: SearchEngine,
     id: &RawStr,
+    data: Form<CsrfTokenForm>,
 ) -> std::result::Result<Redirect, Flash<Redirect>> {
-    let archived_by_email = pool
-        .shared()
-        .and_then(|db| {
+    let archived_by_email = account
+        .verify_csrf_token(data.csrf_token.as_deref())
+        .and_then(|account| {
+            let db = pool.shared()?;
             // Only scouts and admins are entitled to review events
             let user = usecases::authorize_user_by_email(&*db, &account.email(), Role::Scout)?;
             Ok(user.email)
@@ -312,43 +435,77 @@ pub fn get_events_chronologically(
 }
 
 #[get("/dashboard")]
-pub fn get_dashboard(db: sqlite::Connections, account: Account) -> Result<Markup> {
+pub fn get_dashboard(db: sqlite::Connections, account: Account, cfg: State<Cfg>) -> Result<Markup> {
     let db = db.shared()?;
     let tag_count = db.count_tags()?;
     let place_count = db.count_places()?;
     let user_count = db.count_users()?;
     let event_count = db.count_events()?;
+    let view_count = if cfg.count_views {
+        Some(usecases::count_recent_views(&*db, RECENT_VIEWS_DAYS)?)
+    } else {
+        None
+    };
     let user = db
         .try_get_user_by_email(account.email())?
         .ok_or(Error::Parameter(ParameterError::Unauthorized))?;
     if user.role == Role::Admin {
+        let broken_links = db.load_broken_links(usecases::MIN_FAILURES_OF_BROKEN_LINKS)?;
+        let archived_ratings = db.count_archived_ratings_by_reason()?;
+        let archived_comments = db.count_archived_comments_by_reason()?;
         return Ok(view::dashboard(view::DashBoardPresenter {
             user,
             place_count,
             event_count,
             tag_count,
             user_count,
+            view_count,
+            view_count_days: RECENT_VIEWS_DAYS,
+            broken_links,
+            archived_ratings,
+            archived_comments,
         }));
     }
     Err(Error::Parameter(ParameterError::Unauthorized).into())
 }
 
+#[get("/dashboard/data-quality")]
+pub fn get_data_quality_report(db: sqlite::Connections, account: Account) -> Result<Markup> {
+    let db = db.shared()?;
+    let user = db
+        .try_get_user_by_email(account.email())?
+        .ok_or(Error::Parameter(ParameterError::Unauthorized))?;
+    if user.role != Role::Admin {
+        return Err(Error::Parameter(ParameterError::Unauthorized).into());
+    }
+    let regions = usecases::data_quality_report(&*db, TimestampMs::now())?;
+    Ok(view::data_quality_report(&user.email, &regions))
+}
+
 #[derive(FromForm)]
 pub struct ArchiveAction {
     ids: String,
     place_id: String,
+    reason: String,
+    csrf_token: Option<String>,
 }
 
 #[post("/comments/actions/archive", data = "<data>")]
 pub fn post_comments_archive(
-    account: Account,
+    account: UnverifiedAccount,
     db: sqlite::Connections,
     data: Form<ArchiveAction>,
 ) -> std::result::Result<Redirect, Flash<Redirect>> {
     //TODO: dry out
     let d = data.into_inner();
     let ids: Vec<_> = d.ids.split(',').filter(|id| !id.is_empty()).collect();
-    match archive_comments(&db, account.email(), &ids) {
+    let archived = account
+        .verify_csrf_token(d.csrf_token.as_deref())
+        .and_then(|account| {
+            let reason = d.reason.parse::<ArchiveReason>().map_err(Error::from)?;
+            archive_comments(&db, account.email(), &ids, reason)
+        });
+    match archived {
         Err(_) => Err(Flash::error(
             Redirect::to(uri!(get_entry:d.place_id)),
             "Failed to achive the comment.",
@@ -359,14 +516,20 @@ pub fn post_comments_archive(
 
 #[post("/ratings/actions/archive", data = "<data>")]
 pub fn post_ratings_archive(
-    account: Account,
+    account: UnverifiedAccount,
     db: sqlite::Connections,
     mut search_engine: SearchEngine,
     data: Form<ArchiveAction>,
 ) -> std::result::Result<Redirect, Flash<Redirect>> {
     let d = data.into_inner();
     let ids: Vec<_> = d.ids.split(',').filter(|id| !id.is_empty()).collect();
-    match archive_ratings(&db, &mut search_engine, account.email(), &ids) {
+    let archived = account
+        .verify_csrf_token(d.csrf_token.as_deref())
+        .and_then(|account| {
+            let reason = d.reason.parse::<ArchiveReason>().map_err(Error::from)?;
+            archive_ratings(&db, &mut search_engine, account.email(), &ids, reason)
+        });
+    match archived {
         Err(_) => Err(Flash::error(
             Redirect::to(uri!(get_entry:d.place_id)),
             "Failed to archive the rating.",
@@ -384,8 +547,11 @@ pub fn routes() -> Vec<Route> {
         get_index,
         get_index_html,
         get_dashboard,
+        get_data_quality_report,
         get_search,
         get_entry,
+        get_entry_print,
+        get_entry_pdf,
         get_place_history,
         get_place_review,
         post_place_review,
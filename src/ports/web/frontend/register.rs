@@ -2,6 +2,7 @@ use super::login::LoginCredentials;
 use super::view;
 use crate::{
     core::{prelude::*, usecases},
+    infrastructure::cfg::Cfg,
     ports::web::{notify::*, sqlite::Connections},
 };
 use maud::Markup;
@@ -10,6 +11,7 @@ use rocket::{
     http::RawStr,
     request::{FlashMessage, Form},
     response::{Flash, Redirect},
+    State,
 };
 
 #[get("/register")]
@@ -21,6 +23,7 @@ pub fn get_register(flash: Option<FlashMessage>) -> Markup {
 pub fn post_register(
     db: Connections,
     notify: Notify,
+    cfg: State<Cfg>,
     credentials: Form<LoginCredentials>,
 ) -> std::result::Result<Flash<Redirect>, Flash<Redirect>> {
     match db.exclusive() {
@@ -31,12 +34,19 @@ pub fn post_register(
         //TODO: move into flow layer
         Ok(mut db) => {
             let credentials = credentials.into_inner();
-            match usecases::register_with_email(&mut *db, &credentials.as_login()) {
+            match usecases::register_with_email(
+                &mut *db,
+                &credentials.as_login(),
+                &cfg.registration_email_domains,
+            ) {
                 Err(err) => {
                     let msg = match err {
                         Error::Parameter(ParameterError::UserExists) => {
                             "A user with your email address already exists."
                         }
+                        Error::Parameter(ParameterError::EmailDomainNotAllowed) => {
+                            "Registration with your email domain is not allowed."
+                        }
                         Error::Parameter(ParameterError::Credentials) => {
                             "Invalid email or password."
                         }
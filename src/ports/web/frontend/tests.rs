@@ -34,12 +34,19 @@ fn get_user(pool: &Connections, name: &str) -> User {
         .unwrap()
 }
 
-fn login_user(client: &Client, name: &str) {
-    client
+// Returns the CSRF token of the session
+fn login_user(client: &Client, name: &str) -> String {
+    let res = client
         .post("/login")
         .header(ContentType::Form)
         .body(format!("email={}%40example.com&password=secret", name))
         .dispatch();
+    res.headers()
+        .get("Set-Cookie")
+        .find(|v| v.starts_with(COOKIE_CSRF_TOKEN_KEY))
+        .and_then(|val| rocket::http::Cookie::parse_encoded(val).ok())
+        .map(|cookie| cookie.value().to_string())
+        .expect("CSRF token")
 }
 
 mod events {
@@ -111,7 +118,7 @@ mod events {
         let event_ids = {
             let mut event_ids = Vec::with_capacity(new_events.len());
             for e in new_events {
-                let e = flows::create_event(&db, &mut search_engine, &gw, None, e).unwrap();
+                let e = flows::create_event(&db, &mut search_engine, &gw, None, e, None).unwrap();
                 event_ids.push(e.id);
             }
             event_ids
@@ -232,7 +239,7 @@ mod events {
         let event_ids = {
             let mut event_ids = Vec::with_capacity(new_events.len());
             for e in new_events {
-                let e = flows::create_event(&db, &mut search_engine, &gw, None, e).unwrap();
+                let e = flows::create_event(&db, &mut search_engine, &gw, None, e, None).unwrap();
                 event_ids.push(e.id);
             }
             event_ids
@@ -271,6 +278,12 @@ mod events {
             archived: None,
             image_url: None,
             image_link_url: None,
+            time_zone: None,
+            recurrence: None,
+            address_auto_filled: false,
+            publish_at: None,
+            created_at: None,
+            updated_at: None,
         }];
 
         {
@@ -352,7 +365,7 @@ mod entry {
             value: 1.into(),
             entry: e_id.clone().into(),
         };
-        let (r_id, c_id) = flows::prelude::create_rating(db, search, r).unwrap();
+        let (r_id, c_id) = flows::prelude::create_rating(db, search, &gw, r).unwrap();
         (e_id.into(), r_id, c_id)
     }
 
@@ -375,7 +388,7 @@ mod entry {
         let (client, db, mut search) = setup();
         let (id, _, _) = create_place_with_rating(&db, &mut search);
         create_user(&db, "foo", Role::Admin);
-        login_user(&client, "foo");
+        let csrf_token = login_user(&client, "foo");
         let mut res = client.get(format!("/entries/{}", id)).dispatch();
         assert_eq!(res.status(), Status::Ok);
         let body_str = res.body().and_then(|b| b.into_string()).unwrap();
@@ -384,6 +397,7 @@ mod entry {
             body_str.contains("action=\"/comments/actions/archive\""),
             true
         );
+        assert!(body_str.contains(&format!("name=\"csrf_token\" value=\"{}\"", csrf_token)));
     }
 
     #[test]
@@ -402,18 +416,53 @@ mod entry {
         );
     }
 
+    #[test]
+    fn print_entry() {
+        let (client, db, mut search) = setup();
+        let (id, _, _) = create_place_with_rating(&db, &mut search);
+        let mut res = client.get(format!("/entries/{}/print", id)).dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+        assert!(body_str.contains("<h1>entry</h1>"));
+        assert!(body_str.contains("<svg"));
+        assert!(body_str.contains(&format!("https://kartevonmorgen.org/#/?entry={}", id)));
+        // No map tiles are configured
+        assert!(!body_str.contains("class=\"map\""));
+        assert!(!body_str.contains("<nav"));
+
+        let res = client.get("/entries/does-not-exist/print").dispatch();
+        assert_eq!(res.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn get_entry_as_pdf() {
+        let (client, db, mut search) = setup();
+        let (id, _, _) = create_place_with_rating(&db, &mut search);
+        let mut res = client.get(format!("/entries/{}.pdf", id)).dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        assert_eq!(Some(ContentType::PDF), res.content_type());
+        let body = res.body().and_then(|b| b.into_bytes()).unwrap();
+        assert!(body.starts_with(b"%PDF"));
+
+        let res = client.get("/entries/does-not-exist.pdf").dispatch();
+        assert_eq!(res.status(), Status::NotFound);
+    }
+
     #[test]
     fn archive_comment_as_admin() {
         let (client, db, mut search) = setup();
         create_user(&db, "foo", Role::Admin);
-        login_user(&client, "foo");
+        let csrf_token = login_user(&client, "foo");
         let (e_id, _, c_id) = create_place_with_rating(&db, &mut search);
         let comment = db.shared().unwrap().load_comment(&c_id).unwrap();
         assert!(comment.archived_at.is_none());
         let res = client
             .post("/comments/actions/archive")
             .header(ContentType::Form)
-            .body(format!("ids={}&place_id={}", c_id, e_id))
+            .body(format!(
+                "ids={}&place_id={}&reason=spam&csrf_token={}",
+                c_id, e_id, csrf_token
+            ))
             .dispatch();
         assert_eq!(res.status(), Status::SeeOther);
         //TODO: archived comments should be loaded too.
@@ -428,14 +477,17 @@ mod entry {
     fn archive_comment_as_scout() {
         let (client, db, mut search) = setup();
         create_user(&db, "foo", Role::Scout);
-        login_user(&client, "foo");
+        let csrf_token = login_user(&client, "foo");
         let (e_id, _, c_id) = create_place_with_rating(&db, &mut search);
         let comment = db.shared().unwrap().load_comment(&c_id).unwrap();
         assert!(comment.archived_at.is_none());
         let res = client
             .post("/comments/actions/archive")
             .header(ContentType::Form)
-            .body(format!("ids={}&place_id={}", c_id, e_id))
+            .body(format!(
+                "ids={}&place_id={}&reason=spam&csrf_token={}",
+                c_id, e_id, csrf_token
+            ))
             .dispatch();
         assert_eq!(res.status(), Status::SeeOther);
         //TODO: archived comments should be loaded too.
@@ -446,6 +498,30 @@ mod entry {
         }
     }
 
+    #[test]
+    fn archive_comment_without_csrf_token() {
+        let (client, db, mut search) = setup();
+        create_user(&db, "foo", Role::Scout);
+        login_user(&client, "foo");
+        let (e_id, _, c_id) = create_place_with_rating(&db, &mut search);
+        for body in &[
+            format!("ids={}&place_id={}&reason=spam", c_id, e_id),
+            format!(
+                "ids={}&place_id={}&reason=spam&csrf_token=invalid",
+                c_id, e_id
+            ),
+        ] {
+            let res = client
+                .post("/comments/actions/archive")
+                .header(ContentType::Form)
+                .body(body)
+                .dispatch();
+            assert_eq!(res.status(), Status::SeeOther);
+        }
+        let comment = db.shared().unwrap().load_comment(&c_id).unwrap();
+        assert!(comment.archived_at.is_none());
+    }
+
     #[test]
     fn archive_comment_as_guest() {
         let (client, db, mut search) = setup();
@@ -453,7 +529,7 @@ mod entry {
         let res = client
             .post("/comments/actions/archive")
             .header(ContentType::Form)
-            .body(format!("ids={}&place_id={}", c_id, e_id))
+            .body(format!("ids={}&place_id={}&reason=spam", c_id, e_id))
             .dispatch();
         assert_eq!(res.status(), Status::Unauthorized);
         let comment = db.shared().unwrap().load_comment(&c_id).unwrap();
@@ -467,10 +543,42 @@ mod entry {
         let res = client
             .post("/ratings/actions/archive")
             .header(ContentType::Form)
-            .body(format!("ids={}&place_id={}", r_id, e_id))
+            .body(format!("ids={}&place_id={}&reason=spam", r_id, e_id))
             .dispatch();
         assert_eq!(res.status(), Status::Unauthorized);
     }
+
+    #[test]
+    fn archive_rating_with_reason() {
+        let (client, db, mut search) = setup();
+        create_user(&db, "foo", Role::Scout);
+        let csrf_token = login_user(&client, "foo");
+        let (e_id, r_id, _) = create_place_with_rating(&db, &mut search);
+        let archive = |reason: Option<&str>| {
+            let mut body = format!("ids={}&place_id={}&csrf_token={}", r_id, e_id, csrf_token);
+            if let Some(reason) = reason {
+                body.push_str(&format!("&reason={}", reason));
+            }
+            client
+                .post("/ratings/actions/archive")
+                .header(ContentType::Form)
+                .body(body)
+                .dispatch()
+                .status()
+        };
+        assert_eq!(Status::UnprocessableEntity, archive(None));
+        assert_eq!(Status::SeeOther, archive(Some("boring")));
+        assert!(db.shared().unwrap().load_rating(&r_id).is_ok());
+        assert_eq!(Status::SeeOther, archive(Some("off-topic")));
+        assert!(db.shared().unwrap().load_rating(&r_id).is_err());
+
+        let mut res = client.get(format!("/entries/{}", e_id)).dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        let body_str = res.body().and_then(|b| b.into_string()).unwrap();
+        assert!(body_str.contains("Archived Ratings and Comments"));
+        assert!(body_str.contains("Rating: A rating (off-topic)"));
+        assert!(body_str.contains("Comment: Foo (off-topic)"));
+    }
 }
 
 mod admin {
@@ -485,11 +593,14 @@ mod admin {
         let admin = get_user(&db, "admin");
         assert_eq!(admin.role, Role::Admin);
         assert_eq!(user.role, Role::User);
-        login_user(&client, "admin");
+        let csrf_token = login_user(&client, "admin");
         let login_res = client
             .post("/change-user-role")
             .header(ContentType::Form)
-            .body("email=user%40example.com&role=2")
+            .body(format!(
+                "email=user%40example.com&role=2&csrf_token={}",
+                csrf_token
+            ))
             .dispatch();
         assert_eq!(login_res.status(), Status::SeeOther);
         let user = get_user(&db, "user");
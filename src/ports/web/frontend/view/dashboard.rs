@@ -1,5 +1,9 @@
-use super::page;
-use crate::core::entities::*;
+use super::{archive_reason_label, page};
+use crate::core::{
+    db::{LinkCheck, PlaceQualityIssue},
+    entities::*,
+    usecases::RegionQualityIssues,
+};
 use maud::{html, Markup};
 
 pub struct DashBoardPresenter {
@@ -8,6 +12,22 @@ pub struct DashBoardPresenter {
     pub event_count: usize,
     pub tag_count: usize,
     pub user_count: usize,
+    pub view_count: Option<u64>,
+    pub view_count_days: u32,
+    pub broken_links: Vec<LinkCheck>,
+    pub archived_ratings: Vec<(Option<ArchiveReason>, usize)>,
+    pub archived_comments: Vec<(Option<ArchiveReason>, usize)>,
+}
+
+fn count_of_reason(
+    counts: &[(Option<ArchiveReason>, usize)],
+    reason: Option<ArchiveReason>,
+) -> usize {
+    counts
+        .iter()
+        .filter(|(r, _)| *r == reason)
+        .map(|(_, count)| count)
+        .sum()
 }
 
 pub fn dashboard(data: DashBoardPresenter) -> Markup {
@@ -36,10 +56,103 @@ pub fn dashboard(data: DashBoardPresenter) -> Markup {
                         td {"Number of Tags"}
                         td {(data.tag_count)}
                     }
+                    @if let Some(view_count) = data.view_count {
+                        tr {
+                            td {"Number of Views (last " (data.view_count_days) " days)"}
+                            td {(view_count)}
+                        }
+                    }
                 }
+                h3 { "Archived Ratings and Comments" }
+                table {
+                    tr {
+                        th {"Reason"}
+                        th {"Ratings"}
+                        th {"Comments"}
+                    }
+                    @for reason in ArchiveReason::all().iter().copied().map(Some).chain(std::iter::once(None)) {
+                        tr {
+                            td {(archive_reason_label(reason))}
+                            td {(count_of_reason(&data.archived_ratings, reason))}
+                            td {(count_of_reason(&data.archived_comments, reason))}
+                        }
+                    }
+                }
+                @if !data.broken_links.is_empty() {
+                    h3 { "Broken Links" }
+                    table {
+                        tr {
+                            th {"URL"}
+                            th {"Failed Checks"}
+                            th {"Last Error"}
+                        }
+                        @for link in &data.broken_links {
+                            tr {
+                                td { a href=(link.url) { (link.url) } }
+                                td {(link.failures)}
+                                td {(link.last_error.as_deref().unwrap_or_default())}
+                            }
+                        }
+                    }
+                }
+                h3 { "Data Quality" }
+                p { a href="/dashboard/data-quality" { "Places with quality issues" } }
                 h3 { "User Management" }
                 (super::search_users_form())
             }
         },
     )
 }
+
+fn quality_issue_label(issue: PlaceQualityIssue) -> &'static str {
+    match issue {
+        PlaceQualityIssue::MissingAddress => "missing address",
+        PlaceQualityIssue::InvalidUrl => "invalid URL",
+        PlaceQualityIssue::ZeroCoordinates => "coordinates 0/0",
+        PlaceQualityIssue::NoTags => "no tags",
+        PlaceQualityIssue::Unreviewed => "not reviewed for more than a year",
+    }
+}
+
+pub fn data_quality_report(email: &str, regions: &[RegionQualityIssues]) -> Markup {
+    page(
+        "Data Quality",
+        Some(email),
+        None,
+        None,
+        html! {
+            main class="dashboard" {
+                h3 { "Places with Quality Issues" }
+                @if regions.is_empty() {
+                    p { "No issues found." }
+                }
+                @for r in regions {
+                    h4 {
+                        (r.region.state.as_deref().unwrap_or("Unknown state"))
+                        @if let Some(ref country) = r.region.country {
+                            " (" (country) ")"
+                        }
+                    }
+                    table {
+                        tr {
+                            th {"Place"}
+                            th {"Issues"}
+                        }
+                        @for p in &r.places {
+                            tr {
+                                td { a href=(format!("/entries/{}", p.id)) { (p.title) } }
+                                td {
+                                    (p.issues
+                                        .iter()
+                                        .map(|i| quality_issue_label(*i))
+                                        .collect::<Vec<_>>()
+                                        .join(", "))
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+    )
+}
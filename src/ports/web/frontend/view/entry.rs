@@ -1,4 +1,6 @@
-use super::{address_to_html, leaflet_css_link, map_scripts, page};
+use super::{
+    address_to_html, archive_reason_label, csrf_token_input, leaflet_css_link, map_scripts, page,
+};
 use crate::core::prelude::*;
 use maud::{html, Markup};
 use std::collections::HashMap;
@@ -9,6 +11,10 @@ pub struct EntryPresenter {
     pub place: Place,
     pub ratings: HashMap<RatingContext, Ratings>,
     pub allow_archiving: bool,
+    pub csrf_token: Option<String>,
+    // Only visible for scouts and admins
+    pub archived_ratings: Vec<(Rating, Option<ArchiveReason>)>,
+    pub archived_comments: Vec<(Comment, Option<ArchiveReason>)>,
 }
 
 impl From<(Place, Vec<(Rating, Vec<Comment>)>, Role)> for EntryPresenter {
@@ -35,6 +41,9 @@ impl From<(Place, Vec<(Rating, Vec<Comment>)>)> for EntryPresenter {
             place,
             ratings,
             allow_archiving,
+            csrf_token: None,
+            archived_ratings: vec![],
+            archived_comments: vec![],
         }
     }
 }
@@ -109,6 +118,11 @@ fn entry_detail(e: EntryPresenter) -> Markup {
                 }
             }
         }
+        p {
+            a href=(format!("/entries/{}/print", e.place.id)) { "print" }
+            " | "
+            a href=(format!("/entries/{}.pdf", e.place.id)) { "PDF" }
+        }
         h3 { "Ratings" }
 
         @for (ctx, ratings) in e.ratings {
@@ -116,23 +130,53 @@ fn entry_detail(e: EntryPresenter) -> Markup {
             ul {
                 @for (r,comments) in ratings {
                     li {
-                        (rating(e.place.id.as_ref(), e.allow_archiving, &r, &comments))
+                        (rating(e.place.id.as_ref(), e.allow_archiving, e.csrf_token.as_deref(), &r, &comments))
                     }
                 }
             }
         }
+        @if e.allow_archiving && !(e.archived_ratings.is_empty() && e.archived_comments.is_empty()) {
+            h3 { "Archived Ratings and Comments" }
+            ul {
+                @for (r, reason) in &e.archived_ratings {
+                    li { "Rating: " (r.title) " (" (archive_reason_label(*reason)) ")" }
+                }
+                @for (c, reason) in &e.archived_comments {
+                    li { "Comment: " (c.text) " (" (archive_reason_label(*reason)) ")" }
+                }
+            }
+        }
         div id="map" style="height:300px;" { }
         (map_scripts(&[e.place.into()]))
     }
 }
 
-fn rating(place_id: &str, archive: bool, r: &Rating, comments: &[Comment]) -> Markup {
+fn archive_reason_select() -> Markup {
+    html! {
+        select name="reason" required? {
+            option value="" { "-- reason --" }
+            @for reason in &ArchiveReason::all() {
+                option value=(reason.as_str()) { (archive_reason_label(Some(*reason))) }
+            }
+        }
+    }
+}
+
+fn rating(
+    place_id: &str,
+    archive: bool,
+    csrf_token: Option<&str>,
+    r: &Rating,
+    comments: &[Comment],
+) -> Markup {
     html! {
       h5 { (r.title) " " span { (format!("({})",i8::from(r.value))) } }
       @if archive {
         form action = "/ratings/actions/archive" method = "POST" {
             input type="hidden" name="ids" value=(r.id.to_string());
             input type="hidden" name="place_id" value=(place_id);
+            (csrf_token_input(csrf_token))
+            (archive_reason_select())
             input type="submit" value="archive rating";
         }
       }
@@ -147,6 +191,8 @@ fn rating(place_id: &str, archive: bool, r: &Rating, comments: &[Comment]) -> Ma
                     form action = "/comments/actions/archive" method = "POST" {
                         input type="hidden" name="ids" value=(c.id.to_string());
                         input type="hidden" name="place_id" value=(place_id);
+                        (csrf_token_input(csrf_token))
+                        (archive_reason_select())
                         input type="submit" value="archive comment";
                     }
                   }
@@ -1,7 +1,7 @@
 use super::*;
 use maud::{html, Markup};
 
-pub fn event(user: Option<User>, ev: Event) -> Markup {
+pub fn event(user: Option<User>, csrf_token: Option<&str>, ev: Event) -> Markup {
     page(
         &ev.title,
         user.as_ref().map(|u| &*u.email),
@@ -93,6 +93,7 @@ pub fn event(user: Option<User>, ev: Event) -> Markup {
                     @match user.role {
                         Role::Admin | Role::Scout => {
                             form action=(format!("/events/{}/archive", ev.id)) method="POST" {
+                                (super::csrf_token_input(csrf_token))
                                 input type="submit" value="archive event";
                             }
                         }
@@ -15,6 +15,7 @@ mod login;
 mod page;
 mod password;
 mod place;
+mod print;
 mod register;
 
 pub use dashboard::*;
@@ -24,6 +25,7 @@ pub use login::*;
 use page::*;
 pub use password::*;
 pub use place::*;
+pub use print::*;
 pub use register::*;
 
 pub fn index(email: Option<&str>) -> Markup {
@@ -59,6 +61,25 @@ pub fn global_search_form(search_term: Option<&str>) -> Markup {
     }
 }
 
+// Must be submitted with all forms of logged in users
+fn csrf_token_input(csrf_token: Option<&str>) -> Markup {
+    html! {
+        @if let Some(token) = csrf_token {
+            input type="hidden" name="csrf_token" value=(token);
+        }
+    }
+}
+
+fn archive_reason_label(reason: Option<ArchiveReason>) -> &'static str {
+    match reason {
+        Some(ArchiveReason::Spam) => "spam",
+        Some(ArchiveReason::OffTopic) => "off-topic",
+        Some(ArchiveReason::Abusive) => "abusive",
+        Some(ArchiveReason::AuthorRequest) => "requested by the author",
+        None => "unknown reason",
+    }
+}
+
 fn leaflet_css_link() -> Markup {
     html! {
             link
@@ -200,7 +221,7 @@ fn map_scripts(pins: &[MapPin]) -> Markup {
     }
 }
 
-pub fn user_search_result(admin_email: &str, users: &[User]) -> Markup {
+pub fn user_search_result(admin_email: &str, csrf_token: Option<&str>, users: &[User]) -> Markup {
     page(
         "Users",
         Some(admin_email),
@@ -239,6 +260,7 @@ pub fn user_search_result(admin_email: &str, users: &[User]) -> Markup {
                                                     option value=(Role::Scout.to_u8().unwrap()) { "Scout" }
                                                 }
                                                 input type="hidden" name="email" value=(u.email);
+                                                (csrf_token_input(csrf_token))
                                                 input type="submit" value="change";
                                             }
                                         }
@@ -103,6 +103,7 @@ pub fn place_history(user: &User, h: &PlaceHistory) -> Markup {
 fn review_status_log(place_rev: Revision, l: &ReviewStatusLog) -> Markup {
     use ReviewStatus as S;
     let status = match l.status {
+        S::Draft => "Draft",
         S::Rejected => "Rejected",
         S::Archived => "Archived",
         S::Created => {
@@ -133,7 +134,12 @@ fn review_status_log(place_rev: Revision, l: &ReviewStatusLog) -> Markup {
     }
 }
 
-pub fn place_review(email: &str, place: &Place, status: ReviewStatus) -> Markup {
+pub fn place_review(
+    email: &str,
+    csrf_token: Option<&str>,
+    place: &Place,
+    status: ReviewStatus,
+) -> Markup {
     use ReviewStatus as S;
     let options = [
         (S::Rejected, "reject"),
@@ -165,6 +171,7 @@ pub fn place_review(email: &str, place: &Place, status: ReviewStatus) -> Markup
                             }
                         }
                     }
+                    (super::csrf_token_input(csrf_token))
                     input type="submit" value="change";
                 }
             }
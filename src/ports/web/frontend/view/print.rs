@@ -0,0 +1,110 @@
+use super::address_to_html;
+use crate::core::prelude::*;
+use maud::{html, Markup, PreEscaped, DOCTYPE};
+use ofdb_core::gateways::map_tile::MapSnippet;
+use qrcode::{render::svg, QrCode};
+
+const PRINT_CSS: &str = r#"
+body { font-family: sans-serif; max-width: 18cm; margin: 1cm auto; }
+h1 { margin-bottom: 0.2em; }
+.tags { color: #555; }
+.print-footer { display: flex; justify-content: space-between; align-items: flex-end; margin-top: 1em; }
+.map { position: relative; width: 256px; height: 256px; overflow: hidden; border: 1px solid #ccc; }
+.map img { display: block; }
+.map .marker { position: absolute; width: 12px; height: 12px; margin: -6px 0 0 -6px; border-radius: 50%; background: #c00; border: 2px solid #fff; }
+@media print { .no-print { display: none; } body { margin: 0; } }
+"#;
+
+/// The public link to a place that is encoded in the QR code
+pub fn entry_url(id: &str) -> String {
+    format!("https://kartevonmorgen.org/#/?entry={}", id)
+}
+
+fn qr_code_svg(url: &str) -> Option<String> {
+    let code = QrCode::new(url.as_bytes()).ok()?;
+    let svg = code
+        .render::<svg::Color>()
+        .min_dimensions(160, 160)
+        .quiet_zone(false)
+        .build();
+    // Skip the XML declaration for embedding the image into HTML
+    let start = svg.find("<svg")?;
+    Some(svg[start..].to_string())
+}
+
+/// A printer-friendly page of a place with an optional
+/// map snippet given as the URL of the tile image.
+pub fn entry_print(place: &Place, map: Option<(MapSnippet, String)>) -> Markup {
+    let url = entry_url(place.id.as_ref());
+    html! {
+        (DOCTYPE)
+        head {
+            meta charset="utf-8";
+            title { (format!("{} | OpenFairDB", place.title)) }
+            style { (PreEscaped(PRINT_CSS)) }
+        }
+        body {
+            p class="no-print" {
+                a href=(format!("/entries/{}", place.id)) { "back" }
+                " | "
+                a href=(format!("/entries/{}.pdf", place.id)) { "PDF" }
+            }
+            h1 { (place.title) }
+            p { (place.description) }
+            table {
+                @if let Some(ref a) = place.location.address {
+                    @if !a.is_empty() {
+                        tr {
+                            td { "Address" }
+                            td { (address_to_html(&a)) }
+                        }
+                    }
+                }
+                @if let Some(ref c) = place.contact {
+                    @if let Some(ref m) = c.email {
+                        tr {
+                            td { "eMail" }
+                            td { (m) }
+                        }
+                    }
+                    @if let Some(ref t) = c.phone {
+                        tr {
+                            td { "Phone" }
+                            td { (t) }
+                        }
+                    }
+                }
+                @if let Some(ref h) = place.links.as_ref().and_then(|l| l.homepage.as_ref()) {
+                    tr {
+                        td { "Homepage" }
+                        td { (h) }
+                    }
+                }
+            }
+            @if !place.tags.is_empty() {
+                p class="tags" {
+                    (place.tags.iter().map(|t| format!("#{}", t)).collect::<Vec<_>>().join(" "))
+                }
+            }
+            div class="print-footer" {
+                @if let Some((snippet, tile_url)) = map {
+                    div class="map" {
+                        img src=(tile_url) width="256" height="256" alt="map";
+                        div class="marker" style=(format!(
+                            "left:{:.1}%;top:{:.1}%;",
+                            snippet.offset_x * 100.0,
+                            snippet.offset_y * 100.0
+                        )) { }
+                    }
+                }
+                div class="qr-code" {
+                    @if let Some(svg) = qr_code_svg(&url) {
+                        (PreEscaped(svg))
+                    }
+                    br;
+                    small { (url) }
+                }
+            }
+        }
+    }
+}
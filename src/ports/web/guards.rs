@@ -1,11 +1,16 @@
 use crate::{
-    core::db::OrganizationRepo, core::prelude::*, core::usecases, infrastructure::error::AppError,
-    ports::web::jwt,
+    core::db::OrganizationRepo,
+    core::prelude::*,
+    core::usecases,
+    core::util::geo::MapBbox,
+    infrastructure::error::AppError,
+    ports::web::{jwt, sqlite},
 };
 use chrono::prelude::*;
+use ofdb_core::gateways::geolocate::{IpGeoLocationGateway, IpLocation};
 use rocket::{
     self,
-    http::Status,
+    http::{Cookie, Cookies, Method, SameSite, Status},
     request::{self, FromRequest, Request},
     Outcome, State,
 };
@@ -13,6 +18,8 @@ use std::time::Duration;
 
 pub const COOKIE_EMAIL_KEY: &str = "ofdb-user-email";
 pub const COOKIE_CAPTCHA_KEY: &str = "ofdb-captcha";
+pub const COOKIE_CSRF_TOKEN_KEY: &str = "ofdb-csrf-token";
+pub const CSRF_TOKEN_HEADER: &str = "X-CSRF-Token";
 pub const MAX_CAPTCHA_TTL: Duration = Duration::from_secs(120);
 
 type Result<T> = std::result::Result<T, AppError>;
@@ -26,11 +33,39 @@ fn get_bearer_token(auth_header_val: &str) -> Option<&str> {
     }
 }
 
+/// Issues a new token for the double-submit protection against
+/// cross-site request forgery (CSRF) of cookie-authenticated requests.
+pub fn add_csrf_token_cookie(cookies: &mut Cookies, same_site: SameSite) -> String {
+    let token = Nonce::new().to_string();
+    cookies.add(
+        Cookie::build(COOKIE_CSRF_TOKEN_KEY, token.clone())
+            .path("/")
+            .http_only(true)
+            .same_site(same_site)
+            .finish(),
+    );
+    token
+}
+
+fn is_safe_method(method: Method) -> bool {
+    matches!(method, Method::Get | Method::Head | Method::Options)
+}
+
 #[derive(Debug)]
 pub struct Auth {
     bearer_tokens: Vec<String>,
     account_email: Option<String>,
     has_captcha: bool,
+    csrf_token: Option<String>,
+    // Cookie-authenticated requests that might change the state
+    // need to submit the CSRF token of the session
+    csrf_pending: bool,
+    // Personal API tokens with read scope don't permit
+    // requests that might change the state
+    read_only: bool,
+    // Revocable API token that authorizes an organization in
+    // addition to its initial API token
+    org_api_token: Option<OrganizationApiToken>,
 }
 
 impl Auth {
@@ -39,6 +74,16 @@ impl Auth {
     // }
 
     pub fn account_email(&self) -> Result<&str> {
+        let email = self.unverified_account_email()?;
+        if self.csrf_pending || self.read_only {
+            return Err(AppError::Business(Error::Parameter(
+                ParameterError::Forbidden,
+            )));
+        }
+        Ok(email)
+    }
+
+    fn unverified_account_email(&self) -> Result<&str> {
         self.account_email
             .as_deref()
             .ok_or(AppError::Business(Error::Parameter(
@@ -46,6 +91,11 @@ impl Auth {
             )))
     }
 
+    /// The CSRF token of a cookie-authenticated session
+    pub fn csrf_token(&self) -> Option<&str> {
+        self.csrf_token.as_deref()
+    }
+
     pub fn bearer_tokens(&self) -> &Vec<String> {
         &self.bearer_tokens
     }
@@ -61,12 +111,21 @@ impl Auth {
     }
 
     pub fn organization<R: OrganizationRepo>(&self, db: &R) -> Result<Organization> {
+        if let Some(token) = &self.org_api_token {
+            return Ok(db.get_org_by_id(&token.org_id)?);
+        }
         Ok(usecases::authorize_organization_by_possible_api_tokens(
             db,
             &self.bearer_tokens,
         )?)
     }
 
+    /// The revocable API token that authorizes the organization,
+    /// `None` if the initial API token is used
+    pub fn organization_api_token(&self) -> Option<&OrganizationApiToken> {
+        self.org_api_token.as_ref()
+    }
+
     pub fn user_with_min_role<D: Db>(&self, db: &D, min_required_role: Role) -> Result<User> {
         Ok(usecases::authorize_user_by_email(
             db,
@@ -102,6 +161,95 @@ impl Auth {
             .next()
     }
 
+    fn is_deactivated(request: &Request, email: &str) -> bool {
+        let deactivated_at = request
+            .guard::<sqlite::Connections>()
+            .succeeded()
+            .and_then(|connections| connections.shared().ok())
+            .map(|db| db.user_deactivated_at(email));
+        match deactivated_at {
+            Some(Ok(deactivated_at)) => deactivated_at.is_some(),
+            // The account has been anonymized or merged
+            Some(Err(RepoError::NotFound)) => true,
+            Some(Err(err)) => {
+                warn!("Failed to check if user {} is deactivated: {}", email, err);
+                true
+            }
+            // Deny access if the database is not available
+            None => true,
+        }
+    }
+
+    fn account_email_and_scope_from_personal_api_token_in_header(
+        request: &Request,
+        bearer_tokens: &[String],
+    ) -> Option<(String, ApiTokenScope)> {
+        if bearer_tokens.is_empty() {
+            return None;
+        }
+        let connections = request.guard::<sqlite::Connections>().succeeded()?;
+        let db = connections.shared().ok()?;
+        bearer_tokens
+            .iter()
+            .filter_map(|token| usecases::authorize_user_by_personal_api_token(&*db, token).ok())
+            .next()
+    }
+
+    fn organization_api_token_in_header(
+        request: &Request,
+        bearer_tokens: &[String],
+    ) -> Option<OrganizationApiToken> {
+        if bearer_tokens.is_empty() {
+            return None;
+        }
+        let connections = request.guard::<sqlite::Connections>().succeeded()?;
+        let now = Timestamp::now();
+        let token = {
+            let db = connections.shared().ok()?;
+            bearer_tokens
+                .iter()
+                .filter_map(|token| {
+                    usecases::authorize_organization_by_api_token(&*db, token, now).ok()
+                })
+                .next()?
+        };
+        // Most requests don't need to update the time of the last
+        // usage and should not wait for an exclusive connection
+        if !usecases::is_organization_api_token_usage_due(&token, now) {
+            return Some(token);
+        }
+        // The time of the last usage is recorded before handling
+        // the request, i.e. without holding a read-only connection
+        match connections.exclusive() {
+            Ok(db) => {
+                if let Err(err) = usecases::record_organization_api_token_usage(&*db, &token, now) {
+                    warn!("Failed to record usage of organization API token: {}", err);
+                }
+            }
+            Err(err) => warn!("Failed to record usage of organization API token: {}", err),
+        }
+        Some(token)
+    }
+
+    fn csrf_token_from_cookie_or_new(request: &Request) -> String {
+        // The guard might be requested multiple times per request
+        struct CachedCsrfToken(String);
+        request
+            .local_cache(|| {
+                let token = request
+                    .cookies()
+                    .get(COOKIE_CSRF_TOKEN_KEY)
+                    .map(|cookie| cookie.value().to_owned());
+                // Sessions that have been started before
+                // CSRF tokens were introduced don't have one
+                CachedCsrfToken(token.unwrap_or_else(|| {
+                    add_csrf_token_cookie(&mut request.cookies(), SameSite::None)
+                }))
+            })
+            .0
+            .clone()
+    }
+
     fn captcha_from_cookie(request: &Request) -> bool {
         request
             .cookies()
@@ -119,12 +267,44 @@ impl<'a, 'r> FromRequest<'a, 'r> for Auth {
 
         // decide account_email source
         let mut account_email = None;
+        let mut csrf_token = None;
+        let mut csrf_pending = false;
         if cfg!(feature = "cookies") {
             account_email = Self::account_email_from_cookie(request);
+            if account_email.is_some() {
+                let token = Self::csrf_token_from_cookie_or_new(request);
+                csrf_pending = !is_safe_method(request.method())
+                    && request.headers().get_one(CSRF_TOKEN_HEADER) != Some(token.as_str());
+                csrf_token = Some(token);
+            }
         }
         if cfg!(feature = "jwt") && account_email.is_none() {
             account_email = Self::account_email_from_jwt_in_header(request, &bearer_tokens);
         }
+        // Cookies and JWTs that have been issued before the
+        // account has been deleted are no longer accepted
+        if account_email
+            .as_deref()
+            .map_or(false, |email| Self::is_deactivated(request, email))
+        {
+            account_email = None;
+            csrf_token = None;
+            csrf_pending = false;
+        }
+        let mut read_only = false;
+        if account_email.is_none() {
+            if let Some((email, scope)) =
+                Self::account_email_and_scope_from_personal_api_token_in_header(
+                    request,
+                    &bearer_tokens,
+                )
+            {
+                read_only = scope == ApiTokenScope::Read && !is_safe_method(request.method());
+                account_email = Some(email);
+            }
+        }
+
+        let org_api_token = Self::organization_api_token_in_header(request, &bearer_tokens);
 
         let has_captcha = Self::captcha_from_cookie(request);
 
@@ -132,6 +312,10 @@ impl<'a, 'r> FromRequest<'a, 'r> for Auth {
             bearer_tokens,
             account_email,
             has_captcha,
+            csrf_token,
+            csrf_pending,
+            read_only,
+            org_api_token,
         };
 
         Outcome::Success(auth)
@@ -139,11 +323,18 @@ impl<'a, 'r> FromRequest<'a, 'r> for Auth {
 }
 
 #[derive(Debug)]
-pub struct Account(String);
+pub struct Account {
+    email: String,
+    csrf_token: Option<String>,
+}
 
 impl Account {
     pub fn email(&self) -> &str {
-        &self.0
+        &self.email
+    }
+
+    pub fn csrf_token(&self) -> Option<&str> {
+        self.csrf_token.as_deref()
     }
 }
 
@@ -152,8 +343,85 @@ impl<'a, 'r> FromRequest<'a, 'r> for Account {
     fn from_request(request: &'a Request<'r>) -> request::Outcome<Account, Self::Error> {
         let auth = Auth::from_request(request)?;
         match auth.account_email() {
-            Ok(email) => Outcome::Success(Account(email.to_owned())),
+            Ok(email) => Outcome::Success(Account {
+                email: email.to_owned(),
+                csrf_token: auth.csrf_token.clone(),
+            }),
+            Err(AppError::Business(Error::Parameter(ParameterError::Forbidden))) => {
+                Outcome::Failure((Status::Forbidden, ()))
+            }
             _ => Outcome::Failure((Status::Unauthorized, ())),
         }
     }
 }
+
+/// The account of a form submission that still needs to
+/// be verified by the submitted CSRF token.
+#[derive(Debug)]
+pub struct UnverifiedAccount(Auth);
+
+impl UnverifiedAccount {
+    pub fn verify_csrf_token(mut self, token: Option<&str>) -> Result<Account> {
+        if self.0.csrf_pending && token.is_some() && token == self.0.csrf_token() {
+            self.0.csrf_pending = false;
+        }
+        let email = self.0.account_email()?.to_owned();
+        Ok(Account {
+            email,
+            csrf_token: self.0.csrf_token,
+        })
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for UnverifiedAccount {
+    type Error = ();
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        let auth = Auth::from_request(request)?;
+        if auth.unverified_account_email().is_err() {
+            return Outcome::Failure((Status::Unauthorized, ()));
+        }
+        Outcome::Success(UnverifiedAccount(auth))
+    }
+}
+
+/// Locates the IP addresses of clients if a database
+/// has been configured
+pub struct IpGeoLocation(Option<Box<dyn IpGeoLocationGateway + Send + Sync>>);
+
+impl IpGeoLocation {
+    pub fn new(gateway: Option<Box<dyn IpGeoLocationGateway + Send + Sync>>) -> Self {
+        Self(gateway)
+    }
+}
+
+/// The approximate location of the client according
+/// to its IP address
+#[derive(Debug, Clone, Copy)]
+pub struct ClientLocation(Option<IpLocation>);
+
+impl ClientLocation {
+    pub fn pos(&self) -> Option<MapPoint> {
+        self.0.map(|location| location.pos)
+    }
+
+    /// The default bounding box of searches without a location
+    pub fn region(&self) -> Option<MapBbox> {
+        self.0.as_ref().map(IpLocation::region)
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for ClientLocation {
+    type Error = ();
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        let location = request
+            .guard::<State<IpGeoLocation>>()
+            .succeeded()
+            .and_then(|geolocation| {
+                let gateway = geolocation.0.as_ref()?;
+                // Respects the X-Real-IP header of a reverse proxy
+                let ip = request.client_ip()?;
+                gateway.locate_ip(ip)
+            });
+        Outcome::Success(Self(location))
+    }
+}
@@ -4,15 +4,19 @@ use crate::{
         prelude::*,
         usecases,
     },
-    infrastructure::{cfg::Cfg, error::AppError},
+    infrastructure::{
+        self, cfg::Cfg, confirmation_campaign, email_outbox, error::AppError, event_scheduler,
+        geocoding_queue::GeoCodingQueue, homepage_previews, index_snapshot, link_checker, mirror,
+        moderated_tags_summaries, notification_digests, osm_resync, review_digest, user_deletion,
+    },
 };
-use ofdb_core::rating::Rated;
 use popular_tags_cache::PopularTagsCache;
-use rocket::{config::Config as RocketCfg, Rocket, Route};
+use rocket::{config::Config as RocketCfg, http::Method, Rocket, Route};
 use rocket_contrib::json::Json;
-use std::result;
+use std::{collections::BTreeMap, result};
 
 pub mod api;
+mod cors;
 #[cfg(feature = "frontend")]
 mod frontend;
 mod guards;
@@ -28,20 +32,37 @@ pub mod tests;
 
 type Result<T> = result::Result<Json<T>, AppError>;
 
-fn index_all_places<D: PlaceRepo + RatingRepository>(
+const INDEX_PLACES_CHUNK_SIZE: u64 = 1000;
+
+pub(crate) fn index_all_places<D: PlaceRepo + RatingAggregateRepo>(
     db: &D,
     indexer: &mut dyn PlaceIndexer,
 ) -> Result<()> {
-    // TODO: Split into chunks with fixed size instead of
-    // loading all places at once!
-    let places = db.all_places()?;
-    for (place, status) in places {
-        let ratings = db.load_ratings_of_place(place.id.as_ref())?;
-        if let Err(err) =
-            indexer.add_or_update_place(&place, status, &place.avg_ratings(&ratings[..]))
-        {
-            error!("Failed to index place {:?}: {}", place, err);
+    // The persisted aggregates replace loading
+    // and averaging the ratings of each place
+    let mut avg_ratings: BTreeMap<_, _> =
+        db.load_avg_ratings_of_all_places()?.into_iter().collect();
+    let mut offset = 0;
+    loop {
+        let pagination = Pagination {
+            offset: Some(offset),
+            limit: Some(INDEX_PLACES_CHUNK_SIZE),
+        };
+        let places = db.list_places(&pagination)?;
+        let count = places.len() as u64;
+        for (place, status) in places {
+            let last_confirmed_at = db.last_confirmed_at(place.id.as_ref())?;
+            let ratings = avg_ratings.remove(&place.id).unwrap_or_default();
+            if let Err(err) =
+                indexer.add_or_update_place(&place, status, last_confirmed_at, &ratings)
+            {
+                error!("Failed to index place {:?}: {}", place, err);
+            }
+        }
+        if count < INDEX_PLACES_CHUNK_SIZE {
+            break;
         }
+        offset += count;
     }
     if let Err(err) = indexer.flush_index() {
         error!("Failed to build place index: {}", err);
@@ -57,7 +78,7 @@ fn index_all_events_chronologically<D: EventGateway>(
     // loading all events at once!
     let events = db.all_events_chronologically()?;
     for event in events {
-        if let Err(err) = indexer.add_or_update_event(&event) {
+        if let Err(err) = usecases::index_event(indexer, &event) {
             error!("Failed to index event {:?}: {}", event, err);
         }
     }
@@ -78,6 +99,7 @@ pub(crate) fn rocket_instance(
     index_all_places(&*connections.exclusive().unwrap(), &mut search_engine).unwrap();
 
     info!("Indexing all events...");
+    let events_indexed_at = Timestamp::now();
     index_all_events_chronologically(&*connections.exclusive().unwrap(), &mut search_engine)
         .unwrap();
 
@@ -87,8 +109,77 @@ pub(crate) fn rocket_instance(
     info!("Caching most popular tags...");
     let tags_cache = PopularTagsCache::new_from_db(&*connections.shared().unwrap()).unwrap();
 
+    let geocoding_queue = GeoCodingQueue::spawn(
+        connections.clone(),
+        search_engine.clone(),
+        cfg.auto_fill_address,
+    );
+
+    if let Some(interval) = cfg.osm_resync_interval {
+        osm_resync::spawn(connections.clone(), cfg.overpass_api_url.clone(), interval);
+    }
+
+    if let Some(interval) = cfg.link_check_interval {
+        link_checker::spawn(connections.clone(), interval);
+    }
+
+    if let Some(interval) = cfg.homepage_preview_interval {
+        homepage_previews::spawn(connections.clone(), interval);
+    }
+
+    if let Some(snapshot_cfg) = cfg.index_snapshot.clone() {
+        index_snapshot::spawn(search_engine.clone(), snapshot_cfg);
+    }
+
+    event_scheduler::spawn(
+        connections.clone(),
+        search_engine.clone(),
+        notify::Notify::default(),
+        events_indexed_at,
+    );
+
+    let read_only = cfg.mirror_upstream_url.is_some();
+    if let Some(ref upstream_url) = cfg.mirror_upstream_url {
+        info!("Mirroring {}", upstream_url);
+        mirror::spawn(
+            connections.clone(),
+            search_engine.clone(),
+            upstream_url.clone(),
+            cfg.mirror_sync_interval,
+        );
+    }
+
+    // Mirrors don't contact the creators of places
+    if let (Some(campaign_cfg), false) = (cfg.confirmation_campaign.clone(), read_only) {
+        confirmation_campaign::spawn(connections.clone(), notify::Notify::default(), campaign_cfg);
+    }
+
+    if !read_only {
+        match notify::email_gateway() {
+            Some(gateway) => {
+                email_outbox::spawn(connections.clone(), gateway, cfg.email_outbox.clone());
+            }
+            None => warn!("Queued e-mails are not sent without an e-mail gateway"),
+        }
+        user_deletion::spawn(connections.clone(), cfg.user_deletion_grace_period);
+        notification_digests::spawn(connections.clone());
+        if let Some(window) = cfg.moderated_tags_notification_window {
+            moderated_tags_summaries::spawn(connections.clone(), notify::Notify::default(), window);
+        }
+        if let Some(interval) = cfg.review_digest_interval {
+            review_digest::spawn(connections.clone(), interval);
+        }
+    }
+
     let captcha_cache = api::captcha::CaptchaCache::new();
+    let address_completion_cache = api::geocoding::AddressCompletionCache::default();
     let jwt_state = jwt::JwtState::new();
+    let ip_geolocation = guards::IpGeoLocation::new(
+        cfg.ip_geolocation_db
+            .as_deref()
+            .and_then(infrastructure::ip_geolocation_gateway)
+            .map(|gateway| Box::new(gateway) as _),
+    );
 
     info!("Initialization finished");
 
@@ -101,11 +192,24 @@ pub(crate) fn rocket_instance(
         .manage(connections)
         .manage(search_engine)
         .manage(captcha_cache)
+        .manage(address_completion_cache)
         .manage(tags_cache)
         .manage(jwt_state)
-        .manage(cfg);
+        .manage(geocoding_queue)
+        .manage(ip_geolocation)
+        .manage(api::limits::UploadQuotas::default())
+        .manage(cfg)
+        .register(api::limits::catchers());
 
     for (m, r) in mounts {
+        // Mirrors only replicate changes from upstream
+        let r = if read_only {
+            r.into_iter()
+                .filter(|route| route.method == Method::Get)
+                .collect()
+        } else {
+            r
+        };
         instance = instance.mount(m, r);
     }
     instance
@@ -121,22 +225,12 @@ fn mounts() -> Vec<(&'static str, Vec<Route>)> {
     vec![("/api", api::routes()), ("/", frontend::routes())]
 }
 
-pub fn run(
-    connections: sqlite::Connections,
-    search_engine: tantivy::SearchEngine,
-    enable_cors: bool,
-    cfg: Cfg,
-) {
-    if enable_cors {
-        let cors = rocket_cors::CorsOptions {
-            ..Default::default()
-        }
-        .to_cors()
-        .unwrap();
-        rocket_instance(connections, search_engine, mounts(), None, cfg)
-            .attach(cors)
-            .launch();
+pub fn run(connections: sqlite::Connections, search_engine: tantivy::SearchEngine, cfg: Cfg) {
+    let cors = cfg.cors.clone();
+    let instance = rocket_instance(connections, search_engine, mounts(), None, cfg);
+    if let Some(cors) = cors {
+        instance.attach(cors::Cors::new(cors)).launch();
     } else {
-        rocket_instance(connections, search_engine, mounts(), None, cfg).launch();
+        instance.launch();
     }
 }
@@ -1,5 +1,5 @@
 #[cfg(not(test))]
-use crate::infrastructure::{MAILGUN_GW, SENDMAIL_GW};
+use crate::infrastructure::{MAILGUN_GW, SENDMAIL_GW, SMTP_GW};
 #[cfg(test)]
 use crate::ports::web::tests::DummyNotifyGW;
 use core::ops::Deref;
@@ -26,6 +26,25 @@ impl EmailGateway for DummyMailGw {
     }
 }
 
+/// The configured gateway for sending the e-mails of the outbox
+#[cfg(not(test))]
+pub fn email_gateway() -> Option<Box<dyn EmailGateway + Send + Sync>> {
+    if let Some(gw) = &*MAILGUN_GW {
+        Some(Box::new(gw.clone()))
+    } else if let Some(gw) = &*SMTP_GW {
+        Some(Box::new(gw.clone()))
+    } else if let Some(gw) = &*SENDMAIL_GW {
+        Some(Box::new(gw.clone()))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+pub fn email_gateway() -> Option<Box<dyn EmailGateway + Send + Sync>> {
+    None
+}
+
 impl Deref for Notify {
     type Target = dyn ofdb_core::gateways::notify::NotificationGateway;
     fn deref(&self) -> &Self::Target {
@@ -33,24 +52,33 @@ impl Deref for Notify {
     }
 }
 
-impl<'a, 'r> FromRequest<'a, 'r> for Notify {
-    type Error = ();
-
+impl Default for Notify {
     #[cfg(not(test))]
-    fn from_request(_: &'a Request<'r>) -> request::Outcome<Self, ()> {
+    fn default() -> Self {
         if let Some(gw) = &*MAILGUN_GW {
             info!("Use Mailgun gateway");
-            Outcome::Success(Notify(notify::Notify::new(gw.clone())))
+            Notify(notify::Notify::new(gw.clone()))
+        } else if let Some(gw) = &*SMTP_GW {
+            info!("Use SMTP gateway: {}", gw.host);
+            Notify(notify::Notify::new(gw.clone()))
         } else if let Some(gw) = &*SENDMAIL_GW {
-            warn!("Mailgun gateway was not configured: use sendmail as fallback");
-            Outcome::Success(Notify(notify::Notify::new(gw.clone())))
+            warn!("Neither Mailgun nor SMTP gateway was configured: use sendmail as fallback");
+            Notify(notify::Notify::new(gw.clone()))
         } else {
             warn!("No eMail gateway was not configured");
-            Outcome::Success(Notify(notify::Notify::new(DummyMailGw)))
+            Notify(notify::Notify::new(DummyMailGw))
         }
     }
     #[cfg(test)]
+    fn default() -> Self {
+        Notify(DummyNotifyGW)
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for Notify {
+    type Error = ();
+
     fn from_request(_: &'a Request<'r>) -> request::Outcome<Self, ()> {
-        Outcome::Success(Notify(DummyNotifyGW))
+        Outcome::Success(Notify::default())
     }
 }
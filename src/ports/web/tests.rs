@@ -46,7 +46,7 @@ pub fn setup_with_cfg(
         .log_level(LoggingLevel::Debug)
         .finalize()
         .unwrap();
-    let connections = sqlite::Connections::init(":memory:", 1).unwrap();
+    let connections = sqlite::Connections::init_in_memory().unwrap();
     embedded_migrations::run(&*connections.exclusive().unwrap()).unwrap();
     let search_engine = tantivy::SearchEngine::init_in_ram().unwrap();
     let rocket = super::rocket_instance(
@@ -85,10 +85,29 @@ pub struct DummyNotifyGW;
 impl ofdb_core::gateways::notify::NotificationGateway for DummyNotifyGW {
     fn place_added(&self, _: &[String], _: &Place, _: Vec<Category>) {}
     fn place_updated(&self, _: &[String], _: &Place, _: Vec<Category>) {}
+    fn place_moderated_tags_changed(
+        &self,
+        _: &[String],
+        _: &Place,
+        _: &[String],
+        _: &[String],
+        _: &EmailBranding,
+    ) {
+    }
+    fn moderated_tags_summary(
+        &self,
+        _: &[String],
+        _: &[PendingModeratedTagsNotification],
+        _: &EmailBranding,
+    ) {
+    }
+    fn place_rated(&self, _: &[String], _: &Place, _: &Rating) {}
     fn event_created(&self, _: &[String], _: &Event) {}
     fn event_updated(&self, _: &[String], _: &Event) {}
     fn user_registered_kvm(&self, _: &User) {}
     fn user_registered_ofdb(&self, _: &User) {}
     fn user_registered(&self, _: &User, _: &str) {}
     fn user_reset_password_requested(&self, _: &EmailNonce) {}
+    fn subscription_consent_requested(&self, _: &EmailNonce) {}
+    fn place_confirmation_requested(&self, _: &Place, _: &PlaceConfirmationRequest) {}
 }